@@ -36,6 +36,7 @@ use elp_project_model::Project;
 use elp_project_model::ProjectAppData;
 use elp_project_model::ProjectBuildData;
 use elp_project_model::ProjectManifest;
+use elp_project_model::StaticProject;
 use elp_syntax::TextRange;
 use elp_syntax::TextSize;
 use fxhash::FxHashMap;
@@ -179,6 +180,8 @@ impl ChangeFixture {
         let FixtureWithProjectMeta {
             fixture,
             mut diagnostics_enabled,
+            otp_version,
+            standalone,
         } = fixture_with_meta.clone();
 
         let builder = Builder::new(diagnostics_enabled.clone());
@@ -247,18 +250,26 @@ impl ChangeFixture {
             inc_file_id(&mut file_id);
         }
 
-        let otp = otp.unwrap_or_else(|| Otp {
+        let mut otp = otp.unwrap_or_else(|| Otp {
             // We only care about the otp lib_dir for the tests
             lib_dir: AbsPathBuf::assert("/".into()),
+            version: None,
         });
+        otp.version = otp_version;
 
         let root = AbsPathBuf::assert("/".into());
         let apps = app_map.all_apps().cloned().collect();
         let apps_with_includes = RebarProject::add_app_includes(apps, &vec![], &otp.lib_dir);
-        let rebar_project = RebarProject::new(root, Default::default());
         let mut project = Project::otp(otp, app_map.otp_apps().cloned().collect());
         project.add_apps(apps_with_includes);
-        project.project_build_data = ProjectBuildData::Rebar(rebar_project);
+        project.project_build_data = if standalone {
+            ProjectBuildData::Static(StaticProject {
+                config_path: root.join(".static"),
+                standalone: true,
+            })
+        } else {
+            ProjectBuildData::Rebar(RebarProject::new(root, Default::default()))
+        };
 
         if let Some(project_dir) = builder.project_dir() {
             // Dump a copy of the fixture into a temp dir
@@ -303,7 +314,7 @@ impl ChangeFixture {
             let (elp_config, manifest) =
                 ProjectManifest::discover(&AbsPathBuf::assert(json_config_file.into())).unwrap();
             let loaded_project =
-                Project::load(&manifest, elp_config.eqwalizer, &BuckQueryConfig::Original).unwrap();
+                Project::load(&manifest, &elp_config, &BuckQueryConfig::Original).unwrap();
             project = loaded_project;
         }
 
@@ -1030,6 +1041,7 @@ bar() -> ?FOO.
                                 parse_transforms: [],
                                 app_type: App,
                                 ebin_path: None,
+                                applications: [],
                             },
                         ),
                         SourceRootId(
@@ -1070,8 +1082,12 @@ bar() -> ?FOO.
                                         "/opt/lib/comp-1.3/ebin",
                                     ),
                                 ),
+                                applications: [],
                             },
                         ),
+                        SourceRootId(
+                            4,
+                        ): None,
                         SourceRootId(
                             1,
                         ): Some(
@@ -1099,11 +1115,32 @@ bar() -> ?FOO.
                                 parse_transforms: [],
                                 app_type: App,
                                 ebin_path: None,
+                                applications: [],
                             },
                         ),
                         SourceRootId(
                             3,
-                        ): None,
+                        ): Some(
+                            AppData {
+                                project_id: ProjectId(
+                                    1,
+                                ),
+                                name: AppName(
+                                    "virtual_documents",
+                                ),
+                                dir: AbsPathBuf(
+                                    "/opt/lib",
+                                ),
+                                include_path: [],
+                                src_path: [],
+                                extra_src_dirs: [],
+                                macros: [],
+                                parse_transforms: [],
+                                app_type: App,
+                                ebin_path: None,
+                                applications: [],
+                            },
+                        ),
                     },
                     project_map: {
                         ProjectId(
@@ -1155,7 +1192,11 @@ bar() -> ?FOO.
                             eqwalizer_config: EqwalizerConfig {
                                 enable_all: true,
                                 max_tasks: 4,
+                                apps: {},
                             },
+                            routers: [],
+                            standalone: false,
+                            otp_version: None,
                         },
                         ProjectId(
                             1,
@@ -1187,11 +1228,15 @@ bar() -> ?FOO.
                             eqwalizer_config: EqwalizerConfig {
                                 enable_all: true,
                                 max_tasks: 4,
+                                apps: {},
                             },
+                            routers: [],
+                            standalone: false,
+                            otp_version: None,
                         },
                     },
                     catch_all_source_root: SourceRootId(
-                        3,
+                        4,
                     ),
                 },
             )"#]]
@@ -1261,10 +1306,34 @@ foo() -> ?BAR.
                                 parse_transforms: [],
                                 app_type: App,
                                 ebin_path: None,
+                                applications: [],
                             },
                         ),
                         SourceRootId(
                             1,
+                        ): Some(
+                            AppData {
+                                project_id: ProjectId(
+                                    1,
+                                ),
+                                name: AppName(
+                                    "virtual_documents",
+                                ),
+                                dir: AbsPathBuf(
+                                    "/",
+                                ),
+                                include_path: [],
+                                src_path: [],
+                                extra_src_dirs: [],
+                                macros: [],
+                                parse_transforms: [],
+                                app_type: App,
+                                ebin_path: None,
+                                applications: [],
+                            },
+                        ),
+                        SourceRootId(
+                            2,
                         ): None,
                     },
                     project_map: {
@@ -1298,7 +1367,11 @@ foo() -> ?BAR.
                             eqwalizer_config: EqwalizerConfig {
                                 enable_all: true,
                                 max_tasks: 4,
+                                apps: {},
                             },
+                            routers: [],
+                            standalone: false,
+                            otp_version: None,
                         },
                         ProjectId(
                             1,
@@ -1320,11 +1393,15 @@ foo() -> ?BAR.
                             eqwalizer_config: EqwalizerConfig {
                                 enable_all: true,
                                 max_tasks: 4,
+                                apps: {},
                             },
+                            routers: [],
+                            standalone: false,
+                            otp_version: None,
                         },
                     },
                     catch_all_source_root: SourceRootId(
-                        1,
+                        2,
                     ),
                 },
             )"#]]
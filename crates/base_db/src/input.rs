@@ -17,6 +17,7 @@ use elp_project_model::AppType;
 use elp_project_model::EqwalizerConfig;
 use elp_project_model::Project;
 use elp_project_model::ProjectAppData;
+use elp_project_model::RouterConfig;
 use fxhash::FxHashMap;
 use paths::RelPath;
 use paths::Utf8Path;
@@ -27,6 +28,12 @@ use vfs::VfsPath;
 
 use crate::SourceDatabaseExt;
 
+/// The virtual path under which the documents the client opens without a
+/// file on disk (`untitled:` buffers, remote schemes used by code review
+/// tools) are kept in the VFS. They get a source root of their own,
+/// following the ones of the apps, which resolves against OTP only.
+pub const VIRTUAL_DOCUMENTS_ROOT: &str = "/elp-virtual-documents";
+
 /// Files are grouped into source roots. A source root is a directory on the
 /// file systems which is watched for changes. Typically it corresponds to an OTP
 /// application. Source roots *might* be nested: in this case, a file belongs to
@@ -109,6 +116,11 @@ pub struct ProjectData {
     pub otp_project_id: Option<ProjectId>,
     pub app_roots: AppRoots,
     pub eqwalizer_config: EqwalizerConfig,
+    pub routers: Vec<RouterConfig>,
+    /// See `Project::is_standalone`
+    pub standalone: bool,
+    /// The OTP release the project is built with, e.g. `26.2.1`
+    pub otp_version: Option<String>,
 }
 
 /// `AppData` is stored in salsa, indexed by `SourceRootId`.
@@ -125,6 +137,8 @@ pub struct AppData {
     pub parse_transforms: Vec<eetf::Term>,
     pub app_type: AppType,
     pub ebin_path: Option<AbsPathBuf>,
+    /// The applications this one depends on at runtime
+    pub applications: Vec<AppName>,
 }
 
 impl AppData {
@@ -335,6 +349,7 @@ impl<'a> ProjectApps<'a> {
                     app_type: app.app_type,
                     src_path: app.abs_src_dirs.clone(),
                     ebin_path: app.ebin.clone(),
+                    applications: app.applications.clone(),
                 };
                 app_structure.add_app_data(root_id, Some(input_data));
             }
@@ -352,10 +367,30 @@ impl<'a> ProjectApps<'a> {
                 otp_project_id: self.otp_project_id,
                 app_roots,
                 eqwalizer_config: project.eqwalizer_config.clone(),
+                routers: project.routers.clone(),
+                standalone: project.is_standalone(),
+                otp_version: project.otp.version.clone(),
             };
             app_structure.add_project_data(project_id, project_data);
         }
 
+        // SourceRoot for the virtual documents, see `VIRTUAL_DOCUMENTS_ROOT`
+        let virtual_documents = self.otp_project_id.map(|otp_project_id| AppData {
+            project_id: otp_project_id,
+            name: AppName("virtual_documents".to_string()),
+            dir: self.projects[otp_project_id.0 as usize].root().into_owned(),
+            include_path: vec![],
+            src_path: vec![],
+            extra_src_dirs: vec![],
+            macros: vec![],
+            parse_transforms: vec![],
+            app_type: AppType::App,
+            ebin_path: None,
+            applications: vec![],
+        });
+        app_structure.add_app_data(SourceRootId(app_idx), virtual_documents);
+        app_idx += 1;
+
         // Final SourceRoot for out-of-project files
         log::info!("Final source root: {:?}", SourceRootId(app_idx));
         app_structure.add_app_data(SourceRootId(app_idx), None);
@@ -11,12 +11,14 @@ use std::sync::Arc;
 
 use elp_project_model::AppName;
 use elp_syntax::ast::SourceFile;
+use elp_syntax::grammar_file;
 use elp_syntax::AstNode;
 use elp_syntax::Parse;
 use elp_syntax::SmolStr;
 use elp_syntax::TextRange;
 use elp_syntax::TextSize;
 use fxhash::FxHashMap;
+use fxhash::FxHashSet;
 use lazy_static::lazy_static;
 
 mod change;
@@ -43,7 +45,9 @@ pub use input::ProjectData;
 pub use input::ProjectId;
 pub use input::SourceRoot;
 pub use input::SourceRootId;
+pub use input::VIRTUAL_DOCUMENTS_ROOT;
 pub use module_index::ModuleIndex;
+pub use module_index::ModuleIndexShard;
 pub use module_index::ModuleName;
 pub use module_index::Modules;
 pub use paths::AbsPath;
@@ -102,6 +106,8 @@ pub enum FileKind {
     TestModule,
     Header,
     Escript,
+    /// A yecc (`.yrl`) or leex (`.xrl`) grammar
+    Grammar,
     Other,
     OutsideProjectModel,
 }
@@ -151,11 +157,20 @@ pub trait SourceDatabase: FileLoader + salsa::Database {
     #[salsa::input]
     fn project_data(&self, id: ProjectId) -> Arc<ProjectData>;
 
+    /// Grammar files whose generated module is older than them on disk.
+    /// Set by the loader, which reads the modification times.
+    #[salsa::input]
+    fn stale_generated_files(&self) -> Arc<FxHashSet<FileId>>;
+
     fn file_app_data(&self, file_id: FileId) -> Option<Arc<AppData>>;
 
     /// Returns a map from module name to FileId of the containing file.
     fn module_index(&self, project_id: ProjectId) -> Arc<ModuleIndex>;
 
+    /// The modules of the source root, a shard of the module index of
+    /// its project.
+    fn source_root_modules(&self, source_root_id: SourceRootId) -> Arc<ModuleIndexShard>;
+
     fn include_file_index(&self, project_id: ProjectId) -> Arc<IncludeFileIndex>;
 
     /// Parse the file_id to AST
@@ -197,13 +212,8 @@ fn module_index(db: &dyn SourceDatabase, project_id: ProjectId) -> Arc<ModuleInd
 
     let project_data = db.project_data(project_id);
     for &source_root_id in &project_data.source_roots {
-        if let Some(app_data) = db.app_data(source_root_id) {
-            let source_root = db.source_root(source_root_id);
-            for (file_id, file_source, path) in source_root.iter_app_files(&app_data) {
-                if let Some((name, Some("erl"))) = path.name_and_extension() {
-                    builder.insert(file_id, file_source, ModuleName::new(name));
-                }
-            }
+        for (name, file_source, file_id) in db.source_root_modules(source_root_id).iter() {
+            builder.insert(*file_id, *file_source, name.clone());
         }
     }
 
@@ -221,6 +231,22 @@ fn module_index(db: &dyn SourceDatabase, project_id: ProjectId) -> Arc<ModuleInd
     builder.build()
 }
 
+fn source_root_modules(
+    db: &dyn SourceDatabase,
+    source_root_id: SourceRootId,
+) -> Arc<ModuleIndexShard> {
+    let mut modules = ModuleIndexShard::new();
+    if let Some(app_data) = db.app_data(source_root_id) {
+        let source_root = db.source_root(source_root_id);
+        for (file_id, file_source, path) in source_root.iter_app_files(&app_data) {
+            if let Some((name, Some("erl"))) = path.name_and_extension() {
+                modules.push((ModuleName::new(name), file_source, file_id));
+            }
+        }
+    }
+    Arc::new(modules)
+}
+
 /// A map from file path to `FileId` for each `.hrl` file we have
 /// loaded.
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -290,6 +316,11 @@ fn include_file_id(
 
 fn parse(db: &dyn SourceDatabase, file_id: FileId) -> Parse<SourceFile> {
     let text = db.file_text(file_id);
+    if db.file_kind(file_id) == FileKind::Grammar {
+        // Only the `Erlang code.` section is Erlang
+        let start = grammar_file::erlang_code_start(&text).map_or(text.len(), usize::from);
+        return SourceFile::parse_skipping(&text, start);
+    }
     SourceFile::parse_text(&text)
 }
 
@@ -300,6 +331,12 @@ pub fn path_for_file(db: &dyn SourceDatabase, file_id: FileId) -> Option<VfsPath
 }
 
 fn is_generated(db: &dyn SourceDatabase, file_id: FileId) -> bool {
+    is_generated_text(&db.file_text(file_id))
+}
+
+/// Whether the given file contents carry the generated-code marker in
+/// their header.
+pub fn is_generated_text(contents: &str) -> bool {
     lazy_static! {
         // We operate a byte level via a regex (as opposed to use .contains)
         // to avoid issues with UTF8 character boundaries.
@@ -307,7 +344,6 @@ fn is_generated(db: &dyn SourceDatabase, file_id: FileId) -> bool {
         // The format macro is used to avoid marking the whole file as generated
         static ref RE: regex::bytes::Regex = regex::bytes::Regex::new(&format!("{}generated", "@")).unwrap();
     }
-    let contents = db.file_text(file_id);
     RE.is_match(&contents.as_bytes()[0..(2001.min(contents.len()))])
 }
 
@@ -403,11 +439,23 @@ fn file_kind(db: &dyn SourceDatabase, file_id: FileId) -> FileKind {
             }
             Some((_, Some("hrl"))) => FileKind::Header,
             Some((_, Some("escript"))) => FileKind::Escript,
+            Some((_, Some("yrl" | "xrl"))) => FileKind::Grammar,
+            Some((_, None)) if has_escript_shebang(&db.file_text(file_id)) => FileKind::Escript,
             _ => FileKind::Other,
         }
     }
 }
 
+/// Extensionless scripts are recognised by their interpreter line,
+/// e.g. `#!/usr/bin/env escript`.
+fn has_escript_shebang(text: &str) -> bool {
+    text.starts_with("#!")
+        && text
+            .lines()
+            .next()
+            .map_or(false, |line| line.contains("escript"))
+}
+
 /// When we get a range from the client, limit it to what is in the source file
 fn clamp_range(db: &dyn SourceDatabase, file_id: FileId, range: TextRange) -> TextRange {
     let source_file = db.parse(file_id).tree();
@@ -459,3 +507,17 @@ pub fn to_quoted_string(input: &str) -> String {
         format!("'{}'", &input)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::has_escript_shebang;
+
+    #[test]
+    fn escript_shebang() {
+        assert!(has_escript_shebang("#!/usr/bin/env escript\nmain(_).\n"));
+        assert!(has_escript_shebang("#!/usr/local/bin/escript"));
+        assert!(!has_escript_shebang("#!/bin/sh\nexec escript \"$0\"\n"));
+        assert!(!has_escript_shebang("%% escript\nmain(_) -> ok.\n"));
+        assert!(!has_escript_shebang(""));
+    }
+}
@@ -53,6 +53,11 @@ impl Borrow<str> for ModuleName {
 
 pub type Modules = Vec<ModuleName>;
 
+/// The modules of a single source root. The module index of a project is
+/// sharded by source root, so that a change to the files of one
+/// application only recomputes the shard of that application.
+pub type ModuleIndexShard = Vec<(ModuleName, FileSource, FileId)>;
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct ModuleIndex {
     /// - None: No OTP being tracked
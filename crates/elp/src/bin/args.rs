@@ -108,12 +108,12 @@ pub struct EqwalizeAll {
     /// Rebar3 profile to pickup (default is test)
     #[bpaf(long("as"), argument("PROFILE"), fallback("test".to_string()))]
     pub profile: String,
-    /// Show diagnostics in JSON format
+    /// Show diagnostics in JSON or JUnit XML format
     #[bpaf(
         argument("FORMAT"),
-        complete(format_completer),
+        complete(report_format_completer),
         fallback(None),
-        guard(format_guard, "Please use json")
+        guard(report_format_guard, "Please use json or junit")
     )]
     pub format: Option<String>,
     /// Run with rebar
@@ -146,6 +146,14 @@ pub struct EqwalizeApp {
     /// Rebar3 profile to pickup (default is test)
     #[bpaf(long("as"), argument("PROFILE"), fallback("test".to_string()))]
     pub profile: String,
+    /// Show diagnostics in JSON or JUnit XML format
+    #[bpaf(
+        argument("FORMAT"),
+        complete(report_format_completer),
+        fallback(None),
+        guard(report_format_guard, "Please use json or junit")
+    )]
+    pub format: Option<String>,
     /// Also eqwalize opted-in generated modules from project
     pub include_generated: bool,
     /// Run with rebar
@@ -157,6 +165,23 @@ pub struct EqwalizeApp {
     pub clause_coverage: bool,
 }
 
+#[derive(Clone, Debug, Bpaf)]
+pub struct EqwalizeFunctionErrors {
+    /// Path to directory with project, or to a JSON file (defaults to `.`)
+    #[bpaf(argument("PROJECT"), fallback(PathBuf::from(".")))]
+    pub project: PathBuf,
+    /// Rebar3 profile to pickup (default is test)
+    #[bpaf(long("as"), argument("PROFILE"), fallback("test".to_string()))]
+    pub profile: String,
+    /// Run with rebar
+    pub rebar: bool,
+    /// Use experimental clause coverage checker
+    pub clause_coverage: bool,
+    /// Function to report the errors of, as module:function/arity
+    #[bpaf(positional("FUNCTION"))]
+    pub function: String,
+}
+
 #[derive(Clone, Debug, Bpaf)]
 pub struct EqwalizeStats {
     /// Path to directory with project, or to a JSON file (defaults to `.`)
@@ -196,7 +221,15 @@ pub struct GenerateCompletions {
 }
 
 #[derive(Clone, Debug, Bpaf)]
-pub struct RunServer {}
+pub struct RunServer {
+    /// Print the time taken by each phase of the start on stderr, once loaded
+    pub startup_report: bool,
+    /// Analyze each file on its own with OTP only, without looking for its project
+    pub single_file: bool,
+    /// Append the usage count and latency of each feature to this file, as JSON lines
+    #[bpaf(argument("FILE"), optional)]
+    pub metrics_file: Option<PathBuf>,
+}
 
 #[derive(Clone, Debug, Bpaf)]
 pub struct Version {}
@@ -223,12 +256,12 @@ pub struct Lint {
     /// Rebar3 profile to pickup (default is test)
     #[bpaf(long("as"), argument("PROFILE"), fallback("test".to_string()))]
     pub profile: String,
-    /// Show diagnostics in JSON format
+    /// Show diagnostics in JSON or JUnit XML format
     #[bpaf(
         argument("FORMAT"),
-        complete(format_completer),
+        complete(report_format_completer),
         fallback(None),
-        guard(format_guard, "Please use json")
+        guard(report_format_guard, "Please use json or junit")
     )]
     pub format: Option<String>,
     /// Run with rebar
@@ -328,7 +361,159 @@ pub struct Glean {
 }
 
 #[derive(Clone, Debug, Bpaf)]
-pub struct ConfigStanza {}
+pub struct ConfigStanza {
+    /// Path to directory with project (defaults to `.`)
+    #[bpaf(argument("PROJECT"), fallback(PathBuf::from(".")))]
+    pub project: PathBuf,
+    /// Print the value of each setting and the layer it comes from, rather than the schema
+    pub resolved: bool,
+    /// Print the resolved settings as JSON
+    pub json: bool,
+}
+
+#[derive(Clone, Debug, Bpaf)]
+pub struct Atoms {
+    /// Path to directory with project, or to a JSON file (defaults to `.`)
+    #[bpaf(argument("PROJECT"), fallback(PathBuf::from(".")))]
+    pub project: PathBuf,
+    /// Rebar3 profile to pickup (default is test)
+    #[bpaf(long("as"), argument("PROFILE"), fallback("test".to_string()))]
+    pub profile: String,
+    /// Run with rebar
+    pub rebar: bool,
+    /// Cluster near-identical atoms, and list the ones used once next to a popular sibling
+    pub report: bool,
+}
+
+#[derive(Clone, Debug, Bpaf)]
+pub struct Stats {
+    /// Path to directory with project, or to a JSON file (defaults to `.`)
+    #[bpaf(argument("PROJECT"), fallback(PathBuf::from(".")))]
+    pub project: PathBuf,
+    /// Rebar3 profile to pickup (default is test)
+    #[bpaf(long("as"), argument("PROFILE"), fallback("test".to_string()))]
+    pub profile: String,
+    /// Run with rebar
+    pub rebar: bool,
+    /// Show the statistics in JSON format
+    #[bpaf(
+        argument("FORMAT"),
+        complete(format_completer),
+        fallback(None),
+        guard(format_guard, "Please use json")
+    )]
+    pub format: Option<String>,
+}
+
+#[derive(Clone, Debug, Bpaf)]
+pub struct MigrateDocs {
+    /// Path to directory with project, or to a JSON file (defaults to `.`)
+    #[bpaf(argument("PROJECT"), fallback(PathBuf::from(".")))]
+    pub project: PathBuf,
+    /// Rebar3 profile to pickup (default is test)
+    #[bpaf(long("as"), argument("PROFILE"), fallback("test".to_string()))]
+    pub profile: String,
+    /// Run with rebar
+    pub rebar: bool,
+    /// Only migrate this module
+    #[bpaf(argument("MODULE"))]
+    pub module: Option<String>,
+    /// Print the changes as a unified diff instead of writing them
+    pub dry_run: bool,
+}
+
+#[derive(Clone, Debug, Bpaf)]
+pub struct RenderHtml {
+    /// Path to directory with project, or to a JSON file (defaults to `.`)
+    #[bpaf(argument("PROJECT"), fallback(PathBuf::from(".")))]
+    pub project: PathBuf,
+    /// Rebar3 profile to pickup (default is test)
+    #[bpaf(long("as"), argument("PROFILE"), fallback("test".to_string()))]
+    pub profile: String,
+    /// Run with rebar
+    pub rebar: bool,
+    /// Directory to write the HTML pages to
+    #[bpaf(argument("DIR"))]
+    pub out: PathBuf,
+}
+
+#[derive(Clone, Debug, Bpaf)]
+pub struct StatemGraph {
+    /// Path to directory with project, or to a JSON file (defaults to `.`)
+    #[bpaf(argument("PROJECT"), fallback(PathBuf::from(".")))]
+    pub project: PathBuf,
+    /// Rebar3 profile to pickup (default is test)
+    #[bpaf(long("as"), argument("PROFILE"), fallback("test".to_string()))]
+    pub profile: String,
+    /// Run with rebar
+    pub rebar: bool,
+    /// Output format, dot (the default) or json
+    #[bpaf(
+        argument("FORMAT"),
+        complete(graph_format_completer),
+        fallback(None),
+        guard(graph_format_guard, "Please use dot or json")
+    )]
+    pub format: Option<String>,
+    /// File to write the graph to, instead of stdout
+    #[bpaf(argument("TO"))]
+    pub to: Option<PathBuf>,
+    /// The gen_statem module
+    #[bpaf(positional("MODULE"), complete(module_completer))]
+    pub module: String,
+}
+
+#[derive(Clone, Debug, Bpaf)]
+pub struct NewModule {
+    /// Path to directory with project, or to a JSON file (defaults to `.`)
+    #[bpaf(argument("PROJECT"), fallback(PathBuf::from(".")))]
+    pub project: PathBuf,
+    /// Rebar3 profile to pickup (default is test)
+    #[bpaf(long("as"), argument("PROFILE"), fallback("test".to_string()))]
+    pub profile: String,
+    /// Run with rebar
+    pub rebar: bool,
+    /// Application to create the module in, needed when the project has several
+    #[bpaf(argument("APP"))]
+    pub app: Option<String>,
+    /// gen_server, supervisor, ct_suite or escript
+    #[bpaf(
+        positional("KIND"),
+        complete(module_kind_completer),
+        guard(
+            module_kind_guard,
+            "Please use gen_server, supervisor, ct_suite or escript"
+        )
+    )]
+    pub kind: String,
+    /// Name of the module
+    #[bpaf(positional("NAME"))]
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Bpaf)]
+pub struct GenerateConfig {
+    /// Path to directory with project, or to a JSON file (defaults to `.`)
+    #[bpaf(argument("PROJECT"), fallback(PathBuf::from(".")))]
+    pub project: PathBuf,
+    /// Rebar3 profile to pickup (default is test)
+    #[bpaf(long("as"), argument("PROFILE"), fallback("test".to_string()))]
+    pub profile: String,
+    /// Run with rebar
+    pub rebar: bool,
+    /// Report the modules and sys.config values differing from the schemas, without writing
+    pub check: bool,
+}
+
+#[derive(Clone, Debug, Bpaf)]
+pub struct BugReport {
+    /// Path to directory with project, or to a JSON file (defaults to `.`)
+    #[bpaf(argument("PROJECT"), fallback(PathBuf::from(".")))]
+    pub project: PathBuf,
+    /// Path of the archive to write (defaults to `elp-bug-report.tar.gz`)
+    #[bpaf(argument("TO"), fallback(PathBuf::from("elp-bug-report.tar.gz")))]
+    pub to: PathBuf,
+}
 
 #[derive(Clone, Debug)]
 pub enum Command {
@@ -338,6 +523,7 @@ pub enum Command {
     EqwalizeAll(EqwalizeAll),
     EqwalizeTarget(EqwalizeTarget),
     EqwalizeApp(EqwalizeApp),
+    EqwalizeFunctionErrors(EqwalizeFunctionErrors),
     EqwalizeStats(EqwalizeStats),
     DialyzeAll(DialyzeAll),
     BuildInfo(BuildInfo),
@@ -350,6 +536,14 @@ pub enum Command {
     ProjectInfo(ProjectInfo),
     Glean(Glean),
     ConfigStanza(ConfigStanza),
+    Atoms(Atoms),
+    Stats(Stats),
+    MigrateDocs(MigrateDocs),
+    RenderHtml(RenderHtml),
+    StatemGraph(StatemGraph),
+    NewModule(NewModule),
+    GenerateConfig(GenerateConfig),
+    BugReport(BugReport),
     Help(),
 }
 
@@ -367,6 +561,10 @@ pub struct Args {
     /// Use BXL when querying for a buck project model
     pub buck_bxl: bool,
 
+    /// Export tracing spans to an OpenTelemetry collector, over OTLP/HTTP
+    #[bpaf(argument("URL"))]
+    pub otel_endpoint: Option<String>,
+
     #[bpaf(external(command))]
     pub command: Command,
 }
@@ -418,6 +616,12 @@ pub fn command() -> impl Parser<Command> {
         .command("eqwalize-app")
         .help("Eqwalize all opted-in modules in specified application");
 
+    let eqwalize_function_errors = eqwalize_function_errors()
+        .map(Command::EqwalizeFunctionErrors)
+        .to_options()
+        .command("eqwalize-fun-errors")
+        .help("Eqwalize the module of a function, given as module:function/arity, reporting only the errors within the function");
+
     let eqwalize_stats = eqwalize_stats()
         .map(Command::EqwalizeStats)
         .to_options()
@@ -491,10 +695,62 @@ pub fn command() -> impl Parser<Command> {
         .command("config")
         .help("Dump a JSON config stanza suitable for use in VS Code project.json");
 
+    let atoms = atoms()
+        .map(Command::Atoms)
+        .to_options()
+        .command("atoms")
+        .help("List the atoms used in a project, optionally reporting likely typos");
+
+    let stats = stats()
+        .map(Command::Stats)
+        .to_options()
+        .command("stats")
+        .help("Report the size of a project and its diagnostics per code");
+
+    let migrate_docs = migrate_docs()
+        .map(Command::MigrateDocs)
+        .to_options()
+        .command("migrate-docs")
+        .help("Convert EDoc comments to -moduledoc and -doc attributes");
+
+    let render_html = render_html()
+        .map(Command::RenderHtml)
+        .to_options()
+        .command("render-html")
+        .help("Render the project sources as HTML pages, linking references to their definitions");
+
+    let statem_graph = statem_graph()
+        .map(Command::StatemGraph)
+        .to_options()
+        .command("statem-graph")
+        .help("Extract the states and transitions of a gen_statem module as a graph");
+
+    let new_module = new_module()
+        .map(Command::NewModule)
+        .to_options()
+        .command("new")
+        .help("Create a module of the given kind in an application of the project");
+
+    let generate = generate_config()
+        .map(Command::GenerateConfig)
+        .to_options()
+        .command("config")
+        .help("Generate the typed accessor modules of the config_schemas of .elp_lint.toml")
+        .to_options()
+        .command("generate")
+        .help("Generate modules from the configuration of the project");
+
+    let bug_report = bug_report()
+        .map(Command::BugReport)
+        .to_options()
+        .command("bug-report")
+        .help("Bundle the information needed to act on a bug report into an archive");
+
     construct!([
         eqwalize,
         eqwalize_all,
         eqwalize_app,
+        eqwalize_function_errors,
         eqwalize_target,
         dialyze_all,
         lint,
@@ -510,6 +766,14 @@ pub fn command() -> impl Parser<Command> {
         project_info,
         glean,
         config_stanza,
+        atoms,
+        stats,
+        migrate_docs,
+        render_html,
+        statem_graph,
+        new_module,
+        generate,
+        bug_report,
     ])
     .fallback(Help())
 }
@@ -570,6 +834,46 @@ fn format_guard(format: &Option<String>) -> bool {
     }
 }
 
+fn report_format_completer(_: &Option<String>) -> Vec<(String, Option<String>)> {
+    vec![("json".to_string(), None), ("junit".to_string(), None)]
+}
+
+fn report_format_guard(format: &Option<String>) -> bool {
+    match format {
+        None => true,
+        Some(f) if f == "json" || f == "junit" => true,
+        _ => false,
+    }
+}
+
+fn graph_format_completer(_: &Option<String>) -> Vec<(String, Option<String>)> {
+    vec![("dot".to_string(), None), ("json".to_string(), None)]
+}
+
+fn graph_format_guard(format: &Option<String>) -> bool {
+    match format {
+        None => true,
+        Some(f) if f == "dot" || f == "json" => true,
+        _ => false,
+    }
+}
+
+const MODULE_KINDS: &[&str] = &["gen_server", "supervisor", "ct_suite", "escript"];
+
+#[allow(clippy::ptr_arg)]
+fn module_kind_completer(kind: &String) -> Vec<(String, Option<String>)> {
+    MODULE_KINDS
+        .iter()
+        .filter(|candidate| candidate.starts_with(kind.as_str()))
+        .map(|candidate| (candidate.to_string(), None))
+        .collect()
+}
+
+#[allow(clippy::ptr_arg)]
+fn module_kind_guard(kind: &String) -> bool {
+    MODULE_KINDS.contains(&kind.as_str())
+}
+
 fn at_least_1(data: &Vec<String>) -> bool {
     data.len() >= 1
 }
@@ -649,6 +953,10 @@ impl Lint {
     pub fn is_format_json(&self) -> bool {
         self.format == Some("json".to_string())
     }
+
+    pub fn is_format_junit(&self) -> bool {
+        self.format == Some("junit".to_string())
+    }
 }
 
 impl ParseAllElp {
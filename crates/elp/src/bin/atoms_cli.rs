@@ -0,0 +1,109 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use anyhow::Result;
+use elp::build::load;
+use elp::cli::Cli;
+use elp_eqwalizer::Mode;
+use elp_ide::atom_clusters;
+use elp_ide::elp_ide_db::elp_base_db::FileId;
+use elp_ide::elp_ide_db::elp_base_db::IncludeOtp;
+use elp_ide::TextRange;
+use elp_project_model::buck::BuckQueryConfig;
+use elp_project_model::DiscoverConfig;
+use elp_syntax::SmolStr;
+use fxhash::FxHashMap;
+use indicatif::ParallelProgressIterator;
+use itertools::Itertools;
+use rayon::iter::ParallelBridge;
+use rayon::iter::ParallelIterator;
+
+use crate::args::Atoms;
+use crate::reporting;
+
+pub fn atoms(args: &Atoms, cli: &mut dyn Cli, query_config: &BuckQueryConfig) -> Result<()> {
+    let config = DiscoverConfig::new(args.rebar, &args.profile);
+    let loaded = load::load_project_at(
+        cli,
+        &args.project,
+        config,
+        IncludeOtp::No,
+        Mode::Cli,
+        query_config,
+    )?;
+    let analysis = loaded.analysis();
+    let module_index = analysis.module_index(loaded.project_id)?;
+    let pb = cli.progress(module_index.len_own() as u64, "Collecting atoms");
+    let uses: Vec<(FileId, Vec<(SmolStr, TextRange)>)> = module_index
+        .iter_own()
+        .par_bridge()
+        .progress_with(pb.clone())
+        .map_with(analysis.clone(), |analysis, (_name, _source, file_id)| {
+            (file_id, analysis.file_atoms(file_id).expect("cancelled"))
+        })
+        .collect();
+    pb.finish();
+
+    let mut counts: FxHashMap<SmolStr, usize> = FxHashMap::default();
+    for (_, atoms) in &uses {
+        for (atom, _) in atoms {
+            *counts.entry(atom.clone()).or_default() += 1;
+        }
+    }
+
+    if !args.report {
+        for (atom, count) in counts
+            .iter()
+            .sorted_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)))
+        {
+            writeln!(cli, "{count:>8} {atom}")?;
+        }
+        return Ok(());
+    }
+
+    let clusters = atom_clusters(&counts);
+    writeln!(cli, "Near-identical atoms ({}):", clusters.len())?;
+    for cluster in &clusters {
+        let atoms = cluster
+            .atoms
+            .iter()
+            .map(|(atom, count)| format!("{atom} ({count})"))
+            .join(", ");
+        writeln!(cli, "  {atoms}")?;
+    }
+
+    let suspicious: FxHashMap<&SmolStr, &SmolStr> = clusters
+        .iter()
+        .flat_map(|cluster| cluster.suspicious())
+        .collect();
+    let mut locations = Vec::new();
+    for (file_id, atoms) in &uses {
+        for (atom, range) in atoms {
+            if let Some(sibling) = suspicious.get(atom) {
+                let vfs_path = loaded.vfs.file_path(*file_id);
+                let root_path = &analysis
+                    .project_data(*file_id)?
+                    .unwrap_or_else(|| panic!("could not find project data"))
+                    .root_dir;
+                let relative_path = reporting::get_relative_path(root_path, &vfs_path);
+                let line = analysis.line_index(*file_id)?.line_col(range.start()).line + 1;
+                locations.push(format!(
+                    "  {}:{line}: '{atom}' is used once, did you mean '{sibling}'?",
+                    relative_path.display()
+                ));
+            }
+        }
+    }
+    locations.sort();
+    writeln!(cli, "Suspicious atoms ({}):", locations.len())?;
+    for location in locations {
+        writeln!(cli, "{location}")?;
+    }
+    Ok(())
+}
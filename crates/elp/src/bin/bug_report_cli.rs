@@ -0,0 +1,75 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::fs;
+
+use anyhow::Result;
+use elp::bug_report::BugReport;
+use elp::cli::Cli;
+use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
+use elp_log::timeit;
+use elp_project_model::buck::BuckQueryConfig;
+use stdx::format_to;
+
+use crate::args;
+use crate::build_info_cli::load_fallback;
+use crate::build_info_cli::load_project;
+
+pub fn bug_report(
+    args: &args::BugReport,
+    cli: &mut dyn Cli,
+    query_config: &BuckQueryConfig,
+) -> Result<()> {
+    let root = fs::canonicalize(&args.project)?;
+    let root = AbsPathBuf::assert_utf8(root);
+    let mut report = BugReport::new(Some(&root));
+
+    let mut project_info = String::new();
+    let loaded = {
+        let _timer = timeit!("bug-report: load project");
+        match load_project(&root, query_config) {
+            Ok(res) => Ok(res),
+            Err(err) => {
+                format_to!(
+                    project_info,
+                    "could not load manifest:\n-----\n{err},\n-----\nfalling back\n"
+                );
+                load_fallback(&root, query_config)
+            }
+        }
+    };
+    match loaded {
+        Ok((manifest, project)) => {
+            format_to!(project_info, "================manifest================\n");
+            format_to!(project_info, "{:#?}\n", manifest);
+            format_to!(
+                project_info,
+                "================project_build_data================\n"
+            );
+            format_to!(project_info, "{:#?}\n", project.project_build_data);
+            format_to!(
+                project_info,
+                "================project_app_data================\n"
+            );
+            format_to!(project_info, "{:#?}\n", project.project_apps);
+        }
+        Err(err) => format_to!(project_info, "could not load project:\n{err:#}\n"),
+    }
+    report.add("project.txt", project_info);
+    report.add_recent_logs();
+    report.add_panics();
+    report.write(&args.to)?;
+
+    writeln!(cli, "Bug report written to {}", args.to.display())?;
+    writeln!(
+        cli,
+        "Please check it does not contain anything confidential before sharing it."
+    )?;
+    Ok(())
+}
@@ -18,7 +18,6 @@ use elp_project_model::buck::query_buck_targets_raw;
 use elp_project_model::buck::BuckQueryConfig;
 use elp_project_model::json::JsonConfig;
 use elp_project_model::ElpConfig;
-use elp_project_model::EqwalizerConfig;
 use elp_project_model::IncludeParentDirs;
 use elp_project_model::Project;
 use elp_project_model::ProjectBuildData;
@@ -31,7 +30,7 @@ pub(crate) fn save_build_info(args: BuildInfo, query_config: &BuckQueryConfig) -
     let root = fs::canonicalize(&args.project)?;
     let root = AbsPathBuf::assert_utf8(root);
     let (_elp_config, manifest) = ProjectManifest::discover(&root)?;
-    let project = Project::load(&manifest, EqwalizerConfig::default(), query_config)?;
+    let project = Project::load(&manifest, &ElpConfig::default(), query_config)?;
     let mut writer = File::create(&args.to)?;
     let json_str = serde_json::to_string_pretty::<JsonConfig>(&project.as_json(root))?;
     writer.write_all(json_str.as_bytes())?;
@@ -78,21 +77,21 @@ pub(crate) fn save_project_info(args: ProjectInfo, query_config: &BuckQueryConfi
     Ok(())
 }
 
-fn load_project(
+pub(crate) fn load_project(
     root: &AbsPath,
     query_config: &BuckQueryConfig,
 ) -> Result<(ProjectManifest, Project)> {
     let (elp_config, manifest) = ProjectManifest::discover(root)?;
-    let project = Project::load(&manifest, elp_config.eqwalizer, query_config)?;
+    let project = Project::load(&manifest, &elp_config, query_config)?;
     Ok((manifest, project))
 }
 
-fn load_fallback(
+pub(crate) fn load_fallback(
     root: &AbsPath,
     query_config: &BuckQueryConfig,
 ) -> Result<(ProjectManifest, Project)> {
     let manifest = ProjectManifest::discover_no_manifest(root, IncludeParentDirs::Yes);
     let elp_config = ElpConfig::default();
-    let project = Project::load(&manifest, elp_config.eqwalizer, query_config)?;
+    let project = Project::load(&manifest, &elp_config, query_config)?;
     Ok((manifest, project))
 }
@@ -7,13 +7,46 @@
  * of this source tree.
  */
 
+use std::fs;
+
 use anyhow::Result;
 use elp::cli::Cli;
+use elp::config::layers::fetch_org_config;
+use elp::config::layers::ConfigLayers;
 use elp::config::Config;
+use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
+use lsp_types::ClientCapabilities;
 
 use crate::args::ConfigStanza;
 
-pub fn config_stanza(_args: &ConfigStanza, cli: &mut dyn Cli) -> Result<()> {
-    let schema = format!("{:#}", Config::json_schema());
-    Ok(writeln!(cli, "{}", schema)?)
+pub fn config_stanza(args: &ConfigStanza, cli: &mut dyn Cli) -> Result<()> {
+    if !args.resolved && !args.json {
+        let schema = format!("{:#}", Config::json_schema());
+        return Ok(writeln!(cli, "{}", schema)?);
+    }
+    let root = fs::canonicalize(&args.project)?;
+    let mut config = Config::new(AbsPathBuf::assert_utf8(root), ClientCapabilities::default());
+    config.load_layers();
+    if let Some(url) = ConfigLayers::org_config_url() {
+        let settings = fetch_org_config(&url);
+        config.set_org_config(url, settings);
+    }
+    for issue in config.issues() {
+        writeln!(cli.err(), "warning: {}", issue)?;
+    }
+    let resolved = config.resolved();
+    if args.json {
+        writeln!(cli, "{:#}", serde_json::Value::Object(resolved))?;
+    } else {
+        for (name, setting) in resolved {
+            writeln!(
+                cli,
+                "{} = {} ({})",
+                name,
+                setting["value"],
+                setting["layer"].as_str().unwrap_or_default()
+            )?;
+        }
+    }
+    Ok(())
 }
@@ -20,10 +20,12 @@ use elp::build::load;
 use elp::build::types::LoadResult;
 use elp::cli::Cli;
 use elp::convert;
+use elp::convert::ClientPaths;
 use elp::otp_file_to_ignore;
 use elp::server::file_id_to_url;
 use elp_eqwalizer::Mode;
 use elp_ide::diagnostics;
+use elp_ide::diagnostics::DiagnosticCode;
 use elp_ide::diagnostics::DiagnosticsConfig;
 use elp_ide::diagnostics::LabeledDiagnostics;
 use elp_ide::diagnostics::RemoveElpReported;
@@ -215,7 +217,7 @@ fn print_diagnostic(
     err_in_diag: &mut bool,
     cli: &mut dyn Cli,
 ) -> Result<(), anyhow::Error> {
-    let diag = convert::ide_to_lsp_diagnostic(&line_index, &url, &diag);
+    let diag = convert::ide_to_lsp_diagnostic(&line_index, &url, &diag, |_| None);
     let severity = match diag.severity {
         None => DiagnosticSeverity::ERROR,
         Some(sev) => {
@@ -241,7 +243,7 @@ fn maybe_code_as_string(mc: Option<NumberOrString>) -> String {
     match mc {
         Some(ns) => match ns {
             NumberOrString::Number(n) => format!("{}", n),
-            NumberOrString::String(s) => s,
+            NumberOrString::String(s) => DiagnosticCode::unqualified(&s).to_string(),
         },
         None => "".to_string(),
     }
@@ -315,7 +317,7 @@ fn do_parse_one(
     file_id: FileId,
     name: &str,
 ) -> Result<Option<ParseResult>> {
-    let url = file_id_to_url(vfs, file_id);
+    let url = file_id_to_url(vfs, &ClientPaths::default(), file_id);
     let native = db.native_diagnostics(config, &vec![], file_id)?;
     let erlang_service_diagnostics =
         db.erlang_service_diagnostics(file_id, config, RemoveElpReported::Yes)?;
@@ -333,11 +335,13 @@ fn do_parse_one(
         let mut output = File::create(to_path)?;
 
         for diagnostic in native.iter() {
-            let diagnostic = convert::ide_to_lsp_diagnostic(&line_index, &url, diagnostic);
+            let diagnostic =
+                convert::ide_to_lsp_diagnostic(&line_index, &url, diagnostic, |_| None);
             writeln!(output, "{:?}", diagnostic)?;
         }
         for diagnostic in erlang_service.iter() {
-            let diagnostic = convert::ide_to_lsp_diagnostic(&line_index, &url, diagnostic);
+            let diagnostic =
+                convert::ide_to_lsp_diagnostic(&line_index, &url, diagnostic, |_| None);
             writeln!(output, "{:?}", diagnostic)?;
         }
     }
@@ -19,6 +19,7 @@ use elp::build;
 use elp::build::load;
 use elp::build::types::LoadResult;
 use elp::cli::Cli;
+use elp::cli::Event;
 use elp::convert;
 use elp_eqwalizer::EqwalizerConfig;
 use elp_eqwalizer::EqwalizerDiagnosticsDatabase;
@@ -31,17 +32,22 @@ use elp_ide::diagnostics_collection::DiagnosticCollection;
 use elp_ide::elp_ide_db::elp_base_db::FileId;
 use elp_ide::elp_ide_db::elp_base_db::IncludeOtp;
 use elp_ide::elp_ide_db::elp_base_db::ModuleName;
+use elp_ide::elp_ide_db::elp_base_db::ProjectId;
 use elp_ide::elp_ide_db::elp_base_db::VfsPath;
 use elp_ide::elp_ide_db::EqwalizerDiagnostics;
 use elp_ide::elp_ide_db::LineIndex;
 use elp_ide::elp_ide_db::LineIndexDatabase;
 use elp_ide::erlang_service;
 use elp_ide::Analysis;
+use elp_ide::TextRange;
 use elp_project_model::buck::BuckQueryConfig;
 use elp_project_model::AppName;
 use elp_project_model::DiscoverConfig;
 use elp_project_model::ProjectBuildData;
 use fxhash::FxHashMap;
+use fxhash::FxHashSet;
+use hir::Name;
+use hir::NameArity;
 use indicatif::ParallelProgressIterator;
 use itertools::Itertools;
 use lazy_static::lazy_static;
@@ -50,6 +56,7 @@ use rayon::prelude::*;
 use crate::args::Eqwalize;
 use crate::args::EqwalizeAll;
 use crate::args::EqwalizeApp;
+use crate::args::EqwalizeFunctionErrors;
 use crate::args::EqwalizeStats;
 use crate::args::EqwalizeTarget;
 use crate::reporting;
@@ -60,6 +67,12 @@ struct EqwalizerInternalArgs<'a> {
     analysis: &'a Analysis,
     loaded: &'a LoadResult,
     file_ids: Vec<FileId>,
+    /// Only report diagnostics within this range, set when eqwalizing
+    /// a single function
+    range: Option<TextRange>,
+    /// Groups of `file_ids` checked one after the other, each in
+    /// parallel, set when the modules are scheduled by dependency level
+    levels: Option<Vec<Vec<FileId>>>,
     reporter: &'a mut dyn reporting::Reporter,
 }
 
@@ -108,6 +121,8 @@ pub fn do_eqwalize_module(
         analysis,
         loaded,
         file_ids,
+        range: None,
+        levels: None,
         reporter,
     })
 }
@@ -164,13 +179,18 @@ pub fn do_eqwalize_all(
     pb.finish();
 
     let mut json_reporter;
+    let mut junit_reporter;
     let mut pretty_reporter;
 
-    let reporter: &mut dyn Reporter = match args.format {
+    let reporter: &mut dyn Reporter = match args.format.as_deref() {
         None => {
             pretty_reporter = reporting::PrettyReporter::new(analysis, loaded, cli);
             &mut pretty_reporter
         }
+        Some("junit") => {
+            junit_reporter = reporting::JunitReporter::new(analysis, loaded, cli);
+            &mut junit_reporter
+        }
         Some(_) => {
             json_reporter = reporting::JsonReporter::new(analysis, loaded, cli);
             &mut json_reporter
@@ -181,6 +201,8 @@ pub fn do_eqwalize_all(
         analysis,
         loaded,
         file_ids,
+        range: None,
+        levels: None,
         reporter,
     })
 }
@@ -226,15 +248,174 @@ pub fn do_eqwalize_app(
             }
         })
         .collect();
+    let levels = dependency_levels(analysis, loaded.project_id, file_ids)?;
+
+    let mut json_reporter;
+    let mut junit_reporter;
+    let mut pretty_reporter;
+
+    let reporter: &mut dyn Reporter = match args.format.as_deref() {
+        None => {
+            pretty_reporter = reporting::PrettyReporter::new(analysis, loaded, cli);
+            &mut pretty_reporter
+        }
+        Some("junit") => {
+            junit_reporter = reporting::JunitReporter::new(analysis, loaded, cli);
+            &mut junit_reporter
+        }
+        Some(_) => {
+            json_reporter = reporting::JsonReporter::new(analysis, loaded, cli);
+            &mut json_reporter
+        }
+    };
+
+    eqwalize(EqwalizerInternalArgs {
+        analysis,
+        loaded,
+        file_ids: levels.concat(),
+        range: None,
+        levels: Some(levels),
+        reporter,
+    })
+}
+
+/// Groups modules by dependency level. The modules of a level only call
+/// modules of the set in earlier levels, which are checked before the
+/// level starts, so the stubs eqwalizer derives from them are computed
+/// once rather than concurrently by several tasks. Cycles are broken by
+/// module name.
+fn dependency_levels(
+    analysis: &Analysis,
+    project_id: ProjectId,
+    file_ids: Vec<FileId>,
+) -> Result<Vec<Vec<FileId>>> {
+    let module_index = analysis.module_index(project_id)?;
+    let in_set: FxHashSet<FileId> = file_ids.iter().copied().collect();
+    let mut deps: FxHashMap<FileId, Vec<FileId>> = FxHashMap::default();
+    for &file_id in &file_ids {
+        let called = analysis
+            .remote_modules(file_id)?
+            .iter()
+            .filter_map(|module| module_index.file_for_module(module))
+            .filter(|dep| *dep != file_id && in_set.contains(dep))
+            .collect();
+        deps.insert(file_id, called);
+    }
+    let roots = file_ids
+        .into_iter()
+        .sorted_by_key(|file_id| module_index.module_for_file(*file_id));
+    Ok(levels_of(roots, &deps))
+}
+
+/// Assigns each file the level following the highest level of its
+/// dependencies, visiting them depth first from `roots` in order. A
+/// dependency still being visited closes a cycle, and is ignored.
+fn levels_of(
+    roots: impl Iterator<Item = FileId>,
+    deps: &FxHashMap<FileId, Vec<FileId>>,
+) -> Vec<Vec<FileId>> {
+    let mut level: FxHashMap<FileId, usize> = FxHashMap::default();
+    let mut in_progress: FxHashSet<FileId> = FxHashSet::default();
+    let mut levels: Vec<Vec<FileId>> = Vec::new();
+    for root in roots {
+        if level.contains_key(&root) {
+            continue;
+        }
+        in_progress.insert(root);
+        let mut stack = vec![(root, 0)];
+        while let Some((file_id, next)) = stack.pop() {
+            let file_deps = deps.get(&file_id).map_or(&[][..], Vec::as_slice);
+            if let Some(dep) = file_deps.get(next) {
+                stack.push((file_id, next + 1));
+                if !level.contains_key(dep) && in_progress.insert(*dep) {
+                    stack.push((*dep, 0));
+                }
+                continue;
+            }
+            in_progress.remove(&file_id);
+            let file_level = file_deps
+                .iter()
+                .filter_map(|dep| level.get(dep))
+                .map(|dep_level| dep_level + 1)
+                .max()
+                .unwrap_or(0);
+            level.insert(file_id, file_level);
+            if levels.len() <= file_level {
+                levels.resize_with(file_level + 1, Vec::new);
+            }
+            levels[file_level].push(file_id);
+        }
+    }
+    levels
+}
+
+pub fn eqwalize_function_errors(
+    args: &EqwalizeFunctionErrors,
+    cli: &mut dyn Cli,
+    query_config: &BuckQueryConfig,
+) -> Result<()> {
+    let config = DiscoverConfig::new(args.rebar, &args.profile);
+    let mut loaded = load::load_project_at(
+        cli,
+        &args.project,
+        config,
+        IncludeOtp::Yes,
+        Mode::Cli,
+        query_config,
+    )?;
+    build::compile_deps(&loaded, cli)?;
+    do_eqwalize_function_errors(args, &mut loaded, cli)
+}
+
+/// Eqwalizer checks whole modules, looking up the specs of the
+/// functions they call, so this is a filter only: the module defining
+/// the function is eqwalized as a whole, which takes as long as
+/// `elp eqwalize` on it, and only the diagnostics within the function
+/// are reported.
+pub fn do_eqwalize_function_errors(
+    args: &EqwalizeFunctionErrors,
+    loaded: &mut LoadResult,
+    cli: &mut dyn Cli,
+) -> Result<()> {
+    set_eqwalizer_config(loaded, args.clause_coverage);
+    let analysis = &loaded.analysis();
+    let (module, function) = parse_function(&args.function)?;
+    let file_id = analysis
+        .module_file_id(loaded.project_id, module)?
+        .with_context(|| format!("Module {} not found", module))?;
+    let range = analysis
+        .function_range(file_id, &function)?
+        .with_context(|| format!("Function {}:{} not found", module, function))?;
     let mut reporter = reporting::PrettyReporter::new(analysis, loaded, cli);
     eqwalize(EqwalizerInternalArgs {
         analysis,
         loaded,
-        file_ids,
+        file_ids: vec![file_id],
+        range: Some(range),
+        levels: None,
         reporter: &mut reporter,
     })
 }
 
+/// Parses a function given as `module:function/arity`
+fn parse_function(function: &str) -> Result<(&str, NameArity)> {
+    let parsed = function.split_once(':').and_then(|(module, fa)| {
+        let (name, arity) = fa.rsplit_once('/')?;
+        let arity = arity.parse().ok()?;
+        Some((
+            module,
+            NameArity::new(Name::from_erlang_service(name), arity),
+        ))
+    });
+    match parsed {
+        Some(parsed) => Ok(parsed),
+        None => bail!(
+            "Expected a function as module:function/arity, got {}",
+            function
+        ),
+    }
+}
+
 pub fn eqwalize_target(
     args: &EqwalizeTarget,
     cli: &mut dyn Cli,
@@ -308,6 +489,8 @@ elp eqwalize-target erl/chatd #same as //erl/chatd/... but enables shell complet
         analysis,
         loaded: &loaded,
         file_ids,
+        range: None,
+        levels: None,
         reporter: &mut reporter,
     })
 }
@@ -395,6 +578,8 @@ fn eqwalize(
         analysis,
         loaded,
         file_ids,
+        range,
+        levels,
         reporter,
     }: EqwalizerInternalArgs,
 ) -> Result<()> {
@@ -402,28 +587,37 @@ fn eqwalize(
         bail!("No files to eqWAlize detected")
     }
 
+    reporter.event(Event::Section("Type checking"));
     pre_parse_for_speed(reporter, analysis.clone(), &file_ids);
 
     let files_count = file_ids.len();
+    let checked: FxHashSet<FileId> = file_ids.iter().copied().collect();
+    let levels = levels.unwrap_or_else(|| vec![file_ids]);
     let pb = reporter.progress(files_count as u64, "EqWAlizing");
     let output = loaded.with_eqwalizer_progress_bar(pb.clone(), move |analysis| {
         let project_id = loaded.project_id;
         let max_tasks = loaded.project.eqwalizer_config.max_tasks;
-        let chunk_size = (files_count + max_tasks - 1) / max_tasks;
-        file_ids
-            .chunks(chunk_size)
-            .par_bridge()
-            .map_with(analysis, move |analysis, file_ids| {
-                analysis
-                    .eqwalizer_diagnostics(project_id, file_ids.to_vec())
-                    .expect("cancelled")
-            })
-            .fold(EqwalizerDiagnostics::default, |acc, output| {
-                acc.combine((*output).clone())
-            })
-            .reduce(EqwalizerDiagnostics::default, |acc, other| {
-                acc.combine(other)
-            })
+        levels.iter().filter(|level| !level.is_empty()).fold(
+            EqwalizerDiagnostics::default(),
+            |acc, level| {
+                let chunk_size = (level.len() + max_tasks - 1) / max_tasks;
+                let output = level
+                    .chunks(chunk_size)
+                    .par_bridge()
+                    .map_with(analysis.clone(), move |analysis, file_ids| {
+                        analysis
+                            .eqwalizer_diagnostics(project_id, file_ids.to_vec())
+                            .expect("cancelled")
+                    })
+                    .fold(EqwalizerDiagnostics::default, |acc, output| {
+                        acc.combine((*output).clone())
+                    })
+                    .reduce(EqwalizerDiagnostics::default, |acc, other| {
+                        acc.combine(other)
+                    });
+                acc.combine(output)
+            },
+        )
     });
     let eqwalized = pb.position();
     pb.finish();
@@ -432,16 +626,49 @@ fn eqwalize(
             errors: diagnostics_by_module,
             ..
         } => {
-            for (module, diagnostics) in diagnostics_by_module
+            let mut count = 0;
+            let module_index = analysis.module_index(loaded.project_id)?;
+            let mut diagnostics_by_file = diagnostics_by_module
                 .into_iter()
-                .sorted_by(|(name1, _), (name2, _)| Ord::cmp(name1, name2))
-            {
-                let file_id = analysis
-                    .module_index(loaded.project_id)?
-                    .file_for_module(module.as_str())
-                    .with_context(|| format!("module {} not found", module))?;
+                .map(|(module, diagnostics)| {
+                    let file_id = module_index
+                        .file_for_module(module.as_str())
+                        .with_context(|| format!("module {} not found", module))?;
+                    Ok((file_id, diagnostics))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            // Modules without type errors are reported too, as passing
+            // test cases of JUnit reports
+            let reported: FxHashSet<FileId> = diagnostics_by_file
+                .iter()
+                .map(|(file_id, _)| *file_id)
+                .collect();
+            diagnostics_by_file.extend(
+                checked
+                    .difference(&reported)
+                    .map(|file_id| (*file_id, Vec::new())),
+            );
+            // Report by path, then range, then code, whatever the order
+            // the chunks were type-checked in
+            diagnostics_by_file.sort_by_cached_key(|(file_id, _)| loaded.vfs.file_path(*file_id));
+            for (file_id, diagnostics) in diagnostics_by_file {
+                let diagnostics: Vec<_> = diagnostics
+                    .into_iter()
+                    .filter(|d| range.map_or(true, |range| range.contains_range(d.range)))
+                    .sorted_by(|d1, d2| {
+                        Ord::cmp(
+                            &(d1.range.start(), d1.range.end(), &d1.code),
+                            &(d2.range.start(), d2.range.end(), &d2.code),
+                        )
+                    })
+                    .collect();
+                count += diagnostics.len();
                 reporter.write_eqwalizer_diagnostics(file_id, &diagnostics)?;
             }
+            reporter.event(Event::Findings {
+                kind: "type errors",
+                count,
+            });
             if analysis.eqwalizer().mode == Mode::Shell {
                 reporter.write_stats(eqwalized, files_count as u64)?;
             }
@@ -496,6 +723,10 @@ fn eqwalize(
                     })
                     .collect();
                 reporter.write_parse_diagnostics(&parse_diagnostics)?;
+                reporter.event(Event::Findings {
+                    kind: "parse errors",
+                    count: parse_diagnostics.len(),
+                });
                 Ok(())
             } else {
                 bail!(
@@ -539,3 +770,73 @@ lazy_static! {
         spec
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use elp_ide::elp_ide_db::elp_base_db::FileId;
+    use fxhash::FxHashMap;
+
+    use super::levels_of;
+    use super::parse_function;
+
+    fn levels(roots: &[u32], deps: &[(u32, &[u32])]) -> Vec<Vec<u32>> {
+        let deps: FxHashMap<FileId, Vec<FileId>> = deps
+            .iter()
+            .map(|(file, file_deps)| {
+                let file_deps = file_deps.iter().map(|dep| FileId::from_raw(*dep));
+                (FileId::from_raw(*file), file_deps.collect())
+            })
+            .collect();
+        levels_of(roots.iter().map(|root| FileId::from_raw(*root)), &deps)
+            .into_iter()
+            .map(|level| level.into_iter().map(|file_id| file_id.index()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn dependencies_come_first() {
+        assert_eq!(
+            levels(&[0, 1, 2, 3], &[(0, &[1, 2]), (1, &[2])]),
+            vec![vec![2, 3], vec![1], vec![0]]
+        );
+    }
+
+    #[test]
+    fn cycles_are_broken_in_root_order() {
+        assert_eq!(
+            levels(&[0, 1, 2], &[(0, &[1]), (1, &[0]), (2, &[0])]),
+            vec![vec![1], vec![0], vec![2]]
+        );
+    }
+
+    #[test]
+    fn deep_dependency_chain() {
+        let deps: Vec<(u32, Vec<u32>)> = (0..100_000).map(|file| (file, vec![file + 1])).collect();
+        let deps: Vec<(u32, &[u32])> = deps
+            .iter()
+            .map(|(file, file_deps)| (*file, file_deps.as_slice()))
+            .collect();
+        let levels = levels(&[0], &deps);
+        assert_eq!(levels.len(), 100_001);
+        assert_eq!(levels[0], vec![100_000]);
+        assert_eq!(levels[100_000], vec![0]);
+    }
+
+    #[test]
+    fn parse_qualified_function() {
+        let (module, function) = parse_function("my_module:my_function/2").unwrap();
+        assert_eq!(module, "my_module");
+        assert_eq!(function.to_string(), "my_function/2");
+    }
+
+    #[test]
+    fn parse_invalid_functions() {
+        for function in ["my_function/2", "my_module:my_function", "m:f/two"] {
+            let err = parse_function(function).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("Expected a function as module:function/arity, got {function}")
+            );
+        }
+    }
+}
@@ -10,15 +10,38 @@
 use anyhow::Result;
 use elp::cli::Cli;
 use elp_ide::diagnostics::DiagnosticCode;
+use include_dir::include_dir;
+use include_dir::Dir;
 
 use crate::args::Explain;
 
+/// The Erlang Error Index, as published on the website.
+static ERROR_INDEX: Dir = include_dir!("$CARGO_MANIFEST_DIR/../../website/docs/erlang-error-index");
+
 pub fn explain(args: &Explain, cli: &mut dyn Cli) -> Result<()> {
     if let Some(code) = DiagnosticCode::maybe_from_string(&args.code) {
         if let Some(uri) = DiagnosticCode::as_uri(&code) {
             let label = code.as_label();
-            return Ok(writeln!(cli, "{uri} ({label})")?);
+            writeln!(cli, "{uri} ({label})")?;
+            writeln!(cli, "Code: {}", code.as_qualified_code())?;
+            match documentation(&code) {
+                Some(doc) => write!(cli, "\n{doc}")?,
+                None => writeln!(cli, "\nNo documentation available for {}", code.as_code())?,
+            }
+            return Ok(());
         }
     }
     Ok(writeln!(cli, "Unkwnown code: {}", args.code)?)
 }
+
+/// The markdown page of the code, without its front matter.
+fn documentation(code: &DiagnosticCode) -> Option<&'static str> {
+    let namespace = code.as_namespace()?;
+    let path = format!("{namespace}/{}.md", code.as_code());
+    let text = ERROR_INDEX.get_file(path)?.contents_utf8()?;
+    let body = text
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---\n"))
+        .map_or(text, |(_front_matter, body)| body);
+    Some(body.trim_start())
+}
@@ -0,0 +1,77 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Writes the typed accessor module of each `config_schemas` entry of
+//! `.elp_lint.toml`, or checks that the modules and `sys.config` still
+//! agree with the schemas.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::bail;
+use anyhow::Result;
+use elp::build::load;
+use elp::cli::Cli;
+use elp::read_lint_config_file;
+use elp_ide::diagnostics::sys_config_drift;
+use elp_project_model::buck::BuckQueryConfig;
+use elp_project_model::AppType;
+use elp_project_model::DiscoverConfig;
+
+use crate::args::GenerateConfig;
+
+pub fn generate_config(
+    args: &GenerateConfig,
+    cli: &mut dyn Cli,
+    query_config: &BuckQueryConfig,
+) -> Result<()> {
+    let schemas = read_lint_config_file(&args.project, &None)?.config_schemas;
+    if schemas.is_empty() {
+        bail!("No config_schemas in .elp_lint.toml");
+    }
+    let config = DiscoverConfig::new(args.rebar, &args.profile);
+    let project = load::discover_project_at(cli, &args.project, config, query_config)?;
+    let mut drift = false;
+    for schema in &schemas {
+        let Some(app) = project
+            .project_apps
+            .iter()
+            .find(|app| app.app_type == AppType::App && app.name.as_str() == schema.app)
+        else {
+            bail!("No application {} in the project", schema.app);
+        };
+        let dir = match app.abs_src_dirs.first() {
+            Some(dir) => dir.clone(),
+            None => app.dir.join("src"),
+        };
+        let path = PathBuf::from(dir.as_str()).join(format!("{}.erl", schema.module));
+        let text = schema.generate_module();
+        if !args.check {
+            fs::create_dir_all(dir.as_str())?;
+            fs::write(&path, text)?;
+            writeln!(cli, "Generated {}", path.display())?;
+            continue;
+        }
+        if fs::read_to_string(&path).ok().as_ref() != Some(&text) {
+            writeln!(cli, "{}: differs from its config schema", path.display())?;
+            drift = true;
+        }
+        let sys_config = project.root().join(&schema.sys_config);
+        if let Ok(contents) = fs::read_to_string(sys_config.as_str()) {
+            for message in sys_config_drift(schema, &contents) {
+                writeln!(cli, "{}: {message}", sys_config.as_str())?;
+                drift = true;
+            }
+        }
+    }
+    if drift {
+        bail!("Regenerate the modules with `elp generate config` and update sys.config");
+    }
+    Ok(())
+}
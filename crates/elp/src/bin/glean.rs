@@ -15,6 +15,7 @@ use anyhow::Result;
 use elp::build::load;
 use elp::build::types::LoadResult;
 use elp::cli::Cli;
+use elp::cli::Event;
 use elp_eqwalizer::ast::Pos;
 use elp_eqwalizer::EqwalizerDiagnostics;
 use elp_eqwalizer::IncludeGenerated;
@@ -705,7 +706,12 @@ pub fn index(args: &Glean, cli: &mut dyn Cli, query_config: &BuckQueryConfig) ->
         multi: args.multi,
         prefix: args.prefix.clone(),
     };
+    cli.event(Event::Section("Indexing"));
     let (facts, module_index) = indexer.index(config)?;
+    cli.event(Event::Findings {
+        kind: "indexed modules",
+        count: module_index.len(),
+    });
     write_results(facts, module_index, cli, args)
 }
 
@@ -715,7 +721,10 @@ fn write_results(
     cli: &mut dyn Cli,
     args: &Glean,
 ) -> Result<()> {
-    for (name, fact) in facts {
+    for (name, fact) in facts
+        .into_iter()
+        .sorted_by(|(name1, _), (name2, _)| Ord::cmp(name1, name2))
+    {
         let fact = if args.v2 {
             fact.to_v2_facts(&module_index)
         } else {
@@ -870,6 +879,9 @@ impl GleanIndexer {
                 }
             }
         }
+        // Index in path order, so the facts of the files, and the names
+        // of the files with --multi, do not depend on hash map iteration
+        files.sort_by(|(_, path1), (_, path2)| Ord::cmp(path1, path2));
         files
     }
 
@@ -0,0 +1,177 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! JUnit XML reports, as rendered by CI systems such as Jenkins or
+//! GitLab. Each module checked is a test case, and each of its findings
+//! a failure of that test case.
+
+use std::fmt::Write;
+
+use anyhow::Result;
+use elp::cli::Cli;
+
+#[derive(Debug, Clone)]
+pub struct TestCase {
+    /// The name of the module
+    pub name: String,
+    /// The application of the module, grouping the test cases
+    pub classname: String,
+    /// The path of the module, relative to the project root
+    pub file: String,
+    pub failures: Vec<Failure>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Failure {
+    /// The diagnostic code, such as `W0010` or `incompatible_types`
+    pub code: String,
+    pub message: String,
+    /// One-based line of the finding
+    pub line: u32,
+    /// One-based column of the finding
+    pub column: u32,
+}
+
+/// Writes the report of the test suite named `suite`.
+pub fn write_report(cli: &mut dyn Cli, suite: &str, test_cases: &[TestCase]) -> Result<()> {
+    write!(cli, "{}", report(suite, test_cases))?;
+    Ok(())
+}
+
+fn report(suite: &str, test_cases: &[TestCase]) -> String {
+    let tests = test_cases.len();
+    let failures = test_cases
+        .iter()
+        .filter(|test_case| !test_case.failures.is_empty())
+        .count();
+    let suite = escape(suite);
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"{suite}\" tests=\"{tests}\" failures=\"{failures}\">"
+    );
+    let _ = writeln!(
+        out,
+        "  <testsuite name=\"{suite}\" tests=\"{tests}\" failures=\"{failures}\">"
+    );
+    for test_case in test_cases {
+        let _ = write!(
+            out,
+            "    <testcase name=\"{}\" classname=\"{}\" file=\"{}\"",
+            escape(&test_case.name),
+            escape(&test_case.classname),
+            escape(&test_case.file)
+        );
+        if test_case.failures.is_empty() {
+            out.push_str("/>\n");
+            continue;
+        }
+        out.push_str(">\n");
+        for failure in &test_case.failures {
+            let _ = writeln!(
+                out,
+                "      <failure type=\"{}\" message=\"{}\">{}:{}:{}: {}</failure>",
+                escape(&failure.code),
+                escape(&failure.message),
+                escape(&test_case.file),
+                failure.line,
+                failure.column,
+                escape(&failure.message)
+            );
+        }
+        out.push_str("    </testcase>\n");
+    }
+    out.push_str("  </testsuite>\n</testsuites>\n");
+    out
+}
+
+fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '&' => res.push_str("&amp;"),
+            '"' => res.push_str("&quot;"),
+            '\n' => res.push_str("&#10;"),
+            _ => res.push(c),
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::report;
+    use super::Failure;
+    use super::TestCase;
+
+    #[test]
+    fn passing_and_failing_modules() {
+        let test_cases = vec![
+            TestCase {
+                name: "app_a".to_string(),
+                classname: "app_a".to_string(),
+                file: "app_a/src/app_a.erl".to_string(),
+                failures: vec![Failure {
+                    code: "W0010".to_string(),
+                    message: "this variable is unused".to_string(),
+                    line: 3,
+                    column: 5,
+                }],
+            },
+            TestCase {
+                name: "app_b".to_string(),
+                classname: "app_b".to_string(),
+                file: "app_b/src/app_b.erl".to_string(),
+                failures: vec![],
+            },
+        ];
+        expect![[r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <testsuites name="elp lint" tests="2" failures="1">
+              <testsuite name="elp lint" tests="2" failures="1">
+                <testcase name="app_a" classname="app_a" file="app_a/src/app_a.erl">
+                  <failure type="W0010" message="this variable is unused">app_a/src/app_a.erl:3:5: this variable is unused</failure>
+                </testcase>
+                <testcase name="app_b" classname="app_b" file="app_b/src/app_b.erl"/>
+              </testsuite>
+            </testsuites>
+        "#]]
+        .assert_eq(&report("elp lint", &test_cases));
+    }
+
+    #[test]
+    fn escaping() {
+        let test_cases = vec![TestCase {
+            name: "main".to_string(),
+            classname: "app".to_string(),
+            file: "src/main.erl".to_string(),
+            failures: vec![Failure {
+                code: "incompatible_types".to_string(),
+                message: "Expected: <<_>>\n  Got: \"a\" & 'b'".to_string(),
+                line: 1,
+                column: 1,
+            }],
+        }];
+        expect![[r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <testsuites name="elp eqwalize" tests="1" failures="1">
+              <testsuite name="elp eqwalize" tests="1" failures="1">
+                <testcase name="main" classname="app" file="src/main.erl">
+                  <failure type="incompatible_types" message="Expected: &lt;&lt;_&gt;&gt;&#10;  Got: &quot;a&quot; &amp; 'b'">src/main.erl:1:1: Expected: &lt;&lt;_&gt;&gt;&#10;  Got: &quot;a&quot; &amp; 'b'</failure>
+                </testcase>
+              </testsuite>
+            </testsuites>
+        "#]]
+        .assert_eq(&report("elp eqwalize", &test_cases));
+    }
+}
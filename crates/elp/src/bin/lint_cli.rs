@@ -17,10 +17,12 @@ use std::str;
 use std::sync::Arc;
 
 use anyhow::bail;
+use anyhow::Context;
 use anyhow::Result;
 use elp::build::load;
 use elp::build::types::LoadResult;
 use elp::cli::Cli;
+use elp::cli::Event;
 use elp::convert;
 use elp::otp_file_to_ignore;
 use elp::read_lint_config_file;
@@ -65,6 +67,7 @@ use rayon::prelude::ParallelIterator;
 use text_edit::TextSize;
 
 use crate::args::Lint;
+use crate::junit;
 use crate::reporting;
 
 pub fn run_lint_command(
@@ -82,6 +85,7 @@ pub fn run_lint_command(
     // errors. No point wasting time if the config is wrong.
     let mut loaded = load_project(args, cli, query_config)?;
 
+    cli.event(Event::Section("Linting"));
     do_codemod(cli, &mut loaded, &diagnostics_config, args)
 }
 
@@ -120,11 +124,7 @@ fn do_parse_all(
     let module_index = analysis.module_index(*project_id).unwrap();
     let module_iter = module_index.iter_own();
 
-    let ignored_apps: FxHashSet<Option<Option<AppName>>> = args
-        .ignore_apps
-        .iter()
-        .map(|name| Some(Some(AppName(name.to_string()))))
-        .collect();
+    let ignored_apps = ignored_apps(args);
     let pb = cli.progress(module_iter.len() as u64, "Parsing modules (parallel)");
 
     Ok(module_iter
@@ -133,10 +133,7 @@ fn do_parse_all(
         .map_with(
             analysis.clone(),
             |db, (module_name, _file_source, file_id)| {
-                if !otp_file_to_ignore(db, file_id)
-                    && db.file_app_type(file_id).ok() != Some(Some(AppType::Dep))
-                    && !ignored_apps.contains(&db.file_app_name(file_id).ok())
-                {
+                if is_lint_target(db, file_id, &ignored_apps) {
                     do_parse_one(db, config, file_id, module_name.as_str(), args).unwrap()
                 } else {
                     None
@@ -147,6 +144,24 @@ fn do_parse_all(
         .collect())
 }
 
+fn ignored_apps(args: &Lint) -> FxHashSet<Option<Option<AppName>>> {
+    args.ignore_apps
+        .iter()
+        .map(|name| Some(Some(AppName(name.to_string()))))
+        .collect()
+}
+
+/// Whether the module is linted when linting the whole project.
+fn is_lint_target(
+    db: &Analysis,
+    file_id: FileId,
+    ignored_apps: &FxHashSet<Option<Option<AppName>>>,
+) -> bool {
+    !otp_file_to_ignore(db, file_id)
+        && db.file_app_type(file_id).ok() != Some(Some(AppType::Dep))
+        && !ignored_apps.contains(&db.file_app_name(file_id).ok())
+}
+
 fn do_parse_one(
     db: &Analysis,
     config: &DiagnosticsConfig,
@@ -226,7 +241,7 @@ pub fn do_codemod(
 ) -> Result<()> {
     // Declare outside the block so it has the right lifetime for filter_diagnostics
     let res;
-    let mut initial_diags = {
+    let (target, mut initial_diags) = {
         // We put this in its own block so that analysis is
         // freed before we apply lints. To apply lints
         // recursively, we need to update the underlying
@@ -280,20 +295,29 @@ pub fn do_codemod(
             }
         };
 
-        filter_diagnostics(
+        let diags = filter_diagnostics(
             &analysis,
             &args.module,
             Some(&diagnostics_config.enabled),
             &res,
             &FxHashSet::default(),
-        )?
+        )?;
+        (file_id, diags)
     };
+    cli.event(Event::Findings {
+        kind: "diagnostics",
+        count: initial_diags.iter().map(|(_, _, diags)| diags.len()).sum(),
+    });
+    sort_diagnostics(&loaded.vfs, &mut initial_diags);
+    if args.is_format_junit() {
+        let test_cases = junit_test_cases(loaded, args, target, &initial_diags)?;
+        junit::write_report(cli, "elp lint", &test_cases)?;
+    }
     if initial_diags.is_empty() {
         if args.is_format_normal() {
             writeln!(cli, "No diagnostics reported")?;
         }
     } else {
-        initial_diags.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
         let mut err_in_diag = false;
         if args.is_format_json() {
             for (_name, file_id, diags) in &initial_diags {
@@ -321,6 +345,12 @@ pub fn do_codemod(
                     }
                 }
             }
+        } else if args.is_format_junit() {
+            err_in_diag = initial_diags.iter().any(|(_, _, diags)| {
+                diags
+                    .iter()
+                    .any(|diag| diag.severity == diagnostics::Severity::Error)
+            });
         } else {
             writeln!(
                 cli,
@@ -423,6 +453,87 @@ fn print_diagnostic_json(
     Ok(())
 }
 
+/// A test case for each module linted, failing with its diagnostics.
+fn junit_test_cases(
+    loaded: &LoadResult,
+    args: &Lint,
+    target: Option<FileId>,
+    diags: &[(String, FileId, Vec<diagnostics::Diagnostic>)],
+) -> Result<Vec<junit::TestCase>> {
+    let analysis = loaded.analysis();
+    let mut file_ids = match target {
+        Some(file_id) => vec![file_id],
+        None => {
+            let ignored_apps = ignored_apps(args);
+            let module_index = analysis.module_index(loaded.project_id)?;
+            module_index
+                .iter_own()
+                .map(|(_name, _source, file_id)| file_id)
+                .filter(|file_id| is_lint_target(&analysis, *file_id, &ignored_apps))
+                .filter(|file_id| {
+                    args.include_tests
+                        || !analysis
+                            .is_test_suite_or_test_helper(*file_id)
+                            .ok()
+                            .flatten()
+                            .unwrap_or(false)
+                })
+                .collect()
+        }
+    };
+    file_ids.extend(diags.iter().map(|(_, file_id, _)| *file_id));
+    file_ids.sort_by_cached_key(|file_id| loaded.vfs.file_path(*file_id));
+    file_ids.dedup();
+
+    let diags_by_file: FxHashMap<FileId, &[diagnostics::Diagnostic]> = diags
+        .iter()
+        .map(|(_, file_id, diags)| (*file_id, diags.as_slice()))
+        .collect();
+    let mut test_cases = Vec::with_capacity(file_ids.len());
+    for file_id in file_ids {
+        let vfs_path = loaded.vfs.file_path(file_id);
+        let root_path = &analysis
+            .project_data(file_id)?
+            .with_context(|| "could not find project data")?
+            .root_dir;
+        let relative_path = reporting::get_relative_path(root_path, &vfs_path);
+        let file = with_prefix(relative_path, args.prefix.as_ref())
+            .display()
+            .to_string();
+        let name = match analysis.module_name(file_id)? {
+            Some(name) => name.as_str().to_string(),
+            None => file.clone(),
+        };
+        let classname = match analysis.file_app_name(file_id)? {
+            Some(app_name) => app_name.to_string(),
+            None => name.clone(),
+        };
+        let line_index = analysis.line_index(file_id)?;
+        let failures = diags_by_file
+            .get(&file_id)
+            .copied()
+            .unwrap_or_default()
+            .iter()
+            .map(|diag| {
+                let pos = convert::position(&line_index, diag.range.start());
+                junit::Failure {
+                    code: diag.code.as_labeled_code(),
+                    message: diag.message.clone(),
+                    line: pos.line + 1,
+                    column: pos.character + 1,
+                }
+            })
+            .collect();
+        test_cases.push(junit::TestCase {
+            name,
+            classname,
+            file,
+            failures,
+        });
+    }
+    Ok(test_cases)
+}
+
 fn filter_diagnostics<'a>(
     db: &Analysis,
     module: &'a Option<String>,
@@ -540,6 +651,15 @@ impl<'a> Lints<'a> {
         }
     }
 
+    /// The diagnostics of each file, in path order.
+    fn sorted_diags(
+        &self,
+    ) -> impl Iterator<Item = (&FileId, &(String, Vec<diagnostics::Diagnostic>))> + '_ {
+        self.diags
+            .iter()
+            .sorted_by_cached_key(|(file_id, _)| self.vfs.file_path(**file_id))
+    }
+
     // For now we assume that the surrounding codemod environment is
     // invoking this one file at a time.
     fn apply_relevant_fixes(&mut self, format_normal: bool, cli: &mut dyn Cli) -> Result<()> {
@@ -624,7 +744,7 @@ impl<'a> Lints<'a> {
             if !self.diags.is_empty() {
                 writeln!(cli, "---------------------------------------------\n")?;
                 writeln!(cli, "New filtered diagnostics")?;
-                for (file_id, (name, diags)) in &self.diags {
+                for (file_id, (name, diags)) in self.sorted_diags() {
                     writeln!(cli, "  {}: {}", name, diags.len())?;
                     for diag in diags.iter() {
                         print_diagnostic(diag, &self.analysis_host.analysis(), *file_id, cli)?;
@@ -648,7 +768,7 @@ impl<'a> Lints<'a> {
     ) -> Result<Vec<FixResult>> {
         let mut changes: Vec<FixResult> = Vec::default();
         if self.args.one_shot {
-            self.diags.iter().for_each(|(file_id, (m, ds))| {
+            self.sorted_diags().for_each(|(file_id, (m, ds))| {
                 if let Ok(fs) = self.apply_all_fixes(m, ds, *file_id, format_normal, cli) {
                     changes.extend(fs.into_iter());
                 }
@@ -657,8 +777,7 @@ impl<'a> Lints<'a> {
             // Only apply a single fix, then re-parse. This avoids potentially
             // conflicting changes.
             changes = self
-                .diags
-                .iter()
+                .sorted_diags()
                 .flat_map(|(file_id, (m, ds))| {
                     ds.iter().next().map_or(Ok(vec![]), |d| {
                         self.apply_fixes(m, d, *file_id, format_normal, cli)
@@ -841,6 +960,19 @@ impl<'a> Lints<'a> {
     }
 }
 
+/// Sorts the diagnostics by path, then range, then code, so the output
+/// does not depend on the order the modules were processed in.
+fn sort_diagnostics(vfs: &Vfs, diags: &mut [(String, FileId, Vec<diagnostics::Diagnostic>)]) {
+    diags.sort_by_cached_key(|(_, file_id, _)| vfs.file_path(*file_id));
+    for (_, _, diags) in diags.iter_mut() {
+        diags.sort_by_cached_key(diagnostic_sort_key);
+    }
+}
+
+fn diagnostic_sort_key(diag: &diagnostics::Diagnostic) -> (TextSize, TextSize, String) {
+    (diag.range.start(), diag.range.end(), diag.code.as_code())
+}
+
 fn diagnostics_by_file_id(
     diags: &Vec<(String, FileId, Vec<diagnostics::Diagnostic>)>,
 ) -> std::collections::HashMap<
@@ -861,6 +993,9 @@ fn diagnostics_by_file_id(
             }
         };
     }
+    for (_name, diags) in diags_by_file_id.values_mut() {
+        diags.sort_by_cached_key(diagnostic_sort_key);
+    }
     diags_by_file_id
 }
 
@@ -934,6 +1069,15 @@ mod tests {
             },
             enabled_lints: vec![DiagnosticCode::HeadMismatch],
             disabled_lints: vec![],
+            preferred_fixes: Default::default(),
+            injections: vec![],
+            must_use: vec![],
+            assert_must_use: false,
+            target_otp_release: None,
+            migrations: vec![],
+            module_template: None,
+            config_schemas: vec![],
+            denied_calls: vec![],
         })
         .unwrap();
 
@@ -960,6 +1104,22 @@ mod tests {
         let lint_config: LintConfig = toml::from_str(
             r#"enabled_lints =['W0014', 'trivial_match']
                disabled_lints = []
+               must_use = ['db:commit/1']
+               target_otp_release = 24
+               [preferred_fixes]
+               W0010 = 'prefix_with_underscore'
+               [[injections]]
+               mfa = 'epgsql:squery/2'
+               language = 'sql'
+               [[migrations]]
+               from = 'old_lib:fetch/2'
+               to = 'new_lib:get/2'
+               same_args = true
+               [[denied_calls]]
+               mfa = 'erlang:now/0'
+               message = 'use erlang:timestamp/0'
+               severity = 'weak_warning'
+               replacement = 'erlang:timestamp/0'
              "#,
         )
         .unwrap();
@@ -974,6 +1134,67 @@ mod tests {
                 ad_hoc_lints: LintsFromConfig {
                     lints: [],
                 },
+                preferred_fixes: {
+                    UnusedFunctionArg: "prefix_with_underscore",
+                },
+                injections: [
+                    Injection {
+                        mfa: MFA {
+                            module: "epgsql",
+                            name: "squery",
+                            arity: 2,
+                        },
+                        language: Sql,
+                    },
+                ],
+                must_use: [
+                    MFA {
+                        module: "db",
+                        name: "commit",
+                        arity: 1,
+                    },
+                ],
+                assert_must_use: false,
+                target_otp_release: Some(
+                    24,
+                ),
+                migrations: [
+                    ApiMigration {
+                        from: MFA {
+                            module: "old_lib",
+                            name: "fetch",
+                            arity: 2,
+                        },
+                        to: MFA {
+                            module: "new_lib",
+                            name: "get",
+                            arity: 2,
+                        },
+                        since: None,
+                        same_args: true,
+                    },
+                ],
+                module_template: None,
+                config_schemas: [],
+                denied_calls: [
+                    DeniedCall {
+                        mfa: MFA {
+                            module: "erlang",
+                            name: "now",
+                            arity: 0,
+                        },
+                        message: "use erlang:timestamp/0",
+                        severity: WeakWarning,
+                        replacement: Some(
+                            MFA {
+                                module: "erlang",
+                                name: "timestamp",
+                                arity: 0,
+                            },
+                        ),
+                        include_tests: false,
+                    },
+                ],
             }
         "#]]
         .assert_debug_eq(&lint_config);
@@ -12,17 +12,21 @@ use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
 use std::process;
+use std::sync::Arc;
 use std::sync::Once;
 
 use anyhow::Result;
 use bpaf::batteries;
+use elp::bug_report;
 use elp::cli;
 use elp::cli::Cli;
+use elp::server::metrics::JsonLinesMetricsSink;
 use elp::ServerSetup;
 use elp_ide::erlang_service::ESCRIPT;
 use elp_log::timeit;
 use elp_log::FileLogger;
 use elp_log::Logger;
+use elp_log::RecentLogger;
 use elp_project_model::eqwalizer_support;
 use elp_project_model::otp::ERL;
 use include_dir::include_dir;
@@ -30,6 +34,8 @@ use include_dir::Dir;
 use lsp_server::Connection;
 
 mod args;
+mod atoms_cli;
+mod bug_report_cli;
 mod build_info_cli;
 mod config_stanza;
 mod dialyzer_cli;
@@ -37,10 +43,19 @@ mod elp_parse_cli;
 mod eqwalizer_cli;
 mod erlang_service_cli;
 mod explain_cli;
+mod generate_config_cli;
 mod glean;
+mod junit;
 mod lint_cli;
+mod migrate_docs_cli;
+mod new_module_cli;
+#[cfg(feature = "otel")]
+mod otel;
+mod render_html_cli;
 mod reporting;
 mod shell;
+mod statem_graph_cli;
+mod stats_cli;
 
 // Use jemalloc as the global allocator
 #[cfg(not(target_env = "msvc"))]
@@ -62,6 +77,7 @@ const THREAD_STACK_SIZE: usize = 10_000_000;
 
 fn main() {
     let _timer = timeit!("main");
+    bug_report::install_panic_hook();
     let mut cli = cli::Real::default();
     let args = args::args().run();
     let res = try_main(&mut cli, args);
@@ -97,6 +113,15 @@ fn setup_static(args: &Args) {
 
 fn try_main(cli: &mut dyn Cli, args: Args) -> Result<()> {
     let logger = setup_logging(&args.log_file, args.no_log_buffering)?;
+    #[cfg(feature = "otel")]
+    let _otel = match &args.otel_endpoint {
+        Some(endpoint) => Some(otel::init(endpoint)?),
+        None => None,
+    };
+    #[cfg(not(feature = "otel"))]
+    if args.otel_endpoint.is_some() {
+        anyhow::bail!("--otel-endpoint needs ELP to be built with the `otel` feature");
+    }
 
     INIT.call_once(|| {
         setup_static(&args);
@@ -104,13 +129,16 @@ fn try_main(cli: &mut dyn Cli, args: Args) -> Result<()> {
     });
     let query_config = args.query_config();
     match args.command {
-        args::Command::RunServer(_) => run_server(logger)?,
+        args::Command::RunServer(args) => run_server(&args, logger)?,
         args::Command::ParseAll(args) => erlang_service_cli::parse_all(&args, cli, &query_config)?,
         args::Command::ParseAllElp(args) => elp_parse_cli::parse_all(&args, cli, &query_config)?,
         args::Command::Eqwalize(args) => eqwalizer_cli::eqwalize_module(&args, cli, &query_config)?,
         args::Command::EqwalizeAll(args) => eqwalizer_cli::eqwalize_all(&args, cli, &query_config)?,
         args::Command::DialyzeAll(args) => dialyzer_cli::dialyze_all(&args, cli)?,
         args::Command::EqwalizeApp(args) => eqwalizer_cli::eqwalize_app(&args, cli, &query_config)?,
+        args::Command::EqwalizeFunctionErrors(args) => {
+            eqwalizer_cli::eqwalize_function_errors(&args, cli, &query_config)?
+        }
         args::Command::EqwalizeStats(args) => {
             eqwalizer_cli::eqwalize_stats(&args, cli, &query_config)?
         }
@@ -133,6 +161,20 @@ fn try_main(cli: &mut dyn Cli, args: Args) -> Result<()> {
         args::Command::Explain(args) => explain_cli::explain(&args, cli)?,
         args::Command::Glean(args) => glean::index(&args, cli, &query_config)?,
         args::Command::ConfigStanza(args) => config_stanza::config_stanza(&args, cli)?,
+        args::Command::Atoms(args) => atoms_cli::atoms(&args, cli, &query_config)?,
+        args::Command::Stats(args) => stats_cli::stats(&args, cli, &query_config)?,
+        args::Command::MigrateDocs(args) => {
+            migrate_docs_cli::migrate_docs(&args, cli, &query_config)?
+        }
+        args::Command::RenderHtml(args) => render_html_cli::render_html(&args, cli, &query_config)?,
+        args::Command::StatemGraph(args) => {
+            statem_graph_cli::statem_graph(&args, cli, &query_config)?
+        }
+        args::Command::NewModule(args) => new_module_cli::new_module(&args, cli, &query_config)?,
+        args::Command::GenerateConfig(args) => {
+            generate_config_cli::generate_config(&args, cli, &query_config)?
+        }
+        args::Command::BugReport(args) => bug_report_cli::bug_report(&args, cli, &query_config)?,
     }
 
     log::logger().flush();
@@ -157,6 +199,7 @@ fn setup_logging(log_file: &Option<PathBuf>, no_buffering: bool) -> Result<Logge
 
     let logger = Logger::default();
     logger.register_logger("default", Box::new(file_logger));
+    logger.register_logger("recent", Box::<RecentLogger>::default());
     logger.install();
 
     Ok(logger)
@@ -171,13 +214,18 @@ fn setup_thread_pool() -> () {
     }
 }
 
-fn run_server(logger: Logger) -> Result<()> {
+fn run_server(args: &args::RunServer, logger: Logger) -> Result<()> {
     log::info!("server will start, pid: {}", process::id());
     let (connection, io_threads) = Connection::stdio();
 
-    ServerSetup::new(connection, logger)
+    let mut server = ServerSetup::new(connection, logger)
         .to_server()?
-        .main_loop()?;
+        .print_startup_report(args.startup_report)
+        .single_file_mode(args.single_file);
+    if let Some(path) = &args.metrics_file {
+        server = server.metrics_sink(Arc::new(JsonLinesMetricsSink::open(path)?));
+    }
+    server.main_loop()?;
 
     io_threads.join()?;
     log::info!("server did shut down");
@@ -532,6 +580,20 @@ mod tests {
         }
     }
 
+    #[test_case(false ; "rebar")]
+    #[test_case(true  ; "buck")]
+    fn eqwalize_function_errors_match_snapshot_pretty(buck: bool) {
+        if otp_supported_by_eqwalizer() {
+            simple_snapshot(
+                args_vec!["eqwalize-fun-errors", "app_a:test2_neg/0"],
+                "standard",
+                expect_file!("../resources/test/standard/eqwalize_function_errors.pretty"),
+                buck,
+                None,
+            );
+        }
+    }
+
     #[test]
     fn eqwalize_target_diagnostics_match_snapshot_pretty() {
         if cfg!(feature = "buck") {
@@ -1397,6 +1459,29 @@ mod tests {
         .expect("bad test");
     }
 
+    #[test]
+    fn lint_output_does_not_depend_on_parallelism() {
+        let lint = |threads| {
+            let (args, _path) = add_project(
+                args_vec!["lint", "--experimental", "--format", "json", "--rebar"],
+                "linter",
+                None,
+                None,
+            );
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| elp(args))
+        };
+        let (expected, _stderr, _code) = lint(1);
+        assert!(!expected.is_empty());
+        for threads in [2, 4, 8] {
+            let (stdout, _stderr, _code) = lint(threads);
+            assert_eq!(expected, stdout, "output differs with {} threads", threads);
+        }
+    }
+
     #[test_case(false ; "rebar")]
     #[test_case(true  ; "buck")]
     fn lint_applies_fix_using_to_dir(buck: bool) {
@@ -1585,6 +1670,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn migrate_docs_dry_run() {
+        simple_snapshot(
+            args_vec!["migrate-docs", "--module", "app_a_edoc", "--dry-run"],
+            "linter",
+            expect_file!("../resources/test/linter/migrate_docs_dry_run.stdout"),
+            false,
+            None,
+        );
+    }
+
     #[test_case(false ; "rebar")]
     #[test_case(true  ; "buck")]
     fn lint_ct_default_no_tests(buck: bool) {
@@ -1804,6 +1900,76 @@ mod tests {
         expected.assert_eq(&stdout);
     }
 
+    #[test]
+    fn migrate_docs_help() {
+        let args = args::args()
+            .run_inner(Args::from(&["migrate-docs", "--help"]))
+            .unwrap_err();
+        let expected = expect_file!["../resources/test/migrate_docs_help.stdout"];
+        let stdout = args.unwrap_stdout();
+        expected.assert_eq(&stdout);
+    }
+
+    #[test]
+    fn render_html_help() {
+        let args = args::args()
+            .run_inner(Args::from(&["render-html", "--help"]))
+            .unwrap_err();
+        let expected = expect_file!["../resources/test/render_html_help.stdout"];
+        let stdout = args.unwrap_stdout();
+        expected.assert_eq(&stdout);
+    }
+
+    #[test]
+    fn stats_help() {
+        let args = args::args()
+            .run_inner(Args::from(&["stats", "--help"]))
+            .unwrap_err();
+        let expected = expect_file!["../resources/test/stats_help.stdout"];
+        let stdout = args.unwrap_stdout();
+        expected.assert_eq(&stdout);
+    }
+
+    #[test]
+    fn statem_graph_help() {
+        let args = args::args()
+            .run_inner(Args::from(&["statem-graph", "--help"]))
+            .unwrap_err();
+        let expected = expect_file!["../resources/test/statem_graph_help.stdout"];
+        let stdout = args.unwrap_stdout();
+        expected.assert_eq(&stdout);
+    }
+
+    #[test]
+    fn new_module_help() {
+        let args = args::args()
+            .run_inner(Args::from(&["new", "--help"]))
+            .unwrap_err();
+        let expected = expect_file!["../resources/test/new_module_help.stdout"];
+        let stdout = args.unwrap_stdout();
+        expected.assert_eq(&stdout);
+    }
+
+    #[test]
+    fn generate_config_help() {
+        let args = args::args()
+            .run_inner(Args::from(&["generate", "config", "--help"]))
+            .unwrap_err();
+        let expected = expect_file!["../resources/test/generate_config_help.stdout"];
+        let stdout = args.unwrap_stdout();
+        expected.assert_eq(&stdout);
+    }
+
+    #[test]
+    fn bug_report_help() {
+        let args = args::args()
+            .run_inner(Args::from(&["bug-report", "--help"]))
+            .unwrap_err();
+        let expected = expect_file!["../resources/test/bug_report_help.stdout"];
+        let stdout = args.unwrap_stdout();
+        expected.assert_eq(&stdout);
+    }
+
     #[test]
     fn explain_code() {
         let args = args_vec!["explain", "--code", "W0005"];
@@ -0,0 +1,99 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::fs;
+
+use anyhow::Context;
+use anyhow::Result;
+use elp::build::load;
+use elp::cli::Cli;
+use elp_eqwalizer::Mode;
+use elp_ide::diff::diff_from_textedit;
+use elp_ide::elp_ide_db::elp_base_db::IncludeOtp;
+use elp_project_model::buck::BuckQueryConfig;
+use elp_project_model::DiscoverConfig;
+use itertools::Itertools;
+
+use crate::args::MigrateDocs;
+use crate::reporting;
+
+pub fn migrate_docs(
+    args: &MigrateDocs,
+    cli: &mut dyn Cli,
+    query_config: &BuckQueryConfig,
+) -> Result<()> {
+    let config = DiscoverConfig::new(args.rebar, &args.profile);
+    let loaded = load::load_project_at(
+        cli,
+        &args.project,
+        config,
+        IncludeOtp::No,
+        Mode::Cli,
+        query_config,
+    )?;
+    let analysis = loaded.analysis();
+    let module_index = analysis.module_index(loaded.project_id)?;
+    let modules: Vec<_> = module_index
+        .iter_own()
+        .filter(|(name, _, _)| match &args.module {
+            Some(module) => module == name.as_str(),
+            None => true,
+        })
+        .map(|(name, _, file_id)| (name.as_str().to_string(), file_id))
+        .sorted_by(|(name1, _), (name2, _)| name1.cmp(name2))
+        .collect();
+    if let Some(module) = &args.module {
+        if modules.is_empty() {
+            writeln!(cli, "Module {module} not found")?;
+            return Ok(());
+        }
+    }
+
+    let mut migrated = 0;
+    for (name, file_id) in modules {
+        let change = match analysis.migrate_edoc(file_id)? {
+            Some(change) => change,
+            None => continue,
+        };
+        let before = analysis.file_text(file_id)?.to_string();
+        let mut after = before.clone();
+        // Only the edits of the module itself apply to its text
+        if let Some(edit) = change.source_file_edits.get(&file_id) {
+            edit.apply(&mut after);
+        }
+        let vfs_path = loaded.vfs.file_path(file_id);
+        let root_path = &analysis
+            .project_data(file_id)?
+            .unwrap_or_else(|| panic!("could not find project data"))
+            .root_dir;
+        let relative_path = reporting::get_relative_path(root_path, &vfs_path);
+        if args.dry_run {
+            if let (_, Some(unified)) = diff_from_textedit(&before, &after) {
+                writeln!(cli, "--- {}", relative_path.display())?;
+                writeln!(cli, "+++ {}", relative_path.display())?;
+                write!(cli, "{unified}")?;
+            }
+        } else {
+            let path = vfs_path
+                .as_path()
+                .with_context(|| format!("module {name} is not a file on disk"))?;
+            let after = match loaded.line_ending_map.get(&file_id) {
+                Some(line_endings) => line_endings.revert(after),
+                None => after,
+            };
+            fs::write(path, after)?;
+            writeln!(cli, "Migrated {}", relative_path.display())?;
+        }
+        migrated += 1;
+    }
+    if migrated == 0 {
+        writeln!(cli, "No EDoc comments to migrate")?;
+    }
+    Ok(())
+}
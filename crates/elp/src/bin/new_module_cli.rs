@@ -0,0 +1,244 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Scaffolds a module of a common kind in an application of the project,
+//! starting with the `module_template` of `.elp_lint.toml`.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::bail;
+use anyhow::Result;
+use elp::build::load;
+use elp::cli::Cli;
+use elp::read_lint_config_file;
+use elp_ide::diagnostics::ModuleTemplate;
+use elp_project_model::buck::BuckQueryConfig;
+use elp_project_model::AppType;
+use elp_project_model::DiscoverConfig;
+use elp_project_model::ProjectAppData;
+
+use crate::args::NewModule;
+
+pub fn new_module(
+    args: &NewModule,
+    cli: &mut dyn Cli,
+    query_config: &BuckQueryConfig,
+) -> Result<()> {
+    let module = module_name(&args.kind, &args.name);
+    if !is_module_name(&module) {
+        bail!("{} is not a valid module name", module);
+    }
+    let config = DiscoverConfig::new(args.rebar, &args.profile);
+    let project = load::discover_project_at(cli, &args.project, config, query_config)?;
+    let apps: Vec<&ProjectAppData> = project
+        .project_apps
+        .iter()
+        .filter(|app| app.app_type == AppType::App)
+        .collect();
+    let app = match (&args.app, apps.as_slice()) {
+        (Some(name), _) => match apps.iter().find(|app| app.name.as_str() == name) {
+            Some(app) => app,
+            None => bail!("No application {} in the project", name),
+        },
+        (None, [app]) => app,
+        (None, _) => bail!(
+            "The project has several applications, choose one with --app: {}",
+            apps.iter()
+                .map(|app| app.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let path = module_dir(&args.kind, app).join(format!("{module}.erl"));
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+    let template = read_lint_config_file(&args.project, &None)?
+        .module_template
+        .unwrap_or_default();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, module_text(&args.kind, &module, &template))?;
+    writeln!(cli, "Created {}", path.display())?;
+    Ok(())
+}
+
+/// Common Test only runs the modules whose name ends in `_SUITE`.
+fn module_name(kind: &str, name: &str) -> String {
+    if kind == "ct_suite" && !name.ends_with("_SUITE") {
+        format!("{name}_SUITE")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Whether the name can be written as an atom without quotes.
+fn is_module_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map_or(false, |first| first.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '@')
+}
+
+fn module_dir(kind: &str, app: &ProjectAppData) -> PathBuf {
+    let dir = if kind == "ct_suite" {
+        let test_dir = app
+            .extra_src_dirs
+            .iter()
+            .find(|dir| dir.starts_with("test"))
+            .map_or("test", |dir| dir.as_str());
+        app.dir.join(test_dir)
+    } else {
+        match app.abs_src_dirs.first() {
+            Some(dir) => dir.clone(),
+            None => app.dir.join("src"),
+        }
+    };
+    PathBuf::from(dir.as_str())
+}
+
+fn module_text(kind: &str, module: &str, template: &ModuleTemplate) -> String {
+    let body = match kind {
+        "gen_server" => GEN_SERVER,
+        "supervisor" => SUPERVISOR,
+        "ct_suite" => CT_SUITE,
+        _ => ESCRIPT,
+    };
+    format!("{}{body}", template.new_module(module))
+}
+
+const GEN_SERVER: &str = r#"-behaviour(gen_server).
+
+-export([start_link/0]).
+-export([init/1, handle_call/3, handle_cast/2, handle_info/2]).
+
+-record(state, {}).
+
+-spec start_link() -> {ok, pid()} | ignore | {error, term()}.
+start_link() ->
+    gen_server:start_link({local, ?MODULE}, ?MODULE, [], []).
+
+-spec init([]) -> {ok, #state{}}.
+init([]) ->
+    {ok, #state{}}.
+
+-spec handle_call(term(), gen_server:from(), #state{}) -> {reply, ok, #state{}}.
+handle_call(_Request, _From, State) ->
+    {reply, ok, State}.
+
+-spec handle_cast(term(), #state{}) -> {noreply, #state{}}.
+handle_cast(_Msg, State) ->
+    {noreply, State}.
+
+-spec handle_info(term(), #state{}) -> {noreply, #state{}}.
+handle_info(_Info, State) ->
+    {noreply, State}.
+"#;
+
+const SUPERVISOR: &str = r#"-behaviour(supervisor).
+
+-export([start_link/0]).
+-export([init/1]).
+
+-spec start_link() -> supervisor:startlink_ret().
+start_link() ->
+    supervisor:start_link({local, ?MODULE}, ?MODULE, []).
+
+-spec init([]) -> {ok, {supervisor:sup_flags(), [supervisor:child_spec()]}}.
+init([]) ->
+    SupFlags = #{strategy => one_for_one, intensity => 1, period => 5},
+    ChildSpecs = [],
+    {ok, {SupFlags, ChildSpecs}}.
+"#;
+
+const CT_SUITE: &str = r#"-include_lib("common_test/include/ct.hrl").
+
+-export([all/0, init_per_suite/1, end_per_suite/1]).
+-export([example/1]).
+
+-spec all() -> [atom()].
+all() ->
+    [example].
+
+-spec init_per_suite(ct_suite:ct_config()) -> ct_suite:ct_config().
+init_per_suite(Config) ->
+    Config.
+
+-spec end_per_suite(ct_suite:ct_config()) -> term().
+end_per_suite(_Config) ->
+    ok.
+
+-spec example(ct_suite:ct_config()) -> ok.
+example(_Config) ->
+    ok.
+"#;
+
+/// The main module of an escript, as built by `rebar3 escriptize`.
+const ESCRIPT: &str = r#"-export([main/1]).
+
+-spec main([string()]) -> no_return().
+main(Args) ->
+    io:format("~p~n", [Args]),
+    erlang:halt(0).
+"#;
+
+#[cfg(test)]
+mod tests {
+    use elp_ide::diagnostics::AuthorPolicy;
+    use elp_ide::diagnostics::ModuleTemplate;
+    use expect_test::expect;
+
+    use super::is_module_name;
+    use super::module_name;
+    use super::module_text;
+
+    #[test]
+    fn supervisor_with_template() {
+        let template = ModuleTemplate {
+            header: Some("%% Copyright (c) Acme Corp.".to_string()),
+            author: AuthorPolicy::Required,
+            author_name: Some("platform-team".to_string()),
+            attribute_order: vec![],
+        };
+        expect![[r#"
+            %% Copyright (c) Acme Corp.
+
+            -module(my_sup).
+            -author("platform-team").
+            -behaviour(supervisor).
+
+            -export([start_link/0]).
+            -export([init/1]).
+
+            -spec start_link() -> supervisor:startlink_ret().
+            start_link() ->
+                supervisor:start_link({local, ?MODULE}, ?MODULE, []).
+
+            -spec init([]) -> {ok, {supervisor:sup_flags(), [supervisor:child_spec()]}}.
+            init([]) ->
+                SupFlags = #{strategy => one_for_one, intensity => 1, period => 5},
+                ChildSpecs = [],
+                {ok, {SupFlags, ChildSpecs}}.
+        "#]]
+        .assert_eq(&module_text("supervisor", "my_sup", &template));
+    }
+
+    #[test]
+    fn suite_name() {
+        assert_eq!(module_name("ct_suite", "billing"), "billing_SUITE");
+        assert_eq!(module_name("ct_suite", "billing_SUITE"), "billing_SUITE");
+        assert_eq!(module_name("gen_server", "billing"), "billing");
+        assert!(is_module_name("my_worker"));
+        assert!(!is_module_name("MyWorker"));
+        assert!(!is_module_name("my-worker"));
+    }
+}
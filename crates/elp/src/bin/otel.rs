@@ -0,0 +1,102 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Export of the `tracing` spans of ELP, such as the handling of LSP
+//! requests and Erlang service round trips, to an OpenTelemetry collector.
+//!
+//! Spans are exported in batches over OTLP/HTTP, by a thread of their
+//! own, so that the threads ending them, such as the LSP main loop, do
+//! not wait for the collector. Which ones are exported is set by
+//! `ELP_OTEL_FILTER`, e.g. `info,salsa=debug` to also record the salsa
+//! queries executed, and defaults to `info`.
+
+use std::env;
+
+use anyhow::Context;
+use anyhow::Result;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::runtime;
+use opentelemetry_sdk::trace::Config;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::Layer;
+
+/// Flushes the spans not exported yet when dropped.
+pub struct OtelGuard(TracerProvider);
+
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        for res in self.0.force_flush() {
+            if let Err(err) = res {
+                log::warn!("Failed to export spans: {}", err);
+            }
+        }
+    }
+}
+
+pub fn init(endpoint: &str) -> Result<OtelGuard> {
+    let exporter = opentelemetry_otlp::new_exporter()
+        .http()
+        .with_endpoint(endpoint);
+    let resource = Resource::new(vec![
+        KeyValue::new("service.name", "elp"),
+        KeyValue::new("service.version", elp::version()),
+    ]);
+    let provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(Config::default().with_resource(resource))
+        .install_batch(runtime::TokioCurrentThread)
+        .with_context(|| format!("Could not export spans to {endpoint}"))?;
+
+    let filter = filter(env::var("ELP_OTEL_FILTER").ok())?;
+    let layer = tracing_opentelemetry::layer()
+        .with_tracer(provider.tracer("elp"))
+        .with_filter(filter);
+    tracing::subscriber::set_global_default(tracing_subscriber::registry().with(layer))
+        .context("A tracing subscriber is already set")?;
+    Ok(OtelGuard(provider))
+}
+
+/// The spans exported, as set by `ELP_OTEL_FILTER`.
+fn filter(value: Option<String>) -> Result<Targets> {
+    match value {
+        Some(filter) => filter
+            .parse()
+            .with_context(|| format!("Invalid ELP_OTEL_FILTER: {filter}")),
+        None => Ok(Targets::new().with_default(Level::INFO)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing::Level;
+
+    use super::filter;
+
+    #[test]
+    fn default_filter() {
+        let filter = filter(None).unwrap();
+        assert!(filter.would_enable("elp::server", &Level::INFO));
+        assert!(!filter.would_enable("salsa", &Level::DEBUG));
+    }
+
+    #[test]
+    fn filter_from_env() {
+        let filter = filter(Some("info,salsa=debug".to_string())).unwrap();
+        assert!(filter.would_enable("salsa", &Level::DEBUG));
+        assert!(!filter.would_enable("elp::server", &Level::DEBUG));
+        assert!(super::filter(Some("info,salsa=loud".to_string())).is_err());
+    }
+}
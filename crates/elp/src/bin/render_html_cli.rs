@@ -0,0 +1,114 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::fs;
+
+use anyhow::Result;
+use elp::build::load;
+use elp::cli::Cli;
+use elp_eqwalizer::Mode;
+use elp_ide::elp_ide_db::elp_base_db::FileId;
+use elp_ide::elp_ide_db::elp_base_db::FileKind;
+use elp_ide::elp_ide_db::elp_base_db::IncludeOtp;
+use elp_ide::elp_ide_db::elp_base_db::SourceDatabase;
+use elp_project_model::buck::BuckQueryConfig;
+use elp_project_model::AppType;
+use elp_project_model::DiscoverConfig;
+use fxhash::FxHashMap;
+use itertools::Itertools;
+
+use crate::args::RenderHtml;
+
+pub fn render_html(
+    args: &RenderHtml,
+    cli: &mut dyn Cli,
+    query_config: &BuckQueryConfig,
+) -> Result<()> {
+    let config = DiscoverConfig::new(args.rebar, &args.profile);
+    let loaded = load::load_project_at(
+        cli,
+        &args.project,
+        config,
+        IncludeOtp::No,
+        Mode::Cli,
+        query_config,
+    )?;
+    let analysis = loaded.analysis();
+    let project_id = loaded.project_id;
+
+    // The page of each source file of the project apps, mirroring the
+    // layout of the project
+    let pages: FxHashMap<FileId, String> = analysis.with_db(|db| {
+        let project_data = db.project_data(project_id);
+        let mut pages = FxHashMap::default();
+        for &source_root_id in &project_data.source_roots {
+            let is_app = db
+                .app_data(source_root_id)
+                .is_some_and(|app_data| app_data.app_type == AppType::App);
+            if !is_app {
+                continue;
+            }
+            let source_root = db.source_root(source_root_id);
+            for file_id in source_root.iter() {
+                let kind = db.file_kind(file_id);
+                if !(kind.is_module() || kind == FileKind::Header) {
+                    continue;
+                }
+                let path = source_root
+                    .path_for_file(&file_id)
+                    .and_then(|path| path.as_path())
+                    .and_then(|path| path.strip_prefix(&project_data.root_dir));
+                if let Some(path) = path {
+                    pages.insert(file_id, format!("{}.html", path.as_str()));
+                }
+            }
+        }
+        pages
+    })?;
+
+    for (&file_id, page) in pages.iter().sorted_by(|(_, a), (_, b)| a.cmp(b)) {
+        let depth = page.matches('/').count();
+        let root = if depth == 0 {
+            "./".to_string()
+        } else {
+            "../".repeat(depth)
+        };
+        let html = analysis.render_html(file_id, &pages, &root)?;
+        let path = args.out.join(page);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, html)?;
+    }
+
+    let index = index_page(pages.values().sorted());
+    fs::create_dir_all(&args.out)?;
+    let index_path = args.out.join("index.html");
+    fs::write(&index_path, index)?;
+    writeln!(
+        cli,
+        "Rendered {} files to {}",
+        pages.len(),
+        index_path.display()
+    )?;
+    Ok(())
+}
+
+fn index_page<'a>(pages: impl Iterator<Item = &'a String>) -> String {
+    let mut index = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Sources</title>\n</head>\n<body>\n<ul>\n",
+    );
+    for page in pages {
+        let name = page.strip_suffix(".html").unwrap_or(page);
+        index.push_str(&format!("<li><a href=\"{page}\">{name}</a></li>\n"));
+    }
+    index.push_str("</ul>\n</body>\n</html>\n");
+    index
+}
@@ -25,6 +25,7 @@ use codespan_reporting::term::termcolor::ColorSpec;
 use elp::arc_types;
 use elp::build::types::LoadResult;
 use elp::cli::Cli;
+use elp::cli::Event;
 use elp::convert;
 use elp_eqwalizer::IncludeGenerated;
 use elp_ide::elp_ide_db::elp_base_db::AbsPath;
@@ -33,9 +34,12 @@ use elp_ide::elp_ide_db::elp_base_db::VfsPath;
 use elp_ide::elp_ide_db::EqwalizerDiagnostic;
 use elp_ide::Analysis;
 use elp_ide::TextRange;
+use elp_project_model::EqwalizerLevel;
 use indicatif::ProgressBar;
 use lazy_static::lazy_static;
 
+use crate::junit;
+
 pub trait Reporter {
     fn write_eqwalizer_diagnostics(
         &mut self,
@@ -49,6 +53,7 @@ pub trait Reporter {
     fn write_stats(&mut self, count: u64, total: u64) -> Result<()>;
 
     fn progress(&self, len: u64, prefix: &'static str) -> ProgressBar;
+    fn event(&mut self, event: Event);
 }
 
 #[derive(Debug, Clone)]
@@ -110,6 +115,7 @@ impl<'a> Reporter for PrettyReporter<'a> {
         diagnostics: &[EqwalizerDiagnostic],
     ) -> Result<()> {
         let (reporting_files, reporting_id) = self.get_reporting_data(file_id)?;
+        let warnings = self.analysis.eqwalizer_level(file_id)? == EqwalizerLevel::Warnings;
         for diagnostic in diagnostics {
             let range: Range<usize> =
                 diagnostic.range.start().into()..diagnostic.range.end().into();
@@ -126,13 +132,18 @@ impl<'a> Reporter for PrettyReporter<'a> {
                     Label::secondary(reporting_id, range).with_message(format!("\n\n{}", s));
                 labels.push(explanation_label);
             };
-            let d: ReportingDiagnostic<usize> = ReportingDiagnostic::error()
-                .with_message(&diagnostic.code)
-                .with_labels(labels);
+            let d: ReportingDiagnostic<usize> = if warnings {
+                ReportingDiagnostic::warning()
+            } else {
+                ReportingDiagnostic::error()
+            };
+            let d = d.with_message(&diagnostic.code).with_labels(labels);
 
             term::emit(&mut self.cli, &REPORTING_CONFIG, &reporting_files, &d).unwrap();
         }
-        self.error_count += diagnostics.len();
+        if !warnings {
+            self.error_count += diagnostics.len();
+        }
         Ok(())
     }
 
@@ -202,6 +213,10 @@ impl<'a> Reporter for PrettyReporter<'a> {
     fn progress(&self, len: u64, prefix: &'static str) -> ProgressBar {
         self.cli.progress(len, prefix)
     }
+
+    fn event(&mut self, event: Event) {
+        self.cli.event(event)
+    }
 }
 
 impl<'a> JsonReporter<'a> {
@@ -225,6 +240,7 @@ impl<'a> Reporter for JsonReporter<'a> {
             .analysis
             .is_eqwalizer_enabled(file_id, IncludeGenerated::Yes)
             .unwrap();
+        let level = self.analysis.eqwalizer_level(file_id)?;
         let file_path = &self.loaded.vfs.file_path(file_id);
         let root_path = &self
             .analysis
@@ -238,6 +254,7 @@ impl<'a> Reporter for JsonReporter<'a> {
                 &line_index,
                 relative_path,
                 eqwalizer_enabled,
+                level,
             );
             let diagnostic = serde_json::to_string(&diagnostic)?;
             writeln!(self.cli, "{}", diagnostic)?;
@@ -296,6 +313,124 @@ impl<'a> Reporter for JsonReporter<'a> {
     fn progress(&self, len: u64, prefix: &'static str) -> ProgressBar {
         self.cli.progress(len, prefix)
     }
+
+    fn event(&mut self, event: Event) {
+        self.cli.event(event)
+    }
+}
+
+/// Collects a test case for each module reported, written as a JUnit
+/// XML report once all modules are checked.
+pub struct JunitReporter<'a> {
+    analysis: &'a Analysis,
+    loaded: &'a LoadResult,
+    cli: &'a mut dyn Cli,
+    test_cases: Vec<junit::TestCase>,
+}
+
+impl<'a> JunitReporter<'a> {
+    pub fn new(analysis: &'a Analysis, loaded: &'a LoadResult, cli: &'a mut dyn Cli) -> Self {
+        Self {
+            analysis,
+            loaded,
+            cli,
+            test_cases: Vec::new(),
+        }
+    }
+
+    fn test_case(&self, file_id: FileId) -> Result<junit::TestCase> {
+        let file_path = &self.loaded.vfs.file_path(file_id);
+        let root_path = &self
+            .analysis
+            .project_data(file_id)?
+            .with_context(|| "could not find project data")?
+            .root_dir;
+        let relative_path = get_relative_path(root_path, file_path);
+        let name = match self.analysis.module_name(file_id)? {
+            Some(name) => name.as_str().to_string(),
+            None => relative_path.display().to_string(),
+        };
+        let classname = match self.analysis.file_app_name(file_id)? {
+            Some(app_name) => app_name.to_string(),
+            None => name.clone(),
+        };
+        Ok(junit::TestCase {
+            name,
+            classname,
+            file: relative_path.display().to_string(),
+            failures: Vec::new(),
+        })
+    }
+}
+
+impl<'a> Reporter for JunitReporter<'a> {
+    fn write_eqwalizer_diagnostics(
+        &mut self,
+        file_id: FileId,
+        diagnostics: &[EqwalizerDiagnostic],
+    ) -> Result<()> {
+        let line_index = self.analysis.line_index(file_id)?;
+        let mut test_case = self.test_case(file_id)?;
+        // Type errors only fail the modules of the applications where
+        // they are enforced
+        let failures: &[EqwalizerDiagnostic] =
+            if self.analysis.eqwalizer_level(file_id)? == EqwalizerLevel::Warnings {
+                &[]
+            } else {
+                diagnostics
+            };
+        for diagnostic in failures {
+            let pos = convert::position(&line_index, diagnostic.range.start());
+            let message = match &diagnostic.expression {
+                Some(expr) => format!("{}.\n{}", expr, diagnostic.message),
+                None => diagnostic.message.clone(),
+            };
+            test_case.failures.push(junit::Failure {
+                code: diagnostic.code.clone(),
+                message,
+                line: pos.line + 1,
+                column: pos.character + 1,
+            });
+        }
+        self.test_cases.push(test_case);
+        Ok(())
+    }
+
+    /// Parse errors stop the type checking, so the report is written
+    /// right away.
+    fn write_parse_diagnostics(&mut self, diagnostics: &[ParseDiagnostic]) -> Result<()> {
+        for diagnostic in diagnostics {
+            let mut test_case = self.test_case(diagnostic.file_id)?;
+            test_case.failures.push(junit::Failure {
+                code: "parse_error".to_string(),
+                message: diagnostic.msg.clone(),
+                line: diagnostic.line_num,
+                column: 1,
+            });
+            self.test_cases.push(test_case);
+        }
+        junit::write_report(self.cli, "elp eqwalize", &self.test_cases)
+    }
+
+    fn write_file_advice(&mut self, _file_id: FileId, _description: String) -> Result<()> {
+        Ok(())
+    }
+
+    fn write_error_count(&mut self) -> Result<()> {
+        junit::write_report(self.cli, "elp eqwalize", &self.test_cases)
+    }
+
+    fn write_stats(&mut self, _count: u64, _total: u64) -> Result<()> {
+        Ok(())
+    }
+
+    fn progress(&self, len: u64, prefix: &'static str) -> ProgressBar {
+        self.cli.progress(len, prefix)
+    }
+
+    fn event(&mut self, event: Event) {
+        self.cli.event(event)
+    }
 }
 
 pub fn format_raw_parse_error(errs: &[ParseDiagnostic]) -> String {
@@ -37,6 +37,7 @@ use serde::Deserialize;
 use crate::args::Eqwalize;
 use crate::args::EqwalizeAll;
 use crate::args::EqwalizeApp;
+use crate::args::EqwalizeFunctionErrors;
 use crate::args::Shell;
 use crate::eqwalizer_cli;
 
@@ -119,6 +120,7 @@ enum ShellCommand {
     ShellEqwalize(Eqwalize),
     ShellEqwalizeAll(EqwalizeAll),
     ShellEqwalizeApp(EqwalizeApp),
+    ShellEqwalizeFunctionErrors(EqwalizeFunctionErrors),
     Help,
     Quit,
 }
@@ -172,6 +174,7 @@ impl ShellCommand {
                         return Ok(Some(ShellCommand::ShellEqwalizeApp(EqwalizeApp {
                             project,
                             profile,
+                            format: None,
                             rebar,
                             app: app.into(),
                             include_generated,
@@ -180,6 +183,34 @@ impl ShellCommand {
                     }
                     return Err(ShellError::MissingArg("eqwalize-app".into()));
                 }
+                "eqwalize-fun-errors" => {
+                    let clause_coverage = options.contains(&"--clause-coverage");
+                    if let Some(other) = options.into_iter().find(|&opt| opt != "--clause-coverage")
+                    {
+                        return Err(ShellError::UnexpectedOption(
+                            "eqwalize-fun-errors".into(),
+                            other.into(),
+                        ));
+                    }
+                    if let [_, arg, ..] = args[..] {
+                        return Err(ShellError::UnexpectedArg(
+                            "eqwalize-fun-errors".into(),
+                            arg.into(),
+                        ));
+                    }
+                    if let [function] = args[..] {
+                        return Ok(Some(ShellCommand::ShellEqwalizeFunctionErrors(
+                            EqwalizeFunctionErrors {
+                                project,
+                                profile,
+                                rebar,
+                                function: function.into(),
+                                clause_coverage,
+                            },
+                        )));
+                    }
+                    return Err(ShellError::MissingArg("eqwalize-fun-errors".into()));
+                }
                 "eqwalize-all" => {
                     let clause_coverage = options.contains(&"--clause-coverage");
                     let include_generated = options.contains(&"--include-generated");
@@ -214,17 +245,19 @@ impl ShellCommand {
 
 pub const HELP: &str = "\
 COMMANDS:
-    help                       Print this help
-    exit                       Exit the interactive session
-    quit                       Exit the interactive session
-    eqwalize <modules>         Eqwalize specified modules
-        --clause-coverage      Use experimental clause coverage checker
-    eqwalize-all               Eqwalize all modules in the current project
-        --include-generated    Include generated modules
-        --clause-coverage      Use experimental clause coverage checker
-    eqwalize-app <app>         Eqwalize all modules in specified application
-        --include-generated    Include generated modules
-        --clause-coverage      Use experimental clause coverage checker
+    help                         Print this help
+    exit                         Exit the interactive session
+    quit                         Exit the interactive session
+    eqwalize <modules>           Eqwalize specified modules
+        --clause-coverage        Use experimental clause coverage checker
+    eqwalize-all                 Eqwalize all modules in the current project
+        --include-generated      Include generated modules
+        --clause-coverage        Use experimental clause coverage checker
+    eqwalize-app <app>           Eqwalize all modules in specified application
+        --include-generated      Include generated modules
+        --clause-coverage        Use experimental clause coverage checker
+    eqwalize-fun-errors <m:f/a>  Eqwalize the module of a function, reporting only its errors
+        --clause-coverage        Use experimental clause coverage checker
 ";
 
 pub const WELCOME: &str = "\
@@ -374,6 +407,16 @@ pub fn run_shell(shell: &Shell, cli: &mut dyn Cli, query_config: &BuckQueryConfi
                         eqwalizer_cli::do_eqwalize_app(&eqwalize_app, &mut loaded, cli)
                             .or_else(|e| writeln!(cli, "Error: {}", e))?;
                     }
+                    Ok(Some(ShellCommand::ShellEqwalizeFunctionErrors(
+                        eqwalize_function_errors,
+                    ))) => {
+                        eqwalizer_cli::do_eqwalize_function_errors(
+                            &eqwalize_function_errors,
+                            &mut loaded,
+                            cli,
+                        )
+                        .or_else(|e| writeln!(cli, "Error: {}", e))?;
+                    }
                     Ok(Some(ShellCommand::ShellEqwalizeAll(eqwalize_all))) => {
                         eqwalizer_cli::do_eqwalize_all(&eqwalize_all, &mut loaded, cli)
                             .or_else(|e| writeln!(cli, "Error: {}", e))?;
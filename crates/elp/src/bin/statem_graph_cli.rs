@@ -0,0 +1,57 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::fs;
+
+use anyhow::bail;
+use anyhow::Context;
+use anyhow::Result;
+use elp::build::load;
+use elp::cli::Cli;
+use elp_eqwalizer::Mode;
+use elp_ide::elp_ide_db::elp_base_db::IncludeOtp;
+use elp_project_model::buck::BuckQueryConfig;
+use elp_project_model::DiscoverConfig;
+
+use crate::args::StatemGraph;
+
+pub fn statem_graph(
+    args: &StatemGraph,
+    cli: &mut dyn Cli,
+    query_config: &BuckQueryConfig,
+) -> Result<()> {
+    let config = DiscoverConfig::new(args.rebar, &args.profile);
+    let loaded = load::load_project_at(
+        cli,
+        &args.project,
+        config,
+        IncludeOtp::No,
+        Mode::Cli,
+        query_config,
+    )?;
+    let analysis = loaded.analysis();
+    let module_index = analysis.module_index(loaded.project_id)?;
+    let file_id = module_index
+        .file_for_module(args.module.as_str())
+        .with_context(|| format!("module {} not found", args.module))?;
+    let graph = match analysis.statem_graph(file_id)? {
+        Some(graph) => graph,
+        None => bail!("module {} does not implement gen_statem", args.module),
+    };
+
+    let out = match args.format.as_deref() {
+        Some("json") => serde_json::to_string_pretty(&graph)? + "\n",
+        _ => graph.to_dot(&args.module),
+    };
+    match &args.to {
+        Some(to) => fs::write(to, out)?,
+        None => write!(cli, "{out}")?,
+    }
+    Ok(())
+}
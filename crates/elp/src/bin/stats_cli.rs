@@ -0,0 +1,127 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use elp::build::load;
+use elp::cli::Cli;
+use elp::read_lint_config_file;
+use elp_eqwalizer::Mode;
+use elp_ide::diagnostics::DiagnosticsConfig;
+use elp_ide::diagnostics::FallBackToAll;
+use elp_ide::elp_ide_db::elp_base_db::IncludeOtp;
+use elp_ide::FileStats;
+use elp_project_model::buck::BuckQueryConfig;
+use elp_project_model::DiscoverConfig;
+use indicatif::ParallelProgressIterator;
+use rayon::iter::ParallelBridge;
+use rayon::iter::ParallelIterator;
+use serde::Serialize;
+
+use crate::args::Stats;
+
+#[derive(Debug, Default, Serialize)]
+struct ProjectStats {
+    modules: usize,
+    lines: usize,
+    functions: usize,
+    specs: usize,
+    records: usize,
+    macros: usize,
+    test_suites: usize,
+    /// Number of modules implementing each behaviour
+    behaviours: BTreeMap<String, usize>,
+    /// Number of diagnostics reported for each code
+    diagnostics: BTreeMap<String, usize>,
+}
+
+impl ProjectStats {
+    fn add(&mut self, file: FileStats, codes: Vec<String>) {
+        self.modules += 1;
+        self.lines += file.lines;
+        self.functions += file.functions;
+        self.specs += file.specs;
+        self.records += file.records;
+        self.macros += file.macros;
+        if file.test_suite {
+            self.test_suites += 1;
+        }
+        for behaviour in file.behaviours {
+            *self.behaviours.entry(behaviour).or_default() += 1;
+        }
+        for code in codes {
+            *self.diagnostics.entry(code).or_default() += 1;
+        }
+    }
+}
+
+pub fn stats(args: &Stats, cli: &mut dyn Cli, query_config: &BuckQueryConfig) -> Result<()> {
+    let lint_config = read_lint_config_file(&args.project, &None)?;
+    let diagnostics_config = DiagnosticsConfig::default().configure_diagnostics(
+        &lint_config,
+        &None,
+        &None,
+        FallBackToAll::No,
+    )?;
+    let config = DiscoverConfig::new(args.rebar, &args.profile);
+    let loaded = load::load_project_at(
+        cli,
+        &args.project,
+        config,
+        IncludeOtp::No,
+        Mode::Cli,
+        query_config,
+    )?;
+    let analysis = loaded.analysis();
+    let module_index = analysis.module_index(loaded.project_id)?;
+    let pb = cli.progress(module_index.len_own() as u64, "Collecting statistics");
+    let per_module: Vec<(FileStats, Vec<String>)> = module_index
+        .iter_own()
+        .par_bridge()
+        .progress_with(pb.clone())
+        .map_with(analysis.clone(), |analysis, (_name, _source, file_id)| {
+            let file_stats = analysis.file_stats(file_id).expect("cancelled");
+            let codes = analysis
+                .native_diagnostics(&diagnostics_config, &vec![], file_id)
+                .expect("cancelled")
+                .iter()
+                .map(|diagnostic| diagnostic.code.as_code())
+                .collect();
+            (file_stats, codes)
+        })
+        .collect();
+    pb.finish();
+
+    let mut stats = ProjectStats::default();
+    for (file_stats, codes) in per_module {
+        stats.add(file_stats, codes);
+    }
+
+    if args.format.is_some() {
+        writeln!(cli, "{}", serde_json::to_string_pretty(&stats)?)?;
+        return Ok(());
+    }
+    writeln!(cli, "Modules:     {:>8}", stats.modules)?;
+    writeln!(cli, "Lines:       {:>8}", stats.lines)?;
+    writeln!(cli, "Functions:   {:>8}", stats.functions)?;
+    writeln!(cli, "Specs:       {:>8}", stats.specs)?;
+    writeln!(cli, "Records:     {:>8}", stats.records)?;
+    writeln!(cli, "Macros:      {:>8}", stats.macros)?;
+    writeln!(cli, "Test suites: {:>8}", stats.test_suites)?;
+    writeln!(cli, "Behaviours:")?;
+    for (behaviour, count) in &stats.behaviours {
+        writeln!(cli, "{count:>8} {behaviour}")?;
+    }
+    writeln!(cli, "Diagnostics:")?;
+    for (code, count) in &stats.diagnostics {
+        writeln!(cli, "{count:>8} {code}")?;
+    }
+    Ok(())
+}
@@ -17,6 +17,7 @@ use anyhow::bail;
 use anyhow::Result;
 use crossbeam_channel::unbounded;
 use crossbeam_channel::Receiver;
+use elp_ide::diagnostics;
 use elp_ide::elp_ide_db::elp_base_db::loader;
 use elp_ide::elp_ide_db::elp_base_db::loader::Handle;
 use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
@@ -30,11 +31,13 @@ use elp_ide::elp_ide_db::elp_base_db::SourceDatabaseExt;
 use elp_ide::elp_ide_db::elp_base_db::SourceRoot;
 use elp_ide::elp_ide_db::elp_base_db::SourceRootId;
 use elp_ide::elp_ide_db::elp_base_db::Vfs;
+use elp_ide::elp_ide_db::LruCapacities;
 use elp_ide::AnalysisHost;
 use elp_project_model::buck::BuckQueryConfig;
 use elp_project_model::DiscoverConfig;
 use elp_project_model::ElpConfig;
 use elp_project_model::IncludeParentDirs;
+use elp_project_model::LruConfig;
 use elp_project_model::Project;
 use elp_project_model::ProjectManifest;
 use fxhash::FxHashMap;
@@ -53,6 +56,18 @@ pub fn load_project_at(
     eqwalizer_mode: elp_eqwalizer::Mode,
     query_config: &BuckQueryConfig,
 ) -> Result<LoadResult> {
+    let project = discover_project_at(cli, root, conf, query_config)?;
+    load_project(cli, project, include_otp, eqwalizer_mode)
+}
+
+/// Finds the project at `root` and its applications, without loading
+/// their files.
+pub fn discover_project_at(
+    cli: &dyn Cli,
+    root: &Path,
+    conf: DiscoverConfig,
+    query_config: &BuckQueryConfig,
+) -> Result<Project> {
     let root = fs::canonicalize(root)?;
     let root = AbsPathBuf::assert_utf8(root);
     let (elp_config, manifest): (ElpConfig, Option<ProjectManifest>) = match conf.rebar {
@@ -77,10 +92,9 @@ pub fn load_project_at(
 
     log::info!("Discovered project: {:?}", manifest);
     let pb = cli.spinner("Loading build info");
-    let project = Project::load(&manifest, elp_config.eqwalizer.clone(), query_config)?;
+    let project = Project::load(&manifest, &elp_config, query_config)?;
     pb.finish();
-
-    load_project(cli, project, include_otp, eqwalizer_mode)
+    Ok(project)
 }
 
 fn load_project(
@@ -118,6 +132,7 @@ fn load_project(
         &mut line_ending_map,
         &receiver,
         eqwalizer_mode,
+        project.lru,
     )?;
     Ok(LoadResult::new(
         analysis_host,
@@ -129,6 +144,7 @@ fn load_project(
     ))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn load_database(
     cli: &dyn Cli,
     project_apps: &ProjectApps,
@@ -137,6 +153,7 @@ fn load_database(
     line_ending_map: &mut FxHashMap<FileId, LineEndings>,
     receiver: &Receiver<loader::Message>,
     eqwalizer_mode: elp_eqwalizer::Mode,
+    lru_config: LruConfig,
 ) -> Result<AnalysisHost> {
     let mut analysis_host = AnalysisHost::default();
 
@@ -172,6 +189,8 @@ fn load_database(
     let pb = cli.spinner("Seeding database");
 
     let sets = file_set_config.partition(vfs);
+    let files = sets.iter().map(|set| set.len()).sum();
+    db.update_lru_capacities(LruCapacities::new(lru_config, files));
     for (idx, set) in sets.into_iter().enumerate() {
         let root_id = SourceRootId(idx as u32);
         for file_id in set.iter() {
@@ -194,6 +213,8 @@ fn load_database(
             }
         }
     }
+    let stale = diagnostics::stale_generated_files(&*db, vfs.iter().map(|(file_id, _)| file_id));
+    db.set_stale_generated_files(Arc::new(stale));
 
     pb.finish();
 
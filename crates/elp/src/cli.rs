@@ -7,10 +7,12 @@
  * of this source tree.
  */
 
+use std::io::IsTerminal;
 use std::io::Stderr;
 use std::io::Write;
 use std::time::Duration;
 
+use codespan_reporting::term::termcolor::Ansi;
 use codespan_reporting::term::termcolor::Buffer;
 use codespan_reporting::term::termcolor::ColorChoice;
 use codespan_reporting::term::termcolor::ColorSpec;
@@ -18,6 +20,7 @@ use codespan_reporting::term::termcolor::StandardStream;
 use codespan_reporting::term::termcolor::WriteColor;
 use indicatif::ProgressBar;
 use indicatif::ProgressStyle;
+use serde_json::json;
 
 pub trait Cli: Write + WriteColor {
     fn simple_progress(&self, len: u64, prefix: &'static str) -> ProgressBar;
@@ -26,28 +29,88 @@ pub trait Cli: Write + WriteColor {
 
     fn spinner(&self, prefix: &'static str) -> ProgressBar;
 
+    /// Reports the progress of the command, on stderr
+    fn event(&mut self, event: Event);
+
     fn err(&mut self) -> &mut dyn Write;
 }
 
-pub struct Real(StandardStream, Stderr);
+/// What a command reports about its progress, as opposed to its actual
+/// output. Progress bars and spinners are reported as `Progress` events
+/// when they cannot be drawn.
+#[derive(Debug, Clone, Copy)]
+pub enum Event<'a> {
+    /// The command starts a new phase
+    Section(&'a str),
+    /// The command starts working through `len` items, or an unknown
+    /// number of them
+    Progress { prefix: &'a str, len: Option<u64> },
+    /// The command found `count` things, such as diagnostics
+    Findings { kind: &'a str, count: usize },
+}
+
+/// How [`Real`] renders events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventStyle {
+    /// Progress bars, spinners and colors
+    Tty,
+    /// One line of text per event and no escape sequences, so that the
+    /// output stays readable in a log or when read by an Erlang port
+    Plain,
+    /// One JSON object per event and line
+    Json,
+}
+
+impl EventStyle {
+    /// The style set by the `ELP_PROGRESS` environment variable, one of
+    /// `tty`, `plain` or `json`, otherwise the one suited to stderr.
+    pub fn from_env() -> Self {
+        match std::env::var("ELP_PROGRESS").as_deref() {
+            Ok("tty") => EventStyle::Tty,
+            Ok("plain") => EventStyle::Plain,
+            Ok("json") => EventStyle::Json,
+            _ if std::io::stderr().is_terminal() => EventStyle::Tty,
+            _ => EventStyle::Plain,
+        }
+    }
+}
+
+pub struct Real(StandardStream, Stderr, EventStyle);
 
 impl Default for Real {
     fn default() -> Self {
-        Self(
-            StandardStream::stdout(ColorChoice::Always),
-            std::io::stderr(),
-        )
+        Self::new(EventStyle::from_env())
     }
 }
 
 impl Real {
+    pub fn new(style: EventStyle) -> Self {
+        let color = match style {
+            EventStyle::Tty => ColorChoice::Always,
+            EventStyle::Plain | EventStyle::Json => ColorChoice::Never,
+        };
+        Self(StandardStream::stdout(color), std::io::stderr(), style)
+    }
+
+    /// Events only need a shared reference, as the progress bars are
+    /// created from one.
+    fn write_event(&self, event: Event) -> std::io::Result<()> {
+        render_event(self.2, event, &mut &self.1)
+    }
+
     fn progress_with_style(
         &self,
         len: u64,
         prefix: &'static str,
         style: &'static str,
     ) -> ProgressBar {
-        if len == 1 {
+        if self.2 != EventStyle::Tty {
+            let _ = self.write_event(Event::Progress {
+                prefix,
+                len: Some(len),
+            });
+            ProgressBar::hidden()
+        } else if len == 1 {
             self.spinner(prefix)
         } else {
             let pb = ProgressBar::new(len);
@@ -68,6 +131,10 @@ impl Cli for Real {
     }
 
     fn spinner(&self, prefix: &'static str) -> ProgressBar {
+        if self.2 != EventStyle::Tty {
+            let _ = self.write_event(Event::Progress { prefix, len: None });
+            return ProgressBar::hidden();
+        }
         let pb = ProgressBar::new_spinner();
         pb.set_style(
             ProgressStyle::with_template("{spinner} {prefix} {wide_msg}")
@@ -78,6 +145,10 @@ impl Cli for Real {
         pb
     }
 
+    fn event(&mut self, event: Event) {
+        let _ = self.write_event(event);
+    }
+
     fn err(&mut self) -> &mut dyn Write {
         &mut self.1
     }
@@ -107,6 +178,42 @@ impl WriteColor for Real {
     }
 }
 
+/// Writes `event` to `err`, which is stderr outside of tests.
+fn render_event(style: EventStyle, event: Event, err: &mut dyn Write) -> std::io::Result<()> {
+    match (style, event) {
+        (EventStyle::Tty, Event::Section(title)) => {
+            let mut err = Ansi::new(err);
+            err.set_color(ColorSpec::new().set_bold(true))?;
+            write!(err, "{title}")?;
+            err.reset()?;
+            writeln!(err)
+        }
+        // Drawn as progress bars, and the command reports what it
+        // found on stdout
+        (EventStyle::Tty, Event::Progress { .. } | Event::Findings { .. }) => Ok(()),
+        (EventStyle::Plain, Event::Section(title)) => writeln!(err, "{title}"),
+        (EventStyle::Plain, Event::Progress { prefix, len }) => match len {
+            Some(len) => writeln!(err, "{prefix} ({len})"),
+            None => writeln!(err, "{prefix}"),
+        },
+        (EventStyle::Plain, Event::Findings { kind, count }) => {
+            writeln!(err, "Found {count} {kind}")
+        }
+        (EventStyle::Json, event) => {
+            let value = match event {
+                Event::Section(title) => json!({"event": "section", "title": title}),
+                Event::Progress { prefix, len } => {
+                    json!({"event": "progress", "prefix": prefix, "len": len})
+                }
+                Event::Findings { kind, count } => {
+                    json!({"event": "findings", "kind": kind, "count": count})
+                }
+            };
+            writeln!(err, "{value}")
+        }
+    }
+}
+
 pub struct Fake(Buffer, Vec<u8>);
 
 impl Default for Fake {
@@ -136,6 +243,8 @@ impl Cli for Fake {
         ProgressBar::hidden()
     }
 
+    fn event(&mut self, _event: Event) {}
+
     fn err(&mut self) -> &mut dyn Write {
         &mut self.1
     }
@@ -164,3 +273,73 @@ impl WriteColor for Fake {
         self.0.reset()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use super::render_event;
+    use super::Event;
+    use super::EventStyle;
+
+    fn check(style: EventStyle, expect: Expect) {
+        let events = [
+            Event::Section("Loading project"),
+            Event::Progress {
+                prefix: "Parsing",
+                len: Some(3),
+            },
+            Event::Progress {
+                prefix: "Waiting",
+                len: None,
+            },
+            Event::Findings {
+                kind: "diagnostics",
+                count: 2,
+            },
+        ];
+        let mut err = Vec::new();
+        for event in events {
+            render_event(style, event, &mut err).unwrap();
+        }
+        let actual = String::from_utf8(err).unwrap().replace('\u{1b}', "\\e");
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn tty_events() {
+        check(
+            EventStyle::Tty,
+            expect![[r#"
+                \e[0m\e[1mLoading project\e[0m
+            "#]],
+        );
+    }
+
+    #[test]
+    fn plain_events() {
+        check(
+            EventStyle::Plain,
+            expect![[r#"
+                Loading project
+                Parsing (3)
+                Waiting
+                Found 2 diagnostics
+            "#]],
+        );
+    }
+
+    #[test]
+    fn json_events() {
+        check(
+            EventStyle::Json,
+            expect![[r#"
+                {"event":"section","title":"Loading project"}
+                {"event":"progress","len":3,"prefix":"Parsing"}
+                {"event":"progress","len":null,"prefix":"Waiting"}
+                {"count":2,"event":"findings","kind":"diagnostics"}
+            "#]],
+        );
+    }
+}
@@ -7,14 +7,17 @@
  * of this source tree.
  */
 
+use std::fmt;
 use std::iter;
 use std::sync::Arc;
 
+use anyhow::Result;
 use elp_ide::diagnostics::DiagnosticCode;
 use elp_ide::diagnostics::DiagnosticsConfig;
 use elp_ide::diagnostics::FallBackToAll;
 use elp_ide::diagnostics::LintConfig;
 use elp_ide::elp_ide_assists::AssistConfig;
+use elp_ide::elp_ide_db::elp_base_db::is_generated_text;
 use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
 use elp_ide::elp_ide_db::helpers::SnippetCap;
 use elp_ide::HoverActionsConfig;
@@ -23,8 +26,15 @@ use elp_project_model::buck::BuckQueryConfig;
 use fxhash::FxHashSet;
 use lsp_types::ClientCapabilities;
 use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::json;
 
+use self::layers::ConfigIssue;
+use self::layers::ConfigLayer;
+use self::layers::ConfigLayers;
+
+pub mod layers;
+
 // Defines the server-side configuration of ELP. We generate *parts*
 // of VS Code's `package.json` config from this.
 //
@@ -35,6 +45,19 @@ use serde_json::json;
 // `new_name | `old_name` so that we keep parsing the old name.
 config_data! {
   struct ConfigData {
+      /// Whether files marked as generated only get syntax-level analysis
+      /// (no eqwalizer, semantic diagnostics or semantic tokens).
+      analysis_generatedSyntaxOnly_enable: bool = json! { false },
+      /// Files larger than this many bytes only get syntax-level analysis
+      /// (no eqwalizer, semantic diagnostics or semantic tokens).
+      analysis_maxFileSize: usize = json! { 2000000 },
+      /// Whether to offer the assists aligning and un-aligning the arrows
+      /// of clauses.
+      assist_alignArrows_enable: bool = json! { false },
+      /// Oldest OTP release the project supports, restricting the assists
+      /// to the syntax available in it. Defaults to the release of the
+      /// project's toolchain.
+      assist_minOtpRelease: Option<usize> = json! { null },
       /// Use BXL to query for buck project model.
       buck_query_useBxl_enable: bool = json! { false },
       /// Whether to show experimental ELP diagnostics that might
@@ -60,6 +83,9 @@ config_data! {
       /// Whether to show function parameter name inlay hints at the call
       /// site.
       inlayHints_parameterHints_enable: bool = json! { true },
+      /// Whether to show the types found by eqWAlizer for bound variables
+      /// and returned values.
+      inlayHints_typeHints_enable: bool = json! { false },
       /// Whether to show Code Lenses in Erlang files.
       lens_enable: bool = json! { false },
       /// Whether to show the `Run` lenses. Only applies when
@@ -80,6 +106,9 @@ config_data! {
       lens_links_enable: bool = json! { false },
       /// Configure LSP-based logging using env_logger syntax.
       log: String = json! { "error" },
+      /// Whether renaming an atom which names nothing, such as a message
+      /// tag, renames all its uses in the project.
+      rename_atoms_enable: bool = json! { false },
       /// Whether to show Signature Help.
       signatureHelp_enable: bool = json! { true },
       /// Display types when hovering over expressions.
@@ -97,9 +126,17 @@ impl Default for ConfigData {
 pub struct Config {
     pub root_path: AbsPathBuf,
     pub caps: ClientCapabilities,
+    layers: ConfigLayers,
     data: ConfigData,
 }
 
+/// The value of a setting, once the layers of the configuration merged.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ResolvedSetting {
+    pub value: serde_json::Value,
+    pub layer: ConfigLayer,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct LensConfig {
     pub run: bool,
@@ -116,6 +153,43 @@ pub struct EqwalizerConfig {
     pub chunk_size: usize,
 }
 
+/// Limits beyond which a file only gets syntax-level analysis, so a
+/// single huge module cannot stall the server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnalysisBudgetConfig {
+    pub max_file_size: usize,
+    pub generated_syntax_only: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyntaxOnlyReason {
+    TooLarge,
+    Generated,
+}
+
+impl AnalysisBudgetConfig {
+    /// Returns why a file with the given contents is restricted to
+    /// syntax-level analysis, or `None` if it gets the full treatment.
+    pub fn syntax_only_reason(&self, text: &str) -> Option<SyntaxOnlyReason> {
+        if text.len() > self.max_file_size {
+            Some(SyntaxOnlyReason::TooLarge)
+        } else if self.generated_syntax_only && is_generated_text(text) {
+            Some(SyntaxOnlyReason::Generated)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for SyntaxOnlyReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyntaxOnlyReason::TooLarge => write!(f, "it exceeds `elp.analysis.maxFileSize`"),
+            SyntaxOnlyReason::Generated => write!(f, "it is generated"),
+        }
+    }
+}
+
 macro_rules! try_ {
     ($expr:expr) => {
         || -> _ { Some($expr) }()
@@ -132,16 +206,69 @@ impl Config {
         Config {
             root_path,
             caps,
+            layers: ConfigLayers::default(),
             data: ConfigData::default(),
         }
     }
 
+    /// Reads the org config and the settings of the `.elp.toml` at the
+    /// root of the workspace, which the settings sent by the client
+    /// override.
+    pub fn load_layers(&mut self) {
+        self.layers = ConfigLayers::load(&self.root_path, &Config::json_schema());
+        for issue in self.layers.issues() {
+            log::warn!("{}", issue);
+        }
+        self.data = ConfigData::from_json(self.layers.merged());
+    }
+
+    /// Sets the org config fetched from `url`, see
+    /// `ConfigLayers::org_config_url`.
+    pub fn set_org_config(&mut self, url: String, settings: Result<serde_json::Value>) {
+        self.layers
+            .set_org(url, None, settings, &Config::json_schema());
+        self.data = ConfigData::from_json(self.layers.merged());
+    }
+
+    /// Reads the `.elp.toml` at the root of the workspace again.
+    pub fn reload_project_layer(&mut self) {
+        self.layers
+            .reload_project(&self.root_path, &Config::json_schema());
+        self.data = ConfigData::from_json(self.layers.merged());
+    }
+
     pub fn update(&mut self, json: serde_json::Value) {
         log::info!("updating config from JSON: {:#}", json);
         if json.is_null() || json.as_object().map_or(false, |it| it.is_empty()) {
             return;
         }
-        self.data = ConfigData::from_json(json);
+        self.layers.set_workspace(json, &Config::json_schema());
+        self.data = ConfigData::from_json(self.layers.merged());
+    }
+
+    /// The errors found while reading and validating the layers of the
+    /// configuration.
+    pub fn issues(&self) -> &[ConfigIssue] {
+        self.layers.issues()
+    }
+
+    /// Each setting, by name, with its value and the layer it comes from.
+    pub fn resolved(&self) -> serde_json::Map<String, serde_json::Value> {
+        self.data
+            .to_json()
+            .into_iter()
+            .map(|(field, value)| {
+                let pointer = format!("/{}", field.replace('_', "/"));
+                let setting = ResolvedSetting {
+                    value,
+                    layer: self.layers.layer_of(&pointer),
+                };
+                (
+                    format!("elp.{}", field.replace('_', ".")),
+                    serde_json::to_value(setting).unwrap(),
+                )
+            })
+            .collect()
     }
 
     pub fn did_save_text_document_dynamic_registration(&self) -> bool {
@@ -161,15 +288,14 @@ impl Config {
     }
 
     pub fn code_action_literals(&self) -> bool {
-        try_!(
-            self.caps
-                .text_document
-                .as_ref()?
-                .code_action
-                .as_ref()?
-                .code_action_literal_support
-                .as_ref()?
-        )
+        try_!(self
+            .caps
+            .text_document
+            .as_ref()?
+            .code_action
+            .as_ref()?
+            .code_action_literal_support
+            .as_ref()?)
         .is_some()
     }
 
@@ -277,6 +403,13 @@ impl Config {
         }
     }
 
+    pub fn analysis_budget(&self) -> AnalysisBudgetConfig {
+        AnalysisBudgetConfig {
+            max_file_size: self.data.analysis_maxFileSize,
+            generated_syntax_only: self.data.analysis_generatedSyntaxOnly_enable,
+        }
+    }
+
     pub fn eqwalizer(&self) -> EqwalizerConfig {
         EqwalizerConfig {
             all: self.data.eqwalizer_all,
@@ -295,6 +428,10 @@ impl Config {
         self.data.signatureHelp_enable
     }
 
+    pub fn rename_atoms(&self) -> bool {
+        self.data.rename_atoms_enable
+    }
+
     pub fn types_on_hover(&self) -> bool {
         self.data.typesOnHover_enable
     }
@@ -307,6 +444,9 @@ impl Config {
         AssistConfig {
             snippet_cap: SnippetCap::new(self.experimental("snippetTextEdit")),
             allowed: None,
+            disabled: Vec::new(),
+            min_otp_release: self.data.assist_minOtpRelease.map(|release| release as u32),
+            align_arrows: self.data.assist_alignArrows_enable,
         }
     }
 
@@ -325,6 +465,7 @@ impl Config {
     pub fn inlay_hints(&self) -> InlayHintsConfig {
         InlayHintsConfig {
             parameter_hints: self.data.inlayHints_parameterHints_enable,
+            type_hints: self.data.inlayHints_typeHints_enable,
         }
     }
 
@@ -366,6 +507,12 @@ macro_rules! _config_data {
                 )*}
             }
 
+            fn to_json(&self) -> Vec<(&'static str, serde_json::Value)> {
+                vec![$(
+                    (stringify!($field), serde_json::to_value(&self.$field).unwrap()),
+                )*]
+            }
+
             fn json_schema() -> serde_json::Value {
                 schema(&[
                     $({
@@ -526,10 +673,35 @@ mod tests {
 
         let s = remove_ws(&schema);
 
-        expect![[r#""elp.buck.query.useBxl.enable":{"default":false,"markdownDescription":"UseBXLtoqueryforbuckprojectmodel.","type":"boolean"},"elp.diagnostics.disabled":{"default":[],"items":{"type":"string"},"markdownDescription":"ListofELPdiagnosticstodisable.","type":"array","uniqueItems":true},"elp.diagnostics.enableExperimental":{"default":false,"markdownDescription":"WhethertoshowexperimentalELPdiagnosticsthatmight\nhavemorefalsepositivesthanusual.","type":"boolean"},"elp.diagnostics.enableOtp":{"default":false,"markdownDescription":"WhethertoreportdiagnosticsforOTPfiles.","type":"boolean"},"elp.eqwalizer.all":{"default":false,"markdownDescription":"WhethertoreportEqwalizerdiagnosticsforthewholeprojectandnotonlyforopenedfiles.","type":"boolean"},"elp.eqwalizer.chunkSize":{"default":100,"markdownDescription":"Chunksizetouseforproject-wideeqwalization.","minimum":0,"type":"integer"},"elp.eqwalizer.maxTasks":{"default":32,"markdownDescription":"Maximumnumberoftaskstoruninparallelforproject-wideeqwalization.","minimum":0,"type":"integer"},"elp.highlightDynamic.enable":{"default":false,"markdownDescription":"Ifenabled,highlightvariableswithtype`dynamic()`whenEqwalizerresultsareavailable.","type":"boolean"},"elp.hoverActions.docLinks.enable":{"default":false,"markdownDescription":"WhethertoshowHoverActionsoftype'docs'.Onlyapplieswhen\n`#elp.hoverActions.enable#`isset.","type":"boolean"},"elp.hoverActions.enable":{"default":false,"markdownDescription":"WhethertoshowHoverActions.","type":"boolean"},"elp.inlayHints.parameterHints.enable":{"default":true,"markdownDescription":"Whethertoshowfunctionparameternameinlayhintsatthecall\nsite.","type":"boolean"},"elp.lens.debug.enable":{"default":false,"markdownDescription":"Whethertoshowthe`Debug`lenses.Onlyapplieswhen\n`#elp.lens.enable#`isset.","type":"boolean"},"elp.lens.enable":{"default":false,"markdownDescription":"WhethertoshowCodeLensesinErlangfiles.","type":"boolean"},"elp.lens.links.enable":{"default":false,"markdownDescription":"Whethertoshowthe`Link`lenses.Onlyapplieswhen\n`#elp.lens.enable#`isset.","type":"boolean"},"elp.lens.run.coverage.enable":{"default":false,"markdownDescription":"Displaycodecoverageinformationwhenrunningtestsviathe\nCodeLenses.Onlyapplieswhen`#elp.lens.enabled`and\n`#elp.lens.run.enable#`areset.","type":"boolean"},"elp.lens.run.enable":{"default":false,"markdownDescription":"Whethertoshowthe`Run`lenses.Onlyapplieswhen\n`#elp.lens.enable#`isset.","type":"boolean"},"elp.lens.run.interactive.enable":{"default":false,"markdownDescription":"Whethertoshowthe`RunInteractive`lenses.Onlyapplieswhen\n`#elp.lens.enable#`isset.","type":"boolean"},"elp.log":{"default":"error","markdownDescription":"ConfigureLSP-basedloggingusingenv_loggersyntax.","type":"string"},"elp.signatureHelp.enable":{"default":true,"markdownDescription":"WhethertoshowSignatureHelp.","type":"boolean"},"elp.typesOnHover.enable":{"default":false,"markdownDescription":"Displaytypeswhenhoveringoverexpressions.","type":"boolean"},"#]]
+        expect![[r#""elp.analysis.generatedSyntaxOnly.enable":{"default":false,"markdownDescription":"Whetherfilesmarkedasgeneratedonlygetsyntax-levelanalysis\n(noeqwalizer,semanticdiagnosticsorsemantictokens).","type":"boolean"},"elp.analysis.maxFileSize":{"default":2000000,"markdownDescription":"Fileslargerthanthismanybytesonlygetsyntax-levelanalysis\n(noeqwalizer,semanticdiagnosticsorsemantictokens).","minimum":0,"type":"integer"},"elp.assist.alignArrows.enable":{"default":false,"markdownDescription":"Whethertooffertheassistsaligningandun-aligningthearrows\nofclauses.","type":"boolean"},"elp.assist.minOtpRelease":{"default":null,"markdownDescription":"OldestOTPreleasetheprojectsupports,restrictingtheassists\ntothesyntaxavailableinit.Defaultstothereleaseofthe\nproject'stoolchain.","minimum":0,"type":["null","integer"]},"elp.buck.query.useBxl.enable":{"default":false,"markdownDescription":"UseBXLtoqueryforbuckprojectmodel.","type":"boolean"},"elp.diagnostics.disabled":{"default":[],"items":{"type":"string"},"markdownDescription":"ListofELPdiagnosticstodisable.","type":"array","uniqueItems":true},"elp.diagnostics.enableExperimental":{"default":false,"markdownDescription":"WhethertoshowexperimentalELPdiagnosticsthatmight\nhavemorefalsepositivesthanusual.","type":"boolean"},"elp.diagnostics.enableOtp":{"default":false,"markdownDescription":"WhethertoreportdiagnosticsforOTPfiles.","type":"boolean"},"elp.eqwalizer.all":{"default":false,"markdownDescription":"WhethertoreportEqwalizerdiagnosticsforthewholeprojectandnotonlyforopenedfiles.","type":"boolean"},"elp.eqwalizer.chunkSize":{"default":100,"markdownDescription":"Chunksizetouseforproject-wideeqwalization.","minimum":0,"type":"integer"},"elp.eqwalizer.maxTasks":{"default":32,"markdownDescription":"Maximumnumberoftaskstoruninparallelforproject-wideeqwalization.","minimum":0,"type":"integer"},"elp.highlightDynamic.enable":{"default":false,"markdownDescription":"Ifenabled,highlightvariableswithtype`dynamic()`whenEqwalizerresultsareavailable.","type":"boolean"},"elp.hoverActions.docLinks.enable":{"default":false,"markdownDescription":"WhethertoshowHoverActionsoftype'docs'.Onlyapplieswhen\n`#elp.hoverActions.enable#`isset.","type":"boolean"},"elp.hoverActions.enable":{"default":false,"markdownDescription":"WhethertoshowHoverActions.","type":"boolean"},"elp.inlayHints.parameterHints.enable":{"default":true,"markdownDescription":"Whethertoshowfunctionparameternameinlayhintsatthecall\nsite.","type":"boolean"},"elp.inlayHints.typeHints.enable":{"default":false,"markdownDescription":"WhethertoshowthetypesfoundbyeqWAlizerforboundvariables\nandreturnedvalues.","type":"boolean"},"elp.lens.debug.enable":{"default":false,"markdownDescription":"Whethertoshowthe`Debug`lenses.Onlyapplieswhen\n`#elp.lens.enable#`isset.","type":"boolean"},"elp.lens.enable":{"default":false,"markdownDescription":"WhethertoshowCodeLensesinErlangfiles.","type":"boolean"},"elp.lens.links.enable":{"default":false,"markdownDescription":"Whethertoshowthe`Link`lenses.Onlyapplieswhen\n`#elp.lens.enable#`isset.","type":"boolean"},"elp.lens.run.coverage.enable":{"default":false,"markdownDescription":"Displaycodecoverageinformationwhenrunningtestsviathe\nCodeLenses.Onlyapplieswhen`#elp.lens.enabled`and\n`#elp.lens.run.enable#`areset.","type":"boolean"},"elp.lens.run.enable":{"default":false,"markdownDescription":"Whethertoshowthe`Run`lenses.Onlyapplieswhen\n`#elp.lens.enable#`isset.","type":"boolean"},"elp.lens.run.interactive.enable":{"default":false,"markdownDescription":"Whethertoshowthe`RunInteractive`lenses.Onlyapplieswhen\n`#elp.lens.enable#`isset.","type":"boolean"},"elp.log":{"default":"error","markdownDescription":"ConfigureLSP-basedloggingusingenv_loggersyntax.","type":"string"},"elp.rename.atoms.enable":{"default":false,"markdownDescription":"Whetherrenaminganatomwhichnamesnothing,suchasamessage\ntag,renamesallitsusesintheproject.","type":"boolean"},"elp.signatureHelp.enable":{"default":true,"markdownDescription":"WhethertoshowSignatureHelp.","type":"boolean"},"elp.typesOnHover.enable":{"default":false,"markdownDescription":"Displaytypeswhenhoveringoverexpressions.","type":"boolean"},"#]]
         .assert_eq(s.as_str());
 
         expect![[r#"
+            "elp.analysis.generatedSyntaxOnly.enable": {
+              "default": false,
+              "markdownDescription": "Whether files marked as generated only get syntax-level analysis\n(no eqwalizer, semantic diagnostics or semantic tokens).",
+              "type": "boolean"
+            },
+            "elp.analysis.maxFileSize": {
+              "default": 2000000,
+              "markdownDescription": "Files larger than this many bytes only get syntax-level analysis\n(no eqwalizer, semantic diagnostics or semantic tokens).",
+              "minimum": 0,
+              "type": "integer"
+            },
+            "elp.assist.alignArrows.enable": {
+              "default": false,
+              "markdownDescription": "Whether to offer the assists aligning and un-aligning the arrows\nof clauses.",
+              "type": "boolean"
+            },
+            "elp.assist.minOtpRelease": {
+              "default": null,
+              "markdownDescription": "Oldest OTP release the project supports, restricting the assists\nto the syntax available in it. Defaults to the release of the\nproject's toolchain.",
+              "minimum": 0,
+              "type": [
+                "null",
+                "integer"
+              ]
+            },
             "elp.buck.query.useBxl.enable": {
               "default": false,
               "markdownDescription": "Use BXL to query for buck project model.",
@@ -591,6 +763,11 @@ mod tests {
               "markdownDescription": "Whether to show function parameter name inlay hints at the call\nsite.",
               "type": "boolean"
             },
+            "elp.inlayHints.typeHints.enable": {
+              "default": false,
+              "markdownDescription": "Whether to show the types found by eqWAlizer for bound variables\nand returned values.",
+              "type": "boolean"
+            },
             "elp.lens.debug.enable": {
               "default": false,
               "markdownDescription": "Whether to show the `Debug` lenses. Only applies when\n`#elp.lens.enable#` is set.",
@@ -626,6 +803,11 @@ mod tests {
               "markdownDescription": "Configure LSP-based logging using env_logger syntax.",
               "type": "string"
             },
+            "elp.rename.atoms.enable": {
+              "default": false,
+              "markdownDescription": "Whether renaming an atom which names nothing, such as a message\ntag, renames all its uses in the project.",
+              "type": "boolean"
+            },
             "elp.signatureHelp.enable": {
               "default": true,
               "markdownDescription": "Whether to show Signature Help.",
@@ -639,6 +821,30 @@ mod tests {
         "#]].assert_eq(schema.as_str());
     }
 
+    #[test]
+    fn analysis_budget() {
+        let budget = AnalysisBudgetConfig {
+            max_file_size: 20,
+            generated_syntax_only: true,
+        };
+        assert_eq!(budget.syntax_only_reason("-module(small)."), None);
+        assert_eq!(
+            budget.syntax_only_reason("-module(much_too_large)."),
+            Some(SyntaxOnlyReason::TooLarge)
+        );
+        // Split the marker so this file is not itself considered generated
+        let generated = format!("%% {}generated", "@");
+        assert_eq!(
+            budget.syntax_only_reason(&generated),
+            Some(SyntaxOnlyReason::Generated)
+        );
+        let budget = AnalysisBudgetConfig {
+            generated_syntax_only: false,
+            ..budget
+        };
+        assert_eq!(budget.syntax_only_reason(&generated), None);
+    }
+
     fn remove_ws(text: &str) -> String {
         text.replace(char::is_whitespace, "")
     }
@@ -0,0 +1,459 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The settings of the server are layered, each layer overriding the
+//! ones before it:
+//!
+//! - the built-in defaults, see `ConfigData`
+//! - the org config, a TOML or JSON file named by `ELP_ORG_CONFIG`,
+//!   either a path or a URL
+//! - the `[settings]` table of the `.elp.toml` at the root of the workspace
+//! - the settings of the workspace folder, sent by the client
+//!
+//! Each layer is validated against the schema of the settings, so that a
+//! typo in a shared file does not silently fall back to the defaults.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::process::Command;
+
+use anyhow::bail;
+use anyhow::Context;
+use anyhow::Result;
+use elp_ide::elp_ide_db::elp_base_db::AbsPath;
+use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
+use elp_project_model::ElpConfig;
+use elp_project_model::ELP_CONFIG_FILE;
+use lsp_types::Url;
+use serde::Serialize;
+use serde_json::Value;
+
+pub const ORG_CONFIG_ENV: &str = "ELP_ORG_CONFIG";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigLayer {
+    Default,
+    Org,
+    Project,
+    Workspace,
+}
+
+impl fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigLayer::Default => write!(f, "default"),
+            ConfigLayer::Org => write!(f, "org"),
+            ConfigLayer::Project => write!(f, "project"),
+            ConfigLayer::Workspace => write!(f, "workspace"),
+        }
+    }
+}
+
+/// A layer which could not be read, or holding a setting which does not
+/// match the schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub layer: ConfigLayer,
+    /// Where the layer comes from, a path, a URL or the client
+    pub source: String,
+    /// The file of the layer, for the ones read from a local file
+    pub path: Option<AbsPathBuf>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} config {}: {}", self.layer, self.source, self.message)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Layer {
+    source: String,
+    path: Option<AbsPathBuf>,
+    settings: Value,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLayers {
+    org: Option<Layer>,
+    project: Option<Layer>,
+    workspace: Option<Layer>,
+    issues: Vec<ConfigIssue>,
+}
+
+impl ConfigLayers {
+    /// Reads the org and project layers. An org config given by URL is
+    /// left to `fetch_org_config`, so that loading never waits on the
+    /// network. The schema is the one of `Config::json_schema`.
+    pub fn load(root_path: &AbsPath, schema: &Value) -> ConfigLayers {
+        let mut layers = ConfigLayers::default();
+        if let Ok(location) = env::var(ORG_CONFIG_ENV) {
+            if !is_url(&location) {
+                let path = local_path(&location);
+                let settings = read_org_config(&location, path.as_deref());
+                layers.set_org(location, path, settings, schema);
+            }
+        }
+        layers.reload_project(root_path, schema);
+        layers
+    }
+
+    /// The URL of the org config, when it has to be fetched.
+    pub fn org_config_url() -> Option<String> {
+        env::var(ORG_CONFIG_ENV)
+            .ok()
+            .filter(|location| is_url(location))
+    }
+
+    /// Replaces the org layer by the settings read from `location`.
+    pub fn set_org(
+        &mut self,
+        location: String,
+        path: Option<AbsPathBuf>,
+        settings: Result<Value>,
+        schema: &Value,
+    ) {
+        self.issues.retain(|issue| issue.layer != ConfigLayer::Org);
+        self.org = None;
+        match settings {
+            Ok(settings) => {
+                let layer = Layer {
+                    source: location,
+                    path,
+                    settings,
+                };
+                self.validate(ConfigLayer::Org, &layer, schema, true);
+                self.org = Some(layer);
+            }
+            Err(err) => self.issues.push(ConfigIssue {
+                layer: ConfigLayer::Org,
+                source: location,
+                path,
+                message: format!("{err:#}"),
+            }),
+        }
+    }
+
+    /// Reads the `.elp.toml` at the root of the workspace again, e.g.
+    /// once it changed.
+    pub fn reload_project(&mut self, root_path: &AbsPath, schema: &Value) {
+        self.issues
+            .retain(|issue| issue.layer != ConfigLayer::Project);
+        self.project = None;
+        let path = root_path.join(ELP_CONFIG_FILE);
+        if !path.exists() {
+            return;
+        }
+        match read_project_config(&path) {
+            Ok((settings, errors)) => {
+                let layer = Layer {
+                    source: path.to_string(),
+                    path: Some(path),
+                    settings: settings.unwrap_or(Value::Null),
+                };
+                for message in errors {
+                    self.push_issue(ConfigLayer::Project, &layer, message);
+                }
+                self.validate(ConfigLayer::Project, &layer, schema, true);
+                self.project = Some(layer);
+            }
+            Err(err) => self.issues.push(ConfigIssue {
+                layer: ConfigLayer::Project,
+                source: path.to_string(),
+                path: Some(path),
+                message: format!("{err:#}"),
+            }),
+        }
+    }
+
+    /// Replaces the settings of the workspace folder. Clients also send
+    /// the settings only known to the editor extension, so the unknown
+    /// ones are not reported for this layer.
+    pub fn set_workspace(&mut self, settings: Value, schema: &Value) {
+        self.issues
+            .retain(|issue| issue.layer != ConfigLayer::Workspace);
+        let layer = Layer {
+            source: "settings".to_string(),
+            path: None,
+            settings,
+        };
+        self.validate(ConfigLayer::Workspace, &layer, schema, false);
+        self.workspace = Some(layer);
+    }
+
+    /// The settings of all the layers, merged.
+    pub fn merged(&self) -> Value {
+        let mut merged = Value::Object(Default::default());
+        for (_, layer) in self.layers() {
+            if layer.settings.is_object() {
+                merge(&mut merged, &layer.settings);
+            }
+        }
+        merged
+    }
+
+    /// The topmost layer setting the field with the given JSON pointer.
+    pub fn layer_of(&self, pointer: &str) -> ConfigLayer {
+        self.layers()
+            .rev()
+            .find(|(_, layer)| layer.settings.pointer(pointer).is_some())
+            .map_or(ConfigLayer::Default, |(kind, _)| kind)
+    }
+
+    pub fn issues(&self) -> &[ConfigIssue] {
+        &self.issues
+    }
+
+    fn layers(&self) -> impl DoubleEndedIterator<Item = (ConfigLayer, &Layer)> {
+        [
+            (ConfigLayer::Org, &self.org),
+            (ConfigLayer::Project, &self.project),
+            (ConfigLayer::Workspace, &self.workspace),
+        ]
+        .into_iter()
+        .filter_map(|(kind, layer)| Some((kind, layer.as_ref()?)))
+    }
+
+    fn validate(&mut self, kind: ConfigLayer, layer: &Layer, schema: &Value, report_unknown: bool) {
+        let mut errors = Vec::new();
+        check_settings(&mut errors, "elp", &layer.settings, schema, report_unknown);
+        for message in errors {
+            self.push_issue(kind, layer, message);
+        }
+    }
+
+    fn push_issue(&mut self, kind: ConfigLayer, layer: &Layer, message: String) {
+        self.issues.push(ConfigIssue {
+            layer: kind,
+            source: layer.source.clone(),
+            path: layer.path.clone(),
+            message,
+        });
+    }
+}
+
+fn is_url(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+fn local_path(location: &str) -> Option<AbsPathBuf> {
+    if is_url(location) {
+        return None;
+    }
+    let path = match Url::parse(location) {
+        Ok(url) if url.scheme() == "file" => url.to_file_path().ok()?,
+        _ => location.into(),
+    };
+    AbsPathBuf::try_from(path.to_str()?).ok()
+}
+
+fn read_org_config(location: &str, path: Option<&AbsPath>) -> Result<Value> {
+    let text = match path {
+        Some(path) => fs::read_to_string(path).with_context(|| format!("unable to read {path}"))?,
+        None => bail!("expected an absolute path or a URL"),
+    };
+    parse_org_config(location, &text)
+}
+
+/// Fetches the org config given by URL, see `ConfigLayers::org_config_url`.
+/// This waits on the network, so the server runs it as a task.
+pub fn fetch_org_config(url: &str) -> Result<Value> {
+    parse_org_config(url, &fetch(url)?)
+}
+
+fn parse_org_config(location: &str, text: &str) -> Result<Value> {
+    if location.ends_with(".json") {
+        serde_json::from_str(text).context("invalid JSON")
+    } else {
+        let toml: toml::Value = toml::from_str(text).context("invalid TOML")?;
+        Ok(serde_json::to_value(toml)?)
+    }
+}
+
+/// Fetches the org config with curl, which honours the proxy settings of
+/// the machine without ELP having to.
+fn fetch(url: &str) -> Result<String> {
+    let output = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location"])
+        .args(["--max-time", "10"])
+        .arg(url)
+        .output()
+        .context("unable to run curl")?;
+    if !output.status.success() {
+        bail!(
+            "unable to fetch: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// The `[settings]` of the `.elp.toml`, along with the errors of the rest
+/// of the file, which configures the project rather than the server.
+fn read_project_config(path: &AbsPath) -> Result<(Option<Value>, Vec<String>)> {
+    let text = fs::read_to_string(path).with_context(|| format!("unable to read {path}"))?;
+    let mut errors = Vec::new();
+    if let Err(err) = toml::from_str::<ElpConfig>(&text) {
+        errors.push(err.to_string());
+    }
+    let mut toml: toml::value::Table = toml::from_str(&text).context("invalid TOML")?;
+    let settings = toml
+        .remove("settings")
+        .map(serde_json::to_value)
+        .transpose()?;
+    Ok((settings, errors))
+}
+
+fn merge(into: &mut Value, from: &Value) {
+    match (into, from) {
+        (Value::Object(into), Value::Object(from)) => {
+            for (key, value) in from {
+                merge(into.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+        (into, from) => *into = from.clone(),
+    }
+}
+
+fn check_settings(
+    errors: &mut Vec<String>,
+    name: &str,
+    value: &Value,
+    schema: &Value,
+    report_unknown: bool,
+) {
+    if let Some(props) = schema.get(name) {
+        if let Some(expected) = type_mismatch(value, props) {
+            errors.push(format!("`{name}` should be {expected}"));
+        }
+        return;
+    }
+    match value {
+        Value::Object(fields) => {
+            for (field, value) in fields {
+                let name = format!("{name}.{field}");
+                check_settings(errors, &name, value, schema, report_unknown);
+            }
+        }
+        // The value of the whole section, when sent by the client
+        Value::Null if name == "elp" => {}
+        _ => {
+            if report_unknown {
+                errors.push(format!("unknown setting `{name}`"));
+            }
+        }
+    }
+}
+
+/// The type the schema expects, if `value` does not have it.
+fn type_mismatch(value: &Value, props: &Value) -> Option<String> {
+    let types: Vec<&str> = match props.get("type")? {
+        Value::String(ty) => vec![ty.as_str()],
+        Value::Array(tys) => tys.iter().filter_map(|ty| ty.as_str()).collect(),
+        _ => return None,
+    };
+    let matches = |ty: &str| match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_u64(),
+        "string" => value.is_string(),
+        "array" => value.as_array().map_or(false, |items| {
+            items.iter().all(|item| match props.pointer("/items/type") {
+                Some(Value::String(item_ty)) if item_ty == "string" => item.is_string(),
+                _ => true,
+            })
+        }),
+        "object" => value.is_object(),
+        _ => true,
+    };
+    if types.iter().any(|ty| matches(ty)) {
+        None
+    } else {
+        Some(
+            types
+                .iter()
+                .map(|ty| match *ty {
+                    "integer" => "a non-negative integer".to_string(),
+                    "array" => "an array of strings".to_string(),
+                    ty => format!("a {ty}"),
+                })
+                .collect::<Vec<_>>()
+                .join(" or "),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::config::Config;
+
+    fn issues(settings: Value, report_unknown: bool) -> Vec<String> {
+        let mut errors = Vec::new();
+        check_settings(
+            &mut errors,
+            "elp",
+            &settings,
+            &Config::json_schema(),
+            report_unknown,
+        );
+        errors
+    }
+
+    #[test]
+    fn validates_against_schema() {
+        assert_eq!(
+            issues(
+                json!({
+                    "lens": { "enable": "yes", "run": { "enable": true } },
+                    "diagnostics": { "disabled": ["W0011", 12] },
+                    "eqwalizer": { "maxTasks": -1 },
+                    "lense": { "enable": true },
+                }),
+                true
+            ),
+            vec![
+                "`elp.diagnostics.disabled` should be an array of strings",
+                "`elp.eqwalizer.maxTasks` should be a non-negative integer",
+                "`elp.lens.enable` should be a boolean",
+                "unknown setting `elp.lense.enable`",
+            ]
+        );
+        assert_eq!(
+            issues(json!({ "serverPath": "/usr/bin/elp" }), false),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn later_layers_override_earlier_ones() {
+        let schema = Config::json_schema();
+        let mut layers = ConfigLayers {
+            org: Some(Layer {
+                source: "org.toml".to_string(),
+                path: None,
+                settings: json!({ "lens": { "enable": true, "run": { "enable": true } } }),
+            }),
+            ..ConfigLayers::default()
+        };
+        layers.set_workspace(json!({ "lens": { "run": { "enable": false } } }), &schema);
+        assert_eq!(
+            layers.merged(),
+            json!({ "lens": { "enable": true, "run": { "enable": false } } })
+        );
+        assert_eq!(layers.layer_of("/lens/enable"), ConfigLayer::Org);
+        assert_eq!(layers.layer_of("/lens/run/enable"), ConfigLayer::Workspace);
+        assert_eq!(layers.layer_of("/log"), ConfigLayer::Default);
+    }
+}
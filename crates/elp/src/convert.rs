@@ -20,29 +20,169 @@ use elp_ide::elp_ide_db::assists::AssistContextDiagnostic;
 use elp_ide::elp_ide_db::assists::AssistContextDiagnosticCode;
 use elp_ide::elp_ide_db::elp_base_db::AbsPath;
 use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
+use elp_ide::elp_ide_db::elp_base_db::FileRange;
 use elp_ide::elp_ide_db::elp_base_db::VfsPath;
+use elp_ide::elp_ide_db::elp_base_db::VIRTUAL_DOCUMENTS_ROOT;
 use elp_ide::elp_ide_db::EqwalizerDiagnostic;
 use elp_ide::elp_ide_db::LineIndex;
 use elp_ide::TextRange;
 use elp_ide::TextSize;
+use elp_project_model::canonicalize_path;
+use elp_project_model::EqwalizerLevel;
+use fxhash::FxHashMap;
 use lsp_types::DiagnosticRelatedInformation;
 use lsp_types::Location;
 use lsp_types::Url;
+use parking_lot::RwLock;
 use paths::Utf8Component;
 use paths::Utf8Prefix;
 
 use crate::arc_types;
 use crate::from_proto;
 
-pub fn abs_path(url: &lsp_types::Url) -> Result<AbsPathBuf> {
-    let path = url
-        .to_file_path()
-        .map_err(|()| anyhow!("url '{}' is not a file", url))?;
-    Ok(AbsPathBuf::assert_utf8(path))
+/// The client may reach a file through a symlink, or spell it with a
+/// different case, while the VFS only knows it under its canonical path.
+/// The paths of the open documents are resolved once, and the directories
+/// the client uses in place of canonical ones are remembered to give back
+/// to the client the paths it knows. The server holds one for the session.
+#[derive(Default)]
+pub struct ClientPaths {
+    paths: RwLock<Paths>,
 }
 
-pub fn vfs_path(url: &lsp_types::Url) -> Result<VfsPath> {
-    abs_path(url).map(VfsPath::from)
+#[derive(Default)]
+struct Paths {
+    /// The canonical paths of the open documents
+    canonical: FxHashMap<AbsPathBuf, AbsPathBuf>,
+    /// Canonical directories with the directory the client uses for
+    /// them, longest first.
+    client_dirs: Vec<(AbsPathBuf, AbsPathBuf)>,
+}
+
+impl Paths {
+    fn add_client_dirs(&mut self, client: &AbsPath, canonical: &AbsPath) {
+        if client != canonical {
+            if let Some(dirs) = diverging_dirs(client, canonical) {
+                if !self.client_dirs.contains(&dirs) {
+                    self.client_dirs.push(dirs);
+                    self.client_dirs
+                        .sort_by_key(|(canonical, _)| std::cmp::Reverse(canonical.as_str().len()));
+                }
+            }
+        }
+    }
+
+    fn client_path(&self, path: &AbsPath) -> Option<AbsPathBuf> {
+        self.client_dirs.iter().find_map(|(canonical, client)| {
+            let rest = path.strip_prefix(canonical)?;
+            Some(client.join(rest.as_str()))
+        })
+    }
+}
+
+impl ClientPaths {
+    pub fn abs_path(&self, url: &lsp_types::Url) -> Result<AbsPathBuf> {
+        let path = url
+            .to_file_path()
+            .map_err(|()| anyhow!("url '{}' is not a file", url))?;
+        let path = AbsPathBuf::assert_utf8(path);
+        if let Some(canonical) = self.paths.read().canonical.get(&path) {
+            return Ok(canonical.clone());
+        }
+        let canonical = canonicalize_path(&path);
+        self.paths.write().add_client_dirs(&path, &canonical);
+        Ok(canonical)
+    }
+
+    pub fn vfs_path(&self, url: &lsp_types::Url) -> Result<VfsPath> {
+        if url.scheme() == "file" {
+            return self.abs_path(url).map(VfsPath::from);
+        }
+        Ok(virtual_path(url))
+    }
+
+    /// The path of a document the client opened, resolved once for as
+    /// long as it stays open.
+    pub fn open(&self, url: &lsp_types::Url) -> Result<VfsPath> {
+        let path = self.vfs_path(url)?;
+        if let (Ok(client), Some(canonical)) = (url.to_file_path(), path.as_path()) {
+            self.paths
+                .write()
+                .canonical
+                .insert(AbsPathBuf::assert_utf8(client), canonical.to_path_buf());
+        }
+        Ok(path)
+    }
+
+    /// Forgets the path of a document the client closed.
+    pub fn close(&self, url: &lsp_types::Url) {
+        if let Ok(client) = url.to_file_path() {
+            self.paths
+                .write()
+                .canonical
+                .remove(&AbsPathBuf::assert_utf8(client));
+        }
+    }
+
+    /// The URL of a document in the VFS, the inverse of `vfs_path`.
+    pub fn url_from_vfs_path(&self, path: &VfsPath) -> lsp_types::Url {
+        match path.as_path() {
+            Some(path) => self.url_from_abs_path(path),
+            None => url_from_virtual_path(path),
+        }
+    }
+
+    /// The URL of a path, under the directories the client knows it by.
+    pub fn url_from_abs_path(&self, path: &AbsPath) -> lsp_types::Url {
+        let client_path = self.paths.read().client_path(path);
+        url_from_abs_path(client_path.as_deref().unwrap_or(path))
+    }
+}
+
+/// The canonical and client paths without their common trailing
+/// components, which are the directories where they diverge.
+fn diverging_dirs(client: &AbsPath, canonical: &AbsPath) -> Option<(AbsPathBuf, AbsPathBuf)> {
+    let mut client = client;
+    let mut canonical = canonical;
+    while client.file_name().is_some() && client.file_name() == canonical.file_name() {
+        client = client.parent()?;
+        canonical = canonical.parent()?;
+    }
+    Some((canonical.to_path_buf(), client.to_path_buf()))
+}
+
+/// The documents without a file on disk live under
+/// `VIRTUAL_DOCUMENTS_ROOT`, at their URL. An `untitled:` buffer has no
+/// extension, so it is put under this prefix with one, for it to be
+/// analyzed as a module.
+const UNTITLED_MODULE_PREFIX: &str = "untitled/";
+
+fn virtual_path(url: &lsp_types::Url) -> VfsPath {
+    let path = if url.scheme() == "untitled" && !has_erlang_extension(url.path()) {
+        format!("{VIRTUAL_DOCUMENTS_ROOT}/{UNTITLED_MODULE_PREFIX}{url}.erl")
+    } else {
+        format!("{VIRTUAL_DOCUMENTS_ROOT}/{url}")
+    };
+    VfsPath::new_virtual_path(path)
+}
+
+fn has_erlang_extension(path: &str) -> bool {
+    [".erl", ".hrl", ".escript"]
+        .iter()
+        .any(|extension| path.ends_with(extension))
+}
+
+fn url_from_virtual_path(path: &VfsPath) -> lsp_types::Url {
+    let path = path.to_string();
+    let url = path
+        .strip_prefix(VIRTUAL_DOCUMENTS_ROOT)
+        .and_then(|url| url.strip_prefix('/'))
+        .unwrap_or(&path);
+    let url = match url.strip_prefix(UNTITLED_MODULE_PREFIX) {
+        Some(untitled) => untitled.strip_suffix(".erl").unwrap_or(untitled),
+        None => url,
+    };
+    lsp_types::Url::parse(url).expect("virtual documents are stored at their URL")
 }
 
 pub fn range(line_index: &LineIndex, range: TextRange) -> lsp_types::Range {
@@ -65,42 +205,70 @@ pub fn diagnostic_severity(severity: Severity) -> lsp_types::DiagnosticSeverity
     }
 }
 
+/// Converts a diagnostic of the file at `url`. The location of a range of
+/// another file is only needed for the related information of some
+/// diagnostics, which is dropped when `other_location` cannot find it.
 pub fn ide_to_lsp_diagnostic(
     line_index: &LineIndex,
     url: &Url,
     d: &Diagnostic,
+    other_location: impl Fn(FileRange) -> Option<Location>,
 ) -> lsp_types::Diagnostic {
-    let code_description = match &d.code_doc_uri {
-        Some(uri) => match lsp_types::Url::parse(uri) {
+    let code_description = match d.code_doc_uri.clone().or_else(|| d.code.as_uri()) {
+        Some(uri) => match lsp_types::Url::parse(&uri) {
             Ok(href) => Some(lsp_types::CodeDescription { href }),
             Err(_) => None,
         },
         None => None,
     };
-    let (source, code) = match &d.code {
-        DiagnosticCode::Eqwalizer(code) => (Some("eqWAlizer".into()), code.clone()),
-        _ => (Some("elp".into()), d.code.to_string()),
+    let source = match &d.code {
+        DiagnosticCode::Eqwalizer(_) => Some("eqWAlizer".into()),
+        _ => Some("elp".into()),
     };
     lsp_types::Diagnostic {
         range: range(line_index, d.range),
         severity: Some(diagnostic_severity(d.severity)),
-        code: Some(lsp_types::NumberOrString::String(code)),
+        code: Some(lsp_types::NumberOrString::String(
+            d.code.as_qualified_code(),
+        )),
         code_description,
         source,
         message: d.message.clone(),
-        related_information: from_related(line_index, url, &d.related_info),
-        tags: None,
+        related_information: from_related(line_index, url, &d.related_info, other_location),
+        tags: diagnostic_tags(&d.code),
         data: None,
     }
 }
 
+/// Lets clients render unused and deprecated code, and group such
+/// diagnostics together.
+fn diagnostic_tags(code: &DiagnosticCode) -> Option<Vec<lsp_types::DiagnosticTag>> {
+    match code {
+        DiagnosticCode::UnusedMacro
+        | DiagnosticCode::UnusedRecordField
+        | DiagnosticCode::UnusedFunctionArg
+        | DiagnosticCode::UnusedInclude
+        | DiagnosticCode::UnusedSuppression
+        | DiagnosticCode::UnreachableTest => Some(vec![lsp_types::DiagnosticTag::UNNECESSARY]),
+        DiagnosticCode::DeprecatedFunction => Some(vec![lsp_types::DiagnosticTag::DEPRECATED]),
+        DiagnosticCode::ErlangService(code) => match AssistContextDiagnosticCode::from_str(code) {
+            Ok(AssistContextDiagnosticCode::UnusedFunction)
+            | Ok(AssistContextDiagnosticCode::UnusedVariable) => {
+                Some(vec![lsp_types::DiagnosticTag::UNNECESSARY])
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 pub fn lsp_to_assist_context_diagnostic(
     line_index: &LineIndex,
     d: lsp_types::Diagnostic,
 ) -> Option<AssistContextDiagnostic> {
     let range = from_proto::safe_text_range(line_index, d.range)?;
     if let Some(lsp_types::NumberOrString::String(code)) = d.code {
-        match AssistContextDiagnosticCode::from_str(&code) {
+        match AssistContextDiagnosticCode::from_str(DiagnosticCode::unqualified(&code)) {
             Ok(code) => Some(AssistContextDiagnostic::new(code, d.message, range)),
             Err(_) => None,
         }
@@ -114,12 +282,16 @@ pub fn eqwalizer_to_arc_diagnostic(
     line_index: &LineIndex,
     relative_path: &Path,
     eqwalizer_enabled: bool,
+    level: EqwalizerLevel,
 ) -> arc_types::Diagnostic {
     let pos = position(line_index, d.range.start());
     let line_num = pos.line + 1;
     let character = Some(pos.character + 1);
     let severity = if eqwalizer_enabled {
-        arc_types::Severity::Error
+        match level {
+            EqwalizerLevel::Warnings => arc_types::Severity::Warning,
+            _ => arc_types::Severity::Error,
+        }
     } else {
         // We use Severity::Disabled so that we have the ability in our arc linter to choose
         // to display lints for *new* files with errors that are not opted in (T118466310).
@@ -160,18 +332,25 @@ fn from_related(
     line_index: &LineIndex,
     url: &Url,
     r: &Option<Vec<RelatedInformation>>,
+    other_location: impl Fn(FileRange) -> Option<Location>,
 ) -> Option<Vec<DiagnosticRelatedInformation>> {
     r.as_ref().map(|ri| {
         ri.iter()
-            .map(|i| {
-                let location = Location {
-                    range: range(line_index, i.range),
-                    uri: url.clone(),
+            .filter_map(|i| {
+                let location = match i.file_id {
+                    Some(file_id) => other_location(FileRange {
+                        file_id,
+                        range: i.range,
+                    })?,
+                    None => Location {
+                        range: range(line_index, i.range),
+                        uri: url.clone(),
+                    },
                 };
-                DiagnosticRelatedInformation {
+                Some(DiagnosticRelatedInformation {
                     location,
                     message: i.message.clone(),
-                }
+                })
             })
             .collect()
     })
@@ -183,7 +362,7 @@ fn from_related(
 /// This will only happen when processing windows paths.
 ///
 /// When processing non-windows path, this is essentially the same as `Url::from_file_path`.
-pub(crate) fn url_from_abs_path(path: &AbsPath) -> lsp_types::Url {
+fn url_from_abs_path(path: &AbsPath) -> lsp_types::Url {
     let url = lsp_types::Url::from_file_path(path).unwrap();
     match path.components().next() {
         Some(Utf8Component::Prefix(prefix))
@@ -249,3 +428,77 @@ pub fn ide_to_arc_diagnostic(
         None,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use lsp_types::Url;
+
+    use super::ClientPaths;
+
+    #[track_caller]
+    fn check_virtual_document(url: &str, expect: expect_test::Expect) {
+        let client_paths = ClientPaths::default();
+        let url = Url::parse(url).unwrap();
+        let path = client_paths.vfs_path(&url).unwrap();
+        expect.assert_eq(&path.to_string());
+        assert_eq!(client_paths.url_from_vfs_path(&path), url);
+    }
+
+    #[test]
+    fn virtual_documents_round_trip() {
+        check_virtual_document(
+            "untitled:Untitled-1",
+            expect!["/elp-virtual-documents/untitled/untitled:Untitled-1.erl"],
+        );
+        check_virtual_document(
+            "untitled:/scratch/foo.erl",
+            expect!["/elp-virtual-documents/untitled:/scratch/foo.erl"],
+        );
+        check_virtual_document(
+            "vscode-vfs://github/org/repo/src/foo.erl",
+            expect!["/elp-virtual-documents/vscode-vfs://github/org/repo/src/foo.erl"],
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn round_trip_through_symlinked_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("real");
+        std::fs::create_dir_all(real.join("src")).unwrap();
+        std::fs::write(real.join("src/a.erl"), "-module(a).").unwrap();
+        std::fs::write(real.join("src/b.erl"), "-module(b).").unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        let client_paths = ClientPaths::default();
+        let url = Url::from_file_path(link.join("src/a.erl")).unwrap();
+        let path = client_paths.abs_path(&url).unwrap();
+        let canonical_real = real.canonicalize().unwrap();
+        assert_eq!(
+            path.as_str(),
+            canonical_real.join("src/a.erl").to_str().unwrap()
+        );
+        assert_eq!(client_paths.url_from_abs_path(&path), url);
+        // A file the client did not send is given back under the symlink too
+        let other = path.parent().unwrap().join("b.erl");
+        assert_eq!(
+            client_paths.url_from_abs_path(&other),
+            Url::from_file_path(link.join("src/b.erl")).unwrap()
+        );
+    }
+
+    #[test]
+    fn closed_documents_are_forgotten() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.erl");
+        std::fs::write(&file, "-module(a).").unwrap();
+        let client_paths = ClientPaths::default();
+        let url = Url::from_file_path(&file).unwrap();
+        client_paths.open(&url).unwrap();
+        assert_eq!(client_paths.paths.read().canonical.len(), 1);
+        client_paths.close(&url);
+        assert!(client_paths.paths.read().canonical.is_empty());
+    }
+}
@@ -15,9 +15,11 @@ use elp_ide::elp_ide_db::elp_base_db::FilePosition;
 use elp_ide::elp_ide_db::elp_base_db::FileRange;
 use elp_ide::elp_ide_db::LineCol;
 use elp_ide::elp_ide_db::LineIndex;
+use elp_ide::ReferenceKind;
 use elp_ide::TextRange;
 use elp_ide::TextSize;
 
+use crate::lsp_ext;
 use crate::snapshot::Snapshot;
 use crate::Result;
 
@@ -108,8 +110,26 @@ pub(crate) fn assist_kind(kind: lsp_types::CodeActionKind) -> Option<AssistKind>
         k if k == &lsp_types::CodeActionKind::REFACTOR_EXTRACT => AssistKind::RefactorExtract,
         k if k == &lsp_types::CodeActionKind::REFACTOR_INLINE => AssistKind::RefactorInline,
         k if k == &lsp_types::CodeActionKind::REFACTOR_REWRITE => AssistKind::RefactorRewrite,
+        k if k == &lsp_types::CodeActionKind::SOURCE => AssistKind::Source,
         _ => return None,
     };
 
     Some(assist_kind)
 }
+
+/// The declaration is not a reference for the analysis, it is returned
+/// on its own.
+pub(crate) fn reference_kind(kind: lsp_ext::ReferenceKind) -> Option<ReferenceKind> {
+    let kind = match kind {
+        lsp_ext::ReferenceKind::Declaration => return None,
+        lsp_ext::ReferenceKind::Call => ReferenceKind::Call,
+        lsp_ext::ReferenceKind::Export => ReferenceKind::Export,
+        lsp_ext::ReferenceKind::FunCapture => ReferenceKind::FunCapture,
+        lsp_ext::ReferenceKind::Spec => ReferenceKind::Spec,
+        lsp_ext::ReferenceKind::Import => ReferenceKind::Import,
+        lsp_ext::ReferenceKind::Heuristic => ReferenceKind::Heuristic,
+        lsp_ext::ReferenceKind::Doc => ReferenceKind::Doc,
+        lsp_ext::ReferenceKind::Other => ReferenceKind::Other,
+    };
+    Some(kind)
+}
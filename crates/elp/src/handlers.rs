@@ -11,6 +11,9 @@
 //! Protocol. The majority of requests are fulfilled by calling into the
 //! `ide` crate.
 
+use std::env;
+use std::process;
+
 use anyhow::bail;
 use anyhow::Result;
 use elp_ide::elp_ide_assists::AssistKind;
@@ -18,6 +21,7 @@ use elp_ide::elp_ide_assists::AssistResolveStrategy;
 use elp_ide::elp_ide_assists::SingleResolve;
 use elp_ide::elp_ide_db::assists::AssistContextDiagnostic;
 use elp_ide::elp_ide_db::docs::Doc;
+use elp_ide::elp_ide_db::elp_base_db::FileId;
 use elp_ide::elp_ide_db::elp_base_db::FilePosition;
 use elp_ide::elp_ide_db::elp_base_db::FileRange;
 use elp_ide::elp_ide_db::elp_base_db::ProjectId;
@@ -25,6 +29,7 @@ use elp_ide::elp_ide_db::LineIndex;
 use elp_ide::elp_ide_db::SymbolKind;
 use elp_ide::Cancellable;
 use elp_ide::HighlightedRange;
+use elp_ide::HoverConfig;
 use elp_ide::RangeInfo;
 use elp_ide::TextRange;
 use itertools::Itertools;
@@ -42,6 +47,7 @@ use lsp_types::DocumentSymbol;
 use lsp_types::FoldingRange;
 use lsp_types::FoldingRangeParams;
 use lsp_types::HoverParams;
+use lsp_types::PrepareRenameResponse;
 use lsp_types::RenameParams;
 use lsp_types::SemanticTokensDeltaParams;
 use lsp_types::SemanticTokensFullDeltaResult;
@@ -53,7 +59,10 @@ use lsp_types::SymbolInformation;
 use lsp_types::TextDocumentIdentifier;
 use lsp_types::Url;
 use lsp_types::WorkspaceEdit;
+use stdx::format_to;
 
+use crate::bug_report::BugReport;
+use crate::convert::ide_to_lsp_diagnostic;
 use crate::convert::lsp_to_assist_context_diagnostic;
 use crate::from_proto;
 use crate::lsp_ext;
@@ -83,6 +92,7 @@ pub(crate) fn handle_code_action(
         .only
         .clone()
         .map(|it| it.into_iter().filter_map(from_proto::assist_kind).collect());
+    assists_config.disabled = snap.disabled_assists(frange.file_id);
 
     let mut res: Vec<lsp_types::CodeActionOrCommand> = Vec::new();
 
@@ -146,6 +156,7 @@ pub(crate) fn handle_code_action_resolve(
         .context
         .only
         .map(|it| it.into_iter().filter_map(from_proto::assist_kind).collect());
+    assists_config.disabled = snap.disabled_assists(file_id);
 
     let (assist_index, assist_resolve) = match parse_action_id(&params.id) {
         Ok(parsed_data) => parsed_data,
@@ -160,6 +171,7 @@ pub(crate) fn handle_code_action_resolve(
 
     let expected_assist_id = assist_resolve.assist_id.clone();
     let expected_kind = assist_resolve.assist_kind;
+    let expected_subtype = assist_resolve.assist_subtype.clone();
 
     let diagnostics = params.code_action_params.context.diagnostics;
     let assist_context_diagnostics = to_assist_context_diagnostics(&line_index, diagnostics);
@@ -185,7 +197,10 @@ pub(crate) fn handle_code_action_resolve(
         )
             .into())
     };
-    if assist.id.0 != expected_assist_id || assist.id.1 != expected_kind {
+    if assist.id.0 != expected_assist_id
+        || assist.id.1 != expected_kind
+        || assist.subtype != expected_subtype
+    {
         return Err(LspError::new(
             ErrorCode::InvalidParams as i32,
             format!(
@@ -203,9 +218,10 @@ pub(crate) fn handle_code_action_resolve(
 }
 
 fn parse_action_id(action_id: &str) -> Result<(usize, SingleResolve), String> {
-    let id_parts = action_id.split(':').collect_vec();
+    // The subtype comes last, as it may contain colons.
+    let id_parts = action_id.splitn(4, ':').collect_vec();
     match id_parts.as_slice() {
-        &[assist_id_string, assist_kind_string, index_string] => {
+        &[assist_id_string, assist_kind_string, index_string, ref subtype @ ..] => {
             let assist_kind: AssistKind = assist_kind_string.parse()?;
             let index: usize = match index_string.parse() {
                 Ok(index) => index,
@@ -216,6 +232,7 @@ fn parse_action_id(action_id: &str) -> Result<(usize, SingleResolve), String> {
                 SingleResolve {
                     assist_id: assist_id_string.to_string(),
                     assist_kind,
+                    assist_subtype: subtype.first().map(|it| it.to_string()),
                 },
             ))
         }
@@ -240,10 +257,12 @@ pub(crate) fn handle_expand_macro(
         Some(it) => Ok(Some(lsp_ext::ExpandedMacro {
             name: it.name,
             expansion: it.expansion,
+            single_step: it.single_step,
         })),
         None => Ok(Some(lsp_ext::ExpandedMacro {
             name: "Expansion Failed".to_string(),
             expansion: "".to_string(),
+            single_step: "".to_string(),
         })),
     }
 }
@@ -252,6 +271,88 @@ pub(crate) fn pong(_: Snapshot, _: Vec<String>) -> Result<String> {
     Ok("pong".to_string())
 }
 
+pub(crate) fn handle_memory_usage(snap: Snapshot, _: ()) -> Result<String> {
+    let _p = tracing::info_span!("handle_memory_usage").entered();
+    let usage = snap.analysis.memory_usage()?;
+    Ok(usage.to_string())
+}
+
+pub(crate) fn handle_bug_report(snap: Snapshot, _: ()) -> Result<String> {
+    let _p = tracing::info_span!("handle_bug_report").entered();
+    let mut report = BugReport::new(Some(&snap.config.root_path));
+    let mut projects = String::new();
+    for project in snap.projects.iter() {
+        format_to!(
+            projects,
+            "================{}================\n",
+            project.name()
+        );
+        format_to!(projects, "{:#?}\n", project.project_build_data);
+        format_to!(projects, "{:#?}\n", project.project_apps);
+    }
+    report.add("project.txt", projects);
+    report.add("memory.txt", snap.analysis.memory_usage()?.to_string());
+    report.add_recent_logs();
+    report.add_panics();
+    let to = env::temp_dir().join(format!("elp-bug-report-{}.tar.gz", process::id()));
+    report.write(&to)?;
+    Ok(to.display().to_string())
+}
+
+pub(crate) fn handle_eqwalize_function_errors(
+    snap: Snapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<Option<lsp_ext::FunctionEqwalizerErrors>> {
+    let _p = tracing::info_span!("handle_eqwalize_function_errors").entered();
+    let position = from_proto::file_position(&snap, params)?;
+    let (function, range) = match snap.analysis.enclosing_function(position)? {
+        Some(it) => it,
+        None => return Ok(None),
+    };
+    let line_index = snap.analysis.line_index(position.file_id)?;
+    let url = snap.file_id_to_url(position.file_id);
+    let diagnostics = snap
+        .eqwalizer_diagnostics(position.file_id, true)
+        .unwrap_or_default()
+        .iter()
+        .filter(|d| range.contains_range(d.range))
+        .map(|d| {
+            ide_to_lsp_diagnostic(&line_index, &url, d, |file_range| {
+                to_proto::location(&snap, file_range).ok()
+            })
+        })
+        .collect();
+    Ok(Some(lsp_ext::FunctionEqwalizerErrors {
+        name: function.to_string(),
+        diagnostics,
+    }))
+}
+
+pub(crate) fn handle_on_type_formatting(
+    snap: Snapshot,
+    params: lsp_types::DocumentOnTypeFormattingParams,
+) -> Result<Option<Vec<lsp_types::TextEdit>>> {
+    let _p = tracing::info_span!("handle_on_type_formatting").entered();
+    let mut position = from_proto::file_position(&snap, params.text_document_position)?;
+    position.offset = snap
+        .analysis
+        .clamp_offset(position.file_id, position.offset)?;
+    let Some(char_typed) = params.ch.chars().next() else {
+        return Ok(None);
+    };
+    let edit = match snap.analysis.on_char_typed(position, char_typed)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let line_index = snap.analysis.line_index(position.file_id)?;
+    let line_endings = snap.line_endings(position.file_id);
+    let edits = edit
+        .into_iter()
+        .map(|indel| to_proto::text_edit(&line_index, line_endings, indel))
+        .collect();
+    Ok(Some(edits))
+}
+
 pub(crate) fn handle_selection_range(
     snap: Snapshot,
     params: lsp_types::SelectionRangeParams,
@@ -368,7 +469,10 @@ pub(crate) fn handle_references(
                 .into_iter()
                 .flat_map(|(file_id, refs)| {
                     refs.into_iter()
-                        .map(move |range| FileRange { file_id, range })
+                        .map(move |reference| FileRange {
+                            file_id,
+                            range: reference.range,
+                        })
                         .flat_map(|range| to_proto::location(&snap, range).ok())
                 })
                 .chain(decl)
@@ -377,6 +481,88 @@ pub(crate) fn handle_references(
     Ok(Some(locations))
 }
 
+pub(crate) fn handle_references_by_kind(
+    snap: Snapshot,
+    params: lsp_ext::ReferencesParams,
+) -> Result<Option<Vec<lsp_ext::Reference>>> {
+    let _p = tracing::info_span!("handle_references_by_kind").entered();
+    let mut position = from_proto::file_position(&snap, params.text_document_position)?;
+    position.offset = snap
+        .analysis
+        .clamp_offset(position.file_id, position.offset)?;
+    let include_declaration = params.context.include_declaration
+        && params.kinds.as_ref().map_or(true, |kinds| {
+            kinds.contains(&lsp_ext::ReferenceKind::Declaration)
+        });
+    let kinds = params.kinds.map(|kinds| {
+        kinds
+            .into_iter()
+            .filter_map(from_proto::reference_kind)
+            .collect::<Vec<_>>()
+    });
+    // Empty kinds mean any kind for the analysis
+    let declaration_only = kinds.as_ref().map_or(false, |kinds| kinds.is_empty());
+    if declaration_only && !include_declaration {
+        return Ok(Some(vec![]));
+    }
+    let refs = match snap.analysis.find_refs_by_kind(position, kinds)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let mut res = Vec::new();
+    for refs in refs {
+        if include_declaration {
+            if let Ok(location) = to_proto::location_from_nav(&snap, refs.declaration) {
+                res.push(lsp_ext::Reference {
+                    location,
+                    kind: lsp_ext::ReferenceKind::Declaration,
+                });
+            }
+        }
+        if declaration_only {
+            continue;
+        }
+        for (file_id, refs) in refs.references {
+            for reference in refs {
+                let range = FileRange {
+                    file_id,
+                    range: reference.range,
+                };
+                if let Ok(location) = to_proto::location(&snap, range) {
+                    res.push(lsp_ext::Reference {
+                        location,
+                        kind: to_proto::reference_kind(reference.kind),
+                    });
+                }
+            }
+        }
+    }
+    Ok(Some(res))
+}
+
+pub(crate) fn handle_linked_editing_range(
+    snap: Snapshot,
+    params: lsp_types::LinkedEditingRangeParams,
+) -> Result<Option<lsp_types::LinkedEditingRanges>> {
+    let _p = tracing::info_span!("handle_linked_editing_range").entered();
+    let mut position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    position.offset = snap
+        .analysis
+        .clamp_offset(position.file_id, position.offset)?;
+    let ranges = match snap.analysis.linked_editing_ranges(position)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let line_index = snap.analysis.line_index(position.file_id)?;
+    Ok(Some(lsp_types::LinkedEditingRanges {
+        ranges: ranges
+            .into_iter()
+            .map(|range| to_proto::range(&line_index, range))
+            .collect(),
+        word_pattern: None,
+    }))
+}
+
 pub(crate) fn handle_completion(
     snap: Snapshot,
     params: lsp_types::CompletionParams,
@@ -450,23 +636,51 @@ pub(crate) fn handle_workspace_symbol(
     let mut res = Vec::new();
     for (project_id, _project) in snap.projects.iter().enumerate() {
         let project_id = ProjectId(project_id as u32);
-        for nav in snap.analysis.symbol_search(project_id, &params.query)? {
+        for symbol in snap.analysis.symbol_search(project_id, &params.query)? {
+            let range = FileRange {
+                file_id: symbol.file_id,
+                range: symbol.focus_range.unwrap_or(symbol.full_range),
+            };
             #[allow(deprecated)]
             let info = SymbolInformation {
-                name: nav.name.to_string(),
-                kind: to_proto::symbol_kind(nav.kind),
+                name: symbol.name.to_string(),
+                kind: to_proto::symbol_kind(symbol.kind),
                 tags: None,
-                location: to_proto::location_from_nav(&snap, nav)?,
-                container_name: None,
+                location: to_proto::location(&snap, range)?,
+                container_name: symbol.container_name.map(|name| name.to_string()),
                 deprecated: None,
             };
             res.push(info);
         }
     }
-    res.sort_by(|a, b| a.name.cmp(&b.name));
+    // The symbols of each project come best matches first
     Ok(Some(res))
 }
 
+pub(crate) fn handle_prepare_rename(
+    snap: Snapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<Option<PrepareRenameResponse>> {
+    let _p = tracing::info_span!("handle_prepare_rename").entered();
+    let mut position = from_proto::file_position(&snap, params)?;
+    position.offset = snap
+        .analysis
+        .clamp_offset(position.file_id, position.offset)?;
+
+    let range_info = match snap.analysis.prepare_rename(position)? {
+        Ok(range_info) => range_info,
+        Err(err) if snap.config.rename_atoms() => snap
+            .analysis
+            .prepare_rename_atom(position)?
+            .map_err(|_| to_proto::rename_error(err))?,
+        Err(err) => return Err(to_proto::rename_error(err).into()),
+    };
+
+    let line_index = snap.analysis.line_index(position.file_id)?;
+    let range = to_proto::range(&line_index, range_info.range);
+    Ok(Some(PrepareRenameResponse::Range(range)))
+}
+
 pub(crate) fn handle_rename(snap: Snapshot, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
     let _p = tracing::info_span!("handle_rename").entered();
     let mut position = from_proto::file_position(&snap, params.text_document_position)?;
@@ -474,10 +688,21 @@ pub(crate) fn handle_rename(snap: Snapshot, params: RenameParams) -> Result<Opti
         .analysis
         .clamp_offset(position.file_id, position.offset)?;
 
-    let change = snap
-        .analysis
-        .rename(position, &params.new_name)?
-        .map_err(to_proto::rename_error)?;
+    let change = match snap.analysis.rename(position, &params.new_name)? {
+        Ok(change) => change,
+        // An atom which names nothing is renamed across the project
+        Err(_)
+            if snap.config.rename_atoms()
+                && snap.analysis.prepare_rename_atom(position)?.is_ok() =>
+        {
+            let rename = snap
+                .analysis
+                .rename_atom(position, &params.new_name)?
+                .map_err(to_proto::rename_error)?;
+            return Ok(Some(to_proto::atom_rename_edit(&snap, rename)?));
+        }
+        Err(err) => return Err(to_proto::rename_error(err).into()),
+    };
 
     let workspace_edit = to_proto::workspace_edit(&snap, change)?;
     Ok(Some(workspace_edit))
@@ -499,57 +724,39 @@ pub(crate) fn handle_hover(snap: Snapshot, params: HoverParams) -> Result<Option
     position.offset = snap
         .analysis
         .clamp_offset(position.file_id, position.offset)?;
-    let query_range = FileRange {
-        file_id: position.file_id,
-        range: TextRange::empty(position.offset),
+    let hover_config = HoverConfig {
+        types: snap.config.types_on_hover(),
     };
+    let hover = snap.analysis.hover(position, &hover_config)?;
 
     let mut docs: Vec<(Doc, Option<FileRange>)> = Vec::default();
 
-    if snap.config.types_on_hover() {
-        if let Some(type_info) = snap.analysis.type_at_position(query_range)? {
-            let (ty, range) = &*type_info;
-            let text = &snap.analysis.file_text(range.file_id)?[range.range];
-            let type_doc = Doc::new(format!("```erlang\n{} :: {}\n```\n", text, ty));
-            docs.push((type_doc, Some(range.to_owned())));
-            let refs = snap.analysis.type_references(range.file_id, ty)?;
-            if !refs.is_empty() {
-                let goto_list = refs
-                    .into_iter()
-                    .flat_map(|(name, range)| {
-                        to_proto::location(&snap, range)
-                            .map(|loc| {
-                                format!(
-                                    "[{}]({}#L{}-{})",
-                                    name,
-                                    loc.uri,
-                                    loc.range.start.line + 1,
-                                    loc.range.end.line + 1
-                                )
-                            })
-                            .ok()
-                    })
-                    .join(" | ");
-                let goto_docs = Doc::new(format!("Go to: {}", goto_list));
-                docs.push((goto_docs, None));
-            }
+    if let Some(ty) = hover.ty {
+        docs.push((ty.doc, Some(ty.range)));
+        if !ty.references.is_empty() {
+            let goto_list = ty
+                .references
+                .into_iter()
+                .flat_map(|(name, range)| {
+                    to_proto::location(&snap, range)
+                        .map(|loc| {
+                            format!(
+                                "[{}]({}#L{}-{})",
+                                name,
+                                loc.uri,
+                                loc.range.start.line + 1,
+                                loc.range.end.line + 1
+                            )
+                        })
+                        .ok()
+                })
+                .join(" | ");
+            let goto_docs = Doc::new(format!("Go to: {}", goto_list));
+            docs.push((goto_docs, None));
         }
     }
 
-    if let Some(hover) = snap.analysis.get_docs_at_position(position)? {
-        docs.push(hover);
-    }
-
-    if let Some(macro_expansion) = snap.analysis.expand_macro(position)? {
-        let hover_info = (
-            Doc::new(format!(
-                "{}\n\n```erlang\n{}```\n",
-                macro_expansion.name, macro_expansion.expansion
-            )),
-            None,
-        );
-        docs.push(hover_info);
-    }
+    docs.extend(hover.docs);
 
     let hover_actions_config = snap.config.hover_actions();
     let actions = snap
@@ -603,6 +810,21 @@ pub(crate) fn handle_folding_range(
     Ok(Some(res))
 }
 
+pub(crate) fn handle_document_link(
+    snap: Snapshot,
+    params: lsp_types::DocumentLinkParams,
+) -> Result<Option<Vec<lsp_types::DocumentLink>>> {
+    let _p = tracing::info_span!("handle_document_link").entered();
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let links = snap.analysis.document_links(file_id)?;
+    let line_index = snap.analysis.line_index(file_id)?;
+    let res = links
+        .into_iter()
+        .map(|it| to_proto::document_link(&snap, &line_index, it))
+        .collect();
+    Ok(Some(res))
+}
+
 pub(crate) fn handle_document_highlight(
     snap: Snapshot,
     params: lsp_types::DocumentHighlightParams,
@@ -738,6 +960,137 @@ pub(crate) fn handle_call_hierarchy_outgoing(
     Ok(Some(res))
 }
 
+pub(crate) fn handle_module_dependents(
+    snap: Snapshot,
+    params: lsp_ext::ModuleDependenciesParams,
+) -> Result<Vec<lsp_ext::ModuleDependency>> {
+    let _p = tracing::info_span!("handle_module_dependents").entered();
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    snap.analysis
+        .module_dependents(file_id)?
+        .into_iter()
+        .map(|dependent| to_proto::module_dependency(&snap, dependent))
+        .collect()
+}
+
+pub(crate) fn handle_module_dependencies(
+    snap: Snapshot,
+    params: lsp_ext::ModuleDependenciesParams,
+) -> Result<Vec<lsp_ext::ModuleDependency>> {
+    let _p = tracing::info_span!("handle_module_dependencies").entered();
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    snap.analysis
+        .module_dependencies(file_id)?
+        .into_iter()
+        .map(|dependency| to_proto::module_dependency(&snap, dependency))
+        .collect()
+}
+
+pub(crate) fn handle_message_protocol(
+    snap: Snapshot,
+    params: lsp_ext::MessageProtocolParams,
+) -> Result<lsp_ext::MessageProtocolResult> {
+    let _p = tracing::info_span!("handle_message_protocol").entered();
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    let protocol = snap.analysis.message_protocol(file_id)?;
+    to_proto::message_protocol(&snap, file_id, protocol)
+}
+
+pub(crate) fn handle_view_syntax_tree(
+    snap: Snapshot,
+    params: lsp_ext::ViewSyntaxTreeParams,
+) -> Result<String> {
+    let _p = tracing::info_span!("handle_view_syntax_tree").entered();
+    let (file_id, range) = selection(&snap, params)?;
+    Ok(snap.analysis.syntax_tree(file_id, range)?)
+}
+
+pub(crate) fn handle_view_hir(
+    snap: Snapshot,
+    params: lsp_ext::ViewSyntaxTreeParams,
+) -> Result<String> {
+    let _p = tracing::info_span!("handle_view_hir").entered();
+    let (file_id, range) = selection(&snap, params)?;
+    Ok(snap.analysis.view_hir(file_id, range)?)
+}
+
+pub(crate) fn handle_view_include_path(
+    snap: Snapshot,
+    params: lsp_ext::ViewIncludePathParams,
+) -> Result<String> {
+    let _p = tracing::info_span!("handle_view_include_path").entered();
+    let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    Ok(snap.analysis.include_path(file_id)?)
+}
+
+/// The document of the params, and its selection unless it is empty.
+fn selection(
+    snap: &Snapshot,
+    params: lsp_ext::ViewSyntaxTreeParams,
+) -> Result<(FileId, Option<TextRange>)> {
+    let file_id = from_proto::file_id(snap, &params.text_document.uri)?;
+    let line_index = snap.analysis.line_index(file_id)?;
+    let range = params
+        .range
+        .and_then(|range| from_proto::safe_text_range(&line_index, range))
+        .filter(|range| !range.is_empty());
+    Ok((file_id, range))
+}
+
+pub(crate) fn handle_type_hierarchy_prepare(
+    snap: Snapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Result<Option<Vec<lsp_ext::TypeHierarchyItem>>> {
+    let _p = tracing::info_span!("handle_type_hierarchy_prepare").entered();
+    let origin = params.text_document.uri.clone();
+    let mut position = from_proto::file_position(&snap, params)?;
+    position.offset = snap
+        .analysis
+        .clamp_offset(position.file_id, position.offset)?;
+
+    let nav = match snap.analysis.type_hierarchy_prepare(position)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let item = to_proto::type_hierarchy_item(&snap, nav, origin)?;
+    Ok(Some(vec![item]))
+}
+
+pub(crate) fn handle_type_hierarchy_supertypes(
+    snap: Snapshot,
+    params: lsp_ext::TypeHierarchyParams,
+) -> Result<Option<Vec<lsp_ext::TypeHierarchyItem>>> {
+    let _p = tracing::info_span!("handle_type_hierarchy_supertypes").entered();
+    let item = params.item;
+    let file_id = from_proto::file_id(&snap, &item.uri)?;
+    let origin = item.data.unwrap_or(item.uri);
+    let res = snap
+        .analysis
+        .supertypes(file_id)?
+        .into_iter()
+        .map(|nav| to_proto::type_hierarchy_item(&snap, nav, origin.clone()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some(res))
+}
+
+pub(crate) fn handle_type_hierarchy_subtypes(
+    snap: Snapshot,
+    params: lsp_ext::TypeHierarchyParams,
+) -> Result<Option<Vec<lsp_ext::TypeHierarchyItem>>> {
+    let _p = tracing::info_span!("handle_type_hierarchy_subtypes").entered();
+    let item = params.item;
+    let file_id = from_proto::file_id(&snap, &item.uri)?;
+    let origin = item.data.unwrap_or(item.uri);
+    let from_file = from_proto::file_id(&snap, &origin)?;
+    let res = snap
+        .analysis
+        .subtypes(file_id, from_file)?
+        .into_iter()
+        .map(|nav| to_proto::type_hierarchy_item(&snap, nav, origin.clone()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Some(res))
+}
+
 pub(crate) fn handle_signature_help(
     snap: Snapshot,
     params: lsp_types::SignatureHelpParams,
@@ -771,12 +1124,17 @@ pub(crate) fn handle_semantic_tokens_full(
     let _p = tracing::info_span!("handle_semantic_tokens_full").entered();
 
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    if snap.is_syntax_only(file_id) {
+        return Ok(None);
+    }
     let text = snap.analysis.file_text(file_id)?;
     let line_index = snap.analysis.line_index(file_id)?;
 
-    let highlights = snap
-        .analysis
-        .highlight(file_id, snap.eqwalizer_types.get(&file_id).cloned())?;
+    let highlights = snap.analysis.highlight(
+        &snap.highlight_config(),
+        file_id,
+        snap.eqwalizer_types.get(&file_id).cloned(),
+    )?;
     let semantic_tokens = to_proto::semantic_tokens(&text, &line_index, highlights);
 
     // Unconditionally cache the tokens
@@ -794,12 +1152,17 @@ pub(crate) fn handle_semantic_tokens_full_delta(
     let _p = tracing::info_span!("handle_semantic_tokens_full_delta").entered();
 
     let file_id = from_proto::file_id(&snap, &params.text_document.uri)?;
+    if snap.is_syntax_only(file_id) {
+        return Ok(None);
+    }
     let text = snap.analysis.file_text(file_id)?;
     let line_index = snap.analysis.line_index(file_id)?;
 
-    let highlights = snap
-        .analysis
-        .highlight(file_id, snap.eqwalizer_types.get(&file_id).cloned())?;
+    let highlights = snap.analysis.highlight(
+        &snap.highlight_config(),
+        file_id,
+        snap.eqwalizer_types.get(&file_id).cloned(),
+    )?;
     let semantic_tokens = to_proto::semantic_tokens(&text, &line_index, highlights);
 
     let mut cache = snap.semantic_tokens_cache.lock();
@@ -826,12 +1189,17 @@ pub(crate) fn handle_semantic_tokens_range(
 
     let mut frange = from_proto::file_range(&snap, params.text_document, params.range)?;
     frange.range = snap.analysis.clamp_range(frange.file_id, frange.range)?;
+    if snap.is_syntax_only(frange.file_id) {
+        return Ok(None);
+    }
     let text = snap.analysis.file_text(frange.file_id)?;
     let line_index = snap.analysis.line_index(frange.file_id)?;
 
-    let highlights = snap
-        .analysis
-        .highlight_range(snap.eqwalizer_types.get(&frange.file_id).cloned(), frange)?;
+    let highlights = snap.analysis.highlight_range(
+        &snap.highlight_config(),
+        snap.eqwalizer_types.get(&frange.file_id).cloned(),
+        frange,
+    )?;
     let semantic_tokens = to_proto::semantic_tokens(&text, &line_index, highlights);
     Ok(Some(semantic_tokens.into()))
 }
@@ -908,7 +1276,12 @@ pub(crate) fn handle_inlay_hints(
     let inlay_hints_config = snap.config.inlay_hints();
     Ok(Some(
         snap.analysis
-            .inlay_hints(&inlay_hints_config, frange.file_id, Some(frange.range))?
+            .inlay_hints(
+                &inlay_hints_config,
+                frange.file_id,
+                Some(frange.range),
+                snap.eqwalizer_types.get(&frange.file_id).cloned(),
+            )?
             .into_iter()
             .map(|it| to_proto::inlay_hint(&snap, &line_index, it))
             .collect::<Cancellable<Vec<_>>>()?,
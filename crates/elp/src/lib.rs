@@ -25,6 +25,7 @@ use serde::de::DeserializeOwned;
 pub use server::setup::ServerSetup;
 
 pub mod arc_types;
+pub mod bug_report;
 pub mod build;
 pub mod cli;
 pub mod config;
@@ -185,6 +186,15 @@ mod tests {
                     }),
                 ],
             },
+            preferred_fixes: Default::default(),
+            injections: vec![],
+            must_use: vec![],
+            assert_must_use: false,
+            target_otp_release: None,
+            migrations: vec![],
+            module_template: None,
+            config_schemas: vec![],
+            denied_calls: vec![],
         };
         expect![[r#"
             enabled_lints = ["W0011"]
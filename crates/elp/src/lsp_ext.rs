@@ -59,7 +59,29 @@ pub struct ExpandMacroParams {
 #[serde(rename_all = "camelCase")]
 pub struct ExpandedMacro {
     pub name: String,
+    /// The macro fully expanded, recursively
     pub expansion: String,
+    /// The macro expanded one level, leaving the macros it uses as they
+    /// are
+    pub single_step: String,
+}
+
+// ---------------------------------------------------------------------
+
+pub enum EqwalizeFunctionErrors {}
+
+impl Request for EqwalizeFunctionErrors {
+    type Params = TextDocumentPositionParams;
+    type Result = Option<FunctionEqwalizerErrors>;
+    const METHOD: &'static str = "elp/eqwalizeFunctionErrors";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionEqwalizerErrors {
+    /// The function, as name/arity
+    pub name: String,
+    pub diagnostics: Vec<lsp_types::Diagnostic>,
 }
 
 // ---------------------------------------------------------------------
@@ -74,8 +96,13 @@ pub enum Status {
 }
 
 #[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct StatusParams {
     pub status: Status,
+    /// The time taken by each phase of the start of the server, sent once
+    /// it is loaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub startup_report: Option<String>,
 }
 
 impl Notification for StatusNotification {
@@ -94,6 +121,44 @@ impl Request for Ping {
 
 // ---------------------------------------------------------------------
 
+pub enum MemoryUsage {}
+impl Request for MemoryUsage {
+    type Params = ();
+    type Result = String;
+    const METHOD: &'static str = "elp/memoryUsage";
+}
+
+// ---------------------------------------------------------------------
+
+/// Sets the capacities of the LRU caches of expensive queries, over the
+/// ones from `.elp.toml`, returning the memory usage.
+pub enum SetLruCapacities {}
+impl Request for SetLruCapacities {
+    type Params = SetLruCapacitiesParams;
+    type Result = String;
+    const METHOD: &'static str = "elp/setLruCapacities";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetLruCapacitiesParams {
+    pub parse: Option<usize>,
+    pub def_map: Option<usize>,
+    pub eqwalizer_ast: Option<usize>,
+}
+
+// ---------------------------------------------------------------------
+
+/// Writes a bug report archive, returning its path.
+pub enum BugReport {}
+impl Request for BugReport {
+    type Params = ();
+    type Result = String;
+    const METHOD: &'static str = "elp/bugReport";
+}
+
+// ---------------------------------------------------------------------
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Runnable {
@@ -101,7 +166,7 @@ pub struct Runnable {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub location: Option<lsp_types::LocationLink>,
     pub kind: RunnableKind,
-    pub args: Buck2RunnableArgs,
+    pub args: RunnableArgs,
 }
 
 impl Runnable {
@@ -116,13 +181,13 @@ impl Runnable {
             label: "Buck2".to_string(),
             location,
             kind: RunnableKind::Buck2,
-            args: Buck2RunnableArgs {
+            args: RunnableArgs::Buck2(Buck2RunnableArgs {
                 workspace_root,
                 command: "test".to_string(),
                 args: runnable.buck2_test_args(target.clone(), coverage_enabled),
                 target,
                 id: runnable.id(),
-            },
+            }),
         }
     }
 
@@ -136,13 +201,47 @@ impl Runnable {
             label: "Buck2".to_string(),
             location,
             kind: RunnableKind::Buck2,
-            args: Buck2RunnableArgs {
+            args: RunnableArgs::Buck2(Buck2RunnableArgs {
                 workspace_root,
                 command: "run".to_string(),
                 args: runnable.buck2_run_args(target.clone()),
                 target,
                 id: runnable.id(),
-            },
+            }),
+        }
+    }
+
+    pub fn rebar3_test(
+        runnable: elp_ide::Runnable,
+        location: Option<lsp_types::LocationLink>,
+        workspace_root: PathBuf,
+    ) -> Self {
+        Self {
+            label: "Rebar3".to_string(),
+            location,
+            kind: RunnableKind::Rebar3,
+            args: RunnableArgs::Rebar3(Rebar3RunnableArgs {
+                workspace_root,
+                args: runnable.rebar3_test_args(),
+                id: runnable.id(),
+            }),
+        }
+    }
+
+    pub fn rebar3_debug(
+        runnable: elp_ide::Runnable,
+        location: Option<lsp_types::LocationLink>,
+        workspace_root: PathBuf,
+    ) -> Self {
+        Self {
+            label: "Rebar3".to_string(),
+            location,
+            kind: RunnableKind::Rebar3,
+            args: RunnableArgs::Rebar3(Rebar3RunnableArgs {
+                workspace_root,
+                args: runnable.rebar3_debug_args(),
+                id: runnable.id(),
+            }),
         }
     }
 }
@@ -151,6 +250,14 @@ impl Runnable {
 #[serde(rename_all = "lowercase")]
 pub enum RunnableKind {
     Buck2,
+    Rebar3,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum RunnableArgs {
+    Buck2(Buck2RunnableArgs),
+    Rebar3(Rebar3RunnableArgs),
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -162,6 +269,15 @@ pub struct Buck2RunnableArgs {
     pub target: String,
     pub id: String,
 }
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Rebar3RunnableArgs {
+    pub workspace_root: PathBuf,
+    /// The rebar3 task, `ct` or `eunit`, followed by its arguments
+    pub args: Vec<String>,
+    pub id: String,
+}
 pub enum ExternalDocs {}
 
 impl Request for ExternalDocs {
@@ -201,3 +317,228 @@ pub struct CommandLink {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tooltip: Option<String>,
 }
+
+// ---------------------------------------------------------------------
+
+// The type hierarchy of LSP v3.17, which lsp-types 0.93 predates. The
+// types of the hierarchy are behaviours and their callback modules.
+
+pub enum TypeHierarchyPrepare {}
+
+impl Request for TypeHierarchyPrepare {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = Option<Vec<TypeHierarchyItem>>;
+    const METHOD: &'static str = "textDocument/prepareTypeHierarchy";
+}
+
+pub enum TypeHierarchySupertypes {}
+
+impl Request for TypeHierarchySupertypes {
+    type Params = TypeHierarchyParams;
+    type Result = Option<Vec<TypeHierarchyItem>>;
+    const METHOD: &'static str = "typeHierarchy/supertypes";
+}
+
+pub enum TypeHierarchySubtypes {}
+
+impl Request for TypeHierarchySubtypes {
+    type Params = TypeHierarchyParams;
+    type Result = Option<Vec<TypeHierarchyItem>>;
+    const METHOD: &'static str = "typeHierarchy/subtypes";
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeHierarchyItem {
+    pub name: String,
+    pub kind: lsp_types::SymbolKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub uri: lsp_types::Url,
+    pub range: lsp_types::Range,
+    pub selection_range: lsp_types::Range,
+    /// The document the hierarchy was prepared from, whose project
+    /// the subtypes are looked up in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<lsp_types::Url>,
+}
+
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize)]
+pub struct TypeHierarchyParams {
+    pub item: TypeHierarchyItem,
+}
+
+// ---------------------------------------------------------------------
+
+/// `textDocument/references`, telling how each reference uses the item,
+/// and keeping the references of the given kinds only.
+pub enum References {}
+
+impl Request for References {
+    type Params = ReferencesParams;
+    type Result = Option<Vec<Reference>>;
+    const METHOD: &'static str = "elp/references";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferencesParams {
+    #[serde(flatten)]
+    pub text_document_position: TextDocumentPositionParams,
+    pub context: lsp_types::ReferenceContext,
+    /// The references of all kinds are returned when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kinds: Option<Vec<ReferenceKind>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReferenceKind {
+    Declaration,
+    Call,
+    Export,
+    FunCapture,
+    Spec,
+    Import,
+    Heuristic,
+    Doc,
+    Other,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Reference {
+    #[serde(flatten)]
+    pub location: lsp_types::Location,
+    pub kind: ReferenceKind,
+}
+
+// ---------------------------------------------------------------------
+
+/// The modules calling into the module of the document.
+pub enum ModuleDependents {}
+
+impl Request for ModuleDependents {
+    type Params = ModuleDependenciesParams;
+    type Result = Vec<ModuleDependency>;
+    const METHOD: &'static str = "elp/moduleDependents";
+}
+
+/// The modules called by the module of the document.
+pub enum ModuleDependencies {}
+
+impl Request for ModuleDependencies {
+    type Params = ModuleDependenciesParams;
+    type Result = Vec<ModuleDependency>;
+    const METHOD: &'static str = "elp/moduleDependencies";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleDependenciesParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleDependency {
+    pub name: String,
+    /// The number of calls between the two modules
+    pub count: usize,
+    /// The `-module` attribute of the module
+    pub location: lsp_types::Location,
+    /// The calls, in the calling module, to show as a peek list
+    pub calls: Vec<lsp_types::Location>,
+}
+
+// ---------------------------------------------------------------------
+
+/// The messages the module of the document receives and sends, to
+/// document the protocol of a process.
+pub enum MessageProtocol {}
+
+impl Request for MessageProtocol {
+    type Params = MessageProtocolParams;
+    type Result = MessageProtocolResult;
+    const METHOD: &'static str = "elp/messageProtocol";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageProtocolParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageProtocolResult {
+    pub received: Vec<ProtocolMessage>,
+    pub sent: Vec<ProtocolMessage>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolMessage {
+    pub kind: MessageKind,
+    /// The pattern matching the message, or the expression building it
+    pub text: String,
+    /// The function receiving or sending the message, as `name/arity`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    pub location: lsp_types::Location,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MessageKind {
+    Plain,
+    Info,
+    Cast,
+    Call,
+}
+
+// ---------------------------------------------------------------------
+
+/// The parse tree of the document, or of the node covering the range, to
+/// help understand how ELP parses some code, or report a bug.
+pub enum ViewSyntaxTree {}
+
+impl Request for ViewSyntaxTree {
+    type Params = ViewSyntaxTreeParams;
+    type Result = String;
+    const METHOD: &'static str = "elp/viewSyntaxTree";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewSyntaxTreeParams {
+    pub text_document: TextDocumentIdentifier,
+    /// The selection, the whole document when absent or empty
+    pub range: Option<lsp_types::Range>,
+}
+
+/// The HIR the forms of the document, or those intersecting the range,
+/// are lowered to, showing e.g. the expansion of their macros.
+pub enum ViewHir {}
+
+impl Request for ViewHir {
+    type Params = ViewSyntaxTreeParams;
+    type Result = String;
+    const METHOD: &'static str = "elp/viewHir";
+}
+
+/// The directories the includes of the document are searched in, in
+/// order, and what each include resolves to.
+pub enum ViewIncludePath {}
+
+impl Request for ViewIncludePath {
+    type Params = ViewIncludePathParams;
+    type Result = String;
+    const METHOD: &'static str = "elp/viewIncludePath";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewIncludePathParams {
+    pub text_document: TextDocumentIdentifier,
+}
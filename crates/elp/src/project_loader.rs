@@ -21,6 +21,9 @@ use fxhash::FxHashMap;
 
 pub struct ProjectLoader {
     pub(crate) project_roots: FxHashMap<AbsPathBuf, Option<ProjectManifest>>,
+    /// Analyze each file on its own, with OTP only, rather than looking
+    /// for the project it belongs to
+    pub(crate) single_file: bool,
     start: SystemTime,
     initialized: bool,
 }
@@ -37,6 +40,7 @@ impl ProjectLoader {
         let initialized = false;
         ProjectLoader {
             project_roots,
+            single_file: false,
             start,
             initialized,
         }
@@ -61,6 +65,14 @@ impl ProjectLoader {
         &mut self,
         path: &AbsPath,
     ) -> (ElpConfig, Result<ProjectManifest>, ProjectManifest) {
+        if self.single_file {
+            let manifest = ProjectManifest::single_file(path);
+            if let Some(parent) = path.parent() {
+                self.project_roots
+                    .insert(parent.to_path_buf(), Some(manifest.clone()));
+            }
+            return (ElpConfig::default(), Ok(manifest.clone()), manifest);
+        }
         let (config, manifest) = match ProjectManifest::discover(path) {
             Ok((config, manifest)) => (config, Ok(manifest)),
             Err(x) => (ElpConfig::default(), Err(x)),
@@ -14,6 +14,7 @@ use elp_ide::elp_ide_db::elp_base_db::AppType;
 use elp_ide::elp_ide_db::elp_base_db::FileSetConfig;
 use elp_ide::elp_ide_db::elp_base_db::ProjectApps;
 use elp_ide::elp_ide_db::elp_base_db::VfsPath;
+use elp_ide::elp_ide_db::elp_base_db::VIRTUAL_DOCUMENTS_ROOT;
 
 #[derive(Debug)]
 pub struct ProjectFolders {
@@ -24,31 +25,37 @@ pub struct ProjectFolders {
 
 impl ProjectFolders {
     pub fn new(project_apps: &ProjectApps) -> ProjectFolders {
-        let file_set_config = project_apps
-            .all_apps
-            .iter()
-            .fold(
-                FileSetConfig::builder(),
-                |mut builder, (_project_id, app)| {
-                    let mut file_sets: Vec<VfsPath> = app
-                        .abs_src_dirs
-                        .iter()
-                        .map(|src| VfsPath::from(src.clone()))
-                        .collect();
-                    let dir = VfsPath::from(app.dir.clone());
-                    file_sets.push(dir);
-                    builder.add_file_set(file_sets);
-                    builder
-                },
-            )
-            .build();
+        let mut file_set_config = project_apps.all_apps.iter().fold(
+            FileSetConfig::builder(),
+            |mut builder, (_project_id, app)| {
+                let mut file_sets: Vec<VfsPath> = app
+                    .abs_src_dirs
+                    .iter()
+                    .map(|src| VfsPath::from(src.clone()))
+                    .collect();
+                let dir = VfsPath::from(app.dir.clone());
+                file_sets.push(dir);
+                builder.add_file_set(file_sets);
+                builder
+            },
+        );
+        file_set_config.add_file_set(vec![VfsPath::new_virtual_path(
+            VIRTUAL_DOCUMENTS_ROOT.to_string(),
+        )]);
+        let file_set_config = file_set_config.build();
 
-        let load = project_apps
+        let mut load: Vec<_> = project_apps
             .all_apps
             .iter()
             .flat_map(|(_, app)| {
                 let dirs = loader::Directories {
-                    extensions: vec!["erl".to_string(), "hrl".to_string(), "escript".to_string()],
+                    extensions: vec![
+                        "erl".to_string(),
+                        "hrl".to_string(),
+                        "escript".to_string(),
+                        "yrl".to_string(),
+                        "xrl".to_string(),
+                    ],
                     include: app.all_source_dirs(),
                     exclude: vec![],
                 };
@@ -62,6 +69,15 @@ impl ProjectFolders {
                 }
             })
             .collect();
+        // The `sys.config` and other files of the `config` directory of a
+        // project are checked against the config schemas
+        load.extend(project_apps.projects.iter().map(|project| {
+            loader::Entry::Directories(loader::Directories {
+                extensions: vec!["config".to_string()],
+                include: vec![project.root().join("config")],
+                exclude: vec![],
+            })
+        }));
 
         let mut watch: Vec<_> = project_apps
             .all_apps
@@ -106,6 +122,10 @@ impl ProjectFolders {
                     glob_pattern: format!("{}/.elp_lint.toml", root),
                     kind,
                 },
+                lsp_types::FileSystemWatcher {
+                    glob_pattern: format!("{}/config/*.config", root),
+                    kind,
+                },
             ]);
         }
 
@@ -42,7 +42,7 @@ define_semantic_token_types![
     standard {
         // COMMENT,
         // DECORATOR,
-        // ENUM_MEMBER,
+        ENUM_MEMBER,
         // ENUM,
         FUNCTION,
         // INTERFACE,
@@ -53,7 +53,7 @@ define_semantic_token_types![
         // NUMBER,
         // OPERATOR,
         // PARAMETER,
-        // PROPERTY,
+        PROPERTY,
         STRING,
         STRUCT,
         TYPE_PARAMETER,
@@ -88,12 +88,15 @@ macro_rules! define_semantic_token_modifiers {
 
 define_semantic_token_modifiers![
     standard {
+        DEFAULT_LIBRARY,
     }
     custom {
         (BOUND, "bound"),
         (EXPORTED_FUNCTION, "exported_function"),
         (DEPRECATED_FUNCTION, "deprecated_function"),
         (TYPE_DYNAMIC, "type_dynamic"),
+        (INJECTED_SQL, "injected_sql"),
+        (INJECTED_JSON, "injected_json"),
     }
 ];
 
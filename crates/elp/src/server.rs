@@ -8,12 +8,14 @@
  */
 
 use core::str;
+use std::collections::VecDeque;
 use std::fmt;
 use std::mem;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 use always_assert::always;
 use anyhow::bail;
@@ -46,6 +48,7 @@ use elp_ide::elp_ide_db::elp_base_db::SourceRoot;
 use elp_ide::elp_ide_db::elp_base_db::SourceRootId;
 use elp_ide::elp_ide_db::elp_base_db::Vfs;
 use elp_ide::elp_ide_db::elp_base_db::VfsPath;
+use elp_ide::elp_ide_db::LruCapacities;
 use elp_ide::erlang_service::CompileOption;
 use elp_ide::Analysis;
 use elp_ide::AnalysisHost;
@@ -57,8 +60,10 @@ use elp_log::Logger;
 use elp_log::TimeIt;
 use elp_project_model::buck::BuckQueryConfig;
 use elp_project_model::ElpConfig;
+use elp_project_model::LruConfig;
 use elp_project_model::Project;
 use elp_project_model::ProjectManifest;
+use elp_project_model::ELP_CONFIG_FILE;
 use fxhash::FxHashMap;
 use fxhash::FxHashSet;
 use indexmap::map::Entry;
@@ -75,6 +80,7 @@ use lsp_types::request;
 use lsp_types::request::Request as _;
 use lsp_types::FileChangeType;
 use lsp_types::FileEvent;
+use lsp_types::Location;
 use lsp_types::ShowMessageParams;
 use lsp_types::Url;
 use parking_lot::Mutex;
@@ -83,13 +89,23 @@ use parking_lot::RwLockWriteGuard;
 use vfs::Change;
 
 use self::dispatch::RequestDispatcher;
+use self::metrics::MetricsSink;
+use self::metrics::NoopMetricsSink;
 use self::progress::ProgressBar;
 use self::progress::ProgressManager;
 use self::progress::ProgressTask;
 use self::progress::Spinner;
+use self::startup_report::StartupPhase;
+use self::startup_report::StartupReport;
+use self::test_runner::TestRun;
+use crate::config::layers;
+use crate::config::layers::ConfigLayer;
+use crate::config::layers::ConfigLayers;
 use crate::config::Config;
+use crate::config::SyntaxOnlyReason;
 use crate::convert;
 use crate::convert::ide_to_lsp_diagnostic;
+use crate::convert::ClientPaths;
 use crate::document::Document;
 use crate::handlers;
 use crate::line_endings::LineEndings;
@@ -102,12 +118,17 @@ use crate::reload::ProjectFolders;
 use crate::snapshot::SharedMap;
 use crate::snapshot::Snapshot;
 use crate::task_pool::TaskPool;
+use crate::to_proto;
 
 mod capabilities;
 mod dispatch;
 mod logger;
+pub mod metrics;
+mod new_module;
 mod progress;
 pub mod setup;
+mod startup_report;
+pub(crate) mod test_runner;
 
 const LOGGER_NAME: &str = "lsp";
 const ERLANG_SERVICE_SUPPORTED_EXTENSIONS: &[FileKind] = &[
@@ -116,6 +137,9 @@ const ERLANG_SERVICE_SUPPORTED_EXTENSIONS: &[FileKind] = &[
     FileKind::Header,
     FileKind::Escript,
 ];
+/// How many recently changed files to remember, for prioritising
+/// project-wide diagnostics.
+const RECENTLY_CHANGED_LIMIT: usize = 100;
 const SLOW_DURATION: Duration = Duration::from_millis(300);
 /// If the main loop exceeds this time, log the specific request causing the problem
 const TOO_SLOW_DURATION: Duration = Duration::from_millis(3000);
@@ -132,15 +156,16 @@ pub enum Task {
     Response(lsp_server::Response),
     ShowMessage(lsp_types::ShowMessageParams),
     FetchProject(Vec<Project>),
+    FetchOrgConfig(String, Result<serde_json::Value>),
     NativeDiagnostics(Vec<(FileId, LabeledDiagnostics)>),
     EqwalizerDiagnostics(
         Spinner,
         Vec<(FileId, Vec<diagnostics::Diagnostic>, Arc<Vec<(Pos, Type)>>)>,
     ),
-    EqwalizerProjectDiagnostics(
-        Spinner,
-        Vec<(ProjectId, Vec<(FileId, Vec<diagnostics::Diagnostic>)>)>,
-    ),
+    EqwalizerProjectDiagnosticsChunk(Vec<FileId>, Vec<(FileId, Vec<diagnostics::Diagnostic>)>),
+    /// End of a chunked project-wide run, with the files covered if it
+    /// was not cancelled.
+    EqwalizerProjectDiagnostics(Spinner, Option<FxHashSet<FileId>>),
     EdocDiagnostics(Spinner, Vec<(FileId, Vec<diagnostics::Diagnostic>)>),
     CommonTestDiagnostics(Spinner, Vec<(FileId, Vec<diagnostics::Diagnostic>)>),
     ErlangServiceDiagnostics(Vec<(FileId, LabeledDiagnostics)>),
@@ -150,6 +175,9 @@ pub enum Task {
     UpdateCache(Vec<FileId>),
     ScheduleEqwalizeAll(ProjectId),
     UpdateEqwalizeAll(ProgressBar, ProjectId, String, Vec<FileId>),
+    TestOutput(String),
+    /// End of the output of the test with the given id.
+    TestRun(Spinner, String),
 }
 
 impl fmt::Debug for Event {
@@ -173,7 +201,7 @@ impl fmt::Debug for Event {
 }
 
 type ReqHandler = fn(&mut Server, Response) -> Result<()>;
-type ReqQueue = lsp_server::ReqQueue<(String, TimeIt), ReqHandler>;
+type ReqQueue = lsp_server::ReqQueue<(String, Instant, TimeIt), ReqHandler>;
 
 #[derive(Debug)]
 pub enum Status {
@@ -241,12 +269,29 @@ pub struct Server {
     eqwalizer_project_diagnostics_requested: bool,
     edoc_diagnostics_requested: bool,
     ct_diagnostics_requested: bool,
+    recently_changed: VecDeque<FileId>,
     cache_scheduled: bool,
     eqwalize_all_scheduled: FxHashSet<ProjectId>,
     eqwalize_all_completed: bool,
     logger: Logger,
     include_generated: bool,
     compile_options: Vec<CompileOption>,
+    /// LRU capacities set with `elp/setLruCapacities`, over the ones of
+    /// the projects
+    lru_config: LruConfig,
+    startup_report: StartupReport,
+    /// Print the startup report on stderr once the server is loaded
+    print_startup_report: bool,
+    metrics: Arc<dyn MetricsSink>,
+    /// The files holding a layer of the configuration, with issues
+    /// published as diagnostics
+    config_issue_files: FxHashSet<Url>,
+    /// The tests run with `elp.runTest`, by id
+    test_runs: FxHashMap<String, TestRun>,
+    /// Whether a document restricted to syntax-level analysis was shown
+    /// to the user already
+    syntax_only_notified: bool,
+    client_paths: Arc<ClientPaths>,
 
     // Progress reporting
     vfs_config_version: u32,
@@ -295,6 +340,7 @@ impl Server {
             eqwalizer_project_diagnostics_requested: false,
             edoc_diagnostics_requested: false,
             ct_diagnostics_requested: false,
+            recently_changed: VecDeque::default(),
             cache_scheduled: false,
             eqwalize_all_scheduled: FxHashSet::default(),
             eqwalize_all_completed: false,
@@ -302,6 +348,14 @@ impl Server {
             vfs_config_version: 0,
             include_generated: true,
             compile_options: vec![],
+            lru_config: LruConfig::default(),
+            startup_report: StartupReport::default(),
+            print_startup_report: false,
+            metrics: Arc::new(NoopMetricsSink),
+            config_issue_files: FxHashSet::default(),
+            test_runs: FxHashMap::default(),
+            syntax_only_notified: false,
+            client_paths: Arc::new(ClientPaths::default()),
         };
 
         // Run config-based initialisation
@@ -309,6 +363,23 @@ impl Server {
         this
     }
 
+    pub fn print_startup_report(mut self, print: bool) -> Server {
+        self.print_startup_report = print;
+        self
+    }
+
+    pub fn single_file_mode(self, single_file: bool) -> Server {
+        self.project_loader.lock().single_file = single_file;
+        self
+    }
+
+    /// Report the usage of each feature to `metrics`, rather than to
+    /// nowhere.
+    pub fn metrics_sink(mut self, metrics: Arc<dyn MetricsSink>) -> Server {
+        self.metrics = metrics;
+        self
+    }
+
     pub fn snapshot(&self) -> Snapshot {
         Snapshot::new(
             Arc::clone(&self.config),
@@ -320,6 +391,7 @@ impl Server {
             Arc::clone(&self.mem_docs),
             Arc::clone(&self.line_ending_map),
             Arc::clone(&self.projects),
+            Arc::clone(&self.client_paths),
         )
     }
 
@@ -372,6 +444,13 @@ impl Server {
             )
         }
 
+        self.report_config_issues(&[
+            ConfigLayer::Org,
+            ConfigLayer::Project,
+            ConfigLayer::Workspace,
+        ]);
+        self.fetch_org_config();
+
         while let Some(event) = self.next_event() {
             if let Event::Lsp(lsp_server::Message::Notification(notif)) = &event {
                 if notif.method == notification::Exit::METHOD {
@@ -453,14 +532,24 @@ impl Server {
             Event::Task(task) => match task {
                 Task::Response(response) => self.send_response(response),
                 Task::FetchProject(projects) => self.fetch_project_completed(projects)?,
+                Task::FetchOrgConfig(url, settings) => {
+                    let mut config = Config::clone(&self.config);
+                    config.set_org_config(url, settings);
+                    self.update_configuration(config);
+                    self.report_config_issues(&[ConfigLayer::Org]);
+                }
                 Task::NativeDiagnostics(diags) => self.native_diagnostics_completed(diags),
                 Task::EqwalizerDiagnostics(spinner, diags_types) => {
                     spinner.end();
                     self.eqwalizer_diagnostics_completed(diags_types)
                 }
-                Task::EqwalizerProjectDiagnostics(spinner, diags) => {
+                Task::EqwalizerProjectDiagnosticsChunk(file_ids, diags) => {
+                    Arc::make_mut(&mut self.diagnostics)
+                        .set_eqwalizer_project_chunk(&file_ids, diags)
+                }
+                Task::EqwalizerProjectDiagnostics(spinner, visited) => {
                     spinner.end();
-                    self.eqwalizer_project_diagnostics_completed(diags)
+                    self.eqwalizer_project_diagnostics_completed(visited)
                 }
                 Task::EdocDiagnostics(spinner, diags) => {
                     spinner.end();
@@ -488,6 +577,16 @@ impl Server {
                 }
                 Task::ScheduleEqwalizeAll(project_id) => self.schedule_eqwalize_all(project_id),
                 Task::ShowMessage(params) => self.show_message(params),
+                Task::TestOutput(line) => self.send_notification::<notification::LogMessage>(
+                    lsp_types::LogMessageParams {
+                        typ: lsp_types::MessageType::LOG,
+                        message: line,
+                    },
+                ),
+                Task::TestRun(spinner, id) => {
+                    spinner.end();
+                    self.test_run_completed(id)
+                }
             },
             Event::Telemetry(message) => self.on_telemetry(message),
         }
@@ -526,15 +625,21 @@ impl Server {
 
             let snapshot = self.snapshot();
             for file_id in diagnostic_changes {
-                let url = file_id_to_url(&self.vfs.read(), file_id);
+                let url = file_id_to_url(&self.vfs.read(), &self.client_paths, file_id);
                 let line_index = snapshot.analysis.line_index(file_id)?;
                 let diagnostics = self
                     .diagnostics
                     .diagnostics_for(file_id)
                     .iter()
-                    .map(|d| ide_to_lsp_diagnostic(&line_index, &url, d))
+                    .map(|d| {
+                        ide_to_lsp_diagnostic(&line_index, &url, d, |file_range| {
+                            to_proto::location(&snapshot, file_range).ok()
+                        })
+                    })
                     .collect();
-                let version = convert::vfs_path(&url)
+                let version = self
+                    .client_paths
+                    .vfs_path(&url)
                     .map(|path| self.mem_docs.read().get(&path).cloned())
                     .unwrap_or_default()
                     .map(|doc_info| doc_info.version);
@@ -549,6 +654,8 @@ impl Server {
             }
         }
 
+        self.report_startup_if_completed();
+
         Ok(())
     }
 
@@ -586,19 +693,40 @@ impl Server {
                 this.analysis_host.request_cancellation();
                 Ok(())
             })?
+            .on_sync::<request::ExecuteCommand>(|this, params| this.execute_command(params))?
+            .on_sync::<lsp_ext::SetLruCapacities>(|this, params| {
+                let lru_config = LruConfig {
+                    parse: params.parse,
+                    def_map: params.def_map,
+                    eqwalizer_ast: params.eqwalizer_ast,
+                };
+                this.lru_config = lru_config.or(this.lru_config);
+                let db = this.analysis_host.raw_database_mut();
+                let current = db.lru_capacities();
+                db.update_lru_capacities(LruCapacities {
+                    parse: lru_config.parse.unwrap_or(current.parse),
+                    def_map: lru_config.def_map.unwrap_or(current.def_map),
+                    eqwalizer_ast: lru_config.eqwalizer_ast.unwrap_or(current.eqwalizer_ast),
+                });
+                Ok(db.memory_usage().to_string())
+            })?
             .on::<request::CodeActionRequest>(handlers::handle_code_action)
             .on::<request::CodeActionResolveRequest>(handlers::handle_code_action_resolve)
             .on::<request::GotoDefinition>(handlers::handle_goto_definition)
             .on::<request::GotoTypeDefinition>(handlers::handle_goto_type_definition)
             .on::<request::References>(handlers::handle_references)
+            .on::<lsp_ext::References>(handlers::handle_references_by_kind)
             .on::<request::Completion>(handlers::handle_completion)
             .on::<request::ResolveCompletionItem>(handlers::handle_completion_resolve)
             .on::<request::DocumentSymbolRequest>(handlers::handle_document_symbol)
             .on::<request::WorkspaceSymbol>(handlers::handle_workspace_symbol)
+            .on::<request::PrepareRenameRequest>(handlers::handle_prepare_rename)
             .on::<request::Rename>(handlers::handle_rename)
             .on::<lsp_ext::HoverRequest>(handlers::handle_hover)
             .on::<request::FoldingRangeRequest>(handlers::handle_folding_range)
+            .on::<request::DocumentLinkRequest>(handlers::handle_document_link)
             .on::<request::DocumentHighlightRequest>(handlers::handle_document_highlight)
+            .on::<request::LinkedEditingRange>(handlers::handle_linked_editing_range)
             .on::<lsp_types::request::CallHierarchyPrepare>(handlers::handle_call_hierarchy_prepare)
             .on::<lsp_types::request::CallHierarchyIncomingCalls>(
                 handlers::handle_call_hierarchy_incoming,
@@ -606,8 +734,12 @@ impl Server {
             .on::<lsp_types::request::CallHierarchyOutgoingCalls>(
                 handlers::handle_call_hierarchy_outgoing,
             )
+            .on::<lsp_ext::TypeHierarchyPrepare>(handlers::handle_type_hierarchy_prepare)
+            .on::<lsp_ext::TypeHierarchySupertypes>(handlers::handle_type_hierarchy_supertypes)
+            .on::<lsp_ext::TypeHierarchySubtypes>(handlers::handle_type_hierarchy_subtypes)
             .on::<request::SignatureHelpRequest>(handlers::handle_signature_help)
             .on::<request::SelectionRangeRequest>(handlers::handle_selection_range)
+            .on::<request::OnTypeFormatting>(handlers::handle_on_type_formatting)
             .on::<request::SemanticTokensFullRequest>(handlers::handle_semantic_tokens_full)
             .on::<request::SemanticTokensFullDeltaRequest>(
                 handlers::handle_semantic_tokens_full_delta,
@@ -617,7 +749,16 @@ impl Server {
             .on::<request::InlayHintRequest>(handlers::handle_inlay_hints)
             .on::<request::InlayHintResolveRequest>(handlers::handle_inlay_hints_resolve)
             .on::<lsp_ext::ExpandMacro>(handlers::handle_expand_macro)
+            .on::<lsp_ext::ModuleDependents>(handlers::handle_module_dependents)
+            .on::<lsp_ext::ModuleDependencies>(handlers::handle_module_dependencies)
+            .on::<lsp_ext::MessageProtocol>(handlers::handle_message_protocol)
+            .on::<lsp_ext::ViewSyntaxTree>(handlers::handle_view_syntax_tree)
+            .on::<lsp_ext::ViewHir>(handlers::handle_view_hir)
+            .on::<lsp_ext::ViewIncludePath>(handlers::handle_view_include_path)
+            .on::<lsp_ext::EqwalizeFunctionErrors>(handlers::handle_eqwalize_function_errors)
             .on::<lsp_ext::Ping>(handlers::pong)
+            .on::<lsp_ext::MemoryUsage>(handlers::handle_memory_usage)
+            .on::<lsp_ext::BugReport>(handlers::handle_bug_report)
             .on::<lsp_ext::ExternalDocs>(handlers::handle_external_docs)
             .finish();
 
@@ -638,9 +779,24 @@ impl Server {
                 }
                 this.edoc_diagnostics_requested = true;
                 this.ct_diagnostics_requested = true;
-                if let Ok(path) = convert::abs_path(&params.text_document.uri) {
-                    this.fetch_projects_if_needed(&path);
-                    let path = VfsPath::from(path);
+                if let Ok(path) = this.client_paths.open(&params.text_document.uri) {
+                    match path.as_path() {
+                        Some(path) => this.fetch_projects_if_needed(path),
+                        // A document without a file on disk only needs
+                        // OTP, which any project of the workspace brings.
+                        None if this.projects.is_empty() => {
+                            let root_path = this.config.root_path.clone();
+                            this.fetch_projects_if_needed(&root_path)
+                        }
+                        None => {}
+                    }
+                    if let Some(reason) = this
+                        .config
+                        .analysis_budget()
+                        .syntax_only_reason(&params.text_document.text)
+                    {
+                        this.syntax_only_opened(&params.text_document.uri, reason);
+                    }
                     let already_exists = this
                         .mem_docs
                         .write()
@@ -681,7 +837,7 @@ impl Server {
                 Ok(())
             })?
             .on::<notification::DidChangeTextDocument>(|this, params| {
-                if let Ok(path) = convert::vfs_path(&params.text_document.uri) {
+                if let Ok(path) = this.client_paths.vfs_path(&params.text_document.uri) {
                     let mut mem_docs = this.mem_docs.write();
                     let Some(DocumentData { version, data }) = mem_docs.get_mut(&path) else {
                         tracing::error!(?path, "unexpected DidChangeTextDocument");
@@ -707,7 +863,9 @@ impl Server {
                 let url = params.text_document.uri;
                 let analysis = this.snapshot().analysis;
                 let mut diagnostics = Vec::new();
-                if let Ok(path) = convert::vfs_path(&url) {
+                let path = this.client_paths.vfs_path(&url);
+                this.client_paths.close(&url);
+                if let Ok(path) = path {
                     if this.mem_docs.write().remove(&path).is_err() {
                         tracing::error!("orphan DidCloseTextDocument: {}", path);
                         log::error!("unexpected DidCloseTextDocument: {}", path);
@@ -728,7 +886,20 @@ impl Server {
                                     .diagnostics
                                     .project_diagnostics_for(file_id)
                                     .iter()
-                                    .map(|d| ide_to_lsp_diagnostic(&line_index, &url, d))
+                                    .map(|d| {
+                                        ide_to_lsp_diagnostic(&line_index, &url, d, |file_range| {
+                                            let other_index =
+                                                analysis.line_index(file_range.file_id).ok()?;
+                                            Some(Location::new(
+                                                file_id_to_url(
+                                                    &vfs,
+                                                    &this.client_paths,
+                                                    file_range.file_id,
+                                                ),
+                                                convert::range(&other_index, file_range.range),
+                                            ))
+                                        })
+                                    })
                                     .collect()
                             }
                         }
@@ -826,6 +997,7 @@ impl Server {
                 }
             } else {
                 assert_eq!(n_done, n_total);
+                self.startup_report.end(StartupPhase::VfsLoad);
                 self.transition(Status::Running);
                 self.schedule_compile_deps();
                 self.schedule_cache();
@@ -886,6 +1058,15 @@ impl Server {
 
         let raw_database = self.analysis_host.raw_database_mut();
 
+        // Saving a grammar or a generated module may change which
+        // generated modules are stale.
+        let generated_changed = changed_files.values().any(|file| {
+            matches!(
+                vfs.file_path(file.file_id).name_and_extension(),
+                Some((_, Some("yrl" | "xrl" | "erl")))
+            )
+        });
+
         // The writes to salsa as these changes are applied below will
         // trigger Cancellation any pending processing.  This makes
         // sure all calculations see a consistent view of the
@@ -893,6 +1074,7 @@ impl Server {
 
         for (_, file) in &changed_files {
             let file_exists = vfs.exists(file.file_id);
+            record_recently_changed(&mut self.recently_changed, file.file_id);
 
             if &file.change != &vfs::Change::Delete && file_exists {
                 // Temporary for T183487471
@@ -924,6 +1106,12 @@ impl Server {
                 .any(|file| file.is_created_or_deleted())
         {
             let sets = self.file_set_config.partition(&vfs);
+            let files = sets.iter().map(|set| set.len()).sum();
+            let lru_config = self
+                .projects
+                .iter()
+                .fold(self.lru_config, |config, project| config.or(project.lru));
+            raw_database.update_lru_capacities(LruCapacities::new(lru_config, files));
             for (idx, set) in sets.into_iter().enumerate() {
                 let root_id = SourceRootId(idx as u32);
                 for file_id in set.iter() {
@@ -935,6 +1123,14 @@ impl Server {
             self.reset_source_roots = false;
         }
 
+        if generated_changed {
+            let stale = diagnostics::stale_generated_files(
+                &*raw_database,
+                vfs.iter().map(|(file_id, _)| file_id),
+            );
+            raw_database.set_stale_generated_files(Arc::new(stale));
+        }
+
         true
     }
 
@@ -949,6 +1145,9 @@ impl Server {
 
     fn update_native_diagnostics(&mut self) {
         let opened_documents = self.opened_documents();
+        if !opened_documents.is_empty() {
+            self.startup_report.begin(StartupPhase::InitialDiagnostics);
+        }
         let snapshot = self.snapshot();
 
         let include_otp = self.config.enable_otp_diagnostics();
@@ -965,6 +1164,7 @@ impl Server {
     }
 
     fn native_diagnostics_completed(&mut self, diags: Vec<(FileId, LabeledDiagnostics)>) {
+        self.startup_report.end(StartupPhase::InitialDiagnostics);
         for (file_id, diagnostics) in diags {
             Arc::make_mut(&mut self.diagnostics).set_native(file_id, diagnostics);
         }
@@ -999,6 +1199,10 @@ impl Server {
         });
     }
 
+    /// Project-wide diagnostics are computed and published in chunks, so
+    /// that results for the files the user is most likely looking at
+    /// arrive first. A change to the database cancels the remaining
+    /// chunks, leaving the previous results in place for them.
     fn update_eqwalizer_project_diagnostics(&mut self) {
         if self.status != Status::Running || !self.eqwalize_all_completed {
             return;
@@ -1011,22 +1215,49 @@ impl Server {
             .progress
             .begin_spinner("EqWAlizing All (project-wide)".to_string());
         let max_tasks = self.config.eqwalizer().max_tasks;
+        let chunk_size = self.config.eqwalizer().chunk_size.max(1);
+        let opened_documents = self.opened_documents();
+        let recently_changed: Vec<FileId> = self.recently_changed.iter().copied().collect();
 
-        self.eqwalizer_pool.handle.spawn(move || {
-            let diagnostics = snapshot
-                .projects
-                .iter()
-                .enumerate()
-                .filter_map(|(id, _project)| {
-                    let project_id = ProjectId(id as u32);
-                    Some((
+        self.eqwalizer_pool.handle.spawn_with_sender(move |sender| {
+            let mut visited = FxHashSet::default();
+            let mut completed = true;
+            'projects: for (id, _project) in snapshot.projects.iter().enumerate() {
+                let project_id = ProjectId(id as u32);
+                let Some(file_ids) = snapshot.eqwalizer_project_files(project_id) else {
+                    completed = false;
+                    break;
+                };
+                let file_ids = prioritise_files(file_ids, &opened_documents, &recently_changed);
+                for chunk in file_ids.chunks(chunk_size) {
+                    match snapshot.eqwalizer_project_diagnostics(
                         project_id,
-                        snapshot.eqwalizer_project_diagnostics(project_id, max_tasks)?,
-                    ))
-                })
-                .collect();
-
-            Task::EqwalizerProjectDiagnostics(spinner, diagnostics)
+                        chunk.to_vec(),
+                        max_tasks,
+                    ) {
+                        Some(diagnostics) => {
+                            visited.extend(chunk.iter().copied());
+                            sender
+                                .send(Task::EqwalizerProjectDiagnosticsChunk(
+                                    chunk.to_vec(),
+                                    diagnostics,
+                                ))
+                                .unwrap();
+                        }
+                        None => {
+                            // Cancelled
+                            completed = false;
+                            break 'projects;
+                        }
+                    }
+                }
+            }
+            sender
+                .send(Task::EqwalizerProjectDiagnostics(
+                    spinner,
+                    completed.then_some(visited),
+                ))
+                .unwrap();
         });
     }
 
@@ -1093,12 +1324,11 @@ impl Server {
         }
     }
 
-    fn eqwalizer_project_diagnostics_completed(
-        &mut self,
-        diags: Vec<(ProjectId, Vec<(FileId, Vec<diagnostics::Diagnostic>)>)>,
-    ) {
-        for (_project_id, diagnostics) in diags {
-            Arc::make_mut(&mut self.diagnostics).set_eqwalizer_project(diagnostics);
+    fn eqwalizer_project_diagnostics_completed(&mut self, visited: Option<FxHashSet<FileId>>) {
+        // Only a run which went through all chunks knows which files
+        // no longer belong to any project.
+        if let Some(visited) = visited {
+            Arc::make_mut(&mut self.diagnostics).retain_eqwalizer_project(&visited);
         }
     }
 
@@ -1108,6 +1338,81 @@ impl Server {
         }
     }
 
+    fn execute_command(
+        &mut self,
+        params: lsp_types::ExecuteCommandParams,
+    ) -> Result<Option<serde_json::Value>> {
+        self.metrics.count(&params.command);
+        match params.command.as_str() {
+            test_runner::RUN_TEST_COMMAND => {
+                let Some(runnable) = params.arguments.into_iter().next() else {
+                    bail!("{} expects a runnable", test_runner::RUN_TEST_COMMAND);
+                };
+                let runnable: lsp_ext::Runnable = serde_json::from_value(runnable)?;
+                let id = test_runner::runnable_id(&runnable).clone();
+                if self.test_runs.contains_key(&id) {
+                    bail!("{} is already running", id);
+                }
+                let spinner = self
+                    .progress
+                    .begin_spinner(format!("ELP running {}", runnable.label));
+                match test_runner::spawn_test(
+                    &self.projects,
+                    &runnable,
+                    spinner,
+                    self.task_pool.handle.sender(),
+                ) {
+                    Ok(run) => {
+                        self.test_runs.insert(id, run);
+                    }
+                    Err(err) => self.show_message(ShowMessageParams {
+                        typ: lsp_types::MessageType::ERROR,
+                        message: format!("Could not run the test: {:#}", err),
+                    }),
+                }
+                Ok(None)
+            }
+            new_module::NEW_MODULE_COMMAND => {
+                let Some(uri) = params.arguments.into_iter().next() else {
+                    bail!(
+                        "{} expects the URI of the module",
+                        new_module::NEW_MODULE_COMMAND
+                    );
+                };
+                let uri: Url = serde_json::from_value(uri)?;
+                let template = self.lint_config.module_template.clone().unwrap_or_default();
+                let edit = new_module::new_module_edit(uri, &template)?;
+                self.send_request::<request::ApplyWorkspaceEdit>(
+                    lsp_types::ApplyWorkspaceEditParams {
+                        label: Some("New module".to_string()),
+                        edit,
+                    },
+                    |_, _| Ok(()),
+                );
+                Ok(None)
+            }
+            command => bail!("Unknown command {}", command),
+        }
+    }
+
+    fn test_run_completed(&mut self, id: String) {
+        let Some(run) = self.test_runs.remove(&id) else {
+            return;
+        };
+        let (typ, message) = match run.wait() {
+            Ok(true) => (lsp_types::MessageType::INFO, format!("{} passed", id)),
+            Ok(false) => (
+                lsp_types::MessageType::ERROR,
+                format!("{} failed, see the ELP output for details", id),
+            ),
+            Err(err) => (
+                lsp_types::MessageType::ERROR,
+                format!("Could not run the test: {:#}", err),
+            ),
+        };
+        self.show_message(ShowMessageParams { typ, message });
+    }
+
     fn ct_diagnostics_completed(&mut self, diags: Vec<(FileId, Vec<diagnostics::Diagnostic>)>) {
         for (file_id, diagnostics) in diags {
             Arc::make_mut(&mut self.diagnostics).set_ct(file_id, diagnostics);
@@ -1198,6 +1503,8 @@ impl Server {
             version: 0,
         };
         self.vfs_loader.handle.set_config(vfs_loader_config);
+        self.startup_report.end(StartupPhase::Discovery);
+        self.startup_report.begin(StartupPhase::VfsLoad);
 
         self.projects = Arc::new(projects);
         self.project_loader.lock().load_completed();
@@ -1238,6 +1545,7 @@ impl Server {
                             let mut config = Config::clone(&*this.config);
                             config.update(json.take());
                             this.update_configuration(config);
+                            this.report_config_issues(&[ConfigLayer::Workspace]);
                         }
                     }
                     (None, None) => {
@@ -1283,19 +1591,125 @@ impl Server {
             if self.config.server_status_notification() {
                 self.send_notification::<lsp_ext::StatusNotification>(lsp_ext::StatusParams {
                     status: self.status.as_lsp_status(),
+                    startup_report: None,
                 });
             }
         }
     }
 
+    fn report_startup_if_completed(&mut self) {
+        let has_opened_documents = self.mem_docs.read().iter().next().is_some();
+        if !self.startup_report.take_completed(has_opened_documents) {
+            return;
+        }
+        let report = self.startup_report.to_string();
+        log::info!("{}", report);
+        if self.print_startup_report {
+            eprint!("{}", report);
+        }
+        if self.config.server_status_notification() {
+            self.send_notification::<lsp_ext::StatusNotification>(lsp_ext::StatusParams {
+                status: self.status.as_lsp_status(),
+                startup_report: Some(report),
+            });
+        }
+    }
+
+    /// The issues of the files holding a layer of the configuration are
+    /// published as diagnostics of these files, replacing the ones
+    /// published before. The other issues of the given layers are shown.
+    fn report_config_issues(&mut self, layers: &[ConfigLayer]) {
+        let mut by_file: FxHashMap<Url, Vec<lsp_types::Diagnostic>> = FxHashMap::default();
+        let mut messages = Vec::new();
+        for issue in self.config.issues() {
+            match issue
+                .path
+                .as_deref()
+                .map(|path| self.client_paths.url_from_abs_path(path))
+            {
+                Some(url) => by_file.entry(url).or_default().push(lsp_types::Diagnostic {
+                    range: lsp_types::Range::default(),
+                    severity: Some(lsp_types::DiagnosticSeverity::WARNING),
+                    source: Some("elp".to_string()),
+                    message: format!("{} config: {}", issue.layer, issue.message),
+                    ..lsp_types::Diagnostic::default()
+                }),
+                None if layers.contains(&issue.layer) => messages.push(issue.to_string()),
+                None => {}
+            }
+        }
+        let published = by_file.keys().cloned().collect();
+        // Clear the files which no longer have any issue
+        for uri in mem::replace(&mut self.config_issue_files, published) {
+            by_file.entry(uri).or_default();
+        }
+        for (uri, diagnostics) in by_file {
+            self.send_notification::<notification::PublishDiagnostics>(
+                lsp_types::PublishDiagnosticsParams {
+                    uri,
+                    diagnostics,
+                    version: None,
+                },
+            );
+        }
+        if !messages.is_empty() {
+            self.show_message(ShowMessageParams {
+                typ: lsp_types::MessageType::WARNING,
+                message: format!("Invalid ELP configuration:\n{}", messages.join("\n")),
+            });
+        }
+    }
+
+    /// Fetches the org config given by URL as a task, so that the main
+    /// loop does not wait on the network.
+    fn fetch_org_config(&mut self) {
+        if let Some(url) = ConfigLayers::org_config_url() {
+            self.task_pool.handle.spawn_with_sender(move |sender| {
+                let settings = layers::fetch_org_config(&url);
+                sender.send(Task::FetchOrgConfig(url, settings)).unwrap();
+            });
+        }
+    }
+
+    /// Reads the `.elp.toml` layer of the configuration again, once the
+    /// file changed.
+    fn reload_project_layer(&mut self) {
+        let mut config = Config::clone(&self.config);
+        config.reload_project_layer();
+        self.update_configuration(config);
+        self.report_config_issues(&[ConfigLayer::Project]);
+    }
+
+    /// Logs each document restricted to syntax-level analysis, and only
+    /// shows the first one of the session, so that opening large or
+    /// generated files does not keep popping up messages.
+    fn syntax_only_opened(&mut self, uri: &Url, reason: SyntaxOnlyReason) {
+        let message = format!(
+            "Only syntax-level analysis is available for {}, since {}.",
+            uri, reason
+        );
+        self.send_notification::<notification::LogMessage>(lsp_types::LogMessageParams {
+            typ: lsp_types::MessageType::INFO,
+            message: message.clone(),
+        });
+        if !mem::replace(&mut self.syntax_only_notified, true) {
+            self.show_message(ShowMessageParams {
+                typ: lsp_types::MessageType::INFO,
+                message: format!("{} Other such files are listed in the ELP output.", message),
+            });
+        }
+    }
+
     fn show_message(&mut self, params: ShowMessageParams) {
         self.send_notification::<lsp_types::notification::ShowMessage>(params)
     }
 
     fn send_response(&mut self, response: Response) {
-        if let Some((method, request_timer)) = self.req_queue.incoming.complete(response.id.clone())
+        if let Some((method, received, request_timer)) =
+            self.req_queue.incoming.complete(response.id.clone())
         {
             log::debug!("response {}#{}: {:?}", method, response.id, response);
+            self.metrics.latency(&method, received.elapsed());
             // logs time to complete request
             drop(request_timer);
             self.send(response.into());
@@ -1344,9 +1758,10 @@ impl Server {
     }
 
     fn register_request(&mut self, request: &Request, received_timer: TimeIt) {
-        self.req_queue
-            .incoming
-            .register(request.id.clone(), (request.method.clone(), received_timer))
+        self.req_queue.incoming.register(
+            request.id.clone(),
+            (request.method.clone(), Instant::now(), received_timer),
+        )
     }
 
     fn reload_project(&mut self, paths: Vec<AbsPathBuf>) {
@@ -1406,7 +1821,7 @@ impl Server {
                 fallback.clone()
             }
         };
-        let mut project = Project::load(&manifest, elp_config.eqwalizer.clone(), query_config);
+        let mut project = Project::load(&manifest, &elp_config, query_config);
         if let Err(err) = &project {
             log::error!(
                 "Failed to load project for manifest {:?}, error: {:?}",
@@ -1415,7 +1830,7 @@ impl Server {
             );
             errors.push(err.to_string());
             if !fallback_used {
-                project = Project::load(&fallback, elp_config.eqwalizer, query_config);
+                project = Project::load(&fallback, &elp_config, query_config);
                 if let Err(err) = &project {
                     log::error!(
                         "Failed to load project for fallback manifest {:?}, error: {:?}",
@@ -1437,6 +1852,7 @@ impl Server {
     }
 
     fn fetch_projects_if_needed(&mut self, path: &AbsPath) {
+        self.startup_report.begin(StartupPhase::Discovery);
         let path = path.to_path_buf();
         let loader = self.project_loader.clone();
         let query_config = self.config.buck_query();
@@ -1493,6 +1909,7 @@ impl Server {
         if self.cache_scheduled {
             return;
         }
+        self.startup_report.begin(StartupPhase::Indexing);
         let snapshot = self.snapshot();
 
         self.cache_pool.handle.spawn_with_sender(move |sender| {
@@ -1518,6 +1935,7 @@ impl Server {
     fn update_cache(&mut self, mut files: Vec<FileId>) {
         if files.is_empty() {
             self.cache_scheduled = true;
+            self.startup_report.end(StartupPhase::Indexing);
             if self.config.eqwalizer().all {
                 for (i, _) in self.snapshot().projects.iter().enumerate() {
                     let project_id = ProjectId(i as u32);
@@ -1686,15 +2104,21 @@ fn lsp_msg_for_context(message: &lsp_server::Message) -> String {
 fn process_changed_files(this: &mut Server, changes: &[FileEvent]) {
     let mut to_reload = vec![];
     let mut refresh_config = false;
+    let mut reload_project_layer = false;
     for change in changes {
-        if let Ok(path) = convert::abs_path(&change.uri) {
+        if let Ok(path) = this.client_paths.abs_path(&change.uri) {
             if this.should_reload_project_for_path(&path, change) {
                 to_reload.push(path.clone());
             }
+            if path == this.config.root_path.join(ELP_CONFIG_FILE) {
+                reload_project_layer = true;
+            }
             if this.should_reload_config_for_path(&path) {
                 refresh_config = true;
             }
-            let opened = convert::vfs_path(&change.uri)
+            let opened = this
+                .client_paths
+                .vfs_path(&change.uri)
                 .map(|vfs_path| this.mem_docs.read().contains(&vfs_path))
                 .unwrap_or(false);
             if !opened {
@@ -1703,6 +2127,9 @@ fn process_changed_files(this: &mut Server, changes: &[FileEvent]) {
         }
     }
     this.reload_project(to_reload);
+    if reload_project_layer {
+        this.reload_project_layer();
+    }
     if refresh_config {
         this.refresh_config();
     }
@@ -1714,6 +2141,31 @@ fn process_changed_files(this: &mut Server, changes: &[FileEvent]) {
     this.ct_diagnostics_requested = true;
 }
 
+fn record_recently_changed(recently_changed: &mut VecDeque<FileId>, file_id: FileId) {
+    recently_changed.retain(|id| *id != file_id);
+    recently_changed.push_front(file_id);
+    recently_changed.truncate(RECENTLY_CHANGED_LIMIT);
+}
+
+/// Order files so that open documents come first, then recently
+/// changed ones (most recent first), then everything else.
+fn prioritise_files(
+    mut file_ids: Vec<FileId>,
+    opened_documents: &[FileId],
+    recently_changed: &[FileId],
+) -> Vec<FileId> {
+    file_ids.sort_by_key(|file_id| {
+        if opened_documents.contains(file_id) {
+            (0, 0)
+        } else if let Some(pos) = recently_changed.iter().position(|id| id == file_id) {
+            (1, pos)
+        } else {
+            (2, 0)
+        }
+    });
+    file_ids
+}
+
 fn parse_id(id: lsp_types::NumberOrString) -> RequestId {
     match id {
         lsp_types::NumberOrString::Number(id) => id.into(),
@@ -1721,15 +2173,13 @@ fn parse_id(id: lsp_types::NumberOrString) -> RequestId {
     }
 }
 
-pub fn file_id_to_path(vfs: &Vfs, id: FileId) -> Result<AbsPathBuf> {
-    let url = file_id_to_url(vfs, id);
-    convert::abs_path(&url)
+pub fn file_id_to_path(vfs: &Vfs, client_paths: &ClientPaths, id: FileId) -> Result<AbsPathBuf> {
+    let url = file_id_to_url(vfs, client_paths, id);
+    client_paths.abs_path(&url)
 }
 
-pub fn file_id_to_url(vfs: &Vfs, id: FileId) -> Url {
-    let path = vfs.file_path(id);
-    let path = path.as_path().unwrap();
-    convert::url_from_abs_path(path)
+pub fn file_id_to_url(vfs: &Vfs, client_paths: &ClientPaths, id: FileId) -> Url {
+    client_paths.url_from_vfs_path(vfs.file_path(id))
 }
 
 pub fn is_supported_by_erlang_service(analysis: &Analysis, id: FileId) -> bool {
@@ -14,10 +14,14 @@ use lsp_types::CodeActionOptions;
 use lsp_types::CodeActionProviderCapability;
 use lsp_types::CodeLensOptions;
 use lsp_types::CompletionOptions;
+use lsp_types::DocumentLinkOptions;
+use lsp_types::DocumentOnTypeFormattingOptions;
+use lsp_types::ExecuteCommandOptions;
 use lsp_types::FoldingRangeProviderCapability;
 use lsp_types::HoverProviderCapability;
 use lsp_types::InlayHintOptions;
 use lsp_types::InlayHintServerCapabilities;
+use lsp_types::LinkedEditingRangeServerCapabilities;
 use lsp_types::OneOf;
 use lsp_types::RenameOptions;
 use lsp_types::SaveOptions;
@@ -33,6 +37,8 @@ use lsp_types::TextDocumentSyncOptions;
 use lsp_types::TypeDefinitionProviderCapability;
 use lsp_types::WorkDoneProgressOptions;
 
+use super::new_module;
+use super::test_runner;
 use crate::semantic_tokens;
 
 pub fn compute(client: &ClientCapabilities) -> ServerCapabilities {
@@ -80,18 +86,32 @@ pub fn compute(client: &ClientCapabilities) -> ServerCapabilities {
         }),
         document_formatting_provider: None,
         document_range_formatting_provider: None,
-        document_on_type_formatting_provider: None,
+        document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+            first_trigger_character: "\n".to_string(),
+            more_trigger_character: Some(vec![".".to_string(), "d".to_string()]),
+        }),
         rename_provider: Some(OneOf::Right(RenameOptions {
-            prepare_provider: Some(false),
+            prepare_provider: Some(true),
             work_done_progress_options: WorkDoneProgressOptions {
                 work_done_progress: None,
             },
         })),
-        document_link_provider: None,
+        document_link_provider: Some(DocumentLinkOptions {
+            resolve_provider: Some(false),
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
         color_provider: None,
         folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
         declaration_provider: None,
-        execute_command_provider: None,
+        execute_command_provider: Some(ExecuteCommandOptions {
+            commands: vec![
+                test_runner::RUN_TEST_COMMAND.to_string(),
+                new_module::NEW_MODULE_COMMAND.to_string(),
+            ],
+            work_done_progress_options: Default::default(),
+        }),
         workspace: None,
         call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
         semantic_tokens_provider: Some(
@@ -114,7 +134,7 @@ pub fn compute(client: &ClientCapabilities) -> ServerCapabilities {
                 resolve_provider: Some(true),
             },
         ))),
-        linked_editing_range_provider: None,
+        linked_editing_range_provider: Some(LinkedEditingRangeServerCapabilities::Simple(true)),
         experimental: None,
     }
 }
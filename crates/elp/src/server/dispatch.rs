@@ -122,6 +122,7 @@ impl<'a> RequestDispatcher<'a> {
             Some(req) if req.method == R::METHOD => self.req.take().unwrap(),
             _ => return None,
         };
+        self.server.metrics.count(R::METHOD);
 
         let res = crate::from_json(R::METHOD, req.params);
         match res {
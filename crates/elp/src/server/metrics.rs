@@ -0,0 +1,122 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Usage metrics of the server features, for the organizations measuring
+//! the adoption of ELP. Nothing is collected unless a sink is installed
+//! with `Server::metrics_sink`, and no backend is built in: the sink
+//! decides where the metrics go, the JSON lines one writing them to a
+//! local file.
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use serde::Serialize;
+
+/// Receives the usage of each feature, named after the LSP method or the
+/// command run, and how long the server took to answer.
+pub trait MetricsSink: Send + Sync {
+    /// The feature was used once.
+    fn count(&self, _feature: &str) {}
+
+    /// The feature was used, and answered in `duration`.
+    fn latency(&self, _feature: &str, _duration: Duration) {}
+}
+
+/// The default sink, dropping all the metrics.
+#[derive(Debug, Default)]
+pub struct NoopMetricsSink;
+
+impl MetricsSink for NoopMetricsSink {}
+
+/// Appends one JSON object per metric to a file.
+#[derive(Debug)]
+pub struct JsonLinesMetricsSink {
+    file: Mutex<File>,
+}
+
+#[derive(Debug, Serialize)]
+struct MetricLine<'a> {
+    /// Milliseconds since the UNIX epoch
+    timestamp: u128,
+    feature: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<f64>,
+}
+
+impl JsonLinesMetricsSink {
+    pub fn open(path: &Path) -> Result<JsonLinesMetricsSink> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(JsonLinesMetricsSink {
+            file: Mutex::new(file),
+        })
+    }
+
+    fn write(&self, feature: &str, duration: Option<Duration>) {
+        let line = MetricLine {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_millis()),
+            feature,
+            duration_ms: duration.map(|duration| duration.as_secs_f64() * 1000.0),
+        };
+        let mut file = self.file.lock();
+        let written = serde_json::to_writer(&mut *file, &line)
+            .map_err(anyhow::Error::from)
+            .and_then(|()| Ok(writeln!(file)?));
+        if let Err(err) = written {
+            log::warn!("Failed to write metric for {}: {}", feature, err);
+        }
+    }
+}
+
+impl MetricsSink for JsonLinesMetricsSink {
+    fn count(&self, feature: &str) {
+        self.write(feature, None);
+    }
+
+    fn latency(&self, feature: &str, duration: Duration) {
+        self.write(feature, Some(duration));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::Duration;
+
+    use super::JsonLinesMetricsSink;
+    use super::MetricsSink;
+
+    #[test]
+    fn json_lines_sink_appends_one_object_per_metric() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.jsonl");
+        let sink = JsonLinesMetricsSink::open(&path).unwrap();
+        sink.count("elp.runTest");
+        sink.latency("textDocument/hover", Duration::from_millis(12));
+
+        let lines: Vec<serde_json::Value> = fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["feature"], "elp.runTest");
+        assert!(lines[0].get("duration_ms").is_none());
+        assert_eq!(lines[1]["feature"], "textDocument/hover");
+        assert_eq!(lines[1]["duration_ms"], 12.0);
+    }
+}
@@ -0,0 +1,56 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Creates a module from the `module_template` of `.elp_lint.toml`. The
+//! client is asked to apply a workspace edit creating the file, so that
+//! the editor opens and can undo it like any other edit.
+
+use anyhow::bail;
+use anyhow::Result;
+use elp_ide::diagnostics::ModuleTemplate;
+use lsp_types::Url;
+
+pub(crate) const NEW_MODULE_COMMAND: &str = "elp.newModule";
+
+/// The edit creating the module at `uri`, named after the file.
+pub(crate) fn new_module_edit(
+    uri: Url,
+    template: &ModuleTemplate,
+) -> Result<lsp_types::WorkspaceEdit> {
+    let Ok(path) = uri.to_file_path() else {
+        bail!("{} is not a file", uri);
+    };
+    let module = match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(extension)) if extension == "erl" => stem.to_string_lossy(),
+        _ => bail!("{} is not an Erlang module", path.display()),
+    };
+    let create = lsp_types::ResourceOp::Create(lsp_types::CreateFile {
+        uri: uri.clone(),
+        options: Some(lsp_types::CreateFileOptions {
+            overwrite: Some(false),
+            ignore_if_exists: Some(false),
+        }),
+        annotation_id: None,
+    });
+    let contents = lsp_types::TextDocumentEdit {
+        text_document: lsp_types::OptionalVersionedTextDocumentIdentifier { uri, version: None },
+        edits: vec![lsp_types::OneOf::Left(lsp_types::TextEdit {
+            range: lsp_types::Range::default(),
+            new_text: template.new_module(&module),
+        })],
+    };
+    Ok(lsp_types::WorkspaceEdit {
+        changes: None,
+        document_changes: Some(lsp_types::DocumentChanges::Operations(vec![
+            lsp_types::DocumentChangeOperation::Op(create),
+            lsp_types::DocumentChangeOperation::Edit(contents),
+        ])),
+        change_annotations: None,
+    })
+}
@@ -84,6 +84,15 @@ impl Spinner {
         Self { token, sender }
     }
 
+    pub fn report(&self, message: String) {
+        let msg = WorkDoneProgress::Report(WorkDoneProgressReport {
+            cancellable: None,
+            message: Some(message),
+            percentage: None,
+        });
+        send_progress(&self.sender, self.token.clone(), msg);
+    }
+
     pub fn end(self) {
         // let Drop do the job
     }
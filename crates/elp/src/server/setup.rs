@@ -70,8 +70,12 @@ impl ServerSetup {
             offset_encoding: None,
         };
 
+        let mut result_json = serde_json::to_value(result.clone()).unwrap();
+        // lsp-types 0.93 has no field for the type hierarchy of LSP v3.17
+        result_json["capabilities"]["typeHierarchyProvider"] = serde_json::Value::Bool(true);
+
         self.connection
-            .initialize_finish(id, serde_json::to_value(result.clone()).unwrap())
+            .initialize_finish(id, result_json)
             .with_context(|| format!("during initialization finish: {:?}", result))?;
 
         let message = format!("ELP version: {}", crate::version());
@@ -98,6 +102,7 @@ impl ServerSetup {
         // anything.  If they match config, that is because we
         // choose this to be so in the client.
         let mut config = Config::new(root_path, params.capabilities);
+        config.load_layers();
         if let Some(options) = params.initialization_options {
             config.update(options);
         }
@@ -0,0 +1,179 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The phases of a cold start of the server, with the time at which each
+//! of them starts and ends. Only the first run of a phase is recorded,
+//! the ones following a reload of the project are not part of the start.
+
+use std::fmt;
+use std::time::Duration;
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupPhase {
+    /// Finding the manifest of the project and loading the project model
+    Discovery,
+    /// Loading the files of the project and of OTP into the VFS
+    VfsLoad,
+    /// Populating the caches of the modules of the projects, which
+    /// resolves the OTP modules they use
+    Indexing,
+    /// Computing the native diagnostics of the opened documents
+    InitialDiagnostics,
+}
+
+impl StartupPhase {
+    const ALL: [StartupPhase; 4] = [
+        StartupPhase::Discovery,
+        StartupPhase::VfsLoad,
+        StartupPhase::Indexing,
+        StartupPhase::InitialDiagnostics,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            StartupPhase::Discovery => "discovery",
+            StartupPhase::VfsLoad => "vfs load",
+            StartupPhase::Indexing => "indexing",
+            StartupPhase::InitialDiagnostics => "initial diagnostics",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Timing {
+    start: Option<Duration>,
+    end: Option<Duration>,
+}
+
+#[derive(Debug)]
+pub struct StartupReport {
+    started: Instant,
+    timings: [Timing; 4],
+    reported: bool,
+}
+
+impl Default for StartupReport {
+    fn default() -> Self {
+        StartupReport {
+            started: Instant::now(),
+            timings: Default::default(),
+            reported: false,
+        }
+    }
+}
+
+impl StartupReport {
+    pub fn begin(&mut self, phase: StartupPhase) {
+        let elapsed = self.started.elapsed();
+        let timing = self.timing(phase);
+        if timing.start.is_none() {
+            timing.start = Some(elapsed);
+        }
+    }
+
+    pub fn end(&mut self, phase: StartupPhase) {
+        let elapsed = self.started.elapsed();
+        let timing = self.timing(phase);
+        if timing.start.is_some() && timing.end.is_none() {
+            timing.end = Some(elapsed);
+        }
+    }
+
+    /// Whether all the phases ended, for the first time since the last
+    /// call. There are no diagnostics to compute without opened
+    /// documents, in which case the start is complete once indexed.
+    pub fn take_completed(&mut self, has_opened_documents: bool) -> bool {
+        if self.reported {
+            return false;
+        }
+        let completed = StartupPhase::ALL.iter().all(|phase| {
+            let timing = self.timings[*phase as usize];
+            timing.end.is_some()
+                || (*phase == StartupPhase::InitialDiagnostics
+                    && timing.start.is_none()
+                    && !has_opened_documents)
+        });
+        self.reported = completed;
+        completed
+    }
+
+    fn timing(&mut self, phase: StartupPhase) -> &mut Timing {
+        &mut self.timings[phase as usize]
+    }
+}
+
+impl fmt::Display for StartupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "ELP startup report")?;
+        let mut total = Duration::ZERO;
+        for phase in StartupPhase::ALL {
+            let timing = self.timings[phase as usize];
+            match (timing.start, timing.end) {
+                (Some(start), Some(end)) => {
+                    total = total.max(end);
+                    writeln!(
+                        f,
+                        "  {:<20} {:>9.3}s  (from {:.3}s to {:.3}s)",
+                        phase.label(),
+                        (end - start).as_secs_f64(),
+                        start.as_secs_f64(),
+                        end.as_secs_f64()
+                    )?;
+                }
+                (Some(start), None) => writeln!(
+                    f,
+                    "  {:<20} {:>10}  (from {:.3}s)",
+                    phase.label(),
+                    "running",
+                    start.as_secs_f64()
+                )?,
+                (None, _) => writeln!(f, "  {:<20} {:>10}", phase.label(), "skipped")?,
+            }
+        }
+        writeln!(f, "  {:<20} {:>9.3}s", "total", total.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StartupPhase;
+    use super::StartupReport;
+
+    #[test]
+    fn completed_once() {
+        let mut report = StartupReport::default();
+        report.begin(StartupPhase::Discovery);
+        report.end(StartupPhase::Discovery);
+        report.begin(StartupPhase::VfsLoad);
+        report.end(StartupPhase::VfsLoad);
+        report.begin(StartupPhase::Indexing);
+        assert!(!report.take_completed(false));
+        report.end(StartupPhase::Indexing);
+        assert!(report.take_completed(false));
+        assert!(!report.take_completed(false));
+    }
+
+    #[test]
+    fn waits_for_diagnostics_of_opened_documents() {
+        let mut report = StartupReport::default();
+        for phase in [
+            StartupPhase::Discovery,
+            StartupPhase::VfsLoad,
+            StartupPhase::Indexing,
+        ] {
+            report.begin(phase);
+            report.end(phase);
+        }
+        assert!(!report.take_completed(true));
+        report.begin(StartupPhase::InitialDiagnostics);
+        report.end(StartupPhase::InitialDiagnostics);
+        assert!(report.take_completed(true));
+    }
+}
@@ -0,0 +1,243 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Runs the tests the code lenses point at, in the server, for the clients
+//! which do not run them on their own. The command line comes from the
+//! project the test belongs to, so that it uses the same rebar3 profile or
+//! buck2 isolation directory as the rest of ELP.
+
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::process::Stdio;
+
+use anyhow::bail;
+use anyhow::Context;
+use anyhow::Result;
+use crossbeam_channel::Sender;
+use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
+use elp_project_model::CommandProxy;
+use elp_project_model::Project;
+use elp_project_model::ProjectBuildData;
+use jod_thread::JoinHandle;
+use stdx::JodChild;
+
+use super::progress::Spinner;
+use super::Task;
+use crate::lsp_ext::Runnable;
+use crate::lsp_ext::RunnableArgs;
+
+pub(crate) const RUN_TEST_COMMAND: &str = "elp.runTest";
+
+/// A test running in a child process of the server. Its output is sent
+/// line by line as `Task::TestOutput`, then `Task::TestRun` once the
+/// process closed it. Dropping the run kills the process.
+pub(crate) struct TestRun {
+    child: JodChild,
+    _reader: JoinHandle,
+}
+
+impl TestRun {
+    /// Whether the test passed, once the process closed its output.
+    pub(crate) fn wait(mut self) -> Result<bool> {
+        let status = self.child.wait().context("Failed to wait for the test")?;
+        Ok(status.success())
+    }
+}
+
+pub(crate) fn runnable_id(runnable: &Runnable) -> &String {
+    match &runnable.args {
+        RunnableArgs::Buck2(args) => &args.id,
+        RunnableArgs::Rebar3(args) => &args.id,
+    }
+}
+
+pub(crate) fn spawn_test(
+    projects: &[Project],
+    runnable: &Runnable,
+    spinner: Spinner,
+    sender: Sender<Task>,
+) -> Result<TestRun> {
+    let id = runnable_id(runnable).clone();
+    let mut cmd = test_command(projects, runnable)?;
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = cmd
+        .spawn()
+        .with_context(|| format!("Failed to run {}", runnable.label))?;
+    let stdout = child
+        .stdout
+        .take()
+        .context("failed to get stdout for the test process")?;
+    let stderr = child
+        .stderr
+        .take()
+        .context("failed to get stderr for the test process")?;
+    let reader = jod_thread::spawn(move || {
+        let errors = jod_thread::spawn({
+            let sender = sender.clone();
+            move || forward_output(stderr, &sender, None)
+        });
+        forward_output(stdout, &sender, Some(&spinner));
+        // Joins the stderr reader, so its lines come before the outcome
+        drop(errors);
+        // The server may be gone already when shutting down
+        let _ = sender.send(Task::TestRun(spinner, id));
+    });
+    Ok(TestRun {
+        child: JodChild(child),
+        _reader: reader,
+    })
+}
+
+fn forward_output(output: impl Read, sender: &Sender<Task>, spinner: Option<&Spinner>) {
+    for line in BufReader::new(output).lines().map_while(|line| line.ok()) {
+        if let Some(spinner) = spinner {
+            spinner.report(line.clone());
+        }
+        if sender.send(Task::TestOutput(line)).is_err() {
+            return;
+        }
+    }
+}
+
+/// The command running the test, built by the project at the workspace
+/// root of the runnable.
+fn test_command<'a>(projects: &'a [Project], runnable: &Runnable) -> Result<CommandProxy<'a>> {
+    let workspace_root = match &runnable.args {
+        RunnableArgs::Buck2(args) => &args.workspace_root,
+        RunnableArgs::Rebar3(args) => &args.workspace_root,
+    };
+    let workspace_root = AbsPathBuf::assert_utf8(workspace_root.clone());
+    let project = projects
+        .iter()
+        .find(|project| *project.root() == workspace_root)
+        .with_context(|| format!("No project at {}", workspace_root))?;
+    match (&project.project_build_data, &runnable.args) {
+        (ProjectBuildData::Rebar(rebar), RunnableArgs::Rebar3(args)) => {
+            let mut cmd = rebar.rebar3_command();
+            cmd.args(&args.args);
+            Ok(cmd)
+        }
+        (ProjectBuildData::Buck(buck), RunnableArgs::Buck2(args)) => {
+            let mut cmd = buck.buck_conf.buck_command();
+            cmd.arg(&args.command).args(&args.args);
+            Ok(cmd)
+        }
+        _ => bail!(
+            "{} cannot run tests of the project at {}",
+            runnable.label,
+            workspace_root
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
+    use elp_project_model::otp::Otp;
+    use elp_project_model::rebar::Profile;
+    use elp_project_model::rebar::RebarConfig;
+    use elp_project_model::rebar::RebarProject;
+    use elp_project_model::Project;
+    use elp_project_model::ProjectBuildData;
+    use expect_test::expect;
+
+    use super::test_command;
+    use crate::lsp_ext::Buck2RunnableArgs;
+    use crate::lsp_ext::Rebar3RunnableArgs;
+    use crate::lsp_ext::Runnable;
+    use crate::lsp_ext::RunnableArgs;
+    use crate::lsp_ext::RunnableKind;
+
+    fn rebar_project(root: &str) -> Project {
+        let root = AbsPathBuf::assert(root.into());
+        let otp = Otp {
+            lib_dir: AbsPathBuf::assert("/otp/lib".into()),
+            version: None,
+        };
+        let mut project = Project::otp(otp, vec![]);
+        let rebar_config = RebarConfig {
+            config_file: root.join("rebar.config"),
+            profile: Profile::default(),
+        };
+        project.project_build_data = ProjectBuildData::Rebar(RebarProject::new(root, rebar_config));
+        project
+    }
+
+    fn rebar3_runnable(workspace_root: &str, args: &[&str]) -> Runnable {
+        Runnable {
+            label: "Rebar3".to_string(),
+            location: None,
+            kind: RunnableKind::Rebar3,
+            args: RunnableArgs::Rebar3(Rebar3RunnableArgs {
+                workspace_root: PathBuf::from(workspace_root),
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+                id: "main_SUITE - .ok".to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn rebar3_command() {
+        let projects = vec![rebar_project("/other"), rebar_project("/ws")];
+        let runnable = rebar3_runnable("/ws", &["ct", "--suite=main_SUITE", "--case=ok"]);
+        let cmd = test_command(&projects, &runnable).unwrap();
+        expect![[r#"
+            (
+                "rebar3",
+                [
+                    "as",
+                    "test",
+                    "ct",
+                    "--suite=main_SUITE",
+                    "--case=ok",
+                ],
+                Some(
+                    "/ws",
+                ),
+            )
+        "#]]
+        .assert_debug_eq(&(
+            cmd.get_program(),
+            cmd.get_args().collect::<Vec<_>>(),
+            cmd.get_current_dir(),
+        ));
+    }
+
+    #[test]
+    fn no_project_at_workspace_root() {
+        let projects = vec![rebar_project("/ws")];
+        let runnable = rebar3_runnable("/elsewhere", &["eunit", "--test=main:ok_test"]);
+        let err = test_command(&projects, &runnable).err().unwrap();
+        expect!["No project at /elsewhere"].assert_eq(&err.to_string());
+    }
+
+    #[test]
+    fn runnable_of_another_build_system() {
+        let projects = vec![rebar_project("/ws")];
+        let runnable = Runnable {
+            label: "Buck2".to_string(),
+            location: None,
+            kind: RunnableKind::Buck2,
+            args: RunnableArgs::Buck2(Buck2RunnableArgs {
+                workspace_root: PathBuf::from("/ws"),
+                command: "test".to_string(),
+                args: vec![],
+                target: "//ws:main_SUITE".to_string(),
+                id: "main_SUITE - .ok".to_string(),
+            }),
+        };
+        let err = test_command(&projects, &runnable).err().unwrap();
+        expect!["Buck2 cannot run tests of the project at /ws"].assert_eq(&err.to_string());
+    }
+}
@@ -21,12 +21,14 @@ use elp_ide::diagnostics::LabeledDiagnostics;
 use elp_ide::diagnostics::RemoveElpReported;
 use elp_ide::diagnostics_collection::DiagnosticCollection;
 use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
+use elp_ide::elp_ide_db::elp_base_db::AnchoredPathBuf;
 use elp_ide::elp_ide_db::elp_base_db::FileId;
 use elp_ide::elp_ide_db::elp_base_db::FileKind;
 use elp_ide::elp_ide_db::elp_base_db::ProjectId;
 use elp_ide::elp_ide_db::elp_base_db::Vfs;
 use elp_ide::erlang_service;
 use elp_ide::Analysis;
+use elp_ide::HighlightConfig;
 use elp_log::timeit_with_telemetry;
 use elp_project_model::Project;
 use fxhash::FxHashMap;
@@ -39,7 +41,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::config::Config;
-use crate::convert;
+use crate::convert::ClientPaths;
 use crate::line_endings::LineEndings;
 use crate::mem_docs::MemDocs;
 use crate::server::file_id_to_path;
@@ -109,6 +111,7 @@ pub struct Snapshot {
     pub(crate) mem_docs: Arc<RwLock<MemDocs>>,
     line_ending_map: SharedMap<FileId, LineEndings>,
     pub(crate) projects: Arc<Vec<Project>>,
+    client_paths: Arc<ClientPaths>,
 }
 
 impl Snapshot {
@@ -122,6 +125,7 @@ impl Snapshot {
         mem_docs: Arc<RwLock<MemDocs>>,
         line_ending_map: Arc<RwLock<FxHashMap<FileId, LineEndings>>>,
         projects: Arc<Vec<Project>>,
+        client_paths: Arc<ClientPaths>,
     ) -> Self {
         Snapshot {
             config,
@@ -134,11 +138,12 @@ impl Snapshot {
             mem_docs,
             line_ending_map,
             projects,
+            client_paths,
         }
     }
 
     pub(crate) fn url_to_file_id(&self, url: &Url) -> Result<FileId> {
-        let path = convert::vfs_path(url)?;
+        let path = self.client_paths.vfs_path(url)?;
         let vfs = self.vfs.read();
         let res = vfs
             .file_id(&path)
@@ -147,15 +152,25 @@ impl Snapshot {
     }
 
     pub(crate) fn file_id_to_path(&self, id: FileId) -> Option<AbsPathBuf> {
-        file_id_to_path(&self.vfs.read(), id).ok()
+        file_id_to_path(&self.vfs.read(), &self.client_paths, id).ok()
     }
 
     pub(crate) fn file_id_to_url(&self, id: FileId) -> Url {
-        file_id_to_url(&self.vfs.read(), id)
+        file_id_to_url(&self.vfs.read(), &self.client_paths, id)
+    }
+
+    /// The URL of a path relative to the directory of its anchor file.
+    /// `None` when the anchor is a virtual document, which has no
+    /// directory on disk.
+    pub(crate) fn anchored_path(&self, path: &AnchoredPathBuf) -> Option<Url> {
+        let mut base = self.vfs.read().file_path(path.anchor).clone();
+        base.pop();
+        let path = base.join(&path.path)?;
+        Some(self.client_paths.url_from_abs_path(path.as_path()?))
     }
 
     pub(crate) fn url_file_version(&self, url: &Url) -> Option<i32> {
-        let path = convert::vfs_path(url).ok()?;
+        let path = self.client_paths.vfs_path(url).ok()?;
         self.mem_docs.read().get(&path).map(|v| v.version)
     }
 
@@ -196,6 +211,9 @@ impl Snapshot {
         let file_url = self.file_id_to_url(file_id);
         let _timer = timeit_with_telemetry!(TelemetryData::NativeDiagnostics { file_url });
 
+        if self.is_syntax_only(file_id) {
+            return self.analysis.syntax_only_diagnostics(file_id).ok();
+        }
         self.analysis
             .native_diagnostics(&self.diagnostics_config.clone(), &vec![], file_id)
             .ok()
@@ -206,7 +224,7 @@ impl Snapshot {
         file_id: FileId,
         include_otp: bool,
     ) -> Option<Vec<diagnostics::Diagnostic>> {
-        if !include_otp && self.is_otp(file_id) {
+        if (!include_otp && self.is_otp(file_id)) || self.is_syntax_only(file_id) {
             return None;
         }
 
@@ -217,24 +235,29 @@ impl Snapshot {
             .ok()?
     }
 
+    /// Files of the project that project-wide eqwalization covers.
+    /// Returns `None` if cancelled.
+    pub fn eqwalizer_project_files(&self, project_id: ProjectId) -> Option<Vec<FileId>> {
+        let module_index = self.analysis.module_index(project_id).ok()?;
+        let mut file_ids = Vec::new();
+        for (_, _, file_id) in module_index.iter_own() {
+            if self
+                .analysis
+                .should_eqwalize(file_id, IncludeGenerated::No)
+                .ok()?
+            {
+                file_ids.push(file_id);
+            }
+        }
+        Some(file_ids)
+    }
+
     pub fn eqwalizer_project_diagnostics(
         &self,
         project_id: ProjectId,
+        file_ids: Vec<FileId>,
         max_tasks: usize,
     ) -> Option<Vec<(FileId, Vec<diagnostics::Diagnostic>)>> {
-        let module_index = self.analysis.module_index(project_id).ok()?;
-
-        let file_ids: Vec<FileId> = module_index
-            .iter_own()
-            .filter_map(|(_, _, file_id)| {
-                if let Ok(true) = self.analysis.should_eqwalize(file_id, IncludeGenerated::No) {
-                    Some(file_id)
-                } else {
-                    None
-                }
-            })
-            .collect();
-
         log::info!(
             "Calculating eqwalizer diagnostics for {} files",
             file_ids.len()
@@ -258,7 +281,7 @@ impl Snapshot {
         file_id: FileId,
         include_otp: bool,
     ) -> Option<Arc<Vec<(Pos, Type)>>> {
-        if !include_otp && self.is_otp(file_id) {
+        if (!include_otp && self.is_otp(file_id)) || self.is_syntax_only(file_id) {
             return None;
         }
 
@@ -277,7 +300,7 @@ impl Snapshot {
             return None;
         }
 
-        if !include_otp && self.is_otp(file_id) {
+        if (!include_otp && self.is_otp(file_id)) || self.is_syntax_only(file_id) {
             return None;
         }
 
@@ -306,7 +329,7 @@ impl Snapshot {
             return None;
         }
 
-        if !config.include_otp && self.is_otp(file_id) {
+        if (!config.include_otp && self.is_otp(file_id)) || self.is_syntax_only(file_id) {
             return None;
         }
 
@@ -323,7 +346,7 @@ impl Snapshot {
         file_id: FileId,
         config: &DiagnosticsConfig,
     ) -> Option<Vec<(FileId, LabeledDiagnostics)>> {
-        if !config.include_otp && self.is_otp(file_id) {
+        if (!config.include_otp && self.is_otp(file_id)) || self.is_syntax_only(file_id) {
             return None;
         }
 
@@ -373,6 +396,39 @@ impl Snapshot {
         }
     }
 
+    /// Ids of the assists turned off in the `.elp.toml` of the project
+    /// of the file.
+    pub(crate) fn disabled_assists(&self, file_id: FileId) -> Vec<String> {
+        match self.analysis.project_id(file_id) {
+            Ok(Some(project_id)) => self
+                .get_project(project_id)
+                .map(|project| project.assists.disabled)
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// String literals passed to the functions configured in the
+    /// `injections` of `.elp_lint.toml` are highlighted as injected.
+    pub(crate) fn highlight_config(&self) -> HighlightConfig {
+        HighlightConfig {
+            injections: self.diagnostics_config.injections.clone(),
+        }
+    }
+
+    /// Whether the file is over the analysis budget, and so should only
+    /// get syntax-level treatment.
+    pub(crate) fn is_syntax_only(&self, file_id: FileId) -> bool {
+        match self.analysis.file_text(file_id) {
+            Ok(text) => self
+                .config
+                .analysis_budget()
+                .syntax_only_reason(&text)
+                .is_some(),
+            Err(_) => false,
+        }
+    }
+
     fn is_otp(&self, file_id: FileId) -> bool {
         match self.analysis.is_otp(file_id) {
             Ok(is_otp) => Some(true) == is_otp,
@@ -43,6 +43,11 @@ impl<T> TaskPool<T> {
         }
     }
 
+    /// For the tasks reporting from threads of their own.
+    pub fn sender(&self) -> Sender<T> {
+        self.sender.clone()
+    }
+
     pub fn spawn<F>(&mut self, task: F)
     where
         F: FnOnce() -> T + Send + 'static,
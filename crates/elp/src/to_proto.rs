@@ -9,9 +9,11 @@
 
 //! Conversion of rust-analyzer specific types to lsp_types equivalents.
 
+use std::collections::HashMap;
 use std::sync::atomic::AtomicU32;
 use std::sync::atomic::Ordering;
 
+use anyhow::Context;
 use elp_ide::elp_ide_assists::Assist;
 use elp_ide::elp_ide_assists::AssistKind;
 use elp_ide::elp_ide_completion::Completion;
@@ -23,13 +25,16 @@ use elp_ide::elp_ide_db::elp_base_db::FileId;
 use elp_ide::elp_ide_db::elp_base_db::FilePosition;
 use elp_ide::elp_ide_db::elp_base_db::FileRange;
 use elp_ide::elp_ide_db::rename::RenameError;
+use elp_ide::elp_ide_db::source_change::FileSystemEdit;
 use elp_ide::elp_ide_db::source_change::SourceChange;
 use elp_ide::elp_ide_db::LineIndex;
 use elp_ide::elp_ide_db::ReferenceCategory;
 use elp_ide::elp_ide_db::SymbolKind;
 use elp_ide::AnnotationKind;
+use elp_ide::AtomRename;
 use elp_ide::Cancellable;
 use elp_ide::DocLink;
+use elp_ide::DocumentLink;
 use elp_ide::FoldingRange;
 use elp_ide::FoldingRangeKind;
 use elp_ide::Highlight;
@@ -40,7 +45,11 @@ use elp_ide::HoverAction;
 use elp_ide::InlayHintLabel;
 use elp_ide::InlayHintLabelPart;
 use elp_ide::InlayKind;
+use elp_ide::MessageKind;
+use elp_ide::MessageProtocol;
+use elp_ide::ModuleDependency;
 use elp_ide::NavigationTarget;
+use elp_ide::ReferenceKind;
 use elp_ide::Runnable;
 use elp_ide::RunnableKind;
 use elp_ide::SignatureHelp;
@@ -86,11 +95,15 @@ pub(crate) fn symbol_kind(symbol_kind: SymbolKind) -> lsp_types::SymbolKind {
         SymbolKind::RecordField => lsp_types::SymbolKind::STRUCT,
         SymbolKind::Variable => lsp_types::SymbolKind::VARIABLE,
         SymbolKind::Callback => lsp_types::SymbolKind::FUNCTION,
+        SymbolKind::Behaviour => lsp_types::SymbolKind::INTERFACE,
     }
 }
 
-pub(crate) fn folding_range_kind(_kind: FoldingRangeKind) -> lsp_types::FoldingRangeKind {
-    lsp_types::FoldingRangeKind::Region
+pub(crate) fn folding_range_kind(kind: FoldingRangeKind) -> lsp_types::FoldingRangeKind {
+    match kind {
+        FoldingRangeKind::Comment => lsp_types::FoldingRangeKind::Comment,
+        _ => lsp_types::FoldingRangeKind::Region,
+    }
 }
 
 pub(crate) fn text_edit(
@@ -153,7 +166,20 @@ pub(crate) fn workspace_edit(
             edits: edit.edits.into_iter().map(From::from).collect(),
         });
     }
-    let document_changes = lsp_types::DocumentChanges::Edits(edits);
+    let document_changes = if source_change.file_system_edits.is_empty() {
+        lsp_types::DocumentChanges::Edits(edits)
+    } else {
+        // The edits refer to the files by their current name, so they
+        // are applied before the files are moved
+        let mut operations: Vec<lsp_types::DocumentChangeOperation> = edits
+            .into_iter()
+            .map(lsp_types::DocumentChangeOperation::Edit)
+            .collect();
+        for edit in source_change.file_system_edits {
+            operations.extend(document_change_operations(snap, edit)?);
+        }
+        lsp_types::DocumentChanges::Operations(operations)
+    };
     let workspace_edit = lsp_types::WorkspaceEdit {
         changes: None,
         document_changes: Some(document_changes),
@@ -162,6 +188,100 @@ pub(crate) fn workspace_edit(
     Ok(workspace_edit)
 }
 
+/// The edits of an atom rename, each annotated with the usage of the atom
+/// it renames, for the client to preview them grouped by usage.
+pub(crate) fn atom_rename_edit(
+    snap: &Snapshot,
+    rename: AtomRename,
+) -> Result<lsp_types::WorkspaceEdit> {
+    let mut annotations = HashMap::new();
+    let mut edits: Vec<lsp_types::TextDocumentEdit> = vec![];
+    let mut current = None;
+    for usage in rename.usages {
+        let file_id = usage.range.file_id;
+        if current.as_ref().map(|(current_id, _)| *current_id) != Some(file_id) {
+            edits.push(lsp_types::TextDocumentEdit {
+                text_document: optional_versioned_text_document_identifier(snap, file_id),
+                edits: vec![],
+            });
+            current = Some((file_id, snap.analysis.line_index(file_id)?));
+        }
+        let Some((_, line_index)) = &current else {
+            continue;
+        };
+        let label = usage.kind.label().to_string();
+        annotations
+            .entry(label.clone())
+            .or_insert_with(|| lsp_types::ChangeAnnotation {
+                label: label.clone(),
+                needs_confirmation: Some(true),
+                description: None,
+            });
+        let edit = lsp_types::AnnotatedTextEdit {
+            text_edit: lsp_types::TextEdit {
+                range: range(line_index, usage.range.range),
+                new_text: rename.new_name.clone(),
+            },
+            annotation_id: label,
+        };
+        if let Some(document_edit) = edits.last_mut() {
+            document_edit.edits.push(lsp_types::OneOf::Right(edit));
+        }
+    }
+    Ok(lsp_types::WorkspaceEdit {
+        changes: None,
+        document_changes: Some(lsp_types::DocumentChanges::Edits(edits)),
+        change_annotations: Some(annotations),
+    })
+}
+
+fn document_change_operations(
+    snap: &Snapshot,
+    edit: FileSystemEdit,
+) -> Result<Vec<lsp_types::DocumentChangeOperation>> {
+    let operations = match edit {
+        FileSystemEdit::CreateFile {
+            dst,
+            initial_contents,
+        } => {
+            let uri = snap
+                .anchored_path(&dst)
+                .context("cannot create a file next to a virtual document")?;
+            let create = lsp_types::ResourceOp::Create(lsp_types::CreateFile {
+                uri: uri.clone(),
+                options: None,
+                annotation_id: None,
+            });
+            let contents = lsp_types::TextDocumentEdit {
+                text_document: lsp_types::OptionalVersionedTextDocumentIdentifier {
+                    uri,
+                    version: None,
+                },
+                edits: vec![lsp_types::OneOf::Left(lsp_types::TextEdit {
+                    range: lsp_types::Range::default(),
+                    new_text: initial_contents,
+                })],
+            };
+            vec![
+                lsp_types::DocumentChangeOperation::Op(create),
+                lsp_types::DocumentChangeOperation::Edit(contents),
+            ]
+        }
+        FileSystemEdit::MoveFile { src, dst } => {
+            let rename = lsp_types::ResourceOp::Rename(lsp_types::RenameFile {
+                old_uri: snap.file_id_to_url(src),
+                new_uri: snap
+                    .anchored_path(&dst)
+                    .context("cannot move a virtual document")?,
+                options: None,
+                annotation_id: None,
+            });
+            vec![lsp_types::DocumentChangeOperation::Op(rename)]
+        }
+    };
+    Ok(operations)
+}
+
 pub(crate) fn code_action_kind(kind: AssistKind) -> lsp_types::CodeActionKind {
     match kind {
         AssistKind::None | AssistKind::Generate => lsp_types::CodeActionKind::EMPTY,
@@ -170,6 +290,7 @@ pub(crate) fn code_action_kind(kind: AssistKind) -> lsp_types::CodeActionKind {
         AssistKind::RefactorExtract => lsp_types::CodeActionKind::REFACTOR_EXTRACT,
         AssistKind::RefactorInline => lsp_types::CodeActionKind::REFACTOR_INLINE,
         AssistKind::RefactorRewrite => lsp_types::CodeActionKind::REFACTOR_REWRITE,
+        AssistKind::Source => lsp_types::CodeActionKind::SOURCE,
     }
 }
 
@@ -186,7 +307,7 @@ pub(crate) fn code_action(
         //     .map(|gr| gr.0),
         kind: Some(code_action_kind(assist.id.1)),
         edit: None,
-        is_preferred: None,
+        is_preferred: assist.is_preferred.then_some(true),
         data: None,
         diagnostics: None,
         command: None,
@@ -195,8 +316,13 @@ pub(crate) fn code_action(
     match (assist.source_change, resolve_data) {
         (Some(it), _) => res.edit = Some(workspace_edit(snap, it)?),
         (None, Some((index, code_action_params, user_input))) => {
+            let mut id = format!("{}:{}:{}", assist.id.0, assist.id.1.name(), index);
+            if let Some(subtype) = &assist.subtype {
+                id.push(':');
+                id.push_str(subtype);
+            }
             let data = lsp_ext::CodeActionData {
-                id: format!("{}:{}:{}", assist.id.0, assist.id.1.name(), index),
+                id,
                 code_action_params,
                 user_input,
             };
@@ -434,6 +560,19 @@ fn completion_item_data(snap: &Snapshot, pos: Option<FilePosition>) -> Option<Co
     }
 }
 
+pub(crate) fn document_link(
+    snap: &Snapshot,
+    line_index: &LineIndex,
+    link: DocumentLink,
+) -> lsp_types::DocumentLink {
+    lsp_types::DocumentLink {
+        range: range(line_index, link.range),
+        target: Some(url(snap, link.target)),
+        tooltip: None,
+        data: None,
+    }
+}
+
 pub(crate) fn folding_range(line_index: &LineIndex, fold: FoldingRange) -> lsp_types::FoldingRange {
     let kind = folding_range_kind(fold.kind);
     let range = range(line_index, fold.range);
@@ -468,6 +607,71 @@ pub(crate) fn call_hierarchy_item(
     })
 }
 
+pub(crate) fn module_dependency(
+    snap: &Snapshot,
+    dependency: ModuleDependency,
+) -> Result<lsp_ext::ModuleDependency> {
+    let name = dependency.module.name.to_string();
+    let calls = dependency
+        .calls
+        .into_iter()
+        .map(|call| location(snap, call))
+        .collect::<Cancellable<Vec<_>>>()?;
+    Ok(lsp_ext::ModuleDependency {
+        name,
+        count: calls.len(),
+        location: location_from_nav(snap, dependency.module)?,
+        calls,
+    })
+}
+
+pub(crate) fn message_protocol(
+    snap: &Snapshot,
+    file_id: FileId,
+    protocol: MessageProtocol,
+) -> Result<lsp_ext::MessageProtocolResult> {
+    let url = url(snap, file_id);
+    let line_index = snap.analysis.line_index(file_id)?;
+    let messages = |messages: Vec<elp_ide::Message>| {
+        messages
+            .into_iter()
+            .map(|message| lsp_ext::ProtocolMessage {
+                kind: match message.kind {
+                    MessageKind::Plain => lsp_ext::MessageKind::Plain,
+                    MessageKind::Info => lsp_ext::MessageKind::Info,
+                    MessageKind::Cast => lsp_ext::MessageKind::Cast,
+                    MessageKind::Call => lsp_ext::MessageKind::Call,
+                },
+                text: message.text,
+                function: message.function,
+                location: lsp_types::Location::new(url.clone(), range(&line_index, message.range)),
+            })
+            .collect()
+    };
+    Ok(lsp_ext::MessageProtocolResult {
+        received: messages(protocol.received),
+        sent: messages(protocol.sent),
+    })
+}
+
+pub(crate) fn type_hierarchy_item(
+    snap: &Snapshot,
+    target: NavigationTarget,
+    origin: lsp_types::Url,
+) -> Result<lsp_ext::TypeHierarchyItem> {
+    let name = target.name.to_string();
+    let (uri, range, selection_range) = location_info(snap, target)?;
+    Ok(lsp_ext::TypeHierarchyItem {
+        name,
+        kind: lsp_types::SymbolKind::MODULE,
+        detail: None,
+        uri,
+        range,
+        selection_range,
+        data: Some(origin),
+    })
+}
+
 pub(crate) fn signature_help(
     calls_info: Vec<SignatureHelp>,
     active_parameter: usize,
@@ -492,14 +696,23 @@ pub(crate) fn signature_help(
 
 pub(crate) fn signature_information(call_info: SignatureHelp) -> lsp_types::SignatureInformation {
     let label = call_info.signature.clone();
+    // Offsets rather than the text of the parameters, which is ambiguous
+    // when a spec has several parameters of the same type.
+    let utf16_offset =
+        |offset: TextSize| -> u32 { label[..usize::from(offset)].encode_utf16().count() as u32 };
     let parameters = call_info
         .parameter_labels()
-        .map(|label| lsp_types::ParameterInformation {
-            label: lsp_types::ParameterLabel::Simple(label.to_string()),
-            documentation: call_info.parameters_doc.get(label).map(|doc| {
+        .zip(call_info.parameter_ranges())
+        .enumerate()
+        .map(|(idx, (param, range))| lsp_types::ParameterInformation {
+            label: lsp_types::ParameterLabel::LabelOffsets([
+                utf16_offset(range.start()),
+                utf16_offset(range.end()),
+            ]),
+            documentation: call_info.parameter_doc(idx).map(|doc| {
                 lsp_types::Documentation::MarkupContent(lsp_types::MarkupContent {
                     kind: lsp_types::MarkupKind::Markdown,
-                    value: format!("`{}`: {}", label, doc.clone()),
+                    value: format!("`{}`: {}", param, doc.clone()),
                 })
             }),
         })
@@ -577,12 +790,15 @@ fn semantic_token_type_and_modifiers(
             SymbolKind::Module => semantic_tokens::NAMESPACE,
             SymbolKind::Function => semantic_tokens::FUNCTION,
             SymbolKind::Record => semantic_tokens::STRUCT,
-            SymbolKind::RecordField => semantic_tokens::STRUCT,
+            SymbolKind::RecordField => semantic_tokens::PROPERTY,
             SymbolKind::Type => semantic_tokens::TYPE_PARAMETER,
             SymbolKind::Define => semantic_tokens::MACRO,
             SymbolKind::Variable => semantic_tokens::VARIABLE,
             SymbolKind::Callback => semantic_tokens::FUNCTION,
+            SymbolKind::Behaviour => semantic_tokens::NAMESPACE,
         },
+        HlTag::Atom => semantic_tokens::ENUM_MEMBER,
+        HlTag::StringLiteral => semantic_tokens::STRING,
         HlTag::None => semantic_tokens::GENERIC,
     };
 
@@ -592,6 +808,9 @@ fn semantic_token_type_and_modifiers(
             HlMod::ExportedFunction => semantic_tokens::EXPORTED_FUNCTION,
             HlMod::DeprecatedFunction => semantic_tokens::DEPRECATED_FUNCTION,
             HlMod::TypeDynamic => semantic_tokens::TYPE_DYNAMIC,
+            HlMod::InjectedSql => semantic_tokens::INJECTED_SQL,
+            HlMod::InjectedJson => semantic_tokens::INJECTED_JSON,
+            HlMod::Bif => semantic_tokens::DEFAULT_LIBRARY,
         };
         mods |= modifier;
     }
@@ -608,6 +827,19 @@ pub(crate) fn document_highlight_kind(
     }
 }
 
+pub(crate) fn reference_kind(kind: ReferenceKind) -> lsp_ext::ReferenceKind {
+    match kind {
+        ReferenceKind::Call => lsp_ext::ReferenceKind::Call,
+        ReferenceKind::Export => lsp_ext::ReferenceKind::Export,
+        ReferenceKind::FunCapture => lsp_ext::ReferenceKind::FunCapture,
+        ReferenceKind::Spec => lsp_ext::ReferenceKind::Spec,
+        ReferenceKind::Import => lsp_ext::ReferenceKind::Import,
+        ReferenceKind::Heuristic => lsp_ext::ReferenceKind::Heuristic,
+        ReferenceKind::Doc => lsp_ext::ReferenceKind::Doc,
+        ReferenceKind::Other => lsp_ext::ReferenceKind::Other,
+    }
+}
+
 pub(crate) fn buck2_test_runnable(
     snap: &Snapshot,
     runnable: Runnable,
@@ -640,6 +872,18 @@ pub(crate) fn buck2_run_runnable(
     )
 }
 
+pub(crate) fn rebar3_test_runnable(snap: &Snapshot, runnable: Runnable) -> lsp_ext::Runnable {
+    let file_id = runnable.nav.file_id;
+    let location = location_link(snap, None, runnable.clone().nav).ok();
+    lsp_ext::Runnable::rebar3_test(runnable, location, snap.workspace_root(file_id).into())
+}
+
+pub(crate) fn rebar3_debug_runnable(snap: &Snapshot, runnable: Runnable) -> lsp_ext::Runnable {
+    let file_id = runnable.nav.file_id;
+    let location = location_link(snap, None, runnable.clone().nav).ok();
+    lsp_ext::Runnable::rebar3_debug(runnable, location, snap.workspace_root(file_id).into())
+}
+
 pub(crate) fn code_lens(
     acc: &mut Vec<lsp_types::CodeLens>,
     snap: &Snapshot,
@@ -678,7 +922,7 @@ pub(crate) fn code_lens(
                                         data: None,
                                     });
                                 }
-                                RunnableKind::Test { .. } => {
+                                RunnableKind::Test { .. } | RunnableKind::Eunit { .. } => {
                                     let run_command = command::run_interactive(
                                         &interactive_r,
                                         run_interactive_title,
@@ -710,6 +954,24 @@ pub(crate) fn code_lens(
                     }
                 }
             }
+            if let ProjectBuildData::Rebar(_) = project_build_data {
+                if lens_config.run {
+                    let r = rebar3_test_runnable(snap, run.clone());
+                    acc.push(lsp_types::CodeLens {
+                        range: annotation_range,
+                        command: Some(command::run_test(&r, run_title)),
+                        data: None,
+                    });
+                }
+                if lens_config.debug {
+                    let r = rebar3_debug_runnable(snap, run);
+                    acc.push(lsp_types::CodeLens {
+                        range: annotation_range,
+                        command: Some(command::debug_single(&r, debug_title)),
+                        data: None,
+                    });
+                }
+            }
         }
         AnnotationKind::Link(link) => {
             if lens_config.links {
@@ -731,6 +993,7 @@ pub(crate) mod command {
     use serde_json::to_value;
 
     use crate::lsp_ext;
+    use crate::server::test_runner;
 
     pub(crate) fn open_interactive(
         runnable: &lsp_ext::Runnable,
@@ -759,6 +1022,15 @@ pub(crate) mod command {
         }
     }
 
+    /// Run the test in the server, see `test_runner`.
+    pub(crate) fn run_test(runnable: &lsp_ext::Runnable, title: &str) -> lsp_types::Command {
+        lsp_types::Command {
+            title: title.to_string(),
+            command: test_runner::RUN_TEST_COMMAND.into(),
+            arguments: Some(vec![to_value(runnable).unwrap()]),
+        }
+    }
+
     pub(crate) fn debug_single(runnable: &lsp_ext::Runnable, title: &str) -> lsp_types::Command {
         lsp_types::Command {
             title: title.to_string(),
@@ -791,6 +1063,7 @@ pub(crate) fn inlay_hint(
 ) -> Cancellable<lsp_types::InlayHint> {
     match inlay_hint.kind {
         InlayKind::Parameter => inlay_hint.label.append_str(":"),
+        InlayKind::Type => inlay_hint.label.prepend_str(":: "),
     }
 
     let (label, tooltip) = inlay_hint_label(snap, inlay_hint.label)?;
@@ -800,16 +1073,19 @@ pub(crate) fn inlay_hint(
             // before annotated thing
             InlayKind::Parameter => position(line_index, inlay_hint.range.start()),
             // after annotated thing
-            // _ => position(line_index, inlay_hint.range.end()),
+            InlayKind::Type => position(line_index, inlay_hint.range.end()),
         },
         padding_left: Some(match inlay_hint.kind {
             InlayKind::Parameter => false,
+            InlayKind::Type => true,
         }),
         padding_right: Some(match inlay_hint.kind {
             InlayKind::Parameter => true,
+            InlayKind::Type => false,
         }),
         kind: match inlay_hint.kind {
             InlayKind::Parameter => Some(lsp_types::InlayHintKind::PARAMETER),
+            InlayKind::Type => Some(lsp_types::InlayHintKind::TYPE),
         },
         text_edits: None,
         data: None,
@@ -826,12 +1102,10 @@ fn inlay_hint_label(
     Option<lsp_types::InlayHintTooltip>,
 )> {
     let res = match &*label.parts {
-        [
-            InlayHintLabelPart {
-                linked_location: None,
-                ..
-            },
-        ] => {
+        [InlayHintLabelPart {
+            linked_location: None,
+            ..
+        }] => {
             let InlayHintLabelPart { text, tooltip, .. } = label.parts.pop().unwrap();
             (
                 lsp_types::InlayHintLabel::String(text),
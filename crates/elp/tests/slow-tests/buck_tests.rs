@@ -90,8 +90,7 @@ mod tests {
         let (elp_config, buck_config) =
             ProjectManifest::discover(&to_abs_path_buf(&path).unwrap()).unwrap();
 
-        let project =
-            Project::load(&buck_config, elp_config.eqwalizer, &BUCK_QUERY_CONFIG).unwrap();
+        let project = Project::load(&buck_config, &elp_config, &BUCK_QUERY_CONFIG).unwrap();
 
         let project_data: Vec<ProjectAppData> = project
             .non_otp_apps()
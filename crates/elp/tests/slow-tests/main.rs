@@ -30,6 +30,7 @@ use paths::Utf8Path;
 
 use crate::support::code_action_project;
 use crate::support::diagnostic_project;
+use crate::support::eqwalize_function_errors_project;
 
 #[test]
 fn test_run_mock_lsp() {
@@ -327,6 +328,51 @@ fn test_e2e_eqwalizer_module() {
     }
 }
 
+#[test]
+fn test_e2e_eqwalize_function_errors() {
+    if cfg!(feature = "buck") {
+        let workspace_root = AbsPathBuf::assert(
+            Utf8Path::new(env!("CARGO_WORKSPACE_DIR")).join("test_projects/standard"),
+        );
+
+        // Sanity check
+        assert!(std::fs::metadata(&workspace_root).is_ok());
+
+        // Only the type error of test2_neg/0 is returned, not the ones
+        // of the other functions of the module.
+        eqwalize_function_errors_project(
+            &workspace_root,
+            r"app_a/src/app_a.erl",
+            Position::new(12, 4),
+            expect![[r#"
+                {
+                  "diagnostics": [
+                    {
+                      "code": "incompatible_types",
+                      "codeDescription": {
+                        "href": "https://fb.me/eqwalizer_errors#incompatible_types"
+                      },
+                      "message": "`'error'`.\nExpression has type:   'error'\nContext expected type: 'ok'\n        See https://fb.me/eqwalizer_errors#incompatible_types",
+                      "range": {
+                        "end": {
+                          "character": 9,
+                          "line": 12
+                        },
+                        "start": {
+                          "character": 4,
+                          "line": 12
+                        }
+                      },
+                      "severity": 1,
+                      "source": "eqWAlizer"
+                    }
+                  ],
+                  "name": "test2_neg/0"
+                }"#]],
+        );
+    }
+}
+
 // This used to fail because of trigerring eqwalizer for non-modules
 // Now this fails with a timeout, since we never send down notifications
 // if there's no diagnostics for a file.
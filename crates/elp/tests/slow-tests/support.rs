@@ -19,6 +19,7 @@ use crossbeam_channel::after;
 use crossbeam_channel::select;
 use crossbeam_channel::Receiver;
 use elp::config::Config;
+use elp::lsp_ext::EqwalizeFunctionErrors;
 use elp::server::setup;
 use elp_ide::diagnostics::DiagnosticCode;
 use elp_ide::elp_ide_db::elp_base_db::AbsPathBuf;
@@ -37,9 +38,11 @@ use lsp_types::CodeActionContext;
 use lsp_types::CodeActionParams;
 use lsp_types::DidOpenTextDocumentParams;
 use lsp_types::PartialResultParams;
+use lsp_types::Position;
 use lsp_types::Range;
 use lsp_types::TextDocumentIdentifier;
 use lsp_types::TextDocumentItem;
+use lsp_types::TextDocumentPositionParams;
 use lsp_types::Url;
 use lsp_types::WorkDoneProgressParams;
 use paths::Utf8PathBuf;
@@ -94,6 +97,24 @@ impl Project {
         self.mock_lsp(workspace_root, module, action, expected_resp);
     }
 
+    pub(crate) fn check_eqwalize_function_errors(
+        self,
+        workspace_root: &AbsPathBuf,
+        module: &str,
+        position: Position,
+        expected_resp: Expect,
+    ) {
+        // Verify the eqwalizer diagnostics of the function at the position.
+
+        let action = |mock: TestServer, id: TextDocumentIdentifier| -> Value {
+            mock.send_request::<EqwalizeFunctionErrors>(TextDocumentPositionParams {
+                text_document: id,
+                position,
+            })
+        };
+        self.mock_lsp(workspace_root, module, action, expected_resp);
+    }
+
     fn mock_lsp<F: FnOnce(TestServer, TextDocumentIdentifier) -> Value>(
         self,
         workspace_root: &AbsPathBuf,
@@ -216,6 +237,15 @@ pub(crate) fn diagnostic_project(workspace_root: &AbsPathBuf, module: &str, expe
     Project::new().check_diagnostic(workspace_root, module, expected_resp);
 }
 
+pub(crate) fn eqwalize_function_errors_project(
+    workspace_root: &AbsPathBuf,
+    module: &str,
+    position: Position,
+    expected_resp: Expect,
+) {
+    Project::new().check_eqwalize_function_errors(workspace_root, module, position, expected_resp);
+}
+
 // Bridge between (test) Project and real Server.
 // It is called "Server" in rust analyser,
 // yet it's principally mocking the LSP client.
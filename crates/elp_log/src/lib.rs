@@ -24,9 +24,12 @@ use log::Record;
 use parking_lot::RwLock;
 
 mod file;
+mod recent;
 pub mod telemetry;
 
 pub use file::FileLogger;
+pub use recent::recent_logs;
+pub use recent::RecentLogger;
 use serde::Serialize;
 
 use crate::telemetry::send_with_duration;
@@ -0,0 +1,65 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::collections::VecDeque;
+
+use lazy_static::lazy_static;
+use log::LevelFilter;
+use log::Record;
+use parking_lot::Mutex;
+
+use crate::Builder;
+use crate::Filter;
+use crate::ReconfigureLog;
+
+/// How many log lines are kept in memory.
+const CAPACITY: usize = 1000;
+
+lazy_static! {
+    static ref RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::with_capacity(CAPACITY));
+}
+
+/// Keeps the last log lines in memory, whatever the log file is, so
+/// that they can be attached to a bug report.
+pub struct RecentLogger {
+    filter: Filter,
+}
+
+impl Default for RecentLogger {
+    fn default() -> Self {
+        let filter = Builder::new().filter_level(LevelFilter::Info).build();
+        Self { filter }
+    }
+}
+
+impl ReconfigureLog for RecentLogger {
+    fn filter(&self) -> &Filter {
+        &self.filter
+    }
+
+    fn reconfigure(&mut self, mut filter: Builder) {
+        self.filter = filter.build();
+    }
+
+    fn write(&self, record: &Record) {
+        let formatted = format!("[{} {}] {}", record.level(), record.target(), record.args());
+        let mut recent = RECENT.lock();
+        if recent.len() == CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(formatted);
+    }
+
+    fn flush(&self) {}
+}
+
+/// The last lines logged through a `RecentLogger`, oldest first.
+pub fn recent_logs() -> Vec<String> {
+    RECENT.lock().iter().cloned().collect()
+}
@@ -146,9 +146,15 @@ impl EqwalizerDiagnostics {
                         other
                     }
                 }
+                EqwalizerDiagnostics::Error(_) => other,
+                _ => self,
+            },
+            // Chunks are combined in any order when type-checked in
+            // parallel, keep the same error whatever the order
+            EqwalizerDiagnostics::Error(self_error) => match &other {
+                EqwalizerDiagnostics::Error(other_error) if other_error < self_error => other,
                 _ => self,
             },
-            EqwalizerDiagnostics::Error(_) => self,
             EqwalizerDiagnostics::Diagnostics { errors, type_info } => match other {
                 EqwalizerDiagnostics::Diagnostics {
                     errors: other_errors,
@@ -374,6 +374,9 @@ impl Connection {
         unwind: impl Fn(),
         handle_callback: impl Fn(Payload) -> Result<Vec<u8>>,
     ) -> Response {
+        let _p =
+            tracing::info_span!("erlang_service::request", tag = %String::from_utf8_lossy(tag))
+                .entered();
         let (sender, receiver) = bounded::<Response>(0);
         self.sender
             .send((tag, request, RequestType::Sender(sender)))
@@ -0,0 +1,55 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The modules of a project implementing each behaviour, as declared by
+//! their `-behaviour` attributes.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+use elp_base_db::ModuleName;
+use elp_base_db::ProjectId;
+use fxhash::FxHashMap;
+
+use crate::db::DefDatabase;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BehaviourIndex {
+    implementations: FxHashMap<ModuleName, Vec<FileId>>,
+}
+
+impl BehaviourIndex {
+    pub(crate) fn behaviour_index_query(
+        db: &dyn DefDatabase,
+        project_id: ProjectId,
+    ) -> Arc<BehaviourIndex> {
+        let mut implementations: FxHashMap<ModuleName, Vec<FileId>> = FxHashMap::default();
+        for (_name, _source, file_id) in db.module_index(project_id).iter_own() {
+            let form_list = db.file_form_list(file_id);
+            for (_, behaviour) in form_list.behaviour_attributes() {
+                implementations
+                    .entry(ModuleName::new(behaviour.name.as_str()))
+                    .or_default()
+                    .push(file_id);
+            }
+        }
+        for files in implementations.values_mut() {
+            files.sort();
+            files.dedup();
+        }
+        Arc::new(BehaviourIndex { implementations })
+    }
+
+    /// The modules declaring the given behaviour, in file order.
+    pub fn implementations(&self, behaviour: &ModuleName) -> &[FileId] {
+        self.implementations
+            .get(behaviour)
+            .map_or(&[], |files| files.as_slice())
+    }
+}
@@ -33,10 +33,12 @@ use crate::fold::MacroStrategy;
 use crate::fold::ParenStrategy;
 use crate::fold::ParentId;
 use crate::fold::VisibleMacros;
+use crate::known;
 use crate::AnyExprId;
 use crate::AnyExprRef;
 use crate::Attribute;
 use crate::AttributeId;
+use crate::CallTarget;
 use crate::Callback;
 use crate::CallbackId;
 use crate::Clause;
@@ -534,6 +536,15 @@ impl FunctionClauseBody {
         }
     }
 
+    /// A clause whose body only calls `erlang:nif_error/1,2`, as a
+    /// placeholder until the NIF library replaces it on load.
+    pub fn is_nif_stub(&self, db: &dyn DefDatabase) -> bool {
+        match self.clause.exprs.as_slice() {
+            [expr] => is_nif_error_call(&self.body, db, *expr),
+            _ => false,
+        }
+    }
+
     pub(crate) fn lower_clause_body(
         db: &dyn DefDatabase,
         clause_ast: &ast::FunctionClause,
@@ -599,6 +610,24 @@ impl FunctionClauseBody {
     }
 }
 
+fn is_nif_error_call(body: &Body, db: &dyn DefDatabase, expr: ExprId) -> bool {
+    match &body[expr] {
+        Expr::MacroCall { expansion, .. } => is_nif_error_call(body, db, *expansion),
+        Expr::Call {
+            target: CallTarget::Remote { module, name, .. },
+            args,
+        } if args.len() <= 2 => {
+            let is_atom = |id: ExprId, expected: Name| {
+                body[id]
+                    .as_atom()
+                    .map_or(false, |atom| db.lookup_atom(atom) == expected)
+            };
+            is_atom(*module, known::erlang) && is_atom(*name, known::nif_error)
+        }
+        _ => false,
+    }
+}
+
 impl TypeBody {
     pub(crate) fn type_body_with_source_query(
         db: &dyn DefDatabase,
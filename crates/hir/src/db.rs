@@ -11,12 +11,16 @@ use std::sync::Arc;
 
 use elp_base_db::salsa;
 use elp_base_db::FileId;
+use elp_base_db::ProjectId;
 use elp_base_db::SourceDatabase;
+use elp_base_db::SourceRootId;
 use elp_base_db::Upcast;
 use elp_syntax::ast;
 use elp_types_db::TypedSemantic;
 use fxhash::FxHashMap;
+use fxhash::FxHashSet;
 
+use crate::behaviour_index::BehaviourIndex;
 use crate::body::scope::ExprScopes;
 use crate::body::scope::FunctionScopes;
 use crate::body::DefineBody;
@@ -28,6 +32,12 @@ pub use crate::intern::InternDatabase;
 pub use crate::intern::InternDatabaseStorage;
 use crate::macro_exp;
 use crate::macro_exp::MacroResolution;
+use crate::message_tags::MessageTags;
+use crate::mnesia_tables::MnesiaTable;
+use crate::mnesia_tables::MnesiaTables;
+use crate::otp_deprecations::OtpDeprecations;
+use crate::route_index::Route;
+use crate::route_index::RouteIndex;
 use crate::AttributeBody;
 use crate::AttributeId;
 use crate::BodySourceMap;
@@ -43,6 +53,7 @@ use crate::InFile;
 use crate::InFileAstPtr;
 use crate::IncludeAttributeId;
 use crate::MacroName;
+use crate::Name;
 use crate::RecordBody;
 use crate::RecordId;
 use crate::ResolvedMacro;
@@ -82,7 +93,7 @@ pub trait DefDatabase:
 
     #[salsa::invoke(SpecBody::spec_body_with_source_query)]
     fn spec_body_with_source(&self, spec_id: InFile<SpecId>)
-    -> (Arc<SpecBody>, Arc<BodySourceMap>);
+        -> (Arc<SpecBody>, Arc<BodySourceMap>);
 
     #[salsa::invoke(SpecBody::callback_body_with_source_query)]
     fn callback_body_with_source(
@@ -159,6 +170,38 @@ pub trait DefDatabase:
     // if only local information changed
     #[salsa::invoke(DefMap::local_def_map_query)]
     fn local_def_map(&self, file_id: FileId) -> Arc<DefMap>;
+
+    /// Returns the modules of the project implementing each behaviour.
+    #[salsa::invoke(BehaviourIndex::behaviour_index_query)]
+    fn behaviour_index(&self, project_id: ProjectId) -> Arc<BehaviourIndex>;
+
+    /// Returns the routes of the routing tables built in the file.
+    #[salsa::invoke(RouteIndex::file_routes_query)]
+    fn file_routes(&self, file_id: FileId) -> Arc<Vec<Route>>;
+
+    /// Returns the routes of the project, by handler module.
+    #[salsa::invoke(RouteIndex::route_index_query)]
+    fn route_index(&self, project_id: ProjectId) -> Arc<RouteIndex>;
+
+    /// Returns the tags of the messages handled in the file.
+    #[salsa::invoke(MessageTags::file_message_tags_query)]
+    fn file_message_tags(&self, file_id: FileId) -> Arc<FxHashSet<Name>>;
+
+    /// Returns the tags of the messages handled in the project.
+    #[salsa::invoke(MessageTags::message_tags_query)]
+    fn message_tags(&self, project_id: ProjectId) -> Arc<MessageTags>;
+
+    /// Returns the mnesia tables created in the file.
+    #[salsa::invoke(MnesiaTables::file_mnesia_tables_query)]
+    fn file_mnesia_tables(&self, file_id: FileId) -> Arc<Vec<MnesiaTable>>;
+
+    /// Returns the mnesia tables created by the modules of the source root.
+    #[salsa::invoke(MnesiaTables::mnesia_tables_query)]
+    fn mnesia_tables(&self, source_root_id: SourceRootId) -> Arc<MnesiaTables>;
+
+    /// Returns the deprecated and removed functions of the project's OTP.
+    #[salsa::invoke(OtpDeprecations::otp_deprecations_query)]
+    fn otp_deprecations(&self, project_id: ProjectId) -> Arc<OtpDeprecations>;
 }
 
 fn function_body(db: &dyn DefDatabase, function_id: InFile<FunctionDefId>) -> Arc<FunctionBody> {
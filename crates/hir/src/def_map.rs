@@ -373,6 +373,11 @@ impl DefMap {
         &self.exported_functions
     }
 
+    /// Whether the module is compiled with `export_all`.
+    pub fn is_export_all(&self) -> bool {
+        self.export_all
+    }
+
     pub fn is_function_exported(&self, name: &NameArity) -> bool {
         self.exported_functions.contains(name)
     }
@@ -63,6 +63,14 @@ pub struct EdocComment {
 }
 
 impl EdocHeader {
+    pub fn form(&self) -> InFileAstPtr<ast::Form> {
+        self.form
+    }
+
+    pub fn tags(&self) -> &[EdocTag] {
+        &self.tags
+    }
+
     pub fn text_ranges(&self) -> Vec<TextRange> {
         self.tags.iter().flat_map(|tag| tag.text_ranges()).collect()
     }
@@ -96,6 +104,9 @@ impl EdocHeader {
 }
 
 impl EdocTag {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
     pub fn text_ranges(&self) -> Vec<TextRange> {
         self.comments.iter().map(|comment| comment.range).collect()
     }
@@ -158,13 +169,9 @@ fn edoc_from_comments(
                     name,
                     comments: vec![comment],
                 });
-            } else if !&acc.is_empty() {
-                let mut t = acc[0].clone();
-                t.comments.push(comment);
-                acc[0] = EdocTag {
-                    name: t.name,
-                    comments: t.comments,
-                }
+            } else if let Some(last) = acc.last_mut() {
+                // Untagged lines continue the most recent tag
+                last.comments.push(comment);
             }
             acc
         });
@@ -322,6 +329,28 @@ mod tests {
         )
     }
 
+    #[test]
+    fn edoc_continuation_lines() {
+        check(
+            r#"
+                %% @doc First line
+                %% second line
+                %% @param Foo first line
+                %% second line
+                foo(Foo) -> ok.
+"#,
+            expect![[r#"
+                SyntaxNodePtr { range: 74..89, kind: FUN_DECL }
+                  doc
+                    0..18: "%% @doc First line"
+                    19..33: "%% second line"
+                  param
+                    34..58: "%% @param Foo first line"
+                    59..73: "%% second line"
+            "#]],
+        )
+    }
+
     #[test]
     fn edoc_must_be_alone_on_line() {
         check(
@@ -11,6 +11,7 @@ use elp_base_db::FileId;
 use elp_base_db::SourceDatabase;
 use elp_syntax::ast;
 
+mod behaviour_index;
 mod body;
 pub mod db;
 mod def_map;
@@ -22,13 +23,18 @@ pub mod form_list;
 mod include;
 mod intern;
 mod macro_exp;
+mod message_tags;
+mod mnesia_tables;
 mod module_data;
 mod name;
+mod otp_deprecations;
 pub mod resolver;
+mod route_index;
 pub mod sema;
 #[cfg(test)]
 mod test_db;
 
+pub use behaviour_index::BehaviourIndex;
 pub use body::AnyAttribute;
 pub use body::AttributeBody;
 pub use body::Body;
@@ -123,6 +129,9 @@ pub use form_list::TypeExportId;
 pub use intern::Atom;
 pub use intern::Var;
 pub use macro_exp::ResolvedMacro;
+pub use message_tags::MessageTags;
+pub use mnesia_tables::MnesiaTable;
+pub use mnesia_tables::MnesiaTables;
 pub use module_data::CallbackDef;
 pub use module_data::DefineDef;
 pub use module_data::File;
@@ -142,6 +151,10 @@ pub use name::AsName;
 pub use name::MacroName;
 pub use name::Name;
 pub use name::NameArity;
+pub use otp_deprecations::OtpDeprecation;
+pub use otp_deprecations::OtpDeprecations;
+pub use route_index::Route;
+pub use route_index::RouteIndex;
 pub use sema::AtomDef;
 pub use sema::CallDef;
 pub use sema::DefinitionOrReference;
@@ -0,0 +1,122 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The tags of the messages a project handles: the atoms tagging the tuple
+//! patterns of receive clauses, and of the first argument of gen_server
+//! style handlers.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+use elp_base_db::ProjectId;
+use fxhash::FxHashSet;
+
+use crate::db::DefDatabase;
+use crate::fold::MacroStrategy;
+use crate::fold::ParenStrategy;
+use crate::AnyExpr;
+use crate::Body;
+use crate::Expr;
+use crate::FunctionDef;
+use crate::Literal;
+use crate::Name;
+use crate::Pat;
+use crate::PatId;
+use crate::Semantic;
+use crate::Strategy;
+
+const HANDLERS: &[&str] = &["handle_call", "handle_cast", "handle_info"];
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MessageTags {
+    tags: FxHashSet<Name>,
+}
+
+impl MessageTags {
+    /// Only combines the tags of each file, so that editing a file only
+    /// scans that file again.
+    pub(crate) fn message_tags_query(
+        db: &dyn DefDatabase,
+        project_id: ProjectId,
+    ) -> Arc<MessageTags> {
+        let mut tags = FxHashSet::default();
+        for &source_root_id in &db.project_data(project_id).source_roots {
+            for file_id in db.source_root(source_root_id).iter() {
+                if db.file_kind(file_id).is_module() {
+                    tags.extend(db.file_message_tags(file_id).iter().cloned());
+                }
+            }
+        }
+        Arc::new(MessageTags { tags })
+    }
+
+    pub(crate) fn file_message_tags_query(
+        db: &dyn DefDatabase,
+        file_id: FileId,
+    ) -> Arc<FxHashSet<Name>> {
+        let mut res = FxHashSet::default();
+        // Cheap filter, to avoid lowering every function in the project
+        let text = db.file_text(file_id);
+        if !text.contains("receive") && !text.contains("handle_") {
+            return Arc::new(res);
+        }
+        let sema = Semantic { db };
+        sema.for_each_function(file_id, |def| function_tags(&sema, def, &mut res));
+        Arc::new(res)
+    }
+
+    pub fn contains(&self, tag: &Name) -> bool {
+        self.tags.contains(tag)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Name> {
+        self.tags.iter()
+    }
+}
+
+fn function_tags(sema: &Semantic, def: &FunctionDef, acc: &mut FxHashSet<Name>) {
+    let def_fb = def.in_function_body(sema, def);
+    if HANDLERS.contains(&def.name.name().as_str()) {
+        for (_, clause) in def_fb.clauses() {
+            if let Some(pat) = clause.clause.pats.first() {
+                pat_tags(sema, &clause.body, *pat, acc);
+            }
+        }
+    }
+    def_fb.clone().fold_function(
+        Strategy {
+            macros: MacroStrategy::Expand,
+            parens: ParenStrategy::InvisibleParens,
+        },
+        (),
+        &mut |(), clause_id, ctx| {
+            if let AnyExpr::Expr(Expr::Receive { clauses, .. }) = ctx.item {
+                let body = def_fb.body(clause_id);
+                for clause in clauses {
+                    pat_tags(sema, &body, clause.pat, acc);
+                }
+            }
+        },
+    );
+}
+
+fn pat_tags(sema: &Semantic, body: &Body, pat: PatId, acc: &mut FxHashSet<Name>) {
+    match &body[pat] {
+        Pat::Tuple { pats } => {
+            if let Some(Pat::Literal(Literal::Atom(atom))) = pats.first().map(|tag| &body[*tag]) {
+                acc.insert(sema.db.lookup_atom(*atom));
+            }
+        }
+        Pat::Match { lhs, rhs } => {
+            pat_tags(sema, body, *lhs, acc);
+            pat_tags(sema, body, *rhs, acc);
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,163 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The mnesia tables of an application, as created by the
+//! `mnesia:create_table/2` calls of its modules.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+use elp_base_db::SourceRootId;
+use fxhash::FxHashMap;
+
+use crate::db::DefDatabase;
+use crate::fold::MacroStrategy;
+use crate::fold::ParenStrategy;
+use crate::AnyExpr;
+use crate::CallTarget;
+use crate::Expr;
+use crate::ExprId;
+use crate::FunctionDef;
+use crate::InFunctionClauseBody;
+use crate::Name;
+use crate::Semantic;
+use crate::Strategy;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MnesiaTable {
+    pub name: Name,
+    pub record_name: Name,
+    pub index: Vec<Name>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MnesiaTables {
+    tables: FxHashMap<Name, MnesiaTable>,
+}
+
+impl MnesiaTables {
+    /// Only combines the tables of each module, so that editing a module
+    /// only scans that module again.
+    pub(crate) fn mnesia_tables_query(
+        db: &dyn DefDatabase,
+        source_root_id: SourceRootId,
+    ) -> Arc<MnesiaTables> {
+        let mut tables = FxHashMap::default();
+        for file_id in db.source_root(source_root_id).iter() {
+            if db.file_kind(file_id).is_module() {
+                for table in db.file_mnesia_tables(file_id).iter() {
+                    tables.insert(table.name.clone(), table.clone());
+                }
+            }
+        }
+        Arc::new(MnesiaTables { tables })
+    }
+
+    pub(crate) fn file_mnesia_tables_query(
+        db: &dyn DefDatabase,
+        file_id: FileId,
+    ) -> Arc<Vec<MnesiaTable>> {
+        let mut res = Vec::new();
+        // Cheap filter, to avoid lowering every function in the app
+        if !db.file_text(file_id).contains("create_table") {
+            return Arc::new(res);
+        }
+        let sema = Semantic { db };
+        sema.def_map(file_id).get_functions().for_each(|(_, def)| {
+            tables_in_function(&sema, def, &mut res);
+        });
+        Arc::new(res)
+    }
+
+    pub fn get(&self, name: &Name) -> Option<&MnesiaTable> {
+        self.tables.get(name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+}
+
+fn tables_in_function(sema: &Semantic, def: &FunctionDef, acc: &mut Vec<MnesiaTable>) {
+    let def_fb = def.in_function_body(sema, def);
+    def_fb.fold_function(
+        Strategy {
+            macros: MacroStrategy::Expand,
+            parens: ParenStrategy::InvisibleParens,
+        },
+        (),
+        &mut |(), clause_id, ctx| {
+            if let AnyExpr::Expr(Expr::Call {
+                target: CallTarget::Remote { module, name, .. },
+                args,
+            }) = ctx.item
+            {
+                let in_clause = def_fb.in_clause(clause_id);
+                let is_create_table = args.len() == 2
+                    && in_clause
+                        .as_atom_name(&module)
+                        .is_some_and(|module| module.as_str() == "mnesia")
+                    && in_clause
+                        .as_atom_name(&name)
+                        .is_some_and(|name| name.as_str() == "create_table");
+                if is_create_table {
+                    acc.extend(table_schema(in_clause, &args));
+                }
+            }
+        },
+    );
+}
+
+/// Schema from a literal option list, e.g.
+/// `mnesia:create_table(people, [{record_name, person}, {index, [email]}])`
+fn table_schema(
+    in_clause: &InFunctionClauseBody<&FunctionDef>,
+    args: &[ExprId],
+) -> Option<MnesiaTable> {
+    let name = in_clause.as_atom_name(args.get(0)?)?;
+    let options = match &in_clause[*args.get(1)?] {
+        Expr::List { exprs, .. } => exprs.clone(),
+        _ => return None,
+    };
+    let mut table = MnesiaTable {
+        record_name: name.clone(),
+        name,
+        index: Vec::new(),
+    };
+    for option in options {
+        let (key, value) = match &in_clause[option] {
+            Expr::Tuple { exprs } if exprs.len() == 2 => (exprs[0], exprs[1]),
+            _ => continue,
+        };
+        let key = match in_clause.as_atom_name(&key) {
+            Some(key) => key,
+            None => continue,
+        };
+        match key.as_str() {
+            "record_name" => {
+                table.record_name = in_clause.as_atom_name(&value)?;
+            }
+            "index" => {
+                if let Expr::List { exprs, .. } = &in_clause[value] {
+                    table.index = exprs
+                        .iter()
+                        .filter_map(|expr| match &in_clause[*expr] {
+                            Expr::RecordIndex { field, .. } => {
+                                Some(in_clause.sema.db.lookup_atom(*field))
+                            }
+                            _ => in_clause.as_atom_name(expr),
+                        })
+                        .collect();
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(table)
+}
@@ -38,6 +38,7 @@ use crate::InFunctionClauseBody;
 use crate::ModuleAttribute;
 use crate::Name;
 use crate::NameArity;
+use crate::ParamName;
 use crate::Record;
 use crate::RecordField;
 use crate::Semantic;
@@ -199,6 +200,16 @@ impl FunctionDef {
         is_in_otp(self.file.file_id, db)
     }
 
+    /// All clauses are placeholders for a NIF implementation.
+    pub fn is_nif_stub(&self, db: &dyn DefDatabase) -> bool {
+        let body = db.function_body(InFile::new(self.file.file_id, self.function_id));
+        !body.clauses.is_empty()
+            && body
+                .clauses
+                .iter()
+                .all(|(_, clause)| clause.is_nif_stub(db))
+    }
+
     pub fn edoc_comments(&self, db: &dyn DefDatabase) -> Option<EdocHeader> {
         let fun_decls = self.source(db.upcast());
         let fun_decl = fun_decls.get(0)?;
@@ -241,6 +252,30 @@ impl FunctionDef {
             None => self.first_clause_arg_names(),
         }
     }
+
+    /// The name of each parameter: the one given in the `-spec`, or else
+    /// the variable in that position in the first clause binding one.
+    pub fn param_names(&self, db: &dyn SourceDatabase) -> Vec<ParamName> {
+        let spec_names = self
+            .spec
+            .as_ref()
+            .and_then(|spec_def| spec_def.arg_names(db))
+            .unwrap_or_default();
+        (0..self.name.arity() as usize)
+            .map(|idx| {
+                if let Some(SpecArgName::Name(name)) = spec_names.get(idx) {
+                    return ParamName::Name(Name::from_erlang_service(name));
+                }
+                self.function_clauses
+                    .iter()
+                    .find_map(|clause| match clause.param_names.get(idx) {
+                        Some(ParamName::Name(name)) => Some(ParamName::Name(name.clone())),
+                        _ => None,
+                    })
+                    .unwrap_or_else(|| ParamName::Default(Name::arg(idx + 1)))
+            })
+            .collect()
+    }
 }
 
 fn all_spec_arg_names_are_generated(names: &Vec<SpecArgName>) -> bool {
@@ -264,19 +299,20 @@ impl SpecDef {
     }
 
     pub fn arg_names(&self, db: &dyn SourceDatabase) -> Option<Vec<SpecArgName>> {
-        let spec = self.source(db);
-        let first_sig = spec.sigs().next()?;
-        Some(
-            first_sig
-                .args()?
-                .args()
-                .enumerate()
-                .map(|(arg_idx, expr)| arg_name(arg_idx + 1, expr))
-                .collect(),
-        )
+        sig_arg_names(self.source(db).sigs().next()?)
     }
 }
 
+fn sig_arg_names(sig: ast::TypeSig) -> Option<Vec<SpecArgName>> {
+    Some(
+        sig.args()?
+            .args()
+            .enumerate()
+            .map(|(arg_idx, expr)| arg_name(arg_idx + 1, expr))
+            .collect(),
+    )
+}
+
 pub enum SpecArgName {
     Name(String),
     Generated(String),
@@ -454,6 +490,10 @@ impl CallbackDef {
         let source_file = self.file.source(db);
         self.callback.form_id.get(&source_file)
     }
+
+    pub fn arg_names(&self, db: &dyn SourceDatabase) -> Option<Vec<SpecArgName>> {
+        sig_arg_names(self.source(db).sigs().next()?)
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
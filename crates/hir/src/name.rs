@@ -247,6 +247,7 @@ pub mod known {
         is_record,
         main,
         module_info,
+        nif_error,
         ok,
         parse_transform,
         server,
@@ -277,6 +278,9 @@ pub mod known {
         oncall,
         moduledoc,
         doc,
+        on_load,
+        nifs,
+        dialyzer,
     );
 
     #[allow(bad_style)]
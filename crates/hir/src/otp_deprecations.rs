@@ -0,0 +1,136 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The deprecated and removed functions of OTP, as listed by the
+//! `obsolete/3` clauses of the `otp_internal` module of the project's
+//! OTP. The compiler warns about calls to them from the same table, which
+//! OTP generates from its `DEPRECATIONS` file.
+
+use std::sync::Arc;
+
+use elp_base_db::ProjectId;
+
+use crate::db::DefDatabase;
+use crate::Body;
+use crate::Expr;
+use crate::ExprId;
+use crate::InFile;
+use crate::Literal;
+use crate::Name;
+use crate::NameArity;
+use crate::Pat;
+use crate::PatId;
+
+/// A clause of `otp_internal:obsolete/3`. A missing function name or
+/// arity stands for a wildcard, e.g. `obsolete(random, _, _)` covers the
+/// whole module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtpDeprecation {
+    pub module: Name,
+    pub name: Option<Name>,
+    pub arity: Option<u32>,
+    /// Why the function is deprecated, usually naming its replacement
+    pub text: Option<String>,
+    /// Whether the function no longer exists, rather than being deprecated
+    pub removed: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OtpDeprecations {
+    deprecations: Vec<OtpDeprecation>,
+}
+
+impl OtpDeprecations {
+    pub(crate) fn otp_deprecations_query(
+        db: &dyn DefDatabase,
+        project_id: ProjectId,
+    ) -> Arc<OtpDeprecations> {
+        let mut deprecations = Vec::new();
+        let file_id = match db.module_index(project_id).file_for_module("otp_internal") {
+            Some(file_id) => file_id,
+            None => return Arc::new(OtpDeprecations { deprecations }),
+        };
+        let obsolete = NameArity::new(Name::from_erlang_service("obsolete"), 3);
+        if let Some(def) = db.def_map(file_id).get_function(&obsolete) {
+            for clause_id in &def.function_clause_ids {
+                let clause = db.function_clause_body(InFile::new(file_id, *clause_id));
+                deprecations.extend(deprecation(
+                    db,
+                    &clause.body,
+                    &clause.clause.pats,
+                    &clause.clause.exprs,
+                ));
+            }
+        }
+        Arc::new(OtpDeprecations { deprecations })
+    }
+
+    /// The first clause matching the function, as `obsolete/3` would.
+    pub fn get(&self, module: &str, name: &str, arity: u32) -> Option<&OtpDeprecation> {
+        self.deprecations.iter().find(|deprecation| {
+            deprecation.module.as_str() == module
+                && deprecation
+                    .name
+                    .as_ref()
+                    .map_or(true, |deprecated| deprecated.as_str() == name)
+                && deprecation
+                    .arity
+                    .map_or(true, |deprecated| deprecated == arity)
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deprecations.is_empty()
+    }
+}
+
+/// A clause such as
+/// `obsolete(dbg, stop_clear, 0) -> {deprecated, "use dbg:stop/0 instead"};`
+fn deprecation(
+    db: &dyn DefDatabase,
+    body: &Body,
+    pats: &[PatId],
+    exprs: &[ExprId],
+) -> Option<OtpDeprecation> {
+    let atom = |pat: PatId| match &body[pat] {
+        Pat::Literal(Literal::Atom(atom)) => Some(db.lookup_atom(*atom)),
+        _ => None,
+    };
+    let [module, name, arity] = pats else {
+        return None;
+    };
+    let [result] = exprs else {
+        return None;
+    };
+    let Expr::Tuple { exprs } = &body[*result] else {
+        return None;
+    };
+    let removed = match body[*exprs.first()?]
+        .as_atom()
+        .map(|tag| db.lookup_atom(tag))
+    {
+        Some(tag) if tag.as_str() == "deprecated" => false,
+        Some(tag) if tag.as_str() == "removed" => true,
+        _ => return None,
+    };
+    let text = match exprs.get(1).map(|text| &body[*text]) {
+        Some(Expr::Literal(Literal::String(text))) => Some(text.chars().collect()),
+        _ => None,
+    };
+    Some(OtpDeprecation {
+        module: atom(*module)?,
+        name: atom(*name),
+        arity: match &body[*arity] {
+            Pat::Literal(Literal::Integer(arity)) => Some(*arity as u32),
+            _ => None,
+        },
+        text,
+        removed,
+    })
+}
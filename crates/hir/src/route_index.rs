@@ -0,0 +1,214 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Routing tables, and the routes of a project by handler module.
+//!
+//! A route is a `{PathMatch, Handler, ...}` tuple passed to
+//! `cowboy_router:compile/1`, or to any router configured in the
+//! `routers` section of `.elp.toml`.
+
+use std::sync::Arc;
+
+use elp_base_db::FileId;
+use elp_base_db::ModuleName;
+use elp_base_db::ProjectId;
+use elp_syntax::TextRange;
+use fxhash::FxHashMap;
+
+use crate::db::DefDatabase;
+use crate::fold::MacroStrategy;
+use crate::fold::ParenStrategy;
+use crate::AnyExpr;
+use crate::CallTarget;
+use crate::Expr;
+use crate::ExprId;
+use crate::FunctionDef;
+use crate::InFunctionClauseBody;
+use crate::Literal;
+use crate::Name;
+use crate::Semantic;
+use crate::Strategy;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub file_id: FileId,
+    /// Range of the path string
+    pub range: TextRange,
+    pub handler: Name,
+    pub function: Option<Name>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RouteIndex {
+    routes: FxHashMap<ModuleName, Vec<Route>>,
+}
+
+struct Router {
+    module: String,
+    function: String,
+    arity: usize,
+    /// Zero-based index of the argument holding the routes
+    arg: usize,
+}
+
+fn routers(db: &dyn DefDatabase, project_id: ProjectId) -> Vec<Router> {
+    let mut res = vec![Router {
+        module: "cowboy_router".to_string(),
+        function: "compile".to_string(),
+        arity: 1,
+        arg: 0,
+    }];
+    res.extend(
+        db.project_data(project_id)
+            .routers
+            .iter()
+            .map(|router| Router {
+                module: router.module.clone(),
+                function: router.function.clone(),
+                arity: router.arity as usize,
+                arg: router.arg,
+            }),
+    );
+    res
+}
+
+impl RouteIndex {
+    /// Only lowers the modules mentioning a router.
+    pub(crate) fn route_index_query(
+        db: &dyn DefDatabase,
+        project_id: ProjectId,
+    ) -> Arc<RouteIndex> {
+        let routers = routers(db, project_id);
+        let mut routes: FxHashMap<ModuleName, Vec<Route>> = FxHashMap::default();
+        for &source_root_id in &db.project_data(project_id).source_roots {
+            for file_id in db.source_root(source_root_id).iter() {
+                if !db.file_kind(file_id).is_module() {
+                    continue;
+                }
+                let text = db.file_text(file_id);
+                if !routers
+                    .iter()
+                    .any(|router| text.contains(router.module.as_str()))
+                {
+                    continue;
+                }
+                for route in db.file_routes(file_id).iter() {
+                    routes
+                        .entry(ModuleName::new(route.handler.as_str()))
+                        .or_default()
+                        .push(route.clone());
+                }
+            }
+        }
+        Arc::new(RouteIndex { routes })
+    }
+
+    pub(crate) fn file_routes_query(db: &dyn DefDatabase, file_id: FileId) -> Arc<Vec<Route>> {
+        let routers = match db.file_project_id(file_id) {
+            Some(project_id) => routers(db, project_id),
+            None => return Arc::default(),
+        };
+        let sema = Semantic { db };
+        let module = sema.module_name(file_id);
+        let mut res = Vec::new();
+        sema.def_map(file_id).get_functions().for_each(|(_, def)| {
+            routes_in_function(&sema, def, module.as_ref(), &routers, &mut res);
+        });
+        Arc::new(res)
+    }
+
+    /// The routes dispatching to the given module, in file order.
+    pub fn routes(&self, handler: &ModuleName) -> &[Route] {
+        self.routes
+            .get(handler)
+            .map_or(&[], |routes| routes.as_slice())
+    }
+}
+
+fn routes_in_function(
+    sema: &Semantic,
+    def: &FunctionDef,
+    module: Option<&ModuleName>,
+    routers: &[Router],
+    acc: &mut Vec<Route>,
+) {
+    let def_fb = def.in_function_body(sema, def);
+    def_fb.fold_function(
+        Strategy {
+            macros: MacroStrategy::ExpandButIncludeMacroCall,
+            parens: ParenStrategy::InvisibleParens,
+        },
+        (),
+        &mut |(), clause_id, ctx| {
+            if let AnyExpr::Expr(Expr::Call { target, args }) = ctx.item {
+                let in_clause = def_fb.in_clause(clause_id);
+                let called = match target {
+                    CallTarget::Remote { module, name, .. } => in_clause
+                        .as_atom_name(&module)
+                        .zip(in_clause.as_atom_name(&name)),
+                    // A router called from its own module
+                    CallTarget::Local { name } => module
+                        .map(|module| Name::from_erlang_service(module.as_str()))
+                        .zip(in_clause.as_atom_name(&name)),
+                };
+                if let Some((called_module, called_function)) = called {
+                    routers
+                        .iter()
+                        .filter(|router| {
+                            router.arity == args.len()
+                                && called_module.as_str() == router.module
+                                && called_function.as_str() == router.function
+                        })
+                        .filter_map(|router| args.get(router.arg))
+                        .for_each(|table| collect_routes(in_clause, *table, acc));
+                }
+            }
+        },
+    );
+}
+
+/// Routes may be nested in host rules, e.g. `[{'_', [Route, ...]}]`
+fn collect_routes(
+    in_clause: &InFunctionClauseBody<&FunctionDef>,
+    expr: ExprId,
+    acc: &mut Vec<Route>,
+) {
+    match &in_clause[expr] {
+        Expr::List { exprs, .. } => exprs
+            .iter()
+            .for_each(|expr| collect_routes(in_clause, *expr, acc)),
+        Expr::Tuple { exprs } => match as_route(in_clause, exprs) {
+            Some(route) => acc.push(route),
+            None => exprs
+                .iter()
+                .for_each(|expr| collect_routes(in_clause, *expr, acc)),
+        },
+        _ => {}
+    }
+}
+
+/// Handles `{Path, Handler, State}` and `{Path, Constraints, Handler,
+/// State}` as used by cowboy, as well as `{Path, Handler, Function}`.
+fn as_route(in_clause: &InFunctionClauseBody<&FunctionDef>, exprs: &[ExprId]) -> Option<Route> {
+    let (path, rest) = exprs.split_first()?;
+    if !matches!(in_clause[*path], Expr::Literal(Literal::String(_))) {
+        return None;
+    }
+    let handler_idx = rest
+        .iter()
+        .position(|expr| in_clause.as_atom_name(expr).is_some())?;
+    Some(Route {
+        file_id: in_clause.file_id(),
+        range: in_clause.range_for_expr(*path)?,
+        handler: in_clause.as_atom_name(&rest[handler_idx])?,
+        function: rest
+            .get(handler_idx + 1)
+            .and_then(|expr| in_clause.as_atom_name(expr)),
+    })
+}
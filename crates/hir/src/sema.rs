@@ -242,6 +242,26 @@ impl<'db> Semantic<'db> {
         Some((behaviour, behaviour_def_map.get_callbacks().clone()))
     }
 
+    /// The modules declaring the `behaviour`, from the project of `file_id`.
+    pub fn behaviour_implementations(
+        &self,
+        file_id: FileId,
+        behaviour: &ModuleName,
+    ) -> Vec<Module> {
+        let project_id = match self.db.file_app_data(file_id) {
+            Some(app_data) => app_data.project_id,
+            None => return Vec::new(),
+        };
+        self.db
+            .behaviour_index(project_id)
+            .implementations(behaviour)
+            .iter()
+            .map(|file_id| Module {
+                file: File { file_id: *file_id },
+            })
+            .collect()
+    }
+
     pub fn resolve_implemented_callbacks(&self, file_id: FileId) -> FxHashSet<NameArity> {
         let mut res = FxHashSet::default();
         let def_map = self.def_map(file_id);
@@ -17,6 +17,7 @@ use fxhash::FxHashMap;
 use fxhash::FxHashSet;
 
 // @fb-only
+use crate::runnables::eunit_runnables;
 use crate::runnables::runnables;
 use crate::runnables::Runnable;
 
@@ -56,19 +57,29 @@ pub(crate) fn ct_annotations(
     all: FxHashSet<TestDef>,
     groups: FxHashMap<SmolStr, GroupDef>,
 ) -> Vec<Annotation> {
-    let mut ct_annotations = Vec::default();
+    runnable_annotations(db, file_id, runnables(db, file_id, all, groups))
+}
 
-    for runnable in runnables(db, file_id, all, groups) {
-        let range = runnable.nav.range();
-        ct_annotations.push(Annotation {
-            range,
-            kind: AnnotationKind::Runnable(runnable),
-        });
-    }
+/// The annotations of a module which is not a Common Test suite, where
+/// the only tests are the EUnit ones.
+pub(crate) fn eunit_annotations(db: &RootDatabase, file_id: FileId) -> Vec<Annotation> {
+    runnable_annotations(db, file_id, eunit_runnables(db, file_id))
+}
 
-    let annotations = annotations(db, file_id);
+fn runnable_annotations(
+    db: &RootDatabase,
+    file_id: FileId,
+    runnables: Vec<Runnable>,
+) -> Vec<Annotation> {
+    let runnable_annotations = runnables.into_iter().map(|runnable| Annotation {
+        range: runnable.nav.range(),
+        kind: AnnotationKind::Runnable(runnable),
+    });
 
-    annotations.into_iter().chain(ct_annotations).collect()
+    annotations(db, file_id)
+        .into_iter()
+        .chain(runnable_annotations)
+        .collect()
 }
 
 #[cfg(test)]
@@ -0,0 +1,217 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Atom inventory, and clustering of near-identical atoms.
+//!
+//! Erlang accepts any atom, so `time_out` instead of `timeout` in a
+//! message or config key silently never matches. Atoms used once next
+//! to a popular near-identical sibling are likely such typos.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::RootDatabase;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SmolStr;
+use elp_syntax::SyntaxKind;
+use elp_syntax::TextRange;
+use fxhash::FxHashMap;
+use hir::AsName;
+use hir::Semantic;
+
+/// Minimum number of uses for an atom to make its single-use siblings
+/// suspicious.
+pub const POPULAR_ATOM_USES: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtomCluster {
+    /// Near-identical atoms with their number of uses, most used first
+    pub atoms: Vec<(SmolStr, usize)>,
+}
+
+impl AtomCluster {
+    /// Atoms used once, paired with the popular sibling they are most
+    /// likely a typo of.
+    pub fn suspicious(&self) -> impl Iterator<Item = (&SmolStr, &SmolStr)> {
+        let popular = self
+            .atoms
+            .first()
+            .filter(|(_, count)| *count >= POPULAR_ATOM_USES);
+        self.atoms.iter().filter_map(move |(atom, count)| {
+            let (sibling, _) = popular?;
+            (*count == 1).then_some((atom, sibling))
+        })
+    }
+}
+
+/// Atoms written in function bodies, leaving out function, module and
+/// record names, which the compiler already checks.
+pub(crate) fn file_atoms(db: &RootDatabase, file_id: FileId) -> Vec<(SmolStr, TextRange)> {
+    let sema = Semantic::new(db);
+    let source_file = sema.parse(file_id);
+    source_file
+        .value
+        .syntax()
+        .descendants()
+        .filter_map(ast::Atom::cast)
+        .filter(is_data_atom)
+        .map(|atom| (atom.as_name().raw(), atom.syntax().text_range()))
+        .collect()
+}
+
+pub(crate) fn is_data_atom(atom: &ast::Atom) -> bool {
+    let in_function = atom
+        .syntax()
+        .ancestors()
+        .any(|node| node.kind() == SyntaxKind::FUN_DECL);
+    let is_name = atom.syntax().parent().map_or(false, |parent| {
+        matches!(
+            parent.kind(),
+            SyntaxKind::CALL
+                | SyntaxKind::REMOTE
+                | SyntaxKind::REMOTE_MODULE
+                | SyntaxKind::FUNCTION_CLAUSE
+                | SyntaxKind::INTERNAL_FUN
+                | SyntaxKind::EXTERNAL_FUN
+                | SyntaxKind::RECORD_NAME
+                | SyntaxKind::RECORD_FIELD_NAME
+        )
+    });
+    in_function && !is_name
+}
+
+/// Groups the atoms of an inventory that are near-identical to each
+/// other. Atoms without a near-identical sibling are left out.
+pub fn atom_clusters(counts: &FxHashMap<SmolStr, usize>) -> Vec<AtomCluster> {
+    // Atoms within one edit of each other share a variant with at most
+    // one character deleted, which avoids comparing every pair.
+    let mut by_variant: FxHashMap<String, Vec<&SmolStr>> = FxHashMap::default();
+    for atom in counts.keys() {
+        for variant in variants(atom) {
+            by_variant.entry(variant).or_default().push(atom);
+        }
+    }
+    let mut parent: FxHashMap<&SmolStr, &SmolStr> = FxHashMap::default();
+    for atoms in by_variant.values() {
+        for (idx, a) in atoms.iter().enumerate() {
+            for b in &atoms[idx + 1..] {
+                if near_identical(a, b) {
+                    let (root_a, root_b) = (root(&parent, *a), root(&parent, *b));
+                    if root_a != root_b {
+                        parent.insert(root_a, root_b);
+                    }
+                }
+            }
+        }
+    }
+    let mut clusters: FxHashMap<&SmolStr, Vec<(SmolStr, usize)>> = FxHashMap::default();
+    for atom in parent.keys().chain(parent.values()) {
+        clusters
+            .entry(root(&parent, *atom))
+            .or_default()
+            .push(((*atom).clone(), counts[*atom]));
+    }
+    let mut res: Vec<AtomCluster> = clusters
+        .into_values()
+        .map(|mut atoms| {
+            atoms.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+            atoms.dedup();
+            AtomCluster { atoms }
+        })
+        .collect();
+    res.sort_by(|a, b| a.atoms.cmp(&b.atoms));
+    res
+}
+
+fn root<'a>(parent: &FxHashMap<&'a SmolStr, &'a SmolStr>, mut atom: &'a SmolStr) -> &'a SmolStr {
+    while let Some(next) = parent.get(atom) {
+        atom = next;
+    }
+    atom
+}
+
+fn normalize(atom: &str) -> String {
+    atom.chars()
+        .filter(|c| *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn variants(atom: &str) -> Vec<String> {
+    let normalized = normalize(atom);
+    let mut res = vec![normalized.clone()];
+    for (idx, _) in normalized.char_indices() {
+        let mut variant = normalized.clone();
+        variant.remove(idx);
+        res.push(variant);
+    }
+    res
+}
+
+/// Atoms differing only in underscores and case, or by a single edit.
+/// Short atoms, and atoms only differing in digits such as `node1` and
+/// `node2`, are deliberately distinct.
+pub(crate) fn near_identical(a: &str, b: &str) -> bool {
+    if a == b {
+        return false;
+    }
+    let without_digits = |s: &str| {
+        s.chars()
+            .filter(|c| !c.is_ascii_digit())
+            .collect::<String>()
+    };
+    if without_digits(a) == without_digits(b) {
+        return false;
+    }
+    normalize(a) == normalize(b)
+        || (a.len().min(b.len()) >= 5 && strsim::damerau_levenshtein(a, b) <= 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_syntax::SmolStr;
+    use fxhash::FxHashMap;
+
+    use super::atom_clusters;
+    use super::near_identical;
+
+    #[test]
+    fn near_identical_atoms() {
+        assert!(near_identical("timeout", "time_out"));
+        assert!(near_identical("timeout", "timeuot"));
+        assert!(near_identical("request", "requets"));
+        assert!(!near_identical("node1", "node2"));
+        assert!(!near_identical("ok", "on"));
+        assert!(!near_identical("error", "error"));
+    }
+
+    #[test]
+    fn clusters_and_suspicious_singletons() {
+        let counts: FxHashMap<SmolStr, usize> = [
+            ("timeout", 12),
+            ("time_out", 1),
+            ("timeuot", 1),
+            ("ok", 40),
+            ("node1", 3),
+            ("node2", 3),
+        ]
+        .into_iter()
+        .map(|(atom, count)| (SmolStr::new(atom), count))
+        .collect();
+        let clusters = atom_clusters(&counts);
+        assert_eq!(clusters.len(), 1);
+        let suspicious: Vec<_> = clusters[0]
+            .suspicious()
+            .map(|(atom, sibling)| format!("{atom} -> {sibling}"))
+            .collect();
+        assert_eq!(
+            suspicious,
+            vec!["time_out -> timeout", "timeuot -> timeout"]
+        );
+    }
+}
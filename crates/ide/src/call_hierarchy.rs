@@ -7,24 +7,28 @@
  * of this source tree.
  */
 
+use elp_ide_db::call_graph;
+use elp_ide_db::elp_base_db::FileId;
 use elp_ide_db::elp_base_db::FilePosition;
+use elp_ide_db::elp_base_db::FileRange;
+use elp_ide_db::find_best_token;
 use elp_ide_db::FxIndexMap;
+use elp_ide_db::ReferenceType;
 use elp_ide_db::RootDatabase;
+use elp_ide_db::SymbolClass;
+use elp_ide_db::SymbolDefinition;
 use elp_syntax::algo;
 use elp_syntax::ast::{self};
 use elp_syntax::AstNode;
 use elp_syntax::SmolStr;
 use elp_syntax::TextRange;
-use hir::fold::MacroStrategy;
-use hir::fold::ParenStrategy;
-use hir::AnyExpr;
-use hir::Expr;
+use hir::File;
+use hir::FunctionDef;
 use hir::InFile;
+use hir::Module;
 use hir::Semantic;
-use hir::Strategy;
 
 use crate::handlers::goto_definition;
-use crate::handlers::references;
 use crate::navigation_target::ToNav;
 use crate::NavigationTarget;
 use crate::RangeInfo;
@@ -45,33 +49,43 @@ pub(crate) fn call_hierarchy_prepare(
 pub(crate) fn incoming_calls(db: &RootDatabase, position: FilePosition) -> Option<Vec<CallItem>> {
     let sema = Semantic::new(db);
     let mut calls = CallLocations::default();
-    let search_result = references::find_all_refs(&sema, position);
-    let references = search_result?.first()?.references.clone();
+    let token = find_best_token(&sema, position)?;
+    let functions: Vec<FunctionDef> = match SymbolClass::classify(&sema, token)? {
+        SymbolClass::Definition(SymbolDefinition::Function(function)) => vec![function],
+        SymbolClass::Reference {
+            refs: _,
+            typ: ReferenceType::Fuzzy,
+        } => return None,
+        SymbolClass::Reference { refs, typ: _ } => refs
+            .iter()
+            .filter_map(|def| match def {
+                SymbolDefinition::Function(function) => Some(function),
+                _ => None,
+            })
+            .collect(),
+        SymbolClass::Definition(_) => return None,
+    };
 
-    for (file_id, ranges) in references {
-        let source_file = sema.parse(file_id);
-        let syntax = source_file.value.syntax();
-        let form_list = sema.form_list(file_id);
-
-        for range in ranges {
-            if let Some(call) = algo::find_node_at_offset::<ast::Call>(syntax, range.start()) {
-                let enclosing_function_id =
-                    sema.find_enclosing_function_clause_id(file_id, call.syntax())?;
-                let enclosing_function_name = &form_list[enclosing_function_id].name;
-                let def_map = sema.def_map(file_id);
-                let enclosing_function_def = def_map.get_function(enclosing_function_name)?;
-                let mut enclosing_function_nav = enclosing_function_def.to_nav(db);
-                if file_id != position.file_id {
-                    if let Some(module_name) = sema.module_name(file_id) {
-                        enclosing_function_nav.name = SmolStr::new(format!(
-                            "{}:{}",
-                            module_name.as_str(),
-                            enclosing_function_nav.name
-                        ))
-                    }
+    for function in functions {
+        for (caller, call) in call_graph::incoming_calls(&sema, &function) {
+            let file_id = caller.file.file_id;
+            let mut caller_nav = caller.to_nav(db);
+            if file_id != position.file_id {
+                if let Some(module_name) = sema.module_name(file_id) {
+                    caller_nav.name =
+                        SmolStr::new(format!("{}:{}", module_name.as_str(), caller_nav.name))
                 }
-                calls.add(enclosing_function_nav, range);
             }
+            // Point at the function name of remote calls, as references do
+            let source_file = sema.parse(file_id);
+            let range = algo::find_node_at_offset::<ast::Remote>(
+                source_file.value.syntax(),
+                call.range.start(),
+            )
+            .filter(|remote| remote.syntax().text_range() == call.range)
+            .and_then(|remote| remote.fun())
+            .map_or(call.range, |fun| fun.syntax().text_range());
+            calls.add(caller_nav, range);
         }
     }
 
@@ -85,48 +99,67 @@ pub(crate) fn outgoing_calls(db: &RootDatabase, position: FilePosition) -> Optio
     let source_file = sema.parse(file_id);
     let syntax = source_file.value.syntax();
     if let Some(function) = algo::find_node_at_offset::<ast::FunDecl>(syntax, position.offset) {
-        let function_id_idx = sema.find_enclosing_function(file_id, function.syntax())?;
-        let function_id = InFile::new(file_id, function_id_idx);
-        let function_body = sema.to_function_body(function_id);
-        sema.fold_function(
-            Strategy {
-                macros: MacroStrategy::Expand,
-                parens: ParenStrategy::InvisibleParens,
-            },
-            function_id,
-            (),
-            &mut |acc, clause_id, ctx| {
-                if let AnyExpr::Expr(Expr::Call { target, args }) = &ctx.item {
-                    let arity = args.len() as u32;
-                    let body = &function_body.body(clause_id);
-                    if let Some(call_def) = target.resolve_call(arity, &sema, file_id, body) {
-                        let mut nav = call_def.to_nav(db);
-                        if let Some(label) = target.label(arity, &sema, body) {
-                            nav.name = label
-                        }
-                        if let Some(expr) = &function_body.get_body_map(clause_id).any(ctx.item_id)
-                        {
-                            if let Some(node) = expr.to_node(&source_file) {
-                                if let Some(call) = algo::find_node_at_offset::<ast::Call>(
-                                    node.syntax(),
-                                    node.syntax().text_range().start(),
-                                ) {
-                                    if let Some(expr) = call.expr() {
-                                        let range = expr.syntax().text_range();
-                                        calls.add(nav, range);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-                acc
-            },
-        )
+        let function_id = sema.find_enclosing_function(file_id, function.syntax())?;
+        let function = sema.function_def(&InFile::new(file_id, function_id))?;
+        for call in call_graph::outgoing_calls(&sema, &function) {
+            let mut nav = call.callee.to_nav(db);
+            if let Some(label) = call.label {
+                nav.name = label
+            }
+            calls.add(nav, call.range);
+        }
     }
     Some(calls.into_items())
 }
 
+/// A module calling, or called by, the module of interest.
+#[derive(Debug, Clone)]
+pub struct ModuleDependency {
+    pub module: NavigationTarget,
+    /// The calls, in the calling module
+    pub calls: Vec<FileRange>,
+}
+
+pub(crate) fn module_dependencies(db: &RootDatabase, file_id: FileId) -> Vec<ModuleDependency> {
+    let sema = Semantic::new(db);
+    call_graph::module_dependencies(&sema, file_id)
+        .into_iter()
+        .map(|calls| ModuleDependency {
+            module: module_nav(db, calls.file_id),
+            calls: calls
+                .ranges
+                .into_iter()
+                .map(|range| FileRange { file_id, range })
+                .collect(),
+        })
+        .collect()
+}
+
+pub(crate) fn module_dependents(db: &RootDatabase, file_id: FileId) -> Vec<ModuleDependency> {
+    let sema = Semantic::new(db);
+    call_graph::module_dependents(&sema, file_id)
+        .into_iter()
+        .map(|calls| ModuleDependency {
+            module: module_nav(db, calls.file_id),
+            calls: calls
+                .ranges
+                .into_iter()
+                .map(|range| FileRange {
+                    file_id: calls.file_id,
+                    range,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn module_nav(db: &RootDatabase, file_id: FileId) -> NavigationTarget {
+    Module {
+        file: File { file_id },
+    }
+    .to_nav(db)
+}
+
 #[derive(Default)]
 struct CallLocations {
     funcs: FxIndexMap<NavigationTarget, Vec<TextRange>>,
@@ -398,4 +431,33 @@ mod tests {
     "#,
         );
     }
+
+    #[test]
+    fn test_call_hierarchy_through_macro() {
+        check_call_hierarchy(
+            r#"
+    -define(HANDLE(X), handle(X, [])).
+    han~dle(Msg, State) -> log(Msg), State.
+ %% ^^^^^^
+    log(_) -> ok.
+    caller() -> ?HANDLE(hello).
+    "#,
+            r#"
+    -define(HANDLE(X), handle(X, [])).
+    han~dle(Msg, State) -> log(Msg), State.
+    log(_) -> ok.
+    caller() -> ?HANDLE(hello).
+ %% ^^^^^^ from: caller/0
+             %% ^^^^^^^^^^^^^^ from_range: caller/0
+    "#,
+            r#"
+    -define(HANDLE(X), handle(X, [])).
+    handle(Msg, State) -> log(Msg), State.
+ %% ^^^^^^ to: handle/2
+    log(_) -> ok.
+    call~er() -> ?HANDLE(hello).
+             %% ^^^^^^^^^^^^^^ from_range: handle/2
+    "#,
+        );
+    }
 }
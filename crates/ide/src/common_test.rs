@@ -130,7 +130,7 @@ pub fn runnable_names(
             .into_iter()
             .filter_map(|runnable| match runnable.kind {
                 RunnableKind::Test { name, .. } => Some(name),
-                RunnableKind::Suite => None,
+                RunnableKind::Suite | RunnableKind::Eunit { .. } => None,
             })
             .collect()
     })
@@ -32,6 +32,7 @@ use elp_ide_db::elp_base_db::ProjectId;
 use elp_ide_db::erlang_service;
 use elp_ide_db::erlang_service::DiagnosticLocation;
 use elp_ide_db::erlang_service::ParseError;
+use elp_ide_db::metadata::Annotation;
 use elp_ide_db::metadata::Kind;
 use elp_ide_db::metadata::Metadata;
 use elp_ide_db::metadata::Source;
@@ -42,6 +43,7 @@ use elp_ide_db::ErlAstDatabase;
 use elp_ide_db::LineCol;
 use elp_ide_db::LineIndex;
 use elp_ide_db::LineIndexDatabase;
+use elp_project_model::EqwalizerLevel;
 use elp_syntax::algo;
 use elp_syntax::ast;
 use elp_syntax::ast::edit;
@@ -74,47 +76,82 @@ use serde::Serialize;
 use text_edit::TextEdit;
 
 use crate::common_test;
+use crate::injection::Injection;
 use crate::RootDatabase;
 use crate::SourceDatabase;
+use crate::MFA;
 
+mod api_migration;
+mod app_dependency_violation;
 mod application_env;
 mod atoms_exhaustion;
 mod boolean_precedence;
+mod callback_mismatch;
+mod config_schema;
+mod constant_condition;
 mod cross_node_eval;
+mod denied_call;
 mod dependent_header;
 mod deprecated_function;
 mod effect_free_statement;
+mod emptiness_check;
 mod eqwalizer_assists;
 mod expression_can_be_simplified;
 mod from_config;
 mod head_mismatch;
 mod helpers;
+mod incomplete_callbacks;
+mod invalid_attribute;
+mod invalid_json_literal;
+mod legacy_collection;
 mod meck;
+mod message_tag_typo;
 // @fb-only
+mod missing_callback;
 mod missing_compile_warn_missing_spec;
+mod missing_on_load;
 mod missing_separator;
+mod missing_spec;
 mod misspelled_attribute;
+mod mnesia_schema;
+mod module_header;
 mod module_mismatch;
 mod mutable_variable;
 mod record_tuple_match;
 mod redundant_assignment;
+mod redundant_clause;
+mod redundant_export;
 mod replace_call;
 mod replace_in_spec;
+mod return_value_ignored;
 mod slow_functions;
+mod stale_generated_file;
+mod standalone_file;
 mod trivial_match;
+mod uncaught_throw;
 mod undefined_function;
 mod unused_function_args;
 mod unused_include;
 mod unused_macro;
+mod unused_parameter;
+mod unused_record;
 mod unused_record_field;
+mod unused_suppression;
 
+pub use api_migration::ApiMigration;
+pub use config_schema::sys_config_drift;
+pub use config_schema::ConfigSchema;
+pub use denied_call::DeniedCall;
 pub use elp_ide_db::DiagnosticCode;
 pub use from_config::Lint;
 pub use from_config::LintsFromConfig;
 pub use from_config::ReplaceCall;
 pub use from_config::ReplaceCallAction;
+pub use module_header::AuthorPolicy;
+pub use module_header::ModuleTemplate;
 pub use replace_call::Replacement;
 pub use replace_in_spec::TypeReplacement;
+pub use stale_generated_file::stale_generated_files;
 
 use self::eqwalizer_assists::add_eqwalizer_assists;
 
@@ -157,6 +194,7 @@ impl Diagnostic {
 
     pub(crate) fn as_related(&self) -> RelatedInformation {
         RelatedInformation {
+            file_id: None,
             range: self.range,
             message: self.message.clone(),
         }
@@ -193,6 +231,31 @@ impl Diagnostic {
         }
     }
 
+    /// The fixes with the preferred one first, followed by the others
+    /// in their original order, and the ones only suppressing the
+    /// diagnostic last. The fix configured for the code in
+    /// `preferred_fixes` is preferred, otherwise the first one that does
+    /// not suppress the diagnostic. A configured id matching no fix
+    /// leaves none preferred.
+    pub fn ranked_fixes(
+        &self,
+        preferred_fixes: &FxHashMap<DiagnosticCode, String>,
+    ) -> Option<Vec<Assist>> {
+        let mut fixes = self.fixes.clone()?;
+        let is_suppression = |fix: &Assist| fix.group == Some(GroupLabel::ignore());
+        fixes.sort_by_key(is_suppression);
+        let preferred = match preferred_fixes.get(&self.code) {
+            Some(id) => fixes.iter().position(|fix| fix.id.0 == id),
+            None => fixes.iter().position(|fix| !is_suppression(fix)),
+        };
+        if let Some(idx) = preferred {
+            let mut fix = fixes.remove(idx);
+            fix.is_preferred = true;
+            fixes.insert(0, fix);
+        }
+        Some(fixes)
+    }
+
     pub(crate) fn experimental(self) -> Diagnostic {
         self.add_categories([Category::Experimental])
     }
@@ -218,12 +281,16 @@ impl Diagnostic {
             if config.include_suppressed && annotation.kind == Kind::Fixme {
                 return false;
             }
-            annotation.codes.contains(&self.code)
-                && (annotation.suppression_range.contains(self.range.start())
-                    || self.range == DIAGNOSTIC_WHOLE_FILE_RANGE)
+            self.is_suppressed_by(annotation)
         })
     }
 
+    pub(crate) fn is_suppressed_by(&self, annotation: &Annotation) -> bool {
+        annotation.codes.contains(&self.code)
+            && (annotation.suppression_range.contains(self.range.start())
+                || self.range == DIAGNOSTIC_WHOLE_FILE_RANGE)
+    }
+
     pub(crate) fn with_ignore_fix(mut self, sema: &Semantic, file_id: FileId) -> Diagnostic {
         let mut builder = TextEdit::builder();
         let parsed = sema.parse(file_id);
@@ -272,6 +339,8 @@ impl Diagnostic {
                 source_change: Some(source_change),
                 user_input: None,
                 original_diagnostic: None,
+                is_preferred: false,
+                subtype: None,
             };
             match &mut self.fixes {
                 Some(fixes) => fixes.push(ignore_fix),
@@ -322,6 +391,9 @@ impl Diagnostic {
             &AssistConfig {
                 snippet_cap: None,
                 allowed: None,
+                disabled: Vec::new(),
+                min_otp_release: None,
+                align_arrows: false,
             },
             AssistResolveStrategy::All,
             range,
@@ -343,6 +415,8 @@ impl Diagnostic {
 
 #[derive(Debug, Clone)]
 pub struct RelatedInformation {
+    /// The file of the range, when it is not the file of the diagnostic
+    pub file_id: Option<FileId>,
     pub range: TextRange,
     pub message: String,
 }
@@ -357,7 +431,8 @@ impl fmt::Display for Diagnostic {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Severity {
     Error,
     Warning,
@@ -395,6 +470,22 @@ impl<F> AdhocSemanticDiagnostics for F where
 {
 }
 
+/// A checker reading what is configured in `.elp_lint.toml`, such as the
+/// functions whose return value must be used, or needing the whole
+/// database, such as to run eqWAlizer.
+pub trait ConfiguredSemanticDiagnostics:
+    Fn(&mut Vec<Diagnostic>, &Semantic, &RootDatabase, FileId, FileKind, &DiagnosticsConfig)
+    + std::panic::RefUnwindSafe
+    + Sync
+{
+}
+impl<F> ConfiguredSemanticDiagnostics for F where
+    F: Fn(&mut Vec<Diagnostic>, &Semantic, &RootDatabase, FileId, FileKind, &DiagnosticsConfig)
+        + std::panic::RefUnwindSafe
+        + Sync
+{
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DiagnosticConditions {
     pub experimental: bool,
@@ -421,6 +512,12 @@ pub struct DiagnosticDescriptor<'a> {
     checker: &'a dyn AdhocSemanticDiagnostics,
 }
 
+#[derive(Clone)]
+pub struct ConfiguredDiagnosticDescriptor<'a> {
+    conditions: DiagnosticConditions,
+    checker: &'a dyn ConfiguredSemanticDiagnostics,
+}
+
 // ---------------------------------------------------------------------
 
 #[derive(Default, Clone, Debug)]
@@ -492,6 +589,28 @@ pub struct DiagnosticsConfig {
     pub include_otp: bool,
     pub compile_options: Vec<CompileOption>,
     pub override_compile_options: Vec<CompileOption>,
+    /// Per code, the id of the fix to mark as preferred.
+    pub preferred_fixes: FxHashMap<DiagnosticCode, String>,
+    /// Functions whose string literal arguments are checked, for the
+    /// languages that have a check.
+    pub injections: Vec<Injection>,
+    /// Functions, beyond the built-in ones, whose return value must not
+    /// be discarded.
+    pub must_use: Vec<MFA>,
+    /// Whether to offer asserting the success value of a call whose
+    /// return value is discarded.
+    pub assert_must_use: bool,
+    /// The OTP release the code must run on, when it is not the release
+    /// of the project's toolchain.
+    pub target_otp_release: Option<u32>,
+    /// Migrations of functions, beyond the built-in ones.
+    pub migrations: Vec<ApiMigration>,
+    /// The header and attributes every module must start with.
+    pub module_template: Option<ModuleTemplate>,
+    /// Application environments with a generated accessor module.
+    pub config_schemas: Vec<ConfigSchema>,
+    /// Functions the project does not want called.
+    pub denied_calls: Vec<DeniedCall>,
     /// Used in `elp lint` to request erlang service diagnostics if
     /// needed.
     pub request_erlang_service_diagnostics: bool,
@@ -546,6 +665,15 @@ impl DiagnosticsConfig {
             self.enabled = EnabledDiagnostics::from_set(allowed_diagnostics);
         }
         self.lints_from_config = lint_config.ad_hoc_lints.clone();
+        self.preferred_fixes = lint_config.preferred_fixes.clone();
+        self.injections = lint_config.injections.clone();
+        self.must_use = lint_config.must_use.clone();
+        self.assert_must_use = lint_config.assert_must_use;
+        self.target_otp_release = lint_config.target_otp_release;
+        self.migrations = lint_config.migrations.clone();
+        self.module_template = lint_config.module_template.clone();
+        self.config_schemas = lint_config.config_schemas.clone();
+        self.denied_calls = lint_config.denied_calls.clone();
         self.request_erlang_service_diagnostics = self.request_erlang_service_diagnostics();
         Ok(self)
     }
@@ -585,6 +713,46 @@ impl DiagnosticsConfig {
         self
     }
 
+    pub fn set_injections(mut self, injections: Vec<Injection>) -> DiagnosticsConfig {
+        self.injections = injections;
+        self
+    }
+
+    pub fn set_must_use(mut self, must_use: Vec<MFA>) -> DiagnosticsConfig {
+        self.must_use = must_use;
+        self
+    }
+
+    pub fn set_assert_must_use(mut self, value: bool) -> DiagnosticsConfig {
+        self.assert_must_use = value;
+        self
+    }
+
+    pub fn set_target_otp_release(mut self, release: Option<u32>) -> DiagnosticsConfig {
+        self.target_otp_release = release;
+        self
+    }
+
+    pub fn set_migrations(mut self, migrations: Vec<ApiMigration>) -> DiagnosticsConfig {
+        self.migrations = migrations;
+        self
+    }
+
+    pub fn set_module_template(mut self, template: Option<ModuleTemplate>) -> DiagnosticsConfig {
+        self.module_template = template;
+        self
+    }
+
+    pub fn set_config_schemas(mut self, schemas: Vec<ConfigSchema>) -> DiagnosticsConfig {
+        self.config_schemas = schemas;
+        self
+    }
+
+    pub fn set_denied_calls(mut self, denied_calls: Vec<DeniedCall>) -> DiagnosticsConfig {
+        self.denied_calls = denied_calls;
+        self
+    }
+
     pub fn set_lints_from_config(
         mut self,
         lints_from_config: &LintsFromConfig,
@@ -615,6 +783,35 @@ pub struct LintConfig {
     pub disabled_lints: Vec<DiagnosticCode>,
     #[serde(default)]
     pub ad_hoc_lints: LintsFromConfig,
+    /// Overrides the fix editors apply automatically for a code, by id.
+    #[serde(default, skip_serializing_if = "FxHashMap::is_empty")]
+    pub preferred_fixes: FxHashMap<DiagnosticCode, String>,
+    /// Functions whose string literal arguments are in another language.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub injections: Vec<Injection>,
+    /// Functions returning `ok` whose return value must not be discarded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub must_use: Vec<MFA>,
+    /// Offers to assert the success value of a call whose return value
+    /// is discarded, which makes the call crash when it fails.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub assert_must_use: bool,
+    /// The OTP release the code must run on, which defaults to the
+    /// release of the project's toolchain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_otp_release: Option<u32>,
+    /// Functions superseded by other ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub migrations: Vec<ApiMigration>,
+    /// The header and attributes every module must start with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module_template: Option<ModuleTemplate>,
+    /// Application environments to generate a typed accessor module for.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub config_schemas: Vec<ConfigSchema>,
+    /// Functions which must not be called, with the reason to report.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub denied_calls: Vec<DeniedCall>,
 }
 
 // ---------------------------------------------------------------------
@@ -687,10 +884,12 @@ pub fn eqwalizer_to_diagnostic(
     eqwalizer_enabled: bool,
 ) -> Diagnostic {
     let range = d.range;
-    let severity = if eqwalizer_enabled {
-        Severity::Error
-    } else {
+    let severity = if !eqwalizer_enabled {
         Severity::Information
+    } else if sema.db.eqwalizer_level(file_id) == EqwalizerLevel::Warnings {
+        Severity::Warning
+    } else {
+        Severity::Error
     };
     let explanation = match &d.explanation {
         Some(s) => format!("\n\n{}", s),
@@ -717,6 +916,37 @@ pub fn eqwalizer_to_diagnostic(
     diagnostic
 }
 
+/// Diagnostics for a file that is only given syntax-level analysis,
+/// e.g. because it is too large or generated. Only parse errors are
+/// reported, no semantic checks are run.
+pub fn syntax_only_diagnostics(db: &RootDatabase, file_id: FileId) -> LabeledDiagnostics {
+    let mut normal = Vec::new();
+    let parse = db.parse(file_id);
+    normal.append(&mut form_missing_separator_diagnostics(&parse));
+    LabeledDiagnostics {
+        normal,
+        labeled_syntax_errors: parse_error_diagnostics(db, file_id),
+        labeled_undefined_errors: FxHashMap::default(),
+    }
+}
+
+fn parse_error_diagnostics(db: &RootDatabase, file_id: FileId) -> Labeled {
+    let parse = db.parse(file_id);
+    let parse_diagnostics = parse.errors().iter().take(128).map(|err| {
+        let (code, message) = match err {
+            elp_syntax::SyntaxError::Error(_) => {
+                (DiagnosticCode::SyntaxError, "Syntax Error".to_string())
+            }
+            elp_syntax::SyntaxError::Missing(m, _) => (
+                DiagnosticCode::Missing("missing".to_string()),
+                format!("Missing '{}'", m),
+            ),
+        };
+        Diagnostic::error(code, widen_range(err.range()), message)
+    });
+    label_syntax_errors(&parse.tree(), parse_diagnostics)
+}
+
 /// Main entry point to calculate ELP-native diagnostics for a file
 pub fn native_diagnostics(
     db: &RootDatabase,
@@ -737,12 +967,16 @@ pub fn native_diagnostics(
     let report_diagnostics = EXTENSIONS.contains(&file_kind);
 
     let mut res = Vec::new();
+    // Diagnostics of default-disabled descriptors which are not enabled,
+    // only used to tell whether a suppression comment is still needed
+    let mut not_enabled = Vec::new();
 
     let labeled_syntax_errors = if report_diagnostics {
         let sema = Semantic::new(db);
 
         if file_kind.is_module() {
             no_module_definition_diagnostic(&mut res, &parse);
+            standalone_file::standalone_file(&mut res, db, file_id);
             if config.include_generated || !db.is_generated(file_id) {
                 unused_include::unused_includes(&sema, db, &mut res, file_id);
             }
@@ -760,31 +994,36 @@ pub fn native_diagnostics(
         syntax_diagnostics(&sema, &parse, &mut res, file_id);
         diagnostics_from_descriptors(
             &mut res,
-            &sema,
+            &mut not_enabled,
+            db,
             file_id,
-            file_kind,
             config,
             &diagnostics_descriptors(),
+            &configured_diagnostics_descriptors(),
         );
 
-        let parse_diagnostics = parse.errors().iter().take(128).map(|err| {
-            let (code, message) = match err {
-                elp_syntax::SyntaxError::Error(_) => {
-                    (DiagnosticCode::SyntaxError, "Syntax Error".to_string())
-                }
-                elp_syntax::SyntaxError::Missing(m, _) => (
-                    DiagnosticCode::Missing("missing".to_string()),
-                    format!("Missing '{}'", m),
-                ),
-            };
-            Diagnostic::error(code, widen_range(err.range()), message)
-        });
-        let source_file = db.parse(file_id).tree();
-        label_syntax_errors(&source_file, parse_diagnostics)
+        parse_error_diagnostics(db, file_id)
     } else {
+        if file_kind == FileKind::Grammar {
+            stale_generated_file::stale_generated_file(&mut res, db, file_id);
+        }
         FxHashMap::default()
     };
+    if standalone_file::is_standalone(db, file_id) {
+        res.retain(|d| !standalone_file::is_project_dependent(&d.code));
+    }
     let metadata = db.elp_metadata(file_id);
+    if config.enabled.contains(&DiagnosticCode::UnusedSuppression) {
+        let reported: Vec<Diagnostic> = res.iter().chain(&not_enabled).cloned().collect();
+        let unused = unused_suppression::unused_suppressions(
+            db,
+            file_id,
+            &metadata,
+            unused_suppression::SuppressionSource::Native,
+            &reported,
+        );
+        res.extend(unused);
+    }
     // TODO: can we  ever disable DiagnosticCode::SyntaxError?
     //       In which case we must check labeled_syntax_errors
     res.retain(|d| {
@@ -823,38 +1062,80 @@ pub fn diagnostics_descriptors<'a>() -> Vec<&'a DiagnosticDescriptor<'a>> {
         &atoms_exhaustion::DESCRIPTOR,
         &boolean_precedence::DESCRIPTOR,
         &record_tuple_match::DESCRIPTOR,
+        &mnesia_schema::DESCRIPTOR,
+        &missing_on_load::DESCRIPTOR,
+        &invalid_attribute::DESCRIPTOR,
+        &message_tag_typo::DESCRIPTOR,
+        &unused_parameter::DESCRIPTOR,
+        &uncaught_throw::DESCRIPTOR,
+        &unused_record::DESCRIPTOR,
+        &redundant_clause::DESCRIPTOR,
+        &constant_condition::DESCRIPTOR,
+        &missing_callback::DESCRIPTOR,
+        &callback_mismatch::DESCRIPTOR,
+        &app_dependency_violation::DESCRIPTOR,
+        &legacy_collection::DESCRIPTOR,
+        &redundant_export::DESCRIPTOR,
+        &emptiness_check::DESCRIPTOR,
+        &incomplete_callbacks::DESCRIPTOR,
+    ]
+}
+
+pub fn configured_diagnostics_descriptors<'a>() -> Vec<&'a ConfiguredDiagnosticDescriptor<'a>> {
+    vec![
+        &return_value_ignored::DESCRIPTOR,
+        &missing_spec::DESCRIPTOR,
+        &api_migration::DESCRIPTOR,
+        &invalid_json_literal::DESCRIPTOR,
+        &denied_call::DESCRIPTOR,
+        &module_header::DESCRIPTOR,
+        &config_schema::DESCRIPTOR,
     ]
 }
 
+/// The diagnostics of default-disabled descriptors which are not
+/// enabled are put in `not_enabled` instead of `res`.
 pub fn diagnostics_from_descriptors(
     res: &mut Vec<Diagnostic>,
-    sema: &Semantic,
+    not_enabled: &mut Vec<Diagnostic>,
+    db: &RootDatabase,
     file_id: FileId,
-    file_kind: FileKind,
     config: &DiagnosticsConfig,
     descriptors: &[&DiagnosticDescriptor],
+    configured_descriptors: &[&ConfiguredDiagnosticDescriptor],
 ) {
-    let is_generated = sema.db.is_generated(file_id);
-    let is_test = sema
-        .db
-        .is_test_suite_or_test_helper(file_id)
-        .unwrap_or(false);
-    descriptors.into_iter().for_each(|descriptor| {
-        if descriptor.conditions.enabled(config, is_generated, is_test) {
-            if descriptor.conditions.default_disabled {
+    let sema = Semantic::new(db);
+    let file_kind = db.file_kind(file_id);
+    let is_generated = db.is_generated(file_id);
+    let is_test = db.is_test_suite_or_test_helper(file_id).unwrap_or(false);
+    let mut check = |conditions: &DiagnosticConditions, checker: &dyn Fn(&mut Vec<Diagnostic>)| {
+        if conditions.enabled(config, is_generated, is_test) {
+            if conditions.default_disabled {
                 // Filter the returned diagnostics to ensure they are
                 // enabled
                 let mut diags: Vec<Diagnostic> = Vec::default();
-                (descriptor.checker)(&mut diags, sema, file_id, file_kind);
+                checker(&mut diags);
                 for diag in diags {
                     if config.enabled.contains(&diag.code) {
                         res.push(diag);
+                    } else {
+                        not_enabled.push(diag);
                     }
                 }
             } else {
-                (descriptor.checker)(res, sema, file_id, file_kind);
+                checker(res);
             }
         }
+    };
+    descriptors.iter().for_each(|descriptor| {
+        check(&descriptor.conditions, &|diags| {
+            (descriptor.checker)(diags, &sema, file_id, file_kind)
+        })
+    });
+    configured_descriptors.iter().for_each(|descriptor| {
+        check(&descriptor.conditions, &|diags| {
+            (descriptor.checker)(diags, &sema, db, file_id, file_kind, config)
+        })
     });
 }
 
@@ -1161,7 +1442,8 @@ pub fn erlang_service_diagnostics(
 
         // Remove diagnostics kinds already reported by ELP
         let file_kind = db.file_kind(file_id);
-        let diags: Vec<(FileId, Diagnostic)> = if remove_elp_reported == RemoveElpReported::Yes {
+        let mut diags: Vec<(FileId, Diagnostic)> = if remove_elp_reported == RemoveElpReported::Yes
+        {
             diags
                 .into_iter()
                 .filter(|(_, d)| !is_implemented_in_elp(&d.code, file_kind))
@@ -1169,8 +1451,29 @@ pub fn erlang_service_diagnostics(
         } else {
             diags
         };
+        if standalone_file::is_standalone(db, file_id) {
+            diags.retain(|(_, d)| !standalone_file::is_project_dependent(&d.code));
+        }
 
         let metadata = db.elp_metadata(file_id);
+        if config.enabled.contains(&DiagnosticCode::UnusedSuppression) {
+            let own: Vec<Diagnostic> = diags
+                .iter()
+                .filter(|(d_file_id, _)| *d_file_id == file_id)
+                .map(|(_, d)| d.clone())
+                .collect();
+            diags.extend(
+                unused_suppression::unused_suppressions(
+                    db,
+                    file_id,
+                    &metadata,
+                    unused_suppression::SuppressionSource::ErlangService,
+                    &own,
+                )
+                .into_iter()
+                .map(|d| (file_id, d)),
+            );
+        }
         let diags = diags
             .into_iter()
             .filter(|(_file_id, d)| !d.should_be_suppressed(&metadata, config))
@@ -1455,6 +1758,8 @@ pub fn is_implemented_in_elp(code: &DiagnosticCode, file_kind: FileKind) -> bool
             DiagnosticCode::ErlangService(s) => match s.as_str() {
                 "P1700" => true, // "head mismatch"
                 "L1201" => true, // "no module definition"
+                "L1260" => true, // "record X is unused", see unused_record
+                "L1284" => true, // "undefined callback function", see missing_callback
                 _ => false,
             },
             _ => false,
@@ -1905,8 +2210,10 @@ baz(1)->4.
         let diag1 = DiagnosticCode::ErlangService("P1700".to_string());
         let diag2 = DiagnosticCode::ErlangService("L1201".to_string());
         let diag3 = DiagnosticCode::ErlangService("P1711".to_string());
+        let diag4 = DiagnosticCode::ErlangService("L1260".to_string());
+        let diag5 = DiagnosticCode::ErlangService("L1284".to_string());
         let diagk = DiagnosticCode::ErlangService("another diagnostic".to_string());
-        let diags = vec![diag1, diag2, diag3.clone(), diagk.clone()];
+        let diags = vec![diag1, diag2, diag3.clone(), diag4, diag5, diagk.clone()];
         assert_eq!(
             diags
                 .into_iter()
@@ -1990,6 +2297,7 @@ baz(1)->4.
         let fixture_str = r#"
   -module(main).
   -record(person, {(name + XXX)}).
+%%        ^^^^^^ 💡 warning: Unused record (person)
 %%                 ^^^^^^^ error: Syntax Error
 %%                            ^ error: Syntax Error
 "#;
@@ -2464,4 +2772,46 @@ baz(1)->4.
             "#,
         );
     }
+
+    #[test]
+    fn ranked_fixes_prefer_actual_fix() {
+        let range = TextRange::new(0.into(), 1.into());
+        let change = || SourceChange::from_text_edit(FileId::from_raw(0), TextEdit::delete(range));
+        let mut ignore = crate::fix("ignore_problem", "Ignore problem", change(), range);
+        ignore.group = Some(GroupLabel::ignore());
+        let diagnostic = Diagnostic::new(DiagnosticCode::UnexportedFunction, "msg", range)
+            .with_fixes(Some(vec![
+                ignore,
+                crate::fix("export_function", "Export function", change(), range),
+                crate::fix("remove_call", "Remove call", change(), range),
+            ]));
+        let ranked = |preferred_fixes| {
+            diagnostic
+                .ranked_fixes(&preferred_fixes)
+                .unwrap()
+                .iter()
+                .map(|fix| format!("{} {}", fix.id.0, fix.is_preferred))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ranked(FxHashMap::default()),
+            vec![
+                "export_function true",
+                "remove_call false",
+                "ignore_problem false"
+            ]
+        );
+        let configured = [(
+            DiagnosticCode::UnexportedFunction,
+            "remove_call".to_string(),
+        )];
+        assert_eq!(
+            ranked(configured.into_iter().collect()),
+            vec![
+                "remove_call true",
+                "export_function false",
+                "ignore_problem false"
+            ]
+        );
+    }
 }
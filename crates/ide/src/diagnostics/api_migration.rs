@@ -0,0 +1,341 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: api-migration
+//
+// Return a weak warning on a call to an OTP function superseded by
+// another one, such as the functions of the `string` module replaced when
+// it was made Unicode aware in OTP 20. A migration is only reported when
+// its replacement exists in the targeted OTP release, which is the
+// `target_otp_release` of `.elp_lint.toml`, or else the release of the
+// project's toolchain. Further migrations can be listed in the
+// `migrations` field of `.elp_lint.toml`. Offer to rewrite the call when
+// the replacement takes the same arguments. Generated files are not
+// checked.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_syntax::TextRange;
+use hir::Semantic;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use serde::Serialize;
+use text_edit::TextEdit;
+
+use super::helpers::project_otp_release;
+use crate::codemod_helpers::find_call_in_function;
+use crate::codemod_helpers::CheckCallCtx;
+use crate::codemod_helpers::MakeDiagCtx;
+use crate::diagnostics::ConfiguredDiagnosticDescriptor;
+use crate::diagnostics::Diagnostic;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::DiagnosticConditions;
+use crate::diagnostics::Severity;
+use crate::fix;
+use crate::FunctionMatch;
+use crate::MFA;
+
+/// A function superseded by another one.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ApiMigration {
+    pub from: MFA,
+    pub to: MFA,
+    /// The first OTP release providing `to`, if it is not always available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<u32>,
+    /// Whether `to` takes the arguments of `from` in the same order, so
+    /// that calls can be rewritten by replacing the function called.
+    #[serde(default)]
+    pub same_args: bool,
+}
+
+impl ApiMigration {
+    fn new(from: (&str, &str, u32), to: (&str, &str, u32), since: u32, same_args: bool) -> Self {
+        let mfa = |(module, name, arity): (&str, &str, u32)| MFA {
+            module: module.to_string(),
+            name: name.to_string(),
+            arity,
+        };
+        ApiMigration {
+            from: mfa(from),
+            to: mfa(to),
+            since: Some(since),
+            same_args,
+        }
+    }
+}
+
+lazy_static! {
+    static ref OTP_MIGRATIONS: Vec<ApiMigration> = vec![
+        // The separators of `lexemes` are grapheme clusters, so that
+        // `"\r\n"` no longer separates on a lone `$\n`.
+        ApiMigration::new(("string", "tokens", 2), ("string", "lexemes", 2), 20, false),
+        // `length` counts grapheme clusters rather than characters.
+        ApiMigration::new(("string", "len", 1), ("string", "length", 1), 20, false),
+        // `strip/1` only removes spaces, `trim/1` any whitespace.
+        ApiMigration::new(("string", "strip", 1), ("string", "trim", 1), 20, false),
+        // The direction and the characters of `strip` are given
+        // differently to `trim`.
+        ApiMigration::new(("string", "strip", 2), ("string", "trim", 2), 20, false),
+        ApiMigration::new(("string", "strip", 3), ("string", "trim", 3), 20, false),
+        // The old functions also accept a single character.
+        ApiMigration::new(("string", "to_upper", 1), ("string", "uppercase", 1), 20, false),
+        ApiMigration::new(("string", "to_lower", 1), ("string", "lowercase", 1), 20, false),
+        // Positions start at 1 in the old functions, at 0 in `slice`.
+        ApiMigration::new(("string", "substr", 2), ("string", "slice", 2), 20, false),
+        ApiMigration::new(("string", "substr", 3), ("string", "slice", 3), 20, false),
+        ApiMigration::new(("string", "sub_string", 2), ("string", "slice", 2), 20, false),
+        ApiMigration::new(("string", "sub_string", 3), ("string", "slice", 3), 20, false),
+        // `find` returns the rest of the string rather than a position.
+        ApiMigration::new(("string", "str", 2), ("string", "find", 2), 20, false),
+        ApiMigration::new(("string", "rstr", 2), ("string", "find", 3), 20, false),
+        ApiMigration::new(("string", "chr", 2), ("string", "find", 2), 20, false),
+        ApiMigration::new(("string", "rchr", 2), ("string", "find", 3), 20, false),
+        ApiMigration::new(("string", "words", 1), ("string", "lexemes", 2), 20, false),
+        ApiMigration::new(("string", "words", 2), ("string", "lexemes", 2), 20, false),
+        ApiMigration::new(("string", "left", 2), ("string", "pad", 2), 20, false),
+        ApiMigration::new(("string", "left", 3), ("string", "pad", 4), 20, false),
+        ApiMigration::new(("string", "right", 2), ("string", "pad", 3), 20, false),
+        ApiMigration::new(("string", "right", 3), ("string", "pad", 4), 20, false),
+        ApiMigration::new(("string", "centre", 2), ("string", "pad", 3), 20, false),
+        ApiMigration::new(("string", "centre", 3), ("string", "pad", 4), 20, false),
+        // `lists:join/2` takes the separator first and returns a deep list.
+        ApiMigration::new(("string", "join", 2), ("lists", "join", 2), 19, false),
+        ApiMigration::new(("random", "uniform", 0), ("rand", "uniform", 0), 18, true),
+        ApiMigration::new(("random", "uniform", 1), ("rand", "uniform", 1), 18, true),
+        ApiMigration::new(("random", "seed", 1), ("rand", "seed", 2), 18, false),
+        ApiMigration::new(("random", "seed", 3), ("rand", "seed", 2), 18, false),
+        ApiMigration::new(
+            ("crypto", "rand_bytes", 1),
+            ("crypto", "strong_rand_bytes", 1),
+            14,
+            true,
+        ),
+        ApiMigration::new(("crypto", "hmac", 3), ("crypto", "mac", 4), 22, false),
+        ApiMigration::new(("crypto", "hmac", 4), ("crypto", "macN", 5), 22, false),
+        // `handshake` returns the upgraded socket.
+        ApiMigration::new(("ssl", "ssl_accept", 1), ("ssl", "handshake", 1), 21, false),
+        ApiMigration::new(("ssl", "ssl_accept", 2), ("ssl", "handshake", 2), 21, false),
+        ApiMigration::new(("http_uri", "parse", 1), ("uri_string", "parse", 1), 21, false),
+        ApiMigration::new(("pg2", "join", 2), ("pg", "join", 2), 23, false),
+        ApiMigration::new(("pg2", "leave", 2), ("pg", "leave", 2), 23, false),
+        ApiMigration::new(("pg2", "get_members", 1), ("pg", "get_members", 1), 23, false),
+    ];
+}
+
+/// Whether calls to the function are reported as superseded by an OTP
+/// function, so that other diagnostics can leave them to this one.
+pub(crate) fn is_otp_migration(mfa: &MFA) -> bool {
+    OTP_MIGRATIONS
+        .iter()
+        .any(|migration| &migration.from == mfa)
+}
+
+pub(crate) static DESCRIPTOR: ConfiguredDiagnosticDescriptor = ConfiguredDiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, _db, file_id, _file_kind, config| {
+        api_migration(
+            diags,
+            sema,
+            file_id,
+            config.target_otp_release,
+            &config.migrations,
+        );
+    },
+};
+
+fn api_migration(
+    diagnostics: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    target_otp_release: Option<u32>,
+    migrations: &[ApiMigration],
+) {
+    let target = target_otp_release.or_else(|| project_otp_release(sema, file_id));
+    let matches: Vec<(FunctionMatch, &ApiMigration)> = OTP_MIGRATIONS
+        .iter()
+        .chain(migrations)
+        .filter(|migration| match (migration.since, target) {
+            (Some(since), Some(target)) => since <= target,
+            _ => true,
+        })
+        .map(|migration| {
+            let mfa = migration.from.clone();
+            (FunctionMatch::MFA { mfa }, migration)
+        })
+        .collect();
+    let mfas: Vec<(&FunctionMatch, &ApiMigration)> = matches
+        .iter()
+        .map(|(matcher, migration)| (matcher, *migration))
+        .collect();
+    sema.def_map(file_id).get_functions().for_each(|(_, def)| {
+        if def.file.file_id != file_id {
+            return;
+        }
+        find_call_in_function(
+            diagnostics,
+            sema,
+            def,
+            &mfas,
+            &|CheckCallCtx { t, .. }: CheckCallCtx<'_, &ApiMigration>| Some(*t),
+            &|MakeDiagCtx {
+                  range,
+                  range_mf_only,
+                  extra: migration,
+                  ..
+              }| Some(make_diagnostic(file_id, range, range_mf_only, migration)),
+        );
+    });
+}
+
+fn make_diagnostic(
+    file_id: FileId,
+    range: TextRange,
+    range_mf_only: Option<TextRange>,
+    migration: &ApiMigration,
+) -> Diagnostic {
+    let from = migration.from.label();
+    let to = migration.to.label();
+    let diagnostic = Diagnostic::new(
+        DiagnosticCode::ApiMigration,
+        format!("{from} is superseded by {to}"),
+        range,
+    )
+    .with_severity(Severity::WeakWarning);
+    match range_mf_only {
+        Some(mf_range) if migration.same_args => {
+            let edit = TextEdit::replace(
+                mf_range,
+                format!("{}:{}", migration.to.module, migration.to.name),
+            );
+            diagnostic.with_fixes(Some(vec![fix(
+                "migrate_call",
+                &format!("Replace with a call to {to}"),
+                SourceChange::from_text_edit(file_id, edit),
+                range,
+            )]))
+        }
+        _ => diagnostic,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::ApiMigration;
+    use crate::diagnostics::DiagnosticCode;
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+    use crate::MFA;
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig::default().disable(DiagnosticCode::UndefinedFunction)
+    }
+
+    #[test]
+    fn string_functions() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([words/1]).
+words(Line) ->
+    Words = string:tokens(string:strip(Line), " "),
+%%          ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ weak: string:tokens/2 is superseded by string:lexemes/2
+%%                        ^^^^^^^^^^^^^^^^^^ weak: string:strip/1 is superseded by string:trim/1
+    [string:to_upper(W) || W <- Words].
+%%   ^^^^^^^^^^^^^^^^^^ weak: string:to_upper/1 is superseded by string:uppercase/1
+"#,
+        );
+    }
+
+    #[test]
+    fn rewrite_call() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-export([roll/0]).
+roll() -> random:uni~form(6).
+"#,
+            expect![[r#"
+                -module(main).
+                -export([roll/0]).
+                roll() -> rand:uniform(6).
+            "#]],
+        );
+    }
+
+    #[test]
+    fn not_reported_before_target_release() {
+        check_diagnostics_with_config(
+            config().set_target_otp_release(Some(19)),
+            r#"
+-module(main).
+-export([words/1]).
+words(Line) -> string:tokens(Line, " ").
+"#,
+        );
+    }
+
+    #[test]
+    fn project_release() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- otp_version:17.5
+-module(main).
+-export([words/1]).
+words(Line) -> string:tokens(Line, " ").
+"#,
+        );
+    }
+
+    #[test]
+    fn not_in_generated_file() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+%% @generated
+-module(main).
+-export([words/1]).
+words(Line) -> string:tokens(Line, " ").
+"#,
+        );
+    }
+
+    #[test]
+    fn configured_migration() {
+        check_fix_with_config(
+            config().set_migrations(vec![ApiMigration {
+                from: MFA::new("old_lib", "fetch", 2),
+                to: MFA::new("new_lib", "get", 2),
+                since: None,
+                same_args: true,
+            }]),
+            r#"
+-module(main).
+-export([run/1]).
+run(Key) -> old_lib:fe~tch(Key, undefined).
+"#,
+            expect![[r#"
+                -module(main).
+                -export([run/1]).
+                run(Key) -> new_lib:get(Key, undefined).
+            "#]],
+        );
+    }
+}
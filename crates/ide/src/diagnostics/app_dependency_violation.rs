@@ -0,0 +1,223 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: app-dependency-violation
+//
+// Return a warning on a remote call to a module of another application,
+// when that application is not a dependency of the application of the
+// caller, as listed in its `.app.src` file or BUCK target. The call works
+// in the shell or in tests, where every application is loaded, but fails
+// with `undef` in a release missing the application. The dependencies of
+// the dependencies, as well as `erts`, `kernel` and `stdlib`, are always
+// available. Applications without listed dependencies are not checked.
+
+use std::sync::Arc;
+
+use elp_ide_db::elp_base_db::AppData;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::ProjectId;
+use elp_project_model::AppName;
+use fxhash::FxHashMap;
+use fxhash::FxHashSet;
+use hir::CallTarget;
+use hir::Semantic;
+use text_edit::TextRange;
+
+use super::Diagnostic;
+use super::DiagnosticCode;
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use super::Severity;
+use crate::codemod_helpers::find_call_in_function;
+use crate::codemod_helpers::CheckCallCtx;
+use crate::codemod_helpers::MakeDiagCtx;
+use crate::FunctionMatch;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: false,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        app_dependency_violation(diags, sema, file_id);
+    },
+};
+
+/// The applications started in every node.
+const ALWAYS_AVAILABLE: &[&str] = &["erts", "kernel", "stdlib"];
+
+fn app_dependency_violation(diagnostics: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let Some(app_data) = sema.db.file_app_data(file_id) else {
+        return;
+    };
+    if app_data.applications.is_empty() {
+        return;
+    }
+    let available = available_applications(sema, &app_data);
+    let matcher = FunctionMatch::any();
+    sema.def_map(file_id).get_functions().for_each(|(_, def)| {
+        if def.file.file_id != file_id {
+            return;
+        }
+        find_call_in_function(
+            diagnostics,
+            sema,
+            def,
+            &[(&matcher, ())],
+            &|CheckCallCtx {
+                  target,
+                  args,
+                  in_clause,
+                  ..
+              }: CheckCallCtx<'_, ()>| {
+                let CallTarget::Remote { .. } = target else {
+                    return None;
+                };
+                let callee =
+                    target.resolve_call(args.len() as u32, sema, file_id, &in_clause.body())?;
+                let callee_app = sema.db.file_app_data(callee.file.file_id)?;
+                if callee_app.name == app_data.name || available.contains(&callee_app.name) {
+                    return None;
+                }
+                let module = sema.module_name(callee.file.file_id)?;
+                Some((module.to_string(), callee_app.name.clone()))
+            },
+            &|ctx @ MakeDiagCtx {
+                  extra: (module, callee_app),
+                  ..
+              }| {
+                Some(make_diagnostic(
+                    ctx.range_mf_only(),
+                    module,
+                    callee_app,
+                    &app_data.name,
+                ))
+            },
+        );
+    });
+}
+
+/// The dependencies of the application, with their own dependencies.
+fn available_applications(sema: &Semantic, app_data: &AppData) -> FxHashSet<AppName> {
+    let dependencies = dependencies_by_app(sema, app_data.project_id);
+    let mut available: FxHashSet<AppName> = ALWAYS_AVAILABLE
+        .iter()
+        .map(|name| AppName(name.to_string()))
+        .collect();
+    let mut pending = app_data.applications.clone();
+    while let Some(name) = pending.pop() {
+        if available.insert(name.clone()) {
+            if let Some(app) = dependencies.get(&name) {
+                pending.extend(app.applications.iter().cloned());
+            }
+        }
+    }
+    available
+}
+
+/// The applications of the project and of its OTP, by name.
+fn dependencies_by_app(sema: &Semantic, project_id: ProjectId) -> FxHashMap<AppName, Arc<AppData>> {
+    let project = sema.db.project_data(project_id);
+    let otp_roots = project
+        .otp_project_id
+        .map(|otp_id| sema.db.project_data(otp_id).source_roots.clone())
+        .unwrap_or_default();
+    project
+        .source_roots
+        .iter()
+        .chain(otp_roots.iter())
+        .filter_map(|&source_root_id| sema.db.app_data(source_root_id))
+        .map(|app| (app.name.clone(), app))
+        .collect()
+}
+
+fn make_diagnostic(
+    range: TextRange,
+    module: &str,
+    callee_app: &AppName,
+    caller_app: &AppName,
+) -> Diagnostic {
+    Diagnostic::new(
+        DiagnosticCode::AppDependencyViolation,
+        format!(
+            "module {module} belongs to application {callee_app}, which is not a dependency of {caller_app}"
+        ),
+        range,
+    )
+    .with_severity(Severity::Warning)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn call_to_undeclared_application() {
+        check_diagnostics(
+            r#"
+//- /app_a/src/app_a.erl app:app_a deps:kernel,stdlib,app_b
+-module(app_a).
+-export([run/0]).
+run() ->
+    app_b:run(),
+    app_c:run(),
+%%  ^^^^^^^^^ warning: module app_c belongs to application app_c, which is not a dependency of app_a
+    app_d:run().
+//- /app_b/src/app_b.erl app:app_b deps:app_d
+-module(app_b).
+-export([run/0]).
+run() -> ok.
+//- /app_c/src/app_c.erl app:app_c
+-module(app_c).
+-export([run/0]).
+run() -> ok.
+//- /app_d/src/app_d.erl app:app_d
+-module(app_d).
+-export([run/0]).
+run() -> [].
+"#,
+        );
+    }
+
+    #[test]
+    fn dependencies_not_listed() {
+        check_diagnostics(
+            r#"
+//- /app_a/src/app_a.erl app:app_a
+-module(app_a).
+-export([run/0]).
+run() -> app_c:run().
+//- /app_c/src/app_c.erl app:app_c
+-module(app_c).
+-export([run/0]).
+run() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn calls_in_tests_not_checked() {
+        check_diagnostics(
+            r#"
+//- /app_a/src/app_a.erl app:app_a deps:kernel
+-module(app_a).
+//- /app_a/test/app_a_helper.erl app:app_a extra:test
+-module(app_a_helper).
+-export([run/0]).
+run() -> app_c:run().
+//- /app_c/src/app_c.erl app:app_c
+-module(app_c).
+-export([run/0]).
+run() -> ok.
+"#,
+        );
+    }
+}
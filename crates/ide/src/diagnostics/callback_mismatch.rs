@@ -0,0 +1,357 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: callback-mismatch
+//
+// Return a warning if an exported function has the name of a callback of
+// a behaviour of the module but another arity, while the callback itself
+// is not defined, or if the spec of a callback never matches the
+// `-callback` attribute of the behaviour. The types are only compared by
+// their shape: a tuple of some size, an atom, a list and so on, any type
+// defined by a module matching anything. The `-callback` attribute is
+// given as related information.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::ast::is_erlang_type;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use hir::CallbackDef;
+use hir::FunctionDef;
+use hir::Name;
+use hir::Semantic;
+
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use super::RelatedInformation;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::Severity;
+use crate::Diagnostic;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        callback_mismatch(diags, sema, file_id);
+    },
+};
+
+fn callback_mismatch(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let form_list = sema.form_list(file_id);
+    let def_map = sema.def_map(file_id);
+    let callbacks: Vec<(Name, CallbackDef)> = form_list
+        .behaviour_attributes()
+        .filter_map(|(_, behaviour)| {
+            let module = sema.resolve_module_name(file_id, behaviour.name.as_str())?;
+            let behaviour_def_map = sema.def_map(module.file.file_id);
+            Some(
+                behaviour_def_map
+                    .get_callbacks()
+                    .values()
+                    .map(|callback| (behaviour.name.clone(), callback.clone()))
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .flatten()
+        .collect();
+    if callbacks.is_empty() {
+        return;
+    }
+    for (name, def) in def_map.get_functions() {
+        if def.file.file_id != file_id || !def.exported {
+            continue;
+        }
+        let same_name = || {
+            callbacks
+                .iter()
+                .filter(|(_, callback)| callback.callback.name.name() == name.name())
+        };
+        if let Some((behaviour, callback)) =
+            same_name().find(|(_, callback)| &callback.callback.name == name)
+        {
+            if let Some(diag) = spec_mismatch(sema, def, behaviour, callback) {
+                diags.push(diag);
+            }
+        } else if let Some((behaviour, callback)) = same_name()
+            .find(|(_, callback)| def_map.get_function(&callback.callback.name).is_none())
+        {
+            let Some(range) = function_name_range(sema, def) else {
+                continue;
+            };
+            diags.push(make_diagnostic(
+                sema,
+                format!(
+                    "{name} does not match the callback {} of behaviour {behaviour}",
+                    callback.callback.name
+                ),
+                range,
+                callback,
+            ));
+        }
+    }
+}
+
+fn spec_mismatch(
+    sema: &Semantic,
+    def: &FunctionDef,
+    behaviour: &Name,
+    callback: &CallbackDef,
+) -> Option<Diagnostic> {
+    let spec = def.spec.as_ref()?.source(sema.db.upcast());
+    let callback_source = callback.source(sema.db.upcast());
+    let spec_sigs: Vec<ast::TypeSig> = spec.sigs().collect();
+    let callback_sigs: Vec<ast::TypeSig> = callback_source.sigs().collect();
+    let arity = def.name.arity() as usize;
+    let arg = |sigs: &[ast::TypeSig], index: usize| -> Option<Vec<Shape>> {
+        sigs.iter().try_fold(Vec::new(), |mut shapes, sig| {
+            shapes.extend(shapes_of(&sig.args()?.args().nth(index)?));
+            Some(shapes)
+        })
+    };
+    let ret = |sigs: &[ast::TypeSig]| -> Option<Vec<Shape>> {
+        sigs.iter().try_fold(Vec::new(), |mut shapes, sig| {
+            shapes.extend(shapes_of(&sig.ty()?));
+            Some(shapes)
+        })
+    };
+    let mismatch = (0..arity)
+        .find_map(|index| {
+            (!overlap(&arg(&spec_sigs, index)?, &arg(&callback_sigs, index)?))
+                .then(|| format!("argument {}", index + 1))
+        })
+        .or_else(|| {
+            (!overlap(&ret(&spec_sigs)?, &ret(&callback_sigs)?))
+                .then(|| "the return type".to_string())
+        })?;
+    Some(make_diagnostic(
+        sema,
+        format!(
+            "{mismatch} of the spec of {} never matches the callback of behaviour {behaviour}",
+            def.name
+        ),
+        spec.syntax().text_range(),
+        callback,
+    ))
+}
+
+fn make_diagnostic(
+    sema: &Semantic,
+    message: String,
+    range: TextRange,
+    callback: &CallbackDef,
+) -> Diagnostic {
+    Diagnostic::new(DiagnosticCode::CallbackMismatch, message, range)
+        .with_severity(Severity::Warning)
+        .with_related(Some(vec![RelatedInformation {
+            file_id: Some(callback.file.file_id),
+            range: callback.source(sema.db.upcast()).syntax().text_range(),
+            message: "Callback declared here".to_string(),
+        }]))
+}
+
+fn function_name_range(sema: &Semantic, def: &FunctionDef) -> Option<TextRange> {
+    let source = def.source(sema.db.upcast());
+    Some(source.first()?.name()?.syntax().text_range())
+}
+
+/// The kind of the values of a type, as far as can be told without
+/// resolving the types it refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Shape {
+    Any,
+    Atom(Option<String>),
+    /// The size and the tag of a tuple, if known
+    Tuple(Option<(usize, Option<String>)>),
+    List,
+    Map,
+    Integer,
+    Float,
+    Binary,
+    Fun,
+    Pid,
+    Port,
+    Reference,
+}
+
+fn overlap(left: &[Shape], right: &[Shape]) -> bool {
+    left.iter()
+        .any(|left| right.iter().any(|right| shapes_overlap(left, right)))
+}
+
+fn shapes_overlap(left: &Shape, right: &Shape) -> bool {
+    match (left, right) {
+        (Shape::Any, _) | (_, Shape::Any) => true,
+        (Shape::Atom(Some(left)), Shape::Atom(Some(right))) => left == right,
+        (
+            Shape::Tuple(Some((left_size, left_tag))),
+            Shape::Tuple(Some((right_size, right_tag))),
+        ) => {
+            left_size == right_size
+                && match (left_tag, right_tag) {
+                    (Some(left_tag), Some(right_tag)) => left_tag == right_tag,
+                    _ => true,
+                }
+        }
+        _ => std::mem::discriminant(left) == std::mem::discriminant(right),
+    }
+}
+
+fn shapes_of(ty: &ast::Expr) -> Vec<Shape> {
+    match ty {
+        ast::Expr::Pipe(pipe) => {
+            let mut shapes = pipe.lhs().map_or(vec![Shape::Any], |lhs| shapes_of(&lhs));
+            shapes.extend(pipe.rhs().map_or(vec![Shape::Any], |rhs| shapes_of(&rhs)));
+            shapes
+        }
+        ast::Expr::AnnType(ann) => ann.ty().map_or(vec![Shape::Any], |ty| shapes_of(&ty)),
+        ast::Expr::RangeType(_) => vec![Shape::Integer],
+        ast::Expr::MapExpr(_) => vec![Shape::Map],
+        ast::Expr::Call(call) => match (call.expr(), call.args()) {
+            (Some(ast::Expr::ExprMax(ast::ExprMax::Atom(atom))), Some(args)) => {
+                builtin_shapes(&atom_text(&atom), args.args().count())
+            }
+            _ => vec![Shape::Any],
+        },
+        ast::Expr::ExprMax(expr) => match expr {
+            ast::ExprMax::ParenExpr(paren) => {
+                paren.expr().map_or(vec![Shape::Any], |ty| shapes_of(&ty))
+            }
+            ast::ExprMax::Atom(atom) => vec![Shape::Atom(Some(atom_text(atom)))],
+            ast::ExprMax::Tuple(tuple) => {
+                let elements: Vec<ast::Expr> = tuple.expr().collect();
+                let tag = match elements.first() {
+                    Some(ast::Expr::ExprMax(ast::ExprMax::Atom(atom))) => Some(atom_text(atom)),
+                    _ => None,
+                };
+                vec![Shape::Tuple(Some((elements.len(), tag)))]
+            }
+            ast::ExprMax::List(_) | ast::ExprMax::String(_) => vec![Shape::List],
+            ast::ExprMax::Binary(_) => vec![Shape::Binary],
+            ast::ExprMax::Integer(_) | ast::ExprMax::Char(_) => vec![Shape::Integer],
+            ast::ExprMax::Float(_) => vec![Shape::Float],
+            ast::ExprMax::FunType(_) => vec![Shape::Fun],
+            _ => vec![Shape::Any],
+        },
+        ast::Expr::UnaryOpExpr(_) => vec![Shape::Integer],
+        _ => vec![Shape::Any],
+    }
+}
+
+/// The shapes of a predefined type, any other type being defined by a
+/// module.
+fn builtin_shapes(name: &str, arity: usize) -> Vec<Shape> {
+    if !is_erlang_type(name, arity) {
+        return vec![Shape::Any];
+    }
+    match name {
+        "atom" | "boolean" | "module" | "node" => vec![Shape::Atom(None)],
+        "arity" | "byte" | "char" | "integer" | "neg_integer" | "non_neg_integer"
+        | "pos_integer" => vec![Shape::Integer],
+        "float" => vec![Shape::Float],
+        "number" => vec![Shape::Integer, Shape::Float],
+        "binary" | "bitstring" | "nonempty_binary" | "nonempty_bitstring" => vec![Shape::Binary],
+        "iolist"
+        | "list"
+        | "maybe_improper_list"
+        | "nil"
+        | "nonempty_improper_list"
+        | "nonempty_list"
+        | "nonempty_maybe_improper_list"
+        | "nonempty_string"
+        | "string" => {
+            vec![Shape::List]
+        }
+        "iodata" => vec![Shape::List, Shape::Binary],
+        "map" => vec![Shape::Map],
+        "tuple" => vec![Shape::Tuple(None)],
+        "mfa" => vec![Shape::Tuple(Some((3, None)))],
+        "fun" | "function" => vec![Shape::Fun],
+        "pid" => vec![Shape::Pid],
+        "port" => vec![Shape::Port],
+        "reference" => vec![Shape::Reference],
+        "timeout" => vec![Shape::Integer, Shape::Atom(Some("infinity".to_string()))],
+        _ => vec![Shape::Any],
+    }
+}
+
+fn atom_text(atom: &ast::Atom) -> String {
+    atom.syntax()
+        .text()
+        .to_string()
+        .trim_matches('\'')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn arity_mismatch() {
+        check_diagnostics(
+            r#"
+//- /src/my_server.erl
+-module(my_server).
+-callback handle_call(Request :: term(), From :: term(), State :: term()) -> {reply, term(), term()}.
+//- /src/main.erl
+-module(main).
+-behaviour(my_server).
+%%<^^^^^^^^^^^^^^^^^^^ 💡 warning: missing callback of behaviour my_server: handle_call/3
+-export([handle_call/2]).
+handle_call(Request, State) -> {reply, Request, State}.
+%%<^^^^^^^^ warning: handle_call/2 does not match the callback handle_call/3 of behaviour my_server
+"#,
+        );
+    }
+
+    #[test]
+    fn spec_mismatch() {
+        check_diagnostics(
+            r#"
+//- /src/my_server.erl
+-module(my_server).
+-callback init(Args :: term()) -> {ok, State :: term()} | {stop, Reason :: term()}.
+-callback handle(Msg :: atom(), State :: term()) -> {noreply, State :: term()}.
+//- /src/main.erl
+-module(main).
+-behaviour(my_server).
+-export([init/1, handle/2]).
+-spec init(list()) -> ok.
+%%<^^^^^^^^^^^^^^^^^^^^^^ warning: the return type of the spec of init/1 never matches the callback of behaviour my_server
+init(_) -> ok.
+-spec handle(binary(), map()) -> {noreply, map()}.
+%%<^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ warning: argument 1 of the spec of handle/2 never matches the callback of behaviour my_server
+handle(_, State) -> {noreply, State}.
+"#,
+        );
+    }
+
+    #[test]
+    fn compatible_spec() {
+        check_diagnostics(
+            r#"
+//- /src/my_server.erl
+-module(my_server).
+-type state() :: map().
+-callback init(Args :: term()) -> {ok, state()} | ignore.
+//- /src/main.erl
+-module(main).
+-behaviour(my_server).
+-export([init/1, init/0]).
+-spec init(list()) -> {ok, #{atom() => term()}}.
+init(_) -> {ok, #{}}.
+init() -> init([]).
+"#,
+        );
+    }
+}
@@ -0,0 +1,473 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: config-schema-drift
+//
+// Return a warning on the accessor module generated from a `config_schemas`
+// entry of `.elp_lint.toml` when it no longer matches the schema, and when
+// the `sys.config` of the project disagrees with the schema: a key of the
+// schema without a value, a key of the application missing from the
+// schema, or a value not of the type of its key. Offer to regenerate the
+// module. As the module is marked as generated, it is only checked when
+// generated files are included, as they are by the language server.
+
+use std::collections::BTreeMap;
+use std::iter;
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::VfsPath;
+use elp_ide_db::source_change::SourceChange;
+use elp_project_model::term;
+use elp_project_model::term::Term;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use hir::Semantic;
+use serde::Deserialize;
+use serde::Serialize;
+use text_edit::TextEdit;
+
+use crate::diagnostics::ConfiguredDiagnosticDescriptor;
+use crate::diagnostics::Diagnostic;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::DiagnosticConditions;
+use crate::diagnostics::Severity;
+use crate::fix;
+
+/// The environment keys of an application, from which a module with one
+/// typed accessor per key is generated.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ConfigSchema {
+    pub app: String,
+    /// The name of the generated module.
+    pub module: String,
+    /// The `sys.config` giving the values, relative to the project root.
+    #[serde(default = "default_sys_config")]
+    pub sys_config: String,
+    /// The type of each key, as written in a spec, e.g. `inet:port_number()`.
+    pub keys: BTreeMap<String, String>,
+}
+
+fn default_sys_config() -> String {
+    "config/sys.config".to_string()
+}
+
+impl ConfigSchema {
+    /// The text of the accessor module.
+    pub fn generate_module(&self) -> String {
+        // The format macro is used to avoid marking this file as generated
+        let mut text = format!(
+            "%% {}generated from the config_schemas of .elp_lint.toml,\n\
+             %% regenerate it with `elp generate config` instead of editing it.\n\n",
+            "@"
+        );
+        text.push_str(&format!("-module({}).\n\n", atom_text(&self.module)));
+        let exports: Vec<String> = self
+            .keys
+            .keys()
+            .map(|key| format!("{}/0", accessor_name(key)))
+            .collect();
+        text.push_str(&format!("-export([{}]).\n", exports.join(", ")));
+        for (key, key_type) in &self.keys {
+            let name = accessor_name(key);
+            text.push_str(&format!(
+                "\n-spec {name}() -> {key_type}.\n\
+                 {name}() ->\n    \
+                 {{ok, Value}} = application:get_env({}, {}),\n    \
+                 Value.\n",
+                atom_text(&self.app),
+                atom_text(key)
+            ));
+        }
+        text
+    }
+}
+
+fn accessor_name(key: &str) -> String {
+    let key: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("get_{key}")
+}
+
+/// The atom, quoted when needed.
+fn atom_text(name: &str) -> String {
+    let mut chars = name.chars();
+    let plain = chars
+        .next()
+        .map_or(false, |first| first.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '@');
+    if plain {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+pub(crate) static DESCRIPTOR: ConfiguredDiagnosticDescriptor = ConfiguredDiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: true,
+        include_tests: false,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, _db, file_id, file_kind, config| {
+        if file_kind.is_module() {
+            config_schema_drift(diags, sema, file_id, &config.config_schemas);
+        }
+    },
+};
+
+fn config_schema_drift(
+    diagnostics: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    schemas: &[ConfigSchema],
+) {
+    if schemas.is_empty() {
+        return;
+    }
+    let db = sema.db;
+    let (Some(module), Some(app)) = (sema.module_name(file_id), db.file_app_name(file_id)) else {
+        return;
+    };
+    let Some(schema) = schemas
+        .iter()
+        .find(|schema| schema.module == module.as_str() && schema.app == app.as_str())
+    else {
+        return;
+    };
+    let source = sema.parse(file_id).value;
+    let Some(range) = source.forms().find_map(|form| match form {
+        ast::Form::ModuleAttribute(module) => Some(module.syntax().text_range()),
+        _ => None,
+    }) else {
+        return;
+    };
+    let text = db.file_text(file_id);
+    let generated = schema.generate_module();
+    if *text != generated {
+        let whole_file = TextRange::new(TextSize::from(0), TextSize::of(&*text));
+        diagnostics.push(
+            Diagnostic::new(
+                DiagnosticCode::ConfigSchemaDrift,
+                "Module differs from its config schema, regenerate it with `elp generate config`."
+                    .to_string(),
+                range,
+            )
+            .with_severity(Severity::Warning)
+            .with_fixes(Some(vec![fix(
+                "regenerate_config_module",
+                "Regenerate from the config schema",
+                SourceChange::from_text_edit(file_id, TextEdit::replace(whole_file, generated)),
+                range,
+            )])),
+        );
+    }
+    let Some(project_id) = db.file_project_id(file_id) else {
+        return;
+    };
+    let project = db.project_data(project_id);
+    let path = VfsPath::from(project.root_dir.join(&schema.sys_config));
+    // The `config` directory of the project is loaded along with the
+    // sources, a `sys.config` outside of any app is in the catch-all root
+    let sys_config = project
+        .source_roots
+        .iter()
+        .copied()
+        .chain(iter::once(db.catch_all_source_root()))
+        .find_map(|source_root_id| db.source_root(source_root_id).file_for_path(&path));
+    if let Some(sys_config) = sys_config {
+        diagnostics.extend(
+            sys_config_drift(schema, &db.file_text(sys_config))
+                .into_iter()
+                .map(|message| {
+                    Diagnostic::new(
+                        DiagnosticCode::ConfigSchemaDrift,
+                        format!("{}: {message}", schema.sys_config),
+                        range,
+                    )
+                    .with_severity(Severity::Warning)
+                }),
+        );
+    }
+}
+
+/// The disagreements between the schema and the text of a `sys.config`.
+pub fn sys_config_drift(schema: &ConfigSchema, sys_config: &str) -> Vec<String> {
+    let terms = match term::consult(sys_config) {
+        Ok(terms) => terms,
+        Err(err) => return vec![format!("could not be read: {err}")],
+    };
+    let [Term::List(apps)] = terms.as_slice() else {
+        return vec!["not a list of application environments".to_string()];
+    };
+    let env = apps.iter().find_map(|app| match app {
+        Term::Tuple(elements) => match elements.as_slice() {
+            [Term::Atom(name), Term::List(env)] if *name == schema.app => Some(env),
+            _ => None,
+        },
+        _ => None,
+    });
+    let mut values = BTreeMap::new();
+    let mut messages = Vec::new();
+    for entry in env.into_iter().flatten() {
+        let Term::Tuple(elements) = entry else {
+            continue;
+        };
+        let [Term::Atom(key), value] = elements.as_slice() else {
+            continue;
+        };
+        if !schema.keys.contains_key(key) {
+            messages.push(format!(
+                "`{key}` of `{}` is not in the config schema",
+                schema.app
+            ));
+        }
+        values.insert(key, value);
+    }
+    for (key, key_type) in &schema.keys {
+        match values.get(key) {
+            None => messages.push(format!("no value for `{key}` of `{}`", schema.app)),
+            Some(value) => {
+                if has_type(value, key_type) == Some(false) {
+                    messages.push(format!(
+                        "`{key}` of `{}` is `{value}`, which is not of type `{key_type}`",
+                        schema.app,
+                    ));
+                }
+            }
+        }
+    }
+    messages
+}
+
+/// Whether a value has the type, or `None` for the types the check does
+/// not know.
+fn has_type(value: &Term, key_type: &str) -> Option<bool> {
+    let key_type: String = key_type.chars().filter(|c| !c.is_whitespace()).collect();
+    let key_type = key_type.strip_suffix("()").unwrap_or(&key_type);
+    let integer = integer_value(value);
+    let is_float =
+        integer.is_none() && matches!(value, Term::Number(number) if number.parse::<f64>().is_ok());
+    let result = match key_type {
+        "integer" => integer.is_some(),
+        "pos_integer" => integer.map_or(false, |n| n > 0),
+        "non_neg_integer" => integer.map_or(false, |n| n >= 0),
+        "inet:port_number" => integer.map_or(false, |n| (0..=65535).contains(&n)),
+        "float" => is_float,
+        "number" => integer.is_some() || is_float,
+        "boolean" => matches!(value, Term::Atom(name) if name == "true" || name == "false"),
+        "atom" => matches!(value, Term::Atom(_)),
+        "binary" => matches!(value, Term::Binary(_)),
+        "string" | "list" => matches!(value, Term::String(_) | Term::List(_)),
+        "map" => matches!(value, Term::Map(_)),
+        "tuple" => matches!(value, Term::Tuple(_)),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// The value of an integer, written as by `consult`: with an optional
+/// sign, base and underscores, or as a character.
+fn integer_value(value: &Term) -> Option<i128> {
+    let Term::Number(number) = value else {
+        return None;
+    };
+    let (negative, number) = match number.strip_prefix('-') {
+        Some(number) => (true, number),
+        None => (false, number.as_str()),
+    };
+    let value = if let Some(char) = number.strip_prefix('$') {
+        let mut chars = char.chars();
+        let char = match (chars.next()?, chars.next()) {
+            ('\\', Some(escaped)) => match escaped {
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                's' => ' ',
+                other => other,
+            },
+            (c, None) => c,
+            _ => return None,
+        };
+        i128::from(u32::from(char))
+    } else {
+        let number = number.replace('_', "");
+        match number.split_once('#') {
+            Some((base, digits)) => i128::from_str_radix(digits, base.parse().ok()?).ok()?,
+            None => number.parse().ok()?,
+        }
+    };
+    Some(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use expect_test::expect;
+
+    use super::sys_config_drift;
+    use super::ConfigSchema;
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics_with_config;
+
+    fn schema() -> ConfigSchema {
+        ConfigSchema {
+            app: "shop".to_string(),
+            module: "shop_config".to_string(),
+            sys_config: "config/sys.config".to_string(),
+            keys: BTreeMap::from([
+                ("port".to_string(), "inet:port_number()".to_string()),
+                ("debug".to_string(), "boolean()".to_string()),
+                ("pool-size".to_string(), "pos_integer()".to_string()),
+            ]),
+        }
+    }
+
+    #[test]
+    fn generated_module() {
+        let text = schema().generate_module();
+        let (header, module) = text.split_once("\n\n").unwrap();
+        assert_eq!(header.lines().count(), 2);
+        expect![[r#"
+            -module(shop_config).
+
+            -export([get_debug/0, get_pool_size/0, get_port/0]).
+
+            -spec get_debug() -> boolean().
+            get_debug() ->
+                {ok, Value} = application:get_env(shop, debug),
+                Value.
+
+            -spec get_pool_size() -> pos_integer().
+            get_pool_size() ->
+                {ok, Value} = application:get_env(shop, 'pool-size'),
+                Value.
+
+            -spec get_port() -> inet:port_number().
+            get_port() ->
+                {ok, Value} = application:get_env(shop, port),
+                Value.
+        "#]]
+        .assert_eq(module);
+    }
+
+    #[test]
+    fn outdated_module() {
+        let config = DiagnosticsConfig::default().set_config_schemas(vec![schema()]);
+        check_diagnostics_with_config(
+            config,
+            r#"
+//- /src/shop_config.erl app:shop
+-module(shop_config).
+%%<^^^^^^^^^^^^^^^^^^^^ 💡 warning: Module differs from its config schema, regenerate it with `elp generate config`.
+-export([get_port/0]).
+get_port() -> 8080.
+"#,
+        );
+    }
+
+    #[test]
+    fn outdated_generated_module() {
+        let generated = schema().generate_module();
+        let header = generated.lines().next().unwrap();
+        let module = |annotation: &str| {
+            format!(
+                "//- /src/shop_config.erl app:shop\n\
+                 {header}\n\
+                 -module(shop_config).\n\
+                 {annotation}\
+                 -export([get_port/0]).\n\
+                 get_port() -> 8080.\n"
+            )
+        };
+        let config = DiagnosticsConfig::default().set_config_schemas(vec![schema()]);
+        check_diagnostics_with_config(config.clone(), &module(""));
+        check_diagnostics_with_config(
+            config.set_include_generated(true),
+            &module(
+                "%%<^^^^^^^^^^^^^^^^^^^^ 💡 warning: Module differs from its config schema, regenerate it with `elp generate config`.\n",
+            ),
+        );
+    }
+
+    #[test]
+    fn up_to_date_module() {
+        let config = DiagnosticsConfig::default().set_config_schemas(vec![schema()]);
+        check_diagnostics_with_config(
+            config,
+            &format!(
+                "//- /src/shop_config.erl app:shop\n{}",
+                schema().generate_module()
+            ),
+        );
+    }
+
+    #[test]
+    fn loaded_sys_config() {
+        let module = schema().generate_module().replace(
+            "-module(shop_config).\n",
+            "-module(shop_config).\n\
+             %%<^^^^^^^^^^^^^^^^^^^^ warning: config/sys.config: no value for `pool-size` of `shop`\n",
+        );
+        let config = DiagnosticsConfig::default()
+            .set_config_schemas(vec![schema()])
+            .set_include_generated(true);
+        check_diagnostics_with_config(
+            config,
+            &format!(
+                "//- /src/shop_config.erl app:shop\n\
+                 {module}\
+                 //- /config/sys.config app:shop\n\
+                 [{{shop, [{{port, 8080}}, {{debug, false}}]}}].\n"
+            ),
+        );
+    }
+
+    #[test]
+    fn sys_config_disagreements() {
+        let messages = sys_config_drift(
+            &schema(),
+            r#"
+[{kernel, [{logger_level, info}]},
+ {shop, [{port, 80_000},
+         {debug, "yes"},
+         {timeout, 5000}]}].
+"#,
+        );
+        expect![[r#"
+            [
+                "`timeout` of `shop` is not in the config schema",
+                "`debug` of `shop` is `\"yes\"`, which is not of type `boolean()`",
+                "no value for `pool-size` of `shop`",
+                "`port` of `shop` is `80_000`, which is not of type `inet:port_number()`",
+            ]
+        "#]]
+        .assert_debug_eq(&messages);
+    }
+
+    #[test]
+    fn sys_config_matching() {
+        let messages = sys_config_drift(
+            &schema(),
+            "[{shop, [{port, 8080}, {debug, false}, {'pool-size', 16#10}]}].",
+        );
+        assert!(messages.is_empty(), "{messages:?}");
+    }
+}
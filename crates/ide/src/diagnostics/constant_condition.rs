@@ -0,0 +1,635 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: constant-condition
+//
+// Return a warning if a guard test, or the condition of an `if` clause,
+// always evaluates to the same value. The guards are evaluated as far as
+// the values, or the kinds of values, they test are known: literals and
+// terms built from them, and the variables checked by an earlier type test
+// of the same guard. The message explains why the value is constant.
+
+use std::cmp::Ordering;
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::ast::BinaryOp;
+use elp_syntax::ast::CompOp;
+use elp_syntax::ast::LogicOp;
+use elp_syntax::ast::UnaryOp;
+use fxhash::FxHashMap;
+use hir::fold::MacroStrategy;
+use hir::fold::ParenStrategy;
+use hir::AnyExpr;
+use hir::CallTarget;
+use hir::ClauseId;
+use hir::Expr;
+use hir::ExprId;
+use hir::Guards;
+use hir::InFunctionClauseBody;
+use hir::Literal;
+use hir::Semantic;
+use hir::Strategy;
+use hir::Var;
+
+use super::Diagnostic;
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        constant_condition(diags, sema, file_id);
+    },
+};
+
+fn constant_condition(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    sema.for_each_function(file_id, |def| {
+        let def_fb = def.in_function_body(sema, ());
+        let mut guards: Vec<(ClauseId, &'static str, Guards)> = def_fb
+            .clauses()
+            .map(|(clause_id, clause)| (clause_id, "guard", clause.clause.guards.clone()))
+            .collect();
+        guards.extend(def_fb.fold_function(
+            Strategy {
+                macros: MacroStrategy::DoNotExpand,
+                parens: ParenStrategy::InvisibleParens,
+            },
+            Vec::new(),
+            &mut |mut acc, clause_id, ctx| {
+                let mut add = |what: &'static str, clause_guards: &Guards| {
+                    acc.push((clause_id, what, clause_guards.clone()))
+                };
+                match ctx.item {
+                    AnyExpr::Expr(Expr::If { clauses }) => {
+                        clauses.iter().for_each(|c| add("condition", &c.guards))
+                    }
+                    AnyExpr::Expr(Expr::Case { clauses, .. })
+                    | AnyExpr::Expr(Expr::Receive { clauses, .. })
+                    | AnyExpr::Expr(Expr::Maybe {
+                        else_clauses: clauses,
+                        ..
+                    }) => clauses.iter().for_each(|c| add("guard", &c.guards)),
+                    AnyExpr::Expr(Expr::Try {
+                        of_clauses,
+                        catch_clauses,
+                        ..
+                    }) => {
+                        of_clauses.iter().for_each(|c| add("guard", &c.guards));
+                        catch_clauses.iter().for_each(|c| add("guard", &c.guards));
+                    }
+                    AnyExpr::Expr(Expr::Closure { clauses, .. }) => {
+                        clauses.iter().for_each(|c| add("guard", &c.guards))
+                    }
+                    _ => {}
+                }
+                acc
+            },
+        ));
+        for (clause_id, what, guards) in guards {
+            let in_clause = def_fb.in_clause(clause_id);
+            for conjunction in guards {
+                let mut evaluator = Evaluator {
+                    in_clause,
+                    checked: FxHashMap::default(),
+                };
+                for test in conjunction {
+                    let eval = evaluator.eval(test);
+                    if let (Some(value), Some(reason)) = (eval.as_bool(), eval.reason) {
+                        if let Some(range) = in_clause.range_for_expr(test) {
+                            diags.push(
+                                Diagnostic::new(
+                                    DiagnosticCode::ConstantCondition,
+                                    format!("this {what} is always {value}: {reason}"),
+                                    range,
+                                )
+                                .with_severity(Severity::Warning),
+                            );
+                        }
+                    }
+                    evaluator.record_type_test(test);
+                }
+            }
+        }
+    });
+}
+
+/// The kinds of values, in the standard order of terms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Integer,
+    Float,
+    Atom,
+    Fun,
+    Tuple,
+    Map,
+    List,
+    Bitstring,
+}
+
+impl Kind {
+    /// The position of the kind in the standard order of terms, numbers
+    /// being compared by their value.
+    fn rank(self) -> u8 {
+        match self {
+            Kind::Integer | Kind::Float => 0,
+            Kind::Atom => 1,
+            Kind::Fun => 2,
+            Kind::Tuple => 3,
+            Kind::Map => 4,
+            Kind::List => 5,
+            Kind::Bitstring => 6,
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Kind::Integer => "an integer",
+            Kind::Float => "a float",
+            Kind::Atom => "an atom",
+            Kind::Fun => "a fun",
+            Kind::Tuple => "a tuple",
+            Kind::Map => "a map",
+            Kind::List => "a list",
+            Kind::Bitstring => "a binary",
+        }
+    }
+}
+
+/// The kinds of values a type test BIF returns `true` for, and whether it
+/// does for every value of these kinds.
+fn type_test(name: &str) -> Option<(&'static [Kind], bool)> {
+    let test: (&'static [Kind], bool) = match name {
+        "is_integer" => (&[Kind::Integer], true),
+        "is_float" => (&[Kind::Float], true),
+        "is_number" => (&[Kind::Integer, Kind::Float], true),
+        "is_atom" => (&[Kind::Atom], true),
+        "is_boolean" => (&[Kind::Atom], false),
+        "is_function" => (&[Kind::Fun], true),
+        "is_tuple" => (&[Kind::Tuple], true),
+        "is_map" => (&[Kind::Map], true),
+        "is_list" => (&[Kind::List], true),
+        "is_bitstring" => (&[Kind::Bitstring], true),
+        "is_binary" => (&[Kind::Bitstring], false),
+        _ => return None,
+    };
+    Some(test)
+}
+
+/// A constant value.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Integer(i128),
+    Float(f64),
+    Atom(String),
+    Tuple(Vec<Value>),
+    List(Vec<Value>),
+}
+
+impl Value {
+    fn kind(&self) -> Kind {
+        match self {
+            Value::Integer(_) => Kind::Integer,
+            Value::Float(_) => Kind::Float,
+            Value::Atom(_) => Kind::Atom,
+            Value::Tuple(_) => Kind::Tuple,
+            Value::List(_) => Kind::List,
+        }
+    }
+
+    fn bool(value: bool) -> Value {
+        Value::Atom(value.to_string())
+    }
+
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            Value::Integer(int) => Some(*int as f64),
+            Value::Float(float) => Some(*float),
+            _ => None,
+        }
+    }
+
+    /// Compares two values in the standard order of terms.
+    fn compare(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Atom(a), Value::Atom(b)) => a.cmp(b),
+            (Value::Tuple(a), Value::Tuple(b)) => {
+                a.len().cmp(&b.len()).then_with(|| compare_elements(a, b))
+            }
+            (Value::List(a), Value::List(b)) => {
+                compare_elements(a, b).then_with(|| a.len().cmp(&b.len()))
+            }
+            _ => match (self.as_number(), other.as_number()) {
+                (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+                _ => self.kind().rank().cmp(&other.kind().rank()),
+            },
+        }
+    }
+}
+
+fn compare_elements(a: &[Value], b: &[Value]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| a.compare(b))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+#[derive(Debug, Clone)]
+enum Term {
+    Value(Value),
+    /// A value of a known kind, with the name of the variable holding it,
+    /// if the kind is known from an earlier type test.
+    Kind(Kind, Option<String>),
+    Unknown,
+}
+
+impl Term {
+    fn kind(&self) -> Option<Kind> {
+        match self {
+            Term::Value(value) => Some(value.kind()),
+            Term::Kind(kind, _) => Some(*kind),
+            Term::Unknown => None,
+        }
+    }
+
+    /// A description of the term, to explain a comparison.
+    fn description(&self) -> Option<String> {
+        match self {
+            Term::Kind(kind, Some(var)) => {
+                Some(format!("`{var}`, checked to be {},", kind.description()))
+            }
+            _ => Some(self.kind()?.description().to_string()),
+        }
+    }
+}
+
+struct Eval {
+    term: Term,
+    /// Why a boolean value is constant, `None` for a literal.
+    reason: Option<String>,
+}
+
+impl Eval {
+    fn unknown() -> Eval {
+        Eval {
+            term: Term::Unknown,
+            reason: None,
+        }
+    }
+
+    fn term(term: Term) -> Eval {
+        Eval { term, reason: None }
+    }
+
+    fn bool(value: bool, reason: Option<String>) -> Eval {
+        Eval {
+            term: Term::Value(Value::bool(value)),
+            reason,
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match &self.term {
+            Term::Value(Value::Atom(atom)) if atom == "true" => Some(true),
+            Term::Value(Value::Atom(atom)) if atom == "false" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+struct Evaluator<'a, T> {
+    in_clause: &'a InFunctionClauseBody<'a, T>,
+    /// The kinds of the variables checked by the type tests evaluated
+    /// so far, which the value must have for the guard to succeed.
+    checked: FxHashMap<Var, Kind>,
+}
+
+impl<T> Evaluator<'_, T> {
+    fn eval(&self, expr_id: ExprId) -> Eval {
+        match &self.in_clause[expr_id] {
+            Expr::Literal(literal) => Eval::term(Term::Value(self.literal(literal))),
+            Expr::Var(var) => match self.checked.get(var) {
+                Some(kind) => Eval::term(Term::Kind(
+                    *kind,
+                    Some(var.as_string(self.in_clause.sema.db.upcast())),
+                )),
+                None => Eval::unknown(),
+            },
+            Expr::Tuple { exprs } => Eval::term(
+                self.values(exprs)
+                    .map(|values| Term::Value(Value::Tuple(values)))
+                    .unwrap_or(Term::Kind(Kind::Tuple, None)),
+            ),
+            Expr::List { exprs, tail: None } => Eval::term(
+                self.values(exprs)
+                    .map(|values| Term::Value(Value::List(values)))
+                    .unwrap_or(Term::Kind(Kind::List, None)),
+            ),
+            Expr::List { .. } => Eval::term(Term::Kind(Kind::List, None)),
+            Expr::Record { .. } => Eval::term(Term::Kind(Kind::Tuple, None)),
+            Expr::RecordIndex { .. } => Eval::term(Term::Kind(Kind::Integer, None)),
+            Expr::Map { .. } | Expr::MapUpdate { .. } => Eval::term(Term::Kind(Kind::Map, None)),
+            Expr::Binary { .. } => Eval::term(Term::Kind(Kind::Bitstring, None)),
+            Expr::Closure { .. } | Expr::CaptureFun { .. } => {
+                Eval::term(Term::Kind(Kind::Fun, None))
+            }
+            Expr::UnaryOp { expr, op } => self.eval_unary_op(*op, *expr),
+            Expr::BinaryOp { lhs, rhs, op } => match op {
+                BinaryOp::CompOp(op) => self.eval_comparison(*op, *lhs, *rhs),
+                BinaryOp::LogicOp(op) => self.eval_logic_op(*op, *lhs, *rhs),
+                _ => Eval::unknown(),
+            },
+            Expr::Call { target, args } => match (self.bif_name(target), args.as_slice()) {
+                (Some(name), [arg]) => self.eval_type_test(&name, *arg),
+                _ => Eval::unknown(),
+            },
+            Expr::Paren { expr } => self.eval(*expr),
+            // The expansion of a macro may differ between builds, such as
+            // `?TEST`, so it is not treated as constant.
+            _ => Eval::unknown(),
+        }
+    }
+
+    fn literal(&self, literal: &Literal) -> Value {
+        match literal {
+            Literal::String(string) => {
+                Value::List(string.chars().map(|c| Value::Integer(c as i128)).collect())
+            }
+            Literal::Char(c) => Value::Integer(*c as i128),
+            Literal::Atom(atom) => Value::Atom(
+                self.in_clause
+                    .sema
+                    .db
+                    .lookup_atom(*atom)
+                    .as_str()
+                    .to_string(),
+            ),
+            Literal::Integer(int) => Value::Integer(*int),
+            Literal::Float(bits) => Value::Float(f64::from_bits(*bits)),
+        }
+    }
+
+    fn values(&self, exprs: &[ExprId]) -> Option<Vec<Value>> {
+        exprs
+            .iter()
+            .map(|expr| match self.eval(*expr).term {
+                Term::Value(value) => Some(value),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The name of a call to a function of the `erlang` module.
+    fn bif_name(&self, target: &CallTarget<ExprId>) -> Option<String> {
+        let name = match target {
+            CallTarget::Local { name } => name,
+            CallTarget::Remote { module, name, .. } => {
+                if self.in_clause.as_atom_name(module)?.as_str() != "erlang" {
+                    return None;
+                }
+                name
+            }
+        };
+        Some(self.in_clause.as_atom_name(name)?.to_string())
+    }
+
+    fn eval_type_test(&self, name: &str, arg: ExprId) -> Eval {
+        let Some((kinds, complete)) = type_test(name) else {
+            return Eval::unknown();
+        };
+        let arg = self.eval(arg).term;
+        let Some(kind) = arg.kind() else {
+            return Eval::unknown();
+        };
+        let value = match &arg {
+            Term::Value(Value::Atom(atom)) if name == "is_boolean" => {
+                atom == "true" || atom == "false"
+            }
+            _ if !kinds.contains(&kind) => false,
+            _ if complete => true,
+            _ => return Eval::unknown(),
+        };
+        let reason = match arg {
+            Term::Value(Value::Atom(atom)) if name == "is_boolean" => {
+                let not = if value { "" } else { "not " };
+                format!("`{atom}` is {not}a boolean")
+            }
+            Term::Kind(_, Some(var)) => {
+                format!("`{var}` is checked to be {} earlier", kind.description())
+            }
+            _ => format!("{name}/1 is applied to {}", kind.description()),
+        };
+        Eval::bool(value, Some(reason))
+    }
+
+    fn eval_comparison(&self, op: CompOp, lhs: ExprId, rhs: ExprId) -> Eval {
+        let lhs = self.eval(lhs).term;
+        let rhs = self.eval(rhs).term;
+        if let (Term::Value(a), Term::Value(b)) = (&lhs, &rhs) {
+            let value = match op {
+                CompOp::Eq { strict, negated } => {
+                    let equal = if strict { a == b } else { a.compare(b).is_eq() };
+                    equal != negated
+                }
+                CompOp::Ord { ordering, strict } => holds(ordering, strict, a.compare(b)),
+            };
+            return Eval::bool(value, Some("both sides are constants".to_string()));
+        }
+        let (Some(a), Some(b)) = (lhs.kind(), rhs.kind()) else {
+            return Eval::unknown();
+        };
+        let (Some(a_description), Some(b_description)) = (lhs.description(), rhs.description())
+        else {
+            return Eval::unknown();
+        };
+        match op {
+            CompOp::Eq { strict, negated } => {
+                if a.rank() != b.rank() {
+                    let reason = format!("{a_description} is never equal to {b_description}");
+                    Eval::bool(negated, Some(reason))
+                } else if strict && a != b {
+                    let reason =
+                        format!("{a_description} is never exactly equal to {b_description}");
+                    Eval::bool(negated, Some(reason))
+                } else {
+                    Eval::unknown()
+                }
+            }
+            CompOp::Ord { ordering, strict } => {
+                let order = a.rank().cmp(&b.rank());
+                let relation = match order {
+                    Ordering::Less => "less than",
+                    Ordering::Greater => "greater than",
+                    Ordering::Equal => return Eval::unknown(),
+                };
+                let reason = format!("{a_description} always compares {relation} {b_description}");
+                Eval::bool(holds(ordering, strict, order), Some(reason))
+            }
+        }
+    }
+
+    fn eval_unary_op(&self, op: UnaryOp, expr: ExprId) -> Eval {
+        let eval = self.eval(expr);
+        if op == UnaryOp::Not {
+            return match eval.as_bool() {
+                Some(value) => Eval::bool(!value, eval.reason),
+                None => Eval::unknown(),
+            };
+        }
+        let term = match (op, eval.term) {
+            (UnaryOp::Minus, Term::Value(Value::Integer(int))) => Term::Value(Value::Integer(-int)),
+            (UnaryOp::Minus, Term::Value(Value::Float(float))) => Term::Value(Value::Float(-float)),
+            (UnaryOp::Plus, term @ Term::Value(Value::Integer(_) | Value::Float(_))) => term,
+            _ => Term::Unknown,
+        };
+        Eval::term(term)
+    }
+
+    fn eval_logic_op(&self, op: LogicOp, lhs: ExprId, rhs: ExprId) -> Eval {
+        let lhs = self.eval(lhs);
+        let rhs = self.eval(rhs);
+        match (op, lhs.as_bool(), rhs.as_bool()) {
+            // A guard raising an exception fails, like one evaluating to
+            // `false`, so a single `false` operand is enough.
+            (LogicOp::And { .. }, Some(false), _) => Eval::bool(false, lhs.reason),
+            (LogicOp::And { .. }, _, Some(false)) => Eval::bool(false, rhs.reason),
+            (LogicOp::And { .. }, Some(true), Some(true)) => {
+                Eval::bool(true, lhs.reason.or(rhs.reason))
+            }
+            (LogicOp::Or { lazy: true }, Some(true), _) => Eval::bool(true, lhs.reason),
+            (LogicOp::Or { .. }, Some(a), Some(b)) => Eval::bool(a || b, lhs.reason.or(rhs.reason)),
+            (LogicOp::Xor, Some(a), Some(b)) => Eval::bool(a != b, lhs.reason.or(rhs.reason)),
+            _ => Eval::unknown(),
+        }
+    }
+
+    /// Records the kind of the variable checked by a type test, which
+    /// holds for the rest of the guard.
+    fn record_type_test(&mut self, test: ExprId) {
+        let Expr::Call { target, args } = &self.in_clause[test] else {
+            return;
+        };
+        let (Some(name), [arg]) = (self.bif_name(target), args.as_slice()) else {
+            return;
+        };
+        if let (Some((&[kind], true)), Expr::Var(var)) = (type_test(&name), &self.in_clause[*arg]) {
+            self.checked.entry(*var).or_insert(kind);
+        }
+    }
+}
+
+/// Whether a comparison of the given ordering holds for two values
+/// ordered as `order`.
+fn holds(ordering: ast::Ordering, strict: bool, order: Ordering) -> bool {
+    match (ordering, strict) {
+        (ast::Ordering::Less, true) => order.is_lt(),
+        (ast::Ordering::Less, false) => order.is_le(),
+        (ast::Ordering::Greater, true) => order.is_gt(),
+        (ast::Ordering::Greater, false) => order.is_ge(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn type_tests_of_literals() {
+        check_diagnostics(
+            r#""
+-module(main).
+-export([f/1]).
+f(X) when is_list([]) -> X;
+%%        ^^^^^^^^^^^ warning: this guard is always true: is_list/1 is applied to a list
+f(X) when is_atom({X, 1}) -> X;
+%%        ^^^^^^^^^^^^^^^ warning: this guard is always false: is_atom/1 is applied to a tuple
+f(X) when is_boolean(ok); is_number(-1.5) -> X.
+%%        ^^^^^^^^^^^^^^ warning: this guard is always false: `ok` is not a boolean
+%%                        ^^^^^^^^^^^^^^^ warning: this guard is always true: is_number/1 is applied to a float
+"#,
+        );
+    }
+
+    #[test]
+    fn comparisons() {
+        check_diagnostics(
+            r#""
+-module(main).
+-export([f/1]).
+f(X) ->
+    case X of
+        Y when {Y} =:= ok -> Y;
+%%             ^^^^^^^^^^ warning: this guard is always false: a tuple is never equal to an atom
+        Y when 1 =:= 1.0 -> Y;
+%%             ^^^^^^^^^^ warning: this guard is always false: both sides are constants
+        Y when 1 == 1.0 -> Y;
+%%             ^^^^^^^^ warning: this guard is always true: both sides are constants
+        Y when [Y] > {Y} -> Y;
+%%             ^^^^^^^^^ warning: this guard is always true: a list always compares greater than a tuple
+        Y when Y > 1 -> Y
+    end.
+"#,
+        );
+    }
+
+    #[test]
+    fn if_conditions() {
+        check_diagnostics(
+            r#""
+-module(main).
+-export([f/1]).
+f(X) ->
+    if
+        not is_tuple(#{}) -> X;
+%%      ^^^^^^^^^^^^^^^^^ warning: this condition is always true: is_tuple/1 is applied to a map
+        X > 0 andalso is_map(<<>>) -> X;
+%%      ^^^^^^^^^^^^^^^^^^^^^^^^^^ warning: this condition is always false: is_map/1 is applied to a binary
+        true -> X
+    end.
+"#,
+        );
+    }
+
+    #[test]
+    fn checked_by_earlier_test() {
+        check_diagnostics(
+            r#""
+-module(main).
+-export([f/1]).
+f(X) when is_integer(X), is_atom(X) -> X;
+%%                       ^^^^^^^^^^ warning: this guard is always false: `X` is checked to be an integer earlier
+f(X) when is_atom(X), X > 1 -> X;
+%%                    ^^^^^ warning: this guard is always true: `X`, checked to be an atom, always compares greater than an integer
+f(X) when is_integer(X); is_atom(X) -> X.
+"#,
+        );
+    }
+
+    #[test]
+    fn not_constant() {
+        check_diagnostics(
+            r#""
+-module(main).
+-export([f/1]).
+-define(ENABLED, true).
+f(X) when is_list(X), X =/= [] -> X;
+f(X) when ?ENABLED, is_number(X) -> X;
+f(X) when true -> X.
+"#,
+        );
+    }
+}
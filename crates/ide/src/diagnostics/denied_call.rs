@@ -0,0 +1,234 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: denied-call
+//
+// Return a diagnostic on a call to a function listed in the `denied_calls`
+// field of `.elp_lint.toml`, with the message and severity given there.
+// Calls in test suites and their helpers are only reported for the
+// entries setting `include_tests`, and generated files are not checked.
+// Offer to call the replacement of the entry instead, when it has the
+// arity of the denied function.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_syntax::TextRange;
+use hir::Semantic;
+use serde::Deserialize;
+use serde::Serialize;
+use text_edit::TextEdit;
+
+use crate::codemod_helpers::find_call_in_function;
+use crate::codemod_helpers::CheckCallCtx;
+use crate::codemod_helpers::MakeDiagCtx;
+use crate::diagnostics::ConfiguredDiagnosticDescriptor;
+use crate::diagnostics::Diagnostic;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::DiagnosticConditions;
+use crate::diagnostics::Severity;
+use crate::fix;
+use crate::FunctionMatch;
+use crate::MFA;
+
+/// A function the project does not want called.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DeniedCall {
+    pub mfa: MFA,
+    /// Why the function must not be called, or what to do instead.
+    pub message: String,
+    #[serde(default)]
+    pub severity: Severity,
+    /// The function to call instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<MFA>,
+    /// Whether calls in test suites and test helpers are reported too.
+    #[serde(default)]
+    pub include_tests: bool,
+}
+
+pub(crate) static DESCRIPTOR: ConfiguredDiagnosticDescriptor = ConfiguredDiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        // Each entry tells whether tests are checked
+        include_tests: true,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, _db, file_id, _file_kind, config| {
+        denied_call(diags, sema, file_id, &config.denied_calls);
+    },
+};
+
+fn denied_call(
+    diagnostics: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    denied_calls: &[DeniedCall],
+) {
+    let is_test = sema
+        .db
+        .is_test_suite_or_test_helper(file_id)
+        .unwrap_or(false);
+    let matches: Vec<(FunctionMatch, &DeniedCall)> = denied_calls
+        .iter()
+        .filter(|denied| !is_test || denied.include_tests)
+        .map(|denied| {
+            let mfa = denied.mfa.clone();
+            (FunctionMatch::MFA { mfa }, denied)
+        })
+        .collect();
+    if matches.is_empty() {
+        return;
+    }
+    let mfas: Vec<(&FunctionMatch, &DeniedCall)> = matches
+        .iter()
+        .map(|(matcher, denied)| (matcher, *denied))
+        .collect();
+    sema.def_map(file_id).get_functions().for_each(|(_, def)| {
+        if def.file.file_id != file_id {
+            return;
+        }
+        find_call_in_function(
+            diagnostics,
+            sema,
+            def,
+            &mfas,
+            &|CheckCallCtx { t, .. }: CheckCallCtx<'_, &DeniedCall>| Some(*t),
+            &|MakeDiagCtx {
+                  range,
+                  range_mf_only,
+                  extra: denied,
+                  ..
+              }| Some(make_diagnostic(file_id, range, range_mf_only, denied)),
+        );
+    });
+}
+
+fn make_diagnostic(
+    file_id: FileId,
+    range: TextRange,
+    range_mf_only: Option<TextRange>,
+    denied: &DeniedCall,
+) -> Diagnostic {
+    let diagnostic = Diagnostic::new(
+        DiagnosticCode::DeniedCall,
+        format!("{} is denied: {}", denied.mfa.label(), denied.message),
+        range,
+    )
+    .with_severity(denied.severity);
+    match (range_mf_only, &denied.replacement) {
+        (Some(mf_range), Some(to)) if to.arity == denied.mfa.arity => {
+            let edit = TextEdit::replace(mf_range, format!("{}:{}", to.module, to.name));
+            diagnostic.with_fixes(Some(vec![fix(
+                "replace_denied_call",
+                &format!("Replace with a call to {}", to.label()),
+                SourceChange::from_text_edit(file_id, edit),
+                range,
+            )]))
+        }
+        _ => diagnostic,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::DeniedCall;
+    use crate::diagnostics::DiagnosticCode;
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::diagnostics::Severity;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+    use crate::MFA;
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig::default()
+            .disable(DiagnosticCode::UndefinedFunction)
+            .set_denied_calls(vec![
+                DeniedCall {
+                    mfa: MFA::new("io", "format", 2),
+                    message: "use logger in production code".to_string(),
+                    severity: Severity::Warning,
+                    replacement: Some(MFA::new("logger", "info", 2)),
+                    include_tests: false,
+                },
+                DeniedCall {
+                    mfa: MFA::new("os", "cmd", 1),
+                    message: "run commands through open_port/2".to_string(),
+                    severity: Severity::Error,
+                    replacement: None,
+                    include_tests: true,
+                },
+            ])
+    }
+
+    #[test]
+    fn denied_calls() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([run/1]).
+run(Cmd) ->
+    io:format("running ~s~n", [Cmd]),
+%%  ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ 💡 warning: io:format/2 is denied: use logger in production code
+    io:format("done~n"),
+    os:cmd(Cmd).
+%%  ^^^^^^^^^^^ error: os:cmd/1 is denied: run commands through open_port/2
+"#,
+        );
+    }
+
+    #[test]
+    fn tests_only_checked_when_included() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /main/test/main_SUITE.erl app:main extra:test
+-module(main_SUITE).
+-export([run/1]).
+run(Cmd) ->
+    io:format("running ~s~n", [Cmd]),
+    os:cmd(Cmd).
+%%  ^^^^^^^^^^^ error: os:cmd/1 is denied: run commands through open_port/2
+"#,
+        );
+    }
+
+    #[test]
+    fn not_in_generated_file() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+%% @generated
+-module(main).
+-export([run/1]).
+run(Cmd) -> os:cmd(Cmd).
+"#,
+        );
+    }
+
+    #[test]
+    fn replace_denied_call() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-export([run/1]).
+run(Cmd) -> io:for~mat("running ~s~n", [Cmd]).
+"#,
+            expect![[r#"
+                -module(main).
+                -export([run/1]).
+                run(Cmd) -> logger:info("running ~s~n", [Cmd]).
+            "#]],
+        );
+    }
+}
@@ -16,6 +16,10 @@
 // XRef itself ignores that field, which is intended to be used by other tools.
 // This diagnostic does just that and shows the message at the call-site.
 // It also provides a mechanism to augment deprecation information with extra information, such as a URI.
+// Calls to OTP functions which cannot be resolved, such as removed ones, are checked against
+// the `obsolete/3` table of the `otp_internal` module of the project's OTP, as the compiler does.
+// Calls reported by the `api-migration` diagnostic are left to it.
+// The `next_version`, `next_major_release` and `eventually` descriptions give the severity.
 
 use elp_ide_assists::Assist;
 use elp_ide_db::elp_base_db::FileId;
@@ -23,8 +27,12 @@ use elp_ide_db::source_change::SourceChange;
 use elp_syntax::AstNode;
 use hir::fold::MacroStrategy;
 use hir::fold::ParenStrategy;
+use hir::form_list::DeprecatedDesc;
 use hir::AnyExpr;
+use hir::Body;
+use hir::CallTarget;
 use hir::Expr;
+use hir::ExprId;
 use hir::FunctionDef;
 use hir::Semantic;
 use hir::Strategy;
@@ -33,6 +41,7 @@ use text_edit::TextEdit;
 use text_edit::TextRange;
 use text_edit::TextSize;
 
+use super::api_migration::is_otp_migration;
 use super::Diagnostic;
 use super::DiagnosticCode;
 use super::DiagnosticConditions;
@@ -42,6 +51,7 @@ use crate::codemod_helpers::FunctionMatch;
 use crate::codemod_helpers::FunctionMatcher;
 // @fb-only
 use crate::fix;
+use crate::MFA;
 
 pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
     conditions: DiagnosticConditions {
@@ -83,6 +93,95 @@ impl DeprecationDetails {
     }
 }
 
+/// When a deprecated function is going to be removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Removal {
+    NextVersion,
+    NextMajorRelease,
+    Eventually,
+}
+
+impl Removal {
+    fn from_atom(atom: &str) -> Option<Removal> {
+        match atom {
+            "next_version" => Some(Removal::NextVersion),
+            "next_major_release" => Some(Removal::NextMajorRelease),
+            "eventually" => Some(Removal::Eventually),
+            _ => None,
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Removal::NextVersion => "It will be removed in the next version.",
+            Removal::NextMajorRelease => "It will be removed in the next major release.",
+            Removal::Eventually => "It will be removed in a future release.",
+        }
+    }
+
+    fn severity(self) -> Severity {
+        match self {
+            Removal::NextVersion | Removal::NextMajorRelease => Severity::Warning,
+            Removal::Eventually => Severity::WeakWarning,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Deprecation {
+    mfa: MFA,
+    /// Why the function is deprecated, usually naming its replacement
+    text: Option<String>,
+    removal: Option<Removal>,
+    /// Whether the function no longer exists in OTP
+    removed: bool,
+}
+
+impl Deprecation {
+    fn from_def(sema: &Semantic, def: &FunctionDef) -> Option<Deprecation> {
+        let module = sema.module_name(def.file.file_id)?;
+        let (text, removal) = match &def.deprecated_desc {
+            Some(DeprecatedDesc::Str(desc)) => (Some(desc.to_string()), None),
+            Some(DeprecatedDesc::Atom(atom)) => match Removal::from_atom(atom) {
+                Some(removal) => (None, Some(removal)),
+                None => (Some(atom.to_string()), None),
+            },
+            None => (None, None),
+        };
+        Some(Deprecation {
+            mfa: MFA::new(module.as_str(), def.name.name().as_str(), def.name.arity()),
+            text,
+            removal,
+            removed: false,
+        })
+    }
+
+    /// The deprecation of a call to an OTP function which does not
+    /// resolve, from the `otp_internal` module of the project's OTP.
+    fn from_otp(
+        sema: &Semantic,
+        file_id: FileId,
+        target: &CallTarget<ExprId>,
+        arity: u32,
+        body: &Body,
+    ) -> Option<Deprecation> {
+        let CallTarget::Remote { module, name, .. } = target else {
+            return None;
+        };
+        let module = sema.db.lookup_atom(body[*module].as_atom()?);
+        let name = sema.db.lookup_atom(body[*name].as_atom()?);
+        let project_id = sema.db.file_app_data(file_id)?.project_id;
+        let deprecations = sema.db.otp_deprecations(project_id);
+        let deprecation = deprecations.get(module.as_str(), name.as_str(), arity)?;
+        Some(Deprecation {
+            mfa: MFA::new(module.as_str(), name.as_str(), arity),
+            text: deprecation.text.clone(),
+            removal: None,
+            removed: deprecation.removed,
+        })
+    }
+}
+
 fn deprecated_function(diagnostics: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
     lazy_static! {
         static ref DEPRECATED_FUNCTIONS: Vec<(FunctionMatch, DeprecationDetails)> = {
@@ -121,34 +220,32 @@ fn check_function(
         &mut |acc, clause_id, ctx| {
             if let AnyExpr::Expr(Expr::Call { target, args }) = ctx.item {
                 let arity = args.len() as u32;
-                if let Some(target_def) =
-                    target.resolve_call(arity, sema, def_fb.file_id(), &def_fb.body(clause_id))
-                {
-                    let match_result = matcher.get_match(
-                        &target,
-                        args.len() as u32,
-                        Some(&args),
-                        sema,
-                        &def_fb.body(clause_id),
-                    );
-                    let details = match_result.map(|(_match, details)| details.clone());
-                    if target_def.deprecated || match_result.is_some() {
-                        let expr_id = if let Some(expr_id) = ctx.in_macro {
-                            expr_id.idx
-                        } else {
-                            ctx.item_id
-                        };
-                        if let Some(range) = def_fb.range_for_any(clause_id, expr_id) {
-                            let d = make_diagnostic(range, &target_def, details)
-                                .with_fixes(Some(vec![fix_xref_ignore(
-                                    sema,
-                                    def_fb.file_id(),
-                                    &target_def,
-                                    range,
-                                )]))
-                                .with_ignore_fix(sema, def_fb.file_id());
-                            diagnostics.push(d)
-                        }
+                let body = def_fb.body(clause_id);
+                let match_result = matcher.get_match(&target, arity, Some(&args), sema, &body);
+                let details = match_result.map(|(_match, details)| details.clone());
+                let deprecation = match target.resolve_call(arity, sema, def_fb.file_id(), &body) {
+                    Some(target_def) if target_def.deprecated || match_result.is_some() => {
+                        Deprecation::from_def(sema, &target_def)
+                    }
+                    Some(_) => None,
+                    None => Deprecation::from_otp(sema, def_fb.file_id(), &target, arity, &body),
+                };
+                if let Some(deprecation) = deprecation.filter(|d| !is_otp_migration(&d.mfa)) {
+                    let expr_id = if let Some(expr_id) = ctx.in_macro {
+                        expr_id.idx
+                    } else {
+                        ctx.item_id
+                    };
+                    if let Some(range) = def_fb.range_for_any(clause_id, expr_id) {
+                        let d = make_diagnostic(range, &deprecation, details)
+                            .with_fixes(Some(vec![fix_xref_ignore(
+                                sema,
+                                def_fb.file_id(),
+                                &deprecation.mfa,
+                                range,
+                            )]))
+                            .with_ignore_fix(sema, def_fb.file_id());
+                        diagnostics.push(d)
                     }
                 }
             };
@@ -159,18 +256,27 @@ fn check_function(
 
 fn make_diagnostic(
     range: TextRange,
-    def: &FunctionDef,
+    deprecation: &Deprecation,
     details: Option<DeprecationDetails>,
 ) -> Diagnostic {
-    let base_message = format!("Function '{}' is deprecated.", def.name);
-    let base_message = match &def.deprecated_desc {
-        Some(desc) => {
-            let desc = desc.to_string();
-            let desc = strip_quotes(desc.as_str());
-            format!("{base_message}\n{desc}")
-        }
-        None => base_message,
+    let status = if deprecation.removed {
+        "has been removed"
+    } else {
+        "is deprecated"
     };
+    let mut base_message = format!(
+        "Function '{}/{}' {status}.",
+        deprecation.mfa.name, deprecation.mfa.arity
+    );
+    if let Some(text) = &deprecation.text {
+        base_message = format!("{base_message}\n{text}");
+    }
+    if let Some(removal) = deprecation.removal {
+        base_message = format!("{base_message}\n{}", removal.description());
+    }
+    let default_severity = deprecation
+        .removal
+        .map_or(Severity::Warning, Removal::severity);
     let (severity, uri, message) = match details {
         Some(DeprecationDetails {
             severity,
@@ -183,7 +289,7 @@ fn make_diagnostic(
             };
             (severity, uri, message)
         }
-        None => (Severity::Warning, None, base_message),
+        None => (default_severity, None, base_message),
     };
     Diagnostic::new(DiagnosticCode::DeprecatedFunction, message, range)
         .with_severity(severity)
@@ -191,18 +297,7 @@ fn make_diagnostic(
         .experimental()
 }
 
-fn strip_quotes(s: &str) -> &str {
-    let quote = "\"";
-    let s = s.strip_prefix(quote).unwrap_or(s);
-    s.strip_suffix(quote).unwrap_or(s)
-}
-
-fn fix_xref_ignore(
-    sema: &Semantic,
-    file_id: FileId,
-    def: &FunctionDef,
-    range: TextRange,
-) -> Assist {
+fn fix_xref_ignore(sema: &Semantic, file_id: FileId, mfa: &MFA, range: TextRange) -> Assist {
     let source = sema.parse(file_id).value;
     let form_list = sema.form_list(file_id);
 
@@ -213,13 +308,9 @@ fn fix_xref_ignore(
         TextSize::from(0)
     };
 
-    let module = sema.module_name(def.file.file_id).unwrap();
-
     let text = format!(
         "-ignore_xref([{{{}, {}, {}}}]).\n",
-        module.as_str(),
-        def.name.name(),
-        def.name.arity()
+        mfa.module, mfa.name, mfa.arity
     );
     let mut edit_builder = TextEdit::builder();
     edit_builder.insert(offset, text);
@@ -239,7 +330,10 @@ mod tests {
 
     use expect_test::expect;
 
+    use crate::diagnostics::DiagnosticCode;
+    use crate::diagnostics::DiagnosticsConfig;
     use crate::tests::check_diagnostics;
+    use crate::tests::check_diagnostics_with_config;
     use crate::tests::check_fix;
 
     #[test]
@@ -316,6 +410,83 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_deprecated_function_removal() {
+        check_diagnostics(
+            r#"
+//- /src/b.erl
+  -module(b).
+  -export([soon/0, later/0]).
+  -deprecated([{soon, 0, next_version}, {later, 0, eventually}]).
+  soon() -> ok.
+  later() -> ok.
+//- /src/a.erl
+  -module(a).
+
+  main() ->
+    b:soon(),
+%%  ^^^^^^^^ 💡 warning: Function 'soon/0' is deprecated.
+%%         | It will be removed in the next version.
+    b:later().
+%%  ^^^^^^^^^ 💡 weak: Function 'later/0' is deprecated.
+%%          | It will be removed in a future release.
+            "#,
+        )
+    }
+
+    #[test]
+    fn test_deprecated_otp_function() {
+        check_diagnostics_with_config(
+            DiagnosticsConfig::default()
+                .set_experimental(true)
+                .disable(DiagnosticCode::UndefinedFunction)
+                .disable(DiagnosticCode::ApiMigration),
+            r#"
+//- /src/main.erl
+  -module(main).
+
+  main() ->
+    dbg:stop_clear(),
+%%  ^^^^^^^^^^^^^^^^ 💡 warning: Function 'stop_clear/0' is deprecated.
+%%                 | use dbg:stop/0 instead
+    erlang:get_stacktrace(),
+%%  ^^^^^^^^^^^^^^^^^^^^^^^ 💡 warning: Function 'get_stacktrace/0' has been removed.
+%%                        | use the new try/catch syntax for retrieving the stack backtrace
+    random:uniform(),
+    pg2:start().
+%%  ^^^^^^^^^^^ 💡 warning: Function 'start/0' has been removed.
+%%            | this module was removed in OTP 24. Use 'pg' instead
+//- /opt/lib/stdlib-3.17/src/otp_internal.erl otp_app:/opt/lib/stdlib-3.17
+  -module(otp_internal).
+  -export([obsolete/3]).
+  obsolete(dbg, stop_clear, 0) ->
+      {deprecated, "use dbg:stop/0 instead"};
+  obsolete(erlang, get_stacktrace, 0) ->
+      {removed, "use the new try/catch syntax for retrieving the stack backtrace"};
+  obsolete(random, _, _) ->
+      {deprecated, "use the 'rand' module instead"};
+  obsolete(pg2, _, _) ->
+      {removed, "this module was removed in OTP 24. Use 'pg' instead"};
+  obsolete(_,_,_) -> no.
+            "#,
+        )
+    }
+
+    #[test]
+    fn test_deprecated_otp_function_without_otp() {
+        check_diagnostics_with_config(
+            DiagnosticsConfig::default()
+                .set_experimental(true)
+                .disable(DiagnosticCode::UndefinedFunction),
+            r#"
+  -module(main).
+
+  main() ->
+    dbg:stop_clear().
+            "#,
+        )
+    }
+
     #[test]
     fn test_xref_ignore_fix() {
         check_fix(
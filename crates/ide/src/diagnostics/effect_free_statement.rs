@@ -158,7 +158,7 @@ fn has_no_effect(def_fb: &InFunctionClauseBody<&FunctionDef>, expr_id: &ExprId)
 }
 
 #[allow(clippy::match_like_matches_macro)]
-fn is_statement(expr: &ast::Expr) -> bool {
+pub(crate) fn is_statement(expr: &ast::Expr) -> bool {
     let syntax = expr.syntax();
     match syntax.parent() {
         Some(parent) => match parent.kind() {
@@ -178,7 +178,7 @@ fn is_macro_usage(expr: &ast::Expr) -> bool {
     syntax.kind() == SyntaxKind::MACRO_CALL_EXPR
 }
 
-fn is_followed_by(expected_kind: SyntaxKind, expr: &ast::Expr) -> bool {
+pub(crate) fn is_followed_by(expected_kind: SyntaxKind, expr: &ast::Expr) -> bool {
     let node = expr.syntax();
     let elements = iter::successors(node.next_sibling_or_token(), |n| {
         (*n).next_sibling_or_token()
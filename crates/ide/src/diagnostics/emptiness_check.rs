@@ -0,0 +1,453 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: emptiness-check
+//
+// Return a weak warning on a comparison of the length of a list, or of
+// the size of a map, binary or tuple, with 0 or 1 which only checks
+// whether the value is empty, such as `length(L) == 0` or
+// `map_size(M) > 0`. `length/1` traverses the whole list to count its
+// elements. Offer to compare the value with the empty one instead, and,
+// when the check is the whole guard of a clause and the list a variable
+// of its pattern, to match the list against `[]` or `[_|_]` there.
+// A non-emptiness check keeps the type test of the size function, e.g.
+// `is_map(M) andalso M =/= #{}`, as the comparison alone also holds for
+// values which are not collections, changing which guard succeeds.
+
+use elp_ide_assists::helpers::expr_needs_parens_ast;
+use elp_ide_assists::Assist;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_syntax::ast;
+use elp_syntax::ast::BinaryOp;
+use elp_syntax::ast::CompOp;
+use elp_syntax::ast::Ordering;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use elp_syntax::TextRange;
+use hir::fold::MacroStrategy;
+use hir::fold::ParenStrategy;
+use hir::AnyExpr;
+use hir::AnyExprId;
+use hir::ClauseId;
+use hir::Expr;
+use hir::ExprId;
+use hir::FunctionDef;
+use hir::InFunctionBody;
+use hir::Literal;
+use hir::Semantic;
+use hir::Strategy;
+use text_edit::TextEdit;
+
+use super::Diagnostic;
+use super::DiagnosticCode;
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use super::Severity;
+use crate::fix;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        emptiness_check(diags, sema, file_id);
+    },
+};
+
+/// Functions counting the elements of a value, with the empty value and
+/// the guard testing the type they accept.
+const SIZE_FUNCTIONS: &[(&str, &str, &str)] = &[
+    ("length", "[]", "is_list"),
+    ("map_size", "#{}", "is_map"),
+    ("byte_size", "<<>>", "is_bitstring"),
+    ("tuple_size", "{}", "is_tuple"),
+];
+
+#[derive(Debug, Clone, Copy)]
+enum Check {
+    Empty,
+    NonEmpty,
+}
+
+fn emptiness_check(diagnostics: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    sema.for_each_function(file_id, |def| {
+        check_function(diagnostics, sema, file_id, def)
+    });
+}
+
+fn check_function(
+    diagnostics: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    def: &FunctionDef,
+) {
+    let def_fb = def.in_function_body(sema, def);
+    def_fb.clone().fold_function(
+        Strategy {
+            macros: MacroStrategy::DoNotExpand,
+            parens: ParenStrategy::InvisibleParens,
+        },
+        (),
+        &mut |_acc, clause_id, ctx| {
+            if let (
+                AnyExpr::Expr(Expr::BinaryOp {
+                    lhs,
+                    rhs,
+                    op: BinaryOp::CompOp(op),
+                }),
+                AnyExprId::Expr(expr_id),
+            ) = (ctx.item, ctx.item_id)
+            {
+                if let Some(diagnostic) =
+                    check_comparison(sema, file_id, &def_fb, clause_id, expr_id, (lhs, op, rhs))
+                {
+                    diagnostics.push(diagnostic);
+                }
+            }
+        },
+    )
+}
+
+fn check_comparison(
+    sema: &Semantic,
+    file_id: FileId,
+    def_fb: &InFunctionBody<&FunctionDef>,
+    clause_id: ClauseId,
+    expr_id: ExprId,
+    (lhs, op, rhs): (ExprId, CompOp, ExprId),
+) -> Option<Diagnostic> {
+    let body = def_fb.body(clause_id);
+    let (call, op, n) = match (&body[lhs], &body[rhs]) {
+        (Expr::Call { .. }, Expr::Literal(Literal::Integer(n))) => (lhs, op, *n),
+        (Expr::Literal(Literal::Integer(n)), Expr::Call { .. }) => (rhs, flip(op), *n),
+        _ => return None,
+    };
+    let check = match (op, n) {
+        (CompOp::Eq { negated: false, .. }, 0)
+        | (
+            CompOp::Ord {
+                ordering: Ordering::Less,
+                strict: false,
+            },
+            0,
+        )
+        | (
+            CompOp::Ord {
+                ordering: Ordering::Less,
+                strict: true,
+            },
+            1,
+        ) => Check::Empty,
+        (CompOp::Eq { negated: true, .. }, 0)
+        | (
+            CompOp::Ord {
+                ordering: Ordering::Greater,
+                strict: true,
+            },
+            0,
+        )
+        | (
+            CompOp::Ord {
+                ordering: Ordering::Greater,
+                strict: false,
+            },
+            1,
+        ) => Check::NonEmpty,
+        _ => return None,
+    };
+    let Expr::Call { target, args } = &body[call] else {
+        return None;
+    };
+    let [arg] = args.as_slice() else {
+        return None;
+    };
+    let label = target.label(1, sema, &body)?;
+    let name = label
+        .strip_prefix("erlang:")
+        .unwrap_or(&label)
+        .strip_suffix("/1")?;
+    let (fun, empty, is_type) = SIZE_FUNCTIONS.iter().find(|(fun, _, _)| *fun == name)?;
+
+    let range = def_fb.range_for_expr(clause_id, expr_id)?;
+    let body_map = def_fb.get_body_map(clause_id);
+    let source_file = sema.parse(file_id);
+    let comparison = body_map.expr(expr_id)?.to_node(&source_file)?;
+    let operand = body_map.expr(*arg)?.to_node(&source_file)?;
+    let operand_text = if expr_needs_parens_ast(&operand) {
+        format!("({operand})")
+    } else {
+        operand.to_string()
+    };
+    let replacement = match check {
+        Check::Empty => Some(format!("{operand_text} =:= {empty}")),
+        // The operand is repeated, so it must not have side effects
+        Check::NonEmpty => match &operand {
+            ast::Expr::ExprMax(ast::ExprMax::Var(_)) => {
+                let replacement = format!("{is_type}({operand}) andalso {operand} =/= {empty}");
+                let needs_parens = !comparison.syntax().parent().is_some_and(|parent| {
+                    matches!(
+                        parent.kind(),
+                        SyntaxKind::GUARD_CLAUSE | SyntaxKind::CLAUSE_BODY | SyntaxKind::PAREN_EXPR
+                    )
+                });
+                if needs_parens {
+                    Some(format!("({replacement})"))
+                } else {
+                    Some(replacement)
+                }
+            }
+            _ => None,
+        },
+    };
+    let mut fixes: Vec<Assist> = replacement
+        .map(|replacement| {
+            fix(
+                "compare_with_empty",
+                &format!("Replace by `{replacement}`"),
+                SourceChange::from_text_edit(
+                    file_id,
+                    TextEdit::replace(range, replacement.clone()),
+                ),
+                range,
+            )
+        })
+        .into_iter()
+        .collect();
+    let message = if *fun == "length" {
+        fixes.extend(match_in_pattern(
+            file_id,
+            &comparison,
+            &operand,
+            check,
+            range,
+        ));
+        "Checking emptiness with `length/1` traverses the whole list.".to_string()
+    } else {
+        format!("Checking emptiness with `{fun}/1` can be a comparison with `{empty}`.")
+    };
+    Some(
+        Diagnostic::new(DiagnosticCode::EmptinessCheck, message, range)
+            .with_severity(Severity::WeakWarning)
+            .with_fixes((!fixes.is_empty()).then_some(fixes)),
+    )
+}
+
+/// The same comparison with its operands swapped.
+fn flip(op: CompOp) -> CompOp {
+    match op {
+        CompOp::Ord {
+            ordering: Ordering::Less,
+            strict,
+        } => CompOp::Ord {
+            ordering: Ordering::Greater,
+            strict,
+        },
+        CompOp::Ord {
+            ordering: Ordering::Greater,
+            strict,
+        } => CompOp::Ord {
+            ordering: Ordering::Less,
+            strict,
+        },
+        CompOp::Eq { .. } => op,
+    }
+}
+
+/// Replace the guard of a clause made of the check alone by a list
+/// pattern for the variable checked.
+fn match_in_pattern(
+    file_id: FileId,
+    comparison: &ast::Expr,
+    operand: &ast::Expr,
+    check: Check,
+    range: TextRange,
+) -> Option<Assist> {
+    let ast::Expr::ExprMax(ast::ExprMax::Var(var)) = operand else {
+        return None;
+    };
+    let guard_clause = comparison
+        .syntax()
+        .parent()
+        .and_then(ast::GuardClause::cast)?;
+    let guard = guard_clause.syntax().parent().and_then(ast::Guard::cast)?;
+    if guard.clauses().count() != 1 || guard_clause.exprs().count() != 1 {
+        return None;
+    }
+    let clause = guard.syntax().parent()?;
+    let pattern = if let Some(clause) = ast::FunctionClause::cast(clause.clone()) {
+        clause.args()?.syntax().clone()
+    } else if let Some(clause) = ast::FunClause::cast(clause.clone()) {
+        clause.args()?.syntax().clone()
+    } else if let Some(clause) = ast::CrClause::cast(clause.clone()) {
+        clause.pat()?.syntax().clone()
+    } else {
+        return None;
+    };
+    let name = var.syntax().text().to_string();
+    let occurrences = |node: &SyntaxNode| -> Vec<ast::Var> {
+        node.descendants()
+            .filter_map(ast::Var::cast)
+            .filter(|var| var.syntax().text() == name.as_str())
+            .collect()
+    };
+    let [bound] = occurrences(&pattern).try_into().ok()?;
+    let list_pattern = match check {
+        Check::Empty => "[]",
+        Check::NonEmpty => "[_|_]",
+    };
+    // Keep the variable when the clause uses it besides the check
+    let new_pattern = if occurrences(&clause).len() > 2 {
+        format!("{list_pattern} = {name}")
+    } else {
+        list_pattern.to_string()
+    };
+    let mut edit = TextEdit::builder();
+    edit.replace(bound.syntax().text_range(), new_pattern);
+    edit.delete(TextRange::new(
+        pattern.text_range().end(),
+        guard.syntax().text_range().end(),
+    ));
+    Some(fix(
+        "match_in_pattern",
+        &format!("Match {name} against `{list_pattern}` in the pattern"),
+        SourceChange::from_text_edit(file_id, edit.finish()),
+        range,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::tests::check_diagnostics;
+    use crate::tests::check_fix;
+    use crate::tests::check_specific_fix;
+
+    #[test]
+    fn emptiness_checks() {
+        check_diagnostics(
+            r#"
+-module(main).
+-export([f/2, g/1]).
+f(L, M) when length(L) == 0 -> map_size(M) > 0;
+%%           ^^^^^^^^^^^^^^ 💡 weak: Checking emptiness with `length/1` traverses the whole list.
+%%                             ^^^^^^^^^^^^^^^ 💡 weak: Checking emptiness with `map_size/1` can be a comparison with `#{}`.
+f(L, B) ->
+    case 0 < erlang:length(L) of
+%%       ^^^^^^^^^^^^^^^^^^^^ 💡 weak: Checking emptiness with `length/1` traverses the whole list.
+        true -> byte_size(B) =:= 0;
+%%              ^^^^^^^^^^^^^^^^^^ 💡 weak: Checking emptiness with `byte_size/1` can be a comparison with `<<>>`.
+        false -> length(L) > 1
+    end.
+g(T) -> tuple_size(T) < 1 orelse size(T) == 0.
+%%      ^^^^^^^^^^^^^^^^^ 💡 weak: Checking emptiness with `tuple_size/1` can be a comparison with `{}`.
+"#,
+        );
+    }
+
+    #[test]
+    fn compare_with_empty() {
+        check_fix(
+            r#"
+-module(main).
+-export([f/2]).
+f(L1, L2) -> len~gth(L1 ++ L2) == 0.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([f/2]).
+                f(L1, L2) -> (L1 ++ L2) =:= [].
+            "#]],
+        );
+    }
+
+    #[test]
+    fn non_empty_keeps_type_test() {
+        check_specific_fix(
+            "Replace by `is_map(M) andalso M =/= #{}`",
+            r#"
+-module(main).
+-export([f/1]).
+f(M) when map_s~ize(M) > 0 -> non_empty_map;
+f(_) -> other.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([f/1]).
+                f(M) when is_map(M) andalso M =/= #{} -> non_empty_map;
+                f(_) -> other.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn non_empty_in_expression() {
+        check_fix(
+            r#"
+-module(main).
+-export([f/2]).
+f(L, X) -> X orelse len~gth(L) > 0.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([f/2]).
+                f(L, X) -> X orelse (is_list(L) andalso L =/= []).
+            "#]],
+        );
+    }
+
+    #[test]
+    fn match_in_function_head() {
+        check_specific_fix(
+            "Match L against `[_|_]` in the pattern",
+            r#"
+-module(main).
+-export([f/1]).
+f(L) when len~gth(L) > 0 -> ok;
+f(_) -> empty.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([f/1]).
+                f([_|_]) -> ok;
+                f(_) -> empty.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn match_in_case_clause_keeping_variable() {
+        check_specific_fix(
+            "Match L against `[]` in the pattern",
+            r#"
+-module(main).
+-export([f/1]).
+f(X) ->
+    case X of
+        {ok, L} when len~gth(L) =:= 0 -> L;
+        _ -> error
+    end.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([f/1]).
+                f(X) ->
+                    case X of
+                        {ok, [] = L} -> L;
+                        _ -> error
+                    end.
+            "#]],
+        );
+    }
+}
@@ -14,6 +14,7 @@ use hir::Semantic;
 use super::Diagnostic;
 
 mod expected_type;
+mod fixme;
 mod unexported_type;
 
 pub fn add_eqwalizer_assists(
@@ -24,4 +25,5 @@ pub fn add_eqwalizer_assists(
 ) {
     expected_type::expected_type(sema, file_id, d, diagnostic);
     unexported_type::unexported_type(sema, file_id, d, diagnostic);
+    fixme::fixme(sema, file_id, d, diagnostic);
 }
@@ -0,0 +1,181 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Fixes: add an `% eqwalizer:fixme` comment suppressing a type error, and
+// remove one that eqwalizer reports as `redundant_fixme`.
+
+use elp_ide_db::assists::GroupLabel;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_ide_db::EqwalizerDiagnostic;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use hir::Semantic;
+use text_edit::TextEdit;
+use text_edit::TextSize;
+
+use crate::diagnostics::unused_suppression::comment_removal_range;
+use crate::diagnostics::Diagnostic;
+use crate::fix;
+
+/// Codes reported for escape hatches and stale fixmes, which a fixme
+/// cannot suppress.
+const UNSUPPRESSABLE: &[&str] = &[
+    "redundant_fixme",
+    "eqwalizer_fixme",
+    "eqwalizer_ignore",
+    "eqwalizer_nowarn",
+];
+
+pub fn fixme(
+    sema: &Semantic,
+    file_id: FileId,
+    d: &EqwalizerDiagnostic,
+    diagnostic: &mut Diagnostic,
+) {
+    if d.code == "redundant_fixme" {
+        remove_fixme(sema, file_id, d, diagnostic);
+    } else if !UNSUPPRESSABLE.contains(&d.code.as_str()) {
+        add_fixme(sema, file_id, d, diagnostic);
+    }
+}
+
+/// A fixme suppresses the errors starting on the line below it, so it
+/// goes right above the line the error starts on, with its indentation.
+fn add_fixme(
+    sema: &Semantic,
+    file_id: FileId,
+    d: &EqwalizerDiagnostic,
+    diagnostic: &mut Diagnostic,
+) {
+    let text = sema.db.file_text(file_id);
+    let source = sema.parse(file_id).value;
+    let mut offset = d.range.start();
+    let line_start = loop {
+        let line_start = line_start(&text, offset);
+        // Comments cannot go inside a token spanning several lines,
+        // such as a multi-line string, so move above its first line.
+        match source.syntax().token_at_offset(line_start).right_biased() {
+            Some(token)
+                if token.kind() != SyntaxKind::WHITESPACE
+                    && token.text_range().start() < line_start =>
+            {
+                offset = token.text_range().start()
+            }
+            _ => break line_start,
+        }
+    };
+    let indent: String = text[usize::from(line_start)..]
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect();
+    let comment = format!("{indent}% eqwalizer:fixme {}: <reason>\n", d.code);
+    let edit = TextEdit::insert(line_start, comment);
+    let mut assist = fix(
+        "eqwalizer_fixme",
+        "Add eqwalizer:fixme comment",
+        SourceChange::from_text_edit(file_id, edit),
+        d.range,
+    );
+    // Ranked after the fixes of the type error itself.
+    assist.group = Some(GroupLabel::ignore());
+    diagnostic.add_fix(assist);
+}
+
+fn remove_fixme(
+    sema: &Semantic,
+    file_id: FileId,
+    d: &EqwalizerDiagnostic,
+    diagnostic: &mut Diagnostic,
+) {
+    let source = sema.parse(file_id).value;
+    let comment = match source
+        .syntax()
+        .token_at_offset(d.range.start())
+        .right_biased()
+    {
+        Some(token) if token.kind() == SyntaxKind::COMMENT => token.text_range(),
+        _ => return,
+    };
+    let range = comment_removal_range(&sema.db.file_text(file_id), comment);
+    diagnostic.add_fix(fix(
+        "remove_redundant_fixme",
+        "Remove redundant eqwalizer:fixme comment",
+        SourceChange::from_text_edit(file_id, TextEdit::delete(range)),
+        d.range,
+    ));
+}
+
+fn line_start(text: &str, offset: TextSize) -> TextSize {
+    text[..usize::from(offset)]
+        .rfind('\n')
+        .map_or(TextSize::from(0), |idx| TextSize::from(idx as u32 + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_project_model::otp::otp_supported_by_eqwalizer;
+    use expect_test::expect;
+
+    use crate::tests::check_specific_fix;
+
+    #[test]
+    fn add_fixme_above_multi_line_string() {
+        if otp_supported_by_eqwalizer() {
+            check_specific_fix(
+                "Add eqwalizer:fixme comment",
+                r#"
+            //- eqwalizer
+            //- /play/src/fixme_string.erl app:play
+            -module(fixme_string).
+
+            -spec baz() -> {string(), integer()}.
+            baz() ->
+                {"first
+            second", not_an_int~eger}.
+            "#,
+                expect![[r#"
+            -module(fixme_string).
+
+            -spec baz() -> {string(), integer()}.
+            baz() ->
+                % eqwalizer:fixme incompatible_types: <reason>
+                {"first
+            second", not_an_integer}.
+         "#]],
+            )
+        }
+    }
+
+    #[test]
+    fn remove_redundant_fixme() {
+        if otp_supported_by_eqwalizer() {
+            check_specific_fix(
+                "Remove redundant eqwalizer:fixme comment",
+                r#"
+            //- eqwalizer
+            //- /play/src/fixme_stale.erl app:play
+            -module(fixme_stale).
+
+            -spec baz() -> atom().
+            baz() ->
+                % eqwalizer:fi~xme incompatible_types: was a string
+                ok.
+            "#,
+                expect![[r#"
+            -module(fixme_stale).
+
+            -spec baz() -> atom().
+            baz() ->
+                ok.
+         "#]],
+            )
+        }
+    }
+}
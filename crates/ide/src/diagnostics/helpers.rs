@@ -11,8 +11,12 @@
 
 use elp_ide_db::elp_base_db::FileId;
 use elp_ide_db::DiagnosticCode;
+use elp_ide_db::SymbolDefinition;
+use fxhash::FxHashSet;
+use hir::File;
 use hir::FunctionDef;
 use hir::Semantic;
+use itertools::Itertools;
 
 use super::Diagnostic;
 use super::Severity;
@@ -89,6 +93,30 @@ pub(crate) fn check_function_with_diagnostic_template(
     );
 }
 
+/// The module including the header, if it is the only file including it.
+/// The declarations of a header included by several files, or by none
+/// yet, are shared with code which may not be visible.
+pub(crate) fn single_module_includer(sema: &Semantic, file_id: FileId) -> Option<FileId> {
+    let includers: FxHashSet<FileId> = SymbolDefinition::Header(File { file_id })
+        .usages(sema)
+        .all()
+        .iter()
+        .map(|(includer, _)| includer)
+        .collect();
+    let includer = *includers.iter().exactly_one().ok()?;
+    File { file_id: includer }
+        .kind(sema.db.upcast())
+        .is_module()
+        .then_some(includer)
+}
+
+/// The major release of the OTP the project of the file is built with.
+pub(crate) fn project_otp_release(sema: &Semantic, file_id: FileId) -> Option<u32> {
+    let project_id = sema.db.file_app_data(file_id)?.project_id;
+    let version = sema.db.project_data(project_id).otp_version.clone()?;
+    version.split('.').next()?.parse().ok()
+}
+
 // ---------------------------------------------------------------------
 
 #[cfg(test)]
@@ -0,0 +1,405 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: incomplete-callbacks
+//
+// Return a warning on a `gen_server` or `gen_statem` module whose
+// callbacks do not cover what the behaviour will do with it: a
+// `gen_server` without `handle_info/2`, which only logs the messages it
+// gets outside of calls and casts, a module trapping exits without
+// `terminate`, whose cleanup on shutdown then never happens, and a
+// `handle_call/3` clause returning something else than a reply tuple,
+// which crashes the server. Offer to add the missing callbacks.
+
+use elp_ide_assists::helpers::ExportBuilder;
+use elp_ide_assists::helpers::ExportForm;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChangeBuilder;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use hir::Name;
+use hir::NameArity;
+use hir::Semantic;
+
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::Severity;
+use crate::fix;
+use crate::Diagnostic;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        incomplete_callbacks(diags, sema, file_id);
+    },
+};
+
+/// What the diagnostic knows of a behaviour.
+struct Behaviour {
+    name: &'static str,
+    /// The callback handling the messages which are not requests, with a
+    /// stub for it, if the behaviour has one apart from its state
+    /// callbacks.
+    info: Option<(&'static str, u32, &'static str)>,
+    /// The arity of `terminate` and a stub for it.
+    terminate: (u32, &'static str),
+    /// The callbacks whose results are checked, with the tags and sizes
+    /// of the tuples they may return.
+    results: &'static [(&'static str, u32, &'static [(&'static str, usize)])],
+}
+
+const BEHAVIOURS: &[Behaviour] = &[
+    Behaviour {
+        name: "gen_server",
+        info: Some((
+            "handle_info",
+            2,
+            "handle_info(_Info, State) ->\n    {noreply, State}.\n",
+        )),
+        terminate: (2, "terminate(_Reason, _State) ->\n    ok.\n"),
+        results: &[(
+            "handle_call",
+            3,
+            &[
+                ("reply", 3),
+                ("reply", 4),
+                ("noreply", 2),
+                ("noreply", 3),
+                ("stop", 3),
+                ("stop", 4),
+            ],
+        )],
+    },
+    Behaviour {
+        name: "gen_statem",
+        info: None,
+        terminate: (3, "terminate(_Reason, _State, _Data) ->\n    ok.\n"),
+        results: &[],
+    },
+];
+
+fn incomplete_callbacks(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let form_list = sema.form_list(file_id);
+    let source = sema.parse(file_id).value;
+    for (_, attribute) in form_list.behaviour_attributes() {
+        let Some(behaviour) = BEHAVIOURS
+            .iter()
+            .find(|behaviour| behaviour.name == attribute.name.as_str())
+        else {
+            continue;
+        };
+        if let Some((fun, arity, stub)) = behaviour.info {
+            let range = attribute.form_id.get(&source).syntax().text_range();
+            diags.extend(missing_callback(
+                sema,
+                file_id,
+                range,
+                (fun, arity, stub),
+                format!(
+                    "Missing `{fun}/{arity}`: messages sent to this {} outside of calls and casts are only logged.",
+                    behaviour.name
+                ),
+            ));
+        }
+        let (arity, stub) = behaviour.terminate;
+        if let Some(range) = trap_exit(&source) {
+            diags.extend(missing_callback(
+                sema,
+                file_id,
+                range,
+                ("terminate", arity, stub),
+                format!(
+                    "Exits are trapped but `terminate/{arity}` is missing: nothing is cleaned up on shutdown."
+                ),
+            ));
+        }
+        for (fun, arity, shapes) in behaviour.results {
+            check_results(diags, sema, file_id, fun, *arity, shapes);
+        }
+    }
+}
+
+/// A diagnostic offering to add and export the callback, if the module
+/// does not define it.
+fn missing_callback(
+    sema: &Semantic,
+    file_id: FileId,
+    range: TextRange,
+    (fun, arity, stub): (&str, u32, &str),
+    message: String,
+) -> Option<Diagnostic> {
+    let name = NameArity::new(Name::from_erlang_service(fun), arity);
+    if sema.def_map(file_id).get_function(&name).is_some() {
+        return None;
+    }
+    let source = sema.parse(file_id).value;
+    let names = [name.clone()];
+    let mut builder = SourceChangeBuilder::new(file_id);
+    ExportBuilder::new(sema, file_id, ExportForm::Functions, &names, &mut builder).finish();
+    let end = source.syntax().text_range().end();
+    if source.syntax().text().char_at(end - TextSize::from(1)) == Some('\n') {
+        builder.insert(end, format!("\n{stub}"));
+    } else {
+        builder.insert(end, format!("\n\n{stub}"));
+    }
+    Some(
+        Diagnostic::new(DiagnosticCode::IncompleteCallbacks, message, range)
+            .with_severity(Severity::Warning)
+            .with_fixes(Some(vec![fix(
+                "add_callback",
+                &format!("Add `{name}`"),
+                builder.finish(),
+                range,
+            )])),
+    )
+}
+
+/// The range of a `process_flag(trap_exit, true)` call of the module.
+fn trap_exit(source: &ast::SourceFile) -> Option<TextRange> {
+    source
+        .syntax()
+        .descendants()
+        .filter_map(ast::Call::cast)
+        .find(|call| {
+            let Some(target) = call.expr() else {
+                return false;
+            };
+            let target: String = target
+                .syntax()
+                .text()
+                .to_string()
+                .split_whitespace()
+                .collect();
+            let args: Vec<String> = call
+                .args()
+                .map(|args| args.args().map(|arg| arg.syntax().to_string()).collect())
+                .unwrap_or_default();
+            (target == "process_flag" || target == "erlang:process_flag")
+                && args == ["trap_exit", "true"]
+        })
+        .map(|call| call.syntax().text_range())
+}
+
+/// Report the values returned by the clauses of the callback which are
+/// not one of the tuples it may return.
+fn check_results(
+    diags: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    fun: &str,
+    arity: u32,
+    shapes: &[(&str, usize)],
+) {
+    let name = NameArity::new(Name::from_erlang_service(fun), arity);
+    let Some(def) = sema.def_map(file_id).get_function(&name).cloned() else {
+        return;
+    };
+    let expected = shapes
+        .iter()
+        .map(|(tag, size)| format!("`{{{tag}{}}}`", ", _".repeat(size - 1)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut results = Vec::new();
+    for decl in def.source(sema.db.upcast()) {
+        if let Some(ast::FunctionOrMacroClause::FunctionClause(clause)) = decl.clause() {
+            if let Some(body) = clause.body() {
+                body_results(&body, &mut results);
+            }
+        }
+    }
+    for result in results {
+        if is_valid_result(&result, shapes) == Some(false) {
+            diags.push(
+                Diagnostic::new(
+                    DiagnosticCode::IncompleteCallbacks,
+                    format!("`{name}` must return one of {expected}."),
+                    result.syntax().text_range(),
+                )
+                .with_severity(Severity::Warning),
+            );
+        }
+    }
+}
+
+/// The expressions whose value a clause body may evaluate to, looking
+/// into the branches of the expression it ends with.
+fn body_results(body: &ast::ClauseBody, acc: &mut Vec<ast::Expr>) {
+    if let Some(last) = body.exprs().last() {
+        expr_results(last, acc);
+    }
+}
+
+fn expr_results(expr: ast::Expr, acc: &mut Vec<ast::Expr>) {
+    match expr {
+        ast::Expr::ExprMax(ast::ExprMax::CaseExpr(case)) => cr_clauses_results(case.clauses(), acc),
+        ast::Expr::ExprMax(ast::ExprMax::ReceiveExpr(receive)) => {
+            cr_clauses_results(receive.clauses(), acc);
+            if let Some(body) = receive.after().and_then(|after| after.body()) {
+                body_results(&body, acc);
+            }
+        }
+        ast::Expr::ExprMax(ast::ExprMax::IfExpr(if_expr)) => {
+            for clause in if_expr.clauses() {
+                if let Some(body) = clause.body() {
+                    body_results(&body, acc);
+                }
+            }
+        }
+        ast::Expr::ExprMax(ast::ExprMax::BlockExpr(block)) => {
+            if let Some(last) = block.exprs().last() {
+                expr_results(last, acc);
+            }
+        }
+        ast::Expr::ExprMax(ast::ExprMax::ParenExpr(paren)) => {
+            if let Some(inner) = paren.expr() {
+                expr_results(inner, acc);
+            }
+        }
+        expr => acc.push(expr),
+    }
+}
+
+fn cr_clauses_results(clauses: ast::AstChildren<ast::CrClauseOrMacro>, acc: &mut Vec<ast::Expr>) {
+    for clause in clauses {
+        if let ast::CrClauseOrMacro::CrClause(clause) = clause {
+            if let Some(body) = clause.body() {
+                body_results(&body, acc);
+            }
+        }
+    }
+}
+
+/// Whether the value is one of the tuples, or `None` when it cannot be
+/// told from its syntax, such as for a variable or a call.
+fn is_valid_result(expr: &ast::Expr, shapes: &[(&str, usize)]) -> Option<bool> {
+    match expr {
+        ast::Expr::ExprMax(ast::ExprMax::Tuple(tuple)) => {
+            let elements: Vec<ast::Expr> = tuple.expr().collect();
+            let Some(ast::Expr::ExprMax(ast::ExprMax::Atom(tag))) = elements.first() else {
+                return None;
+            };
+            let tag = tag.syntax().text().to_string();
+            Some(
+                shapes
+                    .iter()
+                    .any(|(valid, size)| *valid == tag && *size == elements.len()),
+            )
+        }
+        ast::Expr::ExprMax(
+            ast::ExprMax::Atom(_)
+            | ast::ExprMax::Integer(_)
+            | ast::ExprMax::Float(_)
+            | ast::ExprMax::Char(_)
+            | ast::ExprMax::String(_)
+            | ast::ExprMax::List(_)
+            | ast::ExprMax::Binary(_),
+        )
+        | ast::Expr::MapExpr(_) => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::tests::check_diagnostics;
+    use crate::tests::check_specific_fix;
+
+    #[test]
+    fn gen_server_callbacks() {
+        check_diagnostics(
+            r#"
+//- /src/gen_server.erl
+-module(gen_server).
+//- /src/main.erl
+-module(main).
+-behaviour(gen_server).
+%%<^^^^^^^^^^^^^^^^^^^^^^ 💡 warning: Missing `handle_info/2`: messages sent to this gen_server outside of calls and casts are only logged.
+-export([init/1, handle_call/3, handle_cast/2]).
+init([]) ->
+    process_flag(trap_exit, true),
+%%  ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ 💡 warning: Exits are trapped but `terminate/2` is missing: nothing is cleaned up on shutdown.
+    {ok, #{}}.
+handle_call(get, _From, State) ->
+    {reply, State, State};
+handle_call({set, S}, _From, _State) ->
+    case S of
+        #{} -> {noreply, S};
+        _ -> ok
+%%           ^^ warning: `handle_call/3` must return one of `{reply, _, _}`, `{reply, _, _, _}`, `{noreply, _}`, `{noreply, _, _}`, `{stop, _, _}`, `{stop, _, _, _}`.
+    end;
+handle_call(stop, _From, State) ->
+    {stop, normal, State, ok, extra};
+%%  ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ warning: `handle_call/3` must return one of `{reply, _, _}`, `{reply, _, _, _}`, `{noreply, _}`, `{noreply, _, _}`, `{stop, _, _}`, `{stop, _, _, _}`.
+handle_call(Req, From, State) ->
+    forward(Req, From, State).
+handle_cast(_Msg, State) -> {noreply, State}.
+forward(_, _, State) -> {noreply, State}.
+"#,
+        );
+    }
+
+    #[test]
+    fn complete_gen_statem() {
+        check_diagnostics(
+            r#"
+//- /src/gen_statem.erl
+-module(gen_statem).
+//- /src/main.erl
+-module(main).
+-behaviour(gen_statem).
+-export([init/1, terminate/3]).
+init([]) ->
+    erlang:process_flag(trap_exit, true),
+    {ok, idle, #{}}.
+terminate(_Reason, _State, _Data) -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn add_terminate() {
+        check_specific_fix(
+            "Add `terminate/2`",
+            r#"
+//- /src/gen_server.erl
+-module(gen_server).
+//- /src/main.erl
+-module(main).
+-behaviour(gen_server).
+-export([init/1, handle_info/2]).
+init([]) ->
+    process_flag(trap_~exit, true),
+    {ok, #{}}.
+handle_info(_Info, State) -> {noreply, State}.
+"#,
+            expect![[r#"
+                -module(main).
+                -behaviour(gen_server).
+                -export([init/1, handle_info/2, terminate/2]).
+                init([]) ->
+                    process_flag(trap_exit, true),
+                    {ok, #{}}.
+                handle_info(_Info, State) -> {noreply, State}.
+
+                terminate(_Reason, _State) ->
+                    ok.
+            "#]],
+        );
+    }
+}
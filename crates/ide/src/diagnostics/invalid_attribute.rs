@@ -0,0 +1,356 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: invalid-attribute
+//
+// Return an error if the payload of an `-on_load`, `-nifs`, `-dialyzer`
+// or `-compile` attribute does not have the expected shape, or refers to
+// a function that is not defined in the module.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::TextRange;
+use hir::known;
+use hir::AnyExprId;
+use hir::Body;
+use hir::BodySourceMap;
+use hir::DefMap;
+use hir::InFile;
+use hir::Literal;
+use hir::NameArity;
+use hir::Semantic;
+use hir::Term;
+use hir::TermId;
+
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::Severity;
+use crate::Diagnostic;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        invalid_attribute(diags, sema, file_id);
+    },
+};
+
+/// Warning options accepted by `-dialyzer`, see the "Requesting or
+/// Suppressing Warnings in Source Files" section of the dialyzer docs.
+const DIALYZER_OPTIONS: &[&str] = &[
+    "error_handling",
+    "extra_return",
+    "missing_return",
+    "no_behaviours",
+    "no_contracts",
+    "no_extra_return",
+    "no_fail_call",
+    "no_fun_app",
+    "no_improper_lists",
+    "no_match",
+    "no_missing_calls",
+    "no_missing_return",
+    "no_opaque",
+    "no_return",
+    "no_undefined_callbacks",
+    "no_underspecs",
+    "no_unknown",
+    "no_unused",
+    "nowarn_function",
+    "overspecs",
+    "specdiffs",
+    "underspecs",
+    "unknown",
+    "unmatched_returns",
+];
+
+/// Compile options whose argument is a list of local functions.
+const COMPILE_FUNCTION_OPTIONS: &[&str] = &["inline", "nowarn_unused_function"];
+
+fn invalid_attribute(diagnostics: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let form_list = sema.db.file_form_list(file_id);
+    let def_map = sema.def_map(file_id);
+    for (id, attr) in form_list.attributes() {
+        let name = attr.name.clone();
+        if name != known::on_load && name != known::nifs && name != known::dialyzer {
+            continue;
+        }
+        let (body, source_map) = sema.db.attribute_body_with_source(InFile::new(file_id, id));
+        let mut check = Check {
+            sema,
+            file_id,
+            attribute: name.as_str(),
+            body: &body.body,
+            source_map: &source_map,
+            def_map: &def_map,
+            diagnostics: &mut *diagnostics,
+        };
+        if name == known::on_load {
+            check.on_load(body.value);
+        } else if name == known::nifs {
+            check.nifs(body.value);
+        } else {
+            check.dialyzer(body.value);
+        }
+    }
+    for (id, _) in form_list.compile_attributes() {
+        let (body, source_map) = sema.db.compile_body_with_source(InFile::new(file_id, id));
+        Check {
+            sema,
+            file_id,
+            attribute: "compile",
+            body: &body.body,
+            source_map: &source_map,
+            def_map: &def_map,
+            diagnostics: &mut *diagnostics,
+        }
+        .compile(body.value);
+    }
+}
+
+struct Check<'a> {
+    sema: &'a Semantic<'a>,
+    file_id: FileId,
+    attribute: &'a str,
+    body: &'a Body,
+    source_map: &'a BodySourceMap,
+    def_map: &'a DefMap,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl<'a> Check<'a> {
+    /// `-on_load(F/0).`
+    fn on_load(&mut self, term: TermId) {
+        match self.fun_ref(term) {
+            Some(fa) if fa.arity() == 0 => self.defined(term, fa),
+            _ => self.error(
+                term,
+                "-on_load expects a function of arity 0, e.g. -on_load(init/0).".to_string(),
+            ),
+        }
+    }
+
+    /// `-nifs([F/A, ...]).`
+    fn nifs(&mut self, term: TermId) {
+        match self.list(term) {
+            Some(entries) => self.fun_refs(&entries),
+            None => self.error(
+                term,
+                "-nifs expects a list of functions, e.g. -nifs([add/2]).".to_string(),
+            ),
+        }
+    }
+
+    /// `-dialyzer(Option | {Option, Funs} | [...]).`
+    fn dialyzer(&mut self, term: TermId) {
+        let body = self.body;
+        for option in self.list(term).unwrap_or_else(|| vec![term]) {
+            let option = self.expand(option);
+            match &body[option] {
+                Term::Literal(Literal::Atom(atom)) => {
+                    let name = self.sema.db.lookup_atom(*atom);
+                    self.dialyzer_option(option, name.as_str())
+                }
+                Term::Tuple { exprs } if exprs.len() == 2 => {
+                    let (name, funs) = (self.expand(exprs[0]), exprs[1]);
+                    match &body[name] {
+                        Term::Literal(Literal::Atom(atom)) => {
+                            let option_name = self.sema.db.lookup_atom(*atom);
+                            self.dialyzer_option(name, option_name.as_str());
+                            self.fun_ref_or_list(funs);
+                        }
+                        _ => self.error(name, "Malformed -dialyzer option.".to_string()),
+                    }
+                }
+                _ => self.error(option, "Malformed -dialyzer option.".to_string()),
+            }
+        }
+    }
+
+    fn dialyzer_option(&mut self, term: TermId, name: &str) {
+        if !DIALYZER_OPTIONS.contains(&name) {
+            self.diagnostic(
+                term,
+                format!("Unknown dialyzer option '{name}'."),
+                Severity::Warning,
+            );
+        }
+    }
+
+    /// Only the options referring to local functions are checked, the
+    /// compiler accepts arbitrary terms as options.
+    fn compile(&mut self, term: TermId) {
+        let body = self.body;
+        for option in self.list(term).unwrap_or_else(|| vec![term]) {
+            let option = self.expand(option);
+            if let Term::Tuple { exprs } = &body[option] {
+                if let [name, funs] = exprs.as_slice() {
+                    if let Term::Literal(Literal::Atom(atom)) = &body[self.expand(*name)] {
+                        let name = self.sema.db.lookup_atom(*atom);
+                        if COMPILE_FUNCTION_OPTIONS.contains(&name.as_str()) {
+                            self.fun_ref_or_list(*funs);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn fun_ref_or_list(&mut self, term: TermId) {
+        match self.list(term) {
+            Some(entries) => self.fun_refs(&entries),
+            None => self.fun_refs(&[term]),
+        }
+    }
+
+    fn fun_refs(&mut self, terms: &[TermId]) {
+        for term in terms {
+            match self.fun_ref(*term) {
+                Some(fa) => self.defined(*term, fa),
+                None => self.error(
+                    *term,
+                    format!("-{} expects functions as Name/Arity.", self.attribute),
+                ),
+            }
+        }
+    }
+
+    fn defined(&mut self, term: TermId, fa: NameArity) {
+        if self.def_map.get_function(&fa).is_none() {
+            self.error(
+                term,
+                format!(
+                    "Function {fa} in -{} attribute is undefined.",
+                    self.attribute
+                ),
+            );
+        }
+    }
+
+    /// `F/A`, which is lowered to the same term as `{F, A}`.
+    fn fun_ref(&self, term: TermId) -> Option<NameArity> {
+        match &self.body[self.expand(term)] {
+            Term::Tuple { exprs } => match exprs.as_slice() {
+                [name, arity] => match (&self.body[*name], &self.body[*arity]) {
+                    (
+                        Term::Literal(Literal::Atom(name)),
+                        Term::Literal(Literal::Integer(arity)),
+                    ) => Some(NameArity::new(
+                        self.sema.db.lookup_atom(*name),
+                        u32::try_from(*arity).ok()?,
+                    )),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn list(&self, term: TermId) -> Option<Vec<TermId>> {
+        match &self.body[self.expand(term)] {
+            Term::List { exprs, tail: None } => Some(exprs.clone()),
+            _ => None,
+        }
+    }
+
+    fn expand(&self, term: TermId) -> TermId {
+        match &self.body[term] {
+            Term::MacroCall { expansion, .. } => self.expand(*expansion),
+            _ => term,
+        }
+    }
+
+    fn error(&mut self, term: TermId, message: String) {
+        self.diagnostic(term, message, Severity::Error)
+    }
+
+    fn diagnostic(&mut self, term: TermId, message: String, severity: Severity) {
+        if let Some(range) = self.range(term) {
+            let diag = Diagnostic::new(DiagnosticCode::InvalidAttribute, message, range)
+                .with_severity(severity)
+                .with_ignore_fix(self.sema, self.file_id);
+            self.diagnostics.push(diag);
+        }
+    }
+
+    /// Terms coming from a macro defined elsewhere have no range in
+    /// this file, and are not reported.
+    fn range(&self, term: TermId) -> Option<TextRange> {
+        let source = self.source_map.any(AnyExprId::Term(term))?;
+        (source.file_id() == self.file_id).then(|| source.range())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn on_load() {
+        check_diagnostics(
+            r#"
+-module(main).
+-on_load(init/1).
+%%       ^^^^^^ 💡 error: -on_load expects a function of arity 0, e.g. -on_load(init/0).
+-on_load(setup/0).
+%%       ^^^^^^^ 💡 error: Function setup/0 in -on_load attribute is undefined.
+init(_) -> ok.
+"#,
+        )
+    }
+
+    #[test]
+    fn nifs() {
+        check_diagnostics(
+            r#"
+-module(main).
+-export([add/2]).
+-nifs([add/2, sub/2, mul]).
+%%            ^^^^^ 💡 error: Function sub/2 in -nifs attribute is undefined.
+%%                   ^^^ 💡 error: -nifs expects functions as Name/Arity.
+-on_load(init/0).
+init() -> erlang:load_nif("./main_nif", 0).
+add(_A, _B) -> erlang:nif_error(not_loaded).
+"#,
+        )
+    }
+
+    #[test]
+    fn dialyzer() {
+        check_diagnostics(
+            r#"
+-module(main).
+-dialyzer({nowarn_function, [f/0, g/0]}).
+%%                                ^^^ 💡 error: Function g/0 in -dialyzer attribute is undefined.
+-dialyzer([no_return, no_retrun]).
+%%                    ^^^^^^^^^ 💡 warning: Unknown dialyzer option 'no_retrun'.
+-dialyzer({no_match, f/0}).
+f() -> ok.
+"#,
+        )
+    }
+
+    #[test]
+    fn compile() {
+        check_diagnostics(
+            r#"
+-module(main).
+-compile([export_all, nowarn_export_all, {inline, [f/0, g/1]}]).
+%%                                                      ^^^ 💡 error: Function g/1 in -compile attribute is undefined.
+-compile({nowarn_unused_function, f/0}).
+f() -> ok.
+"#,
+        )
+    }
+}
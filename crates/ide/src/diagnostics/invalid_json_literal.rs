@@ -0,0 +1,125 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: invalid-json-literal
+//
+// Return a warning if a string literal injected as JSON, by a
+// `%% language=json` comment or by the `injections` of `.elp_lint.toml`,
+// is not well-formed JSON. Generated files are not checked.
+
+use elp_ide_db::elp_base_db::FileId;
+use hir::Semantic;
+
+use crate::diagnostics::ConfiguredDiagnosticDescriptor;
+use crate::diagnostics::Diagnostic;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::DiagnosticConditions;
+use crate::diagnostics::Severity;
+use crate::injection::injected_strings;
+use crate::InjectedLanguage;
+use crate::Injection;
+
+pub(crate) static DESCRIPTOR: ConfiguredDiagnosticDescriptor = ConfiguredDiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, _db, file_id, _file_kind, config| {
+        invalid_json_literals(diags, sema, file_id, &config.injections);
+    },
+};
+
+fn invalid_json_literals(
+    diagnostics: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    injections: &[Injection],
+) {
+    for injected in injected_strings(sema, file_id, injections) {
+        if injected.language != InjectedLanguage::Json {
+            continue;
+        }
+        if let Err(err) = serde_json::from_str::<serde_json::Value>(&injected.contents) {
+            diagnostics.push(
+                Diagnostic::new(
+                    DiagnosticCode::InvalidJsonLiteral,
+                    format!("Invalid JSON: {err}."),
+                    injected.range,
+                )
+                .with_severity(Severity::Warning),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::InjectedLanguage;
+    use crate::Injection;
+    use crate::MFA;
+
+    #[test]
+    fn tagged_json_literal() {
+        check_diagnostics(
+            r#"
+-module(main).
+-export([body/0]).
+body() ->
+    %% language=json
+    Good = <<"{\"id\": 1, \"tags\": [\"a\"]}">>,
+    %% language=json
+    Bad = "{\"id\": 1,}",
+ %%       ^^^^^^^^^^^^^^ warning: Invalid JSON: trailing comma at line 1 column 10.
+    {Good, Bad}.
+"#,
+        );
+    }
+
+    #[test]
+    fn not_in_generated_file() {
+        check_diagnostics(
+            r#"
+%% @generated
+-module(main).
+-export([body/0]).
+body() ->
+    %% language=json
+    "{\"id\": 1,}".
+"#,
+        );
+    }
+
+    #[test]
+    fn json_argument_of_configured_function() {
+        let config = DiagnosticsConfig::default().set_injections(vec![Injection {
+            mfa: MFA::new("jsx", "decode", 1),
+            language: InjectedLanguage::Json,
+        }]);
+        check_diagnostics_with_config(
+            config,
+            r#"
+//- /src/main.erl
+-module(main).
+-export([decode/0]).
+decode() ->
+    jsx:decode(<<"{\"id\": 1">>).
+ %%              ^^^^^^^^^^^^ warning: Invalid JSON: EOF while parsing an object at line 1 column 8.
+//- /src/jsx.erl
+-module(jsx).
+-export([decode/1]).
+decode(B) -> B.
+"#,
+        );
+    }
+}
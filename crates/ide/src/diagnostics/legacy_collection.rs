@@ -0,0 +1,357 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: legacy-collection
+//
+// Return a weak warning, when the diagnostic is enabled, on a call to the
+// `dict` or `gb_trees` modules which has an equivalent in `maps`, and on
+// a call creating a set of the old representation of the `sets` module.
+// A value is only converted to a map if all the calls handling it are, so
+// the fix rewrites every call of the function, and is not offered when one
+// of them has no equivalent. Such a call is reported separately, as
+// information.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_syntax::SmolStr;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use hir::fold::MacroStrategy;
+use hir::fold::ParenStrategy;
+use hir::AnyExpr;
+use hir::Expr;
+use hir::FunctionDef;
+use hir::Semantic;
+use hir::Strategy;
+use lazy_static::lazy_static;
+use text_edit::TextEdit;
+
+use super::helpers::project_otp_release;
+use super::Diagnostic;
+use super::DiagnosticCode;
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use super::Severity;
+use crate::codemod_helpers::FunctionMatcher;
+use crate::fix;
+use crate::FunctionMatch;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: true,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        legacy_collection(diags, sema, file_id);
+    },
+};
+
+/// The version 2 representation of `sets` was introduced in OTP 24.
+const SETS_V2_RELEASE: u32 = 24;
+
+#[derive(Debug)]
+enum Rewrite {
+    /// A function of `maps` taking the same arguments.
+    Maps(&'static str),
+    /// A function of `sets` creating a set, to which the version is given.
+    SetsV2,
+    Manual,
+}
+
+lazy_static! {
+    static ref MATCHES: Vec<(FunctionMatch, Rewrite)> = [
+        ("dict", "new", 0, "new"),
+        ("dict", "store", 3, "put"),
+        ("dict", "fetch", 2, "get"),
+        ("dict", "find", 2, "find"),
+        ("dict", "is_key", 2, "is_key"),
+        ("dict", "erase", 2, "remove"),
+        ("dict", "size", 1, "size"),
+        ("dict", "to_list", 1, "to_list"),
+        ("dict", "from_list", 1, "from_list"),
+        ("dict", "fetch_keys", 1, "keys"),
+        ("dict", "map", 2, "map"),
+        ("dict", "filter", 2, "filter"),
+        ("dict", "fold", 3, "fold"),
+        ("dict", "update", 3, "update_with"),
+        ("dict", "update", 4, "update_with"),
+        ("gb_trees", "empty", 0, "new"),
+        ("gb_trees", "enter", 3, "put"),
+        ("gb_trees", "update", 3, "update"),
+        ("gb_trees", "get", 2, "get"),
+        ("gb_trees", "is_defined", 2, "is_key"),
+        ("gb_trees", "delete_any", 2, "remove"),
+        ("gb_trees", "size", 1, "size"),
+        ("gb_trees", "from_orddict", 1, "from_list"),
+    ]
+    .into_iter()
+    .map(|(module, name, arity, to)| (FunctionMatch::mfa(module, name, arity), Rewrite::Maps(to)))
+    .chain([
+        (FunctionMatch::mfa("sets", "new", 0), Rewrite::SetsV2),
+        (FunctionMatch::mfa("sets", "from_list", 1), Rewrite::SetsV2),
+        // Any other function, such as the ones relying on the order of
+        // the keys of a `gb_trees`.
+        (FunctionMatch::m("dict"), Rewrite::Manual),
+        (FunctionMatch::m("gb_trees"), Rewrite::Manual),
+    ])
+    .collect();
+}
+
+struct Call {
+    label: SmolStr,
+    arity: u32,
+    rewrite: &'static Rewrite,
+    range: TextRange,
+    /// The range of `module:function`, unless the call is in a macro.
+    range_mf_only: Option<TextRange>,
+}
+
+fn legacy_collection(diagnostics: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let sets_v2 =
+        project_otp_release(sema, file_id).map_or(true, |release| release >= SETS_V2_RELEASE);
+    let rewrites: Vec<(&FunctionMatch, &'static Rewrite)> = MATCHES
+        .iter()
+        .filter(|(_, rewrite)| sets_v2 || !matches!(rewrite, Rewrite::SetsV2))
+        .map(|(matcher, rewrite)| (matcher, rewrite))
+        .collect();
+    let matcher = FunctionMatcher::new(&rewrites);
+    sema.def_map(file_id).get_functions().for_each(|(_, def)| {
+        if def.file.file_id == file_id {
+            let calls = calls_in_function(sema, def, &matcher);
+            check_function(diagnostics, file_id, def, &calls);
+        }
+    });
+}
+
+fn calls_in_function(
+    sema: &Semantic,
+    def: &FunctionDef,
+    matcher: &FunctionMatcher<'_, &'static Rewrite>,
+) -> Vec<Call> {
+    let def_fb = def.in_function_body(sema, def);
+    def_fb.clone().fold_function(
+        Strategy {
+            macros: MacroStrategy::ExpandButIncludeMacroCall,
+            parens: ParenStrategy::InvisibleParens,
+        },
+        Vec::new(),
+        &mut |mut acc, clause_id, ctx| {
+            if let AnyExpr::Expr(Expr::Call { target, args }) = ctx.item {
+                let arity = args.len() as u32;
+                let body = def_fb.body(clause_id);
+                if let Some((_, rewrite)) = matcher.get_match(&target, arity, None, sema, &body) {
+                    let call_expr_id = ctx.in_macro.map_or(ctx.item_id, |expr_id| expr_id.idx);
+                    let range = def_fb.range_for_any(clause_id, call_expr_id);
+                    let label = target.label(arity, sema, &body);
+                    if let (Some(range), Some(label)) = (range, label) {
+                        let range_mf_only = match ctx.in_macro {
+                            Some(_) => None,
+                            None => target.range(&def_fb.in_clause(clause_id)),
+                        };
+                        acc.push(Call {
+                            label,
+                            arity,
+                            rewrite: *rewrite,
+                            range,
+                            range_mf_only,
+                        });
+                    }
+                }
+            }
+            acc
+        },
+    )
+}
+
+fn check_function(
+    diagnostics: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    def: &FunctionDef,
+    calls: &[Call],
+) {
+    let to_maps: Option<Vec<(TextRange, &str)>> = calls
+        .iter()
+        .filter_map(|call| match call.rewrite {
+            Rewrite::Maps(to) => Some(call.range_mf_only.map(|range| (range, *to))),
+            Rewrite::Manual => Some(None),
+            Rewrite::SetsV2 => None,
+        })
+        .collect();
+    let to_maps_fix = |range| {
+        let replacements = to_maps.as_ref()?;
+        let mut edit = TextEdit::builder();
+        for (mf_range, to) in replacements {
+            edit.replace(*mf_range, format!("maps:{to}"));
+        }
+        Some(fix(
+            "rewrite_to_maps",
+            &format!("Rewrite the calls of {} to maps", def.name),
+            SourceChange::from_text_edit(file_id, edit.finish()),
+            range,
+        ))
+    };
+    for call in calls {
+        let label = &call.label;
+        let diagnostic = match call.rewrite {
+            Rewrite::Maps(to) => Diagnostic::new(
+                DiagnosticCode::LegacyCollection,
+                format!("{label} can be replaced by maps:{to}/{}", call.arity),
+                call.range,
+            )
+            .with_severity(Severity::WeakWarning)
+            .with_fixes(to_maps_fix(call.range).map(|fix| vec![fix])),
+            Rewrite::SetsV2 => {
+                let Some(edit) = sets_v2_edit(call) else {
+                    continue;
+                };
+                Diagnostic::new(
+                    DiagnosticCode::LegacyCollection,
+                    format!("{label} creates a set of the old representation"),
+                    call.range,
+                )
+                .with_severity(Severity::WeakWarning)
+                .with_fixes(Some(vec![fix(
+                    "use_sets_v2",
+                    "Create a set of version 2",
+                    SourceChange::from_text_edit(file_id, edit),
+                    call.range,
+                )]))
+            }
+            Rewrite::Manual => Diagnostic::new(
+                DiagnosticCode::LegacyCollection,
+                format!("{label} has no equivalent in maps, the function must be migrated by hand"),
+                call.range,
+            )
+            .with_severity(Severity::Information),
+        };
+        diagnostics.push(diagnostic);
+    }
+}
+
+/// Give the version as the last argument of the call.
+fn sets_v2_edit(call: &Call) -> Option<TextEdit> {
+    call.range_mf_only?;
+    let closing = call.range.end() - TextSize::from(1);
+    let option = if call.label.ends_with("/0") {
+        "[{version, 2}]"
+    } else {
+        ", [{version, 2}]"
+    };
+    Some(TextEdit::insert(closing, option.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::diagnostics::DiagnosticCode;
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig::default()
+            .enable(DiagnosticCode::LegacyCollection)
+            .disable(DiagnosticCode::UndefinedFunction)
+    }
+
+    #[test]
+    fn dict_calls() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([count/1, increment/2]).
+count(Words) ->
+    lists:foldl(fun(W, D) -> dict:update(W, fun(N) -> N + 1 end, 1, D) end, dict:new(), Words).
+%%                           ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ 💡 weak: dict:update/4 can be replaced by maps:update_with/4
+%%                                                                          ^^^^^^^^^^ 💡 weak: dict:new/0 can be replaced by maps:new/0
+increment(Word, D) ->
+    D1 = dict:update_counter(Word, 1, D),
+%%       ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ information: dict:update_counter/3 has no equivalent in maps, the function must be migrated by hand
+    dict:erase(undefined, D1).
+%%  ^^^^^^^^^^^^^^^^^^^^^^^^^ weak: dict:erase/2 can be replaced by maps:remove/2
+"#,
+        );
+    }
+
+    #[test]
+    fn rewrite_function_to_maps() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-export([index/1]).
+index(Users) ->
+    D = dict:from_list([{id(U), U} || U <- Users]),
+    case dict:fi~nd(admin, D) of
+        {ok, _} -> D;
+        error -> dict:store(admin, default, D)
+    end.
+id(U) -> U.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([index/1]).
+                index(Users) ->
+                    D = maps:from_list([{id(U), U} || U <- Users]),
+                    case maps:find(admin, D) of
+                        {ok, _} -> D;
+                        error -> maps:put(admin, default, D)
+                    end.
+                id(U) -> U.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn sets_v2() {
+        check_fix_with_config(
+            config(),
+            r#"
+//- otp_version:26.2.1
+-module(main).
+-export([unique/1]).
+unique(L) -> sets:to_list(sets:from_li~st(L)).
+"#,
+            expect![[r#"
+                -module(main).
+                -export([unique/1]).
+                unique(L) -> sets:to_list(sets:from_list(L, [{version, 2}])).
+            "#]],
+        );
+    }
+
+    #[test]
+    fn sets_v2_not_available() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- otp_version:23.3
+-module(main).
+-export([empty/0]).
+empty() -> sets:new().
+"#,
+        );
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        check_diagnostics_with_config(
+            DiagnosticsConfig::default().disable(DiagnosticCode::UndefinedFunction),
+            r#"
+-module(main).
+-export([empty/0]).
+empty() -> dict:new().
+"#,
+        );
+    }
+}
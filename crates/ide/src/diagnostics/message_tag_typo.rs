@@ -0,0 +1,213 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: message-tag-typo
+//
+// Return a warning if a tuple tagged with an atom is sent with `!`, no
+// receive or `handle_call/cast/info` clause in the project matches the
+// tag, but one matches a near-identical atom. Off by default, since it
+// scans every module of the project, once per edit of a module.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_syntax::ast::BinaryOp;
+use hir::fold::MacroStrategy;
+use hir::fold::ParenStrategy;
+use hir::AnyExpr;
+use hir::Expr;
+use hir::Semantic;
+use hir::Strategy;
+use text_edit::TextEdit;
+
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use crate::atoms::near_identical;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::Severity;
+use crate::fix;
+use crate::Diagnostic;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: true,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        message_tag_typo(diags, sema, file_id);
+    },
+};
+
+fn message_tag_typo(diagnostics: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let project_id = match sema.db.file_project_id(file_id) {
+        Some(project_id) => project_id,
+        None => return,
+    };
+    let mut handled = None;
+    sema.for_each_function(file_id, |def| {
+        let def_fb = def.in_function_body(sema, def);
+        let sends = def_fb.clone().fold_function(
+            Strategy {
+                macros: MacroStrategy::Expand,
+                parens: ParenStrategy::InvisibleParens,
+            },
+            Vec::new(),
+            &mut |mut acc, clause_id, ctx| {
+                if let AnyExpr::Expr(Expr::BinaryOp {
+                    rhs,
+                    op: BinaryOp::Send,
+                    ..
+                }) = ctx.item
+                {
+                    if let Expr::Tuple { exprs } = &def_fb.body(clause_id)[rhs] {
+                        if let Some(tag) = exprs.first() {
+                            acc.push((clause_id, *tag));
+                        }
+                    }
+                }
+                acc
+            },
+        );
+        for (clause_id, tag_expr) in sends {
+            let tag = match def_fb.in_clause(clause_id).as_atom_name(&tag_expr) {
+                Some(tag) => tag,
+                None => continue,
+            };
+            // Only scan the project once a tagged send is found
+            let handled = handled.get_or_insert_with(|| sema.db.message_tags(project_id));
+            if handled.contains(&tag) {
+                continue;
+            }
+            let sibling = handled
+                .iter()
+                .filter(|handled| near_identical(tag.as_str(), handled.as_str()))
+                .min();
+            if let (Some(sibling), Some(range)) =
+                (sibling, def_fb.range_for_expr(clause_id, tag_expr))
+            {
+                let edit = TextEdit::replace(range, sibling.to_string());
+                let diag = Diagnostic::new(
+                    DiagnosticCode::MessageTagTypo,
+                    format!(
+                        "No receive or handle clause in the project matches '{tag}', but one matches '{sibling}'."
+                    ),
+                    range,
+                )
+                .with_severity(Severity::Warning)
+                .with_fixes(Some(vec![fix(
+                    "replace_message_tag",
+                    &format!("Replace with '{sibling}'"),
+                    SourceChange::from_text_edit(file_id, edit),
+                    range,
+                )]))
+                .with_ignore_fix(sema, file_id);
+                diagnostics.push(diag);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_ide_db::DiagnosticCode;
+    use expect_test::expect;
+
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+
+    #[track_caller]
+    fn check_diagnostics(fixture: &str) {
+        let config = DiagnosticsConfig::default().enable(DiagnosticCode::MessageTagTypo);
+        check_diagnostics_with_config(config, fixture)
+    }
+
+    #[test]
+    fn typo_in_sent_tag() {
+        check_diagnostics(
+            r#"
+//- /src/client.erl
+-module(client).
+-export([send/2, ok/2]).
+send(Pid, Req) -> Pid ! {reqest, self(), Req}.
+%%                       ^^^^^^ 💡 warning: No receive or handle clause in the project matches 'reqest', but one matches 'request'.
+ok(Pid, Req) -> Pid ! {request, self(), Req}.
+//- /src/server.erl
+-module(server).
+-export([loop/0]).
+loop() ->
+    receive
+        {request, From, Req} -> From ! {reply, Req}, loop()
+    end.
+"#,
+        )
+    }
+
+    #[test]
+    fn gen_server_handler() {
+        check_diagnostics(
+            r#"
+//- /src/client.erl
+-module(client).
+-export([stop/1]).
+stop(Pid) -> Pid ! {shut_down, normal}.
+%%                  ^^^^^^^^^ 💡 warning: No receive or handle clause in the project matches 'shut_down', but one matches 'shutdown'.
+//- /src/server.erl
+-module(server).
+-export([handle_info/2]).
+handle_info({shutdown, Reason}, State) -> {stop, Reason, State}.
+"#,
+        )
+    }
+
+    #[test]
+    fn unrelated_tags_are_ignored() {
+        check_diagnostics(
+            r#"
+//- /src/client.erl
+-module(client).
+-export([send/1]).
+send(Pid) -> Pid ! {ping, self()}.
+//- /src/server.erl
+-module(server).
+-export([loop/0]).
+loop() ->
+    receive
+        {request, _From} -> loop()
+    end.
+"#,
+        )
+    }
+
+    #[test]
+    fn fix_replaces_tag() {
+        check_fix_with_config(
+            DiagnosticsConfig::default().enable(DiagnosticCode::MessageTagTypo),
+            r#"
+//- /src/client.erl
+-module(client).
+-export([send/2]).
+send(Pid, Req) -> Pid ! {req~est, self(), Req}.
+//- /src/server.erl
+-module(server).
+-export([loop/0]).
+loop() ->
+    receive
+        {request, _From, _Req} -> loop()
+    end.
+"#,
+            expect![[r#"
+                -module(client).
+                -export([send/2]).
+                send(Pid, Req) -> Pid ! {request, self(), Req}.
+            "#]],
+        )
+    }
+}
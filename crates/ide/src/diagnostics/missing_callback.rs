@@ -0,0 +1,228 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: missing-callback
+//
+// Return a warning on a `-behaviour` attribute if the module does not
+// define a callback the behaviour requires, optional callbacks aside.
+// Offer to export and add a stub for each of them, specced with the
+// `-callback` attribute, in which the types local to the behaviour module
+// are qualified with its name.
+
+use elp_ide_assists::helpers::ExportBuilder;
+use elp_ide_assists::helpers::ExportForm;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChangeBuilder;
+use elp_syntax::ast;
+use elp_syntax::ast::is_erlang_type;
+use elp_syntax::AstNode;
+use elp_syntax::TextSize;
+use hir::CallbackDef;
+use hir::NameArity;
+use hir::Semantic;
+use itertools::Itertools;
+
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::Severity;
+use crate::fix;
+use crate::Diagnostic;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        missing_callback(diags, sema, file_id);
+    },
+};
+
+fn missing_callback(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let form_list = sema.form_list(file_id);
+    let def_map = sema.def_map(file_id);
+    let source = sema.parse(file_id).value;
+    for (_, behaviour) in form_list.behaviour_attributes() {
+        let behaviour_name = behaviour.name.as_str();
+        let Some(module) = sema.resolve_module_name(file_id, behaviour_name) else {
+            continue;
+        };
+        let behaviour_def_map = sema.def_map(module.file.file_id);
+        let missing: Vec<(&NameArity, &CallbackDef)> = behaviour_def_map
+            .get_callbacks()
+            .iter()
+            .filter(|(name, callback)| {
+                !callback.optional
+                    && !behaviour_def_map.is_callback_optional(name)
+                    && def_map.get_function(name).is_none()
+            })
+            .sorted_by(|(a, _), (b, _)| a.cmp(b))
+            .collect();
+        if missing.is_empty() {
+            continue;
+        }
+        let range = behaviour.form_id.get(&source).syntax().text_range();
+        let names: Vec<NameArity> = missing.iter().map(|(name, _)| (*name).clone()).collect();
+        let stubs: String = missing
+            .iter()
+            .map(|(name, callback)| stub(sema, behaviour_name, name, callback))
+            .collect();
+        let mut builder = SourceChangeBuilder::new(file_id);
+        ExportBuilder::new(sema, file_id, ExportForm::Functions, &names, &mut builder).finish();
+        let end = source.syntax().text_range().end();
+        if source.syntax().text().char_at(end - TextSize::from(1)) == Some('\n') {
+            builder.insert(end, stubs);
+        } else {
+            builder.insert(end, format!("\n{stubs}"));
+        }
+        let plural = if names.len() == 1 { "" } else { "s" };
+        diags.push(
+            Diagnostic::new(
+                DiagnosticCode::MissingCallback,
+                format!(
+                    "missing callback{plural} of behaviour {behaviour_name}: {}",
+                    names.iter().join(", ")
+                ),
+                range,
+            )
+            .with_severity(Severity::Warning)
+            .with_fixes(Some(vec![fix(
+                "add_callback_stubs",
+                &format!("Add stub{plural} for the missing callback{plural}"),
+                builder.finish(),
+                range,
+            )])),
+        );
+    }
+}
+
+/// A stub raising an error, with the spec of the callback.
+fn stub(sema: &Semantic, behaviour_name: &str, name: &NameArity, callback: &CallbackDef) -> String {
+    let source = callback.source(sema.db.upcast());
+    let sigs = source
+        .sigs()
+        .map(|sig| qualified_sig(&sig, behaviour_name))
+        .join("; ");
+    let params = callback
+        .arg_names(sema.db.upcast())
+        .map(|names| {
+            names
+                .iter()
+                .map(|name| format!("_{}", name.name()))
+                .join(", ")
+        })
+        .unwrap_or_else(|| {
+            (1..=name.arity())
+                .map(|index| format!("_Arg{index}"))
+                .join(", ")
+        });
+    let fun = name.name();
+    format!("\n-spec {fun}{sigs}.\n{fun}({params}) ->\n    error(not_implemented).\n")
+}
+
+/// The text of the signature, with the types defined by the behaviour
+/// module qualified with its name.
+fn qualified_sig(sig: &ast::TypeSig, behaviour_name: &str) -> String {
+    let start = sig.syntax().text_range().start();
+    let mut text = sig.syntax().text().to_string();
+    let local_types: Vec<TextSize> = sig
+        .syntax()
+        .descendants()
+        .filter_map(ast::Call::cast)
+        .filter_map(|call| {
+            let ast::Expr::ExprMax(ast::ExprMax::Atom(atom)) = call.expr()? else {
+                return None;
+            };
+            let arity = call.args()?.args().count();
+            let name = atom.syntax().text().to_string();
+            (!is_erlang_type(&name, arity)).then(|| atom.syntax().text_range().start() - start)
+        })
+        .collect();
+    for offset in local_types.into_iter().rev() {
+        text.insert_str(offset.into(), &format!("{behaviour_name}:"));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::tests::check_diagnostics;
+    use crate::tests::check_fix;
+
+    #[test]
+    fn missing_callbacks() {
+        check_diagnostics(
+            r#"
+//- /src/my_behaviour.erl
+-module(my_behaviour).
+-type state() :: map().
+-callback init(Args :: list()) -> {ok, state()}.
+-callback handle(Msg :: term(), State :: state()) -> {noreply, state()}.
+-callback stop(state()) -> ok.
+-callback format(state()) -> iolist().
+-optional_callbacks([format/1]).
+//- /src/main.erl
+-module(main).
+-behaviour(my_behaviour).
+%%<^^^^^^^^^^^^^^^^^^^^^^ 💡 warning: missing callbacks of behaviour my_behaviour: handle/2, stop/1
+-export([init/1]).
+init(_) -> {ok, #{}}.
+"#,
+        );
+    }
+
+    #[test]
+    fn all_callbacks_defined() {
+        check_diagnostics(
+            r#"
+//- /src/my_behaviour.erl
+-module(my_behaviour).
+-callback init(Args :: list()) -> ok.
+//- /src/main.erl
+-module(main).
+-behaviour(my_behaviour).
+-export([init/1]).
+init(_) -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn add_callback_stubs() {
+        check_fix(
+            r#"
+//- /src/my_behaviour.erl
+-module(my_behaviour).
+-type state() :: map().
+-callback init(Args :: list()) -> {ok, state()}.
+-callback handle(Msg :: term(), State :: state()) -> {noreply, state()}.
+//- /src/main.erl
+-module(main).
+-behav~iour(my_behaviour).
+-export([init/1]).
+init(_) -> {ok, #{}}.
+"#,
+            expect![[r#"
+                -module(main).
+                -behaviour(my_behaviour).
+                -export([init/1, handle/2]).
+                init(_) -> {ok, #{}}.
+
+                -spec handle(Msg :: term(), State :: my_behaviour:state()) -> {noreply, my_behaviour:state()}.
+                handle(_Msg, _State) ->
+                    error(not_implemented).
+            "#]],
+        );
+    }
+}
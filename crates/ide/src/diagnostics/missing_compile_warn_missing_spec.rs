@@ -60,7 +60,7 @@ fn missing_compile_warn_missing_spec(
     file_kind: FileKind,
 ) {
     match file_kind {
-        FileKind::Header | FileKind::Other | FileKind::OutsideProjectModel => {
+        FileKind::Header | FileKind::Grammar | FileKind::Other | FileKind::OutsideProjectModel => {
             return;
         }
         _ => {}
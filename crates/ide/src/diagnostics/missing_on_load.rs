@@ -0,0 +1,159 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: missing-on-load
+//
+// Return a warning if a module loads a NIF library with
+// `erlang:load_nif/2` and defines NIF stubs, i.e. functions whose body
+// only calls `erlang:nif_error/1,2`, but has no `-on_load` attribute, so
+// that the library is not loaded when the module is. Modules defining
+// stubs without loading a library, e.g. as placeholders, are left alone.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::AstNode;
+use hir::fold::MacroStrategy;
+use hir::fold::ParenStrategy;
+use hir::known;
+use hir::AnyExpr;
+use hir::Expr;
+use hir::Semantic;
+use hir::Strategy;
+
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::Severity;
+use crate::Diagnostic;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: false,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        missing_on_load(diags, sema, file_id);
+    },
+};
+
+fn missing_on_load(diagnostics: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let form_list = sema.db.file_form_list(file_id);
+    if form_list
+        .attributes()
+        .any(|(_, attr)| attr.name == known::on_load)
+    {
+        return;
+    }
+    if !calls_load_nif(sema, file_id) {
+        return;
+    }
+    let def_map = sema.def_map(file_id);
+    let stub = def_map
+        .get_functions()
+        .map(|(_, def)| def)
+        .filter(|def| def.file.file_id == file_id)
+        .find(|def| def.is_nif_stub(sema.db));
+    if let Some(def) = stub {
+        if let Some(name) = def.first_clause_name(sema.db.upcast()) {
+            let diag = Diagnostic::new(
+                DiagnosticCode::MissingOnLoad,
+                format!(
+                    "NIF stub '{}' is never replaced, the module calls erlang:load_nif/2 but has no -on_load attribute.",
+                    def.name
+                ),
+                name.syntax().text_range(),
+            )
+            .with_severity(Severity::Warning)
+            .with_ignore_fix(sema, file_id);
+            diagnostics.push(diag);
+        }
+    }
+}
+
+fn calls_load_nif(sema: &Semantic, file_id: FileId) -> bool {
+    // Cheap filter, to avoid folding every function of the module
+    if !sema.db.file_text(file_id).contains("load_nif") {
+        return false;
+    }
+    let mut found = false;
+    sema.for_each_function(file_id, |def| {
+        let def_fb = def.in_function_body(sema, def);
+        found |= def_fb.clone().fold_function(
+            Strategy {
+                macros: MacroStrategy::Expand,
+                parens: ParenStrategy::InvisibleParens,
+            },
+            false,
+            &mut |acc, clause_id, ctx| match ctx.item {
+                AnyExpr::Expr(Expr::Call { target, .. }) => {
+                    acc || target
+                        .label(2, sema, &def_fb.body(clause_id))
+                        .is_some_and(|label| label.as_str() == "erlang:load_nif/2")
+                }
+                _ => acc,
+            },
+        );
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn missing_on_load() {
+        check_diagnostics(
+            r#"
+-module(main).
+-export([init/0, add/2, sub/2]).
+init() -> erlang:load_nif("./main_nif", 0).
+  add(_A, _B) -> erlang:nif_error(not_loaded).
+%%^^^ 💡 warning: NIF stub 'add/2' is never replaced, the module calls erlang:load_nif/2 but has no -on_load attribute.
+sub(_A, _B) -> erlang:nif_error(not_loaded).
+"#,
+        )
+    }
+
+    #[test]
+    fn stubs_without_load_nif() {
+        check_diagnostics(
+            r#"
+-module(main).
+-export([add/2]).
+add(_A, _B) -> erlang:nif_error(not_implemented).
+"#,
+        )
+    }
+
+    #[test]
+    fn on_load_present() {
+        check_diagnostics(
+            r#"
+-module(main).
+-export([add/2]).
+-on_load(init/0).
+init() -> erlang:load_nif("./main_nif", 0).
+add(_A, _B) -> erlang:nif_error(not_loaded).
+"#,
+        )
+    }
+
+    #[test]
+    fn no_nif_stubs() {
+        check_diagnostics(
+            r#"
+-module(main).
+-export([add/2]).
+add(A, B) -> A + B.
+"#,
+        )
+    }
+}
@@ -0,0 +1,264 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: missing-spec
+//
+// Return a warning if an exported function has no `-spec`, when the
+// diagnostic is enabled. Test suites and generated files are not checked.
+// Offer to add a spec built from the types
+// eqWAlizer found for the arguments and the returned values of the
+// clauses. A type eqWAlizer did not find is `dynamic()` in a module it
+// checks, and `term()` otherwise.
+
+use std::sync::Arc;
+
+use elp_eqwalizer::ast::Pos;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::eqwalizer::type_of_range;
+use elp_ide_db::source_change::SourceChange;
+use elp_ide_db::EqwalizerDatabase;
+use elp_ide_db::RootDatabase;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use elp_types_db::eqwalizer::types::Type;
+use elp_types_db::IncludeGenerated;
+use hir::Semantic;
+use itertools::Itertools;
+use text_edit::TextEdit;
+
+use crate::diagnostics::ConfiguredDiagnosticDescriptor;
+use crate::diagnostics::Diagnostic;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::DiagnosticConditions;
+use crate::diagnostics::Severity;
+use crate::fix;
+
+pub(crate) static DESCRIPTOR: ConfiguredDiagnosticDescriptor = ConfiguredDiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: false,
+        default_disabled: true,
+    },
+    checker: &|diags, sema, db, file_id, file_kind, config| {
+        if file_kind.is_module() {
+            // When not enabled, the diagnostics are only used to tell
+            // whether a suppression is still needed
+            let infer = config.enabled.contains(&DiagnosticCode::MissingSpec);
+            missing_spec(diags, sema, db, file_id, infer);
+        }
+    },
+};
+
+fn missing_spec(
+    diagnostics: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    db: &RootDatabase,
+    file_id: FileId,
+    infer: bool,
+) {
+    let def_map = sema.def_map(file_id);
+    let mut missing = def_map
+        .get_functions()
+        .filter(|(_, def)| def.file.file_id == file_id && def.exported && def.spec.is_none())
+        .peekable();
+    if missing.peek().is_none() {
+        return;
+    }
+    // Only run eqWAlizer when there is a spec to infer.
+    let types = if infer {
+        db.types_for_file(file_id)
+    } else {
+        None
+    };
+    let unknown = if db.is_eqwalizer_enabled(file_id, IncludeGenerated::Yes) {
+        "dynamic()"
+    } else {
+        "term()"
+    };
+    for (name, def) in missing {
+        let source = def.source(sema.db.upcast());
+        let Some(first) = source.first() else {
+            continue;
+        };
+        let Some(fun_name) = first.name() else {
+            continue;
+        };
+        let Some(spec) = inferred_spec(&source, types.as_ref(), unknown) else {
+            continue;
+        };
+        let range = fun_name.syntax().text_range();
+        let edit = TextEdit::insert(first.syntax().text_range().start(), spec);
+        diagnostics.push(
+            Diagnostic::new(
+                DiagnosticCode::MissingSpec,
+                format!("exported function {name} has no spec"),
+                range,
+            )
+            .with_severity(Severity::Warning)
+            .with_fixes(Some(vec![fix(
+                "add_inferred_spec",
+                &format!("Add spec for {name}"),
+                SourceChange::from_text_edit(file_id, edit),
+                range,
+            )])),
+        );
+    }
+}
+
+/// The spec of the function, with the types of each argument and of the
+/// returned value joined across the clauses.
+fn inferred_spec(
+    source: &[ast::FunDecl],
+    types: Option<&Arc<Vec<(Pos, Type)>>>,
+    unknown: &str,
+) -> Option<String> {
+    let clauses: Vec<ast::FunctionClause> = source
+        .iter()
+        .map(|fun_decl| match fun_decl.clause()? {
+            ast::FunctionOrMacroClause::FunctionClause(clause) => Some(clause),
+            ast::FunctionOrMacroClause::MacroCallExpr(_) => None,
+        })
+        .collect::<Option<_>>()?;
+    let name = clauses.first()?.name()?.syntax().text().to_string();
+    let type_of = |ranges: Vec<TextRange>| -> String {
+        let found: Option<Vec<String>> = ranges
+            .into_iter()
+            .map(|range| Some(type_of_range(types?, range)?.to_string()))
+            .collect();
+        match found {
+            Some(found) if !found.is_empty() => found.into_iter().unique().join(" | "),
+            _ => unknown.to_string(),
+        }
+    };
+    let args: Vec<Vec<ast::Expr>> = clauses
+        .iter()
+        .map(|clause| Some(clause.args()?.args().collect()))
+        .collect::<Option<_>>()?;
+    let params = (0..args[0].len())
+        .map(|index| {
+            let ranges = args
+                .iter()
+                .filter_map(|args| Some(args.get(index)?.syntax().text_range()))
+                .collect();
+            let ty = type_of(ranges);
+            match &args[0][index] {
+                ast::Expr::ExprMax(ast::ExprMax::Var(var)) if !var.text().starts_with('_') => {
+                    format!("{} :: {ty}", var.text())
+                }
+                _ => ty,
+            }
+        })
+        .join(", ");
+    let returns = clauses
+        .iter()
+        .map(|clause| Some(clause.body()?.exprs().last()?.syntax().text_range()))
+        .collect::<Option<Vec<_>>>()
+        .map_or_else(|| unknown.to_string(), type_of);
+    Some(format!("-spec {name}({params}) -> {returns}.\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_project_model::otp::otp_supported_by_eqwalizer;
+    use expect_test::expect;
+
+    use crate::diagnostics::DiagnosticCode;
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig::default().enable(DiagnosticCode::MissingSpec)
+    }
+
+    #[test]
+    fn exported_function_without_spec() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([area/1, perimeter/1]).
+-spec perimeter(number()) -> number().
+perimeter(Side) -> 4 * Side.
+area(Side) -> Side * Side.
+%%<^ 💡 warning: exported function area/1 has no spec
+local(X) -> X.
+"#,
+        );
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        check_diagnostics_with_config(
+            DiagnosticsConfig::default(),
+            r#"
+-module(main).
+-export([area/1]).
+area(Side) -> Side * Side.
+"#,
+        );
+    }
+
+    #[test]
+    fn not_in_tests() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /main/test/main_SUITE.erl app:main extra:test
+-module(main_SUITE).
+-export([all/0]).
+all() -> [].
+"#,
+        );
+    }
+
+    #[test]
+    fn spec_without_eqwalizer() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-export([area/2]).
+ar~ea(square, Side) -> Side * Side;
+area(_Shape, _) -> 0.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([area/2]).
+                -spec area(term(), Side :: term()) -> term().
+                area(square, Side) -> Side * Side;
+                area(_Shape, _) -> 0.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn spec_with_eqwalizer() {
+        if otp_supported_by_eqwalizer() {
+            check_fix_with_config(
+                config(),
+                r#"
+//- eqwalizer
+//- /app_a/src/main.erl
+-module(main).
+-export([name/1]).
+na~me(User) -> User.
+"#,
+                expect![[r#"
+                    -module(main).
+                    -export([name/1]).
+                    -spec name(User :: dynamic()) -> dynamic().
+                    name(User) -> User.
+                "#]],
+            );
+        }
+    }
+}
@@ -130,6 +130,7 @@ fn make_diagnostic(
         attr_name_range,
     )
     .with_related(Some(vec![RelatedInformation {
+        file_id: None,
         range: attr_name_range,
         message: "Misspelled attribute".to_string(),
     }]))
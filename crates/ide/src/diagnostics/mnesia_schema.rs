@@ -0,0 +1,262 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: mnesia-schema-mismatch
+//
+// Return a warning if a mnesia access does not agree with the schema
+// given to `mnesia:create_table/2` in the same application.
+// Off by default, since the schema is only known when tables are
+// created with literal options; enable it per project in `.elp_lint.toml`.
+
+use elp_ide_db::elp_base_db::FileId;
+use hir::Expr;
+use hir::ExprId;
+use hir::FunctionDef;
+use hir::InFunctionClauseBody;
+use hir::MnesiaTables;
+use hir::Semantic;
+use lazy_static::lazy_static;
+
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use crate::codemod_helpers::find_call_in_function;
+use crate::codemod_helpers::CheckCallCtx;
+use crate::codemod_helpers::MakeDiagCtx;
+use crate::diagnostics::Diagnostic;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::Severity;
+use crate::FunctionMatch;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: true,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        mnesia_schema(diags, sema, file_id);
+    },
+};
+
+#[derive(Debug, Clone, Copy)]
+enum MnesiaAccess {
+    /// The record argument must be of the record type stored in the
+    /// table. Without a table argument, the record name is the table.
+    Record { table: Option<usize>, record: usize },
+    /// The position argument must be an indexed attribute of the table.
+    Index { table: usize, pos: usize },
+}
+
+fn mnesia_schema(diagnostics: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    lazy_static! {
+        static ref ACCESSES: Vec<(FunctionMatch, MnesiaAccess)> = {
+            use MnesiaAccess::*;
+            let record_only = Record {
+                table: None,
+                record: 0,
+            };
+            let table_and_record = Record {
+                table: Some(0),
+                record: 1,
+            };
+            let index = Index { table: 0, pos: 2 };
+            vec![
+                (FunctionMatch::mfa("mnesia", "write", 1), record_only),
+                (FunctionMatch::mfa("mnesia", "write", 3), table_and_record),
+                (FunctionMatch::mfa("mnesia", "dirty_write", 1), record_only),
+                (
+                    FunctionMatch::mfa("mnesia", "dirty_write", 2),
+                    table_and_record,
+                ),
+                (FunctionMatch::mfa("mnesia", "match_object", 1), record_only),
+                (
+                    FunctionMatch::mfa("mnesia", "match_object", 3),
+                    table_and_record,
+                ),
+                (
+                    FunctionMatch::mfa("mnesia", "dirty_match_object", 1),
+                    record_only,
+                ),
+                (
+                    FunctionMatch::mfa("mnesia", "dirty_match_object", 2),
+                    table_and_record,
+                ),
+                (FunctionMatch::mfa("mnesia", "index_read", 3), index),
+                (FunctionMatch::mfa("mnesia", "dirty_index_read", 3), index),
+            ]
+        };
+        static ref ACCESS_MFAS: Vec<(&'static FunctionMatch, MnesiaAccess)> = ACCESSES
+            .iter()
+            .map(|(matcher, access)| (matcher, *access))
+            .collect();
+    }
+
+    let tables = sema.db.mnesia_tables(sema.db.file_source_root(file_id));
+    if tables.is_empty() {
+        return;
+    }
+    sema.def_map(file_id).get_functions().for_each(|(_, def)| {
+        find_call_in_function(
+            diagnostics,
+            sema,
+            def,
+            &ACCESS_MFAS,
+            &|CheckCallCtx {
+                  t, args, in_clause, ..
+              }: CheckCallCtx<'_, MnesiaAccess>| {
+                check_access(&tables, in_clause, *t, args)
+            },
+            &move |MakeDiagCtx {
+                       sema,
+                       def_fb,
+                       extra: (message, arg),
+                       range,
+                       ..
+                   }: MakeDiagCtx<'_, (String, ExprId)>|
+                  -> Option<Diagnostic> {
+                let range = def_fb.range_for_expr(*arg).unwrap_or(range);
+                let diag = Diagnostic::new(DiagnosticCode::MnesiaSchemaMismatch, message, range)
+                    .with_severity(Severity::Warning)
+                    .with_ignore_fix(sema, def_fb.file_id());
+                Some(diag)
+            },
+        );
+    });
+}
+
+fn check_access(
+    tables: &MnesiaTables,
+    in_clause: &InFunctionClauseBody<&FunctionDef>,
+    access: MnesiaAccess,
+    args: &[ExprId],
+) -> Option<(String, ExprId)> {
+    match access {
+        MnesiaAccess::Record { table, record } => {
+            let record = *args.get(record)?;
+            let record_name = match &in_clause[record] {
+                Expr::Record { name, .. } => in_clause.sema.db.lookup_atom(*name),
+                _ => return None,
+            };
+            let table = match table {
+                Some(table) => tables.get(&in_clause.as_atom_name(args.get(table)?)?)?,
+                None => tables.get(&record_name)?,
+            };
+            if table.record_name == record_name {
+                return None;
+            }
+            Some((
+                format!(
+                    "mnesia table '{}' holds '{}' records, not '{}'.",
+                    table.name, table.record_name, record_name
+                ),
+                record,
+            ))
+        }
+        MnesiaAccess::Index { table, pos } => {
+            let table = tables.get(&in_clause.as_atom_name(args.get(table)?)?)?;
+            let pos = *args.get(pos)?;
+            let field = match &in_clause[pos] {
+                Expr::RecordIndex { field, .. } => in_clause.sema.db.lookup_atom(*field),
+                _ => return None,
+            };
+            if table.index.contains(&field) {
+                return None;
+            }
+            Some((
+                format!("mnesia table '{}' has no index on '{}'.", table.name, field),
+                pos,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_ide_db::DiagnosticCode;
+
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics_with_config;
+
+    #[track_caller]
+    fn check_diagnostics(fixture: &str) {
+        let config = DiagnosticsConfig::default().enable(DiagnosticCode::MnesiaSchemaMismatch);
+        check_diagnostics_with_config(config, fixture)
+    }
+
+    const MNESIA: &str = r#"
+//- /opt/lib/mnesia-4.23/src/mnesia.erl otp_app:/opt/lib/mnesia-4.23
+-module(mnesia).
+-compile([export_all, nowarn_export_all]).
+create_table(_, _) -> ok.
+write(_) -> ok.
+write(_, _, _) -> ok.
+dirty_match_object(_) -> ok.
+index_read(_, _, _) -> ok.
+"#;
+
+    #[test]
+    fn wrong_record() {
+        check_diagnostics(&format!(
+            r#"
+//- /my_app/src/schema.erl app:my_app
+-module(schema).
+-export([init/0]).
+-record(person, {name, email, age}).
+init() ->
+    mnesia:create_table(people, [{record_name, person},
+                                 {attributes, record_info(fields, person)}]).
+//- /my_app/src/main.erl app:my_app
+-module(main).
+-export([store/1, people/0]).
+-record(person, {name}).
+-record(employee, {name}).
+store(N) ->
+    mnesia:write(people, #person{name = N}, write),
+    mnesia:write(people, #employee{name = N}, write).
+%%                       ^^^^^^^^^^^^^^^^^^^ 💡 warning: mnesia table 'people' holds 'person' records, not 'employee'.
+people() ->
+    mnesia:dirty_match_object(#person{_ = '_'}).
+{MNESIA}"#
+        ))
+    }
+
+    #[test]
+    fn missing_index() {
+        check_diagnostics(&format!(
+            r#"
+//- /my_app/src/main.erl app:my_app
+-module(main).
+-export([init/0, by_email/1, by_age/1]).
+-record(person, {name, email, age}).
+init() ->
+    mnesia:create_table(person, [{index, [#person.email]}]).
+by_email(E) ->
+    mnesia:index_read(person, E, #person.email).
+by_age(A) ->
+    mnesia:index_read(person, A, #person.age).
+%%                               ^^^^^^^^^^^ 💡 warning: mnesia table 'person' has no index on 'age'.
+{MNESIA}"#
+        ))
+    }
+
+    #[test]
+    fn unknown_tables_are_ignored() {
+        check_diagnostics(&format!(
+            r#"
+//- /my_app/src/main.erl app:my_app
+-module(main).
+-export([store/1]).
+-record(person, {name}).
+store(N) ->
+    mnesia:write(elsewhere, #person{name = N}, write).
+{MNESIA}"#
+        ))
+    }
+}
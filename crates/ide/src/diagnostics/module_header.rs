@@ -0,0 +1,527 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: module-header
+//
+// Return a warning on a module not following the `module_template` of
+// `.elp_lint.toml`: a module not starting with the header of the template,
+// such as a license, a missing or forbidden `-author` attribute, or an
+// attribute out of the order given by the template. Attributes within
+// conditional sections are left alone. Offer to insert or replace the
+// header, add or remove the `-author` attribute, and move the attribute.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use fxhash::FxHashMap;
+use hir::Semantic;
+use serde::Deserialize;
+use serde::Serialize;
+use text_edit::TextEdit;
+
+use crate::diagnostics::ConfiguredDiagnosticDescriptor;
+use crate::diagnostics::Diagnostic;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::DiagnosticConditions;
+use crate::diagnostics::Severity;
+use crate::fix;
+
+/// How a module template treats the `-author` attribute.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorPolicy {
+    #[default]
+    Optional,
+    Required,
+    Forbidden,
+}
+
+/// The way every module of a project starts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ModuleTemplate {
+    /// Comment lines each module starts with, such as a license.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    #[serde(default)]
+    pub author: AuthorPolicy,
+    /// The author of new modules, also given when adding a missing
+    /// `-author` attribute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author_name: Option<String>,
+    /// Names of attributes, in the order they must appear in when present.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attribute_order: Vec<String>,
+}
+
+impl ModuleTemplate {
+    /// The text of a new module following the template.
+    pub fn new_module(&self, module: &str) -> String {
+        let mut text = String::new();
+        if let Some(header) = self.header_text() {
+            text.push_str(&header);
+            text.push('\n');
+        }
+        text.push_str(&format!("-module({module}).\n"));
+        if let Some(author) = self.author_attribute() {
+            text.push_str(&author);
+            text.push('\n');
+        }
+        text
+    }
+
+    fn header_lines(&self) -> Option<Vec<&str>> {
+        let lines: Vec<&str> = self
+            .header
+            .as_ref()?
+            .trim_end()
+            .lines()
+            .map(str::trim_end)
+            .collect();
+        (!lines.is_empty()).then_some(lines)
+    }
+
+    fn header_text(&self) -> Option<String> {
+        Some(
+            self.header_lines()?
+                .iter()
+                .map(|line| format!("{line}\n"))
+                .collect(),
+        )
+    }
+
+    fn author_attribute(&self) -> Option<String> {
+        if self.author == AuthorPolicy::Forbidden {
+            return None;
+        }
+        let name = self.author_name.as_ref()?;
+        Some(format!("-author(\"{name}\")."))
+    }
+}
+
+pub(crate) static DESCRIPTOR: ConfiguredDiagnosticDescriptor = ConfiguredDiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, _db, file_id, file_kind, config| {
+        if file_kind.is_module() {
+            module_header(diags, sema, file_id, config.module_template.as_ref());
+        }
+    },
+};
+
+fn module_header(
+    diagnostics: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    template: Option<&ModuleTemplate>,
+) {
+    let Some(template) = template else {
+        return;
+    };
+    let source = sema.parse(file_id).value;
+    let Some(module) = source.forms().find_map(|form| match form {
+        ast::Form::ModuleAttribute(module) => Some(module),
+        _ => None,
+    }) else {
+        return;
+    };
+    let text = sema.db.file_text(file_id);
+    diagnostics.extend(check_header(file_id, template, &text, &module));
+    check_author(diagnostics, sema, file_id, template, &text, &module);
+    check_order(diagnostics, file_id, template, &text, &source);
+}
+
+fn check_header(
+    file_id: FileId,
+    template: &ModuleTemplate,
+    text: &str,
+    module: &ast::ModuleAttribute,
+) -> Option<Diagnostic> {
+    let header = template.header_lines()?;
+    let mut lines = text.lines().map(str::trim_end);
+    if header.iter().all(|expected| lines.next() == Some(expected)) {
+        return None;
+    }
+    let header_text = template.header_text()?;
+    let block_len: usize = text
+        .split_inclusive('\n')
+        .take_while(|line| line.starts_with('%'))
+        .map(str::len)
+        .sum();
+    let block = &text[..block_len];
+    // Comments before the module attribute may document it, so only a
+    // block which looks like a license is replaced.
+    let (range, fix_id, label, edit) = if is_license(block) {
+        (
+            TextRange::up_to(TextSize::of(block.trim_end())),
+            "replace_header",
+            "Replace the header with the one of the template",
+            TextEdit::replace(TextRange::up_to(TextSize::of(block)), header_text),
+        )
+    } else {
+        (
+            module.syntax().text_range(),
+            "insert_header",
+            "Insert the header of the template",
+            TextEdit::insert(TextSize::from(0), format!("{header_text}\n")),
+        )
+    };
+    Some(
+        Diagnostic::new(
+            DiagnosticCode::ModuleHeader,
+            "module does not start with the header of the template",
+            range,
+        )
+        .with_severity(Severity::Warning)
+        .with_fixes(Some(vec![fix(
+            fix_id,
+            label,
+            SourceChange::from_text_edit(file_id, edit),
+            range,
+        )])),
+    )
+}
+
+fn is_license(block: &str) -> bool {
+    let block = block.to_lowercase();
+    ["copyright", "license", "licence"]
+        .iter()
+        .any(|word| block.contains(word))
+}
+
+fn check_author(
+    diagnostics: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    template: &ModuleTemplate,
+    text: &str,
+    module: &ast::ModuleAttribute,
+) {
+    let form_list = sema.form_list(file_id);
+    let source = sema.parse(file_id).value;
+    let mut authors = form_list
+        .attributes()
+        .filter(|(_, attribute)| attribute.name.as_str() == "author")
+        .map(|(_, attribute)| attribute.form_id.get(&source).syntax().text_range())
+        .peekable();
+    match template.author {
+        AuthorPolicy::Optional => {}
+        AuthorPolicy::Required => {
+            if authors.peek().is_some() {
+                return;
+            }
+            let range = module.syntax().text_range();
+            let fixes = template.author_attribute().map(|author| {
+                vec![fix(
+                    "add_author",
+                    "Add the -author attribute of the template",
+                    SourceChange::from_text_edit(
+                        file_id,
+                        TextEdit::insert(range.end(), format!("\n{author}")),
+                    ),
+                    range,
+                )]
+            });
+            diagnostics.push(
+                Diagnostic::new(
+                    DiagnosticCode::ModuleHeader,
+                    "module has no -author attribute",
+                    range,
+                )
+                .with_severity(Severity::Warning)
+                .with_fixes(fixes),
+            );
+        }
+        AuthorPolicy::Forbidden => {
+            for range in authors {
+                diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticCode::ModuleHeader,
+                        "the template does not allow an -author attribute",
+                        range,
+                    )
+                    .with_severity(Severity::Warning)
+                    .with_fixes(Some(vec![fix(
+                        "remove_author",
+                        "Remove the -author attribute",
+                        SourceChange::from_text_edit(
+                            file_id,
+                            TextEdit::delete(with_newline(text, range)),
+                        ),
+                        range,
+                    )])),
+                );
+            }
+        }
+    }
+}
+
+fn check_order(
+    diagnostics: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    template: &ModuleTemplate,
+    text: &str,
+    source: &ast::SourceFile,
+) {
+    let ranks: FxHashMap<&str, usize> = template
+        .attribute_order
+        .iter()
+        .enumerate()
+        .map(|(rank, name)| (name.as_str(), rank))
+        .collect();
+    if ranks.is_empty() {
+        return;
+    }
+    let mut depth: usize = 0;
+    // The rank and range of the attributes in order so far
+    let mut placed: Vec<(usize, TextRange)> = Vec::new();
+    for form in source.forms() {
+        let Some(name) = attribute_name(&form) else {
+            continue;
+        };
+        let name = match name.as_str() {
+            "ifdef" | "ifndef" | "if" => {
+                depth += 1;
+                continue;
+            }
+            "endif" => {
+                depth = depth.saturating_sub(1);
+                continue;
+            }
+            "behavior" => "behaviour".to_string(),
+            _ => name,
+        };
+        let Some(&rank) = ranks.get(name.as_str()) else {
+            continue;
+        };
+        if depth > 0 {
+            continue;
+        }
+        let range = form.syntax().text_range();
+        let Some((later_rank, later_range)) = placed.iter().find(|(placed, _)| *placed > rank)
+        else {
+            placed.push((rank, range));
+            continue;
+        };
+        let later = &template.attribute_order[*later_rank];
+        let mut edit = TextEdit::builder();
+        edit.insert(later_range.start(), format!("{}\n", &text[range]));
+        edit.delete(with_newline(text, range));
+        diagnostics.push(
+            Diagnostic::new(
+                DiagnosticCode::ModuleHeader,
+                format!("-{name} should come before -{later}"),
+                range,
+            )
+            .with_severity(Severity::Warning)
+            .with_fixes(Some(vec![fix(
+                "reorder_attribute",
+                &format!("Move -{name} before -{later}"),
+                SourceChange::from_text_edit(file_id, edit.finish()),
+                range,
+            )])),
+        );
+    }
+}
+
+/// The name of an attribute, as written after its `-`.
+fn attribute_name(form: &ast::Form) -> Option<String> {
+    let mut tokens = form
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter(|token| !token.kind().is_trivia());
+    if tokens.next()?.kind() != SyntaxKind::ANON_DASH {
+        return None;
+    }
+    Some(tokens.next()?.text().to_string())
+}
+
+fn with_newline(text: &str, range: TextRange) -> TextRange {
+    if text[usize::from(range.end())..].starts_with('\n') {
+        TextRange::new(range.start(), range.end() + TextSize::of('\n'))
+    } else {
+        range
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::AuthorPolicy;
+    use super::ModuleTemplate;
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+
+    fn template() -> ModuleTemplate {
+        ModuleTemplate {
+            header: Some(
+                "%% Copyright (c) Acme Corp.\n%% SPDX-License-Identifier: Apache-2.0\n".to_string(),
+            ),
+            author: AuthorPolicy::Optional,
+            author_name: None,
+            attribute_order: vec![
+                "module".to_string(),
+                "author".to_string(),
+                "behaviour".to_string(),
+                "include".to_string(),
+                "export".to_string(),
+            ],
+        }
+    }
+
+    fn config(template: ModuleTemplate) -> DiagnosticsConfig {
+        DiagnosticsConfig::default().set_module_template(Some(template))
+    }
+
+    #[test]
+    fn missing_header_and_order() {
+        check_diagnostics_with_config(
+            config(template()),
+            r#"
+-module(main).
+%%<^^^^^^^^^^^^^ 💡 warning: module does not start with the header of the template
+-export([init/1]).
+-behaviour(gen_server).
+%%<^^^^^^^^^^^^^^^^^^^^^^ 💡 warning: -behaviour should come before -export
+-ifdef(TEST).
+-author("test").
+-endif.
+init(_) -> {ok, []}.
+"#,
+        );
+    }
+
+    #[test]
+    fn insert_header() {
+        check_fix_with_config(
+            config(template()),
+            r#"
+-modu~le(main).
+"#,
+            expect![[r#"
+                %% Copyright (c) Acme Corp.
+                %% SPDX-License-Identifier: Apache-2.0
+
+                -module(main).
+            "#]],
+        );
+    }
+
+    #[test]
+    fn replace_outdated_license() {
+        check_fix_with_config(
+            config(template()),
+            r#"
+%% Copyright (c) Ac~me Inc.
+%% All rights reserved.
+
+-module(main).
+"#,
+            expect![[r#"
+                %% Copyright (c) Acme Corp.
+                %% SPDX-License-Identifier: Apache-2.0
+
+                -module(main).
+            "#]],
+        );
+    }
+
+    #[test]
+    fn move_attribute() {
+        check_fix_with_config(
+            config(ModuleTemplate {
+                header: None,
+                ..template()
+            }),
+            r#"
+-module(main).
+-export([init/1]).
+-include("defs.hrl").
+-behav~iour(gen_server).
+init(_) -> {ok, []}.
+"#,
+            expect![[r#"
+                -module(main).
+                -behaviour(gen_server).
+                -export([init/1]).
+                -include("defs.hrl").
+                init(_) -> {ok, []}.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn add_required_author() {
+        check_fix_with_config(
+            config(ModuleTemplate {
+                header: None,
+                author: AuthorPolicy::Required,
+                author_name: Some("platform-team".to_string()),
+                attribute_order: vec![],
+            }),
+            r#"
+-mod~ule(main).
+-export([]).
+"#,
+            expect![[r#"
+                -module(main).
+                -author("platform-team").
+                -export([]).
+            "#]],
+        );
+    }
+
+    #[test]
+    fn remove_forbidden_author() {
+        check_fix_with_config(
+            config(ModuleTemplate {
+                header: None,
+                author: AuthorPolicy::Forbidden,
+                author_name: None,
+                attribute_order: vec![],
+            }),
+            r#"
+-module(main).
+-aut~hor("mary").
+-export([]).
+"#,
+            expect![[r#"
+                -module(main).
+                -export([]).
+            "#]],
+        );
+    }
+
+    #[test]
+    fn new_module() {
+        let template = ModuleTemplate {
+            author: AuthorPolicy::Required,
+            author_name: Some("platform-team".to_string()),
+            ..template()
+        };
+        expect![[r#"
+            %% Copyright (c) Acme Corp.
+            %% SPDX-License-Identifier: Apache-2.0
+
+            -module(billing).
+            -author("platform-team").
+        "#]]
+        .assert_eq(&template.new_module("billing"));
+    }
+}
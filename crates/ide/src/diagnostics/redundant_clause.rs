@@ -0,0 +1,521 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: redundant-clause
+//
+// Return a warning if a function or case clause can never match, because
+// an earlier clause matches every value it does. A clause with a guard
+// only covers the later clauses with the same patterns and guard. The
+// clause covering it is given as related information. Offer to remove the
+// clause, or to move it before the clause covering it.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use elp_syntax::TextRange;
+use hir::fold::MacroStrategy;
+use hir::fold::ParenStrategy;
+use hir::AnyExpr;
+use hir::AnyExprId;
+use hir::Atom;
+use hir::CRClause;
+use hir::ClauseId;
+use hir::Expr;
+use hir::ExprId;
+use hir::FunctionDef;
+use hir::InFunctionClauseBody;
+use hir::Literal;
+use hir::Pat;
+use hir::PatId;
+use hir::Semantic;
+use hir::Strategy;
+use itertools::Itertools;
+use text_edit::TextEdit;
+use text_edit::TextEditBuilder;
+
+use super::Diagnostic;
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use super::RelatedInformation;
+use super::Severity;
+use crate::diagnostics::DiagnosticCode;
+use crate::fix;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        redundant_clause(diags, sema, file_id);
+    },
+};
+
+fn redundant_clause(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    sema.for_each_function(file_id, |def| {
+        if let Some(clauses) = function_clauses(sema, def) {
+            check_clauses(diags, file_id, &clauses);
+        }
+        for clauses in case_clauses(sema, def) {
+            check_clauses(diags, file_id, &clauses);
+        }
+    });
+}
+
+/// The shape of a pattern, abstracting over the body it is lowered in, so
+/// the patterns of different function clauses can be compared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Shape {
+    /// A variable bound by the pattern, or `_`
+    Any,
+    Literal(Literal),
+    Tuple(Vec<Shape>),
+    List(Vec<Shape>, Option<Box<Shape>>),
+    Record(Atom, Vec<(Atom, Shape)>),
+    /// `Lhs = Rhs`
+    Alias(Box<Shape>, Box<Shape>),
+    /// Anything else, including variables bound earlier, matching a
+    /// single value which is not known.
+    Unknown,
+}
+
+struct ClauseInfo {
+    shapes: Vec<Shape>,
+    /// The patterns as written, on a single line
+    head: String,
+    /// The guard as written, on a single line, if it is not just `true`
+    guard: Option<String>,
+    /// The range the diagnostic is reported on
+    head_range: TextRange,
+    syntax: SyntaxNode,
+}
+
+fn function_clauses(sema: &Semantic, def: &FunctionDef) -> Option<Vec<ClauseInfo>> {
+    let def_fb = def.in_function_body(sema, ());
+    def_fb
+        .clauses()
+        .map(|(clause_id, clause)| {
+            let in_clause = def_fb.in_clause(clause_id);
+            let ast::FunctionOrMacroClause::FunctionClause(ast_clause) =
+                in_clause.ast_fun_decl().clause()?
+            else {
+                return None;
+            };
+            let args = ast_clause.args()?;
+            let head_start = ast_clause.syntax().text_range().start();
+            Some(ClauseInfo {
+                shapes: clause
+                    .clause
+                    .pats
+                    .iter()
+                    .map(|pat_id| shape(sema, in_clause, *pat_id))
+                    .collect(),
+                head: single_line(args.syntax()),
+                guard: guard_text(ast_clause.guard()),
+                head_range: TextRange::new(head_start, args.syntax().text_range().end()),
+                syntax: ast_clause.syntax().clone(),
+            })
+        })
+        .collect()
+}
+
+fn case_clauses(sema: &Semantic, def: &FunctionDef) -> Vec<Vec<ClauseInfo>> {
+    let def_fb = def.in_function_body(sema, ());
+    let cases: Vec<(ClauseId, ExprId, Vec<CRClause>)> = def_fb.clone().fold_function(
+        Strategy {
+            macros: MacroStrategy::DoNotExpand,
+            parens: ParenStrategy::InvisibleParens,
+        },
+        Vec::new(),
+        &mut |mut acc, clause_id, ctx| {
+            if let (AnyExprId::Expr(expr_id), AnyExpr::Expr(Expr::Case { clauses, .. })) =
+                (ctx.item_id, ctx.item)
+            {
+                acc.push((clause_id, expr_id, clauses));
+            }
+            acc
+        },
+    );
+    let source_file = sema.parse(def.file.file_id);
+    cases
+        .into_iter()
+        .filter_map(|(clause_id, expr_id, clauses)| {
+            let in_clause = def_fb.in_clause(clause_id);
+            let ast::Expr::ExprMax(ast::ExprMax::CaseExpr(case)) = in_clause
+                .get_body_map()
+                .expr(expr_id)?
+                .to_node(&source_file)?
+            else {
+                return None;
+            };
+            let ast_clauses: Vec<ast::CrClause> = case
+                .clauses()
+                .map(|clause| match clause {
+                    ast::CrClauseOrMacro::CrClause(clause) => Some(clause),
+                    ast::CrClauseOrMacro::MacroCallExpr(_) => None,
+                })
+                .collect::<Option<_>>()?;
+            if ast_clauses.len() != clauses.len() {
+                return None;
+            }
+            clauses
+                .iter()
+                .zip(ast_clauses)
+                .map(|(clause, ast_clause)| {
+                    let pat = ast_clause.pat()?;
+                    Some(ClauseInfo {
+                        shapes: vec![shape(sema, in_clause, clause.pat)],
+                        head: single_line(pat.syntax()),
+                        guard: guard_text(ast_clause.guard()),
+                        head_range: pat.syntax().text_range(),
+                        syntax: ast_clause.syntax().clone(),
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn shape<T>(sema: &Semantic, in_clause: &InFunctionClauseBody<T>, pat_id: PatId) -> Shape {
+    match &in_clause[pat_id] {
+        Pat::Var(var) => {
+            if var.as_string(sema.db.upcast()) == "_"
+                || matches!(
+                    in_clause.to_var_def_pat(pat_id),
+                    Some(hir::DefinitionOrReference::Definition(_))
+                )
+            {
+                Shape::Any
+            } else {
+                Shape::Unknown
+            }
+        }
+        Pat::Literal(literal) => Shape::Literal(literal.clone()),
+        Pat::Tuple { pats } => Shape::Tuple(
+            pats.iter()
+                .map(|pat_id| shape(sema, in_clause, *pat_id))
+                .collect(),
+        ),
+        Pat::List { pats, tail } => Shape::List(
+            pats.iter()
+                .map(|pat_id| shape(sema, in_clause, *pat_id))
+                .collect(),
+            tail.map(|tail| Box::new(shape(sema, in_clause, tail))),
+        ),
+        Pat::Record { name, fields } => Shape::Record(
+            *name,
+            fields
+                .iter()
+                .map(|(field, pat_id)| (*field, shape(sema, in_clause, *pat_id)))
+                .collect(),
+        ),
+        Pat::Match { lhs, rhs } => Shape::Alias(
+            Box::new(shape(sema, in_clause, *lhs)),
+            Box::new(shape(sema, in_clause, *rhs)),
+        ),
+        Pat::MacroCall { expansion, .. } => shape(sema, in_clause, *expansion),
+        _ => Shape::Unknown,
+    }
+}
+
+/// Whether every value matched by `b` is matched by `a`.
+fn subsumes(a: &Shape, b: &Shape) -> bool {
+    match (a, b) {
+        (Shape::Any, _) => true,
+        (Shape::Alias(lhs, rhs), _) => subsumes(lhs, b) && subsumes(rhs, b),
+        (_, Shape::Alias(lhs, rhs)) => subsumes(a, lhs) || subsumes(a, rhs),
+        (Shape::Literal(a), Shape::Literal(b)) => a == b,
+        (Shape::Tuple(a), Shape::Tuple(b)) => all_subsume(a, b),
+        (Shape::List(a, a_tail), Shape::List(b, b_tail)) => {
+            if a.len() > b.len() || !all_subsume(a, &b[..a.len()]) {
+                return false;
+            }
+            let b_rest = Shape::List(b[a.len()..].to_vec(), b_tail.clone());
+            match a_tail {
+                Some(a_tail) => subsumes(a_tail, &b_rest),
+                None => b_rest == Shape::List(Vec::new(), None),
+            }
+        }
+        (Shape::Record(a_name, a_fields), Shape::Record(b_name, b_fields)) => {
+            a_name == b_name
+                && a_fields.iter().all(|(field, a)| {
+                    match b_fields.iter().find(|(b_field, _)| b_field == field) {
+                        Some((_, b)) => subsumes(a, b),
+                        None => *a == Shape::Any,
+                    }
+                })
+        }
+        _ => false,
+    }
+}
+
+fn all_subsume(a: &[Shape], b: &[Shape]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| subsumes(a, b))
+}
+
+/// Whether clause `a` matches every value clause `b` matches. Guards are
+/// not evaluated, so a guarded clause only covers the same clause.
+fn covers(a: &ClauseInfo, b: &ClauseInfo) -> bool {
+    match &a.guard {
+        None => all_subsume(&a.shapes, &b.shapes),
+        Some(guard) => b.guard.as_ref() == Some(guard) && a.head == b.head,
+    }
+}
+
+fn check_clauses(diags: &mut Vec<Diagnostic>, file_id: FileId, clauses: &[ClauseInfo]) {
+    for (idx, clause) in clauses.iter().enumerate() {
+        let Some(covering) = clauses[..idx]
+            .iter()
+            .find(|earlier| covers(earlier, clause))
+        else {
+            continue;
+        };
+        let mut fixes = vec![fix(
+            "remove_redundant_clause",
+            "Remove the clause",
+            SourceChange::from_text_edit(file_id, removal_edit(clauses, idx).finish()),
+            clause.head_range,
+        )];
+        if !covers(clause, covering) {
+            let mut edit = removal_edit(clauses, idx);
+            edit.insert(
+                covering.syntax.text_range().start(),
+                format!("{};{}", clause.syntax.text(), separator(&covering.syntax)),
+            );
+            fixes.push(fix(
+                "move_redundant_clause",
+                "Move the clause before the clause covering it",
+                SourceChange::from_text_edit(file_id, edit.finish()),
+                clause.head_range,
+            ));
+        }
+        diags.push(
+            Diagnostic::new(
+                DiagnosticCode::RedundantClause,
+                "this clause can never match, an earlier clause covers it",
+                clause.head_range,
+            )
+            .with_severity(Severity::Warning)
+            .with_related(Some(vec![RelatedInformation {
+                file_id: None,
+                range: covering.head_range,
+                message: "Covered by this clause".to_string(),
+            }]))
+            .with_fixes(Some(fixes)),
+        );
+    }
+}
+
+/// Deletes the clause at `idx` with its separator, which is the one
+/// before it for the last clause.
+fn removal_edit(clauses: &[ClauseInfo], idx: usize) -> TextEditBuilder {
+    let range = clauses[idx].syntax.text_range();
+    let range = match clauses.get(idx + 1) {
+        Some(next) => TextRange::new(range.start(), next.syntax.text_range().start()),
+        None => TextRange::new(clauses[idx - 1].syntax.text_range().end(), range.end()),
+    };
+    let mut edit = TextEdit::builder();
+    edit.delete(range);
+    edit
+}
+
+/// The whitespace preceding the clause, to lay out a clause inserted
+/// before it in the same way.
+fn separator(clause: &SyntaxNode) -> String {
+    clause
+        .first_token()
+        .and_then(|token| token.prev_token())
+        .filter(|token| token.kind() == SyntaxKind::WHITESPACE)
+        .and_then(|token| {
+            let text = token.text();
+            text.rfind('\n').map(|idx| text[idx..].to_string())
+        })
+        .unwrap_or_else(|| " ".to_string())
+}
+
+fn single_line(node: &SyntaxNode) -> String {
+    node.text().to_string().split_whitespace().join(" ")
+}
+
+fn guard_text(guard: Option<ast::Guard>) -> Option<String> {
+    guard
+        .map(|guard| single_line(guard.syntax()))
+        .filter(|guard| guard != "true")
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::diagnostics::DiagnosticCode;
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::fixture;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+    use crate::tests::check_specific_fix_with_config;
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig::default()
+            .disable(DiagnosticCode::UndefinedFunction)
+            .disable(DiagnosticCode::UnusedParameter)
+    }
+
+    #[test]
+    fn catch_all_function_clause() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([area/1]).
+area({square, Side}) -> Side * Side;
+area(_) -> 0;
+area({circle, _}) -> 1;
+%%<^^^^^^^^^^^^^^ 💡 warning: this clause can never match, an earlier clause covers it
+area(other) -> 2.
+%%<^^^^^^^^ 💡 warning: this clause can never match, an earlier clause covers it
+"#,
+        );
+    }
+
+    #[test]
+    fn structural_subsumption() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([kind/1]).
+kind({ok, _}) -> ok;
+kind({error, Reason}) -> Reason;
+kind({ok, [1 | _]}) -> first;
+%%<^^^^^^^^^^^^^^^^ 💡 warning: this clause can never match, an earlier clause covers it
+kind([_, _ | _]) -> long;
+kind([_]) -> short;
+kind([a, b]) -> ab.
+%%<^^^^^^^^^ 💡 warning: this clause can never match, an earlier clause covers it
+"#,
+        );
+    }
+
+    #[test]
+    fn case_clauses() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([check/2]).
+check(X, Expected) ->
+    case X of
+        Expected -> same;
+        {Y, Y} -> pair;
+        {1, 2} -> one_two;
+        _ when X > 0 -> positive;
+        Other when X > 0 -> Other;
+        _ when X > 0 -> again;
+%%      ^ 💡 warning: this clause can never match, an earlier clause covers it
+        _ -> other;
+        0 -> zero
+%%      ^ 💡 warning: this clause can never match, an earlier clause covers it
+    end.
+"#,
+        );
+    }
+
+    #[test]
+    fn guarded_clause() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([sign/1]).
+sign(N) when N > 0 -> positive;
+sign(N) when N < 0 -> negative;
+sign(N) when N > 0 -> again;
+%%<^^^^ 💡 warning: this clause can never match, an earlier clause covers it
+sign(0) -> zero.
+"#,
+        );
+    }
+
+    #[test]
+    fn covering_clause_related() {
+        let (analysis, file_id) = fixture::single_file(
+            r#"
+-module(main).
+-export([area/1]).
+area({square, Side}) -> Side * Side;
+area(_) -> 0;
+area(other) -> 2.
+"#,
+        );
+        let diagnostics = analysis
+            .native_diagnostics(&config(), &vec![], file_id)
+            .unwrap();
+        let related: Vec<String> = diagnostics
+            .iter()
+            .flat_map(|diagnostic| diagnostic.related_info.iter().flatten())
+            .map(|related| {
+                let text = analysis.file_text(file_id).unwrap();
+                format!("{}: {}", &text[related.range], related.message)
+            })
+            .collect();
+        assert_eq!(related, vec!["area(_): Covered by this clause".to_string()]);
+    }
+
+    #[test]
+    fn remove_clause() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-export([area/1]).
+area(_) -> 0;
+ar~ea(other) -> 2.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([area/1]).
+                area(_) -> 0.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn move_clause() {
+        check_specific_fix_with_config(
+            Some("Move the clause before the clause covering it"),
+            r#"
+-module(main).
+-export([check/1]).
+check(X) ->
+    case X of
+        {ok, _} -> ok;
+        {error, _} -> error;
+        {ok, ~0} -> zero
+%%      ^^^^^^^ 💡 warning: this clause can never match, an earlier clause covers it
+    end.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([check/1]).
+                check(X) ->
+                    case X of
+                        {ok, 0} -> zero;
+                        {ok, _} -> ok;
+                        {error, _} -> error
+                    end.
+            "#]],
+            config(),
+        );
+    }
+}
@@ -0,0 +1,407 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: redundant-export
+//
+// Return a warning, when the diagnostic is enabled, on an entry of an
+// `-export` attribute exporting a function already exported by an earlier
+// entry in the same conditional section, or a function the module does
+// not define, and offer to remove the entry. The compiler reports both as
+// well, without a fix. When the module has an export attribute within
+// `-ifdef(TEST)`, return a weak warning on the functions exported
+// unconditionally whose only remote uses are in test suites or helpers,
+// and offer to move them to that export attribute. Behaviour callbacks
+// are left out, as they are called by the behaviour module.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_ide_db::SymbolDefinition;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SourceFile;
+use elp_syntax::SyntaxKind;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use fxhash::FxHashMap;
+use fxhash::FxHashSet;
+use hir::FormList;
+use hir::NameArity;
+use hir::PPCondition;
+use hir::PPConditionId;
+use hir::Semantic;
+use text_edit::TextEdit;
+
+use super::Diagnostic;
+use super::DiagnosticCode;
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use super::Severity;
+use crate::fix;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: true,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        redundant_export(diags, sema, file_id);
+    },
+};
+
+/// An entry of an `-export` attribute.
+struct Entry {
+    name: NameArity,
+    cond: Option<PPConditionId>,
+    /// The range of `name/arity`
+    range: TextRange,
+    /// The range deleted to remove the entry from its attribute
+    removal: TextRange,
+}
+
+fn redundant_export(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let form_list = sema.form_list(file_id);
+    let def_map = sema.def_map(file_id);
+    let source = sema.parse(file_id).value;
+    let entries = export_entries(&form_list, &source);
+
+    let mut seen: FxHashSet<(&NameArity, Option<PPConditionId>)> = FxHashSet::default();
+    for entry in &entries {
+        let name = &entry.name;
+        let message = if (entry.cond.is_some() && seen.contains(&(name, None)))
+            || !seen.insert((name, entry.cond))
+        {
+            format!("function {name} is already exported")
+        } else if def_map.get_function(name).is_none() {
+            format!("function {name} is exported but not defined")
+        } else {
+            continue;
+        };
+        diags.push(
+            Diagnostic::new(DiagnosticCode::RedundantExport, message, entry.range)
+                .with_severity(Severity::Warning)
+                .with_fixes(Some(vec![fix(
+                    "remove_export",
+                    &format!("Remove {name} from the export list"),
+                    SourceChange::from_text_edit(file_id, TextEdit::delete(entry.removal)),
+                    entry.range,
+                )])),
+        );
+    }
+
+    if let Some(test_export) = test_export(sema, file_id, &form_list, &source) {
+        test_only_exports(diags, sema, file_id, &form_list, &entries, &test_export);
+    }
+}
+
+fn export_entries(form_list: &FormList, source: &SourceFile) -> Vec<Entry> {
+    form_list
+        .exports()
+        .flat_map(|(_, export)| {
+            let attribute = export.form_id.get(source);
+            let funs: Vec<ast::Fa> = attribute.funs().collect();
+            export
+                .entries
+                .clone()
+                .filter_map(|entry_id| {
+                    let entry = &form_list[entry_id];
+                    let idx = entry.idx as usize;
+                    Some(Entry {
+                        name: entry.name.clone(),
+                        cond: export.cond,
+                        range: funs.get(idx)?.syntax().text_range(),
+                        removal: removal_range(&attribute, &funs, idx),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// The entry with the comma separating it from the next one, or from
+/// the previous one for the last entry, or the whole attribute for its
+/// only entry.
+fn removal_range(attribute: &ast::ExportAttribute, funs: &[ast::Fa], idx: usize) -> TextRange {
+    let range = funs[idx].syntax().text_range();
+    match (
+        funs.get(idx + 1),
+        idx.checked_sub(1).map(|prev| &funs[prev]),
+    ) {
+        (Some(next), _) => TextRange::new(range.start(), next.syntax().text_range().start()),
+        (None, Some(prev)) => TextRange::new(prev.syntax().text_range().end(), range.end()),
+        (None, None) => {
+            let range = attribute.syntax().text_range();
+            let newline = attribute
+                .syntax()
+                .last_token()
+                .and_then(|token| token.next_token())
+                .filter(|token| token.kind() == SyntaxKind::WHITESPACE)
+                .map_or(false, |token| token.text().starts_with('\n'));
+            if newline {
+                TextRange::new(range.start(), range.end() + TextSize::from(1))
+            } else {
+                range
+            }
+        }
+    }
+}
+
+/// An `-export` attribute directly within `-ifdef(TEST)`.
+struct TestExport {
+    attribute: ast::ExportAttribute,
+    names: FxHashSet<NameArity>,
+}
+
+fn test_export(
+    sema: &Semantic,
+    file_id: FileId,
+    form_list: &FormList,
+    source: &SourceFile,
+) -> Option<TestExport> {
+    let (_, export) = form_list.exports().find(|(_, export)| {
+        export.cond.map_or(false, |cond| {
+            matches!(&form_list[cond], PPCondition::Ifdef { name, .. } if name.as_str() == "TEST")
+        })
+    })?;
+    // Moving an export to the test section only makes sense in a module
+    // compiled without `TEST` outside of tests.
+    if sema.db.is_test_suite_or_test_helper(file_id) == Some(true) {
+        return None;
+    }
+    Some(TestExport {
+        attribute: export.form_id.get(source),
+        names: export
+            .entries
+            .clone()
+            .map(|entry_id| form_list[entry_id].name.clone())
+            .collect(),
+    })
+}
+
+fn test_only_exports(
+    diags: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    form_list: &FormList,
+    entries: &[Entry],
+    test_export: &TestExport,
+) {
+    let def_map = sema.def_map(file_id);
+    let callbacks: FxHashSet<NameArity> = form_list
+        .behaviour_attributes()
+        .filter_map(|(_, behaviour)| sema.resolve_module_name(file_id, behaviour.name.as_str()))
+        .flat_map(|module| {
+            sema.def_map(module.file.file_id)
+                .get_callbacks()
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let mut is_test_file: FxHashMap<FileId, bool> = FxHashMap::default();
+    for entry in entries {
+        let name = &entry.name;
+        if entry.cond.is_some() || test_export.names.contains(name) || callbacks.contains(name) {
+            continue;
+        }
+        let Some(def) = def_map.get_function(name) else {
+            continue;
+        };
+        let usages = SymbolDefinition::Function(def.clone()).usages(sema).all();
+        let mut remote_files = usages
+            .iter()
+            .map(|(usage_file_id, _)| usage_file_id)
+            .filter(|usage_file_id| *usage_file_id != file_id)
+            .peekable();
+        if remote_files.peek().is_none() {
+            continue;
+        }
+        let only_tests = remote_files.all(|usage_file_id| {
+            *is_test_file.entry(usage_file_id).or_insert_with(|| {
+                sema.db.is_test_suite_or_test_helper(usage_file_id) == Some(true)
+            })
+        });
+        if !only_tests {
+            continue;
+        }
+        let mut edit = TextEdit::builder();
+        edit.delete(entry.removal);
+        match test_export.attribute.funs().last() {
+            Some(last) => edit.insert(last.syntax().text_range().end(), format!(", {name}")),
+            None => {
+                let Some(open) = test_export
+                    .attribute
+                    .syntax()
+                    .children_with_tokens()
+                    .find(|child| child.kind() == SyntaxKind::ANON_LBRACK)
+                else {
+                    continue;
+                };
+                edit.insert(open.text_range().end(), name.to_string())
+            }
+        }
+        diags.push(
+            Diagnostic::new(
+                DiagnosticCode::RedundantExport,
+                format!("function {name} is only used remotely by tests"),
+                entry.range,
+            )
+            .with_severity(Severity::WeakWarning)
+            .with_fixes(Some(vec![fix(
+                "move_export_to_test",
+                &format!("Move {name} to the export list of -ifdef(TEST)"),
+                SourceChange::from_text_edit(file_id, edit.finish()),
+                entry.range,
+            )])),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::diagnostics::DiagnosticCode;
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig::default()
+            .enable(DiagnosticCode::RedundantExport)
+            .disable(DiagnosticCode::UndefinedFunction)
+    }
+
+    #[test]
+    fn duplicate_and_undefined_exports() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([start/0, stop/0]).
+-export([start/0, restart/0]).
+%%       ^^^^^^^ 💡 warning: function start/0 is already exported
+%%                ^^^^^^^^^ 💡 warning: function restart/0 is exported but not defined
+-ifdef(TEST).
+-export([stop/0]).
+%%       ^^^^^^ 💡 warning: function stop/0 is already exported
+-endif.
+start() -> ok.
+stop() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_duplicate_export() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-export([start/0, stop/0]).
+-export([st~art/0, status/0]).
+start() -> ok.
+stop() -> ok.
+status() -> ok.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([start/0, stop/0]).
+                -export([status/0]).
+                start() -> ok.
+                stop() -> ok.
+                status() -> ok.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn remove_sole_export() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-export([start/0]).
+-export([sta~rt/0]).
+start() -> ok.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([start/0]).
+                start() -> ok.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn move_test_only_export() {
+        check_fix_with_config(
+            config(),
+            r#"
+//- /main/src/main.erl app:main
+-module(main).
+-export([start/0, sta~te/0]).
+-ifdef(TEST).
+-export([reset/0]).
+-endif.
+start() -> ok.
+state() -> ok.
+reset() -> ok.
+//- /main/src/main_sup.erl app:main
+-module(main_sup).
+-export([init/0]).
+init() -> main:start().
+//- /main/test/main_SUITE.erl app:main extra:test
+-module(main_SUITE).
+-export([state/1]).
+state(_Config) -> ok = main:state(), main:reset().
+"#,
+            expect![[r#"
+                -module(main).
+                -export([start/0]).
+                -ifdef(TEST).
+                -export([reset/0, state/0]).
+                -endif.
+                start() -> ok.
+                state() -> ok.
+                reset() -> ok.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn no_test_export_section() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /main/src/main.erl app:main
+-module(main).
+-export([state/0]).
+state() -> ok.
+//- /main/test/main_SUITE.erl app:main extra:test
+-module(main_SUITE).
+-export([state/1]).
+state(_Config) -> ok = main:state().
+"#,
+        );
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        check_diagnostics_with_config(
+            DiagnosticsConfig::default().disable(DiagnosticCode::UndefinedFunction),
+            r#"
+-module(main).
+-export([start/0, start/0]).
+start() -> ok.
+"#,
+        );
+    }
+}
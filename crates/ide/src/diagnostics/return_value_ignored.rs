@@ -0,0 +1,309 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: return-value-ignored
+//
+// Return a warning if a call to a function reporting failures through its
+// return value, such as `file:write/2`, is a statement whose value is
+// discarded. Further functions, expected to return `ok`, can be listed in
+// the `must_use` field of `.elp_lint.toml`. Offer to discard the value
+// explicitly and, when `assert_must_use` is set, to assert the value the
+// function returns on success.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::NodeOrToken;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use elp_syntax::TextRange;
+use hir::Semantic;
+use lazy_static::lazy_static;
+use text_edit::TextEdit;
+
+use super::effect_free_statement::is_followed_by;
+use super::effect_free_statement::is_statement;
+use crate::codemod_helpers::find_call_in_function;
+use crate::codemod_helpers::CheckCallCtx;
+use crate::codemod_helpers::MakeDiagCtx;
+use crate::diagnostics::ConfiguredDiagnosticDescriptor;
+use crate::diagnostics::Diagnostic;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::DiagnosticConditions;
+use crate::diagnostics::Severity;
+use crate::fix;
+use crate::FunctionMatch;
+use crate::MFA;
+
+lazy_static! {
+    /// The functions checked by default, with the value they return on
+    /// success.
+    static ref MUST_USE: Vec<(MFA, &'static str)> = [
+        ("file", "write", 2, "ok"),
+        ("file", "write_file", 2, "ok"),
+        ("file", "write_file", 3, "ok"),
+        ("file", "close", 1, "ok"),
+        ("file", "rename", 2, "ok"),
+        ("file", "make_dir", 1, "ok"),
+        ("file", "sync", 1, "ok"),
+        ("gen_tcp", "send", 2, "ok"),
+        ("gen_udp", "send", 4, "ok"),
+        ("ssl", "send", 2, "ok"),
+        ("ets", "insert_new", 2, "true"),
+    ]
+    .into_iter()
+    .map(|(module, name, arity, expected)| {
+        let mfa = MFA {
+            module: module.to_string(),
+            name: name.to_string(),
+            arity,
+        };
+        (mfa, expected)
+    })
+    .collect();
+}
+
+pub(crate) static DESCRIPTOR: ConfiguredDiagnosticDescriptor = ConfiguredDiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: false,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, _db, file_id, _file_kind, config| {
+        return_value_ignored(
+            diags,
+            sema,
+            file_id,
+            &config.must_use,
+            config.assert_must_use,
+        );
+    },
+};
+
+fn return_value_ignored(
+    diagnostics: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    must_use: &[MFA],
+    assert_must_use: bool,
+) {
+    let matches: Vec<(FunctionMatch, (String, &str))> = MUST_USE
+        .iter()
+        .map(|(mfa, expected)| (mfa.clone(), *expected))
+        .chain(must_use.iter().map(|mfa| (mfa.clone(), "ok")))
+        .map(|(mfa, expected)| {
+            let label = mfa.label();
+            (FunctionMatch::MFA { mfa }, (label, expected))
+        })
+        .collect();
+    let mfas: Vec<(&FunctionMatch, (String, &str))> = matches
+        .iter()
+        .map(|(matcher, extra)| (matcher, extra.clone()))
+        .collect();
+    let source = sema.parse(file_id);
+    sema.def_map(file_id).get_functions().for_each(|(_, def)| {
+        if def.file.file_id != file_id {
+            return;
+        }
+        find_call_in_function(
+            diagnostics,
+            sema,
+            def,
+            &mfas,
+            &|CheckCallCtx { t, .. }: CheckCallCtx<'_, (String, &str)>| Some(t.clone()),
+            &|MakeDiagCtx {
+                  range,
+                  extra: (label, expected),
+                  ..
+              }| {
+                let call = call_at(source.value.syntax(), range)?;
+                if !is_statement(&call) || !is_followed_by(SyntaxKind::ANON_COMMA, &call) {
+                    return None;
+                }
+                Some(make_diagnostic(
+                    file_id,
+                    range,
+                    label,
+                    assert_must_use.then_some(expected),
+                ))
+            },
+        );
+    });
+}
+
+/// The outermost expression spanning exactly the range of the call.
+fn call_at(source: &SyntaxNode, range: TextRange) -> Option<ast::Expr> {
+    let node = match source.covering_element(range) {
+        NodeOrToken::Node(node) => node,
+        NodeOrToken::Token(token) => token.parent()?,
+    };
+    node.ancestors()
+        .take_while(|node| node.text_range() == range)
+        .filter_map(ast::Expr::cast)
+        .last()
+}
+
+/// Asserting the value makes the call crash when it fails, so it is only
+/// offered when `expected` is given.
+fn make_diagnostic(
+    file_id: FileId,
+    range: TextRange,
+    label: &str,
+    expected: Option<&str>,
+) -> Diagnostic {
+    let mut fixes = vec![fix(
+        "discard_return_value",
+        "Discard the return value explicitly",
+        SourceChange::from_text_edit(file_id, TextEdit::insert(range.start(), "_ = ".to_string())),
+        range,
+    )];
+    if let Some(expected) = expected {
+        fixes.push(fix(
+            "match_return_value",
+            &format!("Assert that the call returns {expected}"),
+            SourceChange::from_text_edit(
+                file_id,
+                TextEdit::insert(range.start(), format!("{expected} = ")),
+            ),
+            range,
+        ));
+    }
+    Diagnostic::new(
+        DiagnosticCode::ReturnValueIgnored,
+        format!("the return value of {label} is ignored"),
+        range,
+    )
+    .with_severity(Severity::Warning)
+    .with_fixes(Some(fixes))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::diagnostics::DiagnosticCode;
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+    use crate::tests::check_specific_fix_with_config;
+    use crate::MFA;
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig::default().disable(DiagnosticCode::UndefinedFunction)
+    }
+
+    #[test]
+    fn ignored_return_value() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([save/2]).
+save(Fd, Data) ->
+    file:write(Fd, Data),
+%%  ^^^^^^^^^^^^^^^^^^^^ 💡 warning: the return value of file:write/2 is ignored
+    ets:insert_new(cache, {Fd, Data}),
+%%  ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ 💡 warning: the return value of ets:insert_new/2 is ignored
+    file:close(Fd).
+"#,
+        );
+    }
+
+    #[test]
+    fn return_value_used() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([save/2]).
+save(Fd, Data) ->
+    ok = file:write(Fd, Data),
+    case file:sync(Fd) of
+        ok -> file:close(Fd);
+        Error -> Error
+    end.
+"#,
+        );
+    }
+
+    #[test]
+    fn configured_function() {
+        check_diagnostics_with_config(
+            config().set_must_use(vec![MFA::new("db", "commit", 1)]),
+            r#"
+-module(main).
+-export([run/1]).
+run(Tx) ->
+    db:commit(Tx),
+%%  ^^^^^^^^^^^^^ 💡 warning: the return value of db:commit/1 is ignored
+    done.
+"#,
+        );
+    }
+
+    #[test]
+    fn not_in_tests() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /main/test/main_SUITE.erl app:main extra:test
+-module(main_SUITE).
+-export([cleanup/1]).
+cleanup(Fd) ->
+    file:close(Fd),
+    ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn discard_explicitly() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-export([save/2]).
+save(Fd, Data) ->
+    file:wr~ite(Fd, Data),
+    done.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([save/2]).
+                save(Fd, Data) ->
+                    _ = file:write(Fd, Data),
+                    done.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn assert_ok() {
+        check_specific_fix_with_config(
+            Some("Assert that the call returns ok"),
+            r#"
+-module(main).
+-export([save/2]).
+save(Fd, Data) ->
+    file:wr~ite(Fd, Data),
+    done.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([save/2]).
+                save(Fd, Data) ->
+                    ok = file:write(Fd, Data),
+                    done.
+            "#]],
+            config().set_assert_must_use(true),
+        );
+    }
+}
@@ -0,0 +1,113 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: stale-generated-file
+//
+// Return a warning if the `.erl` module generated from a yecc or leex
+// grammar is older than the grammar itself.
+
+use std::fs;
+use std::path::Path;
+
+use elp_ide_db::elp_base_db::path_for_file;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::FileKind;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use fxhash::FxHashSet;
+
+use super::Diagnostic;
+use super::DiagnosticCode;
+use super::Severity;
+
+pub(crate) fn stale_generated_file(
+    diagnostics: &mut Vec<Diagnostic>,
+    db: &dyn SourceDatabase,
+    file_id: FileId,
+) -> Option<()> {
+    if !db.stale_generated_files().contains(&file_id) {
+        return None;
+    }
+    let grammar_path = path_for_file(db, file_id)?;
+    let (name, _ext) = grammar_path.name_and_extension()?;
+    let text = db.file_text(file_id);
+    let first_line_end = text.find('\n').unwrap_or(text.len());
+    let range = TextRange::new(TextSize::from(0), TextSize::from(first_line_end as u32));
+    diagnostics.push(
+        Diagnostic::new(
+            DiagnosticCode::StaleGeneratedFile,
+            format!("Generated module '{name}' is older than this grammar, regenerate it."),
+            range,
+        )
+        .with_severity(Severity::Warning),
+    );
+    Some(())
+}
+
+/// The grammar files among `files` whose generated module is older than
+/// them. This reads the file system, so it is run by the loader rather
+/// than when computing diagnostics.
+pub fn stale_generated_files(
+    db: &dyn SourceDatabase,
+    files: impl IntoIterator<Item = FileId>,
+) -> FxHashSet<FileId> {
+    files
+        .into_iter()
+        .filter(|file_id| db.file_kind(*file_id) == FileKind::Grammar)
+        .filter(|file_id| is_stale_grammar(db, *file_id).unwrap_or(false))
+        .collect()
+}
+
+fn is_stale_grammar(db: &dyn SourceDatabase, file_id: FileId) -> Option<bool> {
+    let grammar_path = path_for_file(db, file_id)?;
+    let (name, _ext) = grammar_path.name_and_extension()?;
+    let project_id = db.file_project_id(file_id)?;
+    let generated_id = db.module_index(project_id).file_for_module(name)?;
+    let generated_path = path_for_file(db, generated_id)?;
+    Some(is_stale(
+        grammar_path.as_path()?.as_ref(),
+        generated_path.as_path()?.as_ref(),
+    ))
+}
+
+/// Compares on-disk modification times, since generated files are
+/// normally rebuilt outside the editor.
+fn is_stale(grammar: &Path, generated: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    match (modified(grammar), modified(generated)) {
+        (Some(grammar), Some(generated)) => generated < grammar,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::time::Duration;
+    use std::time::SystemTime;
+
+    use super::is_stale;
+
+    #[test]
+    fn older_generated_file_is_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let grammar = dir.path().join("parser.yrl");
+        let generated = dir.path().join("parser.erl");
+        let now = SystemTime::now();
+        File::create(&grammar).unwrap().set_modified(now).unwrap();
+        File::create(&generated)
+            .unwrap()
+            .set_modified(now - Duration::from_secs(60))
+            .unwrap();
+        assert!(is_stale(&grammar, &generated));
+        assert!(!is_stale(&generated, &grammar));
+        assert!(!is_stale(&grammar, &dir.path().join("missing.erl")));
+    }
+}
@@ -0,0 +1,126 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: standalone-file
+//
+// Return an information diagnostic for a module analyzed outside of any
+// rebar3 or buck2 project, in which case only OTP is known. The
+// diagnostics which depend on the rest of the project are not reported,
+// rather than being reported wrongly.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+
+use super::Diagnostic;
+use super::DiagnosticCode;
+use super::Severity;
+
+pub(crate) fn is_standalone(db: &dyn SourceDatabase, file_id: FileId) -> bool {
+    db.file_project_id(file_id)
+        .map_or(false, |project_id| db.project_data(project_id).standalone)
+}
+
+/// Whether the diagnostic may only be right given the other modules and
+/// headers of the project, such as the ones reporting calls to unknown
+/// modules or includes which cannot be found.
+pub(crate) fn is_project_dependent(code: &DiagnosticCode) -> bool {
+    match code {
+        DiagnosticCode::UndefinedFunction
+        | DiagnosticCode::UnexportedFunction
+        | DiagnosticCode::DependentHeader => true,
+        // Include not found, undefined macro, record and behaviour
+        DiagnosticCode::ErlangService(code) => {
+            matches!(
+                code.as_str(),
+                "E1516" | "E1507" | "E1508" | "L1252" | "L1285"
+            )
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn standalone_file(
+    diagnostics: &mut Vec<Diagnostic>,
+    db: &dyn SourceDatabase,
+    file_id: FileId,
+) -> Option<()> {
+    if !is_standalone(db, file_id) {
+        return None;
+    }
+    let range = db
+        .parse(file_id)
+        .tree()
+        .forms()
+        .find_map(|form| match form {
+            ast::Form::ModuleAttribute(attr) => Some(attr.syntax().text_range()),
+            _ => None,
+        })
+        .unwrap_or_else(|| TextRange::empty(TextSize::from(0)));
+    diagnostics.push(
+        Diagnostic::new(
+            DiagnosticCode::StandaloneFile,
+            "No project found for this file, only OTP is known. Diagnostics about other modules, headers and records of the project are unavailable.",
+            range,
+        )
+        .with_severity(Severity::Information),
+    );
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_project_dependent;
+    use crate::diagnostics::DiagnosticCode;
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn standalone_file() {
+        check_diagnostics(
+            r#"
+//- standalone
+//- /src/main.erl
+-module(main).
+%%<^^^^^^^^^^^^^ information: No project found for this file, only OTP is known. Diagnostics about other modules, headers and records of the project are unavailable.
+-export([main/0]).
+main() ->
+    other:run().
+"#,
+        );
+    }
+
+    #[test]
+    fn project_dependent_diagnostics_kept_in_a_project() {
+        check_diagnostics(
+            r#"
+//- /src/main.erl
+-module(main).
+-export([main/0]).
+main() ->
+    other:run().
+%%  ^^^^^^^^^^^ 💡 warning: Function 'other:run/0' is undefined.
+"#,
+        );
+    }
+
+    #[test]
+    fn project_dependent_codes() {
+        assert!(is_project_dependent(&DiagnosticCode::UndefinedFunction));
+        assert!(is_project_dependent(&DiagnosticCode::ErlangService(
+            "E1516".to_string()
+        )));
+        assert!(!is_project_dependent(&DiagnosticCode::ErlangService(
+            "L1230".to_string()
+        )));
+        assert!(!is_project_dependent(&DiagnosticCode::UnusedMacro));
+    }
+}
@@ -0,0 +1,343 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: uncaught-throw
+//
+// Return a warning if a process spawned by the module runs a function
+// which may `throw/1` without catching it, with the chain of calls the
+// throw propagates through. A function may throw if it calls `throw/1`,
+// or a function of the module which may throw, outside of a `catch` or of
+// a `try` catching throws. Off by default, as the analysis does not follow
+// calls to other modules.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxNode;
+use elp_syntax::TextRange;
+use fxhash::FxHashMap;
+use hir::Name;
+use hir::NameArity;
+use hir::Semantic;
+use itertools::Itertools;
+
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::Severity;
+use crate::Diagnostic;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: false,
+        default_disabled: true,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        uncaught_throw(diags, sema, file_id);
+    },
+};
+
+/// A call which may throw, if it is not caught where it is made.
+enum Escape {
+    Throw,
+    Call(NameArity),
+}
+
+/// What a spawned process runs.
+enum Entry {
+    Function(NameArity),
+    Fun(ast::AnonymousFun),
+}
+
+fn uncaught_throw(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let def_map = sema.def_map(file_id);
+    let escapes: Vec<(NameArity, Vec<Escape>)> = def_map
+        .get_functions()
+        .filter(|(_, def)| def.file.file_id == file_id)
+        .sorted_by_key(|(_, def)| def.range(sema.db.upcast()).map(|range| range.start()))
+        .map(|(name, def)| {
+            let escapes = def
+                .source(sema.db.upcast())
+                .iter()
+                .flat_map(|fun_decl| escapes_in(fun_decl.syntax()))
+                .collect();
+            (name.clone(), escapes)
+        })
+        .collect();
+    let throwing = throwing_functions(&escapes);
+
+    let module = sema.module_name(file_id);
+    let module = module.as_ref().map(|module| module.as_str());
+    let source = sema.parse(file_id).value;
+    for call in source.syntax().descendants().filter_map(ast::Call::cast) {
+        let Some((range, entry)) = spawned(&call, module) else {
+            continue;
+        };
+        let path: Option<Vec<String>> = match &entry {
+            Entry::Function(name) => throwing
+                .get(name)
+                .map(|path| [name.to_string()].into_iter().chain(path.clone()).collect()),
+            Entry::Fun(fun) => path_of(&escapes_in(fun.syntax()), &throwing)
+                .map(|path| ["fun".to_string()].into_iter().chain(path).collect()),
+        };
+        let Some(path) = path else {
+            continue;
+        };
+        diags.push(
+            Diagnostic::new(
+                DiagnosticCode::UncaughtThrow,
+                format!(
+                    "a throw can escape the spawned process: {}",
+                    path.join(" -> ")
+                ),
+                range,
+            )
+            .with_severity(Severity::Warning),
+        );
+    }
+}
+
+/// For each function which may throw, the calls the throw propagates
+/// through, ending with `throw/1`.
+fn throwing_functions(escapes: &[(NameArity, Vec<Escape>)]) -> FxHashMap<NameArity, Vec<String>> {
+    let mut throwing = FxHashMap::default();
+    loop {
+        let mut changed = false;
+        for (name, escapes) in escapes {
+            if throwing.contains_key(name) {
+                continue;
+            }
+            if let Some(path) = path_of(escapes, &throwing) {
+                throwing.insert(name.clone(), path);
+                changed = true;
+            }
+        }
+        if !changed {
+            return throwing;
+        }
+    }
+}
+
+fn path_of(
+    escapes: &[Escape],
+    throwing: &FxHashMap<NameArity, Vec<String>>,
+) -> Option<Vec<String>> {
+    if escapes.iter().any(|escape| matches!(escape, Escape::Throw)) {
+        return Some(vec!["throw/1".to_string()]);
+    }
+    escapes.iter().find_map(|escape| match escape {
+        Escape::Call(callee) => {
+            let path = throwing.get(callee)?;
+            Some(
+                [callee.to_string()]
+                    .into_iter()
+                    .chain(path.clone())
+                    .collect(),
+            )
+        }
+        Escape::Throw => None,
+    })
+}
+
+/// The calls under `root` which may throw, skipping those caught, and
+/// those in the body of a nested `fun`, which does not run there.
+fn escapes_in(root: &SyntaxNode) -> Vec<Escape> {
+    root.descendants()
+        .filter_map(ast::Call::cast)
+        .filter(|call| !is_caught(call.syntax(), root))
+        .filter_map(|call| {
+            let args = call.args()?.args().count() as u32;
+            let name = match call.expr()? {
+                ast::Expr::ExprMax(ast::ExprMax::Atom(atom)) => atom.syntax().text().to_string(),
+                ast::Expr::Remote(remote) => {
+                    let module = remote.module()?.module()?.syntax().text().to_string();
+                    let fun = remote.fun()?.syntax().text().to_string();
+                    return (module == "erlang" && fun == "throw" && args == 1)
+                        .then_some(Escape::Throw);
+                }
+                _ => return None,
+            };
+            if name == "throw" && args == 1 {
+                Some(Escape::Throw)
+            } else {
+                let name = NameArity::new(Name::from_erlang_service(&name), args);
+                Some(Escape::Call(name))
+            }
+        })
+        .collect()
+}
+
+fn is_caught(node: &SyntaxNode, root: &SyntaxNode) -> bool {
+    let mut child = node.clone();
+    for ancestor in node.ancestors().skip(1) {
+        if &ancestor == root {
+            return false;
+        }
+        if ast::CatchExpr::can_cast(ancestor.kind()) || ast::AnonymousFun::can_cast(ancestor.kind())
+        {
+            return true;
+        }
+        if let Some(try_expr) = ast::TryExpr::cast(ancestor.clone()) {
+            let in_body = try_expr.exprs().any(|expr| expr.syntax() == &child);
+            if in_body && try_expr.catch().any(|clause| catches_throw(&clause)) {
+                return true;
+            }
+        }
+        child = ancestor;
+    }
+    false
+}
+
+/// A catch clause without a class, or whose class is `throw` or a
+/// variable.
+fn catches_throw(clause: &ast::CatchClause) -> bool {
+    match clause.class().and_then(|class| class.class()) {
+        None => true,
+        Some(ast::Name::Atom(atom)) => atom.syntax().text() == "throw",
+        Some(ast::Name::Var(_)) => true,
+        Some(ast::Name::MacroCallExpr(_)) => false,
+    }
+}
+
+/// The process started by a call to a `spawn` function, with the range to
+/// report it at.
+fn spawned(call: &ast::Call, module: Option<&str>) -> Option<(TextRange, Entry)> {
+    let callee = call.expr()?;
+    let (spawn_module, fun) = match &callee {
+        ast::Expr::ExprMax(ast::ExprMax::Atom(atom)) => (None, atom.syntax().text().to_string()),
+        ast::Expr::Remote(remote) => (
+            Some(remote.module()?.module()?.syntax().text().to_string()),
+            remote.fun()?.syntax().text().to_string(),
+        ),
+        _ => return None,
+    };
+    if !matches!(spawn_module.as_deref(), None | Some("erlang" | "proc_lib"))
+        || !matches!(
+            fun.as_str(),
+            "spawn" | "spawn_link" | "spawn_monitor" | "spawn_opt"
+        )
+    {
+        return None;
+    }
+    let range = callee.syntax().text_range();
+    let args: Vec<ast::Expr> = call.args()?.args().collect();
+    for arg in &args {
+        match arg {
+            ast::Expr::ExprMax(ast::ExprMax::InternalFun(fun)) => {
+                let ast::Name::Atom(name) = fun.fun()? else {
+                    return None;
+                };
+                let ast::ArityValue::Integer(arity) = fun.arity()?.value()? else {
+                    return None;
+                };
+                let arity = arity.syntax().text().to_string().parse().ok()?;
+                let name = Name::from_erlang_service(&name.syntax().text().to_string());
+                return Some((range, Entry::Function(NameArity::new(name, arity))));
+            }
+            ast::Expr::ExprMax(ast::ExprMax::AnonymousFun(fun)) => {
+                return Some((range, Entry::Fun(fun.clone())));
+            }
+            _ => {}
+        }
+    }
+    args.iter()
+        .tuple_windows()
+        .find_map(|(m, f, a)| match (f, a) {
+            (
+                ast::Expr::ExprMax(ast::ExprMax::Atom(name)),
+                ast::Expr::ExprMax(ast::ExprMax::List(list)),
+            ) if is_module(m, module) => {
+                let name = Name::from_erlang_service(&name.syntax().text().to_string());
+                let arity = list.exprs().count() as u32;
+                Some((range, Entry::Function(NameArity::new(name, arity))))
+            }
+            _ => None,
+        })
+}
+
+fn is_module(expr: &ast::Expr, module: Option<&str>) -> bool {
+    let text = expr.syntax().text().to_string();
+    text == "?MODULE" || Some(text.as_str()) == module
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::DiagnosticCode;
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics_with_config;
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig::default()
+            .enable(DiagnosticCode::UncaughtThrow)
+            .disable(DiagnosticCode::UndefinedFunction)
+    }
+
+    #[test]
+    fn throw_escapes_spawned_function() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([start/0]).
+start() ->
+    spawn(fun loop/0),
+%%  ^^^^^ warning: a throw can escape the spawned process: loop/0 -> handle/1 -> check/1 -> throw/1
+    proc_lib:spawn_link(?MODULE, loop, []).
+%%  ^^^^^^^^^^^^^^^^^^^ warning: a throw can escape the spawned process: loop/0 -> handle/1 -> check/1 -> throw/1
+loop() ->
+    receive Msg -> handle(Msg), loop() end.
+handle(Msg) -> check(Msg), ok.
+check(bad) -> throw(bad);
+check(_) -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn throw_caught() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([start/0]).
+start() ->
+    spawn(fun loop/0),
+    spawn(fun() -> catch check(bad) end).
+loop() ->
+    try check(bad) of
+        ok -> loop()
+    catch
+        throw:Reason -> Reason
+    end.
+check(bad) -> throw(bad);
+check(_) -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn error_class_does_not_catch_throw() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([start/0]).
+start() ->
+    spawn(fun() ->
+%%  ^^^^^ warning: a throw can escape the spawned process: fun -> check/1 -> throw/1
+        try check(bad) catch error:_ -> ok end
+    end).
+check(bad) -> erlang:throw(bad);
+check(_) -> ok.
+"#,
+        );
+    }
+}
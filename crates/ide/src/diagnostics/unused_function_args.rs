@@ -64,6 +64,11 @@ fn unused_function_args(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: F
             let in_clause = def.in_clause(sema, def);
             let body_map = in_clause.get_body_map();
             let clause = in_clause.clone().body;
+            // NIF stubs are replaced when the library loads, their
+            // arguments are only there to give the function its arity.
+            if clause.is_nif_stub(sema.db) {
+                return;
+            }
 
             let pats = &clause.clause.pats;
             let mut unused_vars_with_wrong_name = HashMap::new();
@@ -303,6 +308,7 @@ mod tests {
                -module(main).
                foo(Args) -> {foo, Args};
                foo(Args2) -> ok.
+               %%<^^^^^^^ 💡 warning: this clause can never match, an earlier clause covers it
                %%  ^^^^^ 💡 warning: this variable is unused
                 "#,
         );
@@ -319,4 +325,17 @@ mod tests {
             "#,
         );
     }
+
+    #[test]
+    fn nif_stub_is_ok() {
+        check_diagnostics(
+            r#"
+               -module(main).
+               -export([add/2]).
+               -on_load(init/0).
+               init() -> erlang:load_nif("./main_nif", 0).
+               add(A, B) -> erlang:nif_error(not_loaded).
+            "#,
+        );
+    }
 }
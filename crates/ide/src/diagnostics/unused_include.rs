@@ -9,13 +9,17 @@
 
 // Diagnostic: unused include
 //
-// Return a warning if nothing is used from an include file
+// Return a warning if nothing is used from an include file. A macro of
+// the include file named in a preprocessor condition of the module, such
+// as `-ifdef(FOO).`, is used, as removing the include changes the code
+// the preprocessor keeps.
 
 use elp_ide_assists::helpers::extend_form_range_for_delete;
 use elp_ide_db::elp_base_db::FileId;
 use elp_ide_db::source_change::SourceChange;
 use elp_ide_db::SearchScope;
 use elp_ide_db::SymbolDefinition;
+use elp_syntax::ast;
 use elp_syntax::ast::AstNode;
 use fxhash::FxHashMap;
 use fxhash::FxHashSet;
@@ -42,10 +46,11 @@ pub(crate) fn unused_includes(
 ) {
     let form_list = db.file_form_list(file_id);
     let mut cache = Default::default();
+    let conditions = condition_names(sema, file_id);
     for (include_idx, attr) in form_list.includes() {
         let in_file = InFile::new(file_id, include_idx);
         if let Some(include_file_id) = db.resolve_include(in_file) {
-            if is_file_used(sema, db, include_file_id, file_id, &mut cache) {
+            if is_file_used(sema, db, include_file_id, file_id, &conditions, &mut cache) {
                 continue;
             }
 
@@ -92,6 +97,7 @@ fn is_file_used(
     db: &dyn DefDatabase,
     include_file_id: FileId,
     target: FileId,
+    conditions: &FxHashSet<String>,
     cache: &mut FxHashMap<FileId, bool>,
 ) -> bool {
     if let Some(used) = cache.get(&include_file_id) {
@@ -175,11 +181,12 @@ fn is_file_used(
             }
         }
 
-        for macro_def in def_map.get_macros().values() {
-            if SymbolDefinition::Define(macro_def.clone())
-                .usages(sema)
-                .set_scope(&scope)
-                .at_least_one()
+        for (name, macro_def) in def_map.get_macros() {
+            if conditions.contains(name.name().as_str())
+                || SymbolDefinition::Define(macro_def.clone())
+                    .usages(sema)
+                    .set_scope(&scope)
+                    .at_least_one()
             {
                 cache.insert(file_id, true);
                 return true;
@@ -221,6 +228,39 @@ fn is_file_used(
     false
 }
 
+/// The names of the macros the preprocessor conditions of the file
+/// depend on, in `-ifdef`, `-ifndef` and `-undef` directives, or called in
+/// the condition of an `-if` or `-elif` directive.
+fn condition_names(sema: &Semantic, file_id: FileId) -> FxHashSet<String> {
+    let mut names = FxHashSet::default();
+    for form in sema.parse(file_id).value.forms() {
+        let ast::Form::PreprocessorDirective(directive) = form else {
+            continue;
+        };
+        let name = match &directive {
+            ast::PreprocessorDirective::PpIfdef(ifdef) => ifdef.name(),
+            ast::PreprocessorDirective::PpIfndef(ifndef) => ifndef.name(),
+            ast::PreprocessorDirective::PpUndef(undef) => undef.name(),
+            ast::PreprocessorDirective::PpIf(_) | ast::PreprocessorDirective::PpElif(_) => {
+                names.extend(
+                    directive
+                        .syntax()
+                        .descendants()
+                        .filter_map(ast::MacroCallExpr::cast)
+                        .filter_map(|call| call.name())
+                        .map(|name| name.syntax().text().to_string()),
+                );
+                None
+            }
+            _ => None,
+        };
+        if let Some(name) = name {
+            names.insert(name.syntax().text().to_string());
+        }
+    }
+    names
+}
+
 lazy_static! {
     /// Attribute names that can occur in a header file without
     /// regarding the file as being used.
@@ -250,6 +290,7 @@ mod tests {
             r#"
 //- /include/foo.hrl include_path:/include
   -define(FOO,3).
+%%        ^^^ 💡 warning: Unused macro (FOO)
 //- /src/foo.erl
   -module(foo).
   -include("foo.hrl").
@@ -335,6 +376,7 @@ mod tests {
             r#"
 //- /include/foo.hrl include_path:/include
   -record(person, {name :: string(), height :: pos_integer()}).
+%%        ^^^^^^ 💡 warning: Unused record (person)
 //- /src/foo.erl
   -module(foo).
   -include("foo.hrl").
@@ -407,6 +449,7 @@ mod tests {
             r#"
 //- /include/foo.hrl include_path:/include
 -define(line,).
+%%      ^^^^ 💡 warning: Unused macro (line)
 lol(A, _B) -> A.
 -define(enum, lol).
 
@@ -418,6 +461,34 @@ lol(A, _B) -> A.
         );
     }
 
+    #[test]
+    fn used_in_preprocessor_condition() {
+        check_diagnostics(
+            r#"
+//- /include/foo.hrl include_path:/include
+  -define(WITH_CACHE, true).
+//- /src/foo.erl
+  -module(foo).
+  -include("foo.hrl").
+  -ifdef(WITH_CACHE).
+  cache() -> on.
+  -endif.
+        "#,
+        );
+        check_diagnostics(
+            r#"
+//- /include/foo.hrl include_path:/include
+  -define(VERSION, 2).
+//- /src/foo.erl
+  -module(foo).
+  -include("foo.hrl").
+  -if(?VERSION > 1).
+  cache() -> on.
+  -endif.
+        "#,
+        );
+    }
+
     #[test]
     fn optimise_includes_used_transitive() {
         check_diagnostics(
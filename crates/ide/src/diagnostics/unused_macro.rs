@@ -9,20 +9,27 @@
 
 // Diagnostic: unused-macro
 //
-// Return a warning if a macro defined in an .erl file has no references to it
+// Return a warning if a macro defined in an .erl file, or in a header
+// included by a single module, has no references to it. A macro named in
+// an `-ifdef`, `-ifndef` or `-undef` attribute, or called in code the
+// preprocessor may skip, is considered used, and macros defined
+// conditionally are not reported.
 
 use elp_ide_assists::Assist;
 use elp_ide_db::elp_base_db::FileId;
 use elp_ide_db::elp_base_db::FileKind;
 use elp_ide_db::source_change::SourceChange;
 use elp_ide_db::SymbolDefinition;
+use elp_syntax::ast;
 use elp_syntax::AstNode;
 use elp_syntax::SyntaxKind;
 use elp_syntax::TextRange;
 use elp_syntax::TextSize;
+use fxhash::FxHashSet;
 use hir::Semantic;
 use text_edit::TextEdit;
 
+use super::helpers::single_module_includer;
 use super::DiagnosticConditions;
 use super::DiagnosticDescriptor;
 use crate::diagnostics::DiagnosticCode;
@@ -47,38 +54,105 @@ fn unused_macro(
     file_id: FileId,
     file_kind: FileKind,
 ) -> Option<()> {
-    if file_kind.is_module() {
-        let def_map = sema.def_map(file_id);
-        for (name, def) in def_map.get_macros() {
-            // Only run the check for macros defined in the local module,
-            // not in the included files.
-            if def.file.file_id == file_id
-                && !SymbolDefinition::Define(def.clone())
-                    .usages(sema)
-                    .at_least_one()
-            {
-                let source = def.source(sema.db.upcast());
-                let macro_syntax = source.syntax();
-                // If after the macro there's a new line, drop it
-                let next_token = macro_syntax.last_token()?.next_token()?;
-                let macro_range = if next_token.kind() == SyntaxKind::WHITESPACE
-                    && next_token.text().starts_with('\n')
-                {
-                    let start = macro_syntax.text_range().start();
-                    let end = macro_syntax.text_range().end() + TextSize::from(1);
-                    TextRange::new(start, end)
-                } else {
-                    macro_syntax.text_range()
-                };
-                let name_range = source.name()?.syntax().text_range();
-                let d = make_diagnostic(file_id, macro_range, name_range, &name.to_string());
-                acc.push(d);
-            }
+    let module_file_id = if file_kind.is_module() {
+        file_id
+    } else if file_kind == FileKind::Header {
+        single_module_includer(sema, file_id)?
+    } else {
+        return Some(());
+    };
+    let referenced = referenced_names(sema, module_file_id);
+    let conditional = conditional_ranges(&sema.parse(file_id).value);
+    let def_map = sema.def_map(file_id);
+    for (name, def) in def_map.get_macros() {
+        // Only run the check for macros defined in the file itself, not
+        // in the files it includes.
+        if def.file.file_id != file_id || referenced.contains(name.name().as_str()) {
+            continue;
+        }
+        let source = def.source(sema.db.upcast());
+        let macro_syntax = source.syntax();
+        if conditional
+            .iter()
+            .any(|range| range.contains_range(macro_syntax.text_range()))
+            || SymbolDefinition::Define(def.clone())
+                .usages(sema)
+                .at_least_one()
+        {
+            continue;
         }
+        // If after the macro there's a new line, drop it
+        let next_token = macro_syntax.last_token()?.next_token()?;
+        let macro_range =
+            if next_token.kind() == SyntaxKind::WHITESPACE && next_token.text().starts_with('\n') {
+                let start = macro_syntax.text_range().start();
+                let end = macro_syntax.text_range().end() + TextSize::from(1);
+                TextRange::new(start, end)
+            } else {
+                macro_syntax.text_range()
+            };
+        let name_range = source.name()?.syntax().text_range();
+        let d = make_diagnostic(file_id, macro_range, name_range, &name.to_string());
+        acc.push(d);
     }
     Some(())
 }
 
+/// The names of the macros called, or named in a preprocessor condition
+/// or `-undef`, in the module or the files it includes, whether the
+/// preprocessor keeps the code or not.
+fn referenced_names(sema: &Semantic, module_file_id: FileId) -> FxHashSet<String> {
+    let included: Vec<FileId> = sema.def_map(module_file_id).get_included_files().collect();
+    let mut names = FxHashSet::default();
+    for file_id in std::iter::once(module_file_id).chain(included) {
+        let source = sema.parse(file_id).value;
+        for node in source.syntax().descendants() {
+            let name = if let Some(call) = ast::MacroCallExpr::cast(node.clone()) {
+                call.name()
+            } else if let Some(directive) = ast::PreprocessorDirective::cast(node) {
+                match directive {
+                    ast::PreprocessorDirective::PpIfdef(ifdef) => ifdef.name(),
+                    ast::PreprocessorDirective::PpIfndef(ifndef) => ifndef.name(),
+                    ast::PreprocessorDirective::PpUndef(undef) => undef.name(),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+            if let Some(name) = name {
+                names.insert(name.syntax().text().to_string());
+            }
+        }
+    }
+    names
+}
+
+/// The ranges of the `-if`, `-ifdef` and `-ifndef` blocks of the file,
+/// up to their `-endif`.
+fn conditional_ranges(source: &ast::SourceFile) -> Vec<TextRange> {
+    let mut starts = Vec::new();
+    let mut ranges = Vec::new();
+    for form in source.forms() {
+        let ast::Form::PreprocessorDirective(directive) = form else {
+            continue;
+        };
+        match directive {
+            ast::PreprocessorDirective::PpIf(_)
+            | ast::PreprocessorDirective::PpIfdef(_)
+            | ast::PreprocessorDirective::PpIfndef(_) => {
+                starts.push(directive.syntax().text_range().start())
+            }
+            ast::PreprocessorDirective::PpEndif(_) => {
+                if let Some(start) = starts.pop() {
+                    ranges.push(TextRange::new(start, directive.syntax().text_range().end()));
+                }
+            }
+            _ => {}
+        }
+    }
+    ranges
+}
+
 fn make_diagnostic(
     file_id: FileId,
     macro_range: TextRange,
@@ -201,6 +275,7 @@ main() ->
 //- /src/foo.hrl
 -define(A, a).
 -define(B, b).
+     %% ^ 💡 warning: Unused macro (B)
 //- /src/foo.erl
 -module(foo).
 -include("foo.hrl").
@@ -211,4 +286,72 @@ main() ->
         "#,
         );
     }
+
+    #[test]
+    fn test_unused_macro_shared_header() {
+        check_diagnostics(
+            r#"
+//- /include/foo.hrl include_path:/include
+-define(A, a).
+-define(B, b).
+//- /src/foo.erl
+-module(foo).
+-include("foo.hrl").
+main() ->
+  ?A.
+//- /src/bar.erl
+-module(bar).
+-include("foo.hrl").
+        "#,
+        );
+    }
+
+    #[test]
+    fn test_unused_macro_ifdef() {
+        check_diagnostics(
+            r#"
+-module(main).
+-define(LOG(X), io:format("~p~n", [X])).
+-define(TRACE, trace).
+-ifdef(NOT_DEFINED).
+main() -> ?LOG(?TRACE).
+-endif.
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_unused_macro_conditional_definition() {
+        check_diagnostics(
+            r#"
+-module(main).
+-ifdef(TEST).
+-define(TIMEOUT, 10).
+-else.
+-define(TIMEOUT, 1000).
+-endif.
+-define(RETRIES, 3).
+     %% ^^^^^^^ 💡 warning: Unused macro (RETRIES)
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_unused_macro_fix_in_header() {
+        check_fix(
+            r#"
+//- /src/foo.hrl
+-define(A, a).
+-define(~B, b).
+//- /src/foo.erl
+-module(foo).
+-include("foo.hrl").
+main() ->
+  ?A.
+            "#,
+            expect![[r#"
+-define(A, a).
+            "#]],
+        )
+    }
 }
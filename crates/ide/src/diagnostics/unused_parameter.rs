@@ -0,0 +1,404 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: unused-parameter
+//
+// Return a warning if a parameter of a function is a variable in each
+// clause, unused in every one of them. For a function which is not
+// exported, offer to remove the parameter from its clauses, its spec and
+// the calls to it. The callers of an exported function cannot all be
+// updated, so only offer to prefix the variables with an underscore.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::source_change::SourceChange;
+use elp_ide_db::SymbolDefinition;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::TextRange;
+use fxhash::FxHashSet;
+use hir::FunctionDef;
+use hir::InFile;
+use hir::NameArity;
+use hir::Semantic;
+use text_edit::TextEdit;
+
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::Severity;
+use crate::fix;
+use crate::Diagnostic;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: true,
+    },
+    checker: &|diags, sema, file_id, _ext| {
+        unused_parameter(diags, sema, file_id);
+    },
+};
+
+fn unused_parameter(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId) {
+    let def_map = sema.def_map(file_id);
+    for (name, def) in def_map.get_functions() {
+        if def.file.file_id != file_id || def.is_nif_stub(sema.db) {
+            continue;
+        }
+        let clauses: Option<Vec<Vec<ast::Expr>>> = def
+            .source(sema.db.upcast())
+            .iter()
+            .map(|fun_decl| match fun_decl.clause()? {
+                ast::FunctionOrMacroClause::FunctionClause(clause) => {
+                    Some(clause.args()?.args().collect())
+                }
+                ast::FunctionOrMacroClause::MacroCallExpr(_) => None,
+            })
+            .collect();
+        let Some(clauses) = clauses else { continue };
+        for index in 0..name.arity() as usize {
+            let vars: Option<Vec<ast::Var>> = clauses
+                .iter()
+                .map(|args| match args.get(index)? {
+                    ast::Expr::ExprMax(ast::ExprMax::Var(var)) => Some(var.clone()),
+                    _ => None,
+                })
+                .collect();
+            let Some(vars) = vars else { continue };
+            if vars.is_empty() || !vars.iter().all(|var| is_unused(sema, file_id, var)) {
+                continue;
+            }
+            // A parameter ignored on purpose in every clause is left alone
+            if vars.iter().all(|var| is_ignored(var)) {
+                continue;
+            }
+
+            let mut fixes = Vec::new();
+            let range = vars[0].syntax().text_range();
+            if !def.exported {
+                if let Some(edit) = removal_edit(sema, def, &clauses, index) {
+                    fixes.push(fix(
+                        "remove_unused_parameter",
+                        "Remove the parameter from the function and its calls",
+                        SourceChange::from_text_edit(file_id, edit),
+                        range,
+                    ));
+                }
+            }
+            if let Some(edit) = prefix_edit(&vars) {
+                fixes.push(fix(
+                    "prefix_parameter_with_underscore",
+                    "Prefix the parameter with an underscore in every clause",
+                    SourceChange::from_text_edit(file_id, edit),
+                    range,
+                ));
+            }
+            if fixes.is_empty() {
+                continue;
+            }
+            diags.push(
+                Diagnostic::new(
+                    DiagnosticCode::UnusedParameter,
+                    format!("this parameter is unused in every clause of {name}"),
+                    range,
+                )
+                .with_severity(Severity::Warning)
+                .with_fixes(Some(fixes)),
+            );
+        }
+    }
+}
+
+fn is_ignored(var: &ast::Var) -> bool {
+    var.syntax().text().to_string().starts_with('_')
+}
+
+fn is_unused(sema: &Semantic, file_id: FileId, var: &ast::Var) -> bool {
+    is_ignored(var)
+        || sema
+            .find_local_usages_ast(InFile::new(file_id, var))
+            .is_some_and(|usages| usages.len() == 1)
+}
+
+/// Removes the argument at `index` from the clauses, the spec and the
+/// calls of the function, if the function is only ever called, the
+/// arguments of the calls have no side effects, and no function of the
+/// lower arity is already defined or imported.
+fn removal_edit(
+    sema: &Semantic,
+    def: &FunctionDef,
+    clauses: &[Vec<ast::Expr>],
+    index: usize,
+) -> Option<TextEdit> {
+    let arity = clauses.first()?.len();
+    let reduced = NameArity::new(def.name.name().clone(), arity as u32 - 1);
+    let def_map = sema.def_map(def.file.file_id);
+    if def_map.get_function(&reduced).is_some() || def_map.get_imports().contains_key(&reduced) {
+        return None;
+    }
+    let mut builder = TextEdit::builder();
+    for args in clauses {
+        builder.delete(argument_range(args, index));
+    }
+    if let Some(spec) = &def.spec {
+        for sig in spec.source(sema.db.upcast()).sigs() {
+            let args: Vec<ast::Expr> = sig.args()?.args().collect();
+            if args.len() != arity {
+                return None;
+            }
+            builder.delete(argument_range(&args, index));
+        }
+    }
+    let usages = SymbolDefinition::Function(def.clone()).usages(sema).all();
+    for (usage_file_id, names) in usages.iter() {
+        if usage_file_id != def.file.file_id {
+            return None;
+        }
+        for name in names {
+            if name
+                .syntax()
+                .ancestors()
+                .any(|node| ast::Spec::can_cast(node.kind()))
+            {
+                continue;
+            }
+            let call = name.syntax().ancestors().find_map(ast::Call::cast)?;
+            if call.expr()?.syntax().text_range() != name.syntax().text_range() {
+                return None;
+            }
+            let args: Vec<ast::Expr> = call.args()?.args().collect();
+            if args.len() != arity || !is_side_effect_free(&args[index]) {
+                return None;
+            }
+            builder.delete(argument_range(&args, index));
+        }
+    }
+    Some(builder.finish())
+}
+
+/// The range of the argument, with the comma separating it from the next
+/// argument, or from the previous one for the last argument.
+fn argument_range(args: &[ast::Expr], index: usize) -> TextRange {
+    let arg = args[index].syntax().text_range();
+    if let Some(next) = args.get(index + 1) {
+        TextRange::new(arg.start(), next.syntax().text_range().start())
+    } else if index > 0 {
+        TextRange::new(args[index - 1].syntax().text_range().end(), arg.end())
+    } else {
+        arg
+    }
+}
+
+fn is_side_effect_free(arg: &ast::Expr) -> bool {
+    matches!(
+        arg,
+        ast::Expr::ExprMax(
+            ast::ExprMax::Var(_)
+                | ast::ExprMax::Atom(_)
+                | ast::ExprMax::Integer(_)
+                | ast::ExprMax::Float(_)
+                | ast::ExprMax::String(_)
+                | ast::ExprMax::Char(_)
+        )
+    )
+}
+
+/// Prefixes the variables not starting with an underscore yet, avoiding
+/// the names already used in their clause.
+fn prefix_edit(vars: &[ast::Var]) -> Option<TextEdit> {
+    let mut builder = TextEdit::builder();
+    let mut changed = false;
+    for var in vars {
+        let name = var.syntax().text().to_string();
+        if name.starts_with('_') {
+            continue;
+        }
+        let clause = var
+            .syntax()
+            .ancestors()
+            .find(|node| node.kind() == SyntaxKind::FUNCTION_CLAUSE)?;
+        let used: FxHashSet<String> = clause
+            .descendants_with_tokens()
+            .filter_map(|element| element.into_token())
+            .filter(|token| token.kind() == SyntaxKind::VAR)
+            .map(|token| token.text().to_string())
+            .collect();
+        let mut new_name = format!("_{name}");
+        while used.contains(&new_name) {
+            new_name = format!("_{new_name}");
+        }
+        builder.replace(var.syntax().text_range(), new_name);
+        changed = true;
+    }
+    changed.then(|| builder.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::diagnostics::DiagnosticCode;
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig::default()
+            .disable(DiagnosticCode::UndefinedFunction)
+            .disable(DiagnosticCode::RedundantClause)
+            .enable(DiagnosticCode::UnusedParameter)
+    }
+
+    #[test]
+    fn unused_in_every_clause() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([main/0]).
+main() -> area(square, 2, red) + area(circle, 1, blue).
+area(square, Side, Colour) -> Side * Side;
+%%                 ^^^^^^ 💡 warning: this parameter is unused in every clause of area/3
+area(circle, Radius, _) -> 3 * Radius * Radius.
+"#,
+        );
+    }
+
+    #[test]
+    fn used_in_one_clause() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([main/0]).
+main() -> area(square, 2, red).
+area(square, Side, Colour) -> {Side * Side, Colour};
+area(circle, Radius, _) -> 3 * Radius * Radius.
+"#,
+        );
+    }
+
+    #[test]
+    fn exported_function_prefixed_already() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([handle/2]).
+handle(Msg, _State) -> Msg.
+"#,
+        );
+    }
+
+    #[test]
+    fn local_function_prefixed_already() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([main/0]).
+main() -> area(square, 2, red) + area(circle, 1, blue).
+area(square, Side, _Colour) -> Side * Side;
+area(circle, Radius, _) -> 3 * Radius * Radius.
+"#,
+        );
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        check_diagnostics_with_config(
+            DiagnosticsConfig::default().disable(DiagnosticCode::UndefinedFunction),
+            r#"
+-module(main).
+-export([main/0]).
+main() -> area(square, 2, red).
+area(square, Side, Colour) -> Side * Side.
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_parameter() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-export([main/0]).
+-spec area(atom(), number(), atom()) -> number().
+main() -> area(square, 2, red) + area(circle, 1, blue).
+area(square, Side, Col~our) -> Side * Side;
+area(circle, Radius, _) -> 3 * Radius * Radius.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([main/0]).
+                -spec area(atom(), number()) -> number().
+                main() -> area(square, 2) + area(circle, 1).
+                area(square, Side) -> Side * Side;
+                area(circle, Radius) -> 3 * Radius * Radius.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn no_removal_when_lower_arity_exists() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-export([main/0]).
+main() -> area(square, 2, red) + area(circle, 1).
+area(square, Side, Col~our) -> Side * Side.
+area(Shape, Size) -> {Shape, Size}.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([main/0]).
+                main() -> area(square, 2, red) + area(circle, 1).
+                area(square, Side, _Colour) -> Side * Side.
+                area(Shape, Size) -> {Shape, Size}.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn prefix_parameter_of_exported_function() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-export([handle/2]).
+handle(Msg, St~ate) -> Msg;
+handle(Msg, _State) -> {Msg, ok}.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([handle/2]).
+                handle(Msg, _State) -> Msg;
+                handle(Msg, _State) -> {Msg, ok}.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn no_removal_when_captured() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([main/0]).
+main() -> lists:map(fun double/2, [1]).
+double(X, _Y) -> X * 2.
+"#,
+        );
+    }
+}
@@ -0,0 +1,260 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: unused-record
+//
+// Return a warning if a record declared in an .erl file, or in a header
+// included by a single module, is never constructed, matched or indexed.
+// A record only inspected with `record_info/2` counts as used.
+
+use elp_ide_assists::Assist;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::FileKind;
+use elp_ide_db::source_change::SourceChange;
+use elp_ide_db::SymbolDefinition;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use fxhash::FxHashSet;
+use hir::Semantic;
+use text_edit::TextEdit;
+
+use super::helpers::single_module_includer;
+use super::DiagnosticConditions;
+use super::DiagnosticDescriptor;
+use crate::diagnostics::DiagnosticCode;
+use crate::fix;
+use crate::Diagnostic;
+
+pub(crate) static DESCRIPTOR: DiagnosticDescriptor = DiagnosticDescriptor {
+    conditions: DiagnosticConditions {
+        experimental: false,
+        include_generated: false,
+        include_tests: true,
+        default_disabled: false,
+    },
+    checker: &|diags, sema, file_id, file_kind| {
+        unused_record(diags, sema, file_id, file_kind);
+    },
+};
+
+fn unused_record(
+    acc: &mut Vec<Diagnostic>,
+    sema: &Semantic,
+    file_id: FileId,
+    file_kind: FileKind,
+) -> Option<()> {
+    // The records of a header included by several modules may be used by
+    // modules yet to be written.
+    let module_file_id = if file_kind.is_module() {
+        file_id
+    } else if file_kind == FileKind::Header {
+        single_module_includer(sema, file_id)?
+    } else {
+        return Some(());
+    };
+    let inspected = record_info_names(sema, module_file_id);
+    let def_map = sema.def_map(file_id);
+    for (name, def) in def_map.get_records() {
+        if def.file.file_id != file_id
+            || inspected.contains(name.as_str())
+            || SymbolDefinition::Record(def.clone())
+                .usages(sema)
+                .at_least_one()
+        {
+            continue;
+        }
+        let source = def.source(sema.db.upcast());
+        let record_syntax = source.syntax();
+        // If after the record there's a new line, drop it
+        let next_token = record_syntax.last_token()?.next_token();
+        let record_range = match next_token {
+            Some(token)
+                if token.kind() == SyntaxKind::WHITESPACE && token.text().starts_with('\n') =>
+            {
+                let start = record_syntax.text_range().start();
+                let end = record_syntax.text_range().end() + TextSize::from(1);
+                TextRange::new(start, end)
+            }
+            _ => record_syntax.text_range(),
+        };
+        // The name of the record may depend on the macros defined in
+        // each includer.
+        let name_range = match source.name()? {
+            ast::Name::Atom(atom) => atom.syntax().text_range(),
+            _ => continue,
+        };
+        acc.push(make_diagnostic(
+            file_id,
+            record_range,
+            name_range,
+            name.as_str(),
+        ));
+    }
+    Some(())
+}
+
+/// The names of the records passed to `record_info/2` in the module.
+fn record_info_names(sema: &Semantic, file_id: FileId) -> FxHashSet<String> {
+    let source = sema.parse(file_id);
+    source
+        .value
+        .syntax()
+        .descendants()
+        .filter_map(ast::Call::cast)
+        .filter_map(|call| {
+            let ast::Expr::ExprMax(ast::ExprMax::Atom(fun)) = call.expr()? else {
+                return None;
+            };
+            if fun.syntax().text() != "record_info" {
+                return None;
+            }
+            match call.args()?.args().collect::<Vec<_>>().as_slice() {
+                [_, ast::Expr::ExprMax(ast::ExprMax::Atom(record))] => {
+                    Some(record.syntax().text().to_string())
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn make_diagnostic(
+    file_id: FileId,
+    record_range: TextRange,
+    name_range: TextRange,
+    name: &str,
+) -> Diagnostic {
+    Diagnostic::warning(
+        DiagnosticCode::UnusedRecord,
+        name_range,
+        format!("Unused record ({name})"),
+    )
+    .with_fixes(Some(vec![delete_unused_record(
+        file_id,
+        record_range,
+        name,
+    )]))
+}
+
+fn delete_unused_record(file_id: FileId, range: TextRange, name: &str) -> Assist {
+    let mut builder = TextEdit::builder();
+    builder.delete(range);
+    let edit = builder.finish();
+    fix(
+        "delete_unused_record",
+        &format!("Delete unused record ({name})"),
+        SourceChange::from_text_edit(file_id, edit),
+        range,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::diagnostics::DiagnosticCode;
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+
+    fn config() -> DiagnosticsConfig {
+        DiagnosticsConfig::default()
+            .disable(DiagnosticCode::UndefinedFunction)
+            .disable(DiagnosticCode::UnusedInclude)
+    }
+
+    #[test]
+    fn unused_record_in_module() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([main/1]).
+-record(used, {a}).
+-record(matched, {a}).
+-record(indexed, {a}).
+-record(unused, {a}).
+%%      ^^^^^^ 💡 warning: Unused record (unused)
+main(#matched{a = A}) -> {#used{a = A}, #indexed.a}.
+"#,
+        );
+    }
+
+    #[test]
+    fn unused_record_in_header_of_one_module() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /include/state.hrl include_path:/include
+-record(state, {count}).
+-record(old_state, {count}).
+%%      ^^^^^^^^^ 💡 warning: Unused record (old_state)
+//- /src/main.erl
+-module(main).
+-include("state.hrl").
+-export([init/0]).
+init() -> #state{count = 0}.
+"#,
+        );
+    }
+
+    #[test]
+    fn header_of_several_modules() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /include/state.hrl include_path:/include
+-record(state, {count}).
+-record(old_state, {count}).
+//- /src/main.erl
+-module(main).
+-include("state.hrl").
+-export([init/0]).
+init() -> #state{count = 0}.
+//- /src/other.erl
+-module(other).
+-include("state.hrl").
+"#,
+        );
+    }
+
+    #[test]
+    fn record_info_is_a_usage() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+-module(main).
+-export([fields/0]).
+-record(person, {name, age}).
+fields() -> record_info(fields, person).
+"#,
+        );
+    }
+
+    #[test]
+    fn delete_unused_record() {
+        check_fix_with_config(
+            config(),
+            r#"
+-module(main).
+-record(used, {a}).
+-record(un~used, {a, b}).
+main() -> #used{}.
+"#,
+            expect![[r#"
+                -module(main).
+                -record(used, {a}).
+                main() -> #used{}.
+            "#]],
+        );
+    }
+}
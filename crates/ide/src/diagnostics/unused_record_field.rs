@@ -137,6 +137,7 @@ main(R) ->
 -export([main/1]).
 
 -record(used_field, {field_a, field_b = 42}).
+%%      ^^^^^^^^^^ 💡 warning: Unused record (used_field)
 
 main(_) -> ok.
             "#,
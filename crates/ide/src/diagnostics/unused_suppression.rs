@@ -0,0 +1,251 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+// Diagnostic: unused-suppression
+//
+// Return a warning if an `% elp:ignore` or `% elp:fixme` comment no
+// longer suppresses any diagnostic, and offer to remove it.
+// Stale `% eqwalizer:fixme` comments are reported by eqwalizer itself,
+// as `redundant_fixme`.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::FileKind;
+use elp_ide_db::metadata::Annotation;
+use elp_ide_db::metadata::Metadata;
+use elp_ide_db::metadata::Source;
+use elp_ide_db::source_change::SourceChange;
+use elp_ide_db::DiagnosticCode;
+use elp_ide_db::RootDatabase;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use itertools::Itertools;
+use text_edit::TextEdit;
+
+use super::is_implemented_in_elp;
+use super::Diagnostic;
+use super::Severity;
+use crate::fix;
+
+/// The diagnostics pass an unused suppression is reported by. Each pass
+/// only sees its own diagnostics, so only judges the comments all of
+/// whose codes it reports. Codes reported by the edoc and common test
+/// passes, or as labeled syntax errors, are judged by none of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SuppressionSource {
+    Native,
+    ErlangService,
+}
+
+impl SuppressionSource {
+    fn reports(self, code: &DiagnosticCode, file_kind: FileKind) -> bool {
+        match code {
+            // Edoc codes start with `O`, and the ones implemented in ELP
+            // are dropped before this pass sees them
+            DiagnosticCode::ErlangService(s) => {
+                self == SuppressionSource::ErlangService
+                    && !s.starts_with('O')
+                    && !is_implemented_in_elp(code, file_kind)
+            }
+            DiagnosticCode::Eqwalizer(_) | DiagnosticCode::AdHoc(_) => false,
+            DiagnosticCode::UnreachableTest
+            | DiagnosticCode::MeckMissingNoLinkInInitPerSuite
+            | DiagnosticCode::CannotEvaluateCTCallbacks
+            | DiagnosticCode::SyntaxError
+            | DiagnosticCode::Missing(_) => false,
+            _ => self == SuppressionSource::Native,
+        }
+    }
+}
+
+/// `diagnostics` are the diagnostics of the pass before suppressions
+/// are applied, including the ones dropped as not enabled, so that a
+/// comment for a disabled diagnostic is not reported.
+pub(crate) fn unused_suppressions(
+    db: &RootDatabase,
+    file_id: FileId,
+    metadata: &Metadata,
+    source: SuppressionSource,
+    diagnostics: &[Diagnostic],
+) -> Vec<Diagnostic> {
+    let file_kind = db.file_kind(file_id);
+    metadata
+        .by_source(Source::Elp)
+        .filter(|annotation| {
+            !annotation.codes.is_empty()
+                && annotation
+                    .codes
+                    .iter()
+                    .all(|c| source.reports(c, file_kind))
+        })
+        .filter(|annotation| !diagnostics.iter().any(|d| d.is_suppressed_by(annotation)))
+        .filter_map(|annotation| make_diagnostic(db, file_id, annotation))
+        .collect()
+}
+
+fn make_diagnostic(
+    db: &RootDatabase,
+    file_id: FileId,
+    annotation: &Annotation,
+) -> Option<Diagnostic> {
+    let source = db.parse(file_id).tree();
+    let comment = source
+        .syntax()
+        .token_at_offset(annotation.comment_range.end())
+        .left_biased()
+        .filter(|token| token.kind() == SyntaxKind::COMMENT)?;
+    let text = db.file_text(file_id);
+    let removal = comment_removal_range(&text, comment.text_range());
+    let codes = annotation
+        .codes
+        .iter()
+        .map(|code| code.as_code())
+        .sorted()
+        .join(", ");
+    let message = format!(
+        "Unused elp:{} comment, no {} diagnostic is reported on the next line.",
+        annotation.kind, codes
+    );
+    Some(
+        Diagnostic::new(
+            DiagnosticCode::UnusedSuppression,
+            message,
+            annotation.comment_range,
+        )
+        .with_severity(Severity::Warning)
+        .with_fixes(Some(vec![fix(
+            "remove_unused_suppression",
+            "Remove unused suppression comment",
+            SourceChange::from_text_edit(file_id, TextEdit::delete(removal)),
+            annotation.comment_range,
+        )])),
+    )
+}
+
+/// The range to delete to remove a comment, which is its whole line
+/// when nothing else is on it.
+pub(crate) fn comment_removal_range(text: &str, comment: TextRange) -> TextRange {
+    let line_start = text[..usize::from(comment.start())]
+        .rfind('\n')
+        .map_or(TextSize::from(0), |idx| TextSize::from(idx as u32 + 1));
+    let alone_on_line = text[usize::from(line_start)..usize::from(comment.start())]
+        .chars()
+        .all(char::is_whitespace);
+    if !alone_on_line {
+        return comment;
+    }
+    let end = text[usize::from(comment.end())..]
+        .find('\n')
+        .map_or(TextSize::of(text), |idx| {
+            comment.end() + TextSize::from(idx as u32 + 1)
+        });
+    TextRange::new(line_start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_ide_db::DiagnosticCode;
+    use expect_test::expect;
+
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::tests::check_diagnostics_with_config;
+    use crate::tests::check_fix_with_config;
+
+    #[test]
+    fn unused_ignore() {
+        let config = DiagnosticsConfig::default().enable(DiagnosticCode::UnusedSuppression);
+        check_diagnostics_with_config(
+            config,
+            r#"
+-module(main).
+-export([foo/0]).
+% elp:ignore W0013 (misspelled_attribute)
+-dyalizer({nowarn_function, f/0}).
+% elp:ignore W0007 (trivial_match)
+%%<^^^^^^^^^^^ 💡 warning: Unused elp:ignore comment, no W0007 diagnostic is reported on the next line.
+foo() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn ignore_of_common_test_diagnostic() {
+        let config = DiagnosticsConfig::default().enable(DiagnosticCode::UnusedSuppression);
+        check_diagnostics_with_config(
+            config,
+            r#"
+//- /my_app/test/my_SUITE.erl extra:test
+-module(my_SUITE).
+-export([all/0]).
+-export([a/1, b/1]).
+all() -> [a].
+a(_Config) -> ok.
+% elp:ignore W0008 (unreachable_test)
+b(_Config) -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn ignore_of_default_disabled_diagnostic() {
+        let config = DiagnosticsConfig::default()
+            .enable(DiagnosticCode::UnusedSuppression)
+            .disable(DiagnosticCode::UndefinedFunction);
+        check_diagnostics_with_config(
+            config,
+            r#"
+-module(main).
+-export([main/0]).
+main() -> area(square, 2, red).
+% elp:ignore W0036 (unused_parameter)
+area(square, Side, Colour) -> Side * Side.
+"#,
+        );
+    }
+
+    #[test]
+    fn ignore_of_disabled_diagnostic() {
+        let config = DiagnosticsConfig::default()
+            .enable(DiagnosticCode::UnusedSuppression)
+            .disable(DiagnosticCode::TrivialMatch);
+        check_diagnostics_with_config(
+            config,
+            r#"
+-module(main).
+-export([foo/0]).
+foo() ->
+    % elp:ignore W0007 (trivial_match)
+    ok = ok,
+    ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_unused_fixme() {
+        let config = DiagnosticsConfig::default().enable(DiagnosticCode::UnusedSuppression);
+        check_fix_with_config(
+            config,
+            r#"
+-module(main).
+-export([foo/0]).
+foo() ->
+    % elp:fi~xme W0007 (trivial_match)
+    ok.
+"#,
+            expect![[r#"
+                -module(main).
+                -export([foo/0]).
+                foo() ->
+                    ok.
+            "#]],
+        );
+    }
+}
@@ -63,6 +63,37 @@ impl DiagnosticCollection {
         }
     }
 
+    /// Update the project-wide eqwalizer diagnostics for a subset of
+    /// the project. Files in `file_ids` without an entry in
+    /// `diagnostics` have their diagnostics cleared, all other files
+    /// are left untouched.
+    pub fn set_eqwalizer_project_chunk(
+        &mut self,
+        file_ids: &[FileId],
+        diagnostics: Vec<(FileId, Vec<Diagnostic>)>,
+    ) {
+        let mut diagnostics: FxHashMap<FileId, Vec<Diagnostic>> = diagnostics.into_iter().collect();
+        for file_id in file_ids {
+            let diagnostics = diagnostics.remove(file_id).unwrap_or_default();
+            if !are_all_diagnostics_equal(&self.eqwalizer_project, *file_id, &diagnostics) {
+                set_diagnostics(&mut self.eqwalizer_project, *file_id, diagnostics);
+                self.changes.insert(*file_id);
+            }
+        }
+    }
+
+    /// Drop project-wide eqwalizer diagnostics for files not seen in a
+    /// completed chunked run.
+    pub fn retain_eqwalizer_project(&mut self, visited: &FxHashSet<FileId>) {
+        let previous: Vec<FileId> = self.eqwalizer_project.keys().cloned().collect();
+        for file_id in previous {
+            if !visited.contains(&file_id) {
+                self.eqwalizer_project.remove(&file_id);
+                self.changes.insert(file_id);
+            }
+        }
+    }
+
     pub fn set_edoc(&mut self, file_id: FileId, diagnostics: Vec<Diagnostic>) {
         if !are_all_diagnostics_equal(&self.edoc, file_id, &diagnostics) {
             set_diagnostics(&mut self.edoc, file_id, diagnostics);
@@ -318,6 +349,36 @@ mod tests {
         assert_eq!(diagnostics.diagnostics_for(file_id).len(), 0);
     }
 
+    #[test]
+    fn eqwalizer_project_chunks() {
+        let (_db, file_ids, _) = RootDatabase::with_many_files(
+            r#"
+//- /src/one.erl
+-module(one).
+//- /src/two.erl
+-module(two).
+            "#,
+        );
+        let (one, two) = (file_ids[0], file_ids[1]);
+        let mut diagnostics = DiagnosticCollection::default();
+        let diagnostic = Diagnostic::default();
+
+        diagnostics.set_eqwalizer_project_chunk(&[one], vec![(one, vec![diagnostic.clone()])]);
+        diagnostics.set_eqwalizer_project_chunk(&[two], vec![(two, vec![diagnostic.clone()])]);
+        assert_eq!(diagnostics.take_changes().map(|c| c.len()), Some(2));
+
+        // A later chunk without diagnostics for `one` clears it, and leaves `two` alone
+        diagnostics.set_eqwalizer_project_chunk(&[one], vec![]);
+        assert_eq!(diagnostics.project_diagnostics_for(one).len(), 0);
+        assert_eq!(diagnostics.project_diagnostics_for(two).len(), 1);
+
+        // Completing a run which no longer includes `two` drops it
+        diagnostics.retain_eqwalizer_project(&FxHashSet::from_iter([one]));
+        assert_eq!(diagnostics.project_diagnostics_for(two).len(), 0);
+        let changes = diagnostics.take_changes();
+        assert_eq!(changes, Some(FxHashSet::from_iter([one, two])));
+    }
+
     // -----------------------------------------------------------------
 
     #[track_caller]
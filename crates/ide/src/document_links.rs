@@ -0,0 +1,89 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::RootDatabase;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use hir::db::DefDatabase;
+use hir::InFile;
+use hir::IncludeAttribute;
+use hir::Semantic;
+
+/// The path of an include attribute, linked to the included file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentLink {
+    pub range: TextRange,
+    pub target: FileId,
+}
+
+// Feature: Document Links
+//
+// The paths of `-include` and `-include_lib` attributes link to the header
+// they resolve to, following the include path of the project.
+pub(crate) fn document_links(db: &RootDatabase, file_id: FileId) -> Vec<DocumentLink> {
+    let sema = Semantic::new(db);
+    let source = sema.parse(file_id).value;
+    let form_list = sema.form_list(file_id);
+    form_list
+        .includes()
+        .filter_map(|(idx, include)| {
+            let target = db.resolve_include(InFile::new(file_id, idx))?;
+            let mut details = match include {
+                IncludeAttribute::Include { form_id, .. } => form_id.get(&source).file(),
+                IncludeAttribute::IncludeLib { form_id, .. } => form_id.get(&source).file(),
+            };
+            let first = details.next()?;
+            let range = details.fold(first.syntax().text_range(), |range, detail| {
+                range.cover(detail.syntax().text_range())
+            });
+            Some(DocumentLink { range, target })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+
+    #[track_caller]
+    fn check(fixture: &str) {
+        let (analysis, position, _, expected) = fixture::annotations(fixture);
+        let actual: Vec<_> = analysis
+            .document_links(position.file_id)
+            .unwrap()
+            .into_iter()
+            .map(|link| (link.range, format!("file {}", link.target.index())))
+            .collect();
+        let expected: Vec<_> = expected
+            .into_iter()
+            .map(|(file_range, label)| (file_range.range, label))
+            .collect();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn include_and_include_lib() {
+        check(
+            r#"
+//- /main/src/main.erl app:main
+~-module(main).
+-include("main.hrl").
+%%       ^^^^^^^^^^ file 1
+-include_lib("another/include/header.hrl").
+%%           ^^^^^^^^^^^^^^^^^^^^^^^^^^^^ file 2
+-include("missing.hrl").
+//- /main/src/main.hrl app:main
+-define(A, 1).
+//- /another-app/include/header.hrl app:another
+-define(B, 2).
+"#,
+        );
+    }
+}
@@ -19,9 +19,13 @@ use hir::db::DefDatabase;
 use hir::DefineDef;
 use hir::FunctionDef;
 use hir::Name;
+use hir::NameArity;
 use hir::RecordDef;
 use hir::Semantic;
 use hir::TypeAliasDef;
+use itertools::Itertools;
+
+use crate::statem_graph;
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct DocumentSymbol {
@@ -213,11 +217,114 @@ pub(crate) fn document_symbols(db: &RootDatabase, file_id: FileId) -> Vec<Docume
         }
     }
 
+    res.extend(lifecycle_symbols(db, &sema, file_id));
+
     res.sort_by(|a, b| a.range.start().cmp(&b.range.start()));
 
     res
 }
 
+/// The callbacks of the OTP behaviours, in the order they come into play
+/// during the lifetime of a process.
+const LIFECYCLES: &[(&str, &[(&str, u32)])] = &[
+    (
+        "gen_server",
+        &[
+            ("init", 1),
+            ("handle_continue", 2),
+            ("handle_call", 3),
+            ("handle_cast", 2),
+            ("handle_info", 2),
+            ("code_change", 3),
+            ("format_status", 1),
+            ("format_status", 2),
+            ("terminate", 2),
+        ],
+    ),
+    (
+        "gen_statem",
+        &[
+            ("init", 1),
+            ("callback_mode", 0),
+            ("handle_event", 4),
+            ("code_change", 4),
+            ("format_status", 1),
+            ("format_status", 2),
+            ("terminate", 3),
+        ],
+    ),
+];
+
+/// A section for each `gen_server` or `gen_statem` behaviour of the module,
+/// at its `-behaviour` attribute, listing the callbacks implemented in
+/// lifecycle order rather than file order. The state callbacks of a
+/// `gen_statem` in the `state_functions` callback mode, the exported
+/// functions of arity 3 named after one of its states, follow
+/// `handle_event/4` in file order.
+fn lifecycle_symbols(db: &RootDatabase, sema: &Semantic, file_id: FileId) -> Vec<DocumentSymbol> {
+    let def_map = sema.def_map(file_id);
+    let functions: Vec<(&NameArity, &FunctionDef)> = def_map
+        .get_functions()
+        .filter(|(_, def)| def.file.file_id == file_id)
+        .sorted_by_key(|(_, def)| def.range(sema.db.upcast()).map(|range| range.start()))
+        .collect();
+    let form_list = sema.form_list(file_id);
+    let source = sema.parse(file_id).value;
+    let state_callbacks = statem_graph::state_callbacks(db, file_id);
+    let mut res = Vec::new();
+    for (_, behaviour) in form_list.behaviour_attributes() {
+        let Some((_, callbacks)) = LIFECYCLES
+            .iter()
+            .find(|(name, _)| behaviour.name.as_str() == *name)
+        else {
+            continue;
+        };
+        let find = |name: &str, arity: u32| {
+            functions
+                .iter()
+                .find(|(na, _)| na.name().as_str() == name && na.arity() == arity)
+        };
+        let mut found = Vec::new();
+        for &(name, arity) in callbacks.iter() {
+            found.extend(find(name, arity));
+            if (name, arity) == ("handle_event", 4) {
+                found.extend(functions.iter().filter(|(na, _)| {
+                    na.arity() == 3
+                        && def_map.is_function_exported(na)
+                        && state_callbacks.contains(na.name().as_str())
+                        && !callbacks
+                            .iter()
+                            .any(|&(name, arity)| na.name().as_str() == name && arity == 3)
+                }));
+            }
+        }
+        let children: Vec<DocumentSymbol> = found
+            .into_iter()
+            .map(|(na, def)| {
+                let mut symbol = def.to_document_symbol(sema.db);
+                symbol.kind = SymbolKind::Callback;
+                symbol.deprecated = def_map.is_deprecated(na);
+                symbol.children = None;
+                symbol
+            })
+            .collect();
+        if children.is_empty() {
+            continue;
+        }
+        let range = behaviour.form_id.get(&source).syntax().text_range();
+        res.push(DocumentSymbol {
+            name: format!("{} lifecycle", behaviour.name),
+            kind: SymbolKind::Behaviour,
+            range,
+            selection_range: range,
+            deprecated: false,
+            detail: None,
+            children: Some(children),
+        });
+    }
+    res
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -363,4 +470,86 @@ mod tests {
 "#,
         );
     }
+
+    #[test]
+    fn test_gen_server_lifecycle() {
+        check(
+            r#"~
+   -module(main).
+   -behaviour(gen_server).
+%% ^^^^^^^^^^^^^^^^^^^^^^^ Behaviour | gen_server lifecycle
+   -export([terminate/2, handle_call/3, init/1]).
+   terminate(_, _) -> ok.
+%% ^^^^^^^^^ Callback | terminate/2
+%% ^^^^^^^^^ Function | terminate/2
+%% ^^^^^^^^^ Function | terminate(_, _) | terminate/2
+   handle_call(_, _, S) -> {reply, ok, S}.
+%% ^^^^^^^^^^^ Callback | handle_call/3
+%% ^^^^^^^^^^^ Function | handle_call/3
+%% ^^^^^^^^^^^ Function | handle_call(_, _, S) | handle_call/3
+   init([]) -> {ok, []}.
+%% ^^^^ Callback | init/1
+%% ^^^^ Function | init/1
+%% ^^^^ Function | init([]) | init/1
+"#,
+        );
+    }
+
+    #[test]
+    fn test_gen_statem_lifecycle() {
+        check(
+            r#"~
+   -module(main).
+   -behaviour(gen_statem).
+%% ^^^^^^^^^^^^^^^^^^^^^^^ Behaviour | gen_statem lifecycle
+   -export([terminate/3, idle/3, callback_mode/0, init/1, helper/3]).
+   terminate(_, _, _) -> ok.
+%% ^^^^^^^^^ Callback | terminate/3
+%% ^^^^^^^^^ Function | terminate/3
+%% ^^^^^^^^^ Function | terminate(_, _, _) | terminate/3
+   idle(_, _, D) -> {keep_state, D}.
+%% ^^^^ Callback | idle/3
+%% ^^^^ Function | idle/3
+%% ^^^^ Function | idle(_, _, D) | idle/3
+   callback_mode() -> state_functions.
+%% ^^^^^^^^^^^^^ Callback | callback_mode/0
+%% ^^^^^^^^^^^^^ Function | callback_mode/0
+%% ^^^^^^^^^^^^^ Function | callback_mode() | callback_mode/0
+   init([]) -> {ok, idle, []}.
+%% ^^^^ Callback | init/1
+%% ^^^^ Function | init/1
+%% ^^^^ Function | init([]) | init/1
+   helper(_, _, _) -> ok.
+%% ^^^^^^ Function | helper/3
+%% ^^^^^^ Function | helper(_, _, _) | helper/3
+"#,
+        );
+    }
+
+    #[test]
+    fn test_lifecycle_order() {
+        let (analysis, pos, _) = fixture::position(
+            r#"~
+-module(main).
+-behaviour(gen_server).
+terminate(_, _) -> ok.
+handle_info(_, S) -> {noreply, S}.
+handle_call(_, _, S) -> {reply, ok, S}.
+init([]) -> {ok, []}.
+"#,
+        );
+        let symbols = analysis.document_symbols(pos.file_id).unwrap();
+        let lifecycle: Vec<String> = symbols
+            .into_iter()
+            .find(|symbol| symbol.name == "gen_server lifecycle")
+            .and_then(|symbol| symbol.children)
+            .unwrap()
+            .into_iter()
+            .map(|symbol| symbol.name)
+            .collect();
+        assert_eq!(
+            lifecycle,
+            vec!["init/1", "handle_call/3", "handle_info/2", "terminate/2"]
+        );
+    }
 }
@@ -11,7 +11,12 @@ use elp_ide_db::helpers::pick_best_token;
 use elp_ide_db::RootDatabase;
 use elp_syntax::ast;
 use elp_syntax::AstNode;
+use elp_syntax::NodeOrToken;
 use elp_syntax::SyntaxKind;
+use elp_syntax::WalkEvent;
+use fxhash::FxHashMap;
+use hir::InFile;
+use hir::MacroCallDef;
 use hir::Semantic;
 
 use crate::FilePosition;
@@ -19,7 +24,12 @@ use crate::FilePosition;
 #[derive(Debug)]
 pub struct ExpandedMacro {
     pub name: String,
+    /// The expansion of the macro and of the macros it uses, lowered and
+    /// pretty-printed
     pub expansion: String,
+    /// The replacement of the macro with its arguments substituted, as
+    /// written, leaving the macros it uses unexpanded
+    pub single_step: String,
 }
 
 // Feature: Expand Macro Recursively
@@ -50,9 +60,12 @@ pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<
 
         if mac.name()?.syntax().text_range().contains(position.offset) {
             let (name, expansion) = sema.expand(source_file.with_value(&mac))?;
+            let single_step = expand_single_step(&sema, source_file.with_value(&mac))
+                .unwrap_or_else(|| expansion.clone());
             Some(ExpandedMacro {
                 name: name.to_string(),
                 expansion,
+                single_step,
             })
         } else {
             None
@@ -60,6 +73,63 @@ pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<
     })
 }
 
+/// Substitutes the arguments of the call into the replacement of the
+/// macro definition, the way the preprocessor does for a single level of
+/// macros. Built-in macros have no definition to substitute into.
+fn expand_single_step(sema: &Semantic, call: InFile<&ast::MacroCallExpr>) -> Option<String> {
+    let MacroCallDef::Macro(define) = sema.to_def(call)? else {
+        return None;
+    };
+    let define = define.source(sema.db.upcast());
+    let params: Vec<String> = match define.lhs()?.args() {
+        Some(params) => params
+            .args()
+            .map(|var| var.syntax().text().to_string())
+            .collect(),
+        None => Vec::new(),
+    };
+    let args: Vec<String> = match call.value.args() {
+        Some(args) => args
+            .args()
+            .map(|arg| arg.syntax().text().to_string())
+            .collect(),
+        None => Vec::new(),
+    };
+    if params.len() != args.len() {
+        return None;
+    }
+    let args: FxHashMap<String, String> = params.into_iter().zip(args).collect();
+
+    let mut out = String::new();
+    let mut preorder = define.replacement()?.syntax().preorder_with_tokens();
+    while let Some(event) = preorder.next() {
+        match event {
+            WalkEvent::Enter(NodeOrToken::Node(node)) => {
+                // `??Arg` is the source text of the argument as a string
+                let arg = ast::MacroString::cast(node)
+                    .and_then(|string| args.get(&string.name()?.syntax().text().to_string()));
+                if let Some(arg) = arg {
+                    out.push_str(&format!(
+                        "\"{}\"",
+                        arg.replace('\\', "\\\\").replace('"', "\\\"")
+                    ));
+                    preorder.skip_subtree();
+                }
+            }
+            WalkEvent::Enter(NodeOrToken::Token(token)) => match token.kind() {
+                SyntaxKind::COMMENT => {}
+                SyntaxKind::VAR => match args.get(token.text()) {
+                    Some(arg) => out.push_str(arg),
+                    None => out.push_str(token.text()),
+                },
+                _ => out.push_str(token.text()),
+            },
+            WalkEvent::Leave(_) => {}
+        }
+    }
+    Some(out.trim().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use expect_test::expect;
@@ -77,6 +147,52 @@ mod tests {
         expect.assert_eq(&expansion);
     }
 
+    #[track_caller]
+    fn check_single_step(elp_fixture: &str, expect: Expect) {
+        let (analysis, pos, _) = fixture::position(elp_fixture);
+        let expansion = match analysis.expand_macro(pos).unwrap() {
+            Some(it) => it.single_step,
+            None => "***EXPANSION FAILED***".to_string(),
+        };
+        expect.assert_eq(&expansion);
+    }
+
+    #[test]
+    fn single_step_leaves_nested_macros() {
+        check_single_step(
+            r#"
+-module(foo).
+-define(BAZ, baz).
+-define(FOO(X, Y), {X, Y, ?BAZ}).
+bar() -> ?F~OO(4, [a, b]).
+"#,
+            expect!["{4, [a, b], ?BAZ}"],
+        );
+    }
+
+    #[test]
+    fn single_step_stringifies_arguments() {
+        check_single_step(
+            r#"
+-module(foo).
+-define(SHOW(Expr), io:format("~s = ~p~n", [??Expr, Expr])).
+bar() -> ?SH~OW(1 + 2).
+"#,
+            expect![[r#"io:format("~s = ~p~n", ["1 + 2", 1 + 2])"#]],
+        );
+    }
+
+    #[test]
+    fn single_step_built_in_macro() {
+        check_single_step(
+            r#"
+-module(foo).
+bar() -> ?L~INE.
+"#,
+            expect!["0"],
+        );
+    }
+
     #[test]
     fn macro_expand_line_macro() {
         check(
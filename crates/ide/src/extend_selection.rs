@@ -218,26 +218,27 @@ fn extend_list_item(node: &SyntaxNode) -> Option<TextRange> {
             .filter(|node| node.kind() == delimiter_kind)
     }
 
-    let delimiter = SyntaxKind::ANON_COMMA;
-
-    if let Some(delimiter_node) = nearby_delimiter(delimiter, node, Direction::Next) {
-        // Include any following whitespace when delimiter is after list item.
-        let final_node = delimiter_node
-            .next_sibling_or_token()
-            .and_then(|it| it.into_token())
-            .filter(is_single_line_ws)
-            .unwrap_or(delimiter_node);
-
-        return Some(TextRange::new(
-            node.text_range().start(),
-            final_node.text_range().end(),
-        ));
-    }
-    if let Some(delimiter_node) = nearby_delimiter(delimiter, node, Direction::Prev) {
-        return Some(TextRange::new(
-            delimiter_node.text_range().start(),
-            node.text_range().end(),
-        ));
+    // Expressions and arguments are separated by commas, clauses by semicolons.
+    for delimiter in [SyntaxKind::ANON_COMMA, SyntaxKind::ANON_SEMI] {
+        if let Some(delimiter_node) = nearby_delimiter(delimiter, node, Direction::Next) {
+            // Include any following whitespace when delimiter is after list item.
+            let final_node = delimiter_node
+                .next_sibling_or_token()
+                .and_then(|it| it.into_token())
+                .filter(is_single_line_ws)
+                .unwrap_or(delimiter_node);
+
+            return Some(TextRange::new(
+                node.text_range().start(),
+                final_node.text_range().end(),
+            ));
+        }
+        if let Some(delimiter_node) = nearby_delimiter(delimiter, node, Direction::Prev) {
+            return Some(TextRange::new(
+                delimiter_node.text_range().start(),
+                node.text_range().end(),
+            ));
+        }
     }
 
     None
@@ -322,6 +323,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extend_selection_function_clauses() {
+        do_check(
+            "foo(~1) -> one;\nfoo(_) -> other.",
+            &[
+                "1",
+                "(1)",
+                "foo(1) -> one",
+                "foo(1) -> one;",
+                "foo(1) -> one;\nfoo(_) -> other.",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_extend_selection_case_clauses() {
+        do_check(
+            r#"
+foo(X) ->
+  case X of
+    1 -> o~ne;
+    _ -> other
+  end.
+"#,
+            &[
+                "one",
+                "-> one",
+                "1 -> one",
+                "1 -> one;",
+                "case X of\n    1 -> one;\n    _ -> other\n  end",
+            ],
+        );
+    }
+
     #[test]
     fn test_extend_selection_strings() {
         do_check(
@@ -0,0 +1,95 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Size and shape of a module, as summed up over a project by `elp stats`.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::FileKind;
+use elp_ide_db::RootDatabase;
+use hir::Semantic;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FileStats {
+    pub lines: usize,
+    /// Functions defined in the file, not in its includes
+    pub functions: usize,
+    pub specs: usize,
+    pub records: usize,
+    pub macros: usize,
+    /// Names of the behaviours the module implements
+    pub behaviours: Vec<String>,
+    pub test_suite: bool,
+}
+
+pub(crate) fn file_stats(db: &RootDatabase, file_id: FileId) -> FileStats {
+    let sema = Semantic::new(db);
+    let form_list = sema.form_list(file_id);
+    let def_map = sema.def_map(file_id);
+    FileStats {
+        lines: db.file_text(file_id).lines().count(),
+        functions: def_map
+            .get_functions()
+            .filter(|(_, def)| def.file.file_id == file_id)
+            .count(),
+        specs: form_list.specs().count(),
+        records: def_map
+            .get_records()
+            .values()
+            .filter(|def| def.file.file_id == file_id)
+            .count(),
+        macros: form_list.define_attributes().count(),
+        behaviours: form_list
+            .behaviour_attributes()
+            .map(|(_, behaviour)| behaviour.name.to_string())
+            .collect(),
+        test_suite: db.file_kind(file_id) == FileKind::TestModule,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileStats;
+    use crate::fixture;
+
+    #[test]
+    fn module_stats() {
+        let (analysis, pos, _) = fixture::position(
+            r#"
+//- /include/defs.hrl
+-record(config, {name}).
+-define(TIMEOUT, 5000).
+//- /src/main.erl
+-module(main).~
+-behaviour(gen_server).
+-include("defs.hrl").
+-record(state, {count = 0}).
+-define(NAME, main).
+-define(WRAP(X), {X}).
+-export([init/1, handle_call/3]).
+-spec init(term()) -> {ok, #state{}}.
+init(_) -> {ok, #state{}}.
+handle_call(_, _, State) -> {reply, ok, State}.
+"#,
+        );
+        let stats = analysis.file_stats(pos.file_id).unwrap();
+        assert_eq!(
+            stats,
+            FileStats {
+                lines: 10,
+                functions: 2,
+                specs: 1,
+                records: 1,
+                macros: 2,
+                behaviours: vec!["gen_server".to_string()],
+                test_suite: false,
+            }
+        );
+    }
+}
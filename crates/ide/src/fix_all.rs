@@ -0,0 +1,442 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Applying a quick fix to every diagnostic it fixes, or a pattern assist
+//! to every site it applies to, in the file or in the whole project, as a
+//! single change.
+//!
+//! Two fixes are the same when they have the same id and fix diagnostics
+//! with the same code, such as replacing each call to a deprecated
+//! function. Fixes only suppressing a diagnostic are left out. Pattern
+//! assists are those applying to each site matching a pattern, such as
+//! qualifying the calls to imported functions.
+
+use elp_ide_assists::Assist;
+use elp_ide_assists::AssistConfig;
+use elp_ide_assists::AssistId;
+use elp_ide_assists::AssistKind;
+use elp_ide_assists::AssistResolveStrategy;
+use elp_ide_assists::GroupLabel;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::FileRange;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_ide_db::source_change::SourceChange;
+use elp_ide_db::RootDatabase;
+use elp_syntax::label::Label;
+use elp_syntax::TextRange;
+use fxhash::FxHashMap;
+use fxhash::FxHashSet;
+use text_edit::TextEdit;
+
+use crate::diagnostics;
+use crate::diagnostics::AdhocSemanticDiagnostics;
+use crate::diagnostics::Diagnostic;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::DiagnosticsConfig;
+use crate::diagnostics_collection::DiagnosticCollection;
+
+const FIX_ALL_IN_FILE: AssistId = AssistId("fix_all_in_file", AssistKind::Source);
+const FIX_ALL_IN_PROJECT: AssistId = AssistId("fix_all_in_project", AssistKind::Source);
+
+/// What the file and project wide variants are computed from.
+pub(crate) struct FixAllContext<'a> {
+    pub(crate) db: &'a RootDatabase,
+    pub(crate) config: &'a DiagnosticsConfig,
+    pub(crate) assist_config: &'a AssistConfig,
+    pub(crate) adhoc_semantic_diagnostics: &'a Vec<&'a dyn AdhocSemanticDiagnostics>,
+    /// The diagnostics the server holds, reused for the other files of
+    /// the project.
+    pub(crate) diagnostics_collection: &'a DiagnosticCollection,
+    pub(crate) resolve: &'a AssistResolveStrategy,
+    pub(crate) frange: FileRange,
+}
+
+/// The file and project wide variants of the fixes of the diagnostics in
+/// `frange`, and of the pattern assists among `assists`. `diagnostics`
+/// are those of the whole file.
+pub(crate) fn fix_all_assists(
+    ctx: &FixAllContext,
+    diagnostics: &[Diagnostic],
+    assists: &[Assist],
+) -> Vec<Assist> {
+    let mut res = Vec::new();
+    let mut seen = FxHashSet::default();
+    let fixes = diagnostics
+        .iter()
+        .flat_map(|d| d.fixes.iter().flatten().map(move |fix| (&d.code, fix)));
+    for (code, fix) in fixes {
+        if fix.target.intersect(ctx.frange.range).is_none()
+            || fix.group == Some(GroupLabel::ignore())
+            || !seen.insert((code.clone(), fix.id.0))
+        {
+            continue;
+        }
+        let mut in_file = FixAll::default();
+        in_file.add_diagnostics(diagnostics, code, fix.id.0);
+        // Each variant is keyed by the code and the fix, so resolving
+        // one of them does not compute the others.
+        let subtype = format!("{}:{}", code.as_code(), fix.id.0);
+        ctx.add_variants(&mut res, fix, in_file, subtype, |all| {
+            for_each_project_diagnostics(ctx, diagnostics, |diagnostics| {
+                all.add_diagnostics(diagnostics, code, fix.id.0)
+            })
+        });
+    }
+    let mut seen = FxHashSet::default();
+    for assist in assists {
+        if !elp_ide_assists::is_pattern_assist(&assist.id) || !seen.insert(assist.id.0) {
+            continue;
+        }
+        let mut in_file = FixAll::default();
+        in_file.add_pattern_assists(ctx, &assist.id, ctx.frange.file_id);
+        let subtype = assist.id.0.to_string();
+        ctx.add_variants(&mut res, assist, in_file, subtype, |all| {
+            for file_id in project_files(ctx) {
+                all.add_pattern_assists(ctx, &assist.id, file_id);
+            }
+        });
+    }
+    res
+}
+
+impl FixAllContext<'_> {
+    /// Adds the file wide variant of `assist` when it applies more than
+    /// once, and the project wide variant, computed by `in_project` when
+    /// the client resolves it.
+    fn add_variants(
+        &self,
+        res: &mut Vec<Assist>,
+        assist: &Assist,
+        in_file: FixAll,
+        subtype: String,
+        in_project: impl FnOnce(&mut FixAll),
+    ) {
+        if in_file.count > 1 {
+            res.push(in_file.assist(
+                FIX_ALL_IN_FILE,
+                format!("{} ({} occurrences in file)", assist.label, in_file.count),
+                assist.target,
+            ));
+        }
+        // Fixing the whole project means going through all its files,
+        // so only do it when the client resolves it.
+        if matches!(self.resolve, AssistResolveStrategy::All) {
+            return;
+        }
+        let label = format!("{} in the whole project", assist.label);
+        let mut assist = if self
+            .resolve
+            .should_resolve_subtype(&FIX_ALL_IN_PROJECT, Some(&subtype))
+        {
+            let mut all = FixAll::default();
+            in_project(&mut all);
+            let label = format!("{label} ({} occurrences)", all.count);
+            all.assist(FIX_ALL_IN_PROJECT, label, assist.target)
+        } else {
+            FixAll::default().assist(FIX_ALL_IN_PROJECT, label, assist.target)
+        };
+        assist.subtype = Some(subtype);
+        res.push(assist);
+    }
+}
+
+/// The files of the project of the file the assists are asked for.
+fn project_files(ctx: &FixAllContext) -> Vec<FileId> {
+    match ctx.db.file_app_data(ctx.frange.file_id) {
+        Some(app_data) => ctx
+            .db
+            .module_index(app_data.project_id)
+            .iter_own()
+            .map(|(_name, _source, file_id)| file_id)
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Calls `f` with the native diagnostics of each file of the project:
+/// `current` for the file the assists are asked for, and those the
+/// server holds for the others when it has them.
+fn for_each_project_diagnostics(
+    ctx: &FixAllContext,
+    current: &[Diagnostic],
+    mut f: impl FnMut(&[Diagnostic]),
+) {
+    for file_id in project_files(ctx) {
+        if file_id == ctx.frange.file_id {
+            f(current);
+            continue;
+        }
+        let diagnostics: Vec<Diagnostic> = match ctx.diagnostics_collection.native.get(&file_id) {
+            Some(diagnostics) => diagnostics.iter().cloned().collect(),
+            None => diagnostics::native_diagnostics(
+                ctx.db,
+                ctx.config,
+                ctx.adhoc_semantic_diagnostics,
+                file_id,
+            )
+            .iter()
+            .cloned()
+            .collect(),
+        };
+        f(&diagnostics);
+    }
+}
+
+#[derive(Default)]
+struct FixAll {
+    edits: FxHashMap<FileId, TextEdit>,
+    count: usize,
+}
+
+impl FixAll {
+    fn add_diagnostics(&mut self, diagnostics: &[Diagnostic], code: &DiagnosticCode, fix_id: &str) {
+        diagnostics
+            .iter()
+            .filter(|d| &d.code == code)
+            .flat_map(|d| d.fixes.iter().flatten())
+            .filter(|fix| fix.id.0 == fix_id)
+            .filter_map(|fix| fix.source_change.as_ref())
+            .for_each(|change| {
+                if self.add_change(change) {
+                    self.count += 1;
+                }
+            });
+    }
+
+    fn add_pattern_assists(&mut self, ctx: &FixAllContext, id: &AssistId, file_id: FileId) {
+        elp_ide_assists::pattern_assists(ctx.db, ctx.assist_config, id, file_id)
+            .iter()
+            .filter_map(|assist| assist.source_change.as_ref())
+            .for_each(|change| {
+                if self.add_change(change) {
+                    self.count += 1;
+                }
+            });
+    }
+
+    /// Fixes overlapping an already added one are left out, as are fixes
+    /// touching the file system.
+    fn add_change(&mut self, change: &SourceChange) -> bool {
+        if !change.file_system_edits.is_empty() {
+            return false;
+        }
+        let mut merged = Vec::new();
+        for (file_id, edit) in &change.source_file_edits {
+            let mut acc = self.edits.get(file_id).cloned().unwrap_or_default();
+            if acc.union(edit.clone()).is_err() {
+                return false;
+            }
+            merged.push((*file_id, acc));
+        }
+        self.edits.extend(merged);
+        true
+    }
+
+    fn assist(self, id: AssistId, label: String, target: TextRange) -> Assist {
+        let source_change =
+            (self.count > 0).then(|| SourceChange::from_edits(self.edits, Vec::new()));
+        Assist {
+            id,
+            label: Label::new(label),
+            group: None,
+            target,
+            source_change,
+            user_input: None,
+            original_diagnostic: None,
+            is_preferred: false,
+            subtype: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_ide_assists::AssistConfig;
+    use elp_ide_assists::AssistKind;
+    use elp_ide_assists::AssistResolveStrategy;
+    use elp_ide_assists::SingleResolve;
+    use elp_ide_db::elp_base_db::FileRange;
+    use elp_syntax::TextRange;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::diagnostics::DiagnosticsConfig;
+    use crate::diagnostics_collection::DiagnosticCollection;
+    use crate::fixture;
+
+    #[track_caller]
+    fn check_fix_all(fixture: &str, resolve: AssistResolveStrategy, expect: Expect) {
+        let (analysis, position, _) = fixture::position(fixture);
+        let assists = analysis
+            .assists_with_fixes(
+                &AssistConfig {
+                    snippet_cap: None,
+                    allowed: None,
+                    disabled: Vec::new(),
+                    min_otp_release: None,
+                    align_arrows: false,
+                },
+                &DiagnosticsConfig::default().set_experimental(true),
+                &vec![],
+                resolve,
+                FileRange {
+                    file_id: position.file_id,
+                    range: TextRange::empty(position.offset),
+                },
+                &[],
+                &DiagnosticCollection::default(),
+                None,
+            )
+            .unwrap();
+        let fix_all: Vec<_> = assists
+            .iter()
+            .filter(|assist| assist.id.1 == AssistKind::Source)
+            .map(|assist| {
+                (
+                    assist.label.to_string(),
+                    assist.subtype.clone(),
+                    assist.source_change.is_some(),
+                )
+            })
+            .collect();
+        expect.assert_debug_eq(&fix_all);
+    }
+
+    #[test]
+    fn fix_all_in_file() {
+        check_fix_all(
+            r#"
+-module(main).
+foo(A~, B) -> {ok}.
+bar(C) -> ok.
+"#,
+            AssistResolveStrategy::All,
+            expect![[r#"
+                [
+                    (
+                        "Prefix variable with an underscore (3 occurrences in file)",
+                        None,
+                        true,
+                    ),
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn fix_all_in_project_unresolved() {
+        check_fix_all(
+            r#"
+//- /src/main.erl
+-module(main).
+foo(A~) -> ok.
+//- /src/other.erl
+-module(other).
+bar(B, C) -> ok.
+"#,
+            AssistResolveStrategy::None,
+            expect![[r#"
+                [
+                    (
+                        "Prefix variable with an underscore in the whole project",
+                        Some(
+                            "W0010:prefix_with_underscore",
+                        ),
+                        false,
+                    ),
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn fix_all_in_project_resolved() {
+        check_fix_all(
+            r#"
+//- /src/main.erl
+-module(main).
+foo(A~) -> ok.
+//- /src/other.erl
+-module(other).
+bar(B, C) -> ok.
+"#,
+            AssistResolveStrategy::Single(SingleResolve {
+                assist_id: "fix_all_in_project".to_string(),
+                assist_kind: AssistKind::Source,
+                assist_subtype: Some("W0010:prefix_with_underscore".to_string()),
+            }),
+            expect![[r#"
+                [
+                    (
+                        "Prefix variable with an underscore in the whole project (3 occurrences)",
+                        Some(
+                            "W0010:prefix_with_underscore",
+                        ),
+                        true,
+                    ),
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn pattern_assist_in_file() {
+        check_fix_all(
+            r#"
+-module(main).
+-export([foo/1, bar/1]).
+-import(lists, [reverse/1, sort/1]).
+foo(L) -> rev~erse(sort(L)).
+bar(L) -> reverse(L).
+"#,
+            AssistResolveStrategy::All,
+            expect![[r#"
+                [
+                    (
+                        "Qualify the imported call (3 occurrences in file)",
+                        None,
+                        true,
+                    ),
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn pattern_assist_in_project_resolved() {
+        check_fix_all(
+            r#"
+//- /src/main.erl
+-module(main).
+-export([foo/0]).
+foo() -> "a~b".
+//- /src/other.erl
+-module(other).
+-export([bar/0]).
+bar() -> {"c", "d"}.
+"#,
+            AssistResolveStrategy::Single(SingleResolve {
+                assist_id: "fix_all_in_project".to_string(),
+                assist_kind: AssistKind::Source,
+                assist_subtype: Some("string_to_binary".to_string()),
+            }),
+            expect![[r#"
+                [
+                    (
+                        "Convert to binary string in the whole project (3 occurrences)",
+                        Some(
+                            "string_to_binary",
+                        ),
+                        true,
+                    ),
+                ]
+            "#]],
+        );
+    }
+}
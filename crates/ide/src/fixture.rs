@@ -17,10 +17,8 @@ use elp_ide_db::elp_base_db::SourceDatabase;
 use elp_ide_db::RootDatabase;
 use elp_project_model::test_fixture::DiagnosticsEnabled;
 
-use crate::diagnostics::AdhocSemanticDiagnostics;
-use crate::diagnostics::DiagnosticsConfig;
-use crate::diagnostics::RemoveElpReported;
-use crate::diagnostics_collection::DiagnosticCollection;
+pub(crate) use crate::testing::diagnostics_for;
+
 use crate::Analysis;
 use crate::AnalysisHost;
 use crate::FilePosition;
@@ -99,59 +97,3 @@ pub fn check_no_parse_errors(analysis: &Analysis, file_id: FileId) -> Option<()>
     };
     Some(())
 }
-
-// TODO: Further up the stack, once all diagnostics sources populated:
-//   Move to Analysis, using ? not unwrap()
-//   And then used in elp_lint too
-pub fn diagnostics_for(
-    analysis: &Analysis,
-    file_id: FileId,
-    config: &DiagnosticsConfig,
-    adhoc_semantic_diagnostics: &Vec<&dyn AdhocSemanticDiagnostics>,
-    diagnostics_enabled: &DiagnosticsEnabled,
-) -> DiagnosticCollection {
-    let mut diagnostics = DiagnosticCollection::default();
-    let DiagnosticsEnabled {
-        use_native,
-        use_erlang_service,
-        use_eqwalizer,
-        use_ct,
-        use_edoc,
-        tmp_dir: _,
-    } = diagnostics_enabled;
-    if *use_native {
-        diagnostics.set_native(
-            file_id,
-            analysis
-                .native_diagnostics(config, adhoc_semantic_diagnostics, file_id)
-                .unwrap(),
-        );
-    }
-    if *use_erlang_service {
-        let erlang_service_diagnostics = analysis
-            .erlang_service_diagnostics(file_id, config, RemoveElpReported::Yes)
-            .unwrap();
-        for (file_id, diags) in erlang_service_diagnostics {
-            diagnostics.set_erlang_service(file_id, diags)
-        }
-    }
-    if *use_eqwalizer {
-        let include_generated = elp_types_db::IncludeGenerated::Yes;
-        if let Some(diags) = analysis
-            .eqwalizer_diagnostics_for_file(file_id, include_generated)
-            .unwrap()
-        {
-            diagnostics.set_eqwalizer(file_id, diags);
-        }
-    }
-    if *use_ct {
-        diagnostics.set_ct(file_id, analysis.ct_diagnostics(file_id, config).unwrap());
-    }
-    if *use_edoc {
-        let edoc_diagnostics = analysis.edoc_diagnostics(file_id).unwrap();
-        for (file_id, diags) in edoc_diagnostics {
-            diagnostics.set_edoc(file_id, diags);
-        }
-    }
-    diagnostics
-}
@@ -8,12 +8,20 @@
  */
 
 use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::SourceDatabase;
 use elp_ide_db::RootDatabase;
+use elp_syntax::AstNode;
+use elp_syntax::NodeOrToken;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxToken;
 use elp_syntax::TextRange;
 use hir::form_list::DocAttribute;
+use hir::form_list::Export;
 use hir::form_list::ModuleDocAttribute;
+use hir::FormIdx;
 use hir::FunctionDef;
 use hir::InFile;
+use hir::PPCondition;
 use hir::RecordDef;
 use hir::Semantic;
 
@@ -23,6 +31,13 @@ pub enum FoldingRangeKind {
     Record,
     ModuleDocAttribute,
     DocAttribute,
+    /// The clauses of a `case`, `if`, `try` or `receive`, or a `begin` block
+    Block,
+    /// Consecutive lines of comments
+    Comment,
+    Export,
+    /// An `-ifdef`, `-ifndef` or `-if` region, up to its `-endif`
+    Condition,
 }
 
 #[derive(Debug)]
@@ -79,14 +94,30 @@ impl FoldingRangeTrait for InFile<&DocAttribute> {
     }
 }
 
+impl FoldingRangeTrait for InFile<&Export> {
+    fn folding_range(&self, db: &RootDatabase) -> Option<FoldingRange> {
+        let range = self.value.form_id.range(db, self.file_id);
+        let folding_range = FoldingRange {
+            kind: FoldingRangeKind::Export,
+            range,
+        };
+        Some(folding_range)
+    }
+}
+
 // Feature: Folding
 //
-// Defines folding regions for functions, records and doc attributes.
+// Defines folding regions for functions, records, doc attributes, export
+// lists, preprocessor conditions, block expressions and groups of comments.
+// Only the export lists and block expressions spanning several lines fold.
 pub(crate) fn folding_ranges(db: &RootDatabase, file_id: FileId) -> Vec<FoldingRange> {
     let mut folds = Vec::new();
     let sema = Semantic::new(db);
     let def_map = sema.def_map(file_id);
     let form_list = sema.form_list(file_id);
+    let source = sema.parse(file_id).value;
+    let text = db.file_text(file_id);
+    let is_multiline = |range: TextRange| text[range].contains('\n');
     // Functions
     for (_, def) in def_map.get_functions() {
         if let Some(folding_range) = def.folding_range(db) {
@@ -113,9 +144,96 @@ pub(crate) fn folding_ranges(db: &RootDatabase, file_id: FileId) -> Vec<FoldingR
             folds.push(folding_range)
         }
     }
+    // Export Attributes
+    for (_idx, export) in form_list.exports() {
+        let in_file = InFile::new(file_id, export);
+        if let Some(folding_range) = in_file.folding_range(db) {
+            if is_multiline(folding_range.range) {
+                folds.push(folding_range)
+            }
+        }
+    }
+    // Preprocessor Conditions, from the opening directive to its `-endif`
+    for form in form_list.forms() {
+        let FormIdx::PPCondition(idx) = form else {
+            continue;
+        };
+        let PPCondition::Endif { prev, form_id } = &form_list[*idx] else {
+            continue;
+        };
+        let mut opening = *prev;
+        while let PPCondition::Else { prev, .. } | PPCondition::Elif { prev, .. } =
+            &form_list[opening]
+        {
+            opening = *prev;
+        }
+        let start = form_list[opening].form_id().range(db, file_id).start();
+        let end = form_id.range(db, file_id).end();
+        folds.push(FoldingRange {
+            kind: FoldingRangeKind::Condition,
+            range: TextRange::new(start, end),
+        });
+    }
+    // Block Expressions
+    for node in source.syntax().descendants() {
+        if matches!(
+            node.kind(),
+            SyntaxKind::CASE_EXPR
+                | SyntaxKind::IF_EXPR
+                | SyntaxKind::TRY_EXPR
+                | SyntaxKind::RECEIVE_EXPR
+                | SyntaxKind::BLOCK_EXPR
+        ) && is_multiline(node.text_range())
+        {
+            folds.push(FoldingRange {
+                kind: FoldingRangeKind::Block,
+                range: node.text_range(),
+            });
+        }
+    }
+    // Comments
+    let mut group: Option<(TextRange, usize)> = None;
+    for element in source.syntax().descendants_with_tokens() {
+        let NodeOrToken::Token(token) = element else {
+            continue;
+        };
+        match token.kind() {
+            SyntaxKind::COMMENT if starts_line(&token) => {
+                group = match group {
+                    Some((range, count)) => Some((range.cover(token.text_range()), count + 1)),
+                    None => Some((token.text_range(), 1)),
+                };
+            }
+            // A blank line ends the group
+            SyntaxKind::WHITESPACE if token.text().matches('\n').count() < 2 => {}
+            _ => {
+                push_comment_group(&mut folds, group.take());
+            }
+        }
+    }
+    push_comment_group(&mut folds, group);
     folds
 }
 
+/// Whether the comment is alone on its line, rather than following code.
+fn starts_line(token: &SyntaxToken) -> bool {
+    match token.prev_token() {
+        Some(prev) => prev.kind() == SyntaxKind::WHITESPACE && prev.text().contains('\n'),
+        None => true,
+    }
+}
+
+fn push_comment_group(folds: &mut Vec<FoldingRange>, group: Option<(TextRange, usize)>) {
+    if let Some((range, count)) = group {
+        if count > 1 {
+            folds.push(FoldingRange {
+                kind: FoldingRangeKind::Comment,
+                range,
+            });
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use elp_ide_db::elp_base_db::fixture::extract_tags;
@@ -152,7 +270,11 @@ mod tests {
                 FoldingRangeKind::Function
                 | FoldingRangeKind::Record
                 | FoldingRangeKind::ModuleDocAttribute
-                | FoldingRangeKind::DocAttribute => "region",
+                | FoldingRangeKind::DocAttribute
+                | FoldingRangeKind::Block
+                | FoldingRangeKind::Export
+                | FoldingRangeKind::Condition => "region",
+                FoldingRangeKind::Comment => "comment",
             };
             assert_eq!(kind, &attr.unwrap());
         }
@@ -226,6 +348,67 @@ This is a module doc
 This is one function
 ".</fold>
 <fold region>one() -> 1.</fold>
+"#,
+        );
+    }
+
+    #[test]
+    fn test_block_expressions() {
+        check(
+            r#"
+-module(my_module).
+<fold region>one(X) ->
+  <fold region>case X of
+    1 -> <fold region>begin
+      ok
+    end</fold>;
+    _ -> begin error end
+  end</fold>.</fold>
+"#,
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        check(
+            r#"
+-module(my_module).
+<fold comment>%% A comment
+%% spanning lines</fold>
+
+%% A single comment
+
+<fold region>one() -> ok.</fold> % trailing
+% not grouped with the trailing one
+"#,
+        );
+    }
+
+    #[test]
+    fn test_export_lists() {
+        check(
+            r#"
+-module(my_module).
+-export([one/0]).
+<fold region>-export([
+    two/0
+]).</fold>
+<fold region>one() -> ok.</fold>
+<fold region>two() -> ok.</fold>
+"#,
+        );
+    }
+
+    #[test]
+    fn test_preprocessor_conditions() {
+        check(
+            r#"
+-module(my_module).
+<fold region>-ifdef(TEST).
+-define(X, 1).
+-else.
+-define(X, 2).
+-endif.</fold>
 "#,
         );
     }
@@ -12,8 +12,15 @@ use elp_ide_db::elp_base_db::FilePosition;
 use elp_ide_db::elp_base_db::FileRange;
 use elp_ide_db::find_best_token;
 use elp_ide_db::RootDatabase;
+use elp_ide_db::SymbolClass;
+use elp_ide_db::SymbolDefinition;
+use elp_syntax::SyntaxToken;
+use hir::InFile;
 use hir::Semantic;
 
+const NIF_STUB_NOTE: &str =
+    "*NIF stub*: implemented natively, the library is loaded with `erlang:load_nif/2`.";
+
 pub(crate) fn get_doc_at_position(
     db: &RootDatabase,
     position: FilePosition,
@@ -27,5 +34,173 @@ pub(crate) fn get_doc_at_position(
         range: token.value.text_range(),
     };
     let doc = Doc::from_reference(&docs, &token);
+    let doc = if is_nif_stub(&sema, &token) {
+        let text = match doc {
+            Some(doc) => format!("{NIF_STUB_NOTE}\n\n{}", doc.markdown_text()),
+            None => NIF_STUB_NOTE.to_string(),
+        };
+        Some(Doc::new(text))
+    } else {
+        doc
+    };
     doc.map(|d| (d, Some(range)))
 }
+
+fn is_nif_stub(sema: &Semantic, token: &InFile<SyntaxToken>) -> bool {
+    match SymbolClass::classify(sema, token.clone()) {
+        Some(class) => class.iter().any(|def| match def {
+            SymbolDefinition::Function(fun) => fun.is_nif_stub(sema.db),
+            _ => false,
+        }),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+
+    #[track_caller]
+    fn check(fixture: &str, expected: Option<&str>) {
+        let (analysis, position, _) = fixture::position(fixture);
+        let actual = analysis
+            .get_docs_at_position(position)
+            .unwrap()
+            .map(|(doc, _)| doc.markdown_text().to_string());
+        assert_eq!(expected.map(|s| s.to_string()), actual);
+    }
+
+    #[test]
+    fn nif_stub() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-export([add/2]).
+-on_load(init/0).
+init() -> erlang:load_nif("./main_nif", 0).
+add(_A, _B) -> erlang:nif_error(not_loaded).
+main() -> ad~d(1, 2).
+"#,
+            Some(
+                "*NIF stub*: implemented natively, the library is loaded with `erlang:load_nif/2`.",
+            ),
+        )
+    }
+
+    #[test]
+    fn not_a_nif_stub() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+add(A, B) -> A + B.
+main() -> ad~d(1, 2).
+"#,
+            None,
+        )
+    }
+
+    #[test]
+    fn doc_attribute() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-doc """
+Adds two numbers.
+
+Both must be integers.
+""".
+-spec add(integer(), integer()) -> integer().
+add(A, B) -> A + B.
+main() -> ad~d(1, 2).
+"#,
+            Some(
+                "```erlang\n-spec add(integer(), integer()) -> integer().\n```\n\n-----\n\nAdds two numbers.\n\nBoth must be integers.",
+            ),
+        )
+    }
+
+    #[test]
+    fn doc_attribute_false() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-doc false.
+add(A, B) -> A + B.
+main() -> ad~d(1, 2).
+"#,
+            None,
+        )
+    }
+
+    #[test]
+    fn doc_attribute_of_type() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-doc "A number to add.".
+-type operand() :: integer().
+add(A, B) -> A + B.
+main() -> ad~d(1, 2).
+"#,
+            None,
+        )
+    }
+
+    #[test]
+    fn edoc_of_remote_function() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+main() -> other:ad~d(1, 2).
+//- /src/other.erl
+-module(other).
+-export([add/2]).
+%% @doc Adds two numbers,
+%% which must be integers.
+%% @returns The sum
+add(A, B) -> A + B.
+"#,
+            Some("Adds two numbers,\nwhich must be integers."),
+        )
+    }
+
+    #[test]
+    fn edoc_before_spec() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+%% @doc Adds two numbers.
+-spec add(integer(), integer()) -> integer().
+add(A, B) -> A + B.
+main() -> ad~d(1, 2).
+"#,
+            Some(
+                "```erlang\n-spec add(integer(), integer()) -> integer().\n```\n\n-----\n\nAdds two numbers.",
+            ),
+        )
+    }
+
+    #[test]
+    fn moduledoc_attribute() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+main() -> oth~er:add(1, 2).
+//- /src/other.erl
+-module(other).
+-moduledoc "Arithmetic helpers.".
+-export([add/2]).
+add(A, B) -> A + B.
+"#,
+            Some("Arithmetic helpers."),
+        )
+    }
+}
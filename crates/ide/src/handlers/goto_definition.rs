@@ -7,29 +7,65 @@
  * of this source tree.
  */
 
+use elp_ide_db::elp_base_db::FileKind;
 use elp_ide_db::elp_base_db::FilePosition;
+use elp_ide_db::elp_base_db::SourceDatabase;
 use elp_ide_db::find_best_token;
 use elp_ide_db::RootDatabase;
 use elp_ide_db::SymbolClass;
+use elp_ide_db::SymbolKind;
+use elp_syntax::grammar_file;
 use hir::Semantic;
 
 use crate::navigation_target::NavigationTarget;
 use crate::navigation_target::ToNav;
+use crate::routes;
 use crate::RangeInfo;
 
 pub(crate) fn goto_definition(
     db: &RootDatabase,
     position: FilePosition,
 ) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    if db.file_kind(position.file_id) == FileKind::Grammar {
+        if let Some(res) = goto_grammar_symbol(db, position) {
+            return Some(res);
+        }
+    }
     let sema = Semantic::new(db);
     let token = find_best_token(&sema, position)?;
-    let targets = SymbolClass::classify(&sema, token.clone())?
-        .iter()
-        .map(|def| def.to_nav(db))
-        .collect();
+    let targets = match SymbolClass::classify(&sema, token.clone()) {
+        Some(class) => class.iter().map(|def| def.to_nav(db)).collect(),
+        // Path strings in routing tables lead to their handler
+        None => return routes::goto_handler(&sema, position),
+    };
     Some(RangeInfo::new(token.value.text_range(), targets))
 }
 
+/// Nonterminals and leex macros live in the grammar part of the file,
+/// which is not Erlang, so they are resolved textually.
+fn goto_grammar_symbol(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let text = db.file_text(position.file_id);
+    let symbol = grammar_file::symbol_at(&text, position.offset)?;
+    let targets: Vec<_> = grammar_file::symbol_definitions(&text)
+        .into_iter()
+        .filter(|def| def.name == symbol.name)
+        .map(|def| NavigationTarget {
+            file_id: position.file_id,
+            full_range: def.range,
+            focus_range: Some(def.range),
+            name: def.name,
+            kind: SymbolKind::Define,
+        })
+        .collect();
+    if targets.is_empty() {
+        return None;
+    }
+    Some(RangeInfo::new(symbol.range, targets))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::fixture;
@@ -3824,4 +3860,60 @@ foo() ->
             "#,
         )
     }
+
+    #[test]
+    fn on_load_function() {
+        check(
+            r#"
+         //- /src/main.erl
+            -module(main).
+            -on_load(in~it/0).
+            init() -> ok.
+         %% ^^^^
+            "#,
+        )
+    }
+
+    #[test]
+    fn dialyzer_function() {
+        check(
+            r#"
+         //- /src/main.erl
+            -module(main).
+            -dialyzer({nowarn_function, [f/0, ~g/1]}).
+            f() -> ok.
+            g(_) -> ok.
+         %% ^
+            "#,
+        )
+    }
+
+    #[test]
+    fn yecc_nonterminal() {
+        check(
+            r#"
+         //- /src/parser.yrl
+            Nonterminals expr term.
+            Terminals int.
+            Rootsymbol expr.
+            expr -> te~rm : '$1'.
+            term -> int : '$1'.
+         %% ^^^^
+            "#,
+        )
+    }
+
+    #[test]
+    fn leex_definition() {
+        check(
+            r#"
+         //- /src/lexer.xrl
+            Definitions.
+            D = [0-9]
+         %% ^
+            Rules.
+            {~D}+ : {token, {int, TokenLine, TokenChars}}.
+            "#,
+        )
+    }
 }
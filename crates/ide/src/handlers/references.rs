@@ -22,11 +22,15 @@ use elp_ide_db::find_best_token;
 use elp_ide_db::ReferenceType;
 use elp_ide_db::SymbolClass;
 use elp_ide_db::SymbolDefinition;
+use elp_syntax::ast;
 use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
 use elp_syntax::TextRange;
 use fxhash::FxHashMap;
 use hir::Semantic;
 
+use crate::routes;
 use crate::FilePosition;
 use crate::NavigationTarget;
 use crate::ToNav;
@@ -34,7 +38,35 @@ use crate::ToNav;
 #[derive(Debug, Clone)]
 pub struct ReferenceSearchResult {
     pub declaration: NavigationTarget,
-    pub references: FxHashMap<FileId, Vec<TextRange>>,
+    pub references: FxHashMap<FileId, Vec<Reference>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub range: TextRange,
+    pub kind: ReferenceKind,
+}
+
+/// How the item is used by a reference, so that the search can be
+/// limited to some uses, e.g. the calls of a function but not its export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceKind {
+    /// The function called, or the module of a remote call
+    Call,
+    /// An entry of `-export` or `-export_type`
+    Export,
+    /// `fun foo/1` or `fun mod:foo/1`
+    FunCapture,
+    /// A mention in a `-spec` or `-callback`
+    Spec,
+    /// An entry of `-import`
+    Import,
+    /// A bare atom, or a string such as a route, which is only likely to
+    /// refer to the item
+    Heuristic,
+    /// `` `m:f/1` `` in a `-doc` attribute, or `{@link m:f/1}` in EDoc
+    Doc,
+    Other,
 }
 
 // Feature: Find All References
@@ -49,26 +81,78 @@ pub struct ReferenceSearchResult {
 pub(crate) fn find_all_refs(
     sema: &Semantic<'_>,
     position: FilePosition,
+) -> Option<Vec<ReferenceSearchResult>> {
+    find_refs(sema, position, None)
+}
+
+/// Finds the references of the item at point used in one of the `kinds`
+/// of ways, or all of them. Unlike `find_all_refs`, the references of a
+/// function include its export, import and spec entries.
+pub(crate) fn find_refs_by_kind(
+    sema: &Semantic<'_>,
+    position: FilePosition,
+    kinds: Option<&[ReferenceKind]>,
+) -> Option<Vec<ReferenceSearchResult>> {
+    find_refs(sema, position, Some(kinds.unwrap_or(&[])))
+}
+
+/// With no `kinds`, the references of functions are their direct uses
+/// only. Empty `kinds` keep all the references.
+fn find_refs(
+    sema: &Semantic<'_>,
+    position: FilePosition,
+    kinds: Option<&[ReferenceKind]>,
 ) -> Option<Vec<ReferenceSearchResult>> {
     let _p = tracing::info_span!("find_all_refs").entered();
+    let wanted =
+        |kind: &ReferenceKind| kinds.map_or(true, |kinds| kinds.is_empty() || kinds.contains(kind));
     let search = move |def: SymbolDefinition| {
         let declaration = def.to_nav(sema.db);
-        let usages = match def {
-            SymbolDefinition::Function(_) => def.usages(sema).direct_only().all(),
+        let routes = routes::routes_for_handler(sema, &def);
+        let doc_usages = def.doc_usages(sema);
+        let usages = match (&def, kinds) {
+            (SymbolDefinition::Function(_), None) => def.usages(sema).direct_only().all(),
             _ => def.usages(sema).all(),
         };
 
-        let references = usages
+        let mut references: FxHashMap<FileId, Vec<Reference>> = usages
             .into_iter()
             .map(|(file_id, refs)| {
                 (
                     file_id,
                     refs.into_iter()
-                        .map(|name| name.syntax().text_range())
+                        .map(|name| Reference {
+                            range: name.syntax().text_range(),
+                            kind: reference_kind(name.syntax()),
+                        })
+                        .filter(|reference| wanted(&reference.kind))
                         .collect(),
                 )
             })
             .collect();
+        if wanted(&ReferenceKind::Doc) {
+            for (file_id, ranges) in doc_usages {
+                references
+                    .entry(file_id)
+                    .or_default()
+                    .extend(ranges.into_iter().map(|range| Reference {
+                        range,
+                        kind: ReferenceKind::Doc,
+                    }));
+            }
+        }
+        if wanted(&ReferenceKind::Heuristic) {
+            for route in routes {
+                references
+                    .entry(route.file_id)
+                    .or_default()
+                    .push(Reference {
+                        range: route.range,
+                        kind: ReferenceKind::Heuristic,
+                    });
+            }
+        }
+        references.retain(|_, refs| !refs.is_empty());
 
         ReferenceSearchResult {
             declaration,
@@ -88,10 +172,54 @@ pub(crate) fn find_all_refs(
     }
 }
 
+fn reference_kind(name: &SyntaxNode) -> ReferenceKind {
+    for ancestor in name.ancestors().skip(1) {
+        match ancestor.kind() {
+            SyntaxKind::EXPORT_ATTRIBUTE | SyntaxKind::EXPORT_TYPE_ATTRIBUTE => {
+                return ReferenceKind::Export;
+            }
+            SyntaxKind::IMPORT_ATTRIBUTE => return ReferenceKind::Import,
+            SyntaxKind::SPEC | SyntaxKind::CALLBACK => return ReferenceKind::Spec,
+            SyntaxKind::INTERNAL_FUN | SyntaxKind::EXTERNAL_FUN => {
+                return ReferenceKind::FunCapture;
+            }
+            SyntaxKind::CALL => {
+                let in_callee = ast::Call::cast(ancestor)
+                    .and_then(|call| call.expr())
+                    .map_or(false, |callee| {
+                        callee
+                            .syntax()
+                            .text_range()
+                            .contains_range(name.text_range())
+                    });
+                if in_callee {
+                    return ReferenceKind::Call;
+                }
+            }
+            SyntaxKind::FUN_DECL => break,
+            _ if ast::Form::can_cast(ancestor.kind()) => return ReferenceKind::Other,
+            _ => {}
+        }
+    }
+    let plain_atom = name.kind() == SyntaxKind::ATOM
+        && name.parent().map_or(false, |parent| {
+            !matches!(
+                parent.kind(),
+                SyntaxKind::RECORD_NAME | SyntaxKind::RECORD_FIELD_NAME
+            )
+        });
+    if plain_atom {
+        ReferenceKind::Heuristic
+    } else {
+        ReferenceKind::Other
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use elp_ide_db::elp_base_db::FileRange;
 
+    use super::ReferenceKind;
     use crate::fixture;
     use crate::tests::check_file_ranges;
 
@@ -117,10 +245,11 @@ mod tests {
                 let found_ranges = res
                     .references
                     .into_iter()
-                    .flat_map(|(file_id, ranges)| {
-                        ranges
-                            .into_iter()
-                            .map(move |range| FileRange { file_id, range })
+                    .flat_map(|(file_id, refs)| {
+                        refs.into_iter().map(move |reference| FileRange {
+                            file_id,
+                            range: reference.range,
+                        })
                     })
                     .collect();
                 check_file_ranges(found_ranges, expected)
@@ -145,6 +274,32 @@ mod tests {
         }
     }
 
+    #[track_caller]
+    fn check_kinds(fixture: &str, kinds: Option<Vec<ReferenceKind>>) {
+        let (analysis, pos, _diagnostics_enabled, annos) = fixture::annotations(fixture);
+        let resolved = analysis.find_refs_by_kind(pos, kinds).unwrap().unwrap();
+        let mut actual: Vec<(FileRange, String)> = resolved
+            .into_iter()
+            .flat_map(|res| res.references)
+            .flat_map(|(file_id, refs)| {
+                refs.into_iter().map(move |reference| {
+                    (
+                        FileRange {
+                            file_id,
+                            range: reference.range,
+                        },
+                        format!("{:?}", reference.kind),
+                    )
+                })
+            })
+            .collect();
+        let mut expected = annos;
+        let key = |(range, _): &(FileRange, String)| (range.file_id, range.range.start());
+        actual.sort_by_key(key);
+        expected.sort_by_key(key);
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn test_module_expr() {
         check(
@@ -675,4 +830,121 @@ foo() ->
 "#,
         );
     }
+
+    #[test]
+    fn test_function_reference_kinds() {
+        check_kinds(
+            r#"
+//- /src/main.erl
+-module(main).
+-export([foo/1]).
+%%       ^^^Export
+-spec foo(any()) -> ok.
+%%    ^^^Spec
+foo(_) -> ok.
+
+bar() ->
+    foo(1),
+%%  ^^^Call
+    F = fun foo/1,
+%%          ^^^FunCapture
+    F(main:foo(2)).
+%%         ^^^Call
+
+//- /src/another.erl
+-module(another).
+-import(main, [foo/1]).
+%%             ^^^Import
+baz() -> fo~o(3).
+%%       ^^^Call
+"#,
+            None,
+        );
+    }
+
+    #[test]
+    fn test_function_calls_only() {
+        check_kinds(
+            r#"
+//- /src/main.erl
+-module(main).
+-export([foo/1]).
+-spec foo(any()) -> ok.
+foo(_) -> ok.
+
+bar() ->
+    foo~(1),
+%%  ^^^Call
+    F = fun foo/1,
+    F(2).
+"#,
+            Some(vec![ReferenceKind::Call]),
+        );
+    }
+
+    #[test]
+    fn test_module_reference_kinds() {
+        check_kinds(
+            r#"
+//- /src/another.erl
+-module(another).
+
+//- /src/main.erl
+foo() -> another~.
+%%       ^^^^^^^Heuristic
+
+bar() -> another:baz().
+%%       ^^^^^^^Call
+"#,
+            None,
+        );
+    }
+
+    #[test]
+    fn test_doc_references() {
+        check_kinds(
+            r#"
+//- /src/main.erl
+-module(main).
+-export([foo/1]).
+%%       ^^^Export
+-doc "Calls `foo/1`, not `foo/2`.".
+%%           ^^^Doc
+fo~o(_) -> ok.
+
+//- /src/another.erl
+-module(another).
+%% @doc Wraps {@link main:foo/1}.
+%%                        ^^^Doc
+"#,
+            None,
+        );
+    }
+
+    #[test]
+    fn test_route_references() {
+        check_kinds(
+            r#"
+//- /src/users.erl
+-module(users).
+-export([show/2]).
+%%       ^^^^Export
+sh~ow(Req, State) -> {ok, Req, State}.
+
+//- /src/main.erl
+-module(main).
+start() ->
+    cowboy_router:compile([{'_', [{"/users/:id", users, show}, {"/", home, []}]}]).
+%%                                 ^^^^^^^^^^^^Heuristic
+
+//- /src/cowboy_router.erl
+-module(cowboy_router).
+-export([compile/1]).
+compile(Routes) -> Routes.
+own() -> compile([{"/own", users, show}]).
+%%                 ^^^^^^Heuristic
+"#,
+            None,
+        );
+    }
 }
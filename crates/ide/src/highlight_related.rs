@@ -7,9 +7,11 @@
  * of this source tree.
  */
 
+use elp_ide_db::elp_base_db::FileId;
 use elp_ide_db::elp_base_db::FilePosition;
 use elp_ide_db::find_best_token;
 use elp_ide_db::ReferenceCategory;
+use elp_ide_db::ReferenceClass;
 use elp_ide_db::ReferenceType;
 use elp_ide_db::SearchScope;
 use elp_ide_db::SymbolClass;
@@ -18,7 +20,10 @@ use elp_syntax::ast;
 use elp_syntax::AstNode;
 use elp_syntax::NodeOrToken;
 use elp_syntax::TextRange;
+use hir::DefinitionOrReference;
+use hir::InFile;
 use hir::Semantic;
+use hir::VarDef;
 
 use crate::navigation_target::ToNav;
 
@@ -37,7 +42,18 @@ pub(crate) fn highlight_related(
     position: FilePosition,
 ) -> Option<Vec<HighlightedRange>> {
     let _p = tracing::info_span!("highlight_related").entered();
-    find_local_refs(sema, position)
+    let mut highlights = find_local_refs(sema, position)?;
+    // A binding is also found as a use of the other bindings of the
+    // same variable, keep it as a write.
+    highlights.sort_by_key(|hl| {
+        (
+            hl.range.start(),
+            hl.range.end(),
+            hl.category != Some(ReferenceCategory::Write),
+        )
+    });
+    highlights.dedup_by_key(|hl| hl.range);
+    Some(highlights)
 }
 
 /// This function is based on `references::find_all_refs()` but limits
@@ -84,11 +100,22 @@ fn find_local_refs(sema: &Semantic<'_>, position: FilePosition) -> Option<Vec<Hi
 
     let token = find_best_token(sema, position)?;
     match SymbolClass::classify(sema, token.clone()) {
+        Some(SymbolClass::Definition(SymbolDefinition::Var(var))) => {
+            Some(var_highlights(sema, position.file_id, vec![var]))
+        }
         Some(SymbolClass::Definition(def)) => Some(search(def)),
         Some(SymbolClass::Reference {
             refs: _,
             typ: ReferenceType::Fuzzy,
         }) => None,
+        Some(SymbolClass::Reference {
+            refs: ReferenceClass::Definition(SymbolDefinition::Var(var)),
+            typ: _,
+        }) => Some(var_highlights(sema, position.file_id, vec![var])),
+        Some(SymbolClass::Reference {
+            refs: ReferenceClass::MultiVar(vars),
+            typ: _,
+        }) => Some(var_highlights(sema, position.file_id, vars)),
         Some(SymbolClass::Reference { refs, typ: _ }) => {
             Some(refs.iter().flat_map(search).collect())
         }
@@ -124,6 +151,56 @@ fn find_local_refs(sema: &Semantic<'_>, position: FilePosition) -> Option<Vec<Hi
     }
 }
 
+/// The bindings and uses of a variable, within its function clause. A
+/// variable bound in each branch of a `case` has one binding per branch,
+/// and the bindings sharing a use after the `case` are all highlighted.
+fn var_highlights(sema: &Semantic, file_id: FileId, vars: Vec<VarDef>) -> Vec<HighlightedRange> {
+    let scope = SearchScope::single_file(file_id, None);
+    let mut bindings: Vec<VarDef> = Vec::new();
+    let mut uses = Vec::new();
+    let mut pending = vars;
+    while let Some(var) = pending.pop() {
+        if bindings.contains(&var) {
+            continue;
+        }
+        let usages = SymbolDefinition::Var(var.clone())
+            .usages(sema)
+            .set_scope(&scope)
+            .all();
+        for (usage_file_id, names) in usages {
+            if usage_file_id != file_id {
+                continue;
+            }
+            for name in names {
+                if let Some(used) = ast::Var::cast(name.syntax().clone()) {
+                    if let Some(DefinitionOrReference::Reference(others)) =
+                        sema.to_def(InFile::new(file_id, &used))
+                    {
+                        pending.extend(others);
+                    }
+                }
+                uses.push(HighlightedRange {
+                    range: name.syntax().text_range(),
+                    category: Some(ReferenceCategory::Read),
+                });
+            }
+        }
+        bindings.push(var);
+    }
+    bindings
+        .into_iter()
+        .filter(|var| var.file.file_id == file_id)
+        .map(|var| {
+            let nav = SymbolDefinition::Var(var).to_nav(sema.db);
+            HighlightedRange {
+                range: nav.focus_range.unwrap_or(nav.full_range),
+                category: Some(ReferenceCategory::Write),
+            }
+        })
+        .chain(uses)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1039,6 +1116,60 @@ mod tests {
                 ?a_macro(Args).
             %%           ^^^^read
 
+"#,
+        );
+    }
+
+    #[test]
+    fn local_variables_bound_in_branches() {
+        check(
+            r#"
+          //- /src/main.erl
+            -module(main).
+
+            foo(X) ->
+              case X of
+                a -> Y = 1;
+             %%      ^write
+                _ -> Y = 2
+             %%      ^write
+              end,
+              ~Y.
+           %% ^read
+
+"#,
+        );
+        check(
+            r#"
+          //- /src/main.erl
+            -module(main).
+
+            foo(X) ->
+              case X of
+                a -> ~Y = 1;
+             %%      ^write
+                _ -> Y = 2
+             %%      ^write
+              end,
+              Y.
+           %% ^read
+
+"#,
+        );
+    }
+
+    #[test]
+    fn local_variables_per_clause() {
+        check(
+            r#"
+          //- /src/main.erl
+            -module(main).
+
+            foo(~X) -> X + 1;
+           %%   ^write
+           %%         ^read
+            foo(X) -> X.
+
 "#,
         );
     }
@@ -7,11 +7,115 @@
  * of this source tree.
  */
 
+use elp_ide_db::docs::Doc;
+use elp_ide_db::elp_base_db::path_for_file;
 use elp_ide_db::elp_base_db::FilePosition;
+use elp_ide_db::elp_base_db::FileRange;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_ide_db::eqwalizer::type_references;
+use elp_ide_db::find_best_token;
+use elp_ide_db::EqwalizerDatabase;
 use elp_ide_db::RootDatabase;
+use elp_ide_db::SymbolClass;
+use elp_ide_db::SymbolDefinition;
+use elp_syntax::AstNode;
+use elp_syntax::SmolStr;
+use elp_syntax::TextRange;
+use hir::Semantic;
 
 use crate::doc_links::external_docs;
 use crate::doc_links::DocLink;
+use crate::expand_macro::expand_macro;
+use crate::handlers::get_docs::get_doc_at_position;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HoverConfig {
+    /// Whether to show the type eqWAlizer found for the expression
+    pub types: bool,
+}
+
+#[derive(Debug)]
+pub struct Hover {
+    pub ty: Option<HoverType>,
+    /// The documentation of the symbol and the expansion of the macro at
+    /// the position, if any
+    pub docs: Vec<(Doc, Option<FileRange>)>,
+}
+
+#[derive(Debug)]
+pub struct HoverType {
+    /// The expression or pattern and its type, as an Erlang snippet
+    pub doc: Doc,
+    pub range: FileRange,
+    /// The definitions of the types the type refers to
+    pub references: Vec<(SmolStr, FileRange)>,
+}
+
+pub(crate) fn hover(db: &RootDatabase, position: FilePosition, config: &HoverConfig) -> Hover {
+    let ty = if config.types {
+        hover_type(db, position)
+    } else {
+        None
+    };
+    let mut docs = Vec::new();
+    docs.extend(get_doc_at_position(db, position));
+    docs.extend(record_declaration(db, position));
+    if let Some(expansion) = expand_macro(db, position) {
+        let doc = Doc::new(format!(
+            "{}\n\n```erlang\n{}```\n",
+            expansion.name, expansion.expansion
+        ));
+        docs.push((doc, None));
+    }
+    Hover { ty, docs }
+}
+
+/// The declaration of the record referred to at the position, with the
+/// header declaring it, if any.
+fn record_declaration(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<(Doc, Option<FileRange>)> {
+    let sema = Semantic::new(db);
+    let token = find_best_token(&sema, position)?;
+    let record = match SymbolClass::classify(&sema, token.clone())? {
+        SymbolClass::Reference { refs, .. } => refs.iter().find_map(|def| match def {
+            SymbolDefinition::Record(record) => Some(record),
+            SymbolDefinition::RecordField(field) => Some(field.record),
+            _ => None,
+        })?,
+        SymbolClass::Definition(_) => return None,
+    };
+    let mut text = format!("```erlang\n{}\n```\n", record.source(db).syntax().text());
+    if record.file.file_id != position.file_id {
+        let path = path_for_file(db, record.file.file_id)?;
+        if let Some((name, Some(extension))) = path.name_and_extension() {
+            text.push_str(&format!("\nDeclared in `{name}.{extension}`\n"));
+        }
+    }
+    let range = FileRange {
+        file_id: token.file_id,
+        range: token.value.text_range(),
+    };
+    Some((Doc::new(text), Some(range)))
+}
+
+/// The type of the smallest expression or pattern around the position,
+/// in a module checked by eqWAlizer.
+fn hover_type(db: &RootDatabase, position: FilePosition) -> Option<HoverType> {
+    let query = FileRange {
+        file_id: position.file_id,
+        range: TextRange::empty(position.offset),
+    };
+    let type_info = db.type_at_position(query)?;
+    let (ty, range) = &*type_info;
+    let text = &db.file_text(range.file_id)[range.range];
+    Some(HoverType {
+        doc: Doc::new(format!("```erlang\n{} :: {}\n```\n", text, ty)),
+        range: *range,
+        references: type_references(db, range.file_id, ty),
+    })
+}
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct HoverActionsConfig {
@@ -44,11 +148,13 @@ fn doc_links_actions(res: &mut Vec<HoverAction>, db: &RootDatabase, position: Fi
 
 #[cfg(test)]
 mod tests {
+    use expect_test::expect;
 
     use crate::doc_links::DocLink;
     use crate::fixture;
     use crate::HoverAction;
     use crate::HoverActionsConfig;
+    use crate::HoverConfig;
 
     #[track_caller]
     fn check(fixture: &str, expected: Vec<HoverAction>) {
@@ -82,4 +188,82 @@ main() ->
             })],
         );
     }
+
+    #[test]
+    fn hover_docs_and_macro_expansion() {
+        let (analysis, position, _) = fixture::position(
+            r#"
+//- /src/main.erl
+-module(main).
+-define(TWICE(X), X + X).
+main() -> ?TW~ICE(2).
+"#,
+        );
+        let hover = analysis
+            .hover(position, &HoverConfig { types: true })
+            .unwrap();
+        // The module is not checked by eqWAlizer
+        assert!(hover.ty.is_none());
+        assert_eq!(hover.docs.len(), 1);
+        let doc = hover.docs[0].0.markdown_text();
+        assert!(doc.starts_with("TWICE/1"), "{doc}");
+        assert!(doc.contains("(2 + 2)"), "{doc}");
+    }
+
+    #[test]
+    fn hover_record_from_header() {
+        let (analysis, position, _) = fixture::position(
+            r#"
+//- /include/user.hrl
+-record(user, {name :: binary(), age = 0 :: non_neg_integer()}).
+
+//- /src/main.erl
+-module(main).
+-include("user.hrl").
+name(U) -> U#user.na~me.
+"#,
+        );
+        let hover = analysis
+            .hover(position, &HoverConfig { types: false })
+            .unwrap();
+        let docs: Vec<String> = hover
+            .docs
+            .iter()
+            .map(|(doc, _)| doc.markdown_text().to_string())
+            .collect();
+        expect![[r#"
+            ```erlang
+            -record(user, {name :: binary(), age = 0 :: non_neg_integer()}).
+            ```
+
+            Declared in `user.hrl`
+        "#]]
+        .assert_eq(&docs.join("---\n"));
+    }
+
+    #[test]
+    fn hover_local_record() {
+        let (analysis, position, _) = fixture::position(
+            r#"
+//- /src/main.erl
+-module(main).
+-record(point, {x = 0, y = 0}).
+origin() -> #po~int{}.
+"#,
+        );
+        let hover = analysis
+            .hover(position, &HoverConfig { types: false })
+            .unwrap();
+        let docs: Vec<String> = hover
+            .docs
+            .iter()
+            .map(|(doc, _)| doc.markdown_text().to_string())
+            .collect();
+        expect![[r#"
+            ```erlang
+            -record(point, {x = 0, y = 0}).
+            ```
+        "#]]
+        .assert_eq(&docs.join("---\n"));
+    }
 }
@@ -0,0 +1,139 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Describes where the includes of a file are looked for, as used by
+//! `elp/viewIncludePath`, to find out why an include is not found.
+
+use std::fmt::Write;
+
+use elp_ide_db::elp_base_db::path_for_file;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_ide_db::RootDatabase;
+use hir::db::DefDatabase;
+use hir::InFile;
+use hir::IncludeAttribute;
+use itertools::Itertools;
+
+/// The directories searched for `-include` and `-include_lib`, in the
+/// order they are searched, followed by what each include of the file
+/// resolves to.
+pub(crate) fn include_path(db: &RootDatabase, file_id: FileId) -> String {
+    let Some(app_data) = db.file_app_data(file_id) else {
+        return "The file is not part of an application, its includes are not resolved.\n"
+            .to_string();
+    };
+    let mut out = String::new();
+    let _ = writeln!(out, "-include(\"File\") looks for File in, in order:");
+    let file_dir = path_for_file(db, file_id).and_then(|path| {
+        path.as_path()
+            .and_then(|path| path.parent())
+            .map(|dir| dir.to_path_buf())
+    });
+    let mut dirs = Vec::new();
+    if let Some(dir) = file_dir {
+        dirs.push(format!("{} (directory of the file)", dir.as_str()));
+    }
+    dirs.extend(
+        app_data
+            .include_path
+            .iter()
+            .map(|dir| format!("{} (include path of {})", dir.as_str(), app_data.name)),
+    );
+    for (n, dir) in dirs.iter().enumerate() {
+        let _ = writeln!(out, "  {}. {dir}", n + 1);
+    }
+
+    let _ = writeln!(
+        out,
+        "\n-include_lib(\"App/File\") looks in the same directories for App/File, \
+         then for File in the directory of App:"
+    );
+    let project_data = db.project_data(app_data.project_id);
+    let otp_roots = project_data
+        .otp_project_id
+        .map(|otp| db.project_data(otp).source_roots.clone())
+        .unwrap_or_default();
+    let apps = project_data
+        .source_roots
+        .iter()
+        .chain(otp_roots.iter())
+        .filter_map(|&root| db.app_data(root))
+        .map(|app| (app.name.to_string(), app.dir.as_str().to_string()))
+        .sorted()
+        .dedup_by(|(a, _), (b, _)| a == b)
+        .collect_vec();
+    let width = apps.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for (name, dir) in apps {
+        let _ = writeln!(out, "  {name:width$}  {dir}");
+    }
+
+    let form_list = db.file_form_list(file_id);
+    let mut includes = form_list.includes().peekable();
+    if includes.peek().is_some() {
+        let _ = writeln!(out, "\nIncludes of the file:");
+    }
+    for (idx, include) in includes {
+        let attribute = match include {
+            IncludeAttribute::Include { path, .. } => format!("-include(\"{path}\")"),
+            IncludeAttribute::IncludeLib { path, .. } => format!("-include_lib(\"{path}\")"),
+        };
+        let target = db
+            .resolve_include(InFile::new(file_id, idx))
+            .and_then(|target| path_for_file(db, target))
+            .map_or("not found".to_string(), |path| path.to_string());
+        let _ = writeln!(out, "  {attribute}: {target}");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_ide_db::elp_base_db::fixture::WithFixture;
+    use elp_ide_db::RootDatabase;
+    use expect_test::expect;
+
+    use crate::AnalysisHost;
+
+    #[test]
+    fn search_path_and_includes() {
+        let (db, file_id, _) = RootDatabase::with_range_or_offset(
+            r#"
+//- /app_a/src/main.erl app:app_a include_path:include
+-module(main).~
+-include("records.hrl").
+-include_lib("app_b/include/util.hrl").
+-include_lib("app_b/include/missing.hrl").
+//- /app_a/include/records.hrl app:app_a
+-record(r, {}).
+//- /app_b/include/util.hrl app:app_b
+-define(UTIL, 1).
+"#,
+        );
+        let analysis = AnalysisHost { db }.analysis();
+        expect![[r#"
+            -include("File") looks for File in, in order:
+              1. /app_a/src (directory of the file)
+              2. / (include path of app_a)
+              3. /app_a/include (include path of app_a)
+              4. /app_a/src (include path of app_a)
+              5. / (include path of app_a)
+
+            -include_lib("App/File") looks in the same directories for App/File, then for File in the directory of App:
+              app_a  /app_a
+              app_b  /app_b
+
+            Includes of the file:
+              -include("records.hrl"): /app_a/include/records.hrl
+              -include_lib("app_b/include/util.hrl"): /app_b/include/util.hrl
+              -include_lib("app_b/include/missing.hrl"): not found
+        "#]]
+        .assert_eq(&analysis.include_path(file_id).unwrap());
+    }
+}
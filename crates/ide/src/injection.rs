@@ -0,0 +1,367 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! String literals holding code in another language, such as SQL
+//! queries or JSON documents.
+//!
+//! A string literal is injected when it is an argument of a function
+//! configured in the `injections` of `.elp_lint.toml`, or when it is in
+//! the expression following a `%% language=<name>` comment.
+
+use std::fmt;
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::NodeOrToken;
+use elp_syntax::SyntaxElement;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use elp_syntax::SyntaxToken;
+use elp_syntax::TextRange;
+use hir::fold::MacroStrategy;
+use hir::fold::ParenStrategy;
+use hir::AnyExpr;
+use hir::Body;
+use hir::Expr;
+use hir::ExprId;
+use hir::Literal;
+use hir::Semantic;
+use hir::Strategy;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::codemod_helpers::FunctionMatcher;
+use crate::FunctionMatch;
+use crate::MFA;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InjectedLanguage {
+    Sql,
+    Json,
+}
+
+impl InjectedLanguage {
+    fn from_tag(tag: &str) -> Option<InjectedLanguage> {
+        match tag.to_lowercase().as_str() {
+            "sql" => Some(InjectedLanguage::Sql),
+            "json" => Some(InjectedLanguage::Json),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for InjectedLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InjectedLanguage::Sql => write!(f, "sql"),
+            InjectedLanguage::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// A function whose string literal arguments are in `language`, e.g.
+///
+/// ```toml
+/// [[injections]]
+/// mfa = "epgsql:squery/2"
+/// language = "sql"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Injection {
+    pub mfa: MFA,
+    pub language: InjectedLanguage,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InjectedString {
+    /// Range of the whole literal, including its quotes
+    pub range: TextRange,
+    pub language: InjectedLanguage,
+    /// The string the literal evaluates to
+    pub contents: String,
+}
+
+pub(crate) fn injected_strings(
+    sema: &Semantic,
+    file_id: FileId,
+    injections: &[Injection],
+) -> Vec<InjectedString> {
+    let source = sema.parse(file_id).value;
+    let mut res = Vec::new();
+    tagged_strings(source.syntax(), &mut res);
+    if !injections.is_empty() {
+        call_arguments(sema, file_id, source.syntax(), injections, &mut res);
+    }
+    res.sort_by_key(|injected| injected.range.start());
+    res.dedup_by_key(|injected| injected.range);
+    res
+}
+
+/// Strings in the expression following a `%% language=<name>` comment.
+fn tagged_strings(source: &SyntaxNode, res: &mut Vec<InjectedString>) {
+    lazy_static! {
+        static ref TAG: Regex = Regex::new(r"^%+\s*language\s*=\s*(\w+)\s*$").unwrap();
+    }
+    for comment in source
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| token.kind() == SyntaxKind::COMMENT)
+    {
+        let language = match TAG
+            .captures(comment.text())
+            .and_then(|captures| InjectedLanguage::from_tag(&captures[1]))
+        {
+            Some(language) => language,
+            None => continue,
+        };
+        if let Some(expr) = next_expr(&comment) {
+            res.extend(
+                expr.descendants()
+                    .filter(is_string_literal)
+                    .filter_map(|node| injected_string(&node, language)),
+            );
+        }
+    }
+}
+
+/// The expression following `comment` within the same form. A comment
+/// is not always a sibling of the expression, e.g. when it comes before
+/// the first expression of a clause body.
+fn next_expr(comment: &SyntaxToken) -> Option<SyntaxNode> {
+    let mut element = SyntaxElement::from(comment.clone());
+    loop {
+        let parent = element.parent()?;
+        if parent.kind() == SyntaxKind::SOURCE_FILE {
+            return None;
+        }
+        let next = std::iter::successors(element.next_sibling_or_token(), |element| {
+            element.next_sibling_or_token()
+        })
+        .find_map(NodeOrToken::into_node);
+        if let Some(node) = next {
+            return node
+                .descendants()
+                .find(|node| ast::Expr::can_cast(node.kind()));
+        }
+        element = parent.into();
+    }
+}
+
+/// String literal arguments of calls to the configured functions.
+fn call_arguments(
+    sema: &Semantic,
+    file_id: FileId,
+    source: &SyntaxNode,
+    injections: &[Injection],
+    res: &mut Vec<InjectedString>,
+) {
+    let matches: Vec<(FunctionMatch, InjectedLanguage)> = injections
+        .iter()
+        .map(|injection| {
+            (
+                FunctionMatch::MFA {
+                    mfa: injection.mfa.clone(),
+                },
+                injection.language,
+            )
+        })
+        .collect();
+    let matches: Vec<(&FunctionMatch, InjectedLanguage)> = matches
+        .iter()
+        .map(|(function, language)| (function, *language))
+        .collect();
+    let matcher = FunctionMatcher::new(&matches);
+    let def_map = sema.def_map(file_id);
+    for (_, def) in def_map.get_functions() {
+        if def.file.file_id != file_id {
+            continue;
+        }
+        let def_fb = def.in_function_body(sema, def);
+        def_fb.clone().fold_function(
+            Strategy {
+                macros: MacroStrategy::Expand,
+                parens: ParenStrategy::InvisibleParens,
+            },
+            (),
+            &mut |acc, clause_id, ctx| {
+                if ctx.in_macro.is_some() {
+                    return acc;
+                }
+                if let AnyExpr::Expr(Expr::Call { target, args }) = ctx.item {
+                    let body = def_fb.body(clause_id);
+                    if let Some((_, language)) =
+                        matcher.get_match(&target, args.len() as u32, Some(&args), sema, &body)
+                    {
+                        for expr_id in args.iter().flat_map(|arg| string_exprs(&body, *arg)) {
+                            if let Some(node) = def_fb
+                                .range_for_expr(clause_id, expr_id)
+                                .and_then(|range| literal_at(source, range))
+                            {
+                                res.extend(injected_string(&node, *language));
+                            }
+                        }
+                    }
+                }
+                acc
+            },
+        );
+    }
+}
+
+/// The string literals making up an argument: the argument itself, or
+/// the segments of a binary built from strings, like `<<"SELECT 1">>`.
+fn string_exprs(body: &Body, expr_id: ExprId) -> Vec<ExprId> {
+    match &body[expr_id] {
+        Expr::Literal(Literal::String(_)) => vec![expr_id],
+        Expr::Binary { segs } => segs
+            .iter()
+            .filter(|seg| matches!(body[seg.elem], Expr::Literal(Literal::String(_))))
+            .map(|seg| seg.elem)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn is_string_literal(node: &SyntaxNode) -> bool {
+    match node.kind() {
+        SyntaxKind::MULTI_STRING => true,
+        SyntaxKind::STRING => {
+            node.parent().map(|parent| parent.kind()) != Some(SyntaxKind::MULTI_STRING)
+        }
+        _ => false,
+    }
+}
+
+fn literal_at(source: &SyntaxNode, range: TextRange) -> Option<SyntaxNode> {
+    match source.covering_element(range) {
+        NodeOrToken::Node(node) if node.text_range() == range => node
+            .ancestors()
+            .take_while(|node| node.text_range() == range)
+            .find(is_string_literal),
+        _ => None,
+    }
+}
+
+fn injected_string(node: &SyntaxNode, language: InjectedLanguage) -> Option<InjectedString> {
+    let contents = match node.kind() {
+        SyntaxKind::STRING => String::from(ast::String::cast(node.clone())?),
+        _ => node
+            .children()
+            .filter_map(ast::String::cast)
+            .map(String::from)
+            .collect(),
+    };
+    Some(InjectedString {
+        range: node.text_range(),
+        language,
+        contents,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use expect_test::Expect;
+    use hir::Semantic;
+
+    use super::injected_strings;
+    use crate::fixture;
+    use crate::InjectedLanguage;
+    use crate::Injection;
+    use crate::MFA;
+
+    #[track_caller]
+    fn check(injections: &[Injection], fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::single_file(fixture);
+        let actual = analysis
+            .with_db(|db| {
+                let sema = Semantic::new(db);
+                let text = sema.db.file_text(file_id);
+                injected_strings(&sema, file_id, injections)
+                    .iter()
+                    .map(|injected| {
+                        format!(
+                            "{} {} -> {:?}\n",
+                            injected.language, &text[injected.range], injected.contents
+                        )
+                    })
+                    .collect::<String>()
+            })
+            .unwrap();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn tagged_strings() {
+        check(
+            &[],
+            r#"
+-module(main).
+-export([f/0]).
+f() ->
+    %% language=sql
+    Query =
+        "SELECT 1",
+    Other = "not injected",
+    %% language=json
+    Doc = <<"{}">>,
+    %% language=cobol
+    Unknown = "MOVE A TO B",
+    {Query, Other, Doc, Unknown}.
+"#,
+            expect![[r#"
+                sql "SELECT 1" -> "SELECT 1"
+                json "{}" -> "{}"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn untagged_strings() {
+        check(
+            &[],
+            r#"
+-module(main).
+-export([f/0]).
+%% language=sql
+f() ->
+    %% A query
+    Query = "SELECT 1",
+    Query.
+"#,
+            expect![[""]],
+        );
+    }
+
+    #[test]
+    fn configured_call_arguments() {
+        check(
+            &[Injection {
+                mfa: MFA::new("epgsql", "squery", 2),
+                language: InjectedLanguage::Sql,
+            }],
+            r#"
+-module(main).
+-export([f/1]).
+f(C) ->
+    epgsql:squery(C, "SELECT" " 1"),
+    epgsql:squery(C, <<"SELECT 2">>),
+    other:squery(C, "SELECT 3").
+"#,
+            expect![[r#"
+                sql "SELECT" " 1" -> "SELECT 1"
+                sql "SELECT 2" -> "SELECT 2"
+            "#]],
+        );
+    }
+}
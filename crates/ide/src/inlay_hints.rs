@@ -8,25 +8,31 @@
  */
 
 use std::fmt::{self};
+use std::sync::Arc;
 
+use elp_eqwalizer::ast::Pos;
 use elp_ide_db::elp_base_db::FileId;
 use elp_ide_db::elp_base_db::FileRange;
 use elp_ide_db::RootDatabase;
 use elp_syntax::TextRange;
+use elp_types_db::eqwalizer::types::Type;
 use hir::Semantic;
 use itertools::Itertools;
 use smallvec::smallvec;
 use smallvec::SmallVec;
+mod eqwalizer_type;
 mod param_name;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct InlayHintsConfig {
     pub parameter_hints: bool,
+    pub type_hints: bool,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum InlayKind {
     Parameter,
+    Type,
 }
 
 #[derive(Debug)]
@@ -190,11 +196,13 @@ impl fmt::Debug for InlayHintLabelPart {
 // Available hints are:
 //
 // * names of function arguments
+// * types found by eqWAlizer for bound variables and returned values
 pub(crate) fn inlay_hints(
     db: &RootDatabase,
     file_id: FileId,
     range_limit: Option<TextRange>,
     config: &InlayHintsConfig,
+    types: Option<Arc<Vec<(Pos, Type)>>>,
 ) -> Vec<InlayHint> {
     let _p = tracing::info_span!("inlay_hints").entered();
     let sema = Semantic::new(db);
@@ -202,6 +210,7 @@ pub(crate) fn inlay_hints(
     let mut acc = Vec::new();
 
     param_name::hints(&mut acc, &sema, config, file_id, range_limit);
+    eqwalizer_type::hints(&mut acc, &sema, config, file_id, range_limit, types);
 
     acc
 }
@@ -216,13 +225,21 @@ mod tests {
 
     pub(super) const DISABLED_CONFIG: InlayHintsConfig = InlayHintsConfig {
         parameter_hints: false,
+        type_hints: false,
     };
 
     #[track_caller]
     pub(super) fn check_with_config(config: InlayHintsConfig, fixture: &str) {
         let (analysis, pos, _) = fixture::position(fixture);
         let mut expected = extract_annotations(&analysis.file_text(pos.file_id).unwrap());
-        let inlay_hints = analysis.inlay_hints(&config, pos.file_id, None).unwrap();
+        let types = if config.type_hints {
+            analysis.types_for_file(pos.file_id).unwrap()
+        } else {
+            None
+        };
+        let inlay_hints = analysis
+            .inlay_hints(&config, pos.file_id, None, types)
+            .unwrap();
         let actual = inlay_hints
             .into_iter()
             .map(|it| (it.range, it.label.to_string()))
@@ -0,0 +1,142 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::sync::Arc;
+
+use elp_eqwalizer::ast::Pos;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::eqwalizer::type_of_range;
+use elp_syntax::TextRange;
+use elp_types_db::eqwalizer::types::Type;
+use hir::fold::MacroStrategy;
+use hir::fold::ParenStrategy;
+use hir::AnyExpr;
+use hir::Expr;
+use hir::InFile;
+use hir::Pat;
+use hir::Semantic;
+use hir::Strategy;
+
+use crate::InlayHint;
+use crate::InlayHintLabel;
+use crate::InlayHintsConfig;
+use crate::InlayKind;
+
+/// The types found by eqWAlizer for the variables bound by `Var = Expr`,
+/// and for the value returned by each function clause.
+pub(super) fn hints(
+    res: &mut Vec<InlayHint>,
+    sema: &Semantic,
+    config: &InlayHintsConfig,
+    file_id: FileId,
+    range_limit: Option<TextRange>,
+    types: Option<Arc<Vec<(Pos, Type)>>>,
+) -> Option<()> {
+    if !config.type_hints {
+        return None;
+    }
+    let types = types?;
+    let in_range = |range: TextRange| range_limit.map_or(true, |limit| limit.contains_range(range));
+    let mut push_hint = |range: Option<TextRange>| {
+        if let Some(range) = range.filter(|range| in_range(*range)) {
+            if let Some(ty) = type_of_range(&types, range) {
+                res.push(InlayHint {
+                    range,
+                    kind: InlayKind::Type,
+                    label: InlayHintLabel::simple(ty.to_string(), None, None),
+                });
+            }
+        }
+    };
+    let def_map = sema.def_map(file_id);
+    for (_, def) in def_map.get_functions() {
+        if def.file.file_id != file_id {
+            continue;
+        }
+        let function_id = InFile::new(file_id, def.function_id);
+        let function_body = sema.to_function_body(function_id);
+        let bindings = function_body.fold_function(
+            Strategy {
+                macros: MacroStrategy::ExpandButIncludeMacroCall,
+                parens: ParenStrategy::InvisibleParens,
+            },
+            Vec::new(),
+            &mut |mut acc, clause_id, ctx| {
+                if let AnyExpr::Expr(Expr::Match { lhs, .. }) = ctx.item {
+                    let body = function_body.body(clause_id);
+                    if ctx.in_macro.is_none() && matches!(body[lhs], Pat::Var(_)) {
+                        acc.push((clause_id, lhs));
+                    }
+                }
+                acc
+            },
+        );
+        for (clause_id, pat_id) in bindings {
+            push_hint(function_body.in_clause(clause_id).range_for_pat(pat_id));
+        }
+        for (clause_id, clause) in function_body.clauses() {
+            if let Some(last) = clause.clause.exprs.last() {
+                push_hint(function_body.range_for_expr(clause_id, *last));
+            }
+        }
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_project_model::otp::otp_supported_by_eqwalizer;
+
+    use crate::inlay_hints::tests::check_with_config;
+    use crate::inlay_hints::tests::DISABLED_CONFIG;
+    use crate::inlay_hints::InlayHintsConfig;
+
+    #[track_caller]
+    fn check_types(fixture: &str) {
+        check_with_config(
+            InlayHintsConfig {
+                type_hints: true,
+                ..DISABLED_CONFIG
+            },
+            fixture,
+        );
+    }
+
+    #[test]
+    fn type_hints_without_eqwalizer() {
+        check_types(
+            r#"
+-module(main).~
+-spec f() -> ok.
+f() ->
+  X = ok,
+  X.
+"#,
+        );
+    }
+
+    #[test]
+    fn type_hints_bindings_and_returns() {
+        if otp_supported_by_eqwalizer() {
+            check_types(
+                r#"
+//- eqwalizer
+//- /app_a/src/main.erl
+-module(main).~
+-spec f(atom()) -> atom().
+f(Status) ->
+  Result = Status,
+%%^^^^^^atom()
+  Result.
+%%^^^^^^atom()
+"#,
+            );
+        }
+    }
+}
@@ -53,7 +53,7 @@ pub(super) fn hints(
                             let body = &function_body.body(clause_id);
                             if let Some(call_def) = target.resolve_call(arity, sema, file_id, body)
                             {
-                                let param_names = &call_def.function_clauses[0].param_names;
+                                let param_names = call_def.param_names(sema.db.upcast());
                                 for (param_name, arg) in param_names.iter().zip(args) {
                                     if should_hint(sema.db.upcast(), param_name, &body[arg]) {
                                         if let Some(arg_range) =
@@ -320,6 +320,75 @@ main() ->
   do(A, B, C).
 %%   ^ X
 %%      ^ Y
+"#,
+        );
+    }
+
+    #[test]
+    fn param_hints_spec_names() {
+        check_params(
+            r#"
+-module(main).~
+-compile(export_all).
+-spec sum(Left :: integer(), Right) -> integer() when Right :: integer().
+sum(A, B) -> A + B.
+main() ->
+  sum(1, 2).
+%%    ^Left
+%%       ^Right
+"#,
+        );
+    }
+
+    #[test]
+    fn param_hints_spec_unnamed_falls_back_to_clauses() {
+        check_params(
+            r#"
+-module(main).~
+-compile(export_all).
+-spec sum(Left :: integer(), integer()) -> integer().
+sum(A, B) -> A + B.
+main() ->
+  sum(1, 2).
+%%    ^Left
+%%       ^B
+"#,
+        );
+    }
+
+    #[test]
+    fn param_hints_names_from_later_clauses() {
+        check_params(
+            r#"
+-module(main).~
+-compile(export_all).
+keyfind(_, _, []) -> false;
+keyfind(Key, N, [H | T]) -> {Key, N, H, T}.
+main() ->
+  keyfind(k, 1, []).
+%%        ^Key
+%%           ^N
+"#,
+        );
+    }
+
+    #[test]
+    fn param_hints_remote_spec_names() {
+        check_params(
+            r#"
+//- /src/main.erl
+-module(main).~
+main() ->
+  lists:keyfind(k, 1, []).
+%%              ^Key
+%%                 ^N
+%%                    ^^TupleList
+//- /src/lists.erl
+-module(lists).
+-export([keyfind/3]).
+-spec keyfind(Key, N, TupleList) -> Tuple | false when
+      Key :: term(), N :: pos_integer(), TupleList :: [Tuple], Tuple :: tuple().
+keyfind(_, _, _) -> erlang:nif_error(undef).
 "#,
         );
     }
@@ -29,6 +29,7 @@ use elp_ide_db::assists::AssistContextDiagnostic;
 use elp_ide_db::assists::AssistUserInput;
 use elp_ide_db::common_test::CommonTestInfo;
 use elp_ide_db::docs::Doc;
+use elp_ide_db::edoc_migration::migrate_edoc;
 use elp_ide_db::elp_base_db::salsa;
 use elp_ide_db::elp_base_db::salsa::ParallelDatabase;
 use elp_ide_db::elp_base_db::Change;
@@ -46,6 +47,7 @@ use elp_ide_db::eqwalizer::type_references;
 use elp_ide_db::erlang_service::ParseResult;
 use elp_ide_db::rename::RenameError;
 use elp_ide_db::source_change::SourceChange;
+use elp_ide_db::symbol_index;
 use elp_ide_db::Eqwalizer;
 use elp_ide_db::EqwalizerDatabase;
 use elp_ide_db::EqwalizerDiagnostics;
@@ -53,9 +55,11 @@ use elp_ide_db::ErlAstDatabase;
 use elp_ide_db::Includes;
 use elp_ide_db::LineIndex;
 use elp_ide_db::LineIndexDatabase;
+use elp_ide_db::MemoryUsage;
 use elp_ide_db::RootDatabase;
 use elp_project_model::AppName;
 use elp_project_model::AppType;
+use elp_project_model::EqwalizerLevel;
 use elp_syntax::algo::ancestors_at_offset;
 use elp_syntax::ast;
 use elp_syntax::label::Label;
@@ -66,36 +70,54 @@ use elp_types_db::eqwalizer::types::Type;
 use elp_types_db::IncludeGenerated;
 use erlang_service::CompileOption;
 use expand_macro::ExpandedMacro;
+use fxhash::FxHashMap;
 use handlers::get_docs;
 use handlers::goto_definition;
 use handlers::goto_type_definition;
 use handlers::references;
 use hir::db::DefDatabase;
+use hir::AsName;
 use hir::DefMap;
-use hir::File;
 use hir::FormList;
-use hir::Module;
+use hir::InFile;
+use hir::NameArity;
 use hir::Semantic;
-use navigation_target::ToNav;
 use rayon::prelude::*;
+use text_edit::TextEdit;
 
 mod annotations;
+mod atoms;
 mod call_hierarchy;
 mod codemod_helpers;
 mod common_test;
 mod doc_links;
+mod document_links;
 mod document_symbols;
 mod expand_macro;
 mod extend_selection;
+mod file_stats;
+mod fix_all;
 mod folding_ranges;
 mod handlers;
 mod hover;
+mod include_path;
+mod injection;
 mod inlay_hints;
+mod linked_editing;
+mod message_protocol;
 mod navigation_target;
 mod rename;
+mod rename_atom;
+mod render_html;
+mod routes;
 mod runnables;
 mod signature_help;
+mod statem_graph;
 mod syntax_highlighting;
+mod syntax_tree;
+mod type_hierarchy;
+mod typing;
+mod view_hir;
 
 #[cfg(test)]
 mod fixture;
@@ -106,37 +128,59 @@ pub mod diagnostics;
 pub mod diagnostics_collection;
 pub mod diff;
 mod highlight_related;
+pub mod testing;
 // @fb-only
 
 pub use annotations::Annotation;
 pub use annotations::AnnotationKind;
+pub use atoms::atom_clusters;
+pub use atoms::AtomCluster;
+pub use call_hierarchy::ModuleDependency;
 pub use codemod_helpers::FunctionMatch;
 pub use codemod_helpers::MFA;
 pub use common_test::GroupName;
 pub use doc_links::DocLink;
+pub use document_links::DocumentLink;
 pub use document_symbols::DocumentSymbol;
 pub use elp_ide_assists;
 pub use elp_ide_completion;
 pub use elp_ide_db;
 pub use elp_ide_db::erlang_service;
+pub use elp_ide_db::symbol_index::FileSymbol;
 pub use elp_syntax::TextRange;
 pub use elp_syntax::TextSize;
+pub use file_stats::FileStats;
 pub use folding_ranges::FoldingRange;
 pub use folding_ranges::FoldingRangeKind;
+pub use handlers::references::Reference;
+pub use handlers::references::ReferenceKind;
 pub use handlers::references::ReferenceSearchResult;
 pub use highlight_related::HighlightedRange;
+pub use hover::Hover;
 pub use hover::HoverAction;
 pub use hover::HoverActionsConfig;
+pub use hover::HoverConfig;
+pub use hover::HoverType;
+pub use injection::InjectedLanguage;
+pub use injection::Injection;
 pub use inlay_hints::InlayHint;
 pub use inlay_hints::InlayHintLabel;
 pub use inlay_hints::InlayHintLabelPart;
 pub use inlay_hints::InlayHintsConfig;
 pub use inlay_hints::InlayKind;
 pub use inlay_hints::InlayTooltip;
+pub use message_protocol::Message;
+pub use message_protocol::MessageKind;
+pub use message_protocol::MessageProtocol;
 pub use navigation_target::NavigationTarget;
+pub use rename_atom::AtomRename;
+pub use rename_atom::AtomUsage;
+pub use rename_atom::AtomUsageKind;
 pub use runnables::Runnable;
 pub use runnables::RunnableKind;
 pub use signature_help::SignatureHelp;
+pub use statem_graph::StatemGraph;
+pub use statem_graph::Transition;
 pub use syntax_highlighting::tags::Highlight;
 pub use syntax_highlighting::tags::HlMod;
 pub use syntax_highlighting::tags::HlMods;
@@ -229,6 +273,11 @@ impl Analysis {
         })
     }
 
+    /// Computes only the parse-level diagnostics for the given file.
+    pub fn syntax_only_diagnostics(&self, file_id: FileId) -> Cancellable<LabeledDiagnostics> {
+        self.with_db(|db| diagnostics::syntax_only_diagnostics(db, file_id))
+    }
+
     pub fn should_eqwalize(
         &self,
         file_id: FileId,
@@ -368,6 +417,11 @@ impl Analysis {
         self.with_db(|db| db.is_eqwalizer_enabled(file_id, include_generated))
     }
 
+    /// The level the application of the file is type checked at
+    pub fn eqwalizer_level(&self, file_id: FileId) -> Cancellable<EqwalizerLevel> {
+        self.with_db(|db| db.eqwalizer_level(file_id))
+    }
+
     /// ETF for the module's abstract forms
     pub fn module_ast(
         &self,
@@ -417,6 +471,7 @@ impl Analysis {
         self.with_db(|db| db.module_index(project_id).file_for_module(module))
     }
 
+    /// Expands the macro call at the position, both one level and fully.
     pub fn expand_macro(&self, position: FilePosition) -> Cancellable<Option<ExpandedMacro>> {
         self.with_db(|db| expand_macro::expand_macro(db, position))
     }
@@ -426,12 +481,49 @@ impl Analysis {
         self.with_db(|db| extend_selection::extend_selection(db, frange))
     }
 
+    /// Renders the file as a static HTML page linking its references to
+    /// the pages of the files defining them.
+    pub fn render_html(
+        &self,
+        file_id: FileId,
+        pages: &FxHashMap<FileId, String>,
+        root: &str,
+    ) -> Cancellable<String> {
+        self.with_db(|db| render_html::render_html(db, file_id, pages, root))
+    }
+
+    /// The states and transitions of the file, if it implements
+    /// `gen_statem`.
+    pub fn statem_graph(&self, file_id: FileId) -> Cancellable<Option<StatemGraph>> {
+        self.with_db(|db| statem_graph::statem_graph(db, file_id))
+    }
+
+    /// Re-indents the code around the position once `char_typed` was typed
+    /// there.
+    pub fn on_char_typed(
+        &self,
+        position: FilePosition,
+        char_typed: char,
+    ) -> Cancellable<Option<TextEdit>> {
+        self.with_db(|db| typing::on_char_typed(db, position, char_typed))
+    }
+
     /// Returns a list of symbols in the file. Useful to draw a
     /// file outline.
     pub fn document_symbols(&self, file_id: FileId) -> Cancellable<Vec<DocumentSymbol>> {
         self.with_db(|db| document_symbols::document_symbols(db, file_id))
     }
 
+    /// Returns the atoms used as data in a file, with their ranges
+    pub fn file_atoms(&self, file_id: FileId) -> Cancellable<Vec<(SmolStr, TextRange)>> {
+        self.with_db(|db| atoms::file_atoms(db, file_id))
+    }
+
+    /// Counts the functions, specs, records and macros defined in a file
+    pub fn file_stats(&self, file_id: FileId) -> Cancellable<FileStats> {
+        self.with_db(|db| file_stats::file_stats(db, file_id))
+    }
+
     /// Returns the contents of a file
     pub fn file_text(&self, file_id: FileId) -> Cancellable<Arc<str>> {
         self.with_db(|db| db.file_text(file_id))
@@ -483,9 +575,15 @@ impl Analysis {
                 .any(|&it| it == AssistKind::None || it == AssistKind::QuickFix),
             None => true,
         };
+        let include_fix_all = match &assist_config.allowed {
+            Some(it) => it
+                .iter()
+                .any(|&it| it == AssistKind::None || it == AssistKind::Source),
+            None => true,
+        };
 
         self.with_db(|db| {
-            let diagnostic_assists = if include_fixes {
+            let native: Vec<Diagnostic> = if include_fixes || include_fix_all {
                 diagnostics::native_diagnostics(
                     db,
                     diagnostics_config,
@@ -493,19 +591,31 @@ impl Analysis {
                     frange.file_id,
                 )
                 .iter()
-                .filter_map(|it| it.fixes.clone())
-                .flatten()
-                .filter(|it| it.target.intersect(frange.range).is_some())
+                .cloned()
                 .collect()
             } else {
                 Vec::new()
             };
+            let diagnostic_assists = if include_fixes {
+                native
+                    .iter()
+                    .filter_map(|it| it.ranked_fixes(&diagnostics_config.preferred_fixes))
+                    .flatten()
+                    .filter(|it| it.target.intersect(frange.range).is_some())
+                    .collect()
+            } else {
+                Vec::new()
+            };
             let eqwalizer_assists = if include_fixes {
                 diagnostics_collection
                     .eqwalizer
                     .get(&frange.file_id)
                     .iter()
-                    .map(|x| x.iter().filter_map(|it| it.fixes.clone()).flatten())
+                    .map(|x| {
+                        x.iter()
+                            .filter_map(|it| it.ranked_fixes(&diagnostics_config.preferred_fixes))
+                            .flatten()
+                    })
                     .flatten()
                     .filter(|it| it.target.intersect(frange.range).is_some())
                     .collect()
@@ -515,15 +625,33 @@ impl Analysis {
             let assists = elp_ide_assists::assists(
                 db,
                 assist_config,
-                resolve,
+                resolve.clone(),
                 frange,
                 context_diagnostics,
                 user_input,
             );
+            let fix_all_assists = if include_fix_all {
+                let ctx = fix_all::FixAllContext {
+                    db,
+                    config: diagnostics_config,
+                    assist_config,
+                    adhoc_semantic_diagnostics,
+                    diagnostics_collection,
+                    resolve: &resolve,
+                    frange,
+                };
+                fix_all::fix_all_assists(&ctx, &native, &assists)
+            } else {
+                Vec::new()
+            };
 
-            let mut res = diagnostic_assists;
+            let mut res: Vec<Assist> = diagnostic_assists;
             res.extend(assists);
             res.extend(eqwalizer_assists);
+            res.extend(fix_all_assists);
+            res.retain(|assist| !assist_config.is_disabled(&assist.id));
+            // Stable, so the fixes of a diagnostic keep their ranking.
+            res.sort_by_key(|assist| assist.id.1.priority());
 
             res
         })
@@ -541,32 +669,17 @@ impl Analysis {
         self.with_db(|db| db.is_otp(file_id))
     }
 
-    /// Search symbols. Only module names are currently supported.
+    /// Search the functions, types, records, macros, modules and
+    /// behaviours of the project. See `symbol_index::Query` for the
+    /// prefixes filtering the symbols by kind.
     pub fn symbol_search(
         &self,
         project_id: ProjectId,
         query: &str,
-    ) -> Cancellable<Vec<NavigationTarget>> {
-        const LIMIT: i32 = 128;
+    ) -> Cancellable<Vec<FileSymbol>> {
+        const LIMIT: usize = 128;
         self.with_db(|db| {
-            let module_index = self.module_index(project_id).unwrap();
-            let mut total = 0;
-            module_index
-                .all_modules()
-                .iter()
-                .filter_map(|name: &ModuleName| {
-                    if total <= LIMIT && name.as_str().contains(query) {
-                        let file_id = module_index.file_for_module(name)?;
-                        let module = Module {
-                            file: File { file_id },
-                        };
-                        total += 1;
-                        Some(module.to_nav(db))
-                    } else {
-                        None
-                    }
-                })
-                .collect()
+            symbol_index::symbol_search(db, project_id, &symbol_index::Query::new(query), LIMIT)
         })
     }
 
@@ -606,6 +719,13 @@ impl Analysis {
         self.with_db(|db| get_docs::get_doc_at_position(db, position))
     }
 
+    /// Returns the hover information for the position: the type
+    /// eqWAlizer found for the expression there, if enabled, and the
+    /// documentation of the symbol or expansion of the macro there
+    pub fn hover(&self, position: FilePosition, config: &HoverConfig) -> Cancellable<Hover> {
+        self.with_db(|db| hover::hover(db, position, config))
+    }
+
     /// Returns available hover actions (rendered as buttons)
     pub fn hover_actions(
         &self,
@@ -623,6 +743,18 @@ impl Analysis {
         self.with_db(|db| references::find_all_refs(&Semantic::new(db), position))
     }
 
+    /// Finds the usages of the reference at point of the given kinds, or
+    /// of any kind.
+    pub fn find_refs_by_kind(
+        &self,
+        position: FilePosition,
+        kinds: Option<Vec<ReferenceKind>>,
+    ) -> Cancellable<Option<Vec<ReferenceSearchResult>>> {
+        self.with_db(|db| {
+            references::find_refs_by_kind(&Semantic::new(db), position, kinds.as_deref())
+        })
+    }
+
     pub fn completions(
         &self,
         position: FilePosition,
@@ -635,6 +767,15 @@ impl Analysis {
         self.with_db(|db| db.resolved_includes(file_id))
     }
 
+    /// Returns the range of the name of the thing at the position, if it
+    /// can be renamed.
+    pub fn prepare_rename(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Result<RangeInfo<()>, RenameError>> {
+        self.with_db(|db| rename::prepare_rename(db, position))
+    }
+
     /// Returns the edit required to rename the thing at the position to the new
     /// name.
     pub fn rename(
@@ -645,6 +786,30 @@ impl Analysis {
         self.with_db(|db| rename::rename(db, position, new_name))
     }
 
+    /// Returns the range of the atom at the position, if it names nothing
+    /// and can be renamed across the project.
+    pub fn prepare_rename_atom(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Result<RangeInfo<()>, RenameError>> {
+        self.with_db(|db| rename_atom::prepare_rename_atom(db, position))
+    }
+
+    /// Returns the uses in the project of the atom at the position, to
+    /// rename to the new name.
+    pub fn rename_atom(
+        &self,
+        position: FilePosition,
+        new_name: &str,
+    ) -> Cancellable<Result<AtomRename, RenameError>> {
+        self.with_db(|db| rename_atom::rename_atom(db, position, new_name))
+    }
+
+    /// Returns the links of the include paths of the file.
+    pub fn document_links(&self, file_id: FileId) -> Cancellable<Vec<DocumentLink>> {
+        self.with_db(|db| document_links::document_links(db, file_id))
+    }
+
     /// Returns the set of folding ranges.
     pub fn folding_ranges(&self, file_id: FileId) -> Cancellable<Vec<FoldingRange>> {
         self.with_db(|db| folding_ranges::folding_ranges(db, file_id))
@@ -668,6 +833,62 @@ impl Analysis {
         self.with_db(|db| call_hierarchy::outgoing_calls(db, position))
     }
 
+    /// The modules called by the given module, most called first.
+    pub fn module_dependencies(&self, file_id: FileId) -> Cancellable<Vec<ModuleDependency>> {
+        self.with_db(|db| call_hierarchy::module_dependencies(db, file_id))
+    }
+
+    /// The modules calling the given module, most calling first.
+    pub fn module_dependents(&self, file_id: FileId) -> Cancellable<Vec<ModuleDependency>> {
+        self.with_db(|db| call_hierarchy::module_dependents(db, file_id))
+    }
+
+    /// The messages the given module receives and sends.
+    pub fn message_protocol(&self, file_id: FileId) -> Cancellable<MessageProtocol> {
+        self.with_db(|db| message_protocol::message_protocol(db, file_id))
+    }
+
+    /// The parse tree of the node covering the range, or of the whole
+    /// file, for debugging.
+    pub fn syntax_tree(&self, file_id: FileId, range: Option<TextRange>) -> Cancellable<String> {
+        self.with_db(|db| syntax_tree::syntax_tree(db, file_id, range))
+    }
+
+    /// The HIR of the forms intersecting the range, or of the whole file,
+    /// for debugging.
+    pub fn view_hir(&self, file_id: FileId, range: Option<TextRange>) -> Cancellable<String> {
+        self.with_db(|db| view_hir::view_hir(db, file_id, range))
+    }
+
+    /// The directories searched for the includes of the file, in order,
+    /// and what each of its includes resolves to, for debugging.
+    pub fn include_path(&self, file_id: FileId) -> Cancellable<String> {
+        self.with_db(|db| include_path::include_path(db, file_id))
+    }
+
+    /// Computes the behaviour hierarchy item for the given file position.
+    pub fn type_hierarchy_prepare(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<NavigationTarget>> {
+        self.with_db(|db| type_hierarchy::type_hierarchy_prepare(db, position))
+    }
+
+    /// Computes the behaviours of the module in the given file.
+    pub fn supertypes(&self, file_id: FileId) -> Cancellable<Vec<NavigationTarget>> {
+        self.with_db(|db| type_hierarchy::supertypes(db, file_id))
+    }
+
+    /// Computes the modules implementing the behaviour in the given file,
+    /// in the project of `from_file`.
+    pub fn subtypes(
+        &self,
+        file_id: FileId,
+        from_file: FileId,
+    ) -> Cancellable<Vec<NavigationTarget>> {
+        self.with_db(|db| type_hierarchy::subtypes(db, file_id, from_file))
+    }
+
     /// Computes parameter information at the given position.
     pub fn signature_help(
         &self,
@@ -682,17 +903,19 @@ impl Analysis {
         config: &InlayHintsConfig,
         file_id: FileId,
         range: Option<TextRange>,
+        types: Option<Arc<Vec<(Pos, Type)>>>,
     ) -> Cancellable<Vec<InlayHint>> {
-        self.with_db(|db| inlay_hints::inlay_hints(db, file_id, range, config))
+        self.with_db(|db| inlay_hints::inlay_hints(db, file_id, range, config, types))
     }
 
     /// Computes syntax highlighting for the given file
     pub fn highlight(
         &self,
+        config: &HighlightConfig,
         file_id: FileId,
         types: Option<Arc<Vec<(Pos, Type)>>>,
     ) -> Cancellable<Vec<HlRange>> {
-        self.with_db(|db| syntax_highlighting::highlight(db, file_id, types, None))
+        self.with_db(|db| syntax_highlighting::highlight(db, config, file_id, types, None))
     }
 
     /// Computes all ranges to highlight for a given item in a file.
@@ -703,14 +926,23 @@ impl Analysis {
         self.with_db(|db| highlight_related::highlight_related(&Semantic::new(db), position))
     }
 
+    /// Computes the names to edit together with the one at position.
+    pub fn linked_editing_ranges(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<Vec<TextRange>>> {
+        self.with_db(|db| linked_editing::linked_editing_ranges(&Semantic::new(db), position))
+    }
+
     /// Computes syntax highlighting for the given file range.
     pub fn highlight_range(
         &self,
+        config: &HighlightConfig,
         types: Option<Arc<Vec<(Pos, Type)>>>,
         frange: FileRange,
     ) -> Cancellable<Vec<HlRange>> {
         self.with_db(|db| {
-            syntax_highlighting::highlight(db, frange.file_id, types, Some(frange.range))
+            syntax_highlighting::highlight(db, config, frange.file_id, types, Some(frange.range))
         })
     }
 
@@ -719,7 +951,7 @@ impl Analysis {
             CommonTestInfo::Result { all, groups } => {
                 annotations::ct_annotations(db, file_id, all.clone(), groups.clone())
             }
-            _ => annotations::annotations(db, file_id),
+            _ => annotations::eunit_annotations(db, file_id),
         })
     }
 
@@ -728,7 +960,7 @@ impl Analysis {
             CommonTestInfo::Result { all, groups } => {
                 runnables::runnables(db, file_id, all.clone(), groups.clone())
             }
-            _ => Vec::new(),
+            _ => runnables::eunit_runnables(db, file_id),
         })
     }
 
@@ -737,6 +969,15 @@ impl Analysis {
         self.with_db(|db| doc_links::external_docs(db, &position))
     }
 
+    /// Computes the change replacing the EDoc comments of a file with
+    /// `-moduledoc` and `-doc` attributes.
+    pub fn migrate_edoc(&self, file_id: FileId) -> Cancellable<Option<SourceChange>> {
+        self.with_db(|db| {
+            migrate_edoc(&Semantic::new(db), file_id)
+                .map(|edit| SourceChange::from_text_edit(file_id, edit))
+        })
+    }
+
     /// Return the form enclosing the given position
     pub fn enclosing_form(&self, position: FilePosition) -> Cancellable<Option<ast::Form>> {
         self.with_db(|db| {
@@ -749,6 +990,52 @@ impl Analysis {
         })
     }
 
+    /// Return the function enclosing the given position, with the range
+    /// covering all its clauses
+    pub fn enclosing_function(
+        &self,
+        position: FilePosition,
+    ) -> Cancellable<Option<(NameArity, TextRange)>> {
+        self.with_db(|db| {
+            let sema = Semantic::new(db);
+            let source = sema.parse(position.file_id);
+            let fun_decl = ancestors_at_offset(source.value.syntax(), position.offset)
+                .and_then(|mut ns| ns.find_map(ast::FunDecl::cast))?;
+            let function_id = sema.find_enclosing_function(position.file_id, fun_decl.syntax())?;
+            let def = sema.function_def(&InFile::new(position.file_id, function_id))?;
+            Some((def.name.clone(), def.range(db)?))
+        })
+    }
+
+    /// Return the modules named in remote calls and remote types of the
+    /// given file, sorted and without duplicates
+    pub fn remote_modules(&self, file_id: FileId) -> Cancellable<Vec<ModuleName>> {
+        self.with_db(|db| {
+            let source = db.parse(file_id).tree();
+            let mut modules: Vec<ModuleName> = source
+                .syntax()
+                .descendants()
+                .filter_map(ast::RemoteModule::cast)
+                .filter_map(|remote| match remote.module()? {
+                    ast::ExprMax::Atom(atom) => Some(ModuleName::new(atom.as_name().as_str())),
+                    _ => None,
+                })
+                .collect();
+            modules.sort();
+            modules.dedup();
+            modules
+        })
+    }
+
+    /// Return the range covering all clauses of the given function
+    pub fn function_range(
+        &self,
+        file_id: FileId,
+        function: &NameArity,
+    ) -> Cancellable<Option<TextRange>> {
+        self.with_db(|db| db.def_map(file_id).get_function(function)?.range(db))
+    }
+
     pub fn def_map(&self, file_id: FileId) -> Cancellable<Arc<DefMap>> {
         self.with_db(|db| db.def_map(file_id))
     }
@@ -757,6 +1044,12 @@ impl Analysis {
         self.with_db(|db| db.file_form_list(file_id))
     }
 
+    /// Sizes of the module indexes and def map tables, for diagnosing
+    /// memory usage on large projects.
+    pub fn memory_usage(&self) -> Cancellable<MemoryUsage> {
+        self.with_db(|db| db.memory_usage())
+    }
+
     /// Performs an operation on the database that may be canceled.
     ///
     /// ELP needs to be able to answer semantic questions about the
@@ -808,5 +1101,7 @@ fn unresolved_fix(id: &'static str, label: &str, target: TextRange) -> Assist {
         source_change: None,
         user_input: None,
         original_diagnostic: None,
+        is_preferred: false,
+        subtype: None,
     }
 }
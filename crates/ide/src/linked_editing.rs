@@ -0,0 +1,140 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::elp_base_db::FilePosition;
+use elp_ide_db::find_best_token;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use hir::InFile;
+use hir::Semantic;
+
+// Feature: Linked Editing
+//
+// Renames all the clauses of a function, and its spec, while the name of
+// one of them is being edited.
+pub(crate) fn linked_editing_ranges(
+    sema: &Semantic,
+    position: FilePosition,
+) -> Option<Vec<TextRange>> {
+    let _p = tracing::info_span!("linked_editing_ranges").entered();
+    let file_id = position.file_id;
+    let token = find_best_token(sema, position)?;
+    let atom = token.value.parent().and_then(ast::Atom::cast)?;
+    let parent = atom.syntax().parent()?;
+    let def = if let Some(clause) = ast::FunctionClause::cast(parent.clone()) {
+        let function_id = sema.find_enclosing_function(file_id, clause.syntax())?;
+        sema.function_def(&InFile::new(file_id, function_id))?
+    } else if let Some(spec) = ast::Spec::cast(parent) {
+        sema.to_def(InFile::new(file_id, &spec))?
+    } else {
+        return None;
+    };
+    if def.file.file_id != file_id {
+        return None;
+    }
+
+    let mut ranges: Vec<TextRange> = def
+        .source(sema.db.upcast())
+        .iter()
+        .filter_map(|fun_decl| fun_decl.name())
+        .map(|name| name.syntax().text_range())
+        .collect();
+    if let Some(spec) = &def.spec {
+        if spec.file.file_id == file_id {
+            let name = spec.source(sema.db.upcast()).fun();
+            ranges.extend(name.map(|name| name.syntax().text_range()));
+        }
+    }
+    // Editing a name used once needs no link
+    if ranges.len() < 2 || !ranges.contains(&atom.syntax().text_range()) {
+        return None;
+    }
+    ranges.sort_by_key(|range| range.start());
+    Some(ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+
+    #[track_caller]
+    fn check(fixture_str: &str) {
+        let (analysis, pos, _diagnostics_enabled, annotations) = fixture::annotations(fixture_str);
+        let actual = analysis
+            .linked_editing_ranges(pos)
+            .unwrap()
+            .unwrap_or_default();
+        let mut expected: Vec<_> = annotations.into_iter().map(|(r, _)| r.range).collect();
+        expected.sort_by_key(|range| range.start());
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn function_clauses_and_spec() {
+        check(
+            r#"
+-module(main).
+-spec tick(integer()) -> atom().
+%%    ^^^^
+tick(0) -> zero;
+%%<^
+ti~ck(_) -> other.
+%%<^
+"#,
+        );
+    }
+
+    #[test]
+    fn from_spec() {
+        check(
+            r#"
+-spec ti~ck(integer()) -> atom().
+%%    ^^^^
+tick(0) -> zero;
+%%<^
+tick(_) -> other.
+%%<^
+"#,
+        );
+    }
+
+    #[test]
+    fn other_arity_not_linked() {
+        check(
+            r#"
+tick(0) -> zero;
+%%<^
+ti~ck(_) -> other.
+%%<^
+tick() -> none.
+"#,
+        );
+    }
+
+    #[test]
+    fn single_clause() {
+        check(
+            r#"
+ti~ck() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn not_a_function_name() {
+        check(
+            r#"
+-spec tick(integer()) -> atom().
+tick(0) -> ze~ro;
+tick(_) -> other.
+"#,
+        );
+    }
+}
@@ -0,0 +1,224 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Summarises the messages a process module can receive and the ones it
+//! sends, to document the protocol it speaks.
+//!
+//! The messages received are the patterns of the `receive` expressions of
+//! the module, and of the `handle_info/2`, `handle_cast/2` and
+//! `handle_call/3` callbacks. The messages sent are the ones sent with
+//! `!`, `erlang:send/2,3`, and the `cast` and `call` functions of
+//! `gen_server` and `gen_statem`.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_ide_db::RootDatabase;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxNode;
+use elp_syntax::TextRange;
+use itertools::Itertools;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageProtocol {
+    /// The messages received, in the order they appear in the module
+    pub received: Vec<Message>,
+    /// The messages sent, in the order they appear in the module
+    pub sent: Vec<Message>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub kind: MessageKind,
+    /// The pattern matching the message, or the expression building it,
+    /// on a single line
+    pub text: String,
+    pub range: TextRange,
+    /// The function the message is received or sent in, as `name/arity`
+    pub function: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    /// Matched by a `receive` expression, or sent with `!` or
+    /// `erlang:send`
+    Plain,
+    /// Handled by `handle_info/2`
+    Info,
+    /// Handled by `handle_cast/2`, or sent with `gen_server:cast` or
+    /// `gen_statem:cast`
+    Cast,
+    /// Handled by `handle_call/3`, or sent with `gen_server:call` or
+    /// `gen_statem:call`
+    Call,
+}
+
+pub(crate) fn message_protocol(db: &RootDatabase, file_id: FileId) -> MessageProtocol {
+    let source = db.parse(file_id).tree();
+    let mut received = Vec::new();
+    let mut sent = Vec::new();
+    for node in source.syntax().descendants() {
+        if let Some(clause) = ast::FunctionClause::cast(node.clone()) {
+            let kind = match function_name(&clause).as_deref() {
+                Some("handle_info/2") => MessageKind::Info,
+                Some("handle_cast/2") => MessageKind::Cast,
+                Some("handle_call/3") => MessageKind::Call,
+                _ => continue,
+            };
+            if let Some(pat) = clause.args().and_then(|args| args.args().next()) {
+                received.push(message(kind, pat.syntax()));
+            }
+        } else if let Some(receive) = ast::ReceiveExpr::cast(node.clone()) {
+            for clause in receive.clauses() {
+                if let ast::CrClauseOrMacro::CrClause(clause) = clause {
+                    if let Some(pat) = clause.pat() {
+                        received.push(message(MessageKind::Plain, pat.syntax()));
+                    }
+                }
+            }
+        } else if let Some(send) = ast::BinaryOpExpr::cast(node.clone()) {
+            if matches!(send.op(), Some((ast::BinaryOp::Send, _))) {
+                if let Some(msg) = send.rhs() {
+                    sent.push(message(MessageKind::Plain, msg.syntax()));
+                }
+            }
+        } else if let Some(call) = ast::Call::cast(node) {
+            if let Some((kind, msg)) = sent_by_call(&call) {
+                sent.push(message(kind, msg.syntax()));
+            }
+        }
+    }
+    MessageProtocol { received, sent }
+}
+
+fn message(kind: MessageKind, node: &SyntaxNode) -> Message {
+    Message {
+        kind,
+        text: node.text().to_string().split_whitespace().join(" "),
+        range: node.text_range(),
+        function: node
+            .ancestors()
+            .find_map(ast::FunctionClause::cast)
+            .and_then(|clause| function_name(&clause)),
+    }
+}
+
+fn function_name(clause: &ast::FunctionClause) -> Option<String> {
+    let name = clause.name()?.syntax().text().to_string();
+    let arity = clause.args()?.args().count();
+    Some(format!("{name}/{arity}"))
+}
+
+/// The message sent by a call to one of the functions sending messages.
+fn sent_by_call(call: &ast::Call) -> Option<(MessageKind, ast::Expr)> {
+    let ast::Expr::Remote(remote) = call.expr()? else {
+        return None;
+    };
+    let module = remote.module()?.module()?.syntax().text().to_string();
+    let fun = remote.fun()?.syntax().text().to_string();
+    let args: Vec<ast::Expr> = call.args()?.args().collect();
+    let kind = match (module.as_str(), fun.as_str(), args.len()) {
+        ("erlang", "send", 2 | 3) => MessageKind::Plain,
+        ("gen_server" | "gen_statem", "cast", 2) => MessageKind::Cast,
+        ("gen_server" | "gen_statem", "call", 2 | 3) => MessageKind::Call,
+        _ => return None,
+    };
+    Some((kind, args.into_iter().nth(1)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use super::Message;
+    use crate::fixture;
+
+    #[track_caller]
+    fn check(fixture: &str, expect: Expect) {
+        let (analysis, pos, _) = fixture::position(fixture);
+        let protocol = analysis.message_protocol(pos.file_id).unwrap();
+        let render = |messages: &[Message]| {
+            messages
+                .iter()
+                .map(|message| {
+                    format!(
+                        "  {:?} {} in {}\n",
+                        message.kind,
+                        message.text,
+                        message.function.as_deref().unwrap_or("-")
+                    )
+                })
+                .collect::<String>()
+        };
+        let actual = format!(
+            "received:\n{}sent:\n{}",
+            render(&protocol.received),
+            render(&protocol.sent)
+        );
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn receive_and_send() {
+        check(
+            r#"
+-module(main).~
+loop(State) ->
+    receive
+        {get, From} ->
+            From ! {value, State},
+            loop(State);
+        {set,
+         Value} ->
+            loop(Value);
+        stop ->
+            erlang:send(logger, {stopped, self()})
+    after 1000 ->
+        loop(State)
+    end.
+"#,
+            expect![[r#"
+                received:
+                  Plain {get, From} in loop/1
+                  Plain {set, Value} in loop/1
+                  Plain stop in loop/1
+                sent:
+                  Plain {value, State} in loop/1
+                  Plain {stopped, self()} in loop/1
+            "#]],
+        );
+    }
+
+    #[test]
+    fn gen_server() {
+        check(
+            r#"
+-module(main).~
+-behaviour(gen_server).
+ping(Pid) -> gen_server:call(Pid, ping, 5000).
+notify(Pid, Event) -> gen_server:cast(Pid, {notify, Event}).
+handle_call(ping, _From, State) -> {reply, pong, State}.
+handle_cast({notify, _}, State) -> {noreply, State}.
+handle_info(timeout, State) -> {noreply, State};
+handle_info({'EXIT', _, _}, State) -> {stop, normal, State}.
+"#,
+            expect![[r#"
+                received:
+                  Call ping in handle_call/3
+                  Cast {notify, _} in handle_cast/2
+                  Info timeout in handle_info/2
+                  Info {'EXIT', _, _} in handle_info/2
+                sent:
+                  Call ping in ping/1
+                  Cast {notify, Event} in notify/2
+            "#]],
+        );
+    }
+}
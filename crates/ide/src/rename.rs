@@ -38,6 +38,8 @@ use hir::Pat;
 use hir::Semantic;
 use hir::VarDef;
 
+use crate::RangeInfo;
+
 // Feature: Rename
 //
 // Renames the item below the cursor and all of its references
@@ -71,6 +73,25 @@ pub(crate) fn rename(
         .ok_or_else(|| format_err!("No references found at position"))
 }
 
+// Feature: Prepare Rename
+//
+// Checks that the item below the cursor can be renamed, giving the range
+// of its name, before the new name is asked for.
+pub(crate) fn prepare_rename(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> RenameResult<RangeInfo<()>> {
+    let sema = Semantic::new(db);
+    let source_file = sema.parse(position.file_id);
+    let syntax = source_file.value.syntax();
+    let name = algo::find_node_at_offset::<ast::Name>(syntax, position.offset)
+        .ok_or_else(|| format_err!("No references found at position"))?;
+    for def in find_definitions(&sema, syntax, position)? {
+        def.check_renameable()?;
+    }
+    Ok(RangeInfo::new(name.syntax().text_range(), ()))
+}
+
 fn find_definitions(
     sema: &Semantic,
     syntax: &SyntaxNode,
@@ -95,35 +116,11 @@ fn find_definitions(
                                 .collect::<Vec<_>>())),
                         }
                     } else {
-                        None
-                    }
-                }
-                ast::Name::Atom(atom) => {
-                    if let Some(token) = atom.syntax().first_token() {
-                        let location = InFile {
-                            file_id: position.file_id,
-                            value: token,
-                        };
-                        match SymbolClass::classify(sema, location) {
-                            Some(SymbolClass::Definition(def)) => Some(Ok(vec![def])),
-                            Some(SymbolClass::Reference {
-                                refs: _,
-                                typ: ReferenceType::Fuzzy,
-                            }) => None,
-                            Some(SymbolClass::Reference { refs, typ: _ }) => match refs {
-                                ReferenceClass::Definition(def) => Some(Ok(vec![def])),
-                                ReferenceClass::MultiVar(defs) => Some(Ok(defs
-                                    .into_iter()
-                                    .map(SymbolDefinition::Var)
-                                    .collect::<Vec<_>>())),
-                                ReferenceClass::MultiMacro(_) => None,
-                            },
-                            None => None,
-                        }
-                    } else {
-                        None
+                        // A macro name, such as `?FOO`
+                        classify(sema, position.file_id, var.syntax())
                     }
                 }
+                ast::Name::Atom(atom) => classify(sema, position.file_id, atom.syntax()),
                 ast::Name::MacroCallExpr(_) => None,
             };
             res
@@ -138,6 +135,36 @@ fn find_definitions(
     }
 }
 
+fn classify(
+    sema: &Semantic,
+    file_id: FileId,
+    name: &SyntaxNode,
+) -> Option<RenameResult<Vec<SymbolDefinition>>> {
+    let token = name.first_token()?;
+    match SymbolClass::classify(sema, InFile::new(file_id, token)) {
+        Some(SymbolClass::Definition(def)) => Some(Ok(vec![def])),
+        Some(SymbolClass::Reference {
+            refs: _,
+            typ: ReferenceType::Fuzzy,
+        }) => None,
+        Some(SymbolClass::Reference { refs, typ: _ }) => match refs {
+            ReferenceClass::Definition(def) => Some(Ok(vec![def])),
+            ReferenceClass::MultiVar(defs) => Some(Ok(defs
+                .into_iter()
+                .map(SymbolDefinition::Var)
+                .collect::<Vec<_>>())),
+            ReferenceClass::MultiMacro(defs) => {
+                let name = defs.first()?.define.name.clone();
+                Some(Err(format_err!(
+                    "Cannot rename macro '{}', it is defined more than once",
+                    name
+                )))
+            }
+        },
+        None => None,
+    }
+}
+
 /// Rename a variable.  Currently only in a function clause body, will
 /// extend in future to other body types.
 pub fn rename_var(
@@ -173,6 +200,7 @@ pub fn rename_var(
 mod tests {
     use elp_ide_db::elp_base_db::assert_eq_text;
     use elp_ide_db::elp_base_db::fixture::WithFixture as _;
+    use elp_ide_db::source_change::FileSystemEdit;
     use elp_ide_db::RootDatabase;
     use elp_project_model::test_fixture::trim_indent;
     use elp_syntax::algo;
@@ -780,6 +808,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rename_function_in_docs() {
+        check(
+            "new_name",
+            r#"
+               -module(main).
+               -doc "Calls `bar/0`, unlike `main:bar/1`.".
+               foo() -> b~ar().
+               %% @doc See {@link bar/0}.
+               bar() -> ok."#,
+            r#"
+               -module(main).
+               -doc "Calls `new_name/0`, unlike `main:bar/1`.".
+               foo() -> new_name().
+               %% @doc See {@link new_name/0}.
+               new_name() -> ok."#,
+        );
+    }
+
+    #[test]
+    fn test_rename_function_in_remote_docs() {
+        check(
+            "new_name",
+            r#"
+               //- /src/main.erl
+               -module(main).
+               -export([bar/0]).
+               b~ar() -> ok.
+               //- /src/other.erl
+               -module(other).
+               -moduledoc "Wraps `main:bar/0`.".
+               "#,
+            r#"
+               //- /src/main.erl
+               -module(main).
+               -export([new_name/0]).
+               new_name() -> ok.
+               //- /src/other.erl
+               -module(other).
+               -moduledoc "Wraps `main:new_name/0`.".
+               "#,
+        );
+    }
+
     #[test]
     fn test_rename_underscore_1() {
         check(
@@ -1078,6 +1150,473 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rename_record() {
+        check(
+            "new_rec",
+            r#"
+            -module(main).
+            -record(re~c, {a, b}).
+            new(A) -> #rec{a = A}.
+            get(#rec{b = B}) -> B.
+            set(R) -> R#rec{b = 1}.
+            idx(R) -> R#rec.a.
+            pos() -> #rec.b.
+             "#,
+            r#"
+            -module(main).
+            -record(new_rec, {a, b}).
+            new(A) -> #new_rec{a = A}.
+            get(#new_rec{b = B}) -> B.
+            set(R) -> R#new_rec{b = 1}.
+            idx(R) -> R#new_rec.a.
+            pos() -> #new_rec.b.
+             "#,
+        );
+    }
+
+    #[test]
+    fn rename_record_in_include() {
+        check(
+            "new_rec",
+            r#"
+             //- /src/main.hrl
+             -record(rec, {a}).
+
+             //- /src/main.erl
+             -module(main).
+             -include("main.hrl").
+             new() -> #re~c{a = 1}.
+
+             //- /src/another.erl
+             -module(another).
+             -include("main.hrl").
+             get(#rec{a = A}) -> A.
+
+             //- /src/different.erl
+             -module(different).
+             -record(rec, {a}).
+             should_not_match() -> #rec{a = 1}.
+             "#,
+            r#"
+             //- /src/main.hrl
+             -record(new_rec, {a}).
+
+             //- /src/main.erl
+             -module(main).
+             -include("main.hrl").
+             new() -> #new_rec{a = 1}.
+
+             //- /src/another.erl
+             -module(another).
+             -include("main.hrl").
+             get(#new_rec{a = A}) -> A.
+
+             //- /src/different.erl
+             -module(different).
+             -record(rec, {a}).
+             should_not_match() -> #rec{a = 1}.
+             "#,
+        );
+    }
+
+    #[test]
+    fn rename_record_already_in_scope() {
+        check(
+            "other",
+            r#"
+            -module(main).
+            -record(re~c, {a}).
+            -record(other, {b}).
+             "#,
+            r#"error: Record 'other' already in scope"#,
+        );
+    }
+
+    #[test]
+    fn rename_record_already_in_scope_in_includer() {
+        check(
+            "other",
+            r#"
+             //- /src/main.hrl
+             -record(re~c, {a}).
+
+             //- /src/main.erl
+             -module(main).
+             -include("main.hrl").
+             -record(other, {b}).
+             new() -> #rec{a = 1}.
+             "#,
+            r#"error: Record 'other' already in scope in module 'main'"#,
+        );
+    }
+
+    #[test]
+    fn rename_record_invalid_name() {
+        check(
+            "Rec",
+            r#"
+            -module(main).
+            -record(re~c, {a}).
+             "#,
+            r#"error: Invalid new record name: 'Rec'"#,
+        );
+    }
+
+    #[test]
+    fn rename_record_field() {
+        check(
+            "new_field",
+            r#"
+            -module(main).
+            -record(rec, {a, b}).
+            new(A) -> #rec{a~ = A}.
+            get(#rec{a = A, b = B}) -> {A, B}.
+            set(R) -> R#rec{a = 1}.
+            idx(R) -> R#rec.a.
+            pos() -> #rec.a.
+            other(R) -> R#rec.b.
+             "#,
+            r#"
+            -module(main).
+            -record(rec, {new_field, b}).
+            new(A) -> #rec{new_field = A}.
+            get(#rec{new_field = A, b = B}) -> {A, B}.
+            set(R) -> R#rec{new_field = 1}.
+            idx(R) -> R#rec.new_field.
+            pos() -> #rec.new_field.
+            other(R) -> R#rec.b.
+             "#,
+        );
+    }
+
+    #[test]
+    fn rename_record_field_in_include() {
+        check(
+            "new_field",
+            r#"
+             //- /src/main.hrl
+             -record(rec, {a~ = 1 :: integer(), b}).
+
+             //- /src/main.erl
+             -module(main).
+             -include("main.hrl").
+             get(R) -> R#rec.a.
+
+             //- /src/different.erl
+             -module(different).
+             -record(rec, {a}).
+             should_not_match(R) -> R#rec.a.
+             "#,
+            r#"
+             //- /src/main.hrl
+             -record(rec, {new_field = 1 :: integer(), b}).
+
+             //- /src/main.erl
+             -module(main).
+             -include("main.hrl").
+             get(R) -> R#rec.new_field.
+
+             //- /src/different.erl
+             -module(different).
+             -record(rec, {a}).
+             should_not_match(R) -> R#rec.a.
+             "#,
+        );
+    }
+
+    #[test]
+    fn rename_record_field_already_defined() {
+        check(
+            "b",
+            r#"
+            -module(main).
+            -record(rec, {a, b}).
+            get(R) -> R#rec.a~.
+             "#,
+            r#"error: Field 'b' already defined in record 'rec'"#,
+        );
+    }
+
+    #[test]
+    fn rename_module() {
+        check(
+            "server",
+            r#"
+             //- /src/main.erl
+             -module(ma~in).
+             -export([start/0]).
+             -export_type([state/0]).
+             -type state() :: ok.
+             start() -> main:start().
+
+             //- /src/client.erl
+             -module(client).
+             -behaviour(main).
+             -spec run() -> main:state().
+             run() -> main:start().
+             callback() -> fun main:start/0.
+             "#,
+            r#"
+             //- /src/main.erl
+             -module(server).
+             -export([start/0]).
+             -export_type([state/0]).
+             -type state() :: ok.
+             start() -> server:start().
+
+             //- /src/client.erl
+             -module(client).
+             -behaviour(server).
+             -spec run() -> server:state().
+             run() -> server:start().
+             callback() -> fun server:start/0.
+             "#,
+        );
+    }
+
+    #[test]
+    fn rename_module_from_remote_call() {
+        check(
+            "server",
+            r#"
+             //- /src/main.erl
+             -module(main).
+             -export([start/0]).
+             start() -> ok.
+
+             //- /src/client.erl
+             -module(client).
+             run() -> ma~in:start().
+             "#,
+            r#"
+             //- /src/main.erl
+             -module(server).
+             -export([start/0]).
+             start() -> ok.
+
+             //- /src/client.erl
+             -module(client).
+             run() -> server:start().
+             "#,
+        );
+    }
+
+    #[test]
+    fn rename_module_moves_file() {
+        let (analysis, position, _) = fixture::position(
+            r#"
+            //- /src/main.erl
+            -module(ma~in).
+            "#,
+        );
+        let source_change = analysis.rename(position, "server").unwrap().unwrap();
+        match source_change.file_system_edits.as_slice() {
+            [FileSystemEdit::MoveFile { src, dst }] => {
+                assert_eq!(*src, position.file_id);
+                assert_eq!(dst.anchor, position.file_id);
+                assert_eq!(dst.path, "server.erl");
+            }
+            edits => panic!("Unexpected file system edits: {:?}", edits),
+        }
+    }
+
+    #[test]
+    fn rename_module_already_exists() {
+        check(
+            "client",
+            r#"
+             //- /src/main.erl
+             -module(ma~in).
+
+             //- /src/client.erl
+             -module(client).
+             "#,
+            r#"error: Module 'client' already exists"#,
+        );
+    }
+
+    #[test]
+    fn rename_module_invalid_name() {
+        check(
+            "'my server'",
+            r#"
+            -module(ma~in).
+             "#,
+            r#"error: Invalid new module name: ''my server''"#,
+        );
+    }
+
+    #[test]
+    fn rename_macro() {
+        check(
+            "BAR",
+            r#"
+            -module(main).
+            -define(FOO, 1).
+            f() -> ?F~OO.
+             "#,
+            r#"
+            -module(main).
+            -define(BAR, 1).
+            f() -> ?BAR.
+             "#,
+        );
+    }
+
+    #[test]
+    fn rename_macro_with_args_from_definition() {
+        check(
+            "plus",
+            r#"
+            -module(main).
+            -define(AD~D(X, Y), X + Y).
+            f() -> ?ADD(1, 2).
+             "#,
+            r#"
+            -module(main).
+            -define(plus(X, Y), X + Y).
+            f() -> ?plus(1, 2).
+             "#,
+        );
+    }
+
+    #[test]
+    fn rename_macro_in_include() {
+        check(
+            "BAR",
+            r#"
+             //- /src/main.hrl
+             -define(FOO, 1).
+
+             //- /src/main.erl
+             -module(main).
+             -include("main.hrl").
+             f() -> ?FO~O.
+
+             //- /src/another.erl
+             -module(another).
+             -include("main.hrl").
+             g() -> ?FOO + 1.
+
+             //- /src/different.erl
+             -module(different).
+             -define(FOO, 2).
+             should_not_match() -> ?FOO.
+             "#,
+            r#"
+             //- /src/main.hrl
+             -define(BAR, 1).
+
+             //- /src/main.erl
+             -module(main).
+             -include("main.hrl").
+             f() -> ?BAR.
+
+             //- /src/another.erl
+             -module(another).
+             -include("main.hrl").
+             g() -> ?BAR + 1.
+
+             //- /src/different.erl
+             -module(different).
+             -define(FOO, 2).
+             should_not_match() -> ?FOO.
+             "#,
+        );
+    }
+
+    #[test]
+    fn rename_macro_defined_in_ifdef_branches() {
+        check(
+            "BAR",
+            r#"
+            -module(main).
+            -ifdef(TEST).
+            -define(FOO, test).
+            -else.
+            -define(FOO, prod).
+            -endif.
+            f() -> ?F~OO.
+             "#,
+            r#"error: Cannot rename macro 'FOO', it is defined more than once"#,
+        );
+    }
+
+    #[test]
+    fn rename_macro_already_in_scope() {
+        check(
+            "BAR",
+            r#"
+            -module(main).
+            -define(FOO, 1).
+            -define(BAR, 2).
+            f() -> ?F~OO.
+             "#,
+            r#"error: Macro 'BAR' already in scope"#,
+        );
+    }
+
+    #[test]
+    fn rename_macro_invalid_name() {
+        check(
+            "foo bar",
+            r#"
+            -module(main).
+            -define(F~OO, 1).
+             "#,
+            r#"error: Invalid new macro name: 'foo bar'"#,
+        );
+    }
+
+    #[track_caller]
+    fn check_prepare_rename(fixture: &str) {
+        let (analysis, position, _, annotations) = fixture::annotations(fixture);
+        let prepare_result = analysis.prepare_rename(position).unwrap();
+        match (prepare_result, annotations.as_slice()) {
+            (Ok(range_info), [(range, label)]) if label.is_empty() => {
+                assert_eq!(range_info.range, range.range)
+            }
+            (Err(err), [(_, label)]) => {
+                assert_eq!(label.trim_start_matches("error: "), err.to_string())
+            }
+            (result, _) => panic!("Unexpected prepare rename result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn prepare_rename_macro() {
+        check_prepare_rename(
+            r#"
+            -module(main).
+            -define(FOO, 1).
+            f() -> ?F~OO.
+            %%      ^^^
+             "#,
+        );
+    }
+
+    #[test]
+    fn prepare_rename_module() {
+        check_prepare_rename(
+            r#"
+            -module(ma~in).
+            %%      ^^^^
+             "#,
+        );
+    }
+
+    #[test]
+    fn prepare_rename_type() {
+        check_prepare_rename(
+            r#"
+            -module(main).
+            -type fo~o() :: ok.
+            %%    ^^^ error: Cannot rename type
+             "#,
+        );
+    }
+
     // ---------------------------------
 
     #[track_caller]
@@ -0,0 +1,390 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Renaming an atom used as data, such as a message tag or a config key,
+//! across the project.
+//!
+//! Atoms naming a function, module, type or record are renamed along with
+//! what they name. Any other atom means the same wherever it is written,
+//! so all of its uses in the applications of the project are renamed,
+//! each classified by how the atom is used for the edit to be previewed
+//! group by group.
+
+use elp_ide_db::elp_base_db::AppType;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::FilePosition;
+use elp_ide_db::elp_base_db::FileRange;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_ide_db::elp_base_db::SourceDatabaseExt;
+use elp_ide_db::rename::format_err;
+use elp_ide_db::rename::is_valid_atom_name;
+use elp_ide_db::rename::rename_error;
+use elp_ide_db::rename::RenameError;
+use elp_ide_db::rename::RenameResult;
+use elp_ide_db::RootDatabase;
+use elp_syntax::algo;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use elp_syntax::TextRange;
+use fxhash::FxHashSet;
+use hir::AsName;
+use hir::Name;
+
+use crate::atoms::is_data_atom;
+use crate::message_protocol::message_protocol;
+use crate::RangeInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AtomUsageKind {
+    /// The message sent or received, or the tag of a tuple message
+    MessageTag,
+    /// The key of an application environment variable or of a proplist
+    ConfigKey,
+    /// Within a pattern
+    Pattern,
+    Literal,
+}
+
+impl AtomUsageKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            AtomUsageKind::MessageTag => "Message tag",
+            AtomUsageKind::ConfigKey => "Config key",
+            AtomUsageKind::Pattern => "Pattern",
+            AtomUsageKind::Literal => "Literal",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtomUsage {
+    pub range: FileRange,
+    pub kind: AtomUsageKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtomRename {
+    pub new_name: String,
+    pub usages: Vec<AtomUsage>,
+}
+
+/// `module:function/arity` calls taking a config key, with the index of
+/// the key in the arguments.
+const CONFIG_KEY_ARGS: &[(&str, &str, usize, usize)] = &[
+    ("application", "get_env", 1, 0),
+    ("application", "get_env", 2, 1),
+    ("application", "get_env", 3, 1),
+    ("application", "set_env", 3, 1),
+    ("application", "set_env", 4, 1),
+    ("application", "unset_env", 2, 1),
+    ("application", "unset_env", 3, 1),
+    ("proplists", "get_value", 2, 0),
+    ("proplists", "get_value", 3, 0),
+    ("proplists", "get_bool", 2, 0),
+    ("proplists", "get_all_values", 2, 0),
+    ("proplists", "is_defined", 2, 0),
+    ("proplists", "lookup", 2, 0),
+    ("proplists", "delete", 2, 0),
+];
+
+// Feature: Rename Atom
+//
+// When `elp.rename.atoms.enable` is set, renaming an atom which names
+// nothing renames all its uses in the project, with a preview of the
+// edits grouped by usage: message tag, config key, pattern and literal.
+pub(crate) fn prepare_rename_atom(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> RenameResult<RangeInfo<()>> {
+    let atom = data_atom_at(db, position)?;
+    Ok(RangeInfo::new(atom.syntax().text_range(), ()))
+}
+
+pub(crate) fn rename_atom(
+    db: &RootDatabase,
+    position: FilePosition,
+    new_name: &str,
+) -> RenameResult<AtomRename> {
+    let new_name = new_name.trim().to_string();
+    if !is_valid_atom_name(&new_name) {
+        rename_error!("Invalid new atom name: '{}'", new_name);
+    }
+    let name = data_atom_at(db, position)?.as_name();
+    let project_id = db
+        .file_project_id(position.file_id)
+        .ok_or_else(|| format_err!("File is not part of a project"))?;
+    let mut usages = Vec::new();
+    for &source_root_id in &db.project_data(project_id).source_roots {
+        for file_id in db.source_root(source_root_id).iter() {
+            if db.file_kind(file_id).is_elp_supported()
+                && db.file_app_type(file_id) == Some(AppType::App)
+            {
+                file_usages(db, file_id, &name, &mut usages);
+            }
+        }
+    }
+    usages.sort_by_key(|usage| (usage.range.file_id, usage.range.range.start()));
+    Ok(AtomRename { new_name, usages })
+}
+
+fn data_atom_at(db: &RootDatabase, position: FilePosition) -> RenameResult<ast::Atom> {
+    let source = db.parse(position.file_id).tree();
+    algo::find_node_at_offset::<ast::Atom>(source.syntax(), position.offset)
+        .filter(is_renameable)
+        .ok_or_else(|| format_err!("No atom to rename at position"))
+}
+
+fn is_renameable(atom: &ast::Atom) -> bool {
+    is_data_atom(atom) || in_declaration(atom)
+}
+
+/// Atoms in types, specs, record defaults and macro definitions, leaving
+/// out the names they declare.
+fn in_declaration(atom: &ast::Atom) -> bool {
+    let mut declared = false;
+    for node in atom.syntax().ancestors() {
+        match node.kind() {
+            SyntaxKind::MACRO_LHS => return false,
+            SyntaxKind::TYPE_ALIAS
+            | SyntaxKind::OPAQUE
+            | SyntaxKind::SPEC
+            | SyntaxKind::CALLBACK
+            | SyntaxKind::RECORD_DECL
+            | SyntaxKind::PP_DEFINE => declared = true,
+            _ => {}
+        }
+    }
+    let is_name = atom.syntax().parent().map_or(false, |parent| {
+        matches!(
+            parent.kind(),
+            SyntaxKind::CALL
+                | SyntaxKind::REMOTE
+                | SyntaxKind::REMOTE_MODULE
+                | SyntaxKind::SPEC
+                | SyntaxKind::CALLBACK
+                | SyntaxKind::MODULE
+                | SyntaxKind::TYPE_NAME
+                | SyntaxKind::RECORD_DECL
+                | SyntaxKind::RECORD_FIELD
+                | SyntaxKind::RECORD_NAME
+                | SyntaxKind::RECORD_FIELD_NAME
+        )
+    });
+    declared && !is_name
+}
+
+fn file_usages(db: &RootDatabase, file_id: FileId, name: &Name, usages: &mut Vec<AtomUsage>) {
+    // Cheap filter, to avoid parsing every file of the project
+    if !db.file_text(file_id).contains(name.as_str()) {
+        return;
+    }
+    let source = db.parse(file_id).tree();
+    let atoms: Vec<ast::Atom> = source
+        .syntax()
+        .descendants()
+        .filter_map(ast::Atom::cast)
+        .filter(|atom| is_renameable(atom) && atom.as_name() == *name)
+        .collect();
+    if atoms.is_empty() {
+        return;
+    }
+    let protocol = message_protocol(db, file_id);
+    let messages: FxHashSet<TextRange> = protocol
+        .received
+        .iter()
+        .chain(&protocol.sent)
+        .map(|message| message.range)
+        .collect();
+    usages.extend(atoms.iter().map(|atom| AtomUsage {
+        range: FileRange {
+            file_id,
+            range: atom.syntax().text_range(),
+        },
+        kind: usage_kind(atom, &messages),
+    }));
+}
+
+fn usage_kind(atom: &ast::Atom, messages: &FxHashSet<TextRange>) -> AtomUsageKind {
+    let node = atom.syntax();
+    // A message is either the atom itself, or a tuple tagged with it
+    let tuple = node.parent().and_then(ast::Tuple::cast).filter(|tuple| {
+        tuple
+            .expr()
+            .next()
+            .map_or(false, |tag| tag.syntax() == node)
+    });
+    if messages.contains(&node.text_range())
+        || tuple.map_or(false, |tuple| {
+            messages.contains(&tuple.syntax().text_range())
+        })
+    {
+        AtomUsageKind::MessageTag
+    } else if is_config_key(atom).unwrap_or(false) {
+        AtomUsageKind::ConfigKey
+    } else if in_pattern(node) {
+        AtomUsageKind::Pattern
+    } else {
+        AtomUsageKind::Literal
+    }
+}
+
+fn is_config_key(atom: &ast::Atom) -> Option<bool> {
+    let args = atom.syntax().parent().and_then(ast::ExprArgs::cast)?;
+    let call = args.syntax().parent().and_then(ast::Call::cast)?;
+    let ast::Expr::Remote(remote) = call.expr()? else {
+        return Some(false);
+    };
+    let module = remote.module()?.module()?.syntax().text().to_string();
+    let fun = remote.fun()?.syntax().text().to_string();
+    let args: Vec<ast::Expr> = args.args().collect();
+    let idx = args.iter().position(|arg| arg.syntax() == atom.syntax())?;
+    Some(
+        CONFIG_KEY_ARGS.iter().any(|&(m, f, arity, key)| {
+            m == module && f == fun && arity == args.len() && key == idx
+        }),
+    )
+}
+
+fn in_pattern(node: &SyntaxNode) -> bool {
+    let mut child = node.clone();
+    for parent in node.ancestors().skip(1) {
+        let is_pattern = if let Some(clause) = ast::CrClause::cast(parent.clone()) {
+            clause.pat().map_or(false, |pat| *pat.syntax() == child)
+        } else if let Some(clause) = ast::FunctionClause::cast(parent.clone()) {
+            clause.args().map_or(false, |args| *args.syntax() == child)
+        } else if let Some(clause) = ast::FunClause::cast(parent.clone()) {
+            clause.args().map_or(false, |args| *args.syntax() == child)
+        } else if let Some(expr) = ast::MatchExpr::cast(parent.clone()) {
+            expr.lhs().map_or(false, |lhs| *lhs.syntax() == child)
+        } else {
+            false
+        };
+        if is_pattern {
+            return true;
+        }
+        child = parent;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::fixture;
+
+    #[track_caller]
+    fn check(new_name: &str, fixture: &str, expect: Expect) {
+        let (analysis, position, _) = fixture::position(fixture);
+        let actual = match analysis.rename_atom(position, new_name).unwrap() {
+            Ok(rename) => rename
+                .usages
+                .iter()
+                .map(|usage| {
+                    let text = analysis.file_text(usage.range.file_id).unwrap();
+                    let start = usize::from(usage.range.range.start());
+                    let end = usize::from(usage.range.range.end());
+                    let line_start = text[..start].rfind('\n').map_or(0, |idx| idx + 1);
+                    let line_end = text[end..].find('\n').map_or(text.len(), |idx| end + idx);
+                    format!(
+                        "{}: {}<{}>{}\n",
+                        usage.kind.label(),
+                        text[line_start..start].trim_start(),
+                        rename.new_name,
+                        &text[end..line_end]
+                    )
+                })
+                .collect::<String>(),
+            Err(err) => format!("error: {err}\n"),
+        };
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn protocol_tag_across_modules() {
+        check(
+            "fetch",
+            r#"
+//- /src/server.erl
+-module(server).
+-behaviour(gen_server).
+-type request() :: {get, term()}.
+get(Pid, Key) -> gen_server:call(Pid, {g~et, Key}).
+handle_call({get, Key}, _From, State) -> {reply, lookup(Key, State), State}.
+lookup(Key, State) -> proplists:get_value(Key, State).
+//- /src/client.erl
+-module(client).
+run(Pid) ->
+    Pid ! {get, self()},
+    receive
+        {get, Value} -> Value
+    end.
+"#,
+            expect![[r#"
+                Literal: -type request() :: {<fetch>, term()}.
+                Message tag: get(Pid, Key) -> gen_server:call(Pid, {<fetch>, Key}).
+                Message tag: handle_call({<fetch>, Key}, _From, State) -> {reply, lookup(Key, State), State}.
+                Message tag: Pid ! {<fetch>, self()},
+                Message tag: {<fetch>, Value} -> Value
+            "#]],
+        );
+    }
+
+    #[test]
+    fn config_key_pattern_and_literal() {
+        check(
+            "'max-retries'",
+            r#"
+-module(main).
+-define(DEFAULT, [{retries, 3}]).
+retries() -> application:get_env(app, ret~ries, 3).
+retries(Opts) -> proplists:get_value(retries, Opts ++ ?DEFAULT).
+kind(retries) -> counter;
+kind(_) -> other.
+"#,
+            expect![[r#"
+                Literal: -define(DEFAULT, [{<'max-retries'>, 3}]).
+                Config key: retries() -> application:get_env(app, <'max-retries'>, 3).
+                Config key: retries(Opts) -> proplists:get_value(<'max-retries'>, Opts ++ ?DEFAULT).
+                Pattern: kind(<'max-retries'>) -> counter;
+            "#]],
+        );
+    }
+
+    #[test]
+    fn not_a_data_atom() {
+        check(
+            "stop",
+            r#"
+-module(main).
+ha~lt() -> ok.
+"#,
+            expect![[r#"
+                error: No atom to rename at position
+            "#]],
+        );
+    }
+
+    #[test]
+    fn invalid_new_name() {
+        check(
+            "Stop",
+            r#"
+-module(main).
+halt() -> ha~lt.
+"#,
+            expect![[r#"
+                error: Invalid new atom name: 'Stop'
+            "#]],
+        );
+    }
+}
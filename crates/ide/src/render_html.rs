@@ -0,0 +1,256 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Renders a file as a static HTML page, its references linked to their
+//! definitions, as used by `elp render-html`.
+
+use std::fmt::Write;
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_ide_db::LineIndexDatabase;
+use elp_ide_db::RootDatabase;
+use elp_ide_db::SymbolClass;
+use elp_ide_db::SymbolDefinition;
+use elp_syntax::AstNode;
+use elp_syntax::NodeOrToken;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxToken;
+use elp_syntax::TextSize;
+use fxhash::FxHashMap;
+use hir::InFile;
+use hir::Semantic;
+use itertools::Itertools;
+
+use crate::navigation_target::ToNav;
+
+const STYLE: &str = "
+body { margin: 0; }
+pre { margin: 0; padding: 1em; font-size: 14px; line-height: 1.4; }
+a { color: inherit; text-decoration: none; }
+a[href]:hover { text-decoration: underline; }
+.comment { color: #6a737d; }
+.string { color: #032f62; }
+.number { color: #005cc5; }
+.keyword { color: #d73a49; font-weight: bold; }
+.variable { color: #e36209; }
+";
+
+/// Renders the file as an HTML page. `pages` holds the path of the page
+/// of each rendered file, relative to `root`, the path from the page to
+/// the directory of the pages. References to files without a page are
+/// not linked.
+pub(crate) fn render_html(
+    db: &RootDatabase,
+    file_id: FileId,
+    pages: &FxHashMap<FileId, String>,
+    root: &str,
+) -> String {
+    let _p = tracing::info_span!("render_html").entered();
+    let sema = Semantic::new(db);
+    let source = sema.parse(file_id).value;
+    let mut renderer = Renderer {
+        db,
+        pages,
+        out: String::new(),
+        line: 1,
+    };
+    let function_anchors: FxHashMap<TextSize, String> = sema
+        .def_map(file_id)
+        .get_functions()
+        .filter(|(_, def)| def.file.file_id == file_id)
+        .filter_map(|(name, def)| {
+            let fun_decl = def.source(db).into_iter().next()?;
+            let start = fun_decl.syntax().text_range().start();
+            Some((start, name.to_string()))
+        })
+        .collect();
+
+    let title = pages.get(&file_id).map_or("", |page| page.as_str());
+    let _ = write!(
+        renderer.out,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <base href=\"{}\">\n<style>{}</style>\n</head>\n<body>\n<pre><code><a id=\"L1\"></a>",
+        escape(title),
+        escape(root),
+        STYLE
+    );
+    for element in source.syntax().descendants_with_tokens() {
+        let NodeOrToken::Token(token) = element else {
+            continue;
+        };
+        if let Some(name) = function_anchors.get(&token.text_range().start()) {
+            let _ = write!(renderer.out, "<a id=\"{}\"></a>", escape(name));
+        }
+        let class = class(&token);
+        let symbol = match token.kind() {
+            SyntaxKind::ATOM | SyntaxKind::VAR => {
+                SymbolClass::classify(&sema, InFile::new(file_id, token.clone()))
+            }
+            _ => None,
+        };
+        match symbol {
+            Some(SymbolClass::Reference { refs, typ: _ }) => match refs.iter().next() {
+                Some(def) => renderer.push_link(&token, class, &def),
+                None => renderer.push_text(token.text(), class),
+            },
+            Some(SymbolClass::Definition(def)) => match renderer.title(&def) {
+                Some(title) => {
+                    let _ = write!(renderer.out, "<span title=\"{}\">", escape(&title));
+                    renderer.push_text(token.text(), class);
+                    renderer.out.push_str("</span>");
+                }
+                None => renderer.push_text(token.text(), class),
+            },
+            None => renderer.push_text(token.text(), class),
+        }
+    }
+    renderer.out.push_str("</code></pre>\n</body>\n</html>\n");
+    renderer.out
+}
+
+struct Renderer<'a> {
+    db: &'a RootDatabase,
+    pages: &'a FxHashMap<FileId, String>,
+    out: String,
+    /// The line being rendered, one-based as in the anchors
+    line: u32,
+}
+
+impl Renderer<'_> {
+    /// Pushes the text, adding an anchor at the start of each line.
+    fn push_text(&mut self, text: &str, class: Option<&str>) {
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.line += 1;
+                let _ = write!(self.out, "\n<a id=\"L{}\"></a>", self.line);
+            }
+            if line.is_empty() {
+                continue;
+            }
+            match class {
+                Some(class) => {
+                    let _ = write!(self.out, "<span class=\"{class}\">{}</span>", escape(line));
+                }
+                None => self.out.push_str(&escape(line)),
+            }
+        }
+    }
+
+    fn push_link(&mut self, token: &SyntaxToken, class: Option<&str>, def: &SymbolDefinition) {
+        let nav = def.to_nav(self.db);
+        let Some(page) = self.pages.get(&nav.file_id) else {
+            self.push_text(token.text(), class);
+            return;
+        };
+        let anchor = match def {
+            SymbolDefinition::Function(_) => nav.name.to_string(),
+            _ => {
+                let offset = nav.focus_range.unwrap_or(nav.full_range).start();
+                let line = self.db.file_line_index(nav.file_id).line_col(offset).line;
+                format!("L{}", line + 1)
+            }
+        };
+        let _ = write!(self.out, "<a href=\"{}#{}\"", escape(page), escape(&anchor));
+        if let Some(title) = self.title(def) {
+            let _ = write!(self.out, " title=\"{}\"", escape(&title));
+        }
+        self.out.push('>');
+        self.push_text(token.text(), class);
+        self.out.push_str("</a>");
+    }
+
+    /// The spec of a function, or the definition of a type, on one line.
+    fn title(&self, def: &SymbolDefinition) -> Option<String> {
+        let text = match def {
+            SymbolDefinition::Function(function) => {
+                let spec = function.spec.as_ref()?;
+                spec.source(self.db).syntax().text().to_string()
+            }
+            SymbolDefinition::Type(type_alias) => {
+                type_alias.source(self.db).syntax().text().to_string()
+            }
+            _ => return None,
+        };
+        Some(text.split_whitespace().join(" "))
+    }
+}
+
+fn class(token: &SyntaxToken) -> Option<&'static str> {
+    match token.kind() {
+        SyntaxKind::COMMENT => Some("comment"),
+        SyntaxKind::STRING | SyntaxKind::CHAR => Some("string"),
+        SyntaxKind::INTEGER | SyntaxKind::FLOAT => Some("number"),
+        SyntaxKind::VAR => Some("variable"),
+        _ if token.text().starts_with(|c: char| c.is_ascii_lowercase())
+            && token.kind() != SyntaxKind::ATOM =>
+        {
+            Some("keyword")
+        }
+        _ => None,
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '&' => res.push_str("&amp;"),
+            '"' => res.push_str("&quot;"),
+            _ => res.push(c),
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_ide_db::elp_base_db::FileId;
+    use fxhash::FxHashMap;
+
+    use crate::fixture;
+
+    #[test]
+    fn links_and_titles() {
+        let analysis = fixture::multi_file(
+            r#"
+//- /src/main.erl
+-module(main).
+-spec run(integer()) -> ok.
+run(N) -> other:go(N).
+//- /src/other.erl
+-module(other).
+-export([go/1]).
+-spec go(N :: integer()) -> ok.
+go(_N) -> ok.
+"#,
+        );
+        let pages: FxHashMap<FileId, String> = [
+            (FileId::from_raw(0), "src/main.erl.html".to_string()),
+            (FileId::from_raw(1), "src/other.erl.html".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let html = analysis
+            .render_html(FileId::from_raw(0), &pages, "../")
+            .unwrap();
+        assert!(html.contains("<base href=\"../\">"));
+        assert!(html.contains("<a id=\"L3\"></a><a id=\"run/1\"></a>"));
+        assert!(html.contains(
+            "<a href=\"src/other.erl.html#go/1\" title=\"-spec go(N :: integer()) -&gt; ok"
+        ));
+        assert!(html.contains("<a href=\"src/other.erl.html#L1\">other</a>"));
+        assert!(html.contains("<span title=\"-spec run(integer()) -&gt; ok"));
+        assert!(
+            html.contains("<a href=\"src/main.erl.html#L3\"><span class=\"variable\">N</span></a>")
+        );
+    }
+}
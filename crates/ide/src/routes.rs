@@ -0,0 +1,122 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Navigation between the routes of routing tables and their handlers.
+//! The routes themselves are collected by [`hir::RouteIndex`].
+
+use elp_ide_db::elp_base_db::FilePosition;
+use elp_ide_db::SymbolDefinition;
+use hir::Route;
+use hir::Semantic;
+
+use crate::navigation_target::ToNav;
+use crate::NavigationTarget;
+use crate::RangeInfo;
+
+/// The function the route dispatches to, if it names one defined in the
+/// handler module, otherwise the module itself.
+pub(crate) fn handler_target(sema: &Semantic, route: &Route) -> Option<NavigationTarget> {
+    let module = sema.resolve_module_name(route.file_id, route.handler.as_str())?;
+    let function = route.function.as_ref().and_then(|function| {
+        sema.def_map(module.file.file_id)
+            .get_functions()
+            .find(|(name, _)| name.name() == function)
+            .map(|(_, def)| def.to_nav(sema.db))
+    });
+    Some(function.unwrap_or_else(|| module.to_nav(sema.db)))
+}
+
+pub(crate) fn goto_handler(
+    sema: &Semantic,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let routes = sema.db.file_routes(position.file_id);
+    let route = routes
+        .iter()
+        .find(|route| route.range.contains_inclusive(position.offset))?;
+    let target = handler_target(sema, route)?;
+    Some(RangeInfo::new(route.range, vec![target]))
+}
+
+/// Routes dispatching to the given module or function.
+pub(crate) fn routes_for_handler(sema: &Semantic, def: &SymbolDefinition) -> Vec<Route> {
+    let (file_id, function) = match def {
+        SymbolDefinition::Module(module) => (module.file.file_id, None),
+        SymbolDefinition::Function(fun) => (fun.file.file_id, Some(fun.name.name())),
+        _ => return Vec::new(),
+    };
+    let (project_id, module) = match (sema.db.file_project_id(file_id), sema.module_name(file_id)) {
+        (Some(project_id), Some(module)) => (project_id, module),
+        _ => return Vec::new(),
+    };
+    sema.db
+        .route_index(project_id)
+        .routes(&module)
+        .iter()
+        .filter(|route| function.map_or(true, |function| route.function.as_ref() == Some(function)))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+    use crate::tests::check_navs;
+
+    #[track_caller]
+    fn check(fixture: &str) {
+        let (analysis, position, _diagnostics_enabled, expected) = fixture::annotations(fixture);
+        let navs = analysis
+            .goto_definition(position)
+            .unwrap()
+            .expect("no definition found")
+            .info;
+        check_navs(navs, expected);
+    }
+
+    #[test]
+    fn cowboy_route_to_module() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+start() ->
+    cowboy_router:compile([{'_', [{"/us~ers", users_handler, []}]}]).
+//- /src/users_handler.erl
+  -module(users_handler).
+%%^^^^^^^^^^^^^^^^^^^^^^^
+//- /src/cowboy_router.erl
+-module(cowboy_router).
+-export([compile/1]).
+compile(Routes) -> Routes.
+"#,
+        )
+    }
+
+    #[test]
+    fn route_to_function() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+start() ->
+    cowboy_router:compile([{'_', [{"/us~ers/:id", [], users, show}]}]).
+//- /src/users.erl
+-module(users).
+-export([show/2]).
+  show(Req, State) -> {ok, Req, State}.
+%%^^^^
+//- /src/cowboy_router.erl
+-module(cowboy_router).
+-export([compile/1]).
+compile(Routes) -> Routes.
+"#,
+        )
+    }
+}
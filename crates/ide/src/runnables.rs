@@ -19,6 +19,7 @@ use hir::NameArity;
 use hir::Semantic;
 
 use crate::common_test;
+use crate::navigation_target::ToNav;
 use crate::NavigationTarget;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -37,6 +38,13 @@ pub enum RunnableKind {
         group: common_test::GroupName,
     },
     Suite,
+    /// An EUnit test function, `*_test/0`, or test generator, `*_test_/0`
+    Eunit {
+        name: NameArity,
+        app_name: AppName,
+        module: String,
+        generator: bool,
+    },
 }
 
 impl Runnable {
@@ -44,6 +52,7 @@ impl Runnable {
         match &self.kind {
             RunnableKind::Test { .. } => "test".to_string(),
             RunnableKind::Suite => "test".to_string(),
+            RunnableKind::Eunit { .. } => "test".to_string(),
         }
     }
     pub fn id(&self) -> String {
@@ -55,6 +64,7 @@ impl Runnable {
                 format!("{suite} - {group}.{case}")
             }
             RunnableKind::Suite => "".to_string(),
+            RunnableKind::Eunit { name, module, .. } => format!("{module}:{}", name.name()),
         }
     }
     pub fn regex(&self) -> String {
@@ -70,12 +80,18 @@ impl Runnable {
                 format!("{app_name}:{suite} - {group}.{case}$")
             }
             RunnableKind::Suite => "".to_string(),
+            RunnableKind::Eunit {
+                name,
+                app_name,
+                module,
+                ..
+            } => format!("{app_name}:{module}:{}$", name.name()),
         }
     }
     pub fn buck2_test_args(&self, target: String, coverage_enabled: bool) -> Vec<String> {
         let mut args = Vec::new();
         match &self.kind {
-            RunnableKind::Test { .. } => {
+            RunnableKind::Test { .. } | RunnableKind::Eunit { .. } => {
                 args.push(target);
                 args.push("--".to_string());
                 args.push("--regex".to_string());
@@ -102,13 +118,51 @@ impl Runnable {
             RunnableKind::Suite => {
                 args.push(target);
             }
-            RunnableKind::Test { .. } => {
+            RunnableKind::Test { .. } | RunnableKind::Eunit { .. } => {
                 args.push(self.id());
             }
         }
         args
     }
 
+    /// The rebar3 task running the test, followed by its arguments.
+    pub fn rebar3_test_args(&self) -> Vec<String> {
+        match &self.kind {
+            RunnableKind::Test {
+                suite, case, group, ..
+            } => {
+                let mut args = vec![
+                    "ct".to_string(),
+                    format!("--suite={suite}"),
+                    format!("--case={case}"),
+                ];
+                if let common_test::GroupName::Name(group) = group {
+                    args.push(format!("--group={group}"));
+                }
+                args
+            }
+            RunnableKind::Suite => vec!["ct".to_string(), format!("--suite={}", self.suite())],
+            RunnableKind::Eunit {
+                generator: false, ..
+            } => vec!["eunit".to_string(), format!("--test={}", self.id())],
+            RunnableKind::Eunit {
+                generator: true, ..
+            } => vec!["eunit".to_string(), format!("--generator={}", self.id())],
+        }
+    }
+
+    /// The rebar3 task running the test in a distributed node named
+    /// `elp_debug`, for the debugger to attach to.
+    pub fn rebar3_debug_args(&self) -> Vec<String> {
+        let mut args = self.rebar3_test_args();
+        args.push("--sname=elp_debug".to_string());
+        args
+    }
+
+    fn suite(&self) -> String {
+        self.nav.name.to_string()
+    }
+
     // The Unicode variation selector is appended to the play button to avoid that
     // the play symbol is transformed into an emoji
     pub fn run_interactive_title(&self) -> String {
@@ -120,6 +174,7 @@ impl Runnable {
                 }
             },
             RunnableKind::Suite => "▶\u{fe0e} Open REPL".to_string(),
+            RunnableKind::Eunit { .. } => "▶\u{fe0e} Run in REPL".to_string(),
         }
     }
     pub fn run_title(&self) -> String {
@@ -131,6 +186,7 @@ impl Runnable {
                 }
             },
             RunnableKind::Suite => "▶\u{fe0e} Run All Tests".to_string(),
+            RunnableKind::Eunit { .. } => "▶\u{fe0e} Run Test".to_string(),
         }
     }
     pub fn debug_title(&self) -> String {
@@ -142,6 +198,7 @@ impl Runnable {
                 }
             },
             RunnableKind::Suite => "▶\u{fe0e} Debug".to_string(),
+            RunnableKind::Eunit { .. } => "▶\u{fe0e} Debug".to_string(),
         }
     }
 }
@@ -164,16 +221,49 @@ pub(crate) fn runnables(
     groups: FxHashMap<SmolStr, GroupDef>,
 ) -> Vec<Runnable> {
     let sema = Semantic::new(db);
-    match common_test::runnables(&sema, file_id, all, groups) {
-        Ok(runnables) => runnables,
-        Err(_) => Vec::new(),
-    }
+    let mut runnables = common_test::runnables(&sema, file_id, all, groups).unwrap_or_default();
+    runnables.extend(eunit_runnables(db, file_id));
+    runnables
+}
+
+/// The EUnit tests of a module: the functions of arity 0 whose name
+/// ends in `_test`, and the generators, ending in `_test_`.
+pub(crate) fn eunit_runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
+    let sema = Semantic::new(db);
+    let (Some(app_name), Some(module)) =
+        (sema.db.file_app_name(file_id), sema.module_name(file_id))
+    else {
+        return Vec::new();
+    };
+    sema.def_map(file_id)
+        .get_functions()
+        .filter(|(name, def)| name.arity() == 0 && def.file.file_id == file_id)
+        .filter_map(|(name, def)| {
+            let generator = if name.name().ends_with("_test_") {
+                true
+            } else if name.name().ends_with("_test") {
+                false
+            } else {
+                return None;
+            };
+            Some(Runnable {
+                nav: def.to_nav(db),
+                kind: RunnableKind::Eunit {
+                    name: name.clone(),
+                    app_name: app_name.clone(),
+                    module: module.to_string(),
+                    generator,
+                },
+            })
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
 
     use elp_ide_db::elp_base_db::FileRange;
+    use expect_test::expect;
     use stdx::trim_indent;
 
     use crate::fixture;
@@ -220,6 +310,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn runnables_eunit() {
+        check_runnables(
+            r#"
+ //- /my_app/test/runnables_tests.erl
+    ~
+    -module(runnables_tests).
+    addition_test() ->
+ %% ^^^^^^^^^^^^^ Run Test
+      2 = 1 + 1.
+    addition_test_() ->
+ %% ^^^^^^^^^^^^^^ Run Test
+      [fun() -> 2 = 1 + 1 end].
+    addition_test(_) ->
+      ok.
+    helper() ->
+      ok.
+    "#,
+        );
+    }
+
+    #[test]
+    fn rebar3_test_args() {
+        let (analysis, pos, _diagnostics_enabled, _annotations) = fixture::annotations(
+            trim_indent(
+                r#"
+ //- /my_app/test/args_tests.erl
+    ~
+    -module(args_tests).
+    plain_test() -> ok.
+    generator_test_() -> [].
+    "#,
+            )
+            .as_str(),
+        );
+        let mut args: Vec<_> = analysis
+            .runnables(pos.file_id)
+            .unwrap()
+            .iter()
+            .map(|runnable| runnable.rebar3_test_args().join(" "))
+            .collect();
+        args.sort();
+        expect![[r#"
+            [
+                "eunit --generator=args_tests:generator_test_",
+                "eunit --test=args_tests:plain_test",
+            ]
+        "#]]
+        .assert_debug_eq(&args);
+    }
+
+    #[test]
+    fn rebar3_debug_args() {
+        let (analysis, pos, _diagnostics_enabled, _annotations) = fixture::annotations(
+            trim_indent(
+                r#"
+ //- /my_app/test/args_tests.erl
+    ~
+    -module(args_tests).
+    plain_test() -> ok.
+    "#,
+            )
+            .as_str(),
+        );
+        let args: Vec<_> = analysis
+            .runnables(pos.file_id)
+            .unwrap()
+            .iter()
+            .map(|runnable| runnable.rebar3_debug_args().join(" "))
+            .collect();
+        expect![[r#"
+            [
+                "eunit --test=args_tests:plain_test --sname=elp_debug",
+            ]
+        "#]]
+        .assert_debug_eq(&args);
+    }
+
+    #[test]
+    fn rebar3_ct_args() {
+        let (analysis, pos, _diagnostics_enabled, _annotations) = fixture::annotations(
+            trim_indent(
+                r#"
+ //- erlang_service
+ //- /my_app/test/args_SUITE.erl
+    ~
+    -module(args_SUITE).
+    -export([all/0, groups/0]).
+    -export([a/1, b/1]).
+    all() -> [a, {group, g}].
+    groups() -> [{g, [], [b]}].
+    a(_Config) -> ok.
+    b(_Config) -> ok.
+    "#,
+            )
+            .as_str(),
+        );
+        let mut args: Vec<_> = analysis
+            .runnables(pos.file_id)
+            .unwrap()
+            .iter()
+            .map(|runnable| runnable.rebar3_test_args().join(" "))
+            .collect();
+        args.sort();
+        expect![[r#"
+            [
+                "ct --suite=args_SUITE",
+                "ct --suite=args_SUITE --case=a",
+                "ct --suite=args_SUITE --case=b --group=g",
+            ]
+        "#]]
+        .assert_debug_eq(&args);
+    }
+
     #[test]
     fn runnables_suite() {
         check_runnables(
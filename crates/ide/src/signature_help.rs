@@ -17,6 +17,7 @@ use elp_ide_db::RootDatabase;
 use elp_syntax::algo;
 use elp_syntax::ast;
 use elp_syntax::AstNode;
+use elp_syntax::SyntaxNode;
 use elp_syntax::TextRange;
 use elp_syntax::TextSize;
 use fxhash::FxHashMap;
@@ -24,6 +25,7 @@ use hir::CallTarget;
 use hir::FunctionDef;
 use hir::InFile;
 use hir::Name;
+use hir::ParamName;
 use hir::Semantic;
 use itertools::Itertools;
 use stdx::format_to;
@@ -41,6 +43,8 @@ pub struct SignatureHelp {
     pub signature: String,
     pub active_parameter: Option<usize>,
     parameters: Vec<TextRange>,
+    /// The variable naming each parameter, as used in `parameters_doc`
+    parameter_names: Vec<String>,
 }
 
 impl SignatureHelp {
@@ -52,14 +56,19 @@ impl SignatureHelp {
         &self.parameters
     }
 
-    fn push_param(&mut self, param: &str) {
+    pub fn parameter_doc(&self, idx: usize) -> Option<&String> {
+        self.parameters_doc.get(self.parameter_names.get(idx)?)
+    }
+
+    fn push_param(&mut self, param: &str, name: &str) {
         if !self.signature.ends_with('(') {
             self.signature.push_str(", ");
         }
         let start = TextSize::of(&self.signature);
         self.signature.push_str(param);
         let end = TextSize::of(&self.signature);
-        self.parameters.push(TextRange::new(start, end))
+        self.parameters.push(TextRange::new(start, end));
+        self.parameter_names.push(name.to_string());
     }
 }
 
@@ -161,7 +170,7 @@ fn signature_help_for_call(
     for (name_arity, _) in functions {
         match def_map.get_function(name_arity) {
             Some(def) => {
-                let help = build_signature_help(
+                res.extend(build_signature_help(
                     db,
                     &sema,
                     file_id,
@@ -169,8 +178,7 @@ fn signature_help_for_call(
                     active_parameter,
                     module_name.clone(),
                     &fun_name,
-                );
-                res.push(help);
+                ));
             }
             None => {
                 // Function could be imported
@@ -178,7 +186,7 @@ fn signature_help_for_call(
                     if let Some(module) = sema.resolve_module_name(file_id, module_name) {
                         let def_map = sema.def_map(module.file.file_id);
                         if let Some(def) = def_map.get_function(name_arity) {
-                            let help = build_signature_help(
+                            res.extend(build_signature_help(
                                 db,
                                 &sema,
                                 module.file.file_id,
@@ -186,8 +194,7 @@ fn signature_help_for_call(
                                 active_parameter,
                                 Some(module_name.clone()),
                                 &fun_name,
-                            );
-                            res.push(help)
+                            ))
                         }
                     }
                 }
@@ -204,27 +211,91 @@ fn build_signature_help(
     active_parameter: Option<usize>,
     module_name: Option<Name>,
     fun_name: &Name,
-) -> SignatureHelp {
+) -> Vec<SignatureHelp> {
     let function_doc = get_function_doc(db, sema, file_id, def);
     let parameters_doc = get_parameters_doc(db, def);
-    let mut help = SignatureHelp {
-        function_doc,
-        parameters_doc,
-        signature: String::new(),
-        parameters: vec![],
-        active_parameter,
+    let new_help = || {
+        let signature = match &module_name {
+            Some(m) => format!("{m}:{fun_name}("),
+            None => format!("{fun_name}("),
+        };
+        SignatureHelp {
+            function_doc: function_doc.clone(),
+            parameters_doc: parameters_doc.clone(),
+            signature,
+            parameters: vec![],
+            parameter_names: vec![],
+            active_parameter,
+        }
     };
-    match &module_name {
-        Some(m) => format_to!(help.signature, "{m}:{fun_name}("),
-        None => format_to!(help.signature, "{fun_name}("),
-    }
-    if let Some(parameters) = def.arg_names(db) {
-        for parameter in parameters {
-            help.push_param(&parameter);
+    let sigs = type_sigs(sema, def);
+    if sigs.is_empty() {
+        let mut help = new_help();
+        if let Some(parameters) = def.arg_names(db) {
+            for parameter in parameters {
+                help.push_param(&parameter, &parameter);
+            }
         }
+        help.signature.push(')');
+        return vec![help];
     }
-    help.signature.push(')');
-    help
+    let param_names = def.param_names(db);
+    sigs.iter()
+        .map(|sig| {
+            let mut help = new_help();
+            for (idx, arg) in sig.args().iter().flat_map(|args| args.args()).enumerate() {
+                let text = single_line(arg.syntax());
+                match (&arg, param_names.get(idx)) {
+                    (ast::Expr::AnnType(ann), _) => {
+                        let name = ann
+                            .var()
+                            .and_then(|var| var.var())
+                            .map(|var| var.text().to_string())
+                            .unwrap_or_default();
+                        help.push_param(&text, &name);
+                    }
+                    (ast::Expr::ExprMax(ast::ExprMax::Var(_)), _) => {
+                        help.push_param(&text, &text);
+                    }
+                    (_, Some(ParamName::Name(name))) => {
+                        help.push_param(&format!("{name} :: {text}"), name.as_str());
+                    }
+                    _ => help.push_param(&text, ""),
+                }
+            }
+            help.signature.push(')');
+            if let Some(ty) = sig.ty() {
+                format_to!(help.signature, " -> {}", single_line(ty.syntax()));
+            }
+            if let Some(guard) = sig.guard() {
+                format_to!(help.signature, " when {}", single_line(guard.syntax()));
+            }
+            help
+        })
+        .collect()
+}
+
+/// The clauses of the `-spec` of the function or, for a callback without
+/// a spec of its own, of the `-callback` of its behaviour.
+fn type_sigs(sema: &Semantic, def: &FunctionDef) -> Vec<ast::TypeSig> {
+    if let Some(spec) = &def.spec {
+        return spec.source(sema.db.upcast()).sigs().collect();
+    }
+    let file_id = def.file.file_id;
+    sema.def_map(file_id)
+        .get_behaviours()
+        .iter()
+        .sorted()
+        .find_map(|behaviour| {
+            let (_module, callbacks) = sema.resolve_behaviour(file_id, behaviour)?;
+            let callback = callbacks.get(&def.name)?;
+            Some(callback.source(sema.db.upcast()).sigs().collect())
+        })
+        .unwrap_or_default()
+}
+
+fn single_line(syntax: &SyntaxNode) -> String {
+    syntax.text().to_string().split_whitespace().join(" ")
 }
 
 fn get_parameters_doc(db: &RootDatabase, def: &FunctionDef) -> FxHashMap<String, String> {
@@ -330,15 +401,15 @@ main() ->
                 -spec add(integer(), integer()) -> integer().
                 ```
                 ------
-                add(This, That)
-                    ^^^^  ----
+                add(This :: integer(), That :: integer()) -> integer()
+                    ^^^^^^^^^^^^^^^^^  -----------------
                 ======
                 ```erlang
                 -spec add(integer(), integer(), integer()) -> integer().
                 ```
                 ------
-                add(This, That, Extra)
-                    ^^^^  ----  -----
+                add(This :: integer(), That :: integer(), Extra :: integer()) -> integer()
+                    ^^^^^^^^^^^^^^^^^  -----------------  ------------------
                 ======
             "#]],
         );
@@ -362,15 +433,15 @@ main() ->
                 -spec add(integer(), integer()) -> integer().
                 ```
                 ------
-                add(This, That)
-                    ^^^^  ----
+                add(This :: integer(), That :: integer()) -> integer()
+                    ^^^^^^^^^^^^^^^^^  -----------------
                 ======
                 ```erlang
                 -spec add(integer(), integer(), integer()) -> integer().
                 ```
                 ------
-                add(This, That, Extra)
-                    ^^^^  ----  -----
+                add(This :: integer(), That :: integer(), Extra :: integer()) -> integer()
+                    ^^^^^^^^^^^^^^^^^  -----------------  ------------------
                 ======
             "#]],
         );
@@ -394,15 +465,15 @@ main() ->
                 -spec add(integer(), integer()) -> integer().
                 ```
                 ------
-                add(This, That)
-                    ----  ^^^^
+                add(This :: integer(), That :: integer()) -> integer()
+                    -----------------  ^^^^^^^^^^^^^^^^^
                 ======
                 ```erlang
                 -spec add(integer(), integer(), integer()) -> integer().
                 ```
                 ------
-                add(This, That, Extra)
-                    ----  ^^^^  -----
+                add(This :: integer(), That :: integer(), Extra :: integer()) -> integer()
+                    -----------------  ^^^^^^^^^^^^^^^^^  ------------------
                 ======
             "#]],
         );
@@ -436,15 +507,15 @@ main() ->
                 -spec add(integer(), integer()) -> integer().
                 ```
                 ------
-                one:add(This, That)
-                        ^^^^  ----
+                one:add(This :: integer(), That :: integer()) -> integer()
+                        ^^^^^^^^^^^^^^^^^  -----------------
                 ======
                 ```erlang
                 -spec add(integer(), integer(), integer()) -> integer().
                 ```
                 ------
-                one:add(This, That, Extra)
-                        ^^^^  ----  -----
+                one:add(This :: integer(), That :: integer(), Extra :: integer()) -> integer()
+                        ^^^^^^^^^^^^^^^^^  -----------------  ------------------
                 ======
             "#]],
         );
@@ -474,15 +545,15 @@ main() ->
                 -spec add(integer(), integer()) -> integer().
                 ```
                 ------
-                one:add(This, That)
-                        ^^^^  ----
+                one:add(This :: integer(), That :: integer()) -> integer()
+                        ^^^^^^^^^^^^^^^^^  -----------------
                 ======
                 ```erlang
                 -spec add(integer(), integer(), integer()) -> integer().
                 ```
                 ------
-                one:add(This, That, Extra)
-                        ^^^^  ----  -----
+                one:add(This :: integer(), That :: integer(), Extra :: integer()) -> integer()
+                        ^^^^^^^^^^^^^^^^^  -----------------  ------------------
                 ======
             "#]],
         );
@@ -512,15 +583,15 @@ main() ->
                 -spec add(integer(), integer()) -> integer().
                 ```
                 ------
-                one:add(This, That)
-                        ----  ^^^^
+                one:add(This :: integer(), That :: integer()) -> integer()
+                        -----------------  ^^^^^^^^^^^^^^^^^
                 ======
                 ```erlang
                 -spec add(integer(), integer(), integer()) -> integer().
                 ```
                 ------
-                one:add(This, That, Extra)
-                        ----  ^^^^  -----
+                one:add(This :: integer(), That :: integer(), Extra :: integer()) -> integer()
+                        -----------------  ^^^^^^^^^^^^^^^^^  ------------------
                 ======
             "#]],
         );
@@ -618,8 +689,8 @@ main() ->
                 -spec add(integer(), integer()) -> integer().
                 ```
                 ------
-                main:add(This, That)
-                         ----  ^^^^
+                main:add(This :: integer(), That :: integer()) -> integer()
+                         -----------------  ^^^^^^^^^^^^^^^^^
                 ------
                 That: The second thing
                 This: The first thing
@@ -628,8 +699,8 @@ main() ->
                 -spec add(integer(), integer(), integer()) -> integer().
                 ```
                 ------
-                main:add(This, That, Extra)
-                         ----  ^^^^  -----
+                main:add(This :: integer(), That :: integer(), Extra :: integer()) -> integer()
+                         -----------------  ^^^^^^^^^^^^^^^^^  ------------------
                 ------
                 Extra: Something more
                 That: The second thing
@@ -666,15 +737,15 @@ main() ->
                 -spec add(integer(), integer()) -> integer().
                 ```
                 ------
-                one:add(This, That)
-                        ^^^^  ----
+                one:add(This :: integer(), That :: integer()) -> integer()
+                        ^^^^^^^^^^^^^^^^^  -----------------
                 ======
                 ```erlang
                 -spec add(integer(), integer(), integer()) -> integer().
                 ```
                 ------
-                one:add(This, That, Extra)
-                        ^^^^  ----  -----
+                one:add(This :: integer(), That :: integer(), Extra :: integer()) -> integer()
+                        ^^^^^^^^^^^^^^^^^  -----------------  ------------------
                 ======
             "#]],
         );
@@ -707,15 +778,75 @@ main() ->
                 -spec add(integer(), integer()) -> integer().
                 ```
                 ------
-                one:add(This, That)
-                        ^^^^  ----
+                one:add(This :: integer(), That :: integer()) -> integer()
+                        ^^^^^^^^^^^^^^^^^  -----------------
                 ======
                 ```erlang
                 -spec add(One :: integer(), integer(), integer()) -> integer().
                 ```
                 ------
-                one:add(One, Arg2, Arg3)
-                        ^^^  ----  ----
+                one:add(One :: integer(), That :: integer(), Extra :: integer()) -> integer()
+                        ^^^^^^^^^^^^^^^^  -----------------  ------------------
+                ======
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_fn_signature_spec_clauses() {
+        check(
+            r#"
+-module(main).
+
+-spec to_list(Bin :: binary()) -> string();
+             (Atom) -> string() when Atom :: atom().
+to_list(X) when is_binary(X) -> binary_to_list(X);
+to_list(X) -> atom_to_list(X).
+
+main() ->
+  to_list(~).
+"#,
+            expect![[r#"
+                ```erlang
+                -spec to_list(Bin :: binary()) -> string();
+                             (Atom) -> string() when Atom :: atom().
+                ```
+                ------
+                to_list(Bin :: binary()) -> string()
+                        ^^^^^^^^^^^^^^^
+                ======
+                ```erlang
+                -spec to_list(Bin :: binary()) -> string();
+                             (Atom) -> string() when Atom :: atom().
+                ```
+                ------
+                to_list(Atom) -> string() when Atom :: atom()
+                        ^^^^
+                ======
+            "#]],
+        );
+    }
+
+    #[test]
+    fn test_fn_signature_callback() {
+        check(
+            r#"
+//- /my_behaviour.erl
+-module(my_behaviour).
+-callback handle(Request :: term(), State :: map()) -> {reply, term(), map()}.
+
+//- /impl.erl
+-module(impl).
+-behaviour(my_behaviour).
+-export([handle/2]).
+handle(Req, State) -> {reply, Req, State}.
+
+main() ->
+  handle(req, ~).
+"#,
+            expect![[r#"
+                handle(Request :: term(), State :: map()) -> {reply, term(), map()}
+                       ---------------  ^^^^^^^^^^^^^
                 ======
             "#]],
         );
@@ -0,0 +1,342 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Extracts the states and transitions of a `gen_statem` module, as used
+//! by `elp statem-graph`.
+//!
+//! The states are the names of the state functions, or the states matched
+//! by the clauses of `handle_event/4`, depending on the callback mode. The
+//! transitions are the `{next_state, State, ...}` tuples the clauses of a
+//! state return, labelled with the event the clause matches.
+
+use std::fmt::Write;
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::RootDatabase;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use fxhash::FxHashSet;
+use hir::DefMap;
+use hir::NameArity;
+use hir::Semantic;
+use itertools::Itertools;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatemGraph {
+    /// The states, in the order they are defined
+    pub states: Vec<String>,
+    /// The state returned by `init/1`, if it is a literal atom
+    pub initial: Option<String>,
+    pub transitions: Vec<Transition>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Transition {
+    /// The state of the clause, `_` when it matches any state
+    pub from: String,
+    pub to: String,
+    /// The event type and content the clause matches, as written
+    pub event: String,
+}
+
+impl StatemGraph {
+    /// Renders the graph in the Graphviz DOT language.
+    pub fn to_dot(&self, name: &str) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "digraph {} {{", quote(name));
+        if let Some(initial) = &self.initial {
+            out.push_str("    \"\" [shape=point];\n");
+            let _ = writeln!(out, "    \"\" -> {};", quote(initial));
+        }
+        for state in &self.states {
+            let _ = writeln!(out, "    {};", quote(state));
+        }
+        for transition in &self.transitions {
+            let _ = writeln!(
+                out,
+                "    {} -> {} [label={}];",
+                quote(&transition.from),
+                quote(&transition.to),
+                quote(&transition.event)
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The state machine of the module, if it implements `gen_statem`.
+pub(crate) fn statem_graph(db: &RootDatabase, file_id: FileId) -> Option<StatemGraph> {
+    let sema = Semantic::new(db);
+    let def_map = sema.def_map(file_id);
+    if !def_map
+        .get_behaviours()
+        .iter()
+        .any(|behaviour| behaviour.as_str() == "gen_statem")
+    {
+        return None;
+    }
+    let clauses = |name: &str, arity: u32| function_clauses(db, &def_map, name, arity);
+
+    let initial = clauses("init", 1).iter().find_map(|clause| {
+        clause
+            .body()?
+            .syntax()
+            .descendants()
+            .filter_map(ast::Tuple::cast)
+            .find_map(|tuple| match tagged(&tuple, "ok")?.as_slice() {
+                [_, state, _, ..] => atom_text(state),
+                _ => None,
+            })
+    });
+
+    let mut states = Vec::new();
+    let mut transitions = Vec::new();
+    if is_handle_event_function(db, &def_map) {
+        for clause in clauses("handle_event", 4) {
+            let Some(args) = clause.args() else { continue };
+            let args: Vec<ast::Expr> = args.args().collect();
+            let [event_type, event_content, state, _] = args.as_slice() else {
+                continue;
+            };
+            let from = atom_text(state).unwrap_or_else(|| "_".to_string());
+            if from != "_" {
+                states.push(from.clone());
+            }
+            let event = format!("{} {}", event_type.syntax(), event_content.syntax());
+            transitions.extend(clause_transitions(&clause, &from, &event));
+        }
+    } else {
+        let not_states: FxHashSet<&str> = ["terminate", "code_change", "format_status"]
+            .into_iter()
+            .collect();
+        let state_functions = def_map
+            .get_functions()
+            .filter(|(name, def)| {
+                def.file.file_id == file_id
+                    && name.arity() == 3
+                    && def_map.is_function_exported(name)
+                    && !not_states.contains(name.name().as_str())
+            })
+            .sorted_by_key(|(_, def)| def.range(db).map(|range| range.start()));
+        for (name, _) in state_functions {
+            let from = name.name().to_string();
+            states.push(from.clone());
+            for clause in clauses(name.name().as_str(), 3) {
+                let Some(args) = clause.args() else { continue };
+                let args: Vec<ast::Expr> = args.args().collect();
+                let [event_type, event_content, _] = args.as_slice() else {
+                    continue;
+                };
+                let event = format!("{} {}", event_type.syntax(), event_content.syntax());
+                transitions.extend(clause_transitions(&clause, &from, &event));
+            }
+        }
+    }
+
+    // States only ever entered, e.g. handled by a catch-all clause
+    for transition in &transitions {
+        if transition.to != "_" && !states.contains(&transition.to) {
+            states.push(transition.to.clone());
+        }
+    }
+    let states = states.into_iter().unique().collect();
+    let transitions = transitions.into_iter().unique().collect();
+    Some(StatemGraph {
+        states,
+        initial,
+        transitions,
+    })
+}
+
+/// The state callbacks of a `gen_statem` module in the `state_functions`
+/// callback mode: the states it starts in or enters, which name its
+/// exported functions of arity 3 handling them.
+pub(crate) fn state_callbacks(db: &RootDatabase, file_id: FileId) -> FxHashSet<String> {
+    let Some(graph) = statem_graph(db, file_id) else {
+        return FxHashSet::default();
+    };
+    if is_handle_event_function(db, &Semantic::new(db).def_map(file_id)) {
+        return FxHashSet::default();
+    }
+    graph
+        .initial
+        .into_iter()
+        .chain(
+            graph
+                .transitions
+                .into_iter()
+                .map(|transition| transition.to),
+        )
+        .collect()
+}
+
+/// Whether `callback_mode/0` returns `handle_event_function`, rather than
+/// `state_functions`.
+fn is_handle_event_function(db: &RootDatabase, def_map: &DefMap) -> bool {
+    function_clauses(db, def_map, "callback_mode", 0)
+        .iter()
+        .any(|clause| {
+            clause.body().is_some_and(|body| {
+                body.syntax()
+                    .descendants()
+                    .filter_map(ast::Atom::cast)
+                    .any(|atom| atom.syntax().text() == "handle_event_function")
+            })
+        })
+}
+
+fn function_clauses(
+    db: &RootDatabase,
+    def_map: &DefMap,
+    name: &str,
+    arity: u32,
+) -> Vec<ast::FunctionClause> {
+    let name = NameArity::new(hir::Name::from_erlang_service(name), arity);
+    def_map
+        .get_function(&name)
+        .map(|def| {
+            def.source(db)
+                .iter()
+                .filter_map(|fun_decl| match fun_decl.clause()? {
+                    ast::FunctionOrMacroClause::FunctionClause(clause) => Some(clause),
+                    ast::FunctionOrMacroClause::MacroCallExpr(_) => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The transitions of the `{next_state, State, ...}` tuples in the body of
+/// the clause, whose state is a literal atom.
+fn clause_transitions(clause: &ast::FunctionClause, from: &str, event: &str) -> Vec<Transition> {
+    let Some(body) = clause.body() else {
+        return Vec::new();
+    };
+    body.syntax()
+        .descendants()
+        .filter_map(ast::Tuple::cast)
+        .filter_map(|tuple| match tagged(&tuple, "next_state")?.as_slice() {
+            [_, state, ..] => atom_text(state),
+            _ => None,
+        })
+        .map(|to| Transition {
+            from: from.to_string(),
+            to,
+            event: event.split_whitespace().join(" "),
+        })
+        .collect()
+}
+
+/// The elements of the tuple, if its first one is the atom `tag`.
+fn tagged(tuple: &ast::Tuple, tag: &str) -> Option<Vec<ast::Expr>> {
+    let elements: Vec<ast::Expr> = tuple.expr().collect();
+    (atom_text(elements.first()?)? == tag).then_some(elements)
+}
+
+fn atom_text(expr: &ast::Expr) -> Option<String> {
+    match expr {
+        ast::Expr::ExprMax(ast::ExprMax::Atom(atom)) => Some(atom.syntax().text().to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::fixture;
+
+    #[track_caller]
+    fn check(fixture: &str, expect: Expect) {
+        let (analysis, pos, _) = fixture::position(fixture);
+        let dot = match analysis.statem_graph(pos.file_id).unwrap() {
+            Some(graph) => graph.to_dot("main"),
+            None => "no graph".to_string(),
+        };
+        expect.assert_eq(&dot);
+    }
+
+    #[test]
+    fn state_functions() {
+        check(
+            r#"
+-module(main).~
+-behaviour(gen_statem).
+-export([init/1, callback_mode/0, locked/3, open/3, terminate/3]).
+init([]) -> {ok, locked, #{}}.
+callback_mode() -> state_functions.
+locked(cast, {button, _}, Data) ->
+    case Data of
+        #{code := ok} -> {next_state, open, Data};
+        _ -> keep_state_and_data
+    end;
+locked({call, From}, status, _Data) ->
+    {keep_state_and_data, [{reply, From, locked}]}.
+open(state_timeout, lock, Data) ->
+    {next_state, locked, Data}.
+terminate(_, _, _) -> ok.
+"#,
+            expect![[r#"
+                digraph "main" {
+                    "" [shape=point];
+                    "" -> "locked";
+                    "locked";
+                    "open";
+                    "locked" -> "open" [label="cast {button, _}"];
+                    "open" -> "locked" [label="state_timeout lock"];
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn handle_event_function() {
+        check(
+            r#"
+-module(main).~
+-behaviour(gen_statem).
+-export([init/1, callback_mode/0, handle_event/4]).
+init([]) -> {ok, idle, []}.
+callback_mode() -> [handle_event_function, state_enter].
+handle_event(cast, start, idle, Data) ->
+    {next_state, running, Data};
+handle_event(cast, stop, _State, Data) ->
+    {next_state, idle, Data}.
+"#,
+            expect![[r#"
+                digraph "main" {
+                    "" [shape=point];
+                    "" -> "idle";
+                    "idle";
+                    "running";
+                    "idle" -> "running" [label="cast start"];
+                    "_" -> "idle" [label="cast stop"];
+                }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn not_a_statem() {
+        check(
+            r#"
+-module(main).~
+-behaviour(gen_server).
+"#,
+            expect!["no graph"],
+        );
+    }
+}
@@ -19,6 +19,7 @@ use elp_ide_db::SymbolKind;
 use elp_syntax::ast;
 use elp_syntax::AstNode;
 use elp_syntax::NodeOrToken;
+use elp_syntax::SyntaxNode;
 use elp_syntax::TextRange;
 use elp_types_db::eqwalizer::types::Type;
 use hir::fold::MacroStrategy;
@@ -30,14 +31,18 @@ use hir::Expr;
 use hir::ExprId;
 use hir::InFile;
 use hir::InFunctionClauseBody;
+use hir::Name;
 use hir::NameArity;
 use hir::Semantic;
 use hir::Strategy;
 
 use self::highlights::Highlights;
 use self::tags::Highlight;
+use crate::injection::injected_strings;
 use crate::HlMod;
 use crate::HlTag;
+use crate::InjectedLanguage;
+use crate::Injection;
 
 #[derive(Debug, Clone, Copy)]
 pub struct HlRange {
@@ -46,17 +51,27 @@ pub struct HlRange {
     pub binding_hash: Option<u64>,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct HighlightConfig {}
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HighlightConfig {
+    /// Functions whose string literal arguments are in another language
+    pub injections: Vec<Injection>,
+}
 
 // Feature: Semantic Syntax Highlighting
 //
 // ELP highlights some code semantically.
 //
-// Initially this is just used for bound variables in patterns
+// Variables, atoms, macros, records and their fields, module names and
+// called functions are classified, the functions of the `erlang` module
+// being marked with a `bif` modifier. On top of this, bound variables in
+// patterns, exported and deprecated functions, and variables of type
+// `dynamic()` get modifiers. String literals holding SQL or JSON are marked with an
+// `injected_sql` or `injected_json` modifier, so that editors can
+// highlight them in that language.
 
 pub(crate) fn highlight(
     db: &RootDatabase,
+    config: &HighlightConfig,
     file_id: FileId,
     types: Option<Arc<Vec<(Pos, Type)>>>,
     range_to_highlight: Option<TextRange>,
@@ -81,13 +96,166 @@ pub(crate) fn highlight(
     };
 
     let mut hl = highlights::Highlights::new(root.text_range());
+    syntactic_highlight(&sema, file_id, &root, range_to_highlight, &mut hl);
     bound_vars_in_pattern_highlight(&sema, file_id, range_to_highlight, &mut hl);
     functions_highlight(&sema, file_id, range_to_highlight, &mut hl);
     deprecated_func_highlight(&sema, file_id, range_to_highlight, &mut hl);
     dynamic_usages_highlight(types, range_to_highlight, &mut hl);
+    injections_highlight(&sema, file_id, config, range_to_highlight, &mut hl);
     hl.to_vec()
 }
 
+/// Classify the tokens from the syntax alone. This comes first, so that
+/// the highlights added for the same ranges afterwards take precedence.
+fn syntactic_highlight(
+    sema: &Semantic,
+    file_id: FileId,
+    root: &SyntaxNode,
+    range_to_highlight: TextRange,
+    hl: &mut Highlights,
+) {
+    let def_map = sema.def_map(file_id);
+    let nodes = || {
+        root.descendants()
+            .filter(|node| range_to_highlight.intersect(node.text_range()).is_some())
+    };
+    let mut add = |range: TextRange, highlight: Highlight| {
+        hl.add(HlRange {
+            range,
+            highlight,
+            binding_hash: None,
+        })
+    };
+    let variable = HlTag::Symbol(SymbolKind::Variable).into();
+    // Variables and atoms first, for the context they are in to win. The
+    // name of a macro is highlighted as a whole, with its `?`.
+    for node in nodes() {
+        let in_macro_name = node
+            .parent()
+            .map_or(false, |parent| ast::MacroName::can_cast(parent.kind()));
+        if in_macro_name {
+            continue;
+        }
+        if ast::Var::can_cast(node.kind()) {
+            add(node.text_range(), variable);
+        } else if ast::Atom::can_cast(node.kind()) {
+            add(node.text_range(), HlTag::Atom.into());
+        }
+    }
+    let module = HlTag::Symbol(SymbolKind::Module).into();
+    let record = HlTag::Symbol(SymbolKind::Record).into();
+    let field = HlTag::Symbol(SymbolKind::RecordField).into();
+    let define = HlTag::Symbol(SymbolKind::Define).into();
+    let function = HlTag::Symbol(SymbolKind::Function);
+    let type_ = HlTag::Symbol(SymbolKind::Type).into();
+    for node in nodes() {
+        if let Some(clause) = ast::FunctionClause::cast(node.clone()) {
+            if let Some(name) = clause.name() {
+                add(name.syntax().text_range(), function.into());
+            }
+        } else if let Some(attr) = ast::ModuleAttribute::cast(node.clone()) {
+            if let Some(name) = attr.name() {
+                add(name.syntax().text_range(), module);
+            }
+        } else if let Some(attr) = ast::BehaviourAttribute::cast(node.clone()) {
+            if let Some(name) = attr.name() {
+                add(name.syntax().text_range(), module);
+            }
+        } else if let Some(remote_module) = ast::RemoteModule::cast(node.clone()) {
+            if let Some(ast::ExprMax::Atom(atom)) = remote_module.module() {
+                add(atom.syntax().text_range(), module);
+            }
+        } else if let Some(call) = ast::Call::cast(node.clone()) {
+            if in_type(&node) {
+                // A type, such as `integer()` or `dict:dict()`
+                if let Some(ast::Expr::ExprMax(ast::ExprMax::Atom(name))) = call.expr() {
+                    add(name.syntax().text_range(), type_);
+                } else if let Some(ast::Expr::Remote(remote)) = call.expr() {
+                    if let Some(ast::ExprMax::Atom(name)) = remote.fun() {
+                        add(name.syntax().text_range(), type_);
+                    }
+                }
+                continue;
+            }
+            let arity = call.args().map_or(0, |args| args.args().count());
+            match call.expr() {
+                Some(ast::Expr::ExprMax(ast::ExprMax::Atom(name))) => {
+                    let is_bif = name.text().map_or(false, |name| {
+                        ast::erlang_funs().contains(&(name.as_str(), arity))
+                            && def_map
+                                .get_function(&NameArity::new(
+                                    Name::from_erlang_service(&name),
+                                    arity as u32,
+                                ))
+                                .is_none()
+                    });
+                    let highlight = if is_bif {
+                        function | HlMod::Bif
+                    } else {
+                        function.into()
+                    };
+                    add(name.syntax().text_range(), highlight);
+                }
+                Some(ast::Expr::Remote(remote)) => {
+                    if let Some(ast::ExprMax::Atom(name)) = remote.fun() {
+                        let is_erlang = match remote.module().and_then(|module| module.module()) {
+                            Some(ast::ExprMax::Atom(module)) => {
+                                module.text().as_deref() == Some("erlang")
+                            }
+                            _ => false,
+                        };
+                        let highlight = if is_erlang {
+                            function | HlMod::Bif
+                        } else {
+                            function.into()
+                        };
+                        add(name.syntax().text_range(), highlight);
+                    }
+                }
+                _ => {}
+            }
+        } else if let Some(macro_call) = ast::MacroCallExpr::cast(node.clone()) {
+            if let Some(name) = macro_call.name() {
+                // Include the `?`
+                let range =
+                    TextRange::new(node.text_range().start(), name.syntax().text_range().end());
+                add(range, define);
+            }
+        } else if let Some(define_attr) = ast::PpDefine::cast(node.clone()) {
+            if let Some(name) = define_attr.lhs().and_then(|lhs| lhs.name()) {
+                add(name.syntax().text_range(), define);
+            }
+        } else if let Some(decl) = ast::RecordDecl::cast(node.clone()) {
+            if let Some(name) = decl.name() {
+                add(name.syntax().text_range(), record);
+            }
+        } else if let Some(name) = ast::RecordName::cast(node.clone()) {
+            if let Some(name) = name.name() {
+                add(name.syntax().text_range(), record);
+            }
+        } else if let Some(name) = ast::RecordFieldName::cast(node.clone()) {
+            if let Some(name) = name.name() {
+                add(name.syntax().text_range(), field);
+            }
+        } else if let Some(record_field) = ast::RecordField::cast(node) {
+            if let Some(name) = record_field.name() {
+                add(name.syntax().text_range(), field);
+            }
+        }
+    }
+}
+
+fn in_type(node: &SyntaxNode) -> bool {
+    node.ancestors().any(|ancestor| {
+        let kind = ancestor.kind();
+        ast::Spec::can_cast(kind)
+            || ast::Callback::can_cast(kind)
+            || ast::TypeAlias::can_cast(kind)
+            || ast::Opaque::can_cast(kind)
+            || ast::FieldType::can_cast(kind)
+    })
+}
+
 fn bound_vars_in_pattern_highlight(
     sema: &Semantic,
     file_id: FileId,
@@ -287,6 +455,28 @@ fn dynamic_usages_highlight(
     }
 }
 
+fn injections_highlight(
+    sema: &Semantic,
+    file_id: FileId,
+    config: &HighlightConfig,
+    range_to_highlight: TextRange,
+    hl: &mut Highlights,
+) {
+    for injected in injected_strings(sema, file_id, &config.injections) {
+        if range_to_highlight.intersect(injected.range).is_some() {
+            let modifier = match injected.language {
+                InjectedLanguage::Sql => HlMod::InjectedSql,
+                InjectedLanguage::Json => HlMod::InjectedJson,
+            };
+            hl.add(HlRange {
+                range: injected.range,
+                highlight: HlTag::StringLiteral | modifier,
+                binding_hash: None,
+            });
+        }
+    }
+}
+
 fn is_dynamic(t: &Type) -> bool {
     match t {
         Type::DynamicType => true,
@@ -307,22 +497,30 @@ mod tests {
     use stdx::trim_indent;
 
     use crate::syntax_highlighting::highlight;
+    use crate::HighlightConfig;
     use crate::HlTag;
+    use crate::InjectedLanguage;
+    use crate::Injection;
+    use crate::MFA;
 
     // These are tests of the specific modifier functionality.  When
     // we go all-in with semantic tokens, we can consider bringing
     // over the RA test mechanism which compares an HTML file.
     #[track_caller]
     fn check_highlights(fixture: &str) {
-        do_check_highlights(fixture, false);
+        do_check_highlights(&HighlightConfig::default(), fixture, false);
     }
     #[track_caller]
     fn check_highlights_eqwalizer(fixture: &str) {
-        do_check_highlights(fixture, true);
+        do_check_highlights(&HighlightConfig::default(), fixture, true);
+    }
+    #[track_caller]
+    fn check_highlights_with_config(config: &HighlightConfig, fixture: &str) {
+        do_check_highlights(config, fixture, false);
     }
 
     #[track_caller]
-    fn do_check_highlights(fixture: &str, provide_types: bool) {
+    fn do_check_highlights(config: &HighlightConfig, fixture: &str, provide_types: bool) {
         let fixture = trim_indent(fixture);
         let (ranges, fixture) = extract_tags(fixture.trim_start(), "tag");
         let range = if !ranges.is_empty() {
@@ -345,10 +543,10 @@ mod tests {
         } else {
             None
         };
-        let highlights = highlight(&db, file_id, types, range);
+        let highlights = highlight(&db, config, file_id, types, range);
         let ranges: Vec<_> = highlights
             .iter()
-            .filter(|h| h.highlight != HlTag::None.into()) // Means with no modifiers either
+            .filter(|h| !h.highlight.mods.is_empty())
             .map(|h| {
                 let mods: Vec<_> = h.highlight.mods.iter().map(|m| format!("{m}")).collect();
                 (h.range, mods.join(","))
@@ -358,6 +556,85 @@ mod tests {
         assert_eq!(expected, ranges);
     }
 
+    /// Check the tag and modifiers of every highlighted range
+    #[track_caller]
+    fn check_highlight_tags(fixture: &str) {
+        let fixture = trim_indent(fixture);
+        let (db, fixture) = RootDatabase::with_fixture(&fixture);
+        let expected: Vec<_> = fixture
+            .annotations(&db)
+            .into_iter()
+            .map(|(fr, tag)| (fr.range, tag))
+            .sorted_by(|a, b| a.0.start().cmp(&b.0.start()))
+            .collect();
+        let file_id = fixture.files[0];
+        let highlights = highlight(&db, &HighlightConfig::default(), file_id, None, None);
+        let ranges: Vec<_> = highlights
+            .iter()
+            .filter(|h| h.highlight != HlTag::None.into())
+            .map(|h| (h.range, h.highlight.to_string()))
+            .sorted_by(|a, b| a.0.start().cmp(&b.0.start()))
+            .collect();
+        assert_eq!(expected, ranges);
+    }
+
+    #[test]
+    fn highlight_tags_expressions() {
+        check_highlight_tags(
+            r#"
+              g(X) ->
+           %% ^function
+           %%   ^variable
+                lists:reverse([ok, X]),
+           %%   ^^^^^module
+           %%         ^^^^^^^function
+           %%                  ^^atom
+           %%                      ^variable
+                erlang:length(X) + length(X).
+           %%   ^^^^^^module
+           %%          ^^^^^^function.bif
+           %%                 ^variable
+           %%                      ^^^^^^function.bif
+           %%                             ^variable
+            "#,
+        )
+    }
+
+    #[test]
+    fn highlight_tags_records_and_macros() {
+        check_highlight_tags(
+            r#"
+              -define(MAX, 10).
+           %%         ^^^constant
+              h(S) ->
+           %% ^function
+           %%   ^variable
+                S#state{count = ?MAX}.
+           %%   ^variable
+           %%     ^^^^^struct
+           %%           ^^^^^struct
+           %%                   ^^^^constant
+            "#,
+        )
+    }
+
+    #[test]
+    fn highlight_tags_local_function_shadows_bif() {
+        check_highlight_tags(
+            r#"
+              length(L) -> L.
+           %% ^^^^^^function
+           %%        ^variable
+           %%              ^variable
+              k(L) -> length(L).
+           %% ^function
+           %%   ^variable
+           %%         ^^^^^^function
+           %%                ^variable
+            "#,
+        )
+    }
+
     #[test]
     fn highlights_1() {
         check_highlights(
@@ -426,6 +703,25 @@ mod tests {
         )
     }
 
+    #[test]
+    fn injected_highlight() {
+        check_highlights_with_config(
+            &HighlightConfig {
+                injections: vec![Injection {
+                    mfa: MFA::new("epgsql", "squery", 2),
+                    language: InjectedLanguage::Sql,
+                }],
+            },
+            r#"
+              f(C) ->
+                epgsql:squery(C, "SELECT * FROM users"),
+           %%                    ^^^^^^^^^^^^^^^^^^^^^injected_sql
+                %% language=json
+                <<"{\"id\": 1}">>.
+           %%     ^^^^^^^^^^^^^injected_json"#,
+        )
+    }
+
     #[test]
     fn eqwalizer_dynamic_highlight() {
         if otp_supported_by_eqwalizer() {
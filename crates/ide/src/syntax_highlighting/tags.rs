@@ -28,6 +28,8 @@ pub struct HlMods(u32);
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum HlTag {
     Symbol(SymbolKind),
+    Atom,
+    StringLiteral,
 
     // For things which don't have a specific highlight. This is the
     // default for anything we do not specifically set, and maps to VS Code `generic` type
@@ -49,6 +51,11 @@ pub enum HlMod {
     DeprecatedFunction,
     // Eqwalizer Dynamic type
     TypeDynamic,
+    // String literal holding code in another language
+    InjectedSql,
+    InjectedJson,
+    // Function of the `erlang` module, auto-imported or called remotely
+    Bif,
 }
 
 impl HlTag {
@@ -65,7 +72,10 @@ impl HlTag {
                 SymbolKind::Define => "constant",
                 SymbolKind::Variable => "variable",
                 SymbolKind::Callback => "function",
+                SymbolKind::Behaviour => "interface",
             },
+            HlTag::Atom => "atom",
+            HlTag::StringLiteral => "string",
             HlTag::None => "none",
         }
     }
@@ -78,11 +88,14 @@ impl fmt::Display for HlTag {
 }
 
 impl HlMod {
-    const ALL: &'static [HlMod; 4] = &[
+    const ALL: &'static [HlMod; 7] = &[
         HlMod::Bound,
         HlMod::ExportedFunction,
         HlMod::DeprecatedFunction,
         HlMod::TypeDynamic,
+        HlMod::InjectedSql,
+        HlMod::InjectedJson,
+        HlMod::Bif,
     ];
 
     fn as_str(self) -> &'static str {
@@ -91,6 +104,9 @@ impl HlMod {
             HlMod::ExportedFunction => "exported_function",
             HlMod::DeprecatedFunction => "deprecated_function",
             HlMod::TypeDynamic => "type_dynamic",
+            HlMod::InjectedSql => "injected_sql",
+            HlMod::InjectedJson => "injected_json",
+            HlMod::Bif => "bif",
         }
     }
 
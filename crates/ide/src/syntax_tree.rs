@@ -0,0 +1,97 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Dumps the parse tree of a file, as used by `elp/viewSyntaxTree`, to
+//! see how ELP parses a piece of code.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_ide_db::RootDatabase;
+use elp_syntax::AstNode;
+use elp_syntax::NodeOrToken;
+use elp_syntax::TextRange;
+
+/// The tree of the smallest node covering the range, or of the whole file
+/// when there is no range.
+pub(crate) fn syntax_tree(db: &RootDatabase, file_id: FileId, range: Option<TextRange>) -> String {
+    let source = db.parse(file_id).tree();
+    let root = source.syntax();
+    let node = match range.filter(|range| root.text_range().contains_range(*range)) {
+        Some(range) => match root.covering_element(range) {
+            NodeOrToken::Node(node) => node,
+            NodeOrToken::Token(token) => token.parent().unwrap_or_else(|| root.clone()),
+        },
+        None => root.clone(),
+    };
+    format!("{node:#?}")
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_ide_db::elp_base_db::fixture::RangeOrOffset;
+    use elp_ide_db::elp_base_db::fixture::WithFixture;
+    use elp_ide_db::RootDatabase;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::AnalysisHost;
+
+    #[track_caller]
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id, range_or_offset) = RootDatabase::with_range_or_offset(fixture);
+        let range = match range_or_offset {
+            RangeOrOffset::Range(range) => Some(range),
+            RangeOrOffset::Offset(_) => None,
+        };
+        let analysis = AnalysisHost { db }.analysis();
+        let tree = analysis.syntax_tree(file_id, range).unwrap();
+        expect.assert_eq(&tree);
+    }
+
+    #[test]
+    fn whole_file() {
+        check(
+            r#"
+-module(m).~
+"#,
+            expect![[r#"
+                SOURCE_FILE@0..12
+                  MODULE_ATTRIBUTE@0..11
+                    ANON_DASH@0..1 "-"
+                    ANON_MODULE@1..7 "module"
+                    ANON_LPAREN@7..8 "("
+                    ATOM@8..9
+                      ATOM@8..9 "m"
+                    ANON_RPAREN@9..10 ")"
+                    ANON_DOT@10..11 "."
+                  WHITESPACE@11..12 "\n"
+            "#]],
+        );
+    }
+
+    #[test]
+    fn selection() {
+        check(
+            r#"
+-module(m).
+f(X) -> ~X + 1~.
+"#,
+            expect![[r#"
+                BINARY_OP_EXPR@20..25
+                  VAR@20..21
+                    VAR@20..21 "X"
+                  WHITESPACE@21..22 " "
+                  ANON_PLUS@22..23 "+"
+                  WHITESPACE@23..24 " "
+                  INTEGER@24..25
+                    INTEGER@24..25 "1"
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,211 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Annotation-based checks of diagnostics and their fixes, shared by the
+//! tests of ELP and by `elp_ide_testing`, which exposes them to projects
+//! writing ad-hoc lints.
+
+use elp_ide_db::elp_base_db::fixture::extract_annotations;
+use elp_ide_db::elp_base_db::fixture::remove_annotations;
+use elp_ide_db::elp_base_db::fixture::WithFixture;
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::SourceDatabaseExt;
+use elp_ide_db::RootDatabase;
+use elp_project_model::test_fixture::DiagnosticsEnabled;
+use expect_test::Expect;
+use text_edit::TextRange;
+
+use crate::diagnostics::AdhocSemanticDiagnostics;
+use crate::diagnostics::Diagnostic;
+use crate::diagnostics::DiagnosticCode;
+use crate::diagnostics::DiagnosticsConfig;
+use crate::diagnostics::RemoveElpReported;
+use crate::diagnostics::Severity;
+use crate::diagnostics_collection::DiagnosticCollection;
+use crate::Analysis;
+use crate::AnalysisHost;
+
+/// Checks that the diagnostics of each file of the fixture match its
+/// annotations.
+#[track_caller]
+pub fn check_diagnostics_with_config_and_ad_hoc(
+    config: DiagnosticsConfig,
+    adhoc_semantic_diagnostics: &Vec<&dyn AdhocSemanticDiagnostics>,
+    elp_fixture: &str,
+) {
+    let (db, files, diagnostics_enabled) = RootDatabase::with_many_files(elp_fixture);
+    let host = AnalysisHost { db };
+    let analysis = host.analysis();
+    for file_id in files {
+        let diagnostics = diagnostics_for(
+            &analysis,
+            file_id,
+            &config,
+            adhoc_semantic_diagnostics,
+            &diagnostics_enabled,
+        );
+        let diagnostics = diagnostics.diagnostics_for(file_id);
+
+        let mut expected = extract_annotations(&analysis.db.file_text(file_id));
+        expected.sort_by_key(|(r1, _)| r1.start());
+        let actual = diagnostics_to_annotations(diagnostics);
+        assert_eq!(expected, actual);
+    }
+}
+
+/// Whether the fixes checked include the assists offered as code
+/// actions on the diagnostic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IncludeCodeActionAssists {
+    Yes,
+    No,
+}
+
+/// Checks that applying the `nth` fix of the last diagnostic of the file
+/// with the cursor gives `fixture_after`. The fix must apply to a range
+/// containing the cursor.
+#[track_caller]
+pub fn check_nth_fix(
+    nth: usize,
+    fixture_before: &str,
+    fixture_after: Expect,
+    config: DiagnosticsConfig,
+    adhoc_semantic_diagnostics: &Vec<&dyn AdhocSemanticDiagnostics>,
+    include_assists: IncludeCodeActionAssists,
+) {
+    let (db, pos, diagnostics_enabled) = RootDatabase::with_position(fixture_before);
+    let analysis = AnalysisHost { db }.analysis();
+
+    let diagnostics = diagnostics_for(
+        &analysis,
+        pos.file_id,
+        &config,
+        adhoc_semantic_diagnostics,
+        &diagnostics_enabled,
+    );
+    let diagnostic = diagnostics
+        .diagnostics_for(pos.file_id)
+        .into_iter()
+        .last()
+        .expect("no diagnostics")
+        .clone();
+    let fixes = match include_assists {
+        IncludeCodeActionAssists::Yes => diagnostic.get_diagnostic_fixes(&analysis.db, pos.file_id),
+        IncludeCodeActionAssists::No => diagnostic.fixes.expect("diagnostic misses fixes"),
+    };
+    let fix = &fixes[nth];
+    let actual = {
+        let source_change = fix.source_change.as_ref().unwrap();
+        let file_id = *source_change.source_file_edits.keys().next().unwrap();
+        let mut actual = analysis.db.file_text(file_id).to_string();
+
+        for edit in source_change.source_file_edits.values() {
+            edit.apply(&mut actual);
+        }
+        actual
+    };
+    let actual = remove_annotations(None, &actual);
+    assert!(
+        fix.target.contains_inclusive(pos.offset),
+        "diagnostic fix range {:?} does not touch cursor position {:?}",
+        fix.target,
+        pos.offset
+    );
+    fixture_after.assert_eq(&actual);
+}
+
+/// The diagnostics as annotations, `💡` marking the ones with fixes.
+pub fn diagnostics_to_annotations(diagnostics: Vec<Diagnostic>) -> Vec<(TextRange, String)> {
+    let mut actual = diagnostics
+        .into_iter()
+        .map(|d| {
+            let mut annotation = String::new();
+            if let Some(fixes) = &d.fixes {
+                assert!(!fixes.is_empty());
+                annotation.push_str("💡 ")
+            }
+            annotation.push_str(match d.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::WeakWarning => "weak",
+                Severity::Information => "information",
+            });
+            annotation.push_str(": ");
+            annotation.push_str(&convert_diagnostic_message(&d));
+            (d.range, annotation)
+        })
+        .collect::<Vec<_>>();
+    actual.sort_by_key(|(range, _)| range.start());
+    actual
+}
+
+fn convert_diagnostic_message(d: &Diagnostic) -> String {
+    match &d.code {
+        DiagnosticCode::Eqwalizer(_) => d.code.as_code(),
+        _ => d.message.clone(),
+    }
+}
+
+/// The diagnostics of the file, from the sources enabled at the top of
+/// the fixture, native ones by default.
+// TODO: Further up the stack, once all diagnostics sources populated:
+//   Move to Analysis, using ? not unwrap()
+//   And then used in elp_lint too
+pub fn diagnostics_for(
+    analysis: &Analysis,
+    file_id: FileId,
+    config: &DiagnosticsConfig,
+    adhoc_semantic_diagnostics: &Vec<&dyn AdhocSemanticDiagnostics>,
+    diagnostics_enabled: &DiagnosticsEnabled,
+) -> DiagnosticCollection {
+    let mut diagnostics = DiagnosticCollection::default();
+    let DiagnosticsEnabled {
+        use_native,
+        use_erlang_service,
+        use_eqwalizer,
+        use_ct,
+        use_edoc,
+        tmp_dir: _,
+    } = diagnostics_enabled;
+    if *use_native {
+        diagnostics.set_native(
+            file_id,
+            analysis
+                .native_diagnostics(config, adhoc_semantic_diagnostics, file_id)
+                .unwrap(),
+        );
+    }
+    if *use_erlang_service {
+        let erlang_service_diagnostics = analysis
+            .erlang_service_diagnostics(file_id, config, RemoveElpReported::Yes)
+            .unwrap();
+        for (file_id, diags) in erlang_service_diagnostics {
+            diagnostics.set_erlang_service(file_id, diags)
+        }
+    }
+    if *use_eqwalizer {
+        let include_generated = elp_types_db::IncludeGenerated::Yes;
+        if let Some(diags) = analysis
+            .eqwalizer_diagnostics_for_file(file_id, include_generated)
+            .unwrap()
+        {
+            diagnostics.set_eqwalizer(file_id, diags);
+        }
+    }
+    if *use_ct {
+        diagnostics.set_ct(file_id, analysis.ct_diagnostics(file_id, config).unwrap());
+    }
+    if *use_edoc {
+        let edoc_diagnostics = analysis.edoc_diagnostics(file_id).unwrap();
+        for (file_id, diags) in edoc_diagnostics {
+            diagnostics.set_edoc(file_id, diags);
+        }
+    }
+    diagnostics
+}
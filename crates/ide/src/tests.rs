@@ -22,15 +22,17 @@ use elp_ide_db::RootDatabase;
 use elp_project_model::test_fixture::trim_indent;
 use expect_test::Expect;
 use itertools::Itertools;
-use text_edit::TextRange;
 
 use crate::diagnostics;
 use crate::diagnostics::AdhocSemanticDiagnostics;
 use crate::diagnostics::Diagnostic;
 use crate::diagnostics::DiagnosticCode;
 use crate::diagnostics::LabeledDiagnostics;
-use crate::diagnostics::Severity;
 use crate::fixture;
+pub(crate) use crate::testing::check_diagnostics_with_config_and_ad_hoc;
+pub(crate) use crate::testing::check_nth_fix;
+use crate::testing::diagnostics_to_annotations;
+pub(crate) use crate::testing::IncludeCodeActionAssists;
 use crate::Analysis;
 use crate::AnalysisHost;
 use crate::DiagnosticsConfig;
@@ -169,12 +171,6 @@ pub(crate) fn check_fix_with_config_and_adhoc(
     );
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum IncludeCodeActionAssists {
-    Yes,
-    No,
-}
-
 #[track_caller]
 pub(crate) fn check_fix_including_assists(fixture_before: &str, fixture_after: Expect) {
     let config = DiagnosticsConfig::default().set_experimental(true);
@@ -188,55 +184,6 @@ pub(crate) fn check_fix_including_assists(fixture_before: &str, fixture_after: E
     )
 }
 
-#[track_caller]
-pub(crate) fn check_nth_fix(
-    nth: usize,
-    fixture_before: &str,
-    fixture_after: Expect,
-    config: DiagnosticsConfig,
-    adhoc_semantic_diagnostics: &Vec<&dyn AdhocSemanticDiagnostics>,
-    include_assists: IncludeCodeActionAssists,
-) {
-    let (analysis, pos, diagnostics_enabled) = fixture::position(fixture_before);
-
-    let diagnostics = fixture::diagnostics_for(
-        &analysis,
-        pos.file_id,
-        &config,
-        &adhoc_semantic_diagnostics,
-        &diagnostics_enabled,
-    );
-    let diagnostic = diagnostics
-        .diagnostics_for(pos.file_id)
-        .into_iter()
-        .last()
-        .expect("no diagnostics")
-        .clone();
-    let fixes = match include_assists {
-        IncludeCodeActionAssists::Yes => diagnostic.get_diagnostic_fixes(&analysis.db, pos.file_id),
-        IncludeCodeActionAssists::No => diagnostic.fixes.expect("diagnostic misses fixes"),
-    };
-    let fix = &fixes[nth];
-    let actual = {
-        let source_change = fix.source_change.as_ref().unwrap();
-        let file_id = *source_change.source_file_edits.keys().next().unwrap();
-        let mut actual = analysis.db.file_text(file_id).to_string();
-
-        for edit in source_change.source_file_edits.values() {
-            edit.apply(&mut actual);
-        }
-        actual
-    };
-    let actual = remove_annotations(None, &actual);
-    assert!(
-        fix.target.contains_inclusive(pos.offset),
-        "diagnostic fix range {:?} does not touch cursor position {:?}",
-        fix.target,
-        pos.offset
-    );
-    fixture_after.assert_eq(&actual);
-}
-
 /// Takes a multi-file input fixture with annotated cursor positions,
 /// and checks that:
 ///  * a diagnostic is produced
@@ -284,7 +231,7 @@ pub(crate) fn check_specific_fix_with_config_and_adhoc(
     let diagnostics = diagnostics.diagnostics_for(pos.file_id);
 
     let expected = extract_annotations(&analysis.db.file_text(pos.file_id));
-    let actual = convert_diagnostics_to_annotations(diagnostics.clone());
+    let actual = diagnostics_to_annotations(diagnostics.clone());
     assert_eq!(expected, actual);
 
     let fix: &Assist = if let Some(label) = assist_label {
@@ -345,37 +292,6 @@ pub(crate) fn check_diagnostics(fixture: &str) {
     check_diagnostics_with_config(config, fixture)
 }
 
-fn convert_diagnostics_to_annotations(diagnostics: Vec<Diagnostic>) -> Vec<(TextRange, String)> {
-    let mut actual = diagnostics
-        .into_iter()
-        .map(|d| {
-            let mut annotation = String::new();
-            if let Some(fixes) = &d.fixes {
-                assert!(!fixes.is_empty());
-                annotation.push_str("💡 ")
-            }
-            annotation.push_str(match d.severity {
-                Severity::Error => "error",
-                Severity::Warning => "warning",
-                Severity::WeakWarning => "weak",
-                Severity::Information => "information",
-            });
-            annotation.push_str(": ");
-            annotation.push_str(&&convert_diagnostic_message(&d));
-            (d.range, annotation)
-        })
-        .collect::<Vec<_>>();
-    actual.sort_by_key(|(range, _)| range.start());
-    actual
-}
-
-fn convert_diagnostic_message(d: &Diagnostic) -> String {
-    match &d.code {
-        DiagnosticCode::Eqwalizer(_) => d.code.as_code(),
-        _ => d.message.clone(),
-    }
-}
-
 #[track_caller]
 pub(crate) fn check_ct_diagnostics(elp_fixture: &str) {
     let (analysis, pos, diagnostics_enabled) = fixture::position(elp_fixture);
@@ -386,7 +302,7 @@ pub(crate) fn check_ct_diagnostics(elp_fixture: &str) {
         fixture::diagnostics_for(&analysis, file_id, &config, &vec![], &diagnostics_enabled);
     let diagnostics = diagnostics.diagnostics_for(file_id);
     let expected = extract_annotations(&analysis.db.file_text(file_id));
-    let actual = convert_diagnostics_to_annotations(diagnostics);
+    let actual = diagnostics_to_annotations(diagnostics);
     assert_eq!(expected, actual);
 }
 
@@ -395,32 +311,6 @@ pub(crate) fn check_diagnostics_with_config(config: DiagnosticsConfig, elp_fixtu
     check_diagnostics_with_config_and_ad_hoc(config, &vec![], elp_fixture);
 }
 
-#[track_caller]
-pub(crate) fn check_diagnostics_with_config_and_ad_hoc(
-    config: DiagnosticsConfig,
-    adhoc_semantic_diagnostics: &Vec<&dyn AdhocSemanticDiagnostics>,
-    elp_fixture: &str,
-) {
-    let (db, files, diagnostics_enabled) = RootDatabase::with_many_files(elp_fixture);
-    let host = AnalysisHost { db };
-    let analysis = host.analysis();
-    for file_id in files {
-        let diagnostics = fixture::diagnostics_for(
-            &analysis,
-            file_id,
-            &config,
-            adhoc_semantic_diagnostics,
-            &diagnostics_enabled,
-        );
-        let diagnostics = diagnostics.diagnostics_for(file_id);
-
-        let mut expected = extract_annotations(&analysis.db.file_text(file_id));
-        expected.sort_by_key(|(r1, _)| r1.start());
-        let actual = convert_diagnostics_to_annotations(diagnostics);
-        assert_eq!(expected, actual);
-    }
-}
-
 #[track_caller]
 pub(crate) fn check_filtered_diagnostics(elp_fixture: &str, filter: &dyn Fn(&Diagnostic) -> bool) {
     let config = DiagnosticsConfig::default();
@@ -452,7 +342,7 @@ pub(crate) fn check_filtered_diagnostics_with_config(
             .collect();
         let mut expected = extract_annotations(&analysis.db.file_text(file_id));
         expected.sort_by_key(|(r1, _)| r1.start());
-        let actual = convert_diagnostics_to_annotations(diagnostics);
+        let actual = diagnostics_to_annotations(diagnostics);
         assert_eq!(expected, actual);
     }
 }
@@ -472,7 +362,7 @@ pub(crate) fn check_diagnostics_with_config_and_extra(
 
         let mut expected = extract_annotations(&analysis.db.file_text(file_id));
         expected.sort_by_key(|(r1, _)| r1.start());
-        let actual = convert_diagnostics_to_annotations(diagnostics);
+        let actual = diagnostics_to_annotations(diagnostics);
         assert_eq!(expected, actual);
     }
 }
@@ -604,3 +494,33 @@ fn check_call_hierarchy_outgoing_calls(fixture: &str) {
     expected.sort_by_key(cmp);
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn enclosing_function() {
+    let (analysis, position, _) = fixture::position(
+        r#"
+-module(main).
+foo(0) -> ok;
+foo(N) -> ba~r(N).
+bar(N) -> N.
+"#,
+    );
+    let (function, range) = analysis.enclosing_function(position).unwrap().unwrap();
+    assert_eq!(function.to_string(), "foo/1");
+    let text = analysis.file_text(position.file_id).unwrap();
+    let text = &text[range];
+    assert!(text.starts_with("foo(0) -> ok;"), "{text}");
+    assert!(text.contains("foo(N) -> bar(N)"), "{text}");
+    assert!(!text.contains("bar(N) -> N"), "{text}");
+}
+
+#[test]
+fn no_enclosing_function() {
+    let (analysis, position, _) = fixture::position(
+        r#"
+-modu~le(main).
+foo() -> ok.
+"#,
+    );
+    assert_eq!(analysis.enclosing_function(position).unwrap(), None);
+}
@@ -0,0 +1,168 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The hierarchy of behaviours: a behaviour is the supertype of the
+//! callback modules declaring it with `-behaviour`.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::elp_base_db::FilePosition;
+use elp_ide_db::RootDatabase;
+use elp_syntax::algo;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use hir::File;
+use hir::InFile;
+use hir::Module;
+use hir::Semantic;
+
+use crate::navigation_target::ToNav;
+use crate::NavigationTarget;
+
+/// The module at `position`: the behaviour of a `-behaviour` attribute,
+/// or the module itself from its `-module` attribute or a `-callback`.
+pub(crate) fn type_hierarchy_prepare(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<NavigationTarget> {
+    let sema = Semantic::new(db);
+    let source_file = sema.parse(position.file_id);
+    let syntax = source_file.value.syntax();
+    if let Some(behaviour) =
+        algo::find_node_at_offset::<ast::BehaviourAttribute>(syntax, position.offset)
+    {
+        let module = sema.to_def(InFile::new(position.file_id, &behaviour))?;
+        return Some(module.to_nav(db));
+    }
+    let in_module_attribute =
+        algo::find_node_at_offset::<ast::ModuleAttribute>(syntax, position.offset).is_some();
+    let in_callback = algo::find_node_at_offset::<ast::Callback>(syntax, position.offset).is_some();
+    if in_module_attribute || in_callback {
+        let module = Module {
+            file: File {
+                file_id: position.file_id,
+            },
+        };
+        return Some(module.to_nav(db));
+    }
+    None
+}
+
+/// The behaviours declared by the module in `file_id`.
+pub(crate) fn supertypes(db: &RootDatabase, file_id: FileId) -> Vec<NavigationTarget> {
+    let sema = Semantic::new(db);
+    let mut behaviours: Vec<NavigationTarget> = sema
+        .def_map(file_id)
+        .get_behaviours()
+        .iter()
+        .filter_map(|name| sema.resolve_behaviour(file_id, name))
+        .map(|(module, _callbacks)| module.to_nav(db))
+        .collect();
+    behaviours.sort_by(|a, b| a.name.cmp(&b.name));
+    behaviours
+}
+
+/// The modules declaring the behaviour defined in `file_id`, among the
+/// ones of the project of `from_file`.
+pub(crate) fn subtypes(
+    db: &RootDatabase,
+    file_id: FileId,
+    from_file: FileId,
+) -> Vec<NavigationTarget> {
+    let sema = Semantic::new(db);
+    let behaviour = match sema.module_name(file_id) {
+        Some(name) => name,
+        None => return Vec::new(),
+    };
+    let mut implementations: Vec<NavigationTarget> = sema
+        .behaviour_implementations(from_file, &behaviour)
+        .iter()
+        .map(|module| module.to_nav(db))
+        .collect();
+    implementations.sort_by(|a, b| a.name.cmp(&b.name));
+    implementations
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::fixture;
+
+    #[track_caller]
+    fn check(fixture: &str, expect: Expect) {
+        let (analysis, position, _) = fixture::position(fixture);
+        let item = analysis
+            .type_hierarchy_prepare(position)
+            .unwrap()
+            .expect("no type hierarchy item");
+        let names = |navs: Vec<crate::NavigationTarget>| {
+            navs.iter()
+                .map(|nav| nav.name.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let supertypes = analysis.supertypes(item.file_id).unwrap();
+        let subtypes = analysis.subtypes(item.file_id, position.file_id).unwrap();
+        let actual = format!(
+            "item: {}\nsupertypes: [{}]\nsubtypes: [{}]\n",
+            item.name,
+            names(supertypes),
+            names(subtypes)
+        );
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn from_behaviour_attribute() {
+        check(
+            r#"
+//- /src/server.erl
+-module(server).
+-behaviour(g~en_store).
+//- /src/memory.erl
+-module(memory).
+-behaviour(gen_store).
+//- /src/gen_store.erl
+-module(gen_store).
+-callback get(atom()) -> binary().
+//- /src/other.erl
+-module(other).
+"#,
+            expect![[r#"
+                item: gen_store
+                supertypes: []
+                subtypes: [memory, server]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn from_callback_module() {
+        check(
+            r#"
+//- /src/server.erl
+-module(se~rver).
+-behaviour(gen_store).
+-behaviour(gen_cache).
+//- /src/gen_store.erl
+-module(gen_store).
+-callback get(atom()) -> binary().
+//- /src/gen_cache.erl
+-module(gen_cache).
+-callback get(atom()) -> binary().
+"#,
+            expect![[r#"
+                item: server
+                supertypes: [gen_cache, gen_store]
+                subtypes: []
+            "#]],
+        );
+    }
+}
@@ -0,0 +1,431 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Re-indentation while typing, following the indentation of the Emacs
+//! erlang-mode: clause bodies are indented by four columns from the start
+//! of the clause, `end` is aligned with the keyword opening its block, and
+//! the elements of a sequence are aligned with the first one.
+
+use elp_ide_db::elp_base_db::FilePosition;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_ide_db::RootDatabase;
+use elp_syntax::AstNode;
+use elp_syntax::NodeOrToken;
+use elp_syntax::SourceFile;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use elp_syntax::SyntaxToken;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use fxhash::FxHashMap;
+use text_edit::TextEdit;
+use text_edit::TextEditBuilder;
+
+const INDENT: usize = 4;
+
+/// The blocks whose clauses are closed by `end`.
+const BLOCKS: &[SyntaxKind] = &[
+    SyntaxKind::ANONYMOUS_FUN,
+    SyntaxKind::BLOCK_EXPR,
+    SyntaxKind::CASE_EXPR,
+    SyntaxKind::IF_EXPR,
+    SyntaxKind::MAYBE_EXPR,
+    SyntaxKind::RECEIVE_EXPR,
+    SyntaxKind::TRY_EXPR,
+];
+
+// Feature: On Type Formatting
+//
+// Re-indents the new line when pressing Enter, the line of an `end`
+// once typed, and the whole form once its final `.` is typed. The `d`
+// ending `end` is the character triggering the indentation of its line.
+pub(crate) fn on_char_typed(
+    db: &RootDatabase,
+    position: FilePosition,
+    char_typed: char,
+) -> Option<TextEdit> {
+    let _p = tracing::info_span!("on_char_typed").entered();
+    let source = db.parse(position.file_id).tree();
+    let text = db.file_text(position.file_id);
+    let mut indenter = Indenter::new(&source, &text);
+    match char_typed {
+        '\n' => {
+            let line_start = indenter.line_start(position.offset);
+            indenter.reindent_line(line_start, true);
+        }
+        'd' => {
+            let token = typed_token(&source, position, SyntaxKind::ANON_END)?;
+            let line_start = indenter.line_start(token.text_range().start());
+            if indenter.first_token(line_start)? != token {
+                return None;
+            }
+            indenter.reindent_line(line_start, false);
+        }
+        '.' => {
+            let token = typed_token(&source, position, SyntaxKind::ANON_DOT)?;
+            let form = token.parent()?;
+            if form.parent()?.kind() != SyntaxKind::SOURCE_FILE {
+                return None;
+            }
+            let range = form.text_range();
+            let mut line_start = indenter.line_start(range.start());
+            while let Some(next_line) = text[usize::from(line_start)..].find('\n') {
+                line_start += TextSize::from(next_line as u32 + 1);
+                if line_start > range.end() {
+                    break;
+                }
+                indenter.reindent_line(line_start, false);
+            }
+        }
+        _ => return None,
+    }
+    indenter.into_edit()
+}
+
+fn typed_token(
+    source: &SourceFile,
+    position: FilePosition,
+    kind: SyntaxKind,
+) -> Option<SyntaxToken> {
+    source
+        .syntax()
+        .token_at_offset(position.offset)
+        .left_biased()
+        .filter(|token| token.kind() == kind && token.text_range().end() == position.offset)
+}
+
+struct Indenter<'a> {
+    source: &'a SourceFile,
+    text: &'a str,
+    /// How many columns the lines re-indented so far moved by, keyed by
+    /// the start of the line
+    shifts: FxHashMap<TextSize, isize>,
+    edit: TextEditBuilder,
+}
+
+impl<'a> Indenter<'a> {
+    fn new(source: &'a SourceFile, text: &'a str) -> Indenter<'a> {
+        Indenter {
+            source,
+            text,
+            shifts: FxHashMap::default(),
+            edit: TextEditBuilder::default(),
+        }
+    }
+
+    fn into_edit(self) -> Option<TextEdit> {
+        (!self.shifts.is_empty()).then(|| self.edit.finish())
+    }
+
+    fn line_start(&self, offset: TextSize) -> TextSize {
+        let start = self.text[..usize::from(offset)]
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        TextSize::from(start as u32)
+    }
+
+    /// The whitespace starting the line.
+    fn leading_whitespace(&self, line_start: TextSize) -> TextRange {
+        let line = &self.text[usize::from(line_start)..];
+        let len = line.len() - line.trim_start_matches([' ', '\t']).len();
+        TextRange::at(line_start, TextSize::from(len as u32))
+    }
+
+    /// The column of `offset`, once the lines re-indented so far moved.
+    fn column(&self, offset: TextSize) -> usize {
+        let line_start = self.line_start(offset);
+        let column = u32::from(offset - line_start) as isize;
+        (column + self.shifts.get(&line_start).copied().unwrap_or(0)).max(0) as usize
+    }
+
+    fn line_indent(&self, offset: TextSize) -> usize {
+        let whitespace = self.leading_whitespace(self.line_start(offset));
+        self.column(whitespace.end())
+    }
+
+    /// The first token of the line, unless the line is empty or continues
+    /// a string or comment started on a previous line.
+    fn first_token(&self, line_start: TextSize) -> Option<SyntaxToken> {
+        let content_start = self.leading_whitespace(line_start).end();
+        if self.text[usize::from(content_start)..].starts_with('\n') {
+            return None;
+        }
+        self.source
+            .syntax()
+            .token_at_offset(content_start)
+            .right_biased()
+            .filter(|token| token.text_range().start() == content_start)
+    }
+
+    /// Re-indents the line starting at `line_start`, an empty line only
+    /// when `empty` is set.
+    fn reindent_line(&mut self, line_start: TextSize, empty: bool) {
+        let whitespace = self.leading_whitespace(line_start);
+        let first_token = self.first_token(line_start);
+        if first_token.is_none() && !(empty && self.is_empty_line(whitespace.end())) {
+            return;
+        }
+        let Some(indent) = self.indent(line_start, first_token) else {
+            return;
+        };
+        let current = u32::from(whitespace.len()) as usize;
+        if self.text[whitespace].chars().all(|c| c == ' ') && current == indent {
+            return;
+        }
+        self.shifts
+            .insert(line_start, indent as isize - current as isize);
+        self.edit.replace(whitespace, " ".repeat(indent));
+    }
+
+    fn is_empty_line(&self, content_start: TextSize) -> bool {
+        let rest = &self.text[usize::from(content_start)..];
+        rest.is_empty() || rest.starts_with('\n')
+    }
+
+    fn indent(&self, line_start: TextSize, first_token: Option<SyntaxToken>) -> Option<usize> {
+        if let Some(token) = first_token {
+            if matches!(
+                token.kind(),
+                SyntaxKind::ANON_END
+                    | SyntaxKind::ANON_AFTER
+                    | SyntaxKind::ANON_CATCH
+                    | SyntaxKind::ANON_OF
+                    | SyntaxKind::ANON_ELSE
+            ) {
+                if let Some(block) = enclosing_block(&token) {
+                    return Some(self.column(block.text_range().start()));
+                }
+            }
+        }
+
+        let previous = self.previous_token(line_start)?;
+        match previous.kind() {
+            SyntaxKind::ANON_DASH_GT => {
+                // The arrow starts the body of the clause
+                let clause = previous.parent()?.parent()?;
+                let anchor = match clause.parent() {
+                    Some(fun) if fun.kind() == SyntaxKind::ANONYMOUS_FUN => fun,
+                    _ => clause,
+                };
+                Some(self.column(anchor.text_range().start()) + INDENT)
+            }
+            SyntaxKind::ANON_OF
+            | SyntaxKind::ANON_BEGIN
+            | SyntaxKind::ANON_RECEIVE
+            | SyntaxKind::ANON_IF
+            | SyntaxKind::ANON_TRY
+            | SyntaxKind::ANON_MAYBE
+            | SyntaxKind::ANON_AFTER
+            | SyntaxKind::ANON_CATCH
+            | SyntaxKind::ANON_ELSE => {
+                let block = enclosing_block(&previous)?;
+                Some(self.column(block.text_range().start()) + INDENT)
+            }
+            SyntaxKind::ANON_COMMA | SyntaxKind::ANON_SEMI => {
+                let sibling = previous_sibling_node(&previous)?;
+                Some(self.column(sibling.text_range().start()))
+            }
+            SyntaxKind::ANON_DOT => {
+                let form = previous.parent()?;
+                (form.parent()?.kind() == SyntaxKind::SOURCE_FILE).then_some(0)
+            }
+            SyntaxKind::ANON_LPAREN | SyntaxKind::ANON_LBRACK | SyntaxKind::ANON_LBRACE => {
+                Some(self.line_indent(previous.text_range().start()) + INDENT)
+            }
+            _ => None,
+        }
+    }
+
+    /// The last token before the line, other than whitespace and comments.
+    fn previous_token(&self, line_start: TextSize) -> Option<SyntaxToken> {
+        let mut token = self
+            .source
+            .syntax()
+            .token_at_offset(line_start)
+            .left_biased()?;
+        while token.kind() == SyntaxKind::WHITESPACE
+            || token.kind() == SyntaxKind::COMMENT
+            || token.text_range().end() > line_start
+        {
+            token = token.prev_token()?;
+        }
+        Some(token)
+    }
+}
+
+/// The block of a keyword, `catch` or `after` being possibly nested in
+/// the clause they start.
+fn enclosing_block(token: &SyntaxToken) -> Option<SyntaxNode> {
+    token
+        .parent_ancestors()
+        .take(2)
+        .find(|node| BLOCKS.contains(&node.kind()))
+}
+
+fn previous_sibling_node(token: &SyntaxToken) -> Option<SyntaxNode> {
+    let mut element = token.prev_sibling_or_token();
+    while let Some(it) = element {
+        match it {
+            NodeOrToken::Node(node) => return Some(node),
+            NodeOrToken::Token(token) => element = token.prev_sibling_or_token(),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fixture;
+
+    #[track_caller]
+    fn check(char_typed: char, fixture_before: &str, expect_after: &str) {
+        let (analysis, position, _) = fixture::position(fixture_before);
+        let mut text = analysis.file_text(position.file_id).unwrap().to_string();
+        if let Some(edit) = analysis.on_char_typed(position, char_typed).unwrap() {
+            edit.apply(&mut text);
+        }
+        assert_eq!(expect_after.trim_start(), text.trim_start());
+    }
+
+    #[test]
+    fn newline_after_arrow() {
+        check(
+            '\n',
+            r#"
+foo(X) ->
+~bar(X).
+"#,
+            r#"
+foo(X) ->
+    bar(X).
+"#,
+        );
+    }
+
+    #[test]
+    fn newline_on_empty_line() {
+        check('\n', "foo(X) ->\n~\n", "foo(X) ->\n    \n");
+    }
+
+    #[test]
+    fn newline_after_comma() {
+        check(
+            '\n',
+            r#"
+foo(X) ->
+    bar(X,
+~Y),
+  baz(X).
+"#,
+            r#"
+foo(X) ->
+    bar(X,
+        Y),
+  baz(X).
+"#,
+        );
+    }
+
+    #[test]
+    fn newline_in_case() {
+        check(
+            '\n',
+            r#"
+foo(X) ->
+    Y = case X of
+~1 -> one
+        end.
+"#,
+            r#"
+foo(X) ->
+    Y = case X of
+            1 -> one
+        end.
+"#,
+        );
+    }
+
+    #[test]
+    fn newline_after_clause() {
+        check(
+            '\n',
+            r#"
+foo(1) -> one;
+    ~foo(_) -> other.
+"#,
+            r#"
+foo(1) -> one;
+foo(_) -> other.
+"#,
+        );
+    }
+
+    #[test]
+    fn end_aligned_with_block() {
+        check(
+            'd',
+            r#"
+foo(X) ->
+    case X of
+        1 -> one;
+        _ -> other
+        end~
+"#,
+            r#"
+foo(X) ->
+    case X of
+        1 -> one;
+        _ -> other
+    end
+"#,
+        );
+    }
+
+    #[test]
+    fn end_not_first_on_line() {
+        check(
+            'd',
+            r#"
+foo() ->
+    begin ok end~
+"#,
+            r#"
+foo() ->
+    begin ok end
+"#,
+        );
+    }
+
+    #[test]
+    fn dot_reindents_form() {
+        check(
+            '.',
+            r#"
+foo(X) ->
+  receive
+  {msg, M} ->
+  handle(M),
+        ok
+        after 100 ->
+  timeout
+      end.~
+"#,
+            r#"
+foo(X) ->
+    receive
+        {msg, M} ->
+            handle(M),
+            ok
+    after 100 ->
+        timeout
+    end.
+"#,
+        );
+    }
+}
@@ -0,0 +1,169 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Dumps the HIR the forms of a file are lowered to, as used by
+//! `elp/viewHir`, to see how ELP interprets a piece of code, e.g. once
+//! its macros are expanded.
+
+use elp_ide_db::elp_base_db::FileId;
+use elp_ide_db::RootDatabase;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use fxhash::FxHashSet;
+use hir::db::DefDatabase;
+use hir::AnyAttribute;
+use hir::FormIdx;
+use hir::InFile;
+use hir::Semantic;
+use hir::SpecOrCallback;
+
+/// The HIR of the forms intersecting the range, or of every form of the
+/// file when there is no range. A function is printed whole, once, even
+/// when only some of its clauses intersect the range.
+pub(crate) fn view_hir(db: &RootDatabase, file_id: FileId, range: Option<TextRange>) -> String {
+    let sema = Semantic::new(db);
+    let source = sema.parse(file_id).value;
+    let form_list = db.file_form_list(file_id);
+    let def_map = sema.def_map(file_id);
+    let mut functions = FxHashSet::default();
+    let mut out = Vec::new();
+    for form in source.forms() {
+        if let Some(range) = range {
+            if form.syntax().text_range().intersect(range).is_none() {
+                continue;
+            }
+        }
+        let Some(form_idx) = form_list.find_form(&form) else {
+            continue;
+        };
+        let printed = match form_idx {
+            FormIdx::FunctionClause(clause_id) => {
+                let Some(function_id) = def_map.function_def_id(&clause_id) else {
+                    continue;
+                };
+                if !functions.insert(*function_id) {
+                    continue;
+                }
+                let function_id = InFile::new(file_id, *function_id);
+                let Some(def) = def_map.get_by_function_id(&function_id) else {
+                    continue;
+                };
+                let body = db.function_body(function_id);
+                format!("%% {}\n{}", def.name, body.tree_print(db))
+            }
+            FormIdx::TypeAlias(type_alias_id) => {
+                let body = db.type_body(InFile::new(file_id, type_alias_id));
+                body.tree_print(db, &form_list[type_alias_id])
+            }
+            FormIdx::Spec(spec_id) => {
+                let spec = SpecOrCallback::Spec(form_list[spec_id].clone());
+                let body = db.spec_body(InFile::new(file_id, spec_id));
+                body.tree_print(db, spec)
+            }
+            FormIdx::Callback(callback_id) => {
+                let callback = SpecOrCallback::Callback(form_list[callback_id].clone());
+                let body = db.callback_body(InFile::new(file_id, callback_id));
+                body.tree_print(db, callback)
+            }
+            FormIdx::Record(record_id) => {
+                let body = db.record_body(InFile::new(file_id, record_id));
+                body.print(db, &form_list, record_id)
+            }
+            FormIdx::Attribute(attribute_id) => {
+                let attribute = AnyAttribute::Attribute(form_list[attribute_id].clone());
+                let body = db.attribute_body(InFile::new(file_id, attribute_id));
+                body.tree_print(db, attribute)
+            }
+            FormIdx::CompileOption(attribute_id) => {
+                let attribute = AnyAttribute::CompileOption(form_list[attribute_id].clone());
+                let body = db.compile_body(InFile::new(file_id, attribute_id));
+                body.tree_print(db, attribute)
+            }
+            // The other forms are not lowered to a body
+            _ => continue,
+        };
+        out.push(printed);
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_ide_db::elp_base_db::fixture::RangeOrOffset;
+    use elp_ide_db::elp_base_db::fixture::WithFixture;
+    use elp_ide_db::RootDatabase;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use crate::AnalysisHost;
+
+    #[track_caller]
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id, range_or_offset) = RootDatabase::with_range_or_offset(fixture);
+        let range = match range_or_offset {
+            RangeOrOffset::Range(range) => Some(range),
+            RangeOrOffset::Offset(_) => None,
+        };
+        let analysis = AnalysisHost { db }.analysis();
+        let hir = analysis.view_hir(file_id, range).unwrap();
+        expect.assert_eq(&hir);
+    }
+
+    #[test]
+    fn function_with_macro() {
+        check(
+            r#"
+-module(m).~
+-define(TAG, tag).
+f(X) -> {?TAG, X}.
+"#,
+            expect![[r#"
+                %% f/1
+                Clause {
+                    pats
+                        Pat::Var(X),
+                    guards
+                    exprs
+                        Expr::Tuple {
+                            Literal(Atom('tag')),
+                            Expr::Var(X),
+                        },
+                }.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn selected_clause_prints_function() {
+        check(
+            r#"
+-module(m).
+g(a) -> 1;
+g(~b~) -> 2.
+h() -> 3.
+"#,
+            expect![[r#"
+                %% g/1
+                Clause {
+                    pats
+                        Literal(Atom('a')),
+                    guards
+                    exprs
+                        Literal(Integer(1)),
+                };Clause {
+                    pats
+                        Literal(Atom('b')),
+                    guards
+                    exprs
+                        Literal(Integer(2)),
+                }.
+            "#]],
+        );
+    }
+}
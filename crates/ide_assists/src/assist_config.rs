@@ -15,10 +15,23 @@
 
 use elp_ide_db::helpers::SnippetCap;
 
+use crate::AssistId;
 use crate::AssistKind;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct AssistConfig {
     pub snippet_cap: Option<SnippetCap>,
     pub allowed: Option<Vec<AssistKind>>,
+    /// Ids of the assists the user turned off
+    pub disabled: Vec<String>,
+    /// Oldest OTP release the code must compile with, when configured
+    pub min_otp_release: Option<u32>,
+    /// Whether to offer aligning and un-aligning the arrows of clauses
+    pub align_arrows: bool,
+}
+
+impl AssistConfig {
+    pub fn is_disabled(&self, id: &AssistId) -> bool {
+        self.disabled.iter().any(|disabled| disabled == id.0)
+    }
 }
@@ -175,6 +175,18 @@ impl<'a> AssistContext<'a> {
         self.frange.file_id
     }
 
+    /// The oldest OTP release the file must compile with: the configured
+    /// one, or else the release of the project's toolchain.
+    pub(crate) fn min_otp_release(&self) -> Option<u32> {
+        if let Some(release) = self.config.min_otp_release {
+            return Some(release);
+        }
+        let db = self.sema.db;
+        let project_id = db.file_app_data(self.file_id())?.project_id;
+        let version = db.project_data(project_id).otp_version.clone()?;
+        version.split('.').next()?.parse().ok()
+    }
+
     #[allow(dead_code)]
     pub(crate) fn in_clause(
         &'a self,
@@ -300,6 +312,7 @@ pub(crate) struct Assists {
     resolve: AssistResolveStrategy,
     buf: Vec<Assist>,
     allowed: Option<Vec<AssistKind>>,
+    disabled: Vec<String>,
 }
 
 impl Assists {
@@ -309,11 +322,13 @@ impl Assists {
             file: ctx.frange.file_id,
             buf: Vec::new(),
             allowed: ctx.config.allowed.clone(),
+            disabled: ctx.config.disabled.clone(),
         }
     }
 
     pub(crate) fn finish(mut self) -> Vec<Assist> {
-        self.buf.sort_by_key(|assist| assist.target.len());
+        self.buf
+            .sort_by_key(|assist| (assist.id.1.priority(), assist.target.len()));
         self.buf
     }
 
@@ -372,6 +387,8 @@ impl Assists {
             source_change: None,
             user_input,
             original_diagnostic,
+            is_preferred: false,
+            subtype: None,
         };
         self.add_impl(assist, f)
     }
@@ -395,6 +412,9 @@ impl Assists {
     }
 
     fn is_allowed(&self, id: &AssistId) -> bool {
+        if self.disabled.iter().any(|disabled| disabled == id.0) {
+            return false;
+        }
         match &self.allowed {
             Some(allowed) => allowed.iter().any(|kind| kind.contains(id.1)),
             None => true,
@@ -0,0 +1,381 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use elp_syntax::SyntaxToken;
+use elp_syntax::TextRange;
+use fxhash::FxHashSet;
+use hir::InFile;
+
+use crate::AssistContext;
+use crate::Assists;
+
+// Assist: align_arrows
+//
+// Aligns the arrows of the clauses of a function, or of a `case`, `if`,
+// `receive`, `try` or `fun` expression, so their bodies start in the same
+// column. Clauses with the arrow or the body on a line of its own are left
+// alone. The alignment can be undone with `unalign_arrows`. Only offered
+// when `elp.assist.alignArrows.enable` is set.
+//
+// ```
+// size(empty) ~-> 0;
+// size({leaf, _}) -> 1;
+// size({node, L, R}) -> size(L) + size(R).
+// ```
+// ->
+// ```
+// size(empty)        -> 0;
+// size({leaf, _})    -> 1;
+// size({node, L, R}) -> size(L) + size(R).
+// ```
+pub(crate) fn align_arrows(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    if !ctx.config.align_arrows {
+        return None;
+    }
+    let (target, arrows) = arrows_at_offset(ctx)?;
+    let text = ctx.db().file_text(ctx.file_id());
+    let column = |arrow: &Arrow| {
+        let line_start = text[..usize::from(arrow.before.start())]
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        text[line_start..usize::from(arrow.before.start())]
+            .chars()
+            .count()
+    };
+    let arrow_column = arrows.iter().map(&column).max()? + 1;
+    let edits = whitespace_edits(&text, &arrows, |arrow| {
+        " ".repeat(arrow_column - column(arrow))
+    });
+    if edits.is_empty() {
+        return None;
+    }
+    acc.add(
+        AssistId("align_arrows", AssistKind::RefactorRewrite),
+        "Align clause arrows",
+        None,
+        target,
+        None,
+        |builder| {
+            for (range, text) in edits {
+                builder.replace(range, text);
+            }
+        },
+    )
+}
+
+// Assist: unalign_arrows
+//
+// Puts a single space around the arrows of the clauses of a function, or
+// of a `case`, `if`, `receive`, `try` or `fun` expression, undoing
+// `align_arrows`. Only offered when `elp.assist.alignArrows.enable` is
+// set.
+//
+// ```
+// size(empty)        ~-> 0;
+// size({leaf, _})    -> 1;
+// size({node, L, R}) -> size(L) + size(R).
+// ```
+// ->
+// ```
+// size(empty) -> 0;
+// size({leaf, _}) -> 1;
+// size({node, L, R}) -> size(L) + size(R).
+// ```
+pub(crate) fn unalign_arrows(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    if !ctx.config.align_arrows {
+        return None;
+    }
+    let (target, arrows) = arrows_at_offset(ctx)?;
+    let text = ctx.db().file_text(ctx.file_id());
+    let edits = whitespace_edits(&text, &arrows, |_| " ".to_string());
+    if edits.is_empty() {
+        return None;
+    }
+    acc.add(
+        AssistId("unalign_arrows", AssistKind::RefactorRewrite),
+        "Un-align clause arrows",
+        None,
+        target,
+        None,
+        |builder| {
+            for (range, text) in edits {
+                builder.replace(range, text);
+            }
+        },
+    )
+}
+
+/// The arrow of a clause, with the head of the clause and its body on the
+/// same line.
+#[derive(Debug)]
+struct Arrow {
+    /// The whitespace between the head of the clause and the arrow
+    before: TextRange,
+    /// The whitespace between the arrow and the body, if the body starts
+    /// on the same line
+    after: Option<TextRange>,
+}
+
+impl Arrow {
+    fn new(body: &ast::ClauseBody) -> Option<Arrow> {
+        let arrow = body.syntax().first_token()?;
+        if arrow.kind() != SyntaxKind::ANON_DASH_GT {
+            return None;
+        }
+        let before = match arrow.prev_token()? {
+            token if is_whitespace(&token) => token.text_range(),
+            token if token.kind() == SyntaxKind::WHITESPACE => return None,
+            _ => TextRange::empty(arrow.text_range().start()),
+        };
+        let after = match arrow.next_token() {
+            Some(token) if is_whitespace(&token) => {
+                let comment = token
+                    .next_token()
+                    .is_some_and(|next| next.kind() == SyntaxKind::COMMENT);
+                (!comment).then(|| token.text_range())
+            }
+            Some(token) if token.kind() == SyntaxKind::WHITESPACE => None,
+            Some(_) => Some(TextRange::empty(arrow.text_range().end())),
+            None => None,
+        };
+        Some(Arrow { before, after })
+    }
+}
+
+/// Whitespace not spanning lines.
+fn is_whitespace(token: &SyntaxToken) -> bool {
+    token.kind() == SyntaxKind::WHITESPACE && !token.text().contains('\n')
+}
+
+/// The arrows of the clauses sharing the arrow at the cursor, if there
+/// are at least two of them, and the range they span.
+fn arrows_at_offset(ctx: &AssistContext) -> Option<(TextRange, Vec<Arrow>)> {
+    let arrow =
+        ctx.find_tokens_syntax_at_offset(FxHashSet::from_iter([SyntaxKind::ANON_DASH_GT]))?;
+    let clause = ast::ClauseBody::cast(arrow.parent()?)?.syntax().parent()?;
+    let bodies: Vec<ast::ClauseBody> = if clause.kind() == SyntaxKind::FUNCTION_CLAUSE {
+        function_clause_bodies(ctx, &clause)?
+    } else {
+        clause
+            .parent()?
+            .children()
+            .filter(|sibling| sibling.kind() == clause.kind())
+            .filter_map(|sibling| sibling.children().find_map(ast::ClauseBody::cast))
+            .collect()
+    };
+    let target = bodies
+        .iter()
+        .map(|body| body.syntax().text_range())
+        .reduce(|acc, range| acc.cover(range))?;
+    let arrows: Vec<Arrow> = bodies.iter().filter_map(Arrow::new).collect();
+    if arrows.len() < 2 {
+        return None;
+    }
+    Some((target, arrows))
+}
+
+fn function_clause_bodies(
+    ctx: &AssistContext,
+    clause: &SyntaxNode,
+) -> Option<Vec<ast::ClauseBody>> {
+    let function = ctx.sema.find_enclosing_function(ctx.file_id(), clause)?;
+    let def_map = ctx.sema.def_map(ctx.file_id());
+    let function_def = def_map.get_by_function_id(&InFile::new(ctx.file_id(), function))?;
+    Some(
+        function_def
+            .source(ctx.sema.db.upcast())
+            .iter()
+            .filter_map(|fun_decl| match fun_decl.clause()? {
+                ast::FunctionOrMacroClause::FunctionClause(clause) => clause.body(),
+                ast::FunctionOrMacroClause::MacroCallExpr(_) => None,
+            })
+            .collect(),
+    )
+}
+
+/// The edits putting `before(arrow)` before each arrow, and a single space
+/// after it, skipping those leaving the text unchanged.
+fn whitespace_edits(
+    text: &str,
+    arrows: &[Arrow],
+    before: impl Fn(&Arrow) -> String,
+) -> Vec<(TextRange, String)> {
+    let mut edits = Vec::new();
+    for arrow in arrows {
+        edits.push((arrow.before, before(arrow)));
+        if let Some(after) = arrow.after {
+            edits.push((after, " ".to_string()));
+        }
+    }
+    edits.retain(|(range, whitespace)| &text[*range] != whitespace);
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_ide_db::elp_base_db::fixture::WithFixture;
+    use elp_ide_db::elp_base_db::FileRange;
+    use elp_ide_db::RootDatabase;
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+    use crate::AssistConfig;
+    use crate::AssistResolveStrategy;
+
+    #[test]
+    fn align_function_clauses() {
+        check_assist(
+            align_arrows,
+            "Align clause arrows",
+            r#"
+size(empty) ~-> 0;
+size({leaf, _}) -> 1;
+size({node, L, R}) ->   size(L) + size(R).
+"#,
+            expect![[r#"
+                size(empty)        -> 0;
+                size({leaf, _})    -> 1;
+                size({node, L, R}) -> size(L) + size(R).
+            "#]],
+        )
+    }
+
+    #[test]
+    fn align_case_clauses() {
+        check_assist(
+            align_arrows,
+            "Align clause arrows",
+            r#"
+describe(X) ->
+    case X of
+        ok ~-> fine;
+        {error, _} -> broken;
+        %% A comment between clauses
+        _ when is_atom(X) ->
+            unknown
+    end.
+"#,
+            expect![[r#"
+                describe(X) ->
+                    case X of
+                        ok                -> fine;
+                        {error, _}        -> broken;
+                        %% A comment between clauses
+                        _ when is_atom(X) ->
+                            unknown
+                    end.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn align_arrow_on_own_line_left_alone() {
+        check_assist(
+            align_arrows,
+            "Align clause arrows",
+            r#"
+f(a) ~-> 1;
+f(bb) -> 2;
+f(long_argument)
+  -> 3.
+"#,
+            expect![[r#"
+                f(a)  -> 1;
+                f(bb) -> 2;
+                f(long_argument)
+                  -> 3.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn already_aligned() {
+        check_assist_not_applicable(
+            align_arrows,
+            r#"
+f(a)  ~-> 1;
+f(bb) -> 2.
+"#,
+        );
+    }
+
+    #[test]
+    fn single_clause() {
+        check_assist_not_applicable(
+            align_arrows,
+            r#"
+f(a) ~-> 1.
+"#,
+        );
+    }
+
+    #[test]
+    fn not_offered_by_default() {
+        let fixture = r#"
+f(a) ~-> 1;
+f(bb) -> 2.
+"#;
+        let (db, file_id, range_or_offset) = RootDatabase::with_range_or_offset(fixture);
+        let frange = FileRange {
+            file_id,
+            range: range_or_offset.into(),
+        };
+        let config = AssistConfig {
+            align_arrows: false,
+            ..TEST_CONFIG
+        };
+        let diagnostics = vec![];
+        let ctx = AssistContext::new(&db, &config, frange, &diagnostics, None);
+        let mut acc = Assists::new(&ctx, AssistResolveStrategy::All);
+        align_arrows(&mut acc, &ctx);
+        unalign_arrows(&mut acc, &ctx);
+        assert!(acc.finish().is_empty());
+    }
+
+    #[test]
+    fn unalign() {
+        check_assist(
+            unalign_arrows,
+            "Un-align clause arrows",
+            r#"
+f(X) ->
+    receive
+        a    ~-> 1;
+        {b, _} ->  2
+    end.
+"#,
+            expect![[r#"
+                f(X) ->
+                    receive
+                        a -> 1;
+                        {b, _} -> 2
+                    end.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn unalign_not_aligned() {
+        check_assist_not_applicable(
+            unalign_arrows,
+            r#"
+f(a) ~-> 1;
+f(bb) -> 2.
+"#,
+        );
+    }
+}
@@ -0,0 +1,267 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+
+use crate::AssistContext;
+use crate::Assists;
+
+// Assist: convert_to_binary_comprehension
+//
+// Converts a call to `list_to_binary/1` or `iolist_to_binary/1` with a
+// list comprehension building binaries to a binary comprehension, which
+// builds the result directly rather than an intermediate list.
+//
+// ```
+// encode(Bytes) -> list_to_bin~ary([<<B:8>> || B <- Bytes, B > 0]).
+// ```
+// ->
+// ```
+// encode(Bytes) -> << <<B:8>> || B <- Bytes, B > 0 >>.
+// ```
+pub(crate) fn convert_to_binary_comprehension(
+    acc: &mut Assists,
+    ctx: &AssistContext,
+) -> Option<()> {
+    let call = ctx.find_node_at_offset::<ast::Call>()?;
+    if !matches!(
+        erlang_bif_name(&call)?.as_str(),
+        "list_to_binary" | "iolist_to_binary"
+    ) {
+        return None;
+    }
+    let args: Vec<ast::Expr> = call.args()?.args().collect();
+    let [ast::Expr::ExprMax(ast::ExprMax::ListComprehension(comprehension))] = args.as_slice()
+    else {
+        return None;
+    };
+    // Any other element, such as an integer, would have to become a
+    // segment of a type which cannot be told from the syntax.
+    let ast::Expr::ExprMax(template @ ast::ExprMax::Binary(_)) = comprehension.expr()? else {
+        return None;
+    };
+    let replacement = format!(
+        "<< {} || {} >>",
+        text(template.syntax()),
+        text(comprehension.lc_exprs()?.syntax())
+    );
+    let range = call.syntax().text_range();
+    acc.add(
+        AssistId(
+            "convert_to_binary_comprehension",
+            AssistKind::RefactorRewrite,
+        ),
+        "Convert to binary comprehension",
+        None,
+        range,
+        None,
+        |edit| edit.replace(range, replacement),
+    )
+}
+
+// Assist: accumulate_iolist
+//
+// Converts a fold appending to a binary accumulator, which may copy the
+// accumulator on each step, to a fold building an iolist converted to a
+// binary once with `iolist_to_binary/1`.
+//
+// ```
+// join(Parts) -> lists:fo~ldl(fun(P, Acc) -> <<Acc/binary, P/binary, ",">> end, <<>>, Parts).
+// ```
+// ->
+// ```
+// join(Parts) -> iolist_to_binary(lists:foldl(fun(P, Acc) -> [Acc, P, <<",">>] end, [], Parts)).
+// ```
+pub(crate) fn accumulate_iolist(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let call = ctx.find_node_at_offset::<ast::Call>()?;
+    let ast::Expr::Remote(remote) = call.expr()? else {
+        return None;
+    };
+    if text(remote.module()?.module()?.syntax()) != "lists"
+        || !matches!(text(remote.fun()?.syntax()).as_str(), "foldl" | "foldr")
+    {
+        return None;
+    }
+    let args: Vec<ast::Expr> = call.args()?.args().collect();
+    let [ast::Expr::ExprMax(ast::ExprMax::AnonymousFun(fun)), init, _list] = args.as_slice() else {
+        return None;
+    };
+    let clauses: Vec<ast::FunClause> = fun.clauses().collect();
+    let [clause] = clauses.as_slice() else {
+        return None;
+    };
+    if clause.guard().is_some() || clause.name().is_some() {
+        return None;
+    }
+    let params: Vec<ast::Expr> = clause.args()?.args().collect();
+    let [_, ast::Expr::ExprMax(ast::ExprMax::Var(acc_var))] = params.as_slice() else {
+        return None;
+    };
+    let acc_name = text(acc_var.syntax());
+    let body = clause.body()?;
+    // The accumulator becomes an iolist, so it must not be used other
+    // than to append to it.
+    let uses = body
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter(|token| token.kind() == SyntaxKind::VAR && token.text() == acc_name)
+        .count();
+    if uses != 1 {
+        return None;
+    }
+    let Some(ast::Expr::ExprMax(ast::ExprMax::Binary(binary))) = body.exprs().last() else {
+        return None;
+    };
+    let elements: Vec<ast::BinElement> = binary.elements().collect();
+    let (first, rest) = elements.split_first()?;
+    if binary_segment(first)? != acc_name || rest.is_empty() {
+        return None;
+    }
+    let parts: Vec<String> = std::iter::once(acc_name)
+        .chain(rest.iter().map(|element| {
+            binary_segment(element).unwrap_or_else(|| format!("<<{}>>", text(element.syntax())))
+        }))
+        .collect();
+    let mut edits = vec![(
+        binary.syntax().text_range(),
+        format!("[{}]", parts.join(", ")),
+    )];
+    if text(init.syntax()).replace(' ', "") == "<<>>" {
+        edits.push((init.syntax().text_range(), "[]".to_string()));
+    }
+    let range = call.syntax().text_range();
+    acc.add(
+        AssistId("accumulate_iolist", AssistKind::RefactorRewrite),
+        "Accumulate an iolist and convert it to a binary once",
+        None,
+        range,
+        None,
+        |edit| {
+            for (range, replacement) in edits {
+                edit.replace(range, replacement);
+            }
+            edit.insert(range.start(), "iolist_to_binary(");
+            edit.insert(range.end(), ")");
+        },
+    )
+}
+
+fn text(node: &SyntaxNode) -> String {
+    node.text().to_string()
+}
+
+/// The name of the function called, when it is an auto-imported BIF or a
+/// function of the `erlang` module.
+fn erlang_bif_name(call: &ast::Call) -> Option<String> {
+    match call.expr()? {
+        ast::Expr::ExprMax(ast::ExprMax::Atom(atom)) => Some(text(atom.syntax())),
+        ast::Expr::Remote(remote) if text(remote.module()?.module()?.syntax()) == "erlang" => {
+            Some(text(remote.fun()?.syntax()))
+        }
+        _ => None,
+    }
+}
+
+/// The expression of a segment `Expr/binary`, which can stand for itself
+/// in an iolist.
+fn binary_segment(element: &ast::BinElement) -> Option<String> {
+    if element.size().is_some() {
+        return None;
+    }
+    let types: Vec<ast::BitType> = element.types()?.types().collect();
+    match types.as_slice() {
+        [ast::BitType::Name(name)]
+            if matches!(text(name.syntax()).as_str(), "binary" | "bytes") =>
+        {
+            Some(text(element.element()?.syntax()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn list_to_binary_comprehension() {
+        check_assist(
+            convert_to_binary_comprehension,
+            "Convert to binary comprehension",
+            r#"
+-module(main).
+encode(Bytes) -> list_to_bin~ary([<<B:8>> || B <- Bytes, B > 0]).
+"#,
+            expect![[r#"
+                -module(main).
+                encode(Bytes) -> << <<B:8>> || B <- Bytes, B > 0 >>.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn not_applicable_to_integer_elements() {
+        check_assist_not_applicable(
+            convert_to_binary_comprehension,
+            r#"
+-module(main).
+encode(Bytes) -> list_to_bin~ary([B + 1 || B <- Bytes]).
+"#,
+        );
+    }
+
+    #[test]
+    fn fold_to_iolist() {
+        check_assist(
+            accumulate_iolist,
+            "Accumulate an iolist and convert it to a binary once",
+            r#"
+-module(main).
+join(Parts) -> lists:fo~ldl(fun(P, Acc) -> <<Acc/binary, P/binary, ",">> end, <<>>, Parts).
+"#,
+            expect![[r#"
+                -module(main).
+                join(Parts) -> iolist_to_binary(lists:foldl(fun(P, Acc) -> [Acc, P, <<",">>] end, [], Parts)).
+            "#]],
+        )
+    }
+
+    #[test]
+    fn not_applicable_when_accumulator_is_read() {
+        check_assist_not_applicable(
+            accumulate_iolist,
+            r#"
+-module(main).
+join(Parts) ->
+    lists:fo~ldl(fun(P, Acc) -> log(Acc), <<Acc/binary, P/binary>> end, <<>>, Parts).
+log(_) -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_prepending() {
+        check_assist_not_applicable(
+            accumulate_iolist,
+            r#"
+-module(main).
+join(Parts) -> lists:fo~ldl(fun(P, Acc) -> <<P/binary, Acc/binary>> end, <<>>, Parts).
+"#,
+        );
+    }
+}
@@ -0,0 +1,501 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_syntax::ast;
+use elp_syntax::ast::BinaryOp;
+use elp_syntax::ast::LogicOp;
+use elp_syntax::ast::UnaryOp;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+
+use crate::AssistContext;
+use crate::Assists;
+
+/// The BIFs allowed in guards, so a filter calling any other function is
+/// evaluated as an ordinary expression.
+const GUARD_BIFS: &[&str] = &[
+    "abs",
+    "binary_part",
+    "bit_size",
+    "byte_size",
+    "ceil",
+    "element",
+    "float",
+    "floor",
+    "hd",
+    "is_atom",
+    "is_binary",
+    "is_bitstring",
+    "is_boolean",
+    "is_float",
+    "is_function",
+    "is_integer",
+    "is_list",
+    "is_map",
+    "is_map_key",
+    "is_number",
+    "is_pid",
+    "is_port",
+    "is_record",
+    "is_reference",
+    "is_tuple",
+    "length",
+    "map_get",
+    "map_size",
+    "max",
+    "min",
+    "node",
+    "round",
+    "self",
+    "size",
+    "tl",
+    "trunc",
+    "tuple_size",
+];
+
+// Assist: comprehension_to_lists_call
+//
+// Converts a list comprehension with a single generator to a call to
+// `lists:map/2`, `lists:filter/2` or `lists:filtermap/2`, joining its
+// filters with `andalso`.
+//
+// ```
+// names(Users) -> [name(U) ~|| U <- Users, is_active(U)].
+// ```
+// ->
+// ```
+// names(Users) -> lists:filtermap(fun(U) -> is_active(U) andalso {true, name(U)} end, Users).
+// ```
+pub(crate) fn comprehension_to_lists_call(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let comprehension = ctx.find_node_at_offset::<ast::ListComprehension>()?;
+    let template = comprehension.expr()?;
+    let mut generator = None;
+    let mut filters = Vec::new();
+    for lc_expr in comprehension.lc_exprs()?.exprs() {
+        match lc_expr {
+            ast::LcExpr::Generator(lc_generator) if generator.is_none() => {
+                generator = Some(lc_generator)
+            }
+            ast::LcExpr::Expr(filter) if generator.is_some() => filters.push(filter),
+            _ => return None,
+        }
+    }
+    let generator = generator?;
+    let pat = generator.lhs()?;
+    let list = generator.rhs()?;
+    let conditions = filters
+        .iter()
+        .map(|filter| {
+            if !keeps_semantics(filter) {
+                return None;
+            }
+            Some(if needs_parens_in_andalso(filter) {
+                format!("({})", text(filter.syntax()))
+            } else {
+                text(filter.syntax())
+            })
+        })
+        .collect::<Option<Vec<String>>>()?
+        .join(" andalso ");
+    let pat_text = text(pat.syntax());
+    let template_text = text(template.syntax());
+    let is_var = matches!(pat, ast::Expr::ExprMax(ast::ExprMax::Var(_)));
+    let (function, fun) = if !is_var {
+        // Unlike a generator, a fun raises an exception for the elements
+        // not matching its pattern, so add a clause skipping them.
+        let value = if conditions.is_empty() {
+            format!("{{true, {template_text}}}")
+        } else {
+            format!("{conditions} andalso {{true, {template_text}}}")
+        };
+        (
+            "filtermap",
+            format!("fun({pat_text}) -> {value}; (_) -> false end"),
+        )
+    } else if conditions.is_empty() {
+        if template_text == pat_text {
+            return None;
+        }
+        ("map", format!("fun({pat_text}) -> {template_text} end"))
+    } else if template_text == pat_text {
+        ("filter", format!("fun({pat_text}) -> {conditions} end"))
+    } else {
+        (
+            "filtermap",
+            format!("fun({pat_text}) -> {conditions} andalso {{true, {template_text}}} end"),
+        )
+    };
+    let replacement = format!("lists:{function}({fun}, {})", text(list.syntax()));
+    let range = comprehension.syntax().text_range();
+    acc.add(
+        AssistId("comprehension_to_lists_call", AssistKind::RefactorRewrite),
+        format!("Convert to lists:{function}/2"),
+        None,
+        range,
+        None,
+        |edit| edit.replace(range, replacement),
+    )
+}
+
+// Assist: lists_call_to_comprehension
+//
+// Converts a call to `lists:map/2`, `lists:filter/2` or
+// `lists:filtermap/2` with a fun to a list comprehension, the operands of
+// `andalso` in the fun becoming filters.
+//
+// ```
+// names(Users) -> lists:fil~termap(fun(U) -> is_active(U) andalso {true, name(U)} end, Users).
+// ```
+// ->
+// ```
+// names(Users) -> [name(U) || U <- Users, is_active(U)].
+// ```
+pub(crate) fn lists_call_to_comprehension(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let call = ctx.find_node_at_offset::<ast::Call>()?;
+    let ast::Expr::Remote(remote) = call.expr()? else {
+        return None;
+    };
+    if text(remote.module()?.module()?.syntax()) != "lists" {
+        return None;
+    }
+    let function = text(remote.fun()?.syntax());
+    let args: Vec<ast::Expr> = call.args()?.args().collect();
+    let [ast::Expr::ExprMax(ast::ExprMax::AnonymousFun(fun)), list] = args.as_slice() else {
+        return None;
+    };
+    let clauses: Vec<ast::FunClause> = fun.clauses().collect();
+    let (pat, body) = clause_parts(clauses.first()?)?;
+    let is_var = matches!(pat, ast::Expr::ExprMax(ast::ExprMax::Var(_)));
+    let (template, filters) = match (function.as_str(), clauses.len()) {
+        ("map", 1) if is_var => (body, Vec::new()),
+        ("filter", 1) if is_var => (pat.clone(), andalso_operands(&body)),
+        ("filtermap", 1) if is_var => true_value(&body)?,
+        ("filtermap", 2) => {
+            let (skip_pat, skip_body) = clause_parts(&clauses[1])?;
+            if text(skip_pat.syntax()) != "_" || text(skip_body.syntax()) != "false" {
+                return None;
+            }
+            true_value(&body)?
+        }
+        _ => return None,
+    };
+    if !filters.iter().all(keeps_semantics)
+        || template
+            .syntax()
+            .descendants()
+            .any(|node| node.kind() == SyntaxKind::MATCH_EXPR)
+    {
+        return None;
+    }
+    let mut parts = vec![format!("{} <- {}", text(pat.syntax()), text(list.syntax()))];
+    parts.extend(filters.iter().map(|filter| text(filter.syntax())));
+    let replacement = format!("[{} || {}]", text(template.syntax()), parts.join(", "));
+    let range = call.syntax().text_range();
+    acc.add(
+        AssistId("lists_call_to_comprehension", AssistKind::RefactorRewrite),
+        "Convert to list comprehension",
+        None,
+        range,
+        None,
+        |edit| edit.replace(range, replacement),
+    )
+}
+
+fn text(node: &SyntaxNode) -> String {
+    node.text().to_string()
+}
+
+/// The single argument and the single body expression of a fun clause
+/// without a guard.
+fn clause_parts(clause: &ast::FunClause) -> Option<(ast::Expr, ast::Expr)> {
+    if clause.guard().is_some() || clause.name().is_some() {
+        return None;
+    }
+    let args: Vec<ast::Expr> = clause.args()?.args().collect();
+    let exprs: Vec<ast::Expr> = clause.body()?.exprs().collect();
+    match (args.as_slice(), exprs.as_slice()) {
+        ([arg], [expr]) => Some((arg.clone(), expr.clone())),
+        _ => None,
+    }
+}
+
+/// Splits `Filters andalso {true, Value}`, or `{true, Value}`, the body
+/// of a fun given to `lists:filtermap/2`.
+fn true_value(body: &ast::Expr) -> Option<(ast::Expr, Vec<ast::Expr>)> {
+    let mut filters = andalso_operands(body);
+    let ast::Expr::ExprMax(ast::ExprMax::Tuple(tuple)) = filters.pop()? else {
+        return None;
+    };
+    let elements: Vec<ast::Expr> = tuple.expr().collect();
+    match elements.as_slice() {
+        [tag, value] if text(tag.syntax()) == "true" => Some((value.clone(), filters)),
+        _ => None,
+    }
+}
+
+fn andalso_operands(expr: &ast::Expr) -> Vec<ast::Expr> {
+    if let ast::Expr::BinaryOpExpr(op) = expr {
+        if let (Some((BinaryOp::LogicOp(LogicOp::And { lazy: true }), _)), Some(lhs), Some(rhs)) =
+            (op.op(), op.lhs(), op.rhs())
+        {
+            let mut operands = andalso_operands(&lhs);
+            operands.extend(andalso_operands(&rhs));
+            return operands;
+        }
+    }
+    vec![expr.clone()]
+}
+
+fn needs_parens_in_andalso(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::BinaryOpExpr(op) => matches!(
+            op.op(),
+            Some((
+                BinaryOp::LogicOp(LogicOp::Or { lazy: true }) | BinaryOp::Send,
+                _
+            ))
+        ),
+        ast::Expr::CatchExpr(_) => true,
+        _ => false,
+    }
+}
+
+/// Whether a filter behaves the same in a comprehension and in a fun. A
+/// filter which is a guard expression fails rather than raising an
+/// exception in a comprehension, so it must be a test which cannot raise
+/// one. Any other filter is evaluated like in a fun, unless it binds
+/// variables.
+fn keeps_semantics(filter: &ast::Expr) -> bool {
+    let unknown = std::iter::once(filter.syntax().clone())
+        .chain(filter.syntax().descendants())
+        .any(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::MACRO_CALL_EXPR | SyntaxKind::MATCH_EXPR
+            )
+        });
+    !unknown && (is_safe_test(filter) || !is_guard_expr(filter))
+}
+
+/// A test always evaluating to a boolean, without raising an exception.
+fn is_safe_test(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::ExprMax(ast::ExprMax::ParenExpr(paren)) => {
+            paren.expr().is_some_and(|expr| is_safe_test(&expr))
+        }
+        ast::Expr::ExprMax(ast::ExprMax::Atom(atom)) => {
+            matches!(text(atom.syntax()).as_str(), "true" | "false")
+        }
+        ast::Expr::BinaryOpExpr(op) => match (op.op(), op.lhs(), op.rhs()) {
+            (Some((BinaryOp::CompOp(_), _)), Some(lhs), Some(rhs)) => {
+                is_atomic(&lhs) && is_atomic(&rhs)
+            }
+            (Some((BinaryOp::LogicOp(_), _)), Some(lhs), Some(rhs)) => {
+                is_safe_test(&lhs) && is_safe_test(&rhs)
+            }
+            _ => false,
+        },
+        ast::Expr::UnaryOpExpr(op) => match (op.op(), op.operand()) {
+            (Some((UnaryOp::Not, _)), Some(operand)) => is_safe_test(&operand),
+            _ => false,
+        },
+        ast::Expr::Call(call) => {
+            let args: Option<Vec<ast::Expr>> = call.args().map(|args| args.args().collect());
+            match (guard_bif_name(call), args.as_deref()) {
+                (Some(name), Some([arg])) => name.starts_with("is_") && is_atomic(arg),
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// A variable or a literal, or a tuple of them, which cannot raise an
+/// exception when evaluated.
+fn is_atomic(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::ExprMax(ast::ExprMax::Tuple(tuple)) => tuple.expr().all(|expr| is_atomic(&expr)),
+        ast::Expr::ExprMax(
+            ast::ExprMax::Var(_)
+            | ast::ExprMax::Atom(_)
+            | ast::ExprMax::Integer(_)
+            | ast::ExprMax::Float(_)
+            | ast::ExprMax::Char(_)
+            | ast::ExprMax::String(_),
+        ) => true,
+        _ => false,
+    }
+}
+
+/// Whether the expression is allowed in a guard, as far as can be told
+/// from its syntax.
+fn is_guard_expr(expr: &ast::Expr) -> bool {
+    std::iter::once(expr.syntax().clone())
+        .chain(expr.syntax().descendants())
+        .all(|node| match node.kind() {
+            SyntaxKind::CALL => ast::Call::cast(node)
+                .and_then(|call| guard_bif_name(&call))
+                .is_some(),
+            SyntaxKind::BINARY_OP_EXPR => ast::BinaryOpExpr::cast(node)
+                .and_then(|op| op.op())
+                .is_some_and(|(op, _)| op != BinaryOp::Send),
+            SyntaxKind::ANONYMOUS_FUN
+            | SyntaxKind::BINARY_COMPREHENSION
+            | SyntaxKind::BLOCK_EXPR
+            | SyntaxKind::CASE_EXPR
+            | SyntaxKind::CATCH_EXPR
+            | SyntaxKind::IF_EXPR
+            | SyntaxKind::LIST_COMPREHENSION
+            | SyntaxKind::MAP_COMPREHENSION
+            | SyntaxKind::MATCH_EXPR
+            | SyntaxKind::MAYBE_EXPR
+            | SyntaxKind::RECEIVE_EXPR
+            | SyntaxKind::TRY_EXPR => false,
+            _ => true,
+        })
+}
+
+/// The name of the guard BIF called, either as a local call or as a call
+/// to the `erlang` module.
+fn guard_bif_name(call: &ast::Call) -> Option<String> {
+    let name = match call.expr()? {
+        ast::Expr::ExprMax(ast::ExprMax::Atom(atom)) => text(atom.syntax()),
+        ast::Expr::Remote(remote) => {
+            if text(remote.module()?.module()?.syntax()) != "erlang" {
+                return None;
+            }
+            text(remote.fun()?.syntax())
+        }
+        _ => return None,
+    };
+    GUARD_BIFS.contains(&name.as_str()).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn comprehension_to_map() {
+        check_assist(
+            comprehension_to_lists_call,
+            "Convert to lists:map/2",
+            r#"
+-module(main).
+names(Users) -> [name(U) ~|| U <- Users].
+name(U) -> U.
+"#,
+            expect![[r#"
+                -module(main).
+                names(Users) -> lists:map(fun(U) -> name(U) end, Users).
+                name(U) -> U.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn comprehension_to_filter() {
+        check_assist(
+            comprehension_to_lists_call,
+            "Convert to lists:filter/2",
+            r#"
+-module(main).
+small(Xs, Max) -> [X ~|| X <- Xs, is_integer(X), X < Max orelse X =:= 0].
+"#,
+            expect![[r#"
+                -module(main).
+                small(Xs, Max) -> lists:filter(fun(X) -> is_integer(X) andalso (X < Max orelse X =:= 0) end, Xs).
+            "#]],
+        )
+    }
+
+    #[test]
+    fn comprehension_with_pattern_to_filtermap() {
+        check_assist(
+            comprehension_to_lists_call,
+            "Convert to lists:filtermap/2",
+            r#"
+-module(main).
+values(Pairs) -> [V ~|| {ok, V} <- Pairs, is_active(V)].
+is_active(V) -> V =/= undefined.
+"#,
+            expect![[r#"
+                -module(main).
+                values(Pairs) -> lists:filtermap(fun({ok, V}) -> is_active(V) andalso {true, V}; (_) -> false end, Pairs).
+                is_active(V) -> V =/= undefined.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn raising_guard_not_applicable() {
+        // `length(X)` raises for an improper list, which the
+        // comprehension skips but a fun does not.
+        check_assist_not_applicable(
+            comprehension_to_lists_call,
+            r#"
+-module(main).
+long(Xs) -> [X ~|| X <- Xs, length(X) > 2].
+"#,
+        );
+    }
+
+    #[test]
+    fn map_to_comprehension() {
+        check_assist(
+            lists_call_to_comprehension,
+            "Convert to list comprehension",
+            r#"
+-module(main).
+names(Users) -> lists:m~ap(fun(U) -> name(U) end, Users).
+name(U) -> U.
+"#,
+            expect![[r#"
+                -module(main).
+                names(Users) -> [name(U) || U <- Users].
+                name(U) -> U.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn filtermap_to_comprehension() {
+        check_assist(
+            lists_call_to_comprehension,
+            "Convert to list comprehension",
+            r#"
+-module(main).
+values(Pairs) -> lists:filter~map(fun({ok, V}) -> is_atom(V) andalso {true, V}; (_) -> false end, Pairs).
+"#,
+            expect![[r#"
+                -module(main).
+                values(Pairs) -> [V || {ok, V} <- Pairs, is_atom(V)].
+            "#]],
+        )
+    }
+
+    #[test]
+    fn fun_with_pattern_not_applicable() {
+        // `lists:map/2` raises for an element not matching the pattern,
+        // which a comprehension would skip.
+        check_assist_not_applicable(
+            lists_call_to_comprehension,
+            r#"
+-module(main).
+values(Pairs) -> lists:m~ap(fun({ok, V}) -> V end, Pairs).
+"#,
+        );
+    }
+}
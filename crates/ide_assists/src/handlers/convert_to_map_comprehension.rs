@@ -0,0 +1,216 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxNode;
+
+use crate::AssistContext;
+use crate::Assists;
+
+/// Map comprehensions were introduced in OTP 26.
+const MAP_COMPREHENSION_RELEASE: u32 = 26;
+
+// Assist: convert_to_map_comprehension
+//
+// Converts a call to `maps:from_list/1` with a list comprehension, or a
+// call to `lists:map/2`, building `{Key, Value}` tuples to a map
+// comprehension. Only offered when the project is known to require OTP 26
+// or later.
+//
+// ```
+// index(Users) -> maps:fr~om_list([{id(U), U} || U <- Users]).
+// ```
+// ->
+// ```
+// index(Users) -> #{id(U) => U || U <- Users}.
+// ```
+pub(crate) fn convert_to_map_comprehension(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let call = ctx.find_node_at_offset::<ast::Call>()?;
+    if remote_name(&call)? != ("maps".to_string(), "from_list".to_string()) {
+        return None;
+    }
+    let args: Vec<ast::Expr> = call.args()?.args().collect();
+    let [arg] = args.as_slice() else {
+        return None;
+    };
+    let (template, qualifiers) = match arg {
+        ast::Expr::ExprMax(ast::ExprMax::ListComprehension(comprehension)) => (
+            comprehension.expr()?,
+            text(comprehension.lc_exprs()?.syntax()),
+        ),
+        ast::Expr::Call(inner) => lists_map_parts(inner)?,
+        _ => return None,
+    };
+    let ast::Expr::ExprMax(ast::ExprMax::Tuple(tuple)) = template else {
+        return None;
+    };
+    let elements: Vec<ast::Expr> = tuple.expr().collect();
+    let [key, value] = elements.as_slice() else {
+        return None;
+    };
+    if ctx.min_otp_release()? < MAP_COMPREHENSION_RELEASE {
+        return None;
+    }
+    let replacement = format!(
+        "#{{{} => {} || {}}}",
+        text(key.syntax()),
+        text(value.syntax()),
+        qualifiers
+    );
+    let range = call.syntax().text_range();
+    acc.add(
+        AssistId("convert_to_map_comprehension", AssistKind::RefactorRewrite),
+        "Convert to map comprehension",
+        None,
+        range,
+        None,
+        |edit| edit.replace(range, replacement),
+    )
+}
+
+fn text(node: &SyntaxNode) -> String {
+    node.text().to_string()
+}
+
+fn remote_name(call: &ast::Call) -> Option<(String, String)> {
+    let ast::Expr::Remote(remote) = call.expr()? else {
+        return None;
+    };
+    Some((
+        text(remote.module()?.module()?.syntax()),
+        text(remote.fun()?.syntax()),
+    ))
+}
+
+/// The body of the fun and the generator equivalent to a call to
+/// `lists:map/2` with a fun of a single clause, whose argument is a
+/// variable so that no element can be skipped by the generator.
+fn lists_map_parts(call: &ast::Call) -> Option<(ast::Expr, String)> {
+    if remote_name(call)? != ("lists".to_string(), "map".to_string()) {
+        return None;
+    }
+    let args: Vec<ast::Expr> = call.args()?.args().collect();
+    let [ast::Expr::ExprMax(ast::ExprMax::AnonymousFun(fun)), list] = args.as_slice() else {
+        return None;
+    };
+    let clauses: Vec<ast::FunClause> = fun.clauses().collect();
+    let [clause] = clauses.as_slice() else {
+        return None;
+    };
+    if clause.guard().is_some() || clause.name().is_some() {
+        return None;
+    }
+    let params: Vec<ast::Expr> = clause.args()?.args().collect();
+    let exprs: Vec<ast::Expr> = clause.body()?.exprs().collect();
+    match (params.as_slice(), exprs.as_slice()) {
+        ([var @ ast::Expr::ExprMax(ast::ExprMax::Var(_))], [body]) => Some((
+            body.clone(),
+            format!("{} <- {}", text(var.syntax()), text(list.syntax())),
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn from_list_comprehension() {
+        check_assist(
+            convert_to_map_comprehension,
+            "Convert to map comprehension",
+            r#"
+//- otp_version:26.2.1
+-module(main).
+index(Users) -> maps:fr~om_list([{id(U), U} || U <- Users, U =/= undefined]).
+id(U) -> U.
+"#,
+            expect![[r#"
+                -module(main).
+                index(Users) -> #{id(U) => U || U <- Users, U =/= undefined}.
+                id(U) -> U.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn from_lists_map() {
+        check_assist(
+            convert_to_map_comprehension,
+            "Convert to map comprehension",
+            r#"
+//- otp_version:27.0
+-module(main).
+index(Users) -> maps:from_l~ist(lists:map(fun(U) -> {id(U), U} end, Users)).
+id(U) -> U.
+"#,
+            expect![[r#"
+                -module(main).
+                index(Users) -> #{id(U) => U || U <- Users}.
+                id(U) -> U.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn not_applicable_before_otp_26() {
+        check_assist_not_applicable(
+            convert_to_map_comprehension,
+            r#"
+//- otp_version:25.3
+-module(main).
+index(Users) -> maps:fr~om_list([{id(U), U} || U <- Users]).
+id(U) -> U.
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_unknown_otp_release() {
+        check_assist_not_applicable(
+            convert_to_map_comprehension,
+            r#"
+-module(main).
+index(Users) -> maps:fr~om_list([{id(U), U} || U <- Users]).
+id(U) -> U.
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_pattern_in_fun() {
+        check_assist_not_applicable(
+            convert_to_map_comprehension,
+            r#"
+//- otp_version:26.2.1
+-module(main).
+index(Users) -> maps:from_l~ist(lists:map(fun({user, Id}) -> {Id, true} end, Users)).
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_other_elements() {
+        check_assist_not_applicable(
+            convert_to_map_comprehension,
+            r#"
+//- otp_version:26.2.1
+-module(main).
+index(Pairs) -> maps:from_l~ist([P || P <- Pairs]).
+"#,
+        );
+    }
+}
@@ -0,0 +1,574 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_ide_db::elp_base_db::path_for_file;
+use elp_ide_db::elp_base_db::AnchoredPathBuf;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use fxhash::FxHashSet;
+use hir::FaDef;
+use hir::FunctionDef;
+use hir::InFile;
+use hir::NameArity;
+use stdx::format_to;
+use text_edit::TextEdit;
+use text_edit::TextRange;
+
+use crate::AssistContext;
+use crate::Assists;
+
+// Assist: extract_interface
+//
+// Splits a module into a facade, keeping the exported functions in the
+// selection, or in the export attribute under the cursor, as its public
+// API, and a `_impl` module holding the implementation. Each function
+// of the facade keeps its spec and delegates to the implementation, and
+// the exported types are re-exported as aliases. The exported functions
+// left out of the selection stay exported, in an export attribute of
+// their own, and delegate to the implementation as well, so that callers
+// of the module are unaffected. Not offered for modules implementing a
+// behaviour or compiled with `export_all`.
+//
+// ```
+// -module(store).
+// -export([get/~1]).
+//
+// -spec get(Key :: atom()) -> binary().
+// get(Key) -> lookup(Key).
+//
+// lookup(_Key) -> <<>>.
+// ```
+// ->
+// ```
+// //- /src/store.erl
+// -module(store).
+//
+// -export([get/1]).
+//
+// -spec get(Key :: atom()) -> binary().
+// get(Key) ->
+//     store_impl:get(Key).
+// //- /src/store_impl.erl
+// -module(store_impl).
+// -export([get/1]).
+//
+// -spec get(Key :: atom()) -> binary().
+// get(Key) -> lookup(Key).
+//
+// lookup(_Key) -> <<>>.
+// ```
+pub(crate) fn extract_interface(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let export = ctx.find_node_at_offset::<ast::ExportAttribute>()?;
+    let selection = ctx.selection_trimmed();
+    let functions: Vec<FunctionDef> = export
+        .funs()
+        .filter(|fa| {
+            ctx.has_empty_selection()
+                || fa
+                    .syntax()
+                    .text_range()
+                    .intersect(selection)
+                    .is_some_and(|range| !range.is_empty())
+        })
+        .filter_map(
+            |fa| match ctx.sema.to_def(InFile::new(ctx.file_id(), &fa))? {
+                FaDef::Function(function) => Some(function),
+                _ => None,
+            },
+        )
+        .collect();
+    if functions.is_empty() {
+        return None;
+    }
+    let def_map = ctx.sema.def_map(ctx.file_id());
+    if def_map.is_export_all() {
+        return None;
+    }
+    let selected: FxHashSet<&NameArity> = functions.iter().map(|function| &function.name).collect();
+    let mut kept: Vec<&FunctionDef> = def_map
+        .get_exported_functions()
+        .iter()
+        .filter(|name| !selected.contains(name))
+        .filter_map(|name| def_map.get_function(name))
+        .collect();
+    kept.sort_by_key(|function| {
+        function
+            .source(ctx.db().upcast())
+            .first()
+            .map(|decl| decl.syntax().text_range().start())
+    });
+    // The implementation module is created next to the file, which a
+    // virtual document does not have
+    if path_for_file(ctx.db(), ctx.file_id())?.as_path().is_none() {
+        return None;
+    }
+
+    let module_name = ctx.sema.module_name(ctx.file_id())?;
+    let impl_name = format!("{}_impl", module_name.as_str());
+    if ctx
+        .sema
+        .resolve_module_name(ctx.file_id(), &impl_name)
+        .is_some()
+    {
+        return None;
+    }
+    let form_list = ctx.sema.form_list(ctx.file_id());
+    if form_list.behaviour_attributes().next().is_some() {
+        return None;
+    }
+    let module_attribute = ctx.form_ast(form_list.module_attribute()?.form_id);
+    let file_text = ctx.db().file_text(ctx.file_id());
+    let header = &file_text[..usize::from(module_attribute.syntax().text_range().start())];
+
+    let local_types: FxHashSet<NameArity> = form_list
+        .type_aliases()
+        .map(|(_, alias)| alias.name().clone())
+        .collect();
+    let mut used_types = FxHashSet::default();
+    let mut facade = format!(
+        "{header}-module({}).\n\n-export([{}]).\n",
+        module_name.as_str(),
+        functions
+            .iter()
+            .map(|function| function.name.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    if !kept.is_empty() {
+        format_to!(
+            facade,
+            "-export([{}]).\n",
+            kept.iter()
+                .map(|function| function.name.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    let mut exported_types: Vec<&NameArity> = def_map.get_exported_types().iter().collect();
+    exported_types.sort_by_key(|name| name.to_string());
+    if !exported_types.is_empty() {
+        format_to!(
+            facade,
+            "-export_type([{}]).\n\n",
+            exported_types
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        for name in &exported_types {
+            let params = (1..=name.arity())
+                .map(|idx| format!("T{idx}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let name = name.name();
+            format_to!(
+                facade,
+                "-type {name}({params}) :: {impl_name}:{name}({params}).\n"
+            );
+        }
+    }
+    for function in functions.iter().chain(kept.iter().copied()) {
+        facade.push('\n');
+        if let Some(spec) = &function.spec {
+            let spec = spec.source(ctx.db().upcast());
+            if let Some(spec) =
+                qualified_spec(spec.syntax(), &impl_name, &local_types, &mut used_types)
+            {
+                facade.push_str(&spec);
+                facade.push('\n');
+            }
+        }
+        let args = arg_names(ctx, function).join(", ");
+        let name = function.name.name();
+        format_to!(
+            facade,
+            "{name}({args}) ->\n    {impl_name}:{name}({args}).\n"
+        );
+    }
+
+    let mut implementation = TextEdit::builder();
+    if let Some(name) = module_attribute.name() {
+        implementation.replace(name.syntax().text_range(), impl_name.clone());
+    }
+    let mut types: Vec<String> = used_types
+        .iter()
+        .filter(|name| !def_map.get_exported_types().contains(name))
+        .map(|name| name.to_string())
+        .collect();
+    types.sort();
+    if !types.is_empty() {
+        implementation.insert(
+            module_attribute.syntax().text_range().end(),
+            format!("\n-export_type([{}]).", types.join(", ")),
+        );
+    }
+    let source = ctx.sema.parse(ctx.file_id()).value;
+    for range in self_references(source.syntax(), module_name.as_str()) {
+        implementation.replace(range, impl_name.clone());
+    }
+    let mut implementation_text = file_text.to_string();
+    implementation.finish().apply(&mut implementation_text);
+
+    let file_range = source.syntax().text_range();
+    acc.add(
+        AssistId("extract_interface", AssistKind::RefactorExtract),
+        format!("Extract the implementation into `{impl_name}`"),
+        None,
+        export.syntax().text_range(),
+        None,
+        |builder| {
+            builder.edit_file(ctx.file_id());
+            builder.replace(file_range, facade);
+            builder.create_file(
+                AnchoredPathBuf {
+                    anchor: ctx.file_id(),
+                    path: format!("./{impl_name}.erl"),
+                },
+                implementation_text,
+            );
+        },
+    )
+}
+
+/// The text of a spec, with the types defined in the module qualified by
+/// the implementation module. `None` when it refers to records or
+/// macros, which the facade does not have.
+fn qualified_spec(
+    spec: &SyntaxNode,
+    impl_name: &str,
+    local_types: &FxHashSet<NameArity>,
+    used_types: &mut FxHashSet<NameArity>,
+) -> Option<String> {
+    if spec.descendants().any(|node| {
+        matches!(
+            node.kind(),
+            SyntaxKind::RECORD_EXPR | SyntaxKind::MACRO_CALL_EXPR
+        )
+    }) {
+        return None;
+    }
+    let start = spec.text_range().start();
+    let mut text = spec.text().to_string();
+    let mut offsets = Vec::new();
+    for call in spec.descendants().filter_map(ast::Call::cast) {
+        if let Some(ast::Expr::ExprMax(ast::ExprMax::Atom(atom))) = call.expr() {
+            let arity = call.args().map_or(0, |args| args.args().count() as u32);
+            let type_name = atom.text();
+            if let Some(name) = local_types.iter().find(|name| {
+                Some(name.name().as_str()) == type_name.as_deref() && name.arity() == arity
+            }) {
+                used_types.insert(name.clone());
+                offsets.push(usize::from(atom.syntax().text_range().start() - start));
+            }
+        }
+    }
+    // From the end, so that the offsets still to insert at are unchanged
+    offsets.sort_unstable();
+    for offset in offsets.into_iter().rev() {
+        text.insert_str(offset, &format!("{impl_name}:"));
+    }
+    Some(text)
+}
+
+/// Names for the parameters of the delegating function, which must be
+/// distinct variables.
+fn arg_names(ctx: &AssistContext, function: &FunctionDef) -> Vec<String> {
+    let names = function.arg_names(ctx.db().upcast()).unwrap_or_default();
+    let mut seen = FxHashSet::default();
+    (0..function.name.arity() as usize)
+        .map(|idx| {
+            let name = names
+                .get(idx)
+                .map(|name| name.trim_start_matches('_'))
+                .filter(|name| name.starts_with(|c: char| c.is_ascii_uppercase()))
+                .filter(|name| !seen.contains(*name))
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("Arg{}", idx + 1));
+            seen.insert(name.clone());
+            name
+        })
+        .collect()
+}
+
+/// Ranges of the module name in remote calls and funs, `Module:f(..)` and
+/// `fun Module:f/1`, of the module itself.
+fn self_references(source: &SyntaxNode, module_name: &str) -> Vec<TextRange> {
+    source
+        .descendants()
+        .filter_map(ast::Atom::cast)
+        .filter(|atom| {
+            let parent = atom.syntax().parent().map(|parent| parent.kind());
+            matches!(
+                parent,
+                Some(SyntaxKind::REMOTE_MODULE) | Some(SyntaxKind::MODULE)
+            ) && atom.text().as_deref() == Some(module_name)
+        })
+        .map(|atom| atom.syntax().text_range())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn extract_exported_functions() {
+        check_assist(
+            extract_interface,
+            "Extract the implementation into `store_impl`",
+            r#"
+//- /src/store.erl
+%% Copyright
+-module(store).
+-export([~get/1, put/2~]).
+-export_type([key/0]).
+
+-type key() :: atom().
+-type value() :: binary().
+
+-spec get(key()) -> value().
+get(Key) -> lookup(Key).
+
+put(_Key, Key) -> store:get(Key).
+
+size() -> 0.
+
+lookup(_Key) -> <<>>.
+"#,
+            expect![[r#"
+                //- /src/store.erl
+                %% Copyright
+                -module(store).
+
+                -export([get/1, put/2]).
+                -export_type([key/0]).
+
+                -type key() :: store_impl:key().
+
+                -spec get(store_impl:key()) -> store_impl:value().
+                get(Key) ->
+                    store_impl:get(Key).
+
+                put(Key, Arg2) ->
+                    store_impl:put(Key, Arg2).
+                //- /src/store_impl.erl
+                %% Copyright
+                -module(store_impl).
+                -export_type([value/0]).
+                -export([get/1, put/2]).
+                -export_type([key/0]).
+
+                -type key() :: atom().
+                -type value() :: binary().
+
+                -spec get(key()) -> value().
+                get(Key) -> lookup(Key).
+
+                put(_Key, Key) -> store_impl:get(Key).
+
+                size() -> 0.
+
+                lookup(_Key) -> <<>>.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn re_export_type_with_parameters() {
+        check_assist(
+            extract_interface,
+            "Extract the implementation into `store_impl`",
+            r#"
+//- /src/store.erl
+-module(store).
+-export([new/~0]).
+-export_type([t/2]).
+-opaque t(K, V) :: #{K => V}.
+new() -> #{}.
+"#,
+            expect![[r#"
+                //- /src/store.erl
+                -module(store).
+
+                -export([new/0]).
+                -export_type([t/2]).
+
+                -type t(T1, T2) :: store_impl:t(T1, T2).
+
+                new() ->
+                    store_impl:new().
+                //- /src/store_impl.erl
+                -module(store_impl).
+                -export([new/0]).
+                -export_type([t/2]).
+                -opaque t(K, V) :: #{K => V}.
+                new() -> #{}.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn spec_with_macro_is_dropped() {
+        check_assist(
+            extract_interface,
+            "Extract the implementation into `store_impl`",
+            r#"
+//- /src/store.erl
+-module(store).
+-export([get/~1]).
+-define(KEY, atom()).
+-spec get(?KEY) -> ok.
+get(_Key) -> ok.
+"#,
+            expect![[r#"
+                //- /src/store.erl
+                -module(store).
+
+                -export([get/1]).
+
+                get(Key) ->
+                    store_impl:get(Key).
+                //- /src/store_impl.erl
+                -module(store_impl).
+                -export([get/1]).
+                -define(KEY, atom()).
+                -spec get(?KEY) -> ok.
+                get(_Key) -> ok.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn keep_exports_left_out_of_the_selection() {
+        check_assist(
+            extract_interface,
+            "Extract the implementation into `store_impl`",
+            r#"
+//- /src/store.erl
+-module(store).
+-export([~get/1~, put/2, delete/1]).
+delete(_Key) -> ok.
+get(_Key) -> ok.
+-spec put(atom(), term()) -> ok.
+put(_Key, _Value) -> ok.
+"#,
+            expect![[r#"
+                //- /src/store.erl
+                -module(store).
+
+                -export([get/1]).
+                -export([delete/1, put/2]).
+
+                get(Key) ->
+                    store_impl:get(Key).
+
+                delete(Key) ->
+                    store_impl:delete(Key).
+
+                -spec put(atom(), term()) -> ok.
+                put(Key, Value) ->
+                    store_impl:put(Key, Value).
+                //- /src/store_impl.erl
+                -module(store_impl).
+                -export([get/1, put/2, delete/1]).
+                delete(_Key) -> ok.
+                get(_Key) -> ok.
+                -spec put(atom(), term()) -> ok.
+                put(_Key, _Value) -> ok.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn keep_exports_of_other_attributes() {
+        check_assist(
+            extract_interface,
+            "Extract the implementation into `store_impl`",
+            r#"
+//- /src/store.erl
+-module(store).
+-export([get/~1]).
+-export([put/2]).
+get(_Key) -> ok.
+put(_Key, _Value) -> ok.
+"#,
+            expect![[r#"
+                //- /src/store.erl
+                -module(store).
+
+                -export([get/1]).
+                -export([put/2]).
+
+                get(Key) ->
+                    store_impl:get(Key).
+
+                put(Key, Value) ->
+                    store_impl:put(Key, Value).
+                //- /src/store_impl.erl
+                -module(store_impl).
+                -export([get/1]).
+                -export([put/2]).
+                get(_Key) -> ok.
+                put(_Key, _Value) -> ok.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn not_applicable_with_export_all() {
+        check_assist_not_applicable(
+            extract_interface,
+            r#"
+//- /src/store.erl
+-module(store).
+-compile(export_all).
+-export([get/~1]).
+get(_Key) -> ok.
+helper() -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_for_behaviour() {
+        check_assist_not_applicable(
+            extract_interface,
+            r#"
+//- /src/store.erl
+-module(store).
+-behaviour(gen_server).
+-export([get/~1]).
+get(_Key) -> ok.
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_impl_exists() {
+        check_assist_not_applicable(
+            extract_interface,
+            r#"
+//- /src/store.erl
+-module(store).
+-export([get/~1]).
+get(_Key) -> ok.
+//- /src/store_impl.erl
+-module(store_impl).
+"#,
+        );
+    }
+}
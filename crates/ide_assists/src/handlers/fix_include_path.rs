@@ -0,0 +1,249 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistContextDiagnosticCode;
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_ide_db::elp_base_db::AbsPath;
+use elp_ide_db::elp_base_db::AppData;
+use elp_ide_db::elp_base_db::SourceDatabase;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use itertools::Itertools;
+
+use crate::assist_context::AssistContext;
+use crate::assist_context::Assists;
+
+/// Headers less similar to the missing one are not offered.
+const MIN_SIMILARITY: f64 = 0.8;
+const MAX_CANDIDATES: usize = 5;
+
+// Assist: fix_include_path
+//
+// Replace an include which cannot be found by one of the headers of the
+// project or OTP with a similar file name.
+//
+// ```
+// -include_lib("app_b/include/record.hrl").
+// %%<^^^^^^^^^^^ 💡 E1516: can't find include lib "app_b/include/record.hrl"
+// ```
+// ->
+// ```
+// -include_lib("app_b/include/records.hrl").
+// ```
+pub(crate) fn fix_include_path(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    for d in ctx.diagnostics {
+        if let AssistContextDiagnosticCode::IncludeNotFound = d.code {
+            let Some((range, path, is_lib)) = include_at(ctx, d.range.start()) else {
+                continue;
+            };
+            let wanted = path.rsplit('/').next().unwrap_or(&path);
+            for attribute in similar_headers(ctx, wanted, is_lib) {
+                let id = AssistId("fix_include_path", AssistKind::QuickFix);
+                acc.add_from_diagnostic(
+                    id,
+                    format!("Use `{attribute}`"),
+                    None,
+                    (*d).clone(),
+                    range,
+                    None,
+                    |builder| {
+                        builder.edit_file(ctx.frange.file_id);
+                        builder.replace(range, format!("{attribute}."));
+                    },
+                );
+            }
+        }
+    }
+    Some(())
+}
+
+/// The range, path and kind of the include attribute at the offset.
+fn include_at(ctx: &AssistContext, offset: TextSize) -> Option<(TextRange, String, bool)> {
+    let path = |details: ast::AstChildren<ast::IncludeDetail>| -> String {
+        details
+            .filter_map(|detail| match detail {
+                ast::IncludeDetail::String(str) => Some(String::from(str)),
+                ast::IncludeDetail::MacroCallExpr(_) => None,
+            })
+            .collect()
+    };
+    if let Some(include) = ctx.find_node_at_custom_offset::<ast::PpIncludeLib>(offset) {
+        Some((include.syntax().text_range(), path(include.file()), true))
+    } else {
+        let include = ctx.find_node_at_custom_offset::<ast::PpInclude>(offset)?;
+        Some((include.syntax().text_range(), path(include.file()), false))
+    }
+}
+
+/// The attributes including the headers of the project and OTP whose file
+/// name is similar to the wanted one, most similar first.
+fn similar_headers(ctx: &AssistContext, wanted: &str, is_lib: bool) -> Vec<String> {
+    let db = ctx.db();
+    let file_id = ctx.file_id();
+    let Some(app_data) = db.file_app_data(file_id) else {
+        return vec![];
+    };
+    let file_dir = db
+        .source_root(db.file_source_root(file_id))
+        .path_for_file(&file_id)
+        .and_then(|path| path.as_path().and_then(|path| path.parent()))
+        .map(|dir| dir.to_path_buf());
+    let project_data = db.project_data(app_data.project_id);
+    let otp_roots = project_data
+        .otp_project_id
+        .map(|otp| db.project_data(otp).source_roots.clone())
+        .unwrap_or_default();
+    project_data
+        .source_roots
+        .iter()
+        .chain(otp_roots.iter())
+        .flat_map(|&root_id| {
+            let root = db.source_root(root_id);
+            let header_app = db.app_data(root_id);
+            root.iter()
+                .filter_map(|header| {
+                    let path = root.path_for_file(&header)?;
+                    let (name, ext) = path.name_and_extension()?;
+                    if ext != Some("hrl") {
+                        return None;
+                    }
+                    let score = strsim::jaro_winkler(wanted, &format!("{name}.hrl"));
+                    if score < MIN_SIMILARITY {
+                        return None;
+                    }
+                    let path = path.as_path()?;
+                    let attribute = if is_lib {
+                        None
+                    } else {
+                        local_include(file_dir.as_deref(), &app_data, path)
+                    };
+                    let attribute =
+                        attribute.or_else(|| include_lib(header_app.as_deref()?, path))?;
+                    Some((score, attribute))
+                })
+                .collect_vec()
+        })
+        .sorted_by(|(a, a_attr), (b, b_attr)| b.total_cmp(a).then_with(|| a_attr.cmp(b_attr)))
+        .map(|(_, attribute)| attribute)
+        .dedup()
+        .take(MAX_CANDIDATES)
+        .collect()
+}
+
+/// An `-include` of the header relative to the directory of the file or
+/// to the include path of its application, when possible.
+fn local_include(
+    file_dir: Option<&AbsPath>,
+    app_data: &AppData,
+    header: &AbsPath,
+) -> Option<String> {
+    file_dir
+        .into_iter()
+        .chain(app_data.include_path.iter().map(|dir| dir.as_path()))
+        .find_map(|dir| header.strip_prefix(dir))
+        .map(|rel| format!("-include(\"{}\")", rel.as_str()))
+}
+
+fn include_lib(header_app: &AppData, header: &AbsPath) -> Option<String> {
+    let rel = header.strip_prefix(&header_app.dir)?;
+    Some(format!(
+        "-include_lib(\"{}/{}\")",
+        header_app.name,
+        rel.as_str()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn include_lib_from_other_app() {
+        check_assist(
+            fix_include_path,
+            r#"Use `-include_lib("app_b/include/records.hrl")`"#,
+            r#"
+//- /app_a/src/main.erl app:app_a
+-module(main).
+-include_lib("app_b/include/rec~ord.hrl").
+%%<^^^^^^^^^^^ 💡 E1516: can't find include lib "app_b/include/record.hrl"
+//- /app_b/include/records.hrl app:app_b
+-record(r, {}).
+//- /app_b/include/unrelated.hrl app:app_b
+-define(X, 1).
+"#,
+            expect![[r#"
+                -module(main).
+                -include_lib("app_b/include/records.hrl").
+            "#]],
+        )
+    }
+
+    #[test]
+    fn include_from_file_directory() {
+        check_assist(
+            fix_include_path,
+            r#"Use `-include("utils.hrl")`"#,
+            r#"
+//- /app_a/src/main.erl app:app_a
+-module(main).
+-include("uti~l.hrl").
+%%<^^^^^^^ 💡 E1516: can't find include file "util.hrl"
+//- /app_a/src/utils.hrl app:app_a
+-define(UTILS, 1).
+"#,
+            expect![[r#"
+                -module(main).
+                -include("utils.hrl").
+            "#]],
+        )
+    }
+
+    #[test]
+    fn diagnostic_not_on_an_include() {
+        check_assist(
+            fix_include_path,
+            r#"Use `-include("utils.hrl")`"#,
+            r#"
+//- /app_a/src/main.erl app:app_a
+-module(main).
+%%<^^^^^^^^^^^ 💡 E1516: can't find include file "stale.hrl"
+-include("uti~l.hrl").
+%%<^^^^^^^ 💡 E1516: can't find include file "util.hrl"
+//- /app_a/src/utils.hrl app:app_a
+-define(UTILS, 1).
+"#,
+            expect![[r#"
+                -module(main).
+                -include("utils.hrl").
+            "#]],
+        )
+    }
+
+    #[test]
+    fn no_similar_header() {
+        check_assist_not_applicable(
+            fix_include_path,
+            r#"
+//- /app_a/src/main.erl app:app_a
+-module(main).
+-include("conf~ig.hrl").
+%%<^^^^^^^ 💡 E1516: can't find include file "config.hrl"
+//- /app_a/src/records.hrl app:app_a
+-record(r, {}).
+"#,
+        )
+    }
+}
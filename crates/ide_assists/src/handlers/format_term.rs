@@ -0,0 +1,277 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_syntax::NodeOrToken;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+
+use crate::AssistContext;
+use crate::Assists;
+
+/// Width the reformatted term is laid out to fit in
+const WIDTH: usize = 100;
+const INDENT: usize = 4;
+
+// Assist: format_term
+//
+// Lays out a large tuple, list or map term, such as one pasted from a
+// log, over several indented lines, keeping the parts fitting in the
+// line width on one line.
+//
+// ```
+// foo() -> #{name => "server", op~ts => [{timeout, 5000}, {retries, 3}, {backoff, exponential}], tags => [a, b]}.
+// ```
+// ->
+// ```
+// foo() -> #{
+//     name => "server",
+//     opts => [{timeout, 5000}, {retries, 3}, {backoff, exponential}],
+//     tags => [a, b]
+// }.
+// ```
+pub(crate) fn format_term(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let term = outermost_term(ctx)?;
+    let range = term.text_range();
+    let original = term.to_string();
+    // Comments cannot be kept in place once the layout changes.
+    if term
+        .descendants_with_tokens()
+        .any(|it| it.kind() == SyntaxKind::COMMENT)
+    {
+        return None;
+    }
+    let text = ctx.db().file_text(ctx.file_id());
+    let line_start = text[..usize::from(range.start())]
+        .rfind('\n')
+        .map_or(0, |idx| idx + 1);
+    let line = &text[line_start..];
+    let indent = line.len() - line.trim_start_matches([' ', '\t']).len();
+    let column = usize::from(range.start()) - line_start;
+    let line_end = line.find('\n').map_or(text.len(), |idx| line_start + idx);
+    let is_large = original.contains('\n') || line_end - line_start > WIDTH;
+    if !is_large {
+        return None;
+    }
+    let mut formatted = String::new();
+    Doc::new(&term).render(indent, column, &mut formatted);
+    if formatted == original {
+        return None;
+    }
+    acc.add(
+        AssistId("format_term", AssistKind::RefactorRewrite),
+        "Reformat term",
+        None,
+        range,
+        None,
+        |edit| edit.replace(range, formatted),
+    )
+}
+
+fn is_container(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::TUPLE | SyntaxKind::LIST | SyntaxKind::MAP_EXPR
+    )
+}
+
+/// The largest term enclosing the cursor made only of nested tuples,
+/// lists and maps.
+fn outermost_term(ctx: &AssistContext) -> Option<SyntaxNode> {
+    let start = match ctx.covering_element() {
+        NodeOrToken::Node(node) => node,
+        NodeOrToken::Token(token) => token.parent()?,
+    };
+    let mut term = start.ancestors().find(|node| is_container(node.kind()))?;
+    while let Some(parent) = term.parent() {
+        let parent = match parent.kind() {
+            SyntaxKind::MAP_FIELD => parent.parent()?,
+            kind if is_container(kind) => parent,
+            _ => break,
+        };
+        term = parent;
+    }
+    Some(term)
+}
+
+/// A term, as laid out by the pretty printer.
+enum Doc {
+    /// Printed as is
+    Text(String),
+    /// Printed on one line if it fits, otherwise with one item per line
+    Group {
+        open: &'static str,
+        items: Vec<Doc>,
+        close: &'static str,
+    },
+    /// A map key and its operator, followed by the value
+    Field(String, Box<Doc>),
+}
+
+impl Doc {
+    fn new(node: &SyntaxNode) -> Doc {
+        let items = || node.children().map(|child| Doc::new(&child)).collect();
+        match node.kind() {
+            SyntaxKind::TUPLE => Doc::Group {
+                open: "{",
+                items: items(),
+                close: "}",
+            },
+            // A list with a tail, `[H | T]`, is kept as is
+            SyntaxKind::LIST
+                if !node
+                    .children_with_tokens()
+                    .any(|it| matches!(it.kind(), SyntaxKind::PIPE | SyntaxKind::ANON_PIPE)) =>
+            {
+                Doc::Group {
+                    open: "[",
+                    items: items(),
+                    close: "]",
+                }
+            }
+            SyntaxKind::MAP_EXPR => Doc::Group {
+                open: "#{",
+                items: items(),
+                close: "}",
+            },
+            SyntaxKind::MAP_FIELD => {
+                let mut children = node.children();
+                match (children.next(), children.next()) {
+                    (Some(key), Some(value)) => {
+                        let op = node
+                            .children_with_tokens()
+                            .filter_map(|it| it.into_token())
+                            .find(|token| matches!(token.text(), "=>" | ":="))
+                            .map_or("=>".to_string(), |token| token.text().to_string());
+                        let key = Doc::new(&key).flat();
+                        Doc::Field(format!("{key} {op} "), Box::new(Doc::new(&value)))
+                    }
+                    _ => Doc::Text(node.to_string()),
+                }
+            }
+            _ => Doc::Text(node.to_string()),
+        }
+    }
+
+    fn flat(&self) -> String {
+        match self {
+            Doc::Text(text) => text.clone(),
+            Doc::Group { open, items, close } => {
+                let items: Vec<String> = items.iter().map(|item| item.flat()).collect();
+                format!("{open}{}{close}", items.join(", "))
+            }
+            Doc::Field(prefix, value) => format!("{prefix}{}", value.flat()),
+        }
+    }
+
+    /// `indent` is the indentation of the line the term starts on, and
+    /// `column` the column it starts at.
+    fn render(&self, indent: usize, column: usize, out: &mut String) {
+        match self {
+            Doc::Text(text) => out.push_str(text),
+            Doc::Field(prefix, value) => {
+                out.push_str(prefix);
+                value.render(indent, column + prefix.len(), out);
+            }
+            Doc::Group { open, items, close } => {
+                let flat = self.flat();
+                if items.is_empty() || (!flat.contains('\n') && column + flat.len() <= WIDTH) {
+                    out.push_str(&flat);
+                    return;
+                }
+                let item_indent = indent + INDENT;
+                out.push_str(open);
+                for (idx, item) in items.iter().enumerate() {
+                    out.push('\n');
+                    out.push_str(&" ".repeat(item_indent));
+                    item.render(item_indent, item_indent, out);
+                    if idx + 1 < items.len() {
+                        out.push(',');
+                    }
+                }
+                out.push('\n');
+                out.push_str(&" ".repeat(indent));
+                out.push_str(close);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn nested_term() {
+        check_assist(
+            format_term,
+            "Reformat term",
+            r#"
+-module(main).
+foo() -> #{name => "server", opts => [{timeout, 5000}, {retr~ies, 3}, {backoff, exponential}], tags => [a, b]}.
+"#,
+            expect![[r#"
+                -module(main).
+                foo() -> #{
+                    name => "server",
+                    opts => [{timeout, 5000}, {retries, 3}, {backoff, exponential}],
+                    tags => [a, b]
+                }.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn deeply_nested_term() {
+        check_assist(
+            format_term,
+            "Reformat term",
+            r#"
+-module(main).
+foo() ->
+    X = {state, [{conn, #{host := "db.internal.example.com", port := 5432, pool => {pool, 10, 20, [primary, repl~ica]}}}], undefined},
+    X.
+"#,
+            expect![[r#"
+                -module(main).
+                foo() ->
+                    X = {
+                        state,
+                        [
+                            {
+                                conn,
+                                #{
+                                    host := "db.internal.example.com",
+                                    port := 5432,
+                                    pool => {pool, 10, 20, [primary, replica]}
+                                }
+                            }
+                        ],
+                        undefined
+                    },
+                    X.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn short_term_not_applicable() {
+        check_assist_not_applicable(
+            format_term,
+            r#"
+-module(main).
+foo() -> {ok, [a, ~b]}.
+"#,
+        );
+    }
+}
@@ -0,0 +1,310 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::TextSize;
+use hir::FaDef;
+use hir::FunctionDef;
+use hir::InFile;
+use hir::NameArity;
+
+use crate::AssistContext;
+use crate::Assists;
+
+// Assist: generate_callbacks
+//
+// Turns the module into a behaviour, generating a `-callback` declaration
+// for each of the exported functions in the selection, or in the export
+// attribute under the cursor, from its spec. It is also offered together
+// with declaring the new behaviour in each module of the same application
+// that already exports all of these functions.
+//
+// ```
+// -module(store).
+// -export([get/~1]).
+//
+// -spec get(Key :: atom()) -> binary().
+// get(_Key) -> <<>>.
+// ```
+// ->
+// ```
+// -module(store).
+// -export([get/1]).
+//
+// -callback get(Key :: atom()) -> binary().
+//
+// -spec get(Key :: atom()) -> binary().
+// get(_Key) -> <<>>.
+// ```
+pub(crate) fn generate_callbacks(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let export = ctx.find_node_at_offset::<ast::ExportAttribute>()?;
+    let def_map = ctx.sema.def_map(ctx.file_id());
+    let selection = ctx.selection_trimmed();
+    let functions: Vec<FunctionDef> = export
+        .funs()
+        .filter(|fa| {
+            ctx.has_empty_selection()
+                || fa
+                    .syntax()
+                    .text_range()
+                    .intersect(selection)
+                    .is_some_and(|range| !range.is_empty())
+        })
+        .filter_map(
+            |fa| match ctx.sema.to_def(InFile::new(ctx.file_id(), &fa))? {
+                FaDef::Function(function) => Some(function),
+                _ => None,
+            },
+        )
+        .filter(|function| !def_map.get_callbacks().contains_key(&function.name))
+        .collect();
+    if functions.is_empty() {
+        return None;
+    }
+
+    let callbacks: Vec<String> = functions
+        .iter()
+        .map(|function| callback_for(ctx, function))
+        .collect();
+    let form_list = ctx.sema.form_list(ctx.file_id());
+    let (insert_at, text) = match form_list.callback_attributes().last() {
+        Some((_, callback)) => (
+            ctx.form_ast(callback.form_id).syntax().text_range().end(),
+            format!("\n{}", callbacks.join("\n")),
+        ),
+        None => {
+            let last_export = form_list
+                .exports()
+                .map(|(_, export)| ctx.form_ast(export.form_id).syntax().text_range().end())
+                .max()?;
+            (last_export, format!("\n\n{}", callbacks.join("\n")))
+        }
+    };
+    let target = export.syntax().text_range();
+
+    acc.add(
+        AssistId("generate_callbacks", AssistKind::Generate),
+        "Generate -callback declarations",
+        None,
+        target,
+        None,
+        |builder| {
+            builder.edit_file(ctx.file_id());
+            builder.insert(insert_at, text.clone());
+        },
+    );
+
+    let names: Vec<&NameArity> = functions.iter().map(|function| &function.name).collect();
+    for (implementation, module_end) in implementations(ctx, &names) {
+        let module_name = ctx.sema.module_name(ctx.file_id())?;
+        let implementation_name = ctx.sema.module_name(implementation)?;
+        acc.add(
+            AssistId("generate_callbacks", AssistKind::Generate),
+            format!(
+                "Generate -callback declarations and declare the behaviour in `{}`",
+                implementation_name.as_str()
+            ),
+            None,
+            target,
+            None,
+            |builder| {
+                builder.edit_file(ctx.file_id());
+                builder.insert(insert_at, text.clone());
+                builder.edit_file(implementation);
+                builder.insert(
+                    module_end,
+                    format!("\n-behaviour({}).", module_name.as_str()),
+                );
+            },
+        );
+    }
+    Some(())
+}
+
+/// The `-callback` declaration of a function: its spec if it has one,
+/// otherwise one taking and returning any term.
+fn callback_for(ctx: &AssistContext, function: &FunctionDef) -> String {
+    if let Some(spec) = &function.spec {
+        let spec = spec.source(ctx.db().upcast()).syntax().text().to_string();
+        if let Some(sigs) = spec.strip_prefix("-spec") {
+            return format!("-callback{sigs}");
+        }
+    }
+    let args: Vec<String> = function
+        .first_clause_arg_names()
+        .unwrap_or_default()
+        .iter()
+        .map(|name| format!("{name} :: term()"))
+        .collect();
+    format!(
+        "-callback {}({}) -> term().",
+        function.name.name(),
+        args.join(", ")
+    )
+}
+
+/// Other modules of the same application exporting all the given
+/// functions, with the end of their module attribute.
+fn implementations(ctx: &AssistContext, names: &[&NameArity]) -> Vec<(FileId, TextSize)> {
+    let source_root = ctx.db().file_source_root(ctx.file_id());
+    let mut res: Vec<(FileId, TextSize)> = ctx
+        .db()
+        .source_root(source_root)
+        .iter()
+        .filter(|file_id| *file_id != ctx.file_id())
+        .filter(|file_id| {
+            let def_map = ctx.sema.def_map(*file_id);
+            names.iter().all(|name| def_map.is_function_exported(name))
+        })
+        .filter_map(|file_id| {
+            let module = ctx.sema.form_list(file_id).module_attribute()?.form_id;
+            let end = module
+                .get_ast(ctx.db(), file_id)
+                .syntax()
+                .text_range()
+                .end();
+            Some((file_id, end))
+        })
+        .collect();
+    res.sort_by_key(|(file_id, _)| {
+        ctx.sema
+            .module_name(*file_id)
+            .map(|name| name.as_str().to_string())
+    });
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn callbacks_from_specs() {
+        check_assist(
+            generate_callbacks,
+            "Generate -callback declarations",
+            r#"
+-module(store).
+-export([get/1, put/2, ~size/0]).
+
+-spec get(Key :: atom()) -> binary().
+get(_Key) -> <<>>.
+
+-spec put(Key :: atom(), Value :: binary()) -> ok.
+put(_Key, _Value) -> ok.
+
+size() -> 0.
+"#,
+            expect![[r#"
+                -module(store).
+                -export([get/1, put/2, size/0]).
+
+                -callback get(Key :: atom()) -> binary().
+                -callback put(Key :: atom(), Value :: binary()) -> ok.
+                -callback size() -> term().
+
+                -spec get(Key :: atom()) -> binary().
+                get(_Key) -> <<>>.
+
+                -spec put(Key :: atom(), Value :: binary()) -> ok.
+                put(_Key, _Value) -> ok.
+
+                size() -> 0.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn selected_functions_only() {
+        check_assist(
+            generate_callbacks,
+            "Generate -callback declarations",
+            r#"
+-module(store).
+-export([get/1, ~put/2~, delete/1]).
+-callback get(Key :: atom()) -> binary().
+
+get(_Key) -> <<>>.
+put(Key, Value) -> ok.
+delete(_Key) -> ok.
+"#,
+            expect![[r#"
+                -module(store).
+                -export([get/1, put/2, delete/1]).
+                -callback get(Key :: atom()) -> binary().
+                -callback put(Key :: term(), Value :: term()) -> term().
+
+                get(_Key) -> <<>>.
+                put(Key, Value) -> ok.
+                delete(_Key) -> ok.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn declare_behaviour_in_implementation() {
+        check_assist(
+            generate_callbacks,
+            "Generate -callback declarations and declare the behaviour in `memory_store`",
+            r#"
+//- /src/store.erl
+-module(store).
+-export([get/~1]).
+
+-spec get(Key :: atom()) -> binary().
+get(_Key) -> <<>>.
+//- /src/memory_store.erl
+-module(memory_store).
+-export([get/1]).
+
+get(_Key) -> <<"value">>.
+//- /src/other.erl
+-module(other).
+-export([put/2]).
+put(_, _) -> ok.
+"#,
+            expect![[r#"
+                //- /src/store.erl
+                -module(store).
+                -export([get/1]).
+
+                -callback get(Key :: atom()) -> binary().
+
+                -spec get(Key :: atom()) -> binary().
+                get(_Key) -> <<>>.
+                //- /src/memory_store.erl
+                -module(memory_store).
+                -behaviour(store).
+                -export([get/1]).
+
+                get(_Key) -> <<"value">>.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn not_applicable_when_already_callbacks() {
+        check_assist_not_applicable(
+            generate_callbacks,
+            r#"
+-module(store).
+-export([get/~1]).
+-callback get(Key :: atom()) -> binary().
+get(_Key) -> <<>>.
+"#,
+        );
+    }
+}
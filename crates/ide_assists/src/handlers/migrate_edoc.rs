@@ -0,0 +1,151 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_ide_db::edoc_migration::migrate_edoc as migrate_edoc_edit;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+
+use crate::AssistContext;
+use crate::Assists;
+
+// Assist: migrate_edoc
+//
+// Converts the EDoc comments of the whole module to the `-moduledoc` and
+// `-doc` attributes of OTP 27.
+//
+// ```
+// %% @doc Adds ~two numbers.
+// %% @param A the first
+// %% @param B the second
+// add(A, B) -> A + B.
+// ```
+// ->
+// ```
+// -doc """
+// Adds two numbers.
+//
+// Arguments:
+//
+// - `A` - the first
+// - `B` - the second
+// """.
+// add(A, B) -> A + B.
+// ```
+pub(crate) fn migrate_edoc(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let target = match ctx.find_node_at_offset::<ast::ModuleAttribute>() {
+        Some(module) => module.syntax().text_range(),
+        None => {
+            let comment = ctx.find_node_at_offset::<ast::Comment>()?;
+            let range = comment.syntax().text_range();
+            let edocs = ctx.sema.file_edoc_comments(ctx.file_id())?;
+            if !edocs
+                .values()
+                .any(|header| header.text_ranges().contains(&range))
+            {
+                return None;
+            }
+            range
+        }
+    };
+    let edit = migrate_edoc_edit(&ctx.sema, ctx.file_id())?;
+    acc.add(
+        AssistId("migrate_edoc", AssistKind::RefactorRewrite),
+        "Convert EDoc comments to -doc attributes",
+        None,
+        target,
+        None,
+        |builder| {
+            builder.edit_file(ctx.file_id());
+            for indel in edit.into_iter() {
+                builder.replace(indel.delete, indel.insert);
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn from_module_attribute() {
+        check_assist(
+            migrate_edoc,
+            "Convert EDoc comments to -doc attributes",
+            r#"
+%% @doc Arithmetic.
+-mod~ule(main).
+-export([add/2]).
+
+%% @doc Adds two numbers.
+%% @since 2.1
+add(A, B) -> A + B.
+"#,
+            expect![[r#"
+                -module(main).
+                -moduledoc """
+                Arithmetic.
+                """.
+                -export([add/2]).
+
+                -doc """
+                Adds two numbers.
+                """.
+                -doc #{since => "2.1"}.
+                add(A, B) -> A + B.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn from_comment() {
+        check_assist(
+            migrate_edoc,
+            "Convert EDoc comments to -doc attributes",
+            r#"
+-module(main).
+%% @doc Adds ~two numbers.
+%% @param A the first
+%% @param B the second
+add(A, B) -> A + B.
+"#,
+            expect![[r#"
+                -module(main).
+                -doc """
+                Adds two numbers.
+
+                Arguments:
+
+                - `A` - the first
+                - `B` - the second
+                """.
+                add(A, B) -> A + B.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn not_applicable_on_plain_comment() {
+        check_assist_not_applicable(
+            migrate_edoc,
+            r#"
+-module(main).
+%% @doc Adds two numbers.
+add(A, B) ->
+    %% Just ~a comment
+    A + B.
+"#,
+        );
+    }
+}
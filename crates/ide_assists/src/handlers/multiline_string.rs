@@ -0,0 +1,378 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use std::fmt::Write;
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_syntax::ast;
+use elp_syntax::unescape::triple_quoted_contents;
+use elp_syntax::unescape::unescape_string;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+
+use crate::helpers::line_indent;
+use crate::AssistContext;
+use crate::Assists;
+
+const INDENT: &str = "    ";
+
+// Assist: make_triple_quoted_string
+//
+// Converts a string with several lines to an OTP 27 triple-quoted
+// string, whose lines are written out verbatim.
+//
+// ```
+// query() -> "SELECT \"id\"~\nFROM users\nWHERE active".
+// ```
+// ->
+// ```
+// query() -> """
+//     SELECT "id"
+//     FROM users
+//     WHERE active
+//     """.
+// ```
+pub(crate) fn make_triple_quoted_string(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let string = ctx.find_node_at_offset::<ast::String>()?;
+    // Triple-quoted strings cannot be concatenated with adjacent strings.
+    if string.syntax().parent()?.kind() == SyntaxKind::MULTI_STRING {
+        return None;
+    }
+    let contents = quoted_contents(&string.to_string())?;
+    if !contents.contains('\n') {
+        return None;
+    }
+    let range = string.syntax().text_range();
+    let file_text = ctx.db().file_text(ctx.file_id());
+    let indent = format!("{}{INDENT}", line_indent(&file_text, range.start()));
+    let triple_quoted = triple_quoted(&contents, &indent)?;
+    acc.add(
+        AssistId("make_triple_quoted_string", AssistKind::RefactorRewrite),
+        "Convert to triple-quoted string",
+        None,
+        range,
+        None,
+        |edit| edit.replace(range, triple_quoted),
+    )
+}
+
+// Assist: make_quoted_string
+//
+// Converts a triple-quoted string to a quoted string, escaping its
+// newlines and quotes.
+//
+// ```
+// query() -> """
+//     SELECT "i~d"
+//     FROM users
+//     """.
+// ```
+// ->
+// ```
+// query() -> "SELECT \"id\"\nFROM users".
+// ```
+pub(crate) fn make_quoted_string(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let string = ctx.find_node_at_offset::<ast::String>()?;
+    let contents = triple_quoted_contents(&string.to_string())?;
+    let range = string.syntax().text_range();
+    acc.add(
+        AssistId("make_quoted_string", AssistKind::RefactorRewrite),
+        "Convert to quoted string",
+        None,
+        range,
+        None,
+        |edit| edit.replace(range, quoted(&contents)),
+    )
+}
+
+// Assist: split_string_lines
+//
+// Splits a string with several lines into a list of strings, one per
+// line, each keeping its newline.
+//
+// ```
+// header() -> "From: ~a\nTo: b\n\nHi".
+// ```
+// ->
+// ```
+// header() -> [
+//     "From: a\n",
+//     "To: b\n",
+//     "\n",
+//     "Hi"
+// ].
+// ```
+pub(crate) fn split_string_lines(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let string = ctx.find_node_at_offset::<ast::String>()?;
+    if matches!(
+        string.syntax().parent()?.kind(),
+        SyntaxKind::MULTI_STRING | SyntaxKind::BIN_ELEMENT
+    ) {
+        return None;
+    }
+    let text = string.to_string();
+    let contents = quoted_contents(&text).or_else(|| triple_quoted_contents(&text))?;
+    let lines: Vec<&str> = contents.split_inclusive('\n').collect();
+    if lines.len() < 2 {
+        return None;
+    }
+    let range = string.syntax().text_range();
+    let file_text = ctx.db().file_text(ctx.file_id());
+    let indent = line_indent(&file_text, range.start());
+    let items: Vec<String> = lines
+        .iter()
+        .map(|line| format!("\n{indent}{INDENT}{}", quoted(line)))
+        .collect();
+    let list = format!("[{}\n{indent}]", items.join(","));
+    acc.add(
+        AssistId("split_string_lines", AssistKind::RefactorRewrite),
+        "Split string into a list of lines",
+        None,
+        range,
+        None,
+        |edit| edit.replace(range, list),
+    )
+}
+
+// Assist: join_string_lines
+//
+// Joins a list of strings, such as one produced by `split_string_lines`,
+// into a single string.
+//
+// ```
+// header() -> [
+//     "From: a\n",
+//     "To: b~\n"
+// ].
+// ```
+// ->
+// ```
+// header() -> "From: a\nTo: b\n".
+// ```
+pub(crate) fn join_string_lines(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let list = ctx.find_node_at_offset::<ast::List>()?;
+    // A list with a tail is not a list of strings
+    if list
+        .syntax()
+        .children_with_tokens()
+        .any(|it| matches!(it.kind(), SyntaxKind::PIPE | SyntaxKind::ANON_PIPE))
+    {
+        return None;
+    }
+    let mut contents = String::new();
+    let mut count = 0;
+    for expr in list.exprs() {
+        let string = ast::String::cast(expr.syntax().clone())?;
+        let text = string.to_string();
+        contents.push_str(&quoted_contents(&text).or_else(|| triple_quoted_contents(&text))?);
+        count += 1;
+    }
+    if count < 2 {
+        return None;
+    }
+    let range = list.syntax().text_range();
+    acc.add(
+        AssistId("join_string_lines", AssistKind::RefactorRewrite),
+        "Join list of strings into one string",
+        None,
+        range,
+        None,
+        |edit| edit.replace(range, quoted(&contents)),
+    )
+}
+
+/// The contents of a quoted string without a sigil, with its escapes
+/// replaced.
+fn quoted_contents(text: &str) -> Option<String> {
+    if !text.starts_with('"') || text.starts_with("\"\"\"") {
+        return None;
+    }
+    unescape_string(text).map(|contents| contents.into_owned())
+}
+
+/// A triple-quoted string with `contents`, whose lines are indented by
+/// `indent`. `None` when the contents have control characters, which
+/// can only be written escaped.
+fn triple_quoted(contents: &str, indent: &str) -> Option<String> {
+    if contents
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\t')
+    {
+        return None;
+    }
+    // The delimiter must be longer than any run of quotes in the contents
+    let longest_run = contents
+        .split(|c| c != '"')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    let quotes = "\"".repeat((longest_run + 1).max(3));
+    let mut res = format!("{quotes}\n");
+    for line in contents.split('\n') {
+        if !line.is_empty() {
+            res.push_str(indent);
+            res.push_str(line);
+        }
+        res.push('\n');
+    }
+    res.push_str(indent);
+    res.push_str(&quotes);
+    Some(res)
+}
+
+fn quoted(contents: &str) -> String {
+    let mut res = String::from("\"");
+    for c in contents.chars() {
+        match c {
+            '\\' => res.push_str("\\\\"),
+            '"' => res.push_str("\\\""),
+            '\n' => res.push_str("\\n"),
+            '\t' => res.push_str("\\t"),
+            '\r' => res.push_str("\\r"),
+            c if c.is_control() => {
+                let _ = write!(res, "\\x{{{:X}}}", c as u32);
+            }
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn quoted_to_triple_quoted() {
+        check_assist(
+            make_triple_quoted_string,
+            "Convert to triple-quoted string",
+            r#"
+-module(main).
+query() ->
+    Q = "SELECT \"id\", name~\n  FROM users\n\nWHERE active = 1\n",
+    Q.
+"#,
+            expect![[r#"
+                -module(main).
+                query() ->
+                    Q = """
+                        SELECT "id", name
+                          FROM users
+
+                        WHERE active = 1
+
+                        """,
+                    Q.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn single_line_not_applicable() {
+        check_assist_not_applicable(
+            make_triple_quoted_string,
+            r#"
+-module(main).
+query() -> "SELECT ~id FROM users".
+"#,
+        );
+    }
+
+    #[test]
+    fn triple_quoted_to_quoted() {
+        check_assist(
+            make_quoted_string,
+            "Convert to quoted string",
+            r#"
+-module(main).
+query() ->
+    """
+      SELECT "id", n~ame
+        FROM users \n
+      """.
+"#,
+            expect![[r#"
+                -module(main).
+                query() ->
+                    "SELECT \"id\", name\n  FROM users \\n".
+            "#]],
+        )
+    }
+
+    #[test]
+    fn split_lines() {
+        check_assist(
+            split_string_lines,
+            "Split string into a list of lines",
+            r#"
+-module(main).
+page() ->
+    io:put_chars("<html>~\n  <body/>\n</html>\n").
+"#,
+            expect![[r#"
+                -module(main).
+                page() ->
+                    io:put_chars([
+                        "<html>\n",
+                        "  <body/>\n",
+                        "</html>\n"
+                    ]).
+            "#]],
+        )
+    }
+
+    #[test]
+    fn join_lines() {
+        check_assist(
+            join_string_lines,
+            "Join list of strings into one string",
+            r#"
+-module(main).
+page() ->
+    io:put_chars([
+        "<html>\n",
+        "  <body/>~\n",
+        """
+        </html>
+        """
+    ]).
+"#,
+            expect![[r#"
+                -module(main).
+                page() ->
+                    io:put_chars("<html>\n  <body/>\n</html>").
+            "#]],
+        )
+    }
+
+    #[test]
+    fn contents_round_trip() {
+        for contents in [
+            "",
+            "one line",
+            "a \"\"\" b\n\"\"\"\"\n",
+            "  leading\n\n\ttab\\n\n",
+        ] {
+            assert_eq!(
+                triple_quoted_contents(&triple_quoted(contents, "  ").unwrap()).as_deref(),
+                Some(contents)
+            );
+            assert_eq!(
+                quoted_contents(&quoted(contents)).as_deref(),
+                Some(contents)
+            );
+        }
+    }
+}
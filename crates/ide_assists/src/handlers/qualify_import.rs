@@ -0,0 +1,130 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use hir::AsName;
+use hir::Name;
+use hir::NameArity;
+use hir::Semantic;
+
+use crate::AssistContext;
+use crate::Assists;
+
+// Assist: qualify_import
+//
+// Calls an imported function through its module, so the call reads the
+// same wherever it is moved to.
+//
+// ```
+// -import(lists, [reverse/1]).
+// foo(L) -> rev~erse(L).
+// ```
+// ->
+// ```
+// -import(lists, [reverse/1]).
+// foo(L) -> lists:reverse(L).
+// ```
+pub(crate) fn qualify_import(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let call = ctx.find_node_at_offset::<ast::Call>()?;
+    let (atom, module) = imported_call(&ctx.sema, ctx.file_id(), &call)?;
+    let range = atom.syntax().text_range();
+    if !range.contains_inclusive(ctx.offset()) {
+        return None;
+    }
+    acc.add(
+        AssistId("qualify_import", AssistKind::RefactorRewrite),
+        "Qualify the imported call",
+        None,
+        range,
+        None,
+        |edit| edit.insert(range.start(), format!("{}:", module.to_quoted_string())),
+    )
+}
+
+/// The calls to imported functions of the file, for applying the assist
+/// to all of them.
+pub(crate) fn sites(sema: &Semantic, file_id: FileId) -> Vec<TextRange> {
+    let source = sema.parse(file_id).value;
+    source
+        .syntax()
+        .descendants()
+        .filter_map(ast::Call::cast)
+        .filter_map(|call| imported_call(sema, file_id, &call))
+        .map(|(atom, _)| TextRange::empty(atom.syntax().text_range().start() + TextSize::from(1)))
+        .collect()
+}
+
+/// The name of a local call to an imported function, and the module it
+/// is imported from.
+fn imported_call(sema: &Semantic, file_id: FileId, call: &ast::Call) -> Option<(ast::Atom, Name)> {
+    let ast::Expr::ExprMax(ast::ExprMax::Atom(atom)) = call.expr()? else {
+        return None;
+    };
+    let arity = call.args()?.args().count();
+    let name = NameArity::new(atom.as_name(), arity as u32);
+    let module = sema.def_map(file_id).get_imports().get(&name)?.clone();
+    Some((atom, module))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn qualify_imported_call() {
+        check_assist(
+            qualify_import,
+            "Qualify the imported call",
+            r#"
+-module(main).
+-import(lists, [reverse/1]).
+foo(L) -> rev~erse(L).
+"#,
+            expect![[r#"
+                -module(main).
+                -import(lists, [reverse/1]).
+                foo(L) -> lists:reverse(L).
+            "#]],
+        )
+    }
+
+    #[test]
+    fn other_arity_not_applicable() {
+        check_assist_not_applicable(
+            qualify_import,
+            r#"
+-module(main).
+-import(lists, [reverse/1]).
+foo(L) -> rev~erse(L, []).
+reverse(L, Acc) -> {L, Acc}.
+"#,
+        );
+    }
+
+    #[test]
+    fn arguments_not_applicable() {
+        check_assist_not_applicable(
+            qualify_import,
+            r#"
+-module(main).
+-import(lists, [reverse/1]).
+foo(L) -> reverse(~L).
+"#,
+        );
+    }
+}
@@ -0,0 +1,365 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::NodeOrToken;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use elp_syntax::SyntaxToken;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use hir::InFile;
+
+use crate::AssistContext;
+use crate::Assists;
+
+// Assist: sort_fields
+//
+// Sorts the fields of a record construction or update, or the keys of a
+// map, alphabetically. The fields of a record can also be sorted in the
+// order of the record declaration. Comments on the lines above a field,
+// or after it on the same line, move with it.
+//
+// ```
+// -record(config, {port, host, timeout}).
+// config() -> #conf~ig{timeout = 5000, host = "localhost", port = 8080}.
+// ```
+// ->
+// ```
+// -record(config, {port, host, timeout}).
+// config() -> #config{host = "localhost", port = 8080, timeout = 5000}.
+// ```
+pub(crate) fn sort_fields(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let container = ctx
+        .token_at_offset()
+        .right_biased()?
+        .parent_ancestors()
+        .find(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::RECORD_EXPR
+                    | SyntaxKind::RECORD_UPDATE_EXPR
+                    | SyntaxKind::MAP_EXPR
+                    | SyntaxKind::MAP_EXPR_UPDATE
+            )
+        })?;
+    let fields: Vec<Field> = container
+        .children()
+        .filter(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::RECORD_FIELD | SyntaxKind::MAP_FIELD
+            )
+        })
+        .map(Field::new)
+        .collect::<Option<_>>()?;
+    if fields.len() < 2 {
+        return None;
+    }
+    let is_map = matches!(
+        container.kind(),
+        SyntaxKind::MAP_EXPR | SyntaxKind::MAP_EXPR_UPDATE
+    );
+
+    let mut alphabetical: Vec<usize> = (0..fields.len()).collect();
+    alphabetical.sort_by(|&a, &b| fields[a].name.cmp(&fields[b].name));
+    if let Some(edits) = reorder(&fields, &alphabetical) {
+        let label = if is_map {
+            "Sort map keys alphabetically"
+        } else {
+            "Sort record fields alphabetically"
+        };
+        acc.add(
+            AssistId("sort_fields", AssistKind::RefactorRewrite),
+            label,
+            None,
+            container.text_range(),
+            None,
+            |builder| {
+                for (range, text) in edits {
+                    builder.replace(range, text);
+                }
+            },
+        );
+    }
+
+    if let Some(declared) = declared_fields(ctx, &container) {
+        let position = |field: &Field| {
+            declared
+                .iter()
+                .position(|name| *name == field.name)
+                .unwrap_or(declared.len())
+        };
+        let mut declaration_order: Vec<usize> = (0..fields.len()).collect();
+        declaration_order.sort_by_key(|&i| position(&fields[i]));
+        if let Some(edits) = reorder(&fields, &declaration_order) {
+            acc.add(
+                AssistId("sort_fields_by_declaration", AssistKind::RefactorRewrite),
+                "Sort record fields in declaration order",
+                None,
+                container.text_range(),
+                None,
+                |builder| {
+                    for (range, text) in edits {
+                        builder.replace(range, text);
+                    }
+                },
+            );
+        }
+    }
+    Some(())
+}
+
+#[derive(Debug)]
+struct Field {
+    /// The field name, or the source of the map key
+    name: String,
+    /// The field, with the comments on the lines above it
+    range: TextRange,
+    text: String,
+    /// The comment following the field on the same line
+    comment: Option<SyntaxToken>,
+    /// Where a comment following the field would go, if one can go there
+    comment_offset: Option<TextSize>,
+}
+
+impl Field {
+    fn new(node: SyntaxNode) -> Option<Field> {
+        let name = if let Some(field) = ast::RecordField::cast(node.clone()) {
+            field.name()?.syntax().text().to_string()
+        } else {
+            ast::MapField::cast(node.clone())?
+                .key()?
+                .syntax()
+                .text()
+                .to_string()
+        };
+
+        let mut start = node.text_range().start();
+        let mut element = node.prev_sibling_or_token();
+        while let Some(NodeOrToken::Token(token)) = element {
+            match token.kind() {
+                SyntaxKind::WHITESPACE => {}
+                SyntaxKind::COMMENT if starts_line(&token) => start = token.text_range().start(),
+                _ => break,
+            }
+            element = token.prev_sibling_or_token();
+        }
+        let range = TextRange::new(start, node.text_range().end());
+        let text = node.ancestors().last()?.text().slice(range).to_string();
+
+        let mut comment = None;
+        let mut comment_offset = None;
+        let mut end = node.text_range().end();
+        let mut element = node.next_sibling_or_token();
+        while let Some(NodeOrToken::Token(token)) = element {
+            match token.kind() {
+                SyntaxKind::WHITESPACE if token.text().contains('\n') => {
+                    comment_offset = Some(end);
+                    break;
+                }
+                SyntaxKind::WHITESPACE => {}
+                SyntaxKind::ANON_COMMA => end = token.text_range().end(),
+                SyntaxKind::COMMENT => {
+                    comment = Some(token);
+                    break;
+                }
+                _ => break,
+            }
+            element = token.next_sibling_or_token();
+        }
+        Some(Field {
+            name,
+            range,
+            text,
+            comment,
+            comment_offset,
+        })
+    }
+}
+
+fn starts_line(comment: &SyntaxToken) -> bool {
+    match comment.prev_sibling_or_token() {
+        Some(NodeOrToken::Token(token)) if token.kind() == SyntaxKind::WHITESPACE => {
+            token.text().contains('\n')
+        }
+        _ => false,
+    }
+}
+
+/// The edits putting the field `order[i]` in place of the field `i`, if
+/// the order changes and the comments can follow their fields.
+fn reorder(fields: &[Field], order: &[usize]) -> Option<Vec<(TextRange, String)>> {
+    if order.iter().enumerate().all(|(i, &j)| i == j) {
+        return None;
+    }
+    let mut edits = Vec::new();
+    for (slot, &j) in fields.iter().zip(order) {
+        let field = &fields[j];
+        let mut text = field.text.clone();
+        match (&slot.comment, &field.comment) {
+            (Some(old), Some(new)) => edits.push((old.text_range(), new.text().to_string())),
+            (Some(old), None) => {
+                let start = match old.prev_token() {
+                    Some(token) if token.kind() == SyntaxKind::WHITESPACE => {
+                        token.text_range().start()
+                    }
+                    _ => old.text_range().start(),
+                };
+                edits.push((TextRange::new(start, old.text_range().end()), String::new()))
+            }
+            (None, Some(new)) => {
+                // A comment cannot be put before the next field or the
+                // closing brace on the same line
+                let offset = slot.comment_offset?;
+                let comment = format!(" {}", new.text());
+                if offset == slot.range.end() {
+                    text.push_str(&comment);
+                } else {
+                    edits.push((TextRange::empty(offset), comment));
+                }
+            }
+            (None, None) => {}
+        }
+        edits.push((slot.range, text));
+    }
+    Some(edits)
+}
+
+/// The names of the fields of the record, in declaration order.
+fn declared_fields(ctx: &AssistContext, container: &SyntaxNode) -> Option<Vec<String>> {
+    let name = container.children().find_map(ast::RecordName::cast)?;
+    let record = ctx.sema.to_def(InFile::new(ctx.file_id(), &name))?;
+    Some(
+        record
+            .field_names(ctx.db())
+            .map(|name| name.as_str().to_string())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn record_alphabetically() {
+        check_assist(
+            sort_fields,
+            "Sort record fields alphabetically",
+            r#"
+-record(config, {port, host, timeout}).
+config() -> #conf~ig{timeout = 5000, host = "localhost", port = 8080}.
+"#,
+            expect![[r#"
+                -record(config, {port, host, timeout}).
+                config() -> #config{host = "localhost", port = 8080, timeout = 5000}.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn record_in_declaration_order() {
+        check_assist(
+            sort_fields,
+            "Sort record fields in declaration order",
+            r#"
+-record(config, {port, host, timeout}).
+config() -> #conf~ig{timeout = 5000, host = "localhost", port = 8080}.
+"#,
+            expect![[r#"
+                -record(config, {port, host, timeout}).
+                config() -> #config{port = 8080, host = "localhost", timeout = 5000}.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn record_update() {
+        check_assist(
+            sort_fields,
+            "Sort record fields alphabetically",
+            r#"
+-record(config, {port, host, timeout}).
+update(C) -> C#config{timeout = 5000, ~host = "localhost"}.
+"#,
+            expect![[r#"
+                -record(config, {port, host, timeout}).
+                update(C) -> C#config{host = "localhost", timeout = 5000}.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn map_keys_with_comments() {
+        check_assist(
+            sort_fields,
+            "Sort map keys alphabetically",
+            r#"
+config() ->
+    #{~
+        timeout => 5000, % milliseconds
+        %% Where to listen
+        port => 8080,
+        host => "localhost"
+    }.
+"#,
+            expect![[r#"
+                config() ->
+                    #{
+                        host => "localhost",
+                        %% Where to listen
+                        port => 8080,
+                        timeout => 5000 % milliseconds
+                    }.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn map_update() {
+        check_assist(
+            sort_fields,
+            "Sort map keys alphabetically",
+            r#"
+update(M) -> M#{~b := 2, a := 1}.
+"#,
+            expect![[r#"
+                update(M) -> M#{a := 1, b := 2}.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn already_sorted() {
+        check_assist_not_applicable(
+            sort_fields,
+            r#"
+config() -> #{~host => "localhost", port => 8080}.
+"#,
+        );
+    }
+
+    #[test]
+    fn comment_cannot_follow() {
+        check_assist_not_applicable(
+            sort_fields,
+            r#"
+config() -> #{~port => 8080, host => "localhost" % the host
+}.
+"#,
+        );
+    }
+}
@@ -0,0 +1,143 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+use elp_ide_db::assists::AssistId;
+use elp_ide_db::assists::AssistKind;
+use elp_ide_db::elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use fxhash::FxHashSet;
+use hir::Semantic;
+
+use crate::AssistContext;
+use crate::Assists;
+
+// Assist: string_to_binary
+//
+// Converts a string in a function body to a binary string.
+//
+// ```
+// greeting() -> "hel~lo".
+// ```
+// ->
+// ```
+// greeting() -> <<"hello">>.
+// ```
+pub(crate) fn string_to_binary(acc: &mut Assists, ctx: &AssistContext) -> Option<()> {
+    let string = ctx.find_node_at_offset::<ast::String>()?;
+    let target = binary_target(&string)?;
+    let range = target.text_range();
+    acc.add(
+        AssistId("string_to_binary", AssistKind::RefactorRewrite),
+        "Convert to binary string",
+        None,
+        range,
+        None,
+        |edit| edit.replace(range, format!("<<{target}>>")),
+    )
+}
+
+/// The strings of the function bodies of the file, for applying the
+/// assist to all of them.
+pub(crate) fn sites(sema: &Semantic, file_id: FileId) -> Vec<TextRange> {
+    let source = sema.parse(file_id).value;
+    let mut seen = FxHashSet::default();
+    source
+        .syntax()
+        .descendants()
+        .filter_map(ast::String::cast)
+        .filter_map(|string| {
+            let target = binary_target(&string)?;
+            seen.insert(target.text_range())
+                .then(|| TextRange::empty(string.syntax().text_range().start() + TextSize::from(1)))
+        })
+        .collect()
+}
+
+/// The string, or the adjacent strings it is part of, when they are in a
+/// function body and not already in a binary.
+fn binary_target(string: &ast::String) -> Option<SyntaxNode> {
+    let mut target = string.syntax().clone();
+    if target.parent()?.kind() == SyntaxKind::MULTI_STRING {
+        target = target.parent()?;
+    }
+    if target.parent()?.kind() == SyntaxKind::BIN_ELEMENT {
+        return None;
+    }
+    target
+        .ancestors()
+        .any(|node| node.kind() == SyntaxKind::FUN_DECL)
+        .then_some(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn convert_string() {
+        check_assist(
+            string_to_binary,
+            "Convert to binary string",
+            r#"
+-module(main).
+greeting() -> "hel~lo".
+"#,
+            expect![[r#"
+                -module(main).
+                greeting() -> <<"hello">>.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn convert_adjacent_strings() {
+        check_assist(
+            string_to_binary,
+            "Convert to binary string",
+            r#"
+-module(main).
+greeting() -> "hello, " "wor~ld".
+"#,
+            expect![[r#"
+                -module(main).
+                greeting() -> <<"hello, " "world">>.
+            "#]],
+        )
+    }
+
+    #[test]
+    fn binary_not_applicable() {
+        check_assist_not_applicable(
+            string_to_binary,
+            r#"
+-module(main).
+greeting() -> <<"hel~lo">>.
+"#,
+        );
+    }
+
+    #[test]
+    fn attribute_not_applicable() {
+        check_assist_not_applicable(
+            string_to_binary,
+            r#"
+-module(main).
+-include("he~ader.hrl").
+"#,
+        );
+    }
+}
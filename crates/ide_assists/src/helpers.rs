@@ -314,6 +314,15 @@ pub(crate) fn change_indent(delta_indent: i8, str: String) -> String {
 
 pub const DEFAULT_INDENT_STEP: i8 = 4;
 
+/// The leading whitespace of the line `offset` is on.
+pub(crate) fn line_indent(text: &str, offset: TextSize) -> &str {
+    let line_start = text[..usize::from(offset)]
+        .rfind('\n')
+        .map_or(0, |idx| idx + 1);
+    let line = &text[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
 /// Any parameters to the `Clause` that are just a single variable.
 pub(crate) fn simple_param_vars(
     clause: &InFunctionClauseBody<&FunctionClauseBody>,
@@ -35,8 +35,10 @@ pub use elp_ide_db::assists::AssistResolveStrategy;
 use elp_ide_db::assists::AssistUserInput;
 pub use elp_ide_db::assists::GroupLabel;
 pub use elp_ide_db::assists::SingleResolve;
+use elp_ide_db::elp_base_db::FileId;
 use elp_ide_db::elp_base_db::FileRange;
 use elp_ide_db::RootDatabase;
+use hir::Semantic;
 
 // use elp_syntax::TextRange;
 pub(crate) use crate::assist_context::AssistContext;
@@ -59,29 +61,84 @@ pub fn assists(
     acc.finish()
 }
 
+/// Whether the assist applies to each site matching a pattern, such as
+/// the calls to imported functions, and so can be applied to all of
+/// them at once.
+pub fn is_pattern_assist(id: &AssistId) -> bool {
+    handlers::patterns()
+        .iter()
+        .any(|(name, _, _)| *name == id.0)
+}
+
+/// The assist with `id` at each of its sites in the file, resolved, for
+/// the assists applying to the sites matching a pattern.
+pub fn pattern_assists(
+    db: &RootDatabase,
+    config: &AssistConfig,
+    id: &AssistId,
+    file_id: FileId,
+) -> Vec<Assist> {
+    let Some((_, handler, sites)) = handlers::patterns()
+        .iter()
+        .find(|(name, _, _)| *name == id.0)
+    else {
+        return Vec::new();
+    };
+    let sema = Semantic::new(db);
+    sites(&sema, file_id)
+        .into_iter()
+        .flat_map(|range| {
+            let ctx = AssistContext::new(db, config, FileRange { file_id, range }, &[], None);
+            let mut acc = Assists::new(&ctx, AssistResolveStrategy::All);
+            handler(&mut acc, &ctx);
+            acc.finish()
+        })
+        .filter(|assist| assist.id == *id)
+        .collect()
+}
+
 mod handlers {
+    use elp_ide_db::elp_base_db::FileId;
+    use elp_syntax::TextRange;
+    use hir::Semantic;
+
     use crate::AssistContext;
     use crate::Assists;
 
     pub(crate) type Handler = fn(&mut Assists, &AssistContext) -> Option<()>;
+    /// The positions in a file at which a pattern assist applies.
+    pub(crate) type Sites = fn(&Semantic, FileId) -> Vec<TextRange>;
 
     mod add_edoc;
     mod add_fixme;
     mod add_format;
     mod add_impl;
     mod add_spec;
+    mod align_arrows;
+    mod binary_building;
     mod bump_variables;
+    mod convert_comprehension;
+    mod convert_to_map_comprehension;
     mod create_function;
     mod delete_function;
     mod export_function;
     mod export_type;
     mod extract_function;
+    mod extract_interface;
     mod extract_variable;
+    mod fix_include_path;
     mod flip_sep;
+    mod format_term;
+    mod generate_callbacks;
     mod ignore_variable;
     mod implement_behaviour;
     mod inline_function;
     mod inline_local_variable;
+    mod migrate_edoc;
+    mod multiline_string;
+    mod qualify_import;
+    mod sort_fields;
+    mod string_to_binary;
 
     pub(crate) fn all() -> &'static [Handler] {
         &[
@@ -91,18 +148,37 @@ mod handlers {
             add_format::add_format,
             add_impl::add_impl,
             add_spec::add_spec,
+            align_arrows::align_arrows,
+            align_arrows::unalign_arrows,
+            binary_building::accumulate_iolist,
+            binary_building::convert_to_binary_comprehension,
             bump_variables::bump_variables,
+            convert_comprehension::comprehension_to_lists_call,
+            convert_comprehension::lists_call_to_comprehension,
+            convert_to_map_comprehension::convert_to_map_comprehension,
             create_function::create_function,
             delete_function::delete_function,
             export_function::export_function,
             export_type::export_type,
             extract_function::extract_function,
+            extract_interface::extract_interface,
             extract_variable::extract_variable,
+            fix_include_path::fix_include_path,
             flip_sep::flip_sep,
+            format_term::format_term,
+            generate_callbacks::generate_callbacks,
             ignore_variable::ignore_variable,
             implement_behaviour::implement_behaviour,
             inline_function::inline_function,
             inline_local_variable::inline_local_variable,
+            migrate_edoc::migrate_edoc,
+            multiline_string::join_string_lines,
+            multiline_string::make_quoted_string,
+            multiline_string::make_triple_quoted_string,
+            multiline_string::split_string_lines,
+            qualify_import::qualify_import,
+            sort_fields::sort_fields,
+            string_to_binary::string_to_binary,
             // These are manually sorted for better priorities. By default,
             // priority is determined by the size of the target range (smaller
             // target wins). If the ranges are equal, position in this list is
@@ -114,4 +190,21 @@ mod handlers {
             // sorted list above?
         ]
     }
+
+    /// The assists applying to each site matching a pattern, which can
+    /// be applied to all the sites of a file or project at once.
+    pub(crate) fn patterns() -> &'static [(&'static str, Handler, Sites)] {
+        &[
+            (
+                "qualify_import",
+                qualify_import::qualify_import,
+                qualify_import::sites,
+            ),
+            (
+                "string_to_binary",
+                string_to_binary::string_to_binary,
+                string_to_binary::sites,
+            ),
+        ]
+    }
 }
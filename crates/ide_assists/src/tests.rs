@@ -46,6 +46,9 @@ use crate::Assists;
 pub(crate) const TEST_CONFIG: AssistConfig = AssistConfig {
     snippet_cap: SnippetCap::new(true),
     allowed: None,
+    disabled: Vec::new(),
+    min_otp_release: None,
+    align_arrows: true,
 };
 
 #[track_caller]
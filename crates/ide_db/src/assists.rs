@@ -52,6 +52,12 @@ pub struct Assist {
     pub user_input: Option<AssistUserInput>,
     /// If this assist is derived from a context diagnostic, it is here.
     pub original_diagnostic: Option<AssistContextDiagnostic>,
+    /// Whether this is the fix of its diagnostic that editors can apply
+    /// without asking, e.g. on save.
+    pub is_preferred: bool,
+    /// Tells apart the assists sharing an id, so that resolving one of
+    /// them does not compute the others.
+    pub subtype: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -65,6 +71,8 @@ pub enum AssistKind {
     RefactorExtract,
     RefactorInline,
     RefactorRewrite,
+    /// Applies to a whole file, rather than to the selection
+    Source,
 }
 
 impl AssistKind {
@@ -94,6 +102,21 @@ impl AssistKind {
             AssistKind::RefactorExtract => "RefactorExtract",
             AssistKind::RefactorInline => "RefactorInline",
             AssistKind::RefactorRewrite => "RefactorRewrite",
+            AssistKind::Source => "Source",
+        }
+    }
+
+    /// Assists of a lower priority are listed first: fixes, then
+    /// refactorings, then code generation.
+    pub fn priority(&self) -> u8 {
+        match self {
+            AssistKind::QuickFix => 0,
+            AssistKind::RefactorExtract
+            | AssistKind::RefactorInline
+            | AssistKind::RefactorRewrite => 1,
+            AssistKind::Refactor => 2,
+            AssistKind::Source => 3,
+            AssistKind::Generate | AssistKind::None => 4,
         }
     }
 }
@@ -110,6 +133,7 @@ impl FromStr for AssistKind {
             "RefactorExtract" => Ok(AssistKind::RefactorExtract),
             "RefactorInline" => Ok(AssistKind::RefactorInline),
             "RefactorRewrite" => Ok(AssistKind::RefactorRewrite),
+            "Source" => Ok(AssistKind::Source),
             unknown => Err(format!("Unknown AssistKind: '{}'", unknown)),
         }
     }
@@ -141,15 +165,23 @@ pub struct SingleResolve {
     pub assist_id: String,
     // The kind of the assist.
     pub assist_kind: AssistKind,
+    /// The subtype of the assist, when it shares its id with others.
+    pub assist_subtype: Option<String>,
 }
 
 impl AssistResolveStrategy {
     pub fn should_resolve(&self, id: &AssistId) -> bool {
+        self.should_resolve_subtype(id, None)
+    }
+
+    pub fn should_resolve_subtype(&self, id: &AssistId, subtype: Option<&str>) -> bool {
         match self {
             AssistResolveStrategy::None => false,
             AssistResolveStrategy::All => true,
             AssistResolveStrategy::Single(single_resolve) => {
-                single_resolve.assist_id == id.0 && single_resolve.assist_kind == id.1
+                single_resolve.assist_id == id.0
+                    && single_resolve.assist_kind == id.1
+                    && single_resolve.assist_subtype.as_deref() == subtype
             }
         }
     }
@@ -161,6 +193,7 @@ pub enum AssistContextDiagnosticCode {
     UndefinedFunction,
     UnusedFunction,
     UnusedVariable,
+    IncludeNotFound,
     ElpDiagnostic(DiagnosticCode),
 }
 
@@ -179,6 +212,7 @@ impl AssistContextDiagnosticCode {
             AssistContextDiagnosticCode::UndefinedFunction => self.make_labeled_code(),
             AssistContextDiagnosticCode::UnusedFunction => self.make_labeled_code(),
             AssistContextDiagnosticCode::UnusedVariable => self.make_labeled_code(),
+            AssistContextDiagnosticCode::IncludeNotFound => self.make_labeled_code(),
             AssistContextDiagnosticCode::ElpDiagnostic(code) => code.as_labeled_code(),
         }
     }
@@ -191,6 +225,7 @@ impl AssistContextDiagnosticCode {
             AssistContextDiagnosticCode::UndefinedFunction => "L1227".to_string(),
             AssistContextDiagnosticCode::UnusedFunction => "L1230".to_string(),
             AssistContextDiagnosticCode::UnusedVariable => "L1268".to_string(),
+            AssistContextDiagnosticCode::IncludeNotFound => "E1516".to_string(),
             AssistContextDiagnosticCode::ElpDiagnostic(code) => code.as_code(),
         }
     }
@@ -203,6 +238,7 @@ impl AssistContextDiagnosticCode {
             AssistContextDiagnosticCode::UndefinedFunction => "undefined_function".to_string(),
             AssistContextDiagnosticCode::UnusedFunction => "unused_function".to_string(),
             AssistContextDiagnosticCode::UnusedVariable => "unused_var".to_string(),
+            AssistContextDiagnosticCode::IncludeNotFound => "include_not_found".to_string(),
             AssistContextDiagnosticCode::ElpDiagnostic(code) => code.as_label(),
         }
     }
@@ -0,0 +1,358 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The calls between functions, as used by the call hierarchy, and
+//! between the modules making them.
+//!
+//! Function bodies are folded with their macros expanded, so that a call
+//! made by the expansion of a macro is attributed to the function using
+//! the macro, at the range of the macro call. Calls in the expansion of a
+//! macro used by another macro are not reported.
+
+use elp_base_db::FileId;
+use elp_base_db::FileKind;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SmolStr;
+use elp_syntax::TextRange;
+use fxhash::FxHashSet;
+use hir::fold::MacroStrategy;
+use hir::fold::ParenStrategy;
+use hir::AnyExpr;
+use hir::Expr;
+use hir::File;
+use hir::FunctionDef;
+use hir::InFile;
+use hir::Module;
+use hir::Semantic;
+use hir::Strategy;
+
+use crate::FxIndexMap;
+use crate::SymbolDefinition;
+
+/// A call made by a function.
+#[derive(Debug, Clone)]
+pub struct Call {
+    pub callee: FunctionDef,
+    /// How the call names the callee, e.g. `lists:map/2`
+    pub label: Option<SmolStr>,
+    /// The range of the called expression, or of the macro call whose
+    /// expansion makes the call
+    pub range: TextRange,
+}
+
+/// The calls made by `function`, in the order they appear.
+pub fn outgoing_calls(sema: &Semantic, function: &FunctionDef) -> Vec<Call> {
+    let file_id = function.file.file_id;
+    let function_id = InFile::new(file_id, function.function_id);
+    let source_file = sema.parse(file_id);
+    let function_body = sema.to_function_body(function_id);
+    let function_ranges: Vec<TextRange> = function
+        .source(sema.db.upcast())
+        .iter()
+        .map(|fun_decl| fun_decl.syntax().text_range())
+        .collect();
+    sema.fold_function(
+        Strategy {
+            macros: MacroStrategy::Expand,
+            parens: ParenStrategy::InvisibleParens,
+        },
+        function_id,
+        Vec::new(),
+        &mut |mut acc, clause_id, ctx| {
+            if let AnyExpr::Expr(Expr::Call { target, args }) = &ctx.item {
+                let arity = args.len() as u32;
+                let body = function_body.body(clause_id);
+                if let Some(callee) = target.resolve_call(arity, sema, file_id, &body) {
+                    let body_map = function_body.get_body_map(clause_id);
+                    let call_range = body_map.any(ctx.item_id).and_then(|expr| {
+                        match expr.to_node(&source_file)? {
+                            ast::Expr::Call(call) => Some(call.expr()?.syntax().text_range()),
+                            _ => None,
+                        }
+                    });
+                    let macro_range = ctx.in_macro.and_then(|macro_call| {
+                        let node = body_map.any(macro_call.idx)?.to_node(&source_file)?;
+                        Some(node.syntax().text_range())
+                    });
+                    // A call in the body of a macro defined in this file has
+                    // its source in the `-define`, the macro call stands for it
+                    let range = [call_range, macro_range]
+                        .into_iter()
+                        .flatten()
+                        .find(|range| {
+                            function_ranges
+                                .iter()
+                                .any(|function_range| function_range.contains_range(*range))
+                        });
+                    if let Some(range) = range {
+                        acc.push(Call {
+                            callee,
+                            label: target.label(arity, sema, &body),
+                            range,
+                        });
+                    }
+                }
+            }
+            acc
+        },
+    )
+}
+
+/// The calls to `function`, with the function making each of them.
+pub fn incoming_calls(sema: &Semantic, function: &FunctionDef) -> Vec<(FunctionDef, Call)> {
+    let mut res = Vec::new();
+    for file_id in caller_files(sema, SymbolDefinition::Function(function.clone())) {
+        let def_map = sema.def_map(file_id);
+        let mut callers: Vec<&FunctionDef> = def_map
+            .get_functions()
+            .map(|(_, def)| def)
+            .filter(|def| def.file.file_id == file_id)
+            .collect();
+        callers.sort_by_key(|def| def.name.to_string());
+        for caller in callers {
+            for call in outgoing_calls(sema, caller) {
+                if call.callee.file.file_id == function.file.file_id
+                    && call.callee.name == function.name
+                {
+                    res.push((caller.clone(), call));
+                }
+            }
+        }
+    }
+    res
+}
+
+/// The calls between two modules.
+#[derive(Debug, Clone)]
+pub struct ModuleCalls {
+    /// The other module, calling for the dependents, called for the
+    /// dependencies
+    pub file_id: FileId,
+    /// The calls, in the calling module
+    pub ranges: Vec<TextRange>,
+}
+
+/// The modules called by the module `file_id`, most called first.
+pub fn module_dependencies(sema: &Semantic, file_id: FileId) -> Vec<ModuleCalls> {
+    let mut res: FxIndexMap<FileId, Vec<TextRange>> = FxIndexMap::default();
+    let def_map = sema.def_map(file_id);
+    let mut functions: Vec<&FunctionDef> = def_map
+        .get_functions()
+        .map(|(_, def)| def)
+        .filter(|def| def.file.file_id == file_id)
+        .collect();
+    functions.sort_by_key(|def| def.name.to_string());
+    for function in functions {
+        for call in outgoing_calls(sema, function) {
+            let callee_file_id = call.callee.file.file_id;
+            if callee_file_id != file_id {
+                res.entry(callee_file_id).or_default().push(call.range);
+            }
+        }
+    }
+    sorted_module_calls(sema, res)
+}
+
+/// The modules calling the module `file_id`, most calling first.
+pub fn module_dependents(sema: &Semantic, file_id: FileId) -> Vec<ModuleCalls> {
+    let mut res: FxIndexMap<FileId, Vec<TextRange>> = FxIndexMap::default();
+    let module = SymbolDefinition::Module(Module {
+        file: File { file_id },
+    });
+    for caller_file_id in caller_files(sema, module) {
+        if caller_file_id == file_id {
+            continue;
+        }
+        let calls = module_dependencies(sema, caller_file_id)
+            .into_iter()
+            .find(|calls| calls.file_id == file_id);
+        if let Some(calls) = calls {
+            res.insert(caller_file_id, calls.ranges);
+        }
+    }
+    sorted_module_calls(sema, res)
+}
+
+fn sorted_module_calls(
+    sema: &Semantic,
+    calls: FxIndexMap<FileId, Vec<TextRange>>,
+) -> Vec<ModuleCalls> {
+    let mut res: Vec<ModuleCalls> = calls
+        .into_iter()
+        .map(|(file_id, ranges)| ModuleCalls { file_id, ranges })
+        .collect();
+    res.sort_by_cached_key(|calls| {
+        let name = sema.module_name(calls.file_id);
+        (
+            std::cmp::Reverse(calls.ranges.len()),
+            name.map(|name| name.as_str().to_string()),
+        )
+    });
+    res
+}
+
+/// The modules which may call `def`: the ones referring to it, and the
+/// ones including a header referring to it from a macro.
+fn caller_files(sema: &Semantic, def: SymbolDefinition) -> Vec<FileId> {
+    let usages = def.usages(sema).all();
+    let mut files = FxHashSet::default();
+    let mut headers: Vec<FileId> = Vec::new();
+    for (file_id, _) in usages.iter() {
+        if files.insert(file_id) && is_header(sema, file_id) {
+            headers.push(file_id);
+        }
+    }
+    while let Some(header) = headers.pop() {
+        let includers = SymbolDefinition::Header(File { file_id: header })
+            .usages(sema)
+            .all();
+        for (file_id, _) in includers.iter() {
+            if files.insert(file_id) && is_header(sema, file_id) {
+                headers.push(file_id);
+            }
+        }
+    }
+    let mut files: Vec<FileId> = files
+        .into_iter()
+        .filter(|file_id| !is_header(sema, *file_id))
+        .collect();
+    files.sort();
+    files
+}
+
+fn is_header(sema: &Semantic, file_id: FileId) -> bool {
+    File { file_id }.kind(sema.db.upcast()) == FileKind::Header
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use elp_base_db::FileRange;
+    use elp_base_db::SourceDatabaseExt;
+    use expect_test::expect;
+    use expect_test::Expect;
+    use hir::Name;
+    use hir::NameArity;
+    use hir::Semantic;
+
+    use super::incoming_calls;
+    use super::module_dependencies;
+    use super::module_dependents;
+    use super::outgoing_calls;
+    use crate::RootDatabase;
+
+    #[track_caller]
+    fn check(fixture: &str, name: &str, arity: u32, expect: Expect) {
+        let (db, position, _) = RootDatabase::with_position(fixture);
+        let sema = Semantic::new(&db);
+        let def_map = sema.def_map(position.file_id);
+        let function = def_map
+            .get_function(&NameArity::new(Name::from_erlang_service(name), arity))
+            .unwrap();
+        let text = |range: FileRange| db.file_text(range.file_id)[range.range].to_string();
+        let mut res = String::new();
+        for call in outgoing_calls(&sema, function) {
+            res.push_str(&format!(
+                "calls {} at `{}`\n",
+                call.callee.name,
+                text(FileRange {
+                    file_id: position.file_id,
+                    range: call.range
+                })
+            ));
+        }
+        for (caller, call) in incoming_calls(&sema, function) {
+            res.push_str(&format!(
+                "called by {} at `{}`\n",
+                caller.name,
+                text(FileRange {
+                    file_id: caller.file.file_id,
+                    range: call.range
+                })
+            ));
+        }
+        expect.assert_eq(&res);
+    }
+
+    #[test]
+    fn calls_through_macros() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+-define(HANDLE(X), handle(X, [])).
+~handle(Msg, State) -> log(Msg), State.
+log(_) -> ok.
+caller() -> ?HANDLE(hello).
+other() -> main:handle(hello, []).
+"#,
+            "handle",
+            2,
+            expect![[r#"
+                calls log/1 at `log`
+                called by caller/0 at `?HANDLE(hello)`
+                called by other/0 at `main:handle`
+            "#]],
+        );
+    }
+
+    #[track_caller]
+    fn check_modules(fixture: &str, expect: Expect) {
+        let (db, position, _) = RootDatabase::with_position(fixture);
+        let sema = Semantic::new(&db);
+        let mut res = String::new();
+        for calls in module_dependencies(&sema, position.file_id) {
+            let name = sema.module_name(calls.file_id).unwrap();
+            res.push_str(&format!("calls {} {}\n", name.as_str(), calls.ranges.len()));
+        }
+        for calls in module_dependents(&sema, position.file_id) {
+            let name = sema.module_name(calls.file_id).unwrap();
+            res.push_str(&format!(
+                "called by {} {}\n",
+                name.as_str(),
+                calls.ranges.len()
+            ));
+        }
+        expect.assert_eq(&res);
+    }
+
+    #[test]
+    fn module_dependencies_and_dependents() {
+        check_modules(
+            r#"
+//- /src/main.erl
+-module(~main).
+-export([run/0]).
+run() -> lists:reverse(other:get()), other:put(1), third:go().
+local() -> run().
+//- /src/other.erl
+-module(other).
+-export([get/0, put/1]).
+get() -> main:run().
+put(_) -> ok.
+//- /src/third.erl
+-module(third).
+-export([go/0]).
+go() -> main:run(), main:run().
+//- /src/lists.erl
+-module(lists).
+-export([reverse/1]).
+reverse(L) -> L.
+"#,
+            expect![[r#"
+                calls other 2
+                calls lists 1
+                calls third 1
+                called by third 2
+                called by other 1
+            "#]],
+        );
+    }
+}
@@ -38,6 +38,7 @@ use hir::InFile;
 use hir::Literal;
 use hir::MacroCallDef;
 use hir::Module;
+use hir::NameArity;
 use hir::RecordDef;
 use hir::RecordFieldDef;
 use hir::Semantic;
@@ -247,6 +248,10 @@ impl SymbolClass {
                         from_wrapper(sema, &token, wrapper)
                     }
                 },
+                ast::BinaryOpExpr(op) => {
+                    from_attribute_fa(sema, &token, &op)
+                        .or_else(|| from_wrapper(sema, &token, wrapper))
+                },
                 _ => from_wrapper(sema, &token, wrapper),
             }
         }
@@ -466,6 +471,36 @@ pub fn from_apply(
     }
 }
 
+/// `f/1` in an attribute, e.g. `-on_load(init/0)` or
+/// `-dialyzer({nowarn_function, f/1})`, names a local function.
+fn from_attribute_fa(
+    sema: &Semantic,
+    token: &InFile<SyntaxToken>,
+    op: &ast::BinaryOpExpr,
+) -> Option<SymbolClass> {
+    op.syntax().ancestors().find(|node| {
+        ast::WildAttribute::can_cast(node.kind())
+            || ast::CompileOptionsAttribute::can_cast(node.kind())
+    })?;
+    let (name, arity) = match (op.lhs()?, op.rhs()?) {
+        (
+            ast::Expr::ExprMax(ast::ExprMax::Atom(name)),
+            ast::Expr::ExprMax(ast::ExprMax::Integer(arity)),
+        ) => (name, arity),
+        _ => return None,
+    };
+    if !name
+        .syntax()
+        .text_range()
+        .contains_range(token.value.text_range())
+    {
+        return None;
+    }
+    let arity = arity.text().replace('_', "").parse().ok()?;
+    let fa = NameArity::new(name.as_name(), arity);
+    reference_direct(sema.def_map(token.file_id).get_function(&fa).cloned())
+}
+
 pub fn from_is_record(
     sema: &Semantic,
     token: &InFile<SyntaxToken>,
@@ -58,6 +58,32 @@ pub enum DiagnosticCode {
     BooleanPrecedence,
     UnexportedFunction,
     RecordTupleMatch,
+    StaleGeneratedFile,
+    MnesiaSchemaMismatch,
+    MissingOnLoad,
+    InvalidAttribute,
+    MessageTagTypo,
+    UnusedSuppression,
+    InvalidJsonLiteral,
+    StandaloneFile,
+    UnusedParameter,
+    ReturnValueIgnored,
+    UncaughtThrow,
+    UnusedRecord,
+    RedundantClause,
+    ConstantCondition,
+    MissingSpec,
+    MissingCallback,
+    CallbackMismatch,
+    ApiMigration,
+    AppDependencyViolation,
+    LegacyCollection,
+    RedundantExport,
+    ModuleHeader,
+    EmptinessCheck,
+    ConfigSchemaDrift,
+    IncompleteCallbacks,
+    DeniedCall,
 
     // Wrapper for erlang service diagnostic codes
     ErlangService(String),
@@ -128,6 +154,32 @@ impl DiagnosticCode {
             DiagnosticCode::BooleanPrecedence => "W0025".to_string(),
             DiagnosticCode::UnexportedFunction => "W0026".to_string(),
             DiagnosticCode::RecordTupleMatch => "W0027".to_string(),
+            DiagnosticCode::StaleGeneratedFile => "W0028".to_string(),
+            DiagnosticCode::MnesiaSchemaMismatch => "W0029".to_string(),
+            DiagnosticCode::MissingOnLoad => "W0030".to_string(),
+            DiagnosticCode::InvalidAttribute => "W0031".to_string(),
+            DiagnosticCode::MessageTagTypo => "W0032".to_string(),
+            DiagnosticCode::UnusedSuppression => "W0033".to_string(),
+            DiagnosticCode::InvalidJsonLiteral => "W0034".to_string(),
+            DiagnosticCode::StandaloneFile => "W0035".to_string(),
+            DiagnosticCode::UnusedParameter => "W0036".to_string(),
+            DiagnosticCode::ReturnValueIgnored => "W0037".to_string(),
+            DiagnosticCode::UncaughtThrow => "W0038".to_string(),
+            DiagnosticCode::UnusedRecord => "W0039".to_string(),
+            DiagnosticCode::RedundantClause => "W0040".to_string(),
+            DiagnosticCode::ConstantCondition => "W0041".to_string(),
+            DiagnosticCode::MissingSpec => "W0042".to_string(),
+            DiagnosticCode::MissingCallback => "W0043".to_string(),
+            DiagnosticCode::CallbackMismatch => "W0044".to_string(),
+            DiagnosticCode::ApiMigration => "W0045".to_string(),
+            DiagnosticCode::AppDependencyViolation => "W0046".to_string(),
+            DiagnosticCode::LegacyCollection => "W0047".to_string(),
+            DiagnosticCode::RedundantExport => "W0048".to_string(),
+            DiagnosticCode::ModuleHeader => "W0049".to_string(),
+            DiagnosticCode::EmptinessCheck => "W0050".to_string(),
+            DiagnosticCode::ConfigSchemaDrift => "W0051".to_string(),
+            DiagnosticCode::IncompleteCallbacks => "W0052".to_string(),
+            DiagnosticCode::DeniedCall => "W0053".to_string(),
             DiagnosticCode::ErlangService(c) => c.to_string(),
             DiagnosticCode::Eqwalizer(c) => format!("eqwalizer: {c}"),
             DiagnosticCode::AdHoc(c) => format!("ad-hoc: {c}"),
@@ -174,6 +226,32 @@ impl DiagnosticCode {
             DiagnosticCode::Unexpected(_) => "unexpected_semi_or_dot".to_string(),
             DiagnosticCode::ExpressionCanBeSimplified => "expression_can_be_simplified".to_string(),
             DiagnosticCode::RecordTupleMatch => "record_tuple_match".to_string(),
+            DiagnosticCode::StaleGeneratedFile => "stale_generated_file".to_string(),
+            DiagnosticCode::MnesiaSchemaMismatch => "mnesia_schema_mismatch".to_string(),
+            DiagnosticCode::MissingOnLoad => "missing_on_load".to_string(),
+            DiagnosticCode::InvalidAttribute => "invalid_attribute".to_string(),
+            DiagnosticCode::MessageTagTypo => "message_tag_typo".to_string(),
+            DiagnosticCode::UnusedSuppression => "unused_suppression".to_string(),
+            DiagnosticCode::InvalidJsonLiteral => "invalid_json_literal".to_string(),
+            DiagnosticCode::StandaloneFile => "standalone_file".to_string(),
+            DiagnosticCode::UnusedParameter => "unused_parameter".to_string(),
+            DiagnosticCode::ReturnValueIgnored => "return_value_ignored".to_string(),
+            DiagnosticCode::UncaughtThrow => "uncaught_throw".to_string(),
+            DiagnosticCode::UnusedRecord => "unused_record".to_string(),
+            DiagnosticCode::RedundantClause => "redundant_clause".to_string(),
+            DiagnosticCode::ConstantCondition => "constant_condition".to_string(),
+            DiagnosticCode::MissingSpec => "missing_spec".to_string(),
+            DiagnosticCode::MissingCallback => "missing_callback".to_string(),
+            DiagnosticCode::CallbackMismatch => "callback_mismatch".to_string(),
+            DiagnosticCode::ApiMigration => "api_migration".to_string(),
+            DiagnosticCode::AppDependencyViolation => "app_dependency_violation".to_string(),
+            DiagnosticCode::LegacyCollection => "legacy_collection".to_string(),
+            DiagnosticCode::RedundantExport => "redundant_export".to_string(),
+            DiagnosticCode::ModuleHeader => "module_header".to_string(),
+            DiagnosticCode::EmptinessCheck => "emptiness_check".to_string(),
+            DiagnosticCode::ConfigSchemaDrift => "config_schema_drift".to_string(),
+            DiagnosticCode::IncompleteCallbacks => "incomplete_callbacks".to_string(),
+            DiagnosticCode::DeniedCall => "denied_call".to_string(),
             DiagnosticCode::ErlangService(c) => c.to_string(),
             DiagnosticCode::Eqwalizer(c) => c.to_string(),
             DiagnosticCode::AdHoc(c) => format!("ad-hoc: {c}"),
@@ -186,6 +264,7 @@ impl DiagnosticCode {
     }
 
     pub fn maybe_from_string(s: &str) -> Option<DiagnosticCode> {
+        let s = Self::unqualified(s);
         DIAGNOSTIC_CODE_LOOKUPS
             .get(s).cloned()
             // @fb-only
@@ -214,6 +293,43 @@ impl DiagnosticCode {
         }
     }
 
+    /// The tool emitting the code, one per namespace of the Erlang
+    /// Error Index, plus eqwalizer.
+    pub fn as_category(&self) -> Option<&'static str> {
+        match self {
+            DiagnosticCode::Eqwalizer(_) => Some("eqwalizer"),
+            _ => match self.as_namespace()?.as_str() {
+                "c" => Some("compiler"),
+                "d" => Some("epp_dodger"),
+                "e" => Some("preprocessor"),
+                "l" => Some("linter"),
+                "o" => Some("edoc"),
+                "p" => Some("parser"),
+                "s" => Some("scanner"),
+                "w" => Some("elp"),
+                _ => None,
+            },
+        }
+    }
+
+    /// The code prefixed with its category, such as `linter::L1230`,
+    /// letting clients group related diagnostics.
+    pub fn as_qualified_code(&self) -> String {
+        let code = match self {
+            DiagnosticCode::Eqwalizer(c) => c.to_string(),
+            _ => self.as_code(),
+        };
+        match self.as_category() {
+            Some(category) => format!("{category}::{code}"),
+            None => code,
+        }
+    }
+
+    /// Strip the category from a qualified code, if any.
+    pub fn unqualified(s: &str) -> &str {
+        s.split_once("::").map_or(s, |(_, code)| code)
+    }
+
     pub fn as_uri(&self) -> Option<String> {
         let namespace = self.as_namespace()?;
         let code = self.as_code();
@@ -258,6 +374,11 @@ impl DiagnosticCode {
             DiagnosticCode::CrossNodeEval => true,
             DiagnosticCode::MissingCompileWarnMissingSpec => true,
             DiagnosticCode::RecordTupleMatch => true,
+            DiagnosticCode::MnesiaSchemaMismatch => true,
+            DiagnosticCode::MissingOnLoad => true,
+            DiagnosticCode::InvalidAttribute => true,
+            DiagnosticCode::MessageTagTypo => true,
+            DiagnosticCode::InvalidJsonLiteral => true,
             // False list
             DiagnosticCode::DefaultCodeForEnumIter => false,
             DiagnosticCode::HeadMismatch => false,
@@ -287,6 +408,27 @@ impl DiagnosticCode {
             DiagnosticCode::SlowFunction => false,
             DiagnosticCode::BooleanPrecedence => false,
             DiagnosticCode::UnexportedFunction => false,
+            DiagnosticCode::StaleGeneratedFile => false,
+            DiagnosticCode::UnusedSuppression => false,
+            DiagnosticCode::StandaloneFile => false,
+            DiagnosticCode::UnusedParameter => false,
+            DiagnosticCode::ReturnValueIgnored => false,
+            DiagnosticCode::UncaughtThrow => false,
+            DiagnosticCode::UnusedRecord => false,
+            DiagnosticCode::RedundantClause => false,
+            DiagnosticCode::ConstantCondition => false,
+            DiagnosticCode::MissingSpec => false,
+            DiagnosticCode::MissingCallback => false,
+            DiagnosticCode::CallbackMismatch => false,
+            DiagnosticCode::ApiMigration => false,
+            DiagnosticCode::AppDependencyViolation => false,
+            DiagnosticCode::LegacyCollection => false,
+            DiagnosticCode::RedundantExport => false,
+            DiagnosticCode::ModuleHeader => false,
+            DiagnosticCode::EmptinessCheck => false,
+            DiagnosticCode::ConfigSchemaDrift => false,
+            DiagnosticCode::IncompleteCallbacks => false,
+            DiagnosticCode::DeniedCall => false,
             DiagnosticCode::ErlangService(_) => false,
             DiagnosticCode::Eqwalizer(_) => false,
             DiagnosticCode::AdHoc(_) => false,
@@ -410,6 +552,35 @@ mod tests {
         .assert_debug_eq(&codes);
     }
 
+    #[test]
+    fn qualified_code_round_trip() {
+        let codes = vec![
+            DiagnosticCode::UndefinedFunction,
+            DiagnosticCode::ErlangService("L1230".to_string()),
+            DiagnosticCode::Eqwalizer("incompatible_types".to_string()),
+        ];
+        let qualified = codes
+            .iter()
+            .map(|c| c.as_qualified_code())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            qualified,
+            vec![
+                "elp::W0017",
+                "linter::L1230",
+                "eqwalizer::incompatible_types"
+            ]
+        );
+        assert_eq!(
+            DiagnosticCode::maybe_from_string(&qualified[0]),
+            Some(DiagnosticCode::UndefinedFunction)
+        );
+        assert_eq!(
+            DiagnosticCode::maybe_from_string(&qualified[1]),
+            Some(DiagnosticCode::ErlangService("L1230".to_string()))
+        );
+    }
+
     #[test]
     fn serde_serialize_diagnostic_code() {
         assert_eq!(
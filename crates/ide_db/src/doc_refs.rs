@@ -0,0 +1,279 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! References to modules and functions from the documentation: the
+//! `` `m:f/1` `` code spans of `-doc` and `-moduledoc` attributes, and the
+//! `{@link m:f/1}` tags of EDoc comments. They are not part of the syntax
+//! tree, so they are extracted from the text of the attributes and comments.
+
+use elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::NodeOrToken;
+use elp_syntax::SyntaxKind;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use fxhash::FxHashMap;
+use hir::Name;
+use hir::NameArity;
+use hir::Semantic;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::SymbolDefinition;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocTarget {
+    Module(String),
+    Function {
+        module: Option<String>,
+        name: String,
+        arity: u32,
+    },
+}
+
+/// A module or function name written in the documentation, `range` being
+/// the one of the name only, e.g. `f` in `` `m:f/1` ``.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocReference {
+    pub range: TextRange,
+    pub target: DocTarget,
+}
+
+impl DocReference {
+    pub fn name(&self) -> &str {
+        match &self.target {
+            DocTarget::Module(name) => name,
+            DocTarget::Function { name, .. } => name,
+        }
+    }
+
+    /// The module or function referenced from `file_id`, if it exists.
+    pub fn resolve(&self, sema: &Semantic, file_id: FileId) -> Option<SymbolDefinition> {
+        match &self.target {
+            DocTarget::Module(name) => {
+                let module = sema.resolve_module_name(file_id, name)?;
+                Some(SymbolDefinition::Module(module))
+            }
+            DocTarget::Function {
+                module,
+                name,
+                arity,
+            } => {
+                let target_file_id = match module {
+                    Some(module) => sema.resolve_module_name(file_id, module)?.file.file_id,
+                    None => file_id,
+                };
+                let name = NameArity::new(Name::from_erlang_service(name), *arity);
+                let function = sema.def_map(target_file_id).get_function(&name)?.clone();
+                Some(SymbolDefinition::Function(function))
+            }
+        }
+    }
+}
+
+/// All the references written in the documentation of `file_id`.
+pub fn doc_references(sema: &Semantic, file_id: FileId) -> Vec<DocReference> {
+    lazy_static! {
+        static ref CODE_SPAN: Regex = Regex::new(r"`([^`\s]+)`").unwrap();
+        static ref EDOC_LINK: Regex = Regex::new(r"\{@link\s+([^\s}]+)").unwrap();
+    }
+    let source = sema.parse(file_id).value;
+    let mut res = Vec::new();
+    for element in source.syntax().descendants_with_tokens() {
+        match element {
+            NodeOrToken::Node(node) => {
+                let Some(value) = ast::WildAttribute::cast(node)
+                    .filter(is_doc_attribute)
+                    .and_then(|attribute| attribute.value())
+                else {
+                    continue;
+                };
+                let text = value.syntax().text().to_string();
+                let start = value.syntax().text_range().start();
+                for captures in CODE_SPAN.captures_iter(&text) {
+                    let span = captures.get(1).unwrap();
+                    res.extend(parse_reference(
+                        span.as_str(),
+                        start + offset(span.start()),
+                        true,
+                    ));
+                }
+            }
+            NodeOrToken::Token(token) if token.kind() == SyntaxKind::COMMENT => {
+                let start = token.text_range().start();
+                for captures in EDOC_LINK.captures_iter(token.text()) {
+                    let target = captures.get(1).unwrap();
+                    res.extend(parse_reference(
+                        target.as_str(),
+                        start + offset(target.start()),
+                        false,
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    res
+}
+
+impl SymbolDefinition {
+    /// The references to this module or function from the documentation
+    /// of the files which may refer to it.
+    pub fn doc_usages(&self, sema: &Semantic) -> FxHashMap<FileId, Vec<TextRange>> {
+        let mut res: FxHashMap<FileId, Vec<TextRange>> = FxHashMap::default();
+        if !matches!(
+            self,
+            SymbolDefinition::Module(_) | SymbolDefinition::Function(_)
+        ) {
+            return res;
+        }
+        let name = self.search_name(sema.db);
+        for (file_id, _) in self.search_scope(sema) {
+            if !sema.db.file_text(file_id).contains(name.as_str()) {
+                continue;
+            }
+            for reference in doc_references(sema, file_id) {
+                if reference.name() == name.as_str()
+                    && reference.resolve(sema, file_id).as_ref() == Some(self)
+                {
+                    res.entry(file_id).or_default().push(reference.range);
+                }
+            }
+        }
+        res
+    }
+}
+
+fn is_doc_attribute(attribute: &ast::WildAttribute) -> bool {
+    attribute
+        .name()
+        .and_then(|name| name.name())
+        .is_some_and(|name| {
+            matches!(
+                name.syntax().text().to_string().as_str(),
+                "doc" | "moduledoc"
+            )
+        })
+}
+
+/// Parses `f/1`, `m:f/1` and the module references, `` `m:mod` `` in
+/// markdown and `{@link mod}` in EDoc. The types and callbacks of the
+/// `` `t:type/0` `` and `` `c:cb/1` `` markdown spans are left out.
+fn parse_reference(text: &str, start: TextSize, markdown: bool) -> Vec<DocReference> {
+    let (path, arity) = match text.rsplit_once('/') {
+        Some((path, arity)) => match arity.parse::<u32>() {
+            Ok(arity) => (path, Some(arity)),
+            Err(_) => return vec![],
+        },
+        None => (text, None),
+    };
+    let mut parts = Vec::new();
+    let mut position = 0;
+    for part in path.split(':') {
+        if !is_name(part) {
+            return vec![];
+        }
+        parts.push((part, start + offset(position)));
+        position += part.len() + 1;
+    }
+    let range = |part: &str, start: TextSize| TextRange::at(start, TextSize::of(part));
+    let module = |part: &str, start: TextSize| DocReference {
+        range: range(part, start),
+        target: DocTarget::Module(part.to_string()),
+    };
+    let function = |module: Option<&str>, part: &str, start: TextSize, arity: u32| DocReference {
+        range: range(part, start),
+        target: DocTarget::Function {
+            module: module.map(|module| module.to_string()),
+            name: part.to_string(),
+            arity,
+        },
+    };
+    match (parts.as_slice(), arity) {
+        ([(name, name_start)], Some(arity)) => vec![function(None, *name, *name_start, arity)],
+        ([(kind, _), _], Some(_)) if markdown && matches!(*kind, "t" | "c") => vec![],
+        ([(m, m_start), (name, name_start)], Some(arity)) => vec![
+            module(*m, *m_start),
+            function(Some(*m), *name, *name_start, arity),
+        ],
+        ([("m", _), (m, m_start)], None) if markdown => vec![module(*m, *m_start)],
+        ([(m, m_start)], None) if !markdown => vec![module(*m, *m_start)],
+        _ => vec![],
+    }
+}
+
+fn is_name(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '@')
+}
+
+fn offset(offset: usize) -> TextSize {
+    TextSize::from(offset as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use elp_base_db::SourceDatabaseExt;
+    use expect_test::expect;
+    use expect_test::Expect;
+    use hir::Semantic;
+
+    use super::doc_references;
+    use crate::RootDatabase;
+
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id) = RootDatabase::with_single_file(fixture);
+        let sema = Semantic::new(&db);
+        let text = db.file_text(file_id);
+        let actual = doc_references(&sema, file_id)
+            .into_iter()
+            .map(|reference| format!("{} {:?}\n", &text[reference.range], reference.target))
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn doc_attribute_references() {
+        check(
+            r#"
+-module(main).
+-moduledoc "Uses `m:lists` and `t:main:t/0`, see `foo/1`.".
+-doc "Like `lists:map/2` or `c:init/1`, not `Foo/1` or `bar`.".
+foo(X) -> X.
+"#,
+            expect![[r#"
+                lists Module("lists")
+                foo Function { module: None, name: "foo", arity: 1 }
+                lists Module("lists")
+                map Function { module: Some("lists"), name: "map", arity: 2 }
+            "#]],
+        );
+    }
+
+    #[test]
+    fn edoc_link_references() {
+        check(
+            r#"
+-module(main).
+%% @doc See {@link foo/1} and {@link lists:map/2 the map}, from {@link lists}.
+%% Not {@link https://erlang.org} nor {@link t()}.
+foo(X) -> X.
+"#,
+            expect![[r#"
+                foo Function { module: None, name: "foo", arity: 1 }
+                lists Module("lists")
+                map Function { module: Some("lists"), name: "map", arity: 2 }
+                lists Module("lists")
+            "#]],
+        );
+    }
+}
@@ -23,11 +23,16 @@ use elp_erlang_service::DocOrigin;
 use elp_erlang_service::DocRequest;
 use elp_syntax::ast;
 use elp_syntax::match_ast;
+use elp_syntax::unescape::triple_quoted_contents;
+use elp_syntax::unescape::unescape_string;
 use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
 use elp_syntax::SyntaxToken;
 use fxhash::FxHashMap;
 use hir::db::DefDatabase;
 use hir::CallDef;
+use hir::FormIdx;
 use hir::InFile;
 use hir::Name;
 use hir::NameArity;
@@ -239,7 +244,21 @@ fn get_file_docs(db: &dyn DocDatabase, file_id: FileId) -> Arc<FileDoc> {
         DocOrigin::Edoc
     };
 
-    let descriptions = db.load_doc_descriptions(file_id, origin);
+    let mut descriptions = db.load_doc_descriptions(file_id, origin);
+    // The documentation found in the source fills in what the loader
+    // could not provide, e.g. when the Erlang service is unavailable or
+    // the file is not saved
+    let source_docs = get_file_source_docs(db.upcast(), file_id);
+    if descriptions
+        .module_doc
+        .as_ref()
+        .map_or(true, |doc| doc.markdown_text.trim().is_empty())
+    {
+        descriptions.module_doc = source_docs.module_doc;
+    }
+    for (name, doc) in source_docs.function_docs {
+        descriptions.function_docs.entry(name).or_insert(doc);
+    }
     let specs = get_file_function_specs(db.upcast(), file_id);
     Arc::new(FileDoc {
         module_doc: descriptions.module_doc,
@@ -248,6 +267,127 @@ fn get_file_docs(db: &dyn DocDatabase, file_id: FileId) -> Arc<FileDoc> {
     })
 }
 
+/// The documentation written in the file: its `-moduledoc` and `-doc`
+/// attributes, already Markdown, and failing those the `@doc` tags of
+/// its EDoc comments, as plain text.
+fn get_file_source_docs(def_db: &dyn DefDatabase, file_id: FileId) -> FileDoc {
+    let form_list = def_db.file_form_list(file_id);
+    let source = def_db.parse(file_id).tree();
+    let mut module_doc = None;
+    let mut function_docs = FxHashMap::default();
+    // The `-doc` attribute, or the EDoc comment of the `-spec`, waiting
+    // for the function it documents
+    let mut pending_doc = None;
+    let mut pending_edoc = None;
+    for &form in form_list.forms() {
+        if !matches!(
+            form,
+            FormIdx::DocAttribute(_) | FormIdx::Spec(_) | FormIdx::FunctionClause(_)
+        ) {
+            // Documentation followed by another form, e.g. a type,
+            // belongs to that form rather than to the next function
+            pending_doc = None;
+            pending_edoc = None;
+        }
+        match form {
+            FormIdx::ModuleDocAttribute(idx) => {
+                let attribute = form_list[idx].form_id.get(&source);
+                module_doc = doc_attribute_text(&attribute);
+            }
+            FormIdx::ModuleAttribute(idx) if module_doc.is_none() => {
+                let attribute = form_list[idx].form_id.get(&source);
+                module_doc = edoc_comment_text(attribute.syntax());
+            }
+            FormIdx::DocAttribute(idx) => {
+                let attribute = form_list[idx].form_id.get(&source);
+                if !is_doc_metadata(&attribute) {
+                    pending_doc = doc_attribute_text(&attribute);
+                }
+            }
+            FormIdx::Spec(idx) => {
+                let spec = form_list[idx].form_id.get(&source);
+                pending_edoc = edoc_comment_text(spec.syntax());
+            }
+            FormIdx::FunctionClause(idx) => {
+                let clause = &form_list[idx];
+                let doc = pending_doc.take().or(pending_edoc.take());
+                if function_docs.contains_key(&clause.name) {
+                    continue;
+                }
+                let doc = doc.or_else(|| edoc_comment_text(clause.form_id.get(&source).syntax()));
+                if let Some(doc) = doc {
+                    function_docs.insert(clause.name.clone(), doc);
+                }
+            }
+            _ => {}
+        }
+    }
+    FileDoc {
+        module_doc,
+        function_docs,
+        diagnostics: vec![],
+    }
+}
+
+/// `-doc #{...}.` gives the metadata of the documentation, rather than
+/// the documentation itself.
+fn is_doc_metadata(attribute: &ast::WildAttribute) -> bool {
+    matches!(attribute.value(), Some(ast::Expr::MapExpr(_)))
+}
+
+/// The text of a `-doc` or `-moduledoc` attribute, `None` for
+/// `-doc false.` or an empty text.
+fn doc_attribute_text(attribute: &ast::WildAttribute) -> Option<Doc> {
+    let value = attribute.value()?.syntax().text().to_string();
+    let text = if value.starts_with("\"\"\"") {
+        triple_quoted_contents(&value)?
+    } else if value.starts_with('"') {
+        unescape_string(&value)?.into_owned()
+    } else {
+        return None;
+    };
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some(Doc::new(text.to_string()))
+}
+
+/// The `@doc` tag of the EDoc comment right before the form, up to the
+/// next tag.
+fn edoc_comment_text(form: &SyntaxNode) -> Option<Doc> {
+    let mut lines = Vec::new();
+    let mut token = form.first_token()?.prev_token();
+    while let Some(t) = token {
+        match t.kind() {
+            SyntaxKind::COMMENT => lines.push(t.text().trim_start_matches('%').to_string()),
+            SyntaxKind::WHITESPACE if t.text().matches('\n').count() <= 1 => {}
+            _ => break,
+        }
+        token = t.prev_token();
+    }
+    lines.reverse();
+    let mut doc: Option<Vec<String>> = None;
+    for line in lines {
+        let line = line.strip_prefix(' ').unwrap_or(&line);
+        match &mut doc {
+            None => {
+                if let Some(rest) = line.trim_start().strip_prefix("@doc") {
+                    doc = Some(vec![rest.trim().to_string()]);
+                }
+            }
+            Some(_) if line.trim_start().starts_with('@') => break,
+            Some(doc) => doc.push(line.to_string()),
+        }
+    }
+    let text = doc?.join("\n");
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some(Doc::new(text.to_string()))
+}
+
 fn get_file_specs(db: &dyn DocDatabase, file_id: FileId) -> Arc<FxHashMap<NameArity, Doc>> {
     let specs = get_file_function_specs(db.upcast(), file_id);
     Arc::new(specs)
@@ -301,7 +441,7 @@ fn get_file_function_specs(def_db: &dyn DefDatabase, file_id: FileId) -> FxHashM
 impl DocLoader for crate::RootDatabase {
     fn load_doc_descriptions(&self, file_id: FileId, doc_origin: DocOrigin) -> FileDoc {
         _ = SourceDatabaseExt::file_text(self, file_id); // Take dependency on the contents of the file we're getting docs for
-        // Context for T171541590
+                                                         // Context for T171541590
         let _ = stdx::panic_context::enter(format!("\nload_doc_descriptions: {:?}", file_id));
         let root_id = self.file_source_root(file_id);
         let root = self.source_root(root_id);
@@ -319,7 +459,14 @@ impl DocLoader for crate::RootDatabase {
 
         let project_id = app_data.project_id;
         let erlang_service = self.erlang_service_for(project_id);
-        let path = root.path_for_file(&file_id).unwrap().as_path().unwrap();
+        let Some(path) = root.path_for_file(&file_id).unwrap().as_path() else {
+            // The documents without a file on disk have no EDoc
+            return FileDoc {
+                module_doc: None,
+                function_docs: FxHashMap::default(),
+                diagnostics: vec![],
+            };
+        };
         let format = elp_erlang_service::Format::OffsetEtf;
         let src_path = path.to_path_buf().into();
         let doc_request = match doc_origin {
@@ -0,0 +1,384 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Conversion of legacy EDoc comments to the `-moduledoc` and `-doc`
+//! attributes of OTP 27.
+//!
+//! The `@doc`, `@param`, `@returns`, `@since`, `@deprecated`, `@hidden`,
+//! `@private` and `@end` tags are converted, and their comments removed.
+//! Comments with any other tag, such as `@author` or `@see`, are kept as
+//! they are. Forms already documented with a `-doc` or `-moduledoc`
+//! attribute are left alone.
+
+use elp_base_db::FileId;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SyntaxKind;
+use elp_syntax::SyntaxNode;
+use elp_syntax::TextRange;
+use elp_syntax::TextSize;
+use hir::edoc::EdocHeader;
+use hir::edoc::EdocTag;
+use hir::Semantic;
+use lazy_static::lazy_static;
+use regex::Regex;
+use text_edit::TextEdit;
+
+const CONVERTED_TAGS: &[&str] = &[
+    "doc",
+    "param",
+    "returns",
+    "return",
+    "since",
+    "deprecated",
+    "hidden",
+    "private",
+    "end",
+];
+
+/// The edit replacing the EDoc comments of `file_id` with documentation
+/// attributes, or `None` if there is nothing to convert.
+pub fn migrate_edoc(sema: &Semantic, file_id: FileId) -> Option<TextEdit> {
+    let source = sema.parse(file_id);
+    let text = sema.db.file_text(file_id);
+    let edocs = sema.file_edoc_comments(file_id)?;
+    let mut headers: Vec<&EdocHeader> = edocs.values().collect();
+    headers.sort_by_key(|header| header.form().range().start());
+
+    let mut edit = TextEdit::builder();
+    let mut changed = false;
+    for header in headers {
+        let tags: Vec<&EdocTag> = header
+            .tags()
+            .iter()
+            .filter(|tag| CONVERTED_TAGS.contains(&tag.name()))
+            .collect();
+        if tags.is_empty() {
+            continue;
+        }
+        let ranges: Vec<TextRange> = tags.iter().flat_map(|tag| tag.text_ranges()).collect();
+        match header.form().to_node(&source) {
+            Some(ast::Form::ModuleAttribute(module)) => {
+                if has_attribute(source.value.syntax().children(), "moduledoc") {
+                    continue;
+                }
+                let attributes = doc_attributes("moduledoc", &tags);
+                for range in ranges {
+                    edit.delete(line_range(&text, range));
+                }
+                if !attributes.is_empty() {
+                    edit.insert(
+                        module.syntax().text_range().end(),
+                        format!("\n{attributes}"),
+                    );
+                }
+            }
+            Some(ast::Form::FunDecl(function)) => {
+                if has_attribute(prev_form_nodes(function.syntax()), "doc") {
+                    continue;
+                }
+                let attributes = doc_attributes("doc", &tags);
+                let mut ranges = ranges.into_iter();
+                match ranges.next() {
+                    Some(first) if !attributes.is_empty() => edit.replace(first, attributes),
+                    Some(first) => edit.delete(line_range(&text, first)),
+                    None => {}
+                }
+                for range in ranges {
+                    edit.delete(line_range(&text, range));
+                }
+            }
+            _ => continue,
+        }
+        changed = true;
+    }
+    if changed {
+        Some(edit.finish())
+    } else {
+        None
+    }
+}
+
+/// The documentation attributes equivalent to the given tags.
+fn doc_attributes(attribute: &str, tags: &[&EdocTag]) -> String {
+    let mut doc = Vec::new();
+    let mut params = Vec::new();
+    let mut returns = None;
+    let mut metadata = Vec::new();
+    let mut hidden = false;
+    for tag in tags {
+        let text = tag_text(tag);
+        match tag.name() {
+            "doc" if !text.is_empty() => doc.push(text),
+            "param" => {
+                let (name, description) = text
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((text.as_str(), ""));
+                params.push(format!(
+                    "- `{name}` - {}",
+                    description.trim_start().replace('\n', "\n  ")
+                ));
+            }
+            "returns" | "return" if !text.is_empty() => returns = Some(text),
+            "since" | "deprecated" if !text.is_empty() => metadata.push(format!(
+                "{} => {}",
+                tag.name(),
+                quoted(&text.split_whitespace().collect::<Vec<_>>().join(" "))
+            )),
+            "hidden" | "private" => hidden = true,
+            _ => {}
+        }
+    }
+    if hidden {
+        return format!("-{attribute} false.");
+    }
+    if !params.is_empty() {
+        doc.push(format!("Arguments:\n\n{}", params.join("\n")));
+    }
+    if let Some(returns) = returns {
+        doc.push(format!("Returns: {returns}"));
+    }
+    let mut res = Vec::new();
+    if !doc.is_empty() {
+        res.push(format!(
+            "-{attribute} {}.",
+            triple_quoted(&edoc_to_markdown(&doc.join("\n\n")))
+        ));
+    }
+    if !metadata.is_empty() {
+        res.push(format!("-{attribute} #{{{}}}.", metadata.join(", ")));
+    }
+    res.join("\n")
+}
+
+/// The text of a tag, without comment markers or the tag itself.
+fn tag_text(tag: &EdocTag) -> String {
+    let sources = tag.sources();
+    let mut lines: Vec<&str> = sources
+        .iter()
+        .map(|source| {
+            let line = source.trim_start_matches('%');
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        })
+        .collect();
+    if let Some(first) = lines.first_mut() {
+        let line = first.trim_start();
+        *first = line
+            .strip_prefix('@')
+            .and_then(|line| line.strip_prefix(tag.name()))
+            .unwrap_or(line)
+            .trim_start();
+    }
+    // Continuation lines are usually aligned with the text after the tag
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let lines: Vec<&str> = lines
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            if idx == 0 {
+                *line
+            } else {
+                line.get(indent..).unwrap_or("")
+            }
+        })
+        .collect();
+    lines.join("\n").trim_matches('\n').to_string()
+}
+
+/// Rewrites the EDoc markup that has a markdown equivalent. Text that is
+/// already markdown is left as it is.
+fn edoc_to_markdown(text: &str) -> String {
+    lazy_static! {
+        static ref LINK: Regex = Regex::new(r"\{@link\s+([^\s}]+)\s*([^}]*)\}").unwrap();
+        static ref CODE: Regex = Regex::new(r"\{@code\s+([^}]*)\}").unwrap();
+        static ref DOUBLE_QUOTED: Regex = Regex::new(r"``([^`'\n]+)''").unwrap();
+        static ref QUOTED: Regex = Regex::new(r"`([^`'\s][^`'\n]*)'").unwrap();
+        static ref EMPHASIS: Regex = Regex::new(r"<(em|i)>(.*?)</(em|i)>").unwrap();
+        static ref STRONG: Regex = Regex::new(r"<(b|strong)>(.*?)</(b|strong)>").unwrap();
+        static ref TT: Regex = Regex::new(r"<(code|tt)>(.*?)</(code|tt)>").unwrap();
+    }
+    let text = LINK.replace_all(text, |captures: &regex::Captures| {
+        let target = &captures[1];
+        let label = captures[2].trim();
+        match (target.contains("://"), label.is_empty()) {
+            (true, true) => format!("<{target}>"),
+            (true, false) => format!("[{label}]({target})"),
+            (false, true) => format!("`{target}`"),
+            (false, false) => format!("[{label}](`{target}`)"),
+        }
+    });
+    let text = CODE.replace_all(&text, "`$1`");
+    let text = DOUBLE_QUOTED.replace_all(&text, "`$1`");
+    let text = QUOTED.replace_all(&text, "`$1`");
+    let text = EMPHASIS.replace_all(&text, "*$2*");
+    let text = STRONG.replace_all(&text, "**$2**");
+    let text = TT.replace_all(&text, "`$2`");
+    text.into_owned()
+}
+
+/// The range of the whole line holding a comment, including its newline,
+/// so that removing the comment leaves no blank line behind.
+fn line_range(text: &str, range: TextRange) -> TextRange {
+    let before = &text[..usize::from(range.start())];
+    let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    if !before[line_start..].trim().is_empty() {
+        return range;
+    }
+    let end = usize::from(range.end());
+    let line_end = text[end..]
+        .find('\n')
+        .map_or(text.len(), |idx| end + idx + 1);
+    TextRange::new(
+        TextSize::from(line_start as u32),
+        TextSize::from(line_end as u32),
+    )
+}
+
+fn prev_form_nodes(syntax: &SyntaxNode) -> impl Iterator<Item = SyntaxNode> {
+    syntax
+        .siblings_with_tokens(elp_syntax::Direction::Prev)
+        .skip(1) // Starts with itself
+        .filter_map(|node_or_token| node_or_token.into_node())
+        .take_while(|node| node.kind() != SyntaxKind::FUN_DECL)
+}
+
+fn has_attribute(mut nodes: impl Iterator<Item = SyntaxNode>, name: &str) -> bool {
+    nodes.any(|node| {
+        ast::WildAttribute::cast(node)
+            .and_then(|attribute| attribute.name()?.name())
+            .is_some_and(|attr_name| attr_name.syntax().text() == name)
+    })
+}
+
+fn triple_quoted(contents: &str) -> String {
+    // The delimiter must be longer than any run of quotes in the contents
+    let longest_run = contents
+        .split(|c| c != '"')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    let quotes = "\"".repeat((longest_run + 1).max(3));
+    format!("{quotes}\n{contents}\n{quotes}")
+}
+
+fn quoted(contents: &str) -> String {
+    format!(
+        "\"{}\"",
+        contents.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use elp_base_db::SourceDatabaseExt;
+    use expect_test::expect;
+    use expect_test::Expect;
+    use hir::Semantic;
+
+    use super::edoc_to_markdown;
+    use super::migrate_edoc;
+    use crate::RootDatabase;
+
+    #[track_caller]
+    fn check(fixture: &str, expect: Expect) {
+        let (db, file_id) = RootDatabase::with_single_file(fixture);
+        let sema = Semantic::new(&db);
+        let mut text = db.file_text(file_id).to_string();
+        if let Some(edit) = migrate_edoc(&sema, file_id) {
+            edit.apply(&mut text);
+        }
+        expect.assert_eq(&text);
+    }
+
+    #[test]
+    fn module_and_functions() {
+        check(
+            r#"
+%% @author Jane
+%% @doc A module that greets.
+%% @since 1.0
+-module(main).
+-export([greet/2, helper/0]).
+
+%% @doc Greets `Who'.
+%%      Politely, see {@link greet_all/1}.
+%% @param Who the person
+%%        to greet
+%% @param Opts options
+%% @returns ``ok''
+-spec greet(atom(), map()) -> ok.
+greet(Who, Opts) -> ok.
+
+%% @private
+helper() -> ok.
+"#,
+            expect![[r#"
+                %% @author Jane
+                -module(main).
+                -moduledoc """
+                A module that greets.
+                """.
+                -moduledoc #{since => "1.0"}.
+                -export([greet/2, helper/0]).
+
+                -doc """
+                Greets `Who`.
+                Politely, see `greet_all/1`.
+
+                Arguments:
+
+                - `Who` - the person
+                  to greet
+                - `Opts` - options
+
+                Returns: `ok`
+                """.
+                -spec greet(atom(), map()) -> ok.
+                greet(Who, Opts) -> ok.
+
+                -doc false.
+                helper() -> ok.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn already_documented() {
+        check(
+            r#"
+-module(main).
+%% @doc Old doc.
+-doc "New doc.".
+foo() -> ok.
+"#,
+            expect![[r#"
+                -module(main).
+                %% @doc Old doc.
+                -doc "New doc.".
+                foo() -> ok.
+            "#]],
+        );
+    }
+
+    #[test]
+    fn markup() {
+        expect![[r#"[docs](https://erlang.org) and *very* `lists:map/2`"#]].assert_eq(
+            &edoc_to_markdown(
+                "{@link https://erlang.org docs} and <em>very</em> {@link lists:map/2}",
+            ),
+        );
+    }
+}
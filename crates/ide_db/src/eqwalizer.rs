@@ -29,14 +29,19 @@ use elp_eqwalizer::EqwalizerDiagnostic;
 use elp_eqwalizer::EqwalizerDiagnostics;
 use elp_eqwalizer::EqwalizerDiagnosticsDatabase;
 use elp_project_model::otp::otp_supported_by_eqwalizer;
+use elp_project_model::EqwalizerLevel;
 use elp_syntax::ast;
+use elp_syntax::AstNode;
 use elp_syntax::SmolStr;
+use elp_syntax::SyntaxKind;
+use elp_syntax::TextRange;
 use elp_types_db::eqwalizer;
 use elp_types_db::eqwalizer::types::Type;
 use elp_types_db::IncludeGenerated;
 use fxhash::FxHashSet;
 use parking_lot::Mutex;
 
+use crate::diagnostic_code::BASE_URL;
 use crate::ErlAstDatabase;
 use crate::LineCol;
 
@@ -94,6 +99,7 @@ pub trait EqwalizerDatabase:
     fn has_eqwalizer_module_marker(&self, file_id: FileId) -> bool;
     fn has_eqwalizer_ignore_marker(&self, file_id: FileId) -> bool;
     fn is_eqwalizer_enabled(&self, file_id: FileId, include_generated: IncludeGenerated) -> bool;
+    fn eqwalizer_level(&self, file_id: FileId) -> EqwalizerLevel;
 }
 
 pub fn eqwalizer_diagnostics_by_project(
@@ -101,7 +107,83 @@ pub fn eqwalizer_diagnostics_by_project(
     project_id: ProjectId,
     file_ids: Vec<FileId>,
 ) -> Arc<EqwalizerDiagnostics> {
-    Arc::new(db.typecheck(project_id, file_ids))
+    let strict: Vec<FileId> = file_ids
+        .iter()
+        .copied()
+        .filter(|&file_id| db.eqwalizer_level(file_id) == EqwalizerLevel::StrictNoDynamic)
+        .collect();
+    let mut diagnostics = db.typecheck(project_id, file_ids);
+    if let EqwalizerDiagnostics::Diagnostics { errors, .. } = &mut diagnostics {
+        let module_index = db.module_index(project_id);
+        for file_id in strict {
+            let dynamic_types = dynamic_type_diagnostics(db, file_id);
+            if let Some(module) = module_index.module_for_file(file_id) {
+                if !dynamic_types.is_empty() {
+                    errors
+                        .entry(module.as_str().to_string())
+                        .or_default()
+                        .extend(dynamic_types);
+                }
+            }
+        }
+    }
+    Arc::new(diagnostics)
+}
+
+/// The uses of `dynamic()` in the specs and types of the file, which
+/// applications at the `strict-no-dynamic` level may not contain.
+fn dynamic_type_diagnostics(
+    db: &dyn EqwalizerDatabase,
+    file_id: FileId,
+) -> Vec<EqwalizerDiagnostic> {
+    db.parse(file_id)
+        .tree()
+        .syntax()
+        .descendants()
+        .filter_map(ast::Call::cast)
+        .filter(is_dynamic_type)
+        .map(|call| EqwalizerDiagnostic {
+            range: call.syntax().text_range(),
+            message: "dynamic() is not allowed in an application at the strict-no-dynamic level"
+                .to_string(),
+            uri: format!("{BASE_URL}/get-started/configure-project/elp-toml#eqwalizer"),
+            code: "dynamic_type".to_string(),
+            expression: None,
+            explanation: None,
+            diagnostic: None,
+        })
+        .collect()
+}
+
+/// A call to `dynamic()` or `eqwalizer:dynamic()` in a type.
+fn is_dynamic_type(call: &ast::Call) -> bool {
+    let is_dynamic = match call.expr() {
+        Some(ast::Expr::ExprMax(ast::ExprMax::Atom(atom))) => atom.syntax().text() == "dynamic",
+        Some(ast::Expr::Remote(remote)) => {
+            remote
+                .module()
+                .and_then(|module| module.module())
+                .map_or(false, |module| module.syntax().text() == "eqwalizer")
+                && remote
+                    .fun()
+                    .map_or(false, |fun| fun.syntax().text() == "dynamic")
+        }
+        _ => false,
+    };
+    is_dynamic
+        && call
+            .args()
+            .map_or(false, |args| args.args().next().is_none())
+        && call.syntax().ancestors().any(|node| {
+            matches!(
+                node.kind(),
+                SyntaxKind::SPEC
+                    | SyntaxKind::CALLBACK
+                    | SyntaxKind::TYPE_ALIAS
+                    | SyntaxKind::OPAQUE
+                    | SyntaxKind::FIELD_TYPE
+            )
+        })
 }
 
 fn eqwalizer_stats(
@@ -166,6 +248,15 @@ fn types_for_file(db: &dyn EqwalizerDatabase, file_id: FileId) -> Option<Arc<Vec
     None
 }
 
+/// The type eqWAlizer found for the expression or pattern spanning exactly
+/// `range`, among the `types` of a file.
+pub fn type_of_range(types: &[(Pos, Type)], range: TextRange) -> Option<&Type> {
+    types.iter().find_map(|(pos, ty)| match pos {
+        Pos::TextRange(r) if TextRange::from(r.clone()) == range => Some(ty),
+        _ => None,
+    })
+}
+
 fn is_eqwalizer_enabled(
     db: &dyn EqwalizerDatabase,
     file_id: FileId,
@@ -188,16 +279,36 @@ fn is_eqwalizer_enabled(
     };
     let project_id = app_data.project_id;
     let project = db.project_data(project_id);
+    if project.standalone {
+        // Without the project there are no specs of the modules the file calls.
+        return false;
+    }
     let eqwalizer_config = &project.eqwalizer_config;
+    let level = eqwalizer_config.level(app_data.name.as_str());
+    if level == Some(EqwalizerLevel::Off) {
+        return false;
+    }
     let module_index = db.module_index(project_id);
     let is_src = module_index.file_source_for_file(file_id) == Some(FileSource::Src);
     let app_or_global_opt_in =
-        eqwalizer_config.enable_all || db.has_eqwalizer_app_marker(source_root);
+        level.is_some() || eqwalizer_config.enable_all || db.has_eqwalizer_app_marker(source_root);
     let opt_in = (app_or_global_opt_in && is_src) || db.has_eqwalizer_module_marker(file_id);
     let ignored = db.has_eqwalizer_ignore_marker(file_id);
     opt_in && !ignored
 }
 
+/// The level the application of the file is checked at, as set in the
+/// `[eqwalizer.apps]` section of `.elp.toml`.
+fn eqwalizer_level(db: &dyn EqwalizerDatabase, file_id: FileId) -> EqwalizerLevel {
+    db.file_app_data(file_id)
+        .and_then(|app_data| {
+            db.project_data(app_data.project_id)
+                .eqwalizer_config
+                .level(app_data.name.as_str())
+        })
+        .unwrap_or_default()
+}
+
 fn has_eqwalizer_app_marker(db: &dyn EqwalizerDatabase, source_root_id: SourceRootId) -> bool {
     if let Some(app_data) = db.app_data(source_root_id) {
         let source_root = db.source_root(source_root_id);
@@ -547,4 +658,30 @@ mod tests {
 
         assert!(db.has_eqwalizer_ignore_marker(file_id));
     }
+
+    #[test]
+    fn test_dynamic_type_diagnostics() {
+        let (db, file_id) = RootDatabase::with_single_file(
+            r#"
+-module(test).
+-type json() :: dynamic().
+-record(state, {data :: dynamic(), count = dynamic() :: integer()}).
+-spec decode(binary()) -> dynamic().
+-spec encode(eqwalizer:dynamic()) -> binary().
+decode(Bin) -> dynamic(Bin).
+encode(Term) -> eqwalizer:dynamic(Term).
+dynamic() -> ok.
+"#,
+        );
+
+        let text = db.file_text(file_id);
+        let ranges: Vec<&str> = dynamic_type_diagnostics(&db, file_id)
+            .iter()
+            .map(|diagnostic| &text[diagnostic.range])
+            .collect();
+        assert_eq!(
+            ranges,
+            vec!["dynamic()", "dynamic()", "dynamic()", "eqwalizer:dynamic()"]
+        );
+    }
 }
@@ -136,22 +136,33 @@ fn module_ast(
     let _ = stdx::panic_context::enter(format!("\nmodule_ast: {:?}", file_id));
     let root_id = db.file_source_root(file_id);
     let root = db.source_root(root_id);
-    let path = root.path_for_file(&file_id).unwrap().as_path().unwrap();
+    let vfs_path = root.path_for_file(&file_id).unwrap();
     let metadata = db.elp_metadata(file_id);
     let app_data = if let Some(app_data) = db.file_app_data(file_id) {
         app_data
     } else {
         return Arc::new(ParseResult::error(ParseError {
-            path: path.to_path_buf().into(),
+            path: vfs_path.to_string().into(),
             location: None,
             msg: "Unknown application".to_string(),
             code: "L0003".to_string(),
         }));
     };
+    // A document without a file on disk is compiled as if it was in
+    // the directory of its app.
+    let path = match vfs_path.as_path() {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let (name, extension) = vfs_path.name_and_extension().unwrap_or(("untitled", None));
+            app_data
+                .dir
+                .join(format!("{name}.{}", extension.unwrap_or("erl")))
+        }
+    };
     Arc::new(db.load_ast(
         app_data.project_id,
         file_id,
-        path,
+        &path,
         &app_data.include_path,
         &app_data.macros,
         &app_data.parse_transforms,
@@ -23,10 +23,14 @@ use elp_base_db::FileRange;
 use elp_base_db::ProjectId;
 use elp_base_db::SourceDatabase;
 use elp_base_db::Upcast;
+use elp_eqwalizer::ast::db::ConvertedAstQuery;
+use elp_eqwalizer::ast::db::ConvertedStubQuery;
+use elp_eqwalizer::ast::db::TransitiveStubQuery;
 use elp_eqwalizer::ipc::IpcHandle;
 use elp_eqwalizer::EqwalizerConfig;
 use elp_eqwalizer::EqwalizerDiagnosticsDatabase;
 use elp_eqwalizer::Mode;
+use elp_project_model::LruConfig;
 use elp_syntax::AstNode;
 use elp_syntax::SyntaxKind;
 use elp_syntax::SyntaxToken;
@@ -45,17 +49,22 @@ use parking_lot::RwLock;
 use parking_lot::RwLockUpgradableReadGuard;
 use paths::Utf8PathBuf;
 use salsa::Database;
+use salsa::Query;
 use serde::Deserialize;
 use serde::Serialize;
 
 mod apply_change;
+pub mod call_graph;
 pub mod common_test;
 mod defs;
 pub mod diagnostic_code;
+pub mod doc_refs;
 pub mod docs;
+pub mod edoc_migration;
 pub mod eqwalizer;
 mod erl_ast;
 mod line_index;
+pub mod memory_usage;
 // @fb-only
 pub mod metadata;
 mod search;
@@ -65,6 +74,7 @@ pub mod assists;
 pub mod helpers;
 pub mod rename;
 pub mod source_change;
+pub mod symbol_index;
 
 pub use defs::ReferenceClass;
 pub use defs::ReferenceType;
@@ -81,6 +91,7 @@ pub use eqwalizer::EqwalizerDatabase;
 pub use erl_ast::ErlAstDatabase;
 pub use line_index::LineCol;
 pub use line_index::LineIndex;
+pub use memory_usage::MemoryUsage;
 pub use search::FindUsages;
 pub use search::ReferenceCategory;
 pub use search::SearchScope;
@@ -111,7 +122,8 @@ pub trait EqwalizerProgressReporter: Send + Sync + RefUnwindSafe {
     elp_eqwalizer::EqwalizerDiagnosticsDatabaseStorage,
     erl_ast::ErlAstDatabaseStorage,
     hir::db::InternDatabaseStorage,
-    hir::db::DefDatabaseStorage
+    hir::db::DefDatabaseStorage,
+    symbol_index::SymbolIndexDatabaseStorage
 )]
 
 pub struct RootDatabase {
@@ -120,7 +132,45 @@ pub struct RootDatabase {
     eqwalizer: Eqwalizer,
     eqwalizer_progress_reporter: EqwalizerProgressReporterBox,
     ipc_handles: Arc<AssertUnwindSafe<RwLock<FxHashMap<String, Arc<Mutex<IpcHandle>>>>>>,
+    lru_capacities: LruCapacities,
 }
+
+/// Number of def maps kept in memory before the least recently used
+/// ones are evicted. Evicted def maps are recomputed on demand, so this
+/// mostly affects unopened dependency modules.
+pub const DEFAULT_LRU_CAPACITY: usize = 2048;
+
+/// Number of entries kept by the caches of expensive queries, before the
+/// least recently used ones are evicted. A capacity of 0 leaves the cache
+/// unbounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LruCapacities {
+    pub parse: usize,
+    pub def_map: usize,
+    pub eqwalizer_ast: usize,
+}
+
+impl Default for LruCapacities {
+    fn default() -> Self {
+        LruCapacities::new(LruConfig::default(), 0)
+    }
+}
+
+impl LruCapacities {
+    /// The capacities set in `config`. Def maps are otherwise bounded by
+    /// the number of `files` of the projects, while syntax trees and
+    /// eqWAlizer ASTs, which are costly to recompute, are not bounded.
+    pub fn new(config: LruConfig, files: usize) -> LruCapacities {
+        LruCapacities {
+            parse: config.parse.unwrap_or(0),
+            def_map: config
+                .def_map
+                .unwrap_or(files.clamp(DEFAULT_LRU_CAPACITY, 4 * DEFAULT_LRU_CAPACITY)),
+            eqwalizer_ast: config.eqwalizer_ast.unwrap_or(0),
+        }
+    }
+}
+
 impl Default for RootDatabase {
     fn default() -> Self {
         let mut db = RootDatabase {
@@ -129,8 +179,11 @@ impl Default for RootDatabase {
             eqwalizer: Eqwalizer::default(),
             eqwalizer_progress_reporter: EqwalizerProgressReporterBox::default(),
             ipc_handles: Arc::default(),
+            lru_capacities: LruCapacities::default(),
         };
         db.set_eqwalizer_config(Arc::new(EqwalizerConfig::default()));
+        db.set_stale_generated_files(Arc::default());
+        db.update_lru_capacities(LruCapacities::default());
         db
     }
 }
@@ -165,7 +218,14 @@ impl fmt::Debug for RootDatabase {
     }
 }
 
-impl salsa::Database for RootDatabase {}
+impl salsa::Database for RootDatabase {
+    fn salsa_event(&self, event: salsa::Event) {
+        // Recorded on the span of the request running the query
+        if let salsa::EventKind::WillExecute { database_key } = event.kind {
+            tracing::debug!(target: "salsa", query = ?database_key.debug(self), "execute");
+        }
+    }
+}
 
 impl salsa::ParallelDatabase for RootDatabase {
     fn snapshot(&self) -> salsa::Snapshot<RootDatabase> {
@@ -175,6 +235,7 @@ impl salsa::ParallelDatabase for RootDatabase {
             eqwalizer: self.eqwalizer.clone(),
             eqwalizer_progress_reporter: self.eqwalizer_progress_reporter.clone(),
             ipc_handles: self.ipc_handles.clone(),
+            lru_capacities: self.lru_capacities,
         })
     }
 }
@@ -186,6 +247,41 @@ impl RootDatabase {
             .synthetic_write(salsa::Durability::LOW);
     }
 
+    /// Bound the number of syntax trees, def maps and eqWAlizer ASTs
+    /// held in memory.
+    pub fn update_lru_capacities(&mut self, capacities: LruCapacities) {
+        self.lru_capacities = capacities;
+        elp_base_db::ParseQuery
+            .in_db_mut(self)
+            .set_lru_capacity(capacities.parse);
+        hir::db::DefMapQuery
+            .in_db_mut(self)
+            .set_lru_capacity(capacities.def_map);
+        hir::db::LocalDefMapQuery
+            .in_db_mut(self)
+            .set_lru_capacity(capacities.def_map);
+        erl_ast::ModuleAstQuery
+            .in_db_mut(self)
+            .set_lru_capacity(capacities.eqwalizer_ast);
+        ConvertedAstQuery
+            .in_db_mut(self)
+            .set_lru_capacity(capacities.eqwalizer_ast);
+        ConvertedStubQuery
+            .in_db_mut(self)
+            .set_lru_capacity(capacities.eqwalizer_ast);
+        TransitiveStubQuery
+            .in_db_mut(self)
+            .set_lru_capacity(capacities.eqwalizer_ast);
+    }
+
+    pub fn lru_capacities(&self) -> LruCapacities {
+        self.lru_capacities
+    }
+
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage::collect(self)
+    }
+
     pub fn clear_erlang_services(&mut self) {
         self.erlang_services.write().clear();
     }
@@ -346,6 +442,7 @@ pub enum SymbolKind {
     Define,
     Variable,
     Callback,
+    Behaviour,
 }
 
 // ---------------------------------------------------------------------
@@ -0,0 +1,193 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Introspection of the salsa tables holding the bulk of the per-module
+//! state, used to check that LRU eviction keeps large projects in bounds.
+
+use std::fmt;
+
+use elp_base_db::salsa::debug::DebugQueryTable;
+use elp_base_db::salsa::debug::TableEntry;
+use elp_base_db::salsa::Query;
+use elp_base_db::ModuleIndexQuery;
+use elp_base_db::SourceRootModulesQuery;
+use hir::db::DefMapQuery;
+use hir::db::FileFormListQuery;
+use hir::db::LocalDefMapQuery;
+
+use crate::LruCapacities;
+use crate::RootDatabase;
+
+/// Number of entries in a single query table, and how many of them
+/// still have their value in memory (as opposed to having been evicted).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct QueryMemoryUsage {
+    pub entries: usize,
+    pub retained: usize,
+}
+
+impl<K, V> FromIterator<TableEntry<K, V>> for QueryMemoryUsage {
+    fn from_iter<T: IntoIterator<Item = TableEntry<K, V>>>(iter: T) -> Self {
+        let mut res = QueryMemoryUsage::default();
+        for entry in iter {
+            res.entries += 1;
+            if entry.value.is_some() {
+                res.retained += 1;
+            }
+        }
+        res
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Total number of modules known to the module indexes of all projects.
+    pub modules: usize,
+    pub module_indexes: QueryMemoryUsage,
+    /// The per source root shards the module indexes are built from
+    pub module_index_shards: QueryMemoryUsage,
+    pub form_lists: QueryMemoryUsage,
+    pub local_def_maps: QueryMemoryUsage,
+    pub def_maps: QueryMemoryUsage,
+    pub lru_capacities: LruCapacities,
+}
+
+impl MemoryUsage {
+    pub fn collect(db: &RootDatabase) -> MemoryUsage {
+        let _p = tracing::info_span!("MemoryUsage::collect").entered();
+        let module_indexes = ModuleIndexQuery.in_db(db).entries::<Vec<_>>();
+        let modules = module_indexes
+            .iter()
+            .filter_map(|entry| entry.value.as_ref())
+            .map(|index| index.len_own())
+            .sum();
+        MemoryUsage {
+            modules,
+            module_indexes: module_indexes.into_iter().collect(),
+            module_index_shards: SourceRootModulesQuery.in_db(db).entries(),
+            form_lists: FileFormListQuery.in_db(db).entries(),
+            local_def_maps: LocalDefMapQuery.in_db(db).entries(),
+            def_maps: DefMapQuery.in_db(db).entries(),
+            lru_capacities: db.lru_capacities(),
+        }
+    }
+}
+
+impl fmt::Display for MemoryUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "modules: {}", self.modules)?;
+        let lru = self.lru_capacities;
+        let capacity = |capacity: usize| match capacity {
+            0 => "unbounded".to_string(),
+            capacity => capacity.to_string(),
+        };
+        writeln!(
+            f,
+            "LRU capacities: parse {}, def_map {}, eqwalizer_ast {}",
+            capacity(lru.parse),
+            capacity(lru.def_map),
+            capacity(lru.eqwalizer_ast)
+        )?;
+        let tables = [
+            ("module_index", self.module_indexes),
+            ("source_root_modules", self.module_index_shards),
+            ("file_form_list", self.form_lists),
+            ("local_def_map", self.local_def_maps),
+            ("def_map", self.def_maps),
+        ];
+        for (name, usage) in tables {
+            writeln!(
+                f,
+                "{name}: {} entries, {} retained",
+                usage.entries, usage.retained
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use elp_base_db::SourceDatabase;
+    use hir::db::DefDatabase;
+
+    use super::MemoryUsage;
+    use crate::LruCapacities;
+    use crate::RootDatabase;
+
+    #[test]
+    fn def_maps_are_counted() {
+        let fixture = r#"
+//- /src/one.erl
+-module(one).
+//- /src/two.erl
+-module(two).
+"#;
+        let (db, files, _) = RootDatabase::with_many_files(fixture);
+        for file_id in files {
+            db.def_map(file_id);
+        }
+        let usage = MemoryUsage::collect(&db);
+        assert_eq!(usage.def_maps.entries, 2);
+        assert_eq!(usage.def_maps.retained, 2);
+        assert_eq!(usage.lru_capacities.def_map, crate::DEFAULT_LRU_CAPACITY);
+        // Syntax trees and eqWAlizer ASTs are only bounded when configured
+        assert_eq!(usage.lru_capacities.parse, 0);
+        assert_eq!(usage.lru_capacities.eqwalizer_ast, 0);
+    }
+
+    #[test]
+    fn def_maps_are_evicted() {
+        let fixture = r#"
+//- /src/one.erl
+-module(one).
+//- /src/two.erl
+-module(two).
+//- /src/three.erl
+-module(three).
+//- /src/four.erl
+-module(four).
+//- /src/five.erl
+-module(five).
+"#;
+        let (mut db, files, _) = RootDatabase::with_many_files(fixture);
+        db.update_lru_capacities(LruCapacities {
+            def_map: 3,
+            ..db.lru_capacities()
+        });
+        for file_id in files {
+            db.def_map(file_id);
+        }
+        let usage = MemoryUsage::collect(&db);
+        assert_eq!(usage.def_maps.entries, 5);
+        assert_eq!(usage.def_maps.retained, 3);
+    }
+
+    #[test]
+    fn module_index_is_sharded_by_source_root() {
+        let fixture = r#"
+//- /app_a/src/one.erl app:app_a
+-module(one).
+//- /app_a/src/two.erl app:app_a
+-module(two).
+//- /app_b/src/three.erl app:app_b
+-module(three).
+"#;
+        let (db, files, _) = RootDatabase::with_many_files(fixture);
+        let project_id = db.file_app_data(files[0]).unwrap().project_id;
+        db.module_index(project_id);
+        let shard = |file_id| db.source_root_modules(db.file_source_root(file_id)).len();
+        assert_eq!(shard(files[0]), 2);
+        assert_eq!(shard(files[2]), 1);
+        let usage = MemoryUsage::collect(&db);
+        assert_eq!(usage.modules, 3);
+        assert!(usage.module_index_shards.entries >= 2);
+    }
+}
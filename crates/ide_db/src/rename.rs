@@ -14,16 +14,20 @@
 use std::fmt;
 use std::iter::once;
 
+use elp_base_db::AnchoredPathBuf;
 use elp_base_db::FileId;
 use elp_base_db::FileRange;
 use elp_syntax::ast;
 use elp_syntax::ast::in_erlang_module;
 use elp_syntax::AstNode;
+use fxhash::FxHashSet;
 use hir::InFile;
+use hir::MacroName;
 use hir::Semantic;
 use text_edit::TextEdit;
 
 use crate::search::NameLike;
+use crate::source_change::FileSystemEdit;
 use crate::source_change::SourceChange;
 use crate::SymbolDefinition;
 
@@ -77,6 +81,83 @@ pub fn is_valid_function_name(new_name: &String) -> bool {
     }
 }
 
+// Delegate checking name validity to the parser
+pub fn is_valid_record_name(new_name: &String) -> bool {
+    let parse = ast::SourceFile::parse_text(format!("-record({}, {{}}).", new_name).as_str());
+    match parse.tree().forms().next() {
+        Some(ast::Form::RecordDecl(record)) => match record.name() {
+            Some(ast::Name::Atom(atom)) => atom.syntax().text().to_string() == *new_name,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// Delegate checking name validity to the parser
+pub fn is_valid_record_field_name(new_name: &String) -> bool {
+    let parse = ast::SourceFile::parse_text(format!("-record(r, {{{}}}).", new_name).as_str());
+    match parse.tree().forms().next() {
+        Some(ast::Form::RecordDecl(record)) => {
+            let mut fields = record.fields();
+            match (fields.next().and_then(|field| field.name()), fields.next()) {
+                (Some(ast::Name::Atom(atom)), None) => {
+                    atom.syntax().text().to_string() == *new_name
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+// Delegate checking name validity to the parser. The module being
+// renamed after its file, quoted atoms are not allowed.
+pub fn is_valid_module_name(new_name: &String) -> bool {
+    let parse = ast::SourceFile::parse_text(format!("-module({}).", new_name).as_str());
+    match parse.tree().forms().next() {
+        Some(ast::Form::ModuleAttribute(attr)) => match attr.name() {
+            Some(ast::Name::Atom(atom)) => {
+                atom.syntax().text().to_string() == *new_name && !new_name.starts_with('\'')
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// Delegate checking name validity to the parser
+pub fn is_valid_atom_name(new_name: &String) -> bool {
+    let parse = ast::SourceFile::parse_text(format!("foo() -> {}.", new_name).as_str());
+    parse
+        .tree()
+        .syntax()
+        .descendants()
+        .find_map(ast::ClauseBody::cast)
+        .map_or(false, |body| {
+            let mut exprs = body.exprs();
+            match (exprs.next(), exprs.next()) {
+                (Some(ast::Expr::ExprMax(ast::ExprMax::Atom(atom))), None) => {
+                    atom.syntax().text().to_string() == *new_name
+                }
+                _ => false,
+            }
+        })
+}
+
+// Delegate checking name validity to the parser
+pub fn is_valid_macro_name(new_name: &String) -> bool {
+    let parse = ast::SourceFile::parse_text(format!("-define({}, ok).", new_name).as_str());
+    match parse.tree().forms().next() {
+        Some(ast::Form::PreprocessorDirective(ast::PreprocessorDirective::PpDefine(define))) => {
+            match define.lhs().and_then(|lhs| lhs.name()) {
+                Some(name) => name.syntax().text().to_string() == *new_name,
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SafetyChecks {
     Yes,
@@ -92,8 +173,18 @@ impl SymbolDefinition {
         safety_check: SafetyChecks,
     ) -> RenameResult<SourceChange> {
         match self.clone() {
-            SymbolDefinition::Module(_) => {
-                rename_error!("Cannot rename module")
+            SymbolDefinition::Module(module) => {
+                if safety_check == SafetyChecks::Yes && !is_valid_module_name(new_name) {
+                    rename_error!("Invalid new module name: '{}'", new_name);
+                }
+
+                if safety_check == SafetyChecks::Yes
+                    && !is_safe_module(sema, module.file.file_id, new_name)
+                {
+                    rename_error!("Module '{}' already exists", new_name);
+                } else {
+                    self.rename_reference(sema, new_name, parens_needed_in_context, safety_check)
+                }
             }
             SymbolDefinition::Function(fun) => {
                 if safety_check == SafetyChecks::Yes && !is_valid_function_name(new_name) {
@@ -109,11 +200,38 @@ impl SymbolDefinition {
                     self.rename_reference(sema, new_name, parens_needed_in_context, safety_check)
                 }
             }
-            SymbolDefinition::Record(_) => {
-                rename_error!("Cannot rename record")
+            SymbolDefinition::Record(record) => {
+                if safety_check == SafetyChecks::Yes && !is_valid_record_name(new_name) {
+                    rename_error!("Invalid new record name: '{}'", new_name);
+                }
+
+                if safety_check == SafetyChecks::Yes
+                    && !is_safe_record(sema, record.file.file_id, new_name)
+                {
+                    rename_error!("Record '{}' already in scope", new_name);
+                } else {
+                    self.rename_reference(sema, new_name, parens_needed_in_context, safety_check)
+                }
             }
-            SymbolDefinition::RecordField(_) => {
-                rename_error!("Cannot rename record field")
+            SymbolDefinition::RecordField(field) => {
+                if safety_check == SafetyChecks::Yes && !is_valid_record_field_name(new_name) {
+                    rename_error!("Invalid new record field name: '{}'", new_name);
+                }
+
+                if safety_check == SafetyChecks::Yes
+                    && field
+                        .record
+                        .field_names(sema.db)
+                        .any(|name| name.as_str() == new_name)
+                {
+                    rename_error!(
+                        "Field '{}' already defined in record '{}'",
+                        new_name,
+                        field.record.record.name.as_str()
+                    );
+                } else {
+                    self.rename_reference(sema, new_name, parens_needed_in_context, safety_check)
+                }
             }
             SymbolDefinition::Type(_) => {
                 rename_error!("Cannot rename type")
@@ -122,7 +240,11 @@ impl SymbolDefinition {
                 rename_error!("Cannot rename callback")
             }
             SymbolDefinition::Define(_) => {
-                rename_error!("Cannot rename define")
+                if safety_check == SafetyChecks::Yes && !is_valid_macro_name(new_name) {
+                    rename_error!("Invalid new macro name: '{}'", new_name);
+                }
+
+                self.rename_reference(sema, new_name, parens_needed_in_context, safety_check)
             }
             SymbolDefinition::Header(_) => {
                 rename_error!("Cannot rename header")
@@ -137,6 +259,22 @@ impl SymbolDefinition {
         }
     }
 
+    /// Fails when definitions of this kind can't be renamed, as checked
+    /// by `prepareRename` before asking for the new name.
+    pub fn check_renameable(&self) -> RenameResult<()> {
+        match self {
+            SymbolDefinition::Type(_) => rename_error!("Cannot rename type"),
+            SymbolDefinition::Callback(_) => rename_error!("Cannot rename callback"),
+            SymbolDefinition::Header(_) => rename_error!("Cannot rename header"),
+            SymbolDefinition::Module(_)
+            | SymbolDefinition::Function(_)
+            | SymbolDefinition::Record(_)
+            | SymbolDefinition::RecordField(_)
+            | SymbolDefinition::Define(_)
+            | SymbolDefinition::Var(_) => Ok(()),
+        }
+    }
+
     /// Textual range of the identifier which will change when
     /// renaming this `Definition`. Note that some definitions, like
     /// builtin types, can't be renamed.
@@ -164,6 +302,35 @@ impl SymbolDefinition {
         let file_id = self.file().file_id;
         let mut source_change = SourceChange::default();
         match self {
+            SymbolDefinition::Module(module) => {
+                let usages = self.clone().usages(sema).all();
+                let source = sema.parse(file_id).value;
+                let def_usages: Vec<_> = module
+                    .module_attribute(sema.db.upcast())
+                    .and_then(|attr| attr.form_id.get(&source).name())
+                    .map(NameLike::Name)
+                    .into_iter()
+                    .collect();
+                let usages: Vec<_> = usages
+                    .iter()
+                    .chain(once((file_id, &def_usages[..])))
+                    .collect();
+                source_edit_from_usages(
+                    &mut source_change,
+                    usages,
+                    new_name,
+                    parens_needed_in_context,
+                );
+                // The module name must match the file name
+                source_change.push_file_system_edit(FileSystemEdit::MoveFile {
+                    src: file_id,
+                    dst: AnchoredPathBuf {
+                        anchor: file_id,
+                        path: format!("{}.erl", new_name),
+                    },
+                });
+                Ok(source_change)
+            }
             SymbolDefinition::Function(function) => {
                 let usages = self.clone().usages(sema).all();
                 let mut def_usages = Vec::default();
@@ -211,6 +378,122 @@ impl SymbolDefinition {
                     new_name,
                     parens_needed_in_context,
                 );
+                source_change.extend(self.doc_usages(sema).into_iter().map(|(file_id, ranges)| {
+                    let mut edit = TextEdit::builder();
+                    for range in ranges {
+                        edit.replace(range, new_name.clone());
+                    }
+                    (file_id, edit.finish())
+                }));
+                Ok(source_change)
+            }
+            SymbolDefinition::Record(record) => {
+                let usages = self.clone().usages(sema).all();
+                if safety_check == SafetyChecks::Yes {
+                    // The record is safe in its defining file, check
+                    // the files including it now.
+                    let mut problems = usages
+                        .iter()
+                        .filter(|(file_id, _refs)| !is_safe_record(sema, *file_id, new_name));
+                    // Report the first one only, an existence proof of problems
+                    if let Some((file_id, _)) = problems.next() {
+                        if let Some(module_name) = sema.module_name(file_id) {
+                            rename_error!(
+                                "Record '{}' already in scope in module '{}'",
+                                new_name,
+                                module_name.as_str()
+                            );
+                        } else {
+                            rename_error!("Record '{}' already in scope", new_name);
+                        }
+                    }
+                }
+
+                let def_usages: Vec<_> = record
+                    .source(sema.db.upcast())
+                    .name()
+                    .map(NameLike::Name)
+                    .into_iter()
+                    .collect();
+                let usages: Vec<_> = usages
+                    .iter()
+                    .chain(once((file_id, &def_usages[..])))
+                    .collect();
+                source_edit_from_usages(
+                    &mut source_change,
+                    usages,
+                    new_name,
+                    parens_needed_in_context,
+                );
+                Ok(source_change)
+            }
+            SymbolDefinition::RecordField(field) => {
+                let usages = self.clone().usages(sema).all();
+                let def_usages: Vec<_> = field
+                    .source(sema.db.upcast())
+                    .name()
+                    .map(NameLike::Name)
+                    .into_iter()
+                    .collect();
+                let usages: Vec<_> = usages
+                    .iter()
+                    .chain(once((file_id, &def_usages[..])))
+                    .collect();
+                source_edit_from_usages(
+                    &mut source_change,
+                    usages,
+                    new_name,
+                    parens_needed_in_context,
+                );
+                Ok(source_change)
+            }
+            SymbolDefinition::Define(define) => {
+                let usages = self.clone().usages(sema).all();
+                if safety_check == SafetyChecks::Yes {
+                    let name = &define.define.name;
+                    let files: FxHashSet<FileId> = once(file_id)
+                        .chain(usages.iter().map(|(file_id, _refs)| file_id))
+                        .collect();
+                    // Only one of the definitions would be renamed, e.g.
+                    // when defined in both branches of an `-ifdef`
+                    let definitions: usize = files
+                        .iter()
+                        .map(|file_id| count_defines(sema, *file_id, name))
+                        .sum();
+                    if definitions > 1 {
+                        rename_error!(
+                            "Cannot rename macro '{}', it is defined more than once",
+                            name
+                        );
+                    }
+                    if !files
+                        .iter()
+                        .all(|file_id| is_safe_macro(sema, *file_id, new_name, name.arity()))
+                    {
+                        rename_error!("Macro '{}' already in scope", new_name);
+                    }
+                }
+
+                let def_usages: Vec<_> = define
+                    .source(sema.db.upcast())
+                    .lhs()
+                    .and_then(|lhs| lhs.name())
+                    .map(|name| match name {
+                        ast::MacroName::Atom(atom) => NameLike::Name(ast::Name::Atom(atom)),
+                        ast::MacroName::Var(var) => NameLike::Name(ast::Name::Var(var)),
+                    })
+                    .into_iter()
+                    .collect();
+                let usages: Vec<_> = usages
+                    .iter()
+                    .chain(once((file_id, &def_usages[..])))
+                    .collect();
+                source_edit_from_usages(
+                    &mut source_change,
+                    usages,
+                    new_name,
+                    parens_needed_in_context,
+                );
                 Ok(source_change)
             }
             SymbolDefinition::Var(var) => {
@@ -386,3 +669,46 @@ pub fn is_safe_function(sema: &Semantic, file_id: FileId, new_name: &String, ari
 
     scope_ok && !in_erlang_module(new_name, arity as usize)
 }
+
+/// Check that no record with the new name is in scope already, either
+/// defined in the file or in one of its includes.
+fn is_safe_record(sema: &Semantic, file_id: FileId, new_name: &String) -> bool {
+    sema.db
+        .def_map(file_id)
+        .get_records()
+        .keys()
+        .all(|name| name.as_str() != new_name)
+}
+
+/// Check that no module with the new name exists in the project.
+fn is_safe_module(sema: &Semantic, file_id: FileId, new_name: &String) -> bool {
+    match sema.db.file_app_data(file_id) {
+        Some(app_data) => sema
+            .db
+            .module_index(app_data.project_id)
+            .file_for_module(new_name.as_str())
+            .is_none(),
+        None => true,
+    }
+}
+
+/// The number of `-define` directives in the file for the macro.
+fn count_defines(sema: &Semantic, file_id: FileId, name: &MacroName) -> usize {
+    let form_list = sema.db.file_form_list(file_id);
+    form_list
+        .pp_stack()
+        .iter()
+        .filter_map(|(_idx, directive)| directive.as_define())
+        .filter(|&define| &form_list[define].name == name)
+        .count()
+}
+
+/// Check that no macro with the new name and the same arity is in
+/// scope already.
+fn is_safe_macro(sema: &Semantic, file_id: FileId, new_name: &String, arity: Option<u32>) -> bool {
+    sema.db
+        .def_map(file_id)
+        .get_macros()
+        .keys()
+        .all(|name| !(name.name().as_str() == new_name && name.arity() == arity))
+}
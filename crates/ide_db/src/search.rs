@@ -137,7 +137,7 @@ impl IntoIterator for SearchScope {
 }
 
 impl SymbolDefinition {
-    fn search_scope(&self, sema: &Semantic) -> SearchScope {
+    pub(crate) fn search_scope(&self, sema: &Semantic) -> SearchScope {
         if let SymbolDefinition::Var(var) = self {
             let range = var
                 .source(sema.db.upcast())
@@ -166,6 +166,7 @@ impl SymbolDefinition {
                     SearchScope::files(includers.into_iter())
                 }
                 FileKind::Escript => SearchScope::single_file(self.file().file_id, None),
+                FileKind::Grammar => SearchScope::single_file(self.file().file_id, None),
                 FileKind::Other => SearchScope::single_file(self.file().file_id, None),
                 FileKind::OutsideProjectModel => {
                     SearchScope::single_file(self.file().file_id, None)
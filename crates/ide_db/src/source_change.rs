@@ -226,6 +226,14 @@ impl SourceChangeBuilder {
         self.edit.replace(range, replace_with.into())
     }
 
+    pub fn create_file(&mut self, dst: AnchoredPathBuf, content: impl Into<String>) {
+        let file_system_edit = FileSystemEdit::CreateFile {
+            dst,
+            initial_contents: content.into(),
+        };
+        self.source_change.push_file_system_edit(file_system_edit);
+    }
+
     pub fn edits_count(&self) -> usize {
         self.edits_count
     }
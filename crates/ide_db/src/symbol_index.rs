@@ -0,0 +1,409 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! The index of the symbols defined in the project files, and in the
+//! files of the OTP it uses, for the workspace symbol search. The symbols
+//! of each file are computed once and kept in salsa until the file, or one
+//! of its includes, changes. The index lives for the session of the
+//! server, it is not persisted to disk.
+
+use std::sync::Arc;
+
+use elp_base_db::salsa;
+use elp_base_db::FileId;
+use elp_base_db::FileKind;
+use elp_base_db::ProjectId;
+use elp_base_db::SourceDatabase;
+use elp_base_db::Upcast;
+use elp_syntax::ast;
+use elp_syntax::AstNode;
+use elp_syntax::SmolStr;
+use elp_syntax::TextRange;
+use hir::db::DefDatabase;
+use hir::File;
+use hir::Module;
+
+use crate::SymbolKind;
+
+#[salsa::query_group(SymbolIndexDatabaseStorage)]
+pub trait SymbolIndexDatabase: DefDatabase + SourceDatabase + Upcast<dyn DefDatabase> {
+    fn file_symbols(&self, file_id: FileId) -> Arc<Vec<FileSymbol>>;
+}
+
+/// A module, behaviour, function, type, record or macro defined in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSymbol {
+    /// The name shown to the user, `foo/1` for functions and types
+    pub name: SmolStr,
+    pub kind: SymbolKind,
+    /// The module defining the symbol, or the name of the header file
+    pub container_name: Option<SmolStr>,
+    pub file_id: FileId,
+    pub full_range: TextRange,
+    pub focus_range: Option<TextRange>,
+}
+
+impl FileSymbol {
+    /// The name without the arity, the one matched against the query.
+    fn search_name(&self) -> &str {
+        match self.kind {
+            SymbolKind::Function | SymbolKind::Type => self
+                .name
+                .rsplit_once('/')
+                .map_or(self.name.as_str(), |(name, _arity)| name),
+            _ => self.name.as_str(),
+        }
+    }
+}
+
+fn file_symbols(db: &dyn SymbolIndexDatabase, file_id: FileId) -> Arc<Vec<FileSymbol>> {
+    let _p = tracing::info_span!("file_symbols", ?file_id).entered();
+    let kind = db.file_kind(file_id);
+    if !matches!(
+        kind,
+        FileKind::SrcModule | FileKind::TestModule | FileKind::Header | FileKind::Escript
+    ) {
+        return Arc::default();
+    }
+    let def_db: &dyn DefDatabase = db.upcast();
+    let file = File { file_id };
+    let def_map = db.def_map(file_id);
+    let source = file.source(db.upcast());
+    let mut res = Vec::new();
+
+    let module = Module { file };
+    let container_name = match module.module_attribute(def_db) {
+        Some(attr) => {
+            let name = attr.name.raw();
+            // A module defining callbacks is a behaviour
+            let is_behaviour = def_map
+                .get_callbacks()
+                .values()
+                .any(|callback| callback.file.file_id == file_id);
+            res.push(FileSymbol {
+                name: name.clone(),
+                kind: if is_behaviour {
+                    SymbolKind::Behaviour
+                } else {
+                    SymbolKind::Module
+                },
+                container_name: None,
+                file_id,
+                full_range: source.syntax().text_range(),
+                focus_range: Some(attr.form_id.get(&source).syntax().text_range()),
+            });
+            name
+        }
+        None => file.name(db.upcast()),
+    };
+    let mut push = |name: SmolStr, kind, full_range, focus_range| {
+        res.push(FileSymbol {
+            name,
+            kind,
+            container_name: Some(container_name.clone()),
+            file_id,
+            full_range,
+            focus_range,
+        })
+    };
+
+    for (name, def) in def_map.get_functions() {
+        if def.file.file_id != file_id {
+            continue;
+        }
+        let focus_range = def
+            .source(db.upcast())
+            .iter()
+            .find_map(|fun_decl| fun_decl.name())
+            .map(|name| name.syntax().text_range());
+        push(
+            SmolStr::new(name.to_string()),
+            SymbolKind::Function,
+            def.range(db.upcast()).unwrap_or_default(),
+            focus_range,
+        );
+    }
+    for (name, def) in def_map.get_types() {
+        if def.file.file_id != file_id {
+            continue;
+        }
+        let type_source = def.source(db.upcast());
+        push(
+            SmolStr::new(name.to_string()),
+            SymbolKind::Type,
+            type_source.syntax().text_range(),
+            type_source
+                .type_name()
+                .map(|name| name.syntax().text_range()),
+        );
+    }
+    for (name, def) in def_map.get_records() {
+        if def.file.file_id != file_id {
+            continue;
+        }
+        let record_source = def.source(db.upcast());
+        push(
+            name.raw(),
+            SymbolKind::Record,
+            record_source.syntax().text_range(),
+            record_source.name().map(|name| name.syntax().text_range()),
+        );
+    }
+    for def in def_map.get_macros().values() {
+        if def.file.file_id != file_id {
+            continue;
+        }
+        let define_source = def.source(db.upcast());
+        push(
+            def.define.name.name().raw(),
+            SymbolKind::Define,
+            define_source.syntax().text_range(),
+            define_source.lhs().map(|lhs| lhs.syntax().text_range()),
+        );
+    }
+    res.sort_by_key(|symbol| symbol.full_range.start());
+    Arc::new(res)
+}
+
+/// A workspace symbol query. The kind of the symbols searched can be
+/// restricted with a prefix: `#rec` for records, `?MACRO` for macros,
+/// `t:` for types, `f:` for functions, `m:` for modules and `b:` for
+/// behaviours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    text: String,
+    kind: Option<SymbolKind>,
+}
+
+impl Query {
+    pub fn new(query: &str) -> Query {
+        let query = query.trim();
+        let prefixes = [
+            ("#", SymbolKind::Record),
+            ("?", SymbolKind::Define),
+            ("t:", SymbolKind::Type),
+            ("f:", SymbolKind::Function),
+            ("m:", SymbolKind::Module),
+            ("b:", SymbolKind::Behaviour),
+        ];
+        for (prefix, kind) in prefixes {
+            if let Some(text) = query.strip_prefix(prefix) {
+                return Query {
+                    text: text.to_lowercase(),
+                    kind: Some(kind),
+                };
+            }
+        }
+        Query {
+            text: query.to_lowercase(),
+            kind: None,
+        }
+    }
+
+    /// How well `symbol` matches, lower being better: an exact match, a
+    /// prefix, a substring, or the query characters appearing in order.
+    fn score(&self, symbol: &FileSymbol) -> Option<u8> {
+        if let Some(kind) = self.kind {
+            if kind != symbol.kind {
+                return None;
+            }
+        }
+        let name = symbol.search_name().to_lowercase();
+        if name == self.text {
+            Some(0)
+        } else if name.starts_with(&self.text) {
+            Some(1)
+        } else if name.contains(&self.text) {
+            Some(2)
+        } else {
+            let mut chars = name.chars();
+            self.text
+                .chars()
+                .all(|c| chars.any(|n| n == c))
+                .then_some(3)
+        }
+    }
+}
+
+/// The symbols of the project files, and of the OTP files, matching
+/// `query`, best matches first.
+pub fn symbol_search(
+    db: &dyn SymbolIndexDatabase,
+    project_id: ProjectId,
+    query: &Query,
+    limit: usize,
+) -> Vec<FileSymbol> {
+    let _p = tracing::info_span!("symbol_search").entered();
+    let project_data = db.project_data(project_id);
+    let otp_source_roots = project_data
+        .otp_project_id
+        .filter(|&otp_project_id| otp_project_id != project_id)
+        .map(|otp_project_id| db.project_data(otp_project_id).source_roots.clone())
+        .unwrap_or_default();
+    let mut res = Vec::new();
+    for &source_root_id in project_data.source_roots.iter().chain(&otp_source_roots) {
+        let source_root = db.source_root(source_root_id);
+        for file_id in source_root.iter() {
+            for symbol in db.file_symbols(file_id).iter() {
+                if let Some(score) = query.score(symbol) {
+                    res.push((score, symbol.clone()));
+                }
+            }
+        }
+    }
+    res.sort_by(|(score_a, a), (score_b, b)| (score_a, &a.name).cmp(&(score_b, &b.name)));
+    res.into_iter()
+        .take(limit)
+        .map(|(_score, symbol)| symbol)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_base_db::fixture::WithFixture;
+    use elp_base_db::SourceDatabase;
+    use expect_test::expect;
+    use expect_test::Expect;
+
+    use super::symbol_search;
+    use super::Query;
+    use crate::RootDatabase;
+
+    fn check(fixture: &str, query: &str, expect: Expect) {
+        let (db, fixture) = RootDatabase::with_fixture(fixture);
+        let project_id = db.file_project_id(fixture.files[0]).unwrap();
+        let actual = symbol_search(&db, project_id, &Query::new(query), 128)
+            .into_iter()
+            .map(|symbol| {
+                format!(
+                    "{:?} {} {:?}\n",
+                    symbol.kind, symbol.name, symbol.container_name
+                )
+            })
+            .collect::<String>();
+        expect.assert_eq(&actual);
+    }
+
+    const FIXTURE: &str = r#"
+//- /src/server.erl
+-module(server).
+-include("server.hrl").
+-callback handle(term()) -> ok.
+-type state() :: #state{}.
+-define(TIMEOUT, 5000).
+start_server() -> ok.
+stop(State) -> State.
+
+//- /src/server.hrl
+-record(state, {pid}).
+-define(SERVER, server).
+
+//- /src/client.erl
+-module(client).
+-behaviour(server).
+-record(request, {id}).
+handle(_) -> ok.
+start() -> server:start_server().
+"#;
+
+    #[test]
+    fn fuzzy_matching() {
+        check(
+            FIXTURE,
+            "start",
+            expect![[r#"
+                Function start/0 Some("client")
+                Function start_server/0 Some("server")
+            "#]],
+        );
+        check(
+            FIXTURE,
+            "stsrv",
+            expect![[r#"
+                Function start_server/0 Some("server")
+            "#]],
+        );
+    }
+
+    #[test]
+    fn all_kinds() {
+        check(
+            FIXTURE,
+            "s",
+            expect![[r#"
+                Define SERVER Some("server.hrl")
+                Behaviour server None
+                Function start/0 Some("client")
+                Function start_server/0 Some("server")
+                Record state Some("server.hrl")
+                Type state/0 Some("server")
+                Function stop/1 Some("server")
+                Record request Some("client")
+            "#]],
+        );
+    }
+
+    #[test]
+    fn kind_prefixes() {
+        check(
+            FIXTURE,
+            "#s",
+            expect![[r#"
+                Record state Some("server.hrl")
+                Record request Some("client")
+            "#]],
+        );
+        check(
+            FIXTURE,
+            "?server",
+            expect![[r#"
+                Define SERVER Some("server.hrl")
+            "#]],
+        );
+        check(
+            FIXTURE,
+            "t:state",
+            expect![[r#"
+                Type state/0 Some("server")
+            "#]],
+        );
+        check(
+            FIXTURE,
+            "b:",
+            expect![[r#"
+                Behaviour server None
+            "#]],
+        );
+        check(
+            FIXTURE,
+            "m:",
+            expect![[r#"
+                Module client None
+            "#]],
+        );
+    }
+
+    #[test]
+    fn otp_symbols() {
+        check(
+            r#"
+//- /src/main.erl
+-module(main).
+main() -> lists:reverse([]).
+//- /opt/lib/stdlib-3.17/src/lists.erl otp_app:/opt/lib/stdlib-3.17
+-module(lists).
+reverse(L) -> L.
+"#,
+            "f:reverse",
+            expect![[r#"
+                Function reverse/1 Some("lists")
+            "#]],
+        );
+    }
+}
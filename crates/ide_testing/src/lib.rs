@@ -0,0 +1,168 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Annotation-based tests for diagnostics and their fixes, the ones ELP
+//! uses for its own lints, for projects writing ad-hoc lints or embedding
+//! ELP.
+//!
+//! A test is a fixture, describing one or more files, as documented in
+//! [`elp_project_model::test_fixture`]. The expected diagnostics are
+//! written as annotations below the line they apply to: a comment with
+//! carets under the range of the diagnostic, followed by its severity and
+//! message. A diagnostic coming with fixes is prefixed with `💡`.
+//!
+//! ```ignore
+//! use elp_ide::diagnostics::DiagnosticsConfig;
+//! use elp_ide_testing::check_diagnostics_with_config;
+//!
+//! // `no_io_format` being an ad-hoc lint, a function implementing
+//! // `AdhocSemanticDiagnostics`
+//! check_diagnostics_with_config(
+//!     DiagnosticsConfig::default(),
+//!     &vec![&no_io_format],
+//!     r#"
+//! //- /src/main.erl
+//! -module(main).
+//! -export([main/0]).
+//! main() -> io:format("hello").
+//! %%        ^^^^^^^^^ warning: Do not print to stdout
+//! "#,
+//! );
+//! ```
+//!
+//! [`check_fix`] applies the fix of the diagnostic found at the cursor,
+//! marked with `~`, and compares the result with an [`Expect`].
+//!
+//! The checks are the ones of `elp_ide::testing`, which ELP's own tests
+//! use, with the defaults of an ad-hoc lint.
+
+use elp_ide::diagnostics::AdhocSemanticDiagnostics;
+use elp_ide::diagnostics::DiagnosticsConfig;
+pub use elp_ide::testing::check_nth_fix;
+pub use elp_ide::testing::diagnostics_for;
+pub use elp_ide::testing::diagnostics_to_annotations;
+use elp_ide::testing::IncludeCodeActionAssists;
+pub use elp_project_model::test_fixture::DiagnosticsEnabled;
+pub use expect_test::expect;
+pub use expect_test::Expect;
+
+/// Checks that the diagnostics of each file of the fixture match its
+/// annotations, with the experimental diagnostics enabled.
+#[track_caller]
+pub fn check_diagnostics(fixture: &str) {
+    let config = DiagnosticsConfig::default().set_experimental(true);
+    check_diagnostics_with_config(config, &vec![], fixture)
+}
+
+/// Like [`check_diagnostics`], with the given configuration and ad-hoc
+/// diagnostics.
+#[track_caller]
+pub fn check_diagnostics_with_config(
+    config: DiagnosticsConfig,
+    adhoc_semantic_diagnostics: &Vec<&dyn AdhocSemanticDiagnostics>,
+    fixture: &str,
+) {
+    elp_ide::testing::check_diagnostics_with_config_and_ad_hoc(
+        config,
+        adhoc_semantic_diagnostics,
+        fixture,
+    )
+}
+
+/// Checks that applying the first fix of the last diagnostic of the file
+/// with the cursor gives `fixture_after`. The fix must apply to a range
+/// containing the cursor.
+#[track_caller]
+pub fn check_fix(fixture_before: &str, fixture_after: Expect) {
+    let config = DiagnosticsConfig::default().set_experimental(true);
+    check_fix_with_config(config, &vec![], fixture_before, fixture_after)
+}
+
+/// Like [`check_fix`], with the given configuration and ad-hoc
+/// diagnostics.
+#[track_caller]
+pub fn check_fix_with_config(
+    config: DiagnosticsConfig,
+    adhoc_semantic_diagnostics: &Vec<&dyn AdhocSemanticDiagnostics>,
+    fixture_before: &str,
+    fixture_after: Expect,
+) {
+    check_nth_fix(
+        0,
+        fixture_before,
+        fixture_after,
+        config,
+        adhoc_semantic_diagnostics,
+        IncludeCodeActionAssists::No,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use elp_ide::diagnostics::Diagnostic;
+    use elp_ide::diagnostics::DiagnosticCode;
+    use elp_ide::diagnostics::DiagnosticsConfig;
+    use elp_ide::diagnostics::Severity;
+    use elp_ide::elp_ide_db::elp_base_db::FileId;
+    use elp_ide::elp_ide_db::elp_base_db::FileKind;
+    use elp_ide::elp_ide_db::elp_base_db::SourceDatabase;
+    use elp_ide::TextRange;
+    use elp_ide::TextSize;
+    use hir::Semantic;
+
+    use super::check_diagnostics_with_config;
+    use super::check_fix;
+    use super::expect;
+
+    fn no_io_format(diags: &mut Vec<Diagnostic>, sema: &Semantic, file_id: FileId, _: FileKind) {
+        let text = sema.db.file_text(file_id);
+        for (offset, call) in text.match_indices("io:format") {
+            let range = TextRange::at(
+                TextSize::from(offset as u32),
+                TextSize::from(call.len() as u32),
+            );
+            let mut diag = Diagnostic::new(
+                DiagnosticCode::AdHoc("no_io_format".to_string()),
+                "Do not print to stdout",
+                range,
+            );
+            diag.severity = Severity::Warning;
+            diags.push(diag);
+        }
+    }
+
+    #[test]
+    fn adhoc_diagnostics() {
+        check_diagnostics_with_config(
+            DiagnosticsConfig::default().disable(DiagnosticCode::UndefinedFunction),
+            &vec![&no_io_format],
+            r#"
+//- /src/main.erl
+-module(main).
+-export([main/0]).
+main() -> io:format("hello").
+%%        ^^^^^^^^^ warning: Do not print to stdout
+"#,
+        );
+    }
+
+    #[test]
+    fn native_fix() {
+        check_fix(
+            r#"
+                -module(main).
+                do_something(U~nused, Used) -> Used.
+                "#,
+            expect![[r#"
+                -module(main).
+                do_something(_Unused, Used) -> Used.
+                "#]],
+        );
+    }
+}
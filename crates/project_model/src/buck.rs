@@ -187,6 +187,10 @@ pub struct BuckTarget {
     includes: Vec<String>,
     #[serde(default)]
     labels: FxHashSet<String>,
+    /// The applications the target depends on at runtime, as targets
+    /// or OTP application names
+    #[serde(default)]
+    applications: Vec<String>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -201,6 +205,7 @@ pub struct Target {
     pub target_type: TargetType,
     /// true if there are .hrl files in the src dir
     pub private_header: bool,
+    pub applications: Vec<AppName>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
@@ -268,6 +273,11 @@ pub fn load_buck_targets(
                 };
                 (src_files, include_files, target_type, private_header, ebin)
             };
+        let applications = target
+            .applications
+            .iter()
+            .map(|application| buck_application_name(application))
+            .collect();
         let target = Target {
             name: name.clone(),
             app_name: target.name,
@@ -277,11 +287,22 @@ pub fn load_buck_targets(
             ebin,
             target_type,
             private_header,
+            applications,
         };
         target_info.targets.insert(name, target);
     }
     Ok(target_info)
 }
+
+/// The name of an application given as a target, such as
+/// `cell//path/to:app`, or as the name of an OTP application.
+fn buck_application_name(application: &str) -> AppName {
+    let name = application
+        .rsplit_once(':')
+        .map_or(application, |(_, name)| name);
+    AppName(name.to_string())
+}
+
 fn compute_target_type(name: &TargetFullName, target: &BuckTarget) -> TargetType {
     if name.contains("//third-party") {
         TargetType::ThirdParty
@@ -655,6 +676,7 @@ struct ProjectAppDataAcc {
     pub macros: Vec<Term>,
     pub app_type: Option<AppType>,
     pub include_path: FxHashSet<AbsPathBuf>,
+    pub applications: Vec<AppName>,
 }
 
 impl ProjectAppDataAcc {
@@ -670,6 +692,7 @@ impl ProjectAppDataAcc {
             app_type: None,
             include_path: Default::default(),
             abs_src_dirs: Default::default(),
+            applications: vec![],
         }
     }
 
@@ -784,6 +807,22 @@ impl ProjectAppDataAcc {
         }
     }
 
+    fn add_applications(&mut self, target: &Target) {
+        // Suites and their helpers may depend on more applications than
+        // the code they test, which must not widen what the app may call.
+        if matches!(
+            target.target_type,
+            TargetType::ErlangTest | TargetType::ErlangTestUtils
+        ) {
+            return;
+        }
+        for application in &target.applications {
+            if !self.applications.contains(application) {
+                self.applications.push(application.clone());
+            }
+        }
+    }
+
     fn add_global_includes(&mut self, includes: Vec<AbsPathBuf>) {
         self.include_path.extend(includes);
     }
@@ -826,6 +865,7 @@ impl ProjectAppDataAcc {
         self.add_src(target);
         self.add_include(target);
         self.add_include_path(target);
+        self.add_applications(target);
     }
 }
 
@@ -865,6 +905,7 @@ impl From<ProjectAppDataAcc> for ProjectAppData {
                     len1.cmp(&len2)
                 })
                 .collect(),
+            applications: acc.applications,
         }
     }
 }
@@ -894,6 +935,7 @@ mod tests {
             srcs: vec!["cell//app_a/src/app.erl".to_string()],
             includes: vec![],
             labels: FxHashSet::default(),
+            applications: vec![],
         };
 
         let actual = find_app_root(root, &target_name, &target);
@@ -916,6 +958,7 @@ mod tests {
             srcs: vec![],
             includes: vec!["cell//app_a/include/app.hrl".to_string()],
             labels: FxHashSet::default(),
+            applications: vec![],
         };
 
         let actual = find_app_root(root, &target_name, &target);
@@ -938,6 +981,7 @@ mod tests {
             srcs: vec![],
             includes: vec![],
             labels: FxHashSet::default(),
+            applications: vec![],
         };
 
         let actual = find_app_root(root, &target_name, &target);
@@ -964,6 +1008,7 @@ mod tests {
             ],
             includes: vec![],
             labels: FxHashSet::default(),
+            applications: vec![],
         };
 
         let actual = find_app_root(root, &target_name, &target);
@@ -987,6 +1032,7 @@ mod tests {
             srcs: vec!["cell//app_a/app.erl".to_string()],
             includes: vec!["cell//app_a/app.hrl".to_string()],
             labels: FxHashSet::default(),
+            applications: vec![],
         };
 
         let actual = find_app_root(root, &target_name, &target);
@@ -1010,6 +1056,7 @@ mod tests {
             srcs: vec!["cell//app_a/sub/app.erl".to_string()],
             includes: vec!["cell//app_a/sub/app.hrl".to_string()],
             labels: FxHashSet::default(),
+            applications: vec![],
         };
 
         let actual = find_app_root(root, &target_name, &target);
@@ -9,6 +9,7 @@
 
 use std::borrow::Borrow;
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
@@ -30,6 +31,7 @@ use buck::BuckConfig;
 use buck::BuckQueryConfig;
 use elp_log::timeit;
 use fxhash::FxHashMap;
+use fxhash::FxHashSet;
 use glob::glob;
 use itertools::Either;
 use json::JsonProjectAppData;
@@ -51,6 +53,7 @@ use crate::otp::Otp;
 use crate::rebar::Profile;
 use crate::rebar::RebarConfig;
 use crate::rebar::RebarProject;
+use crate::term::Term;
 
 pub mod buck;
 pub mod eqwalizer_support;
@@ -59,6 +62,7 @@ pub mod no_manifest;
 pub mod otp;
 pub mod rebar;
 pub mod temp_dir;
+pub mod term;
 pub mod test_fixture;
 
 pub const ELP_CONFIG_FILE: &str = ".elp.toml";
@@ -239,6 +243,22 @@ impl ProjectManifest {
         }
     }
 
+    /// The manifest of a lone file, analyzed on its own without looking
+    /// for the project it may belong to.
+    pub fn single_file(path: &AbsPath) -> ProjectManifest {
+        let root_path = path
+            .parent()
+            .unwrap_or_else(|| panic!("Error getting parent from {:?}", &path))
+            .to_path_buf();
+        let name = AppName(
+            root_path
+                .file_name()
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| "generic".to_string()),
+        );
+        ProjectManifest::NoManifest(no_manifest::NoManifestConfig::single_file(root_path, name))
+    }
+
     pub fn discover_no_manifest(
         path: &AbsPath,
         include_parents: IncludeParentDirs,
@@ -342,6 +362,9 @@ pub enum ProjectBuildData {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StaticProject {
     pub config_path: AbsPathBuf,
+    /// Made up for files outside of any rebar3, buck2 or JSON project,
+    /// which only know about OTP and the files next to them.
+    pub standalone: bool,
 }
 
 // Sample config:
@@ -378,6 +401,13 @@ pub struct ElpConfig {
     pub eqwalizer: EqwalizerConfig,
     #[serde(default)]
     pub rebar: ElpRebarConfig,
+    /// Calls holding routing tables, in addition to `cowboy_router:compile/1`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routers: Vec<RouterConfig>,
+    #[serde(default, skip_serializing_if = "AssistsConfig::is_empty")]
+    pub assists: AssistsConfig,
+    #[serde(default, skip_serializing_if = "LruConfig::is_empty")]
+    pub lru: LruConfig,
 }
 
 #[derive(
@@ -396,6 +426,43 @@ pub struct EqwalizerConfig {
     pub enable_all: bool,
     #[serde(default = "eqwalizer_max_tasks_default")]
     pub max_tasks: usize,
+    /// The level of enforcement of each application listed, which opts
+    /// it in even when `enable_all` is false
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub apps: BTreeMap<String, EqwalizerLevel>,
+}
+
+impl EqwalizerConfig {
+    pub fn level(&self, app: &str) -> Option<EqwalizerLevel> {
+        self.apps.get(app).copied()
+    }
+}
+
+/// How strictly eqWAlizer is enforced on an application.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Ord,
+    PartialOrd,
+    Deserialize,
+    Serialize
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum EqwalizerLevel {
+    /// Not type checked
+    Off,
+    /// Type errors are reported as warnings
+    Warnings,
+    /// Type errors are reported as errors
+    #[default]
+    Errors,
+    /// As `Errors`, and uses of `dynamic()` are reported too
+    StrictNoDynamic,
 }
 
 fn eqwalizer_enable_all_default() -> bool {
@@ -411,6 +478,7 @@ impl Default for EqwalizerConfig {
         Self {
             enable_all: eqwalizer_enable_all_default(),
             max_tasks: eqwalizer_max_tasks_default(),
+            apps: BTreeMap::new(),
         }
     }
 }
@@ -461,6 +529,115 @@ impl Default for ElpRebarConfig {
     }
 }
 
+/// A function taking a routing table as one of its arguments, e.g.
+///
+/// ```toml
+/// [[routers]]
+/// module = "my_router"
+/// function = "compile"
+/// arity = 2
+/// arg = 1
+/// ```
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Ord,
+    PartialOrd,
+    Deserialize,
+    Serialize
+)]
+pub struct RouterConfig {
+    pub module: String,
+    pub function: String,
+    pub arity: u32,
+    /// Zero-based index of the argument holding the routes
+    #[serde(default)]
+    pub arg: usize,
+}
+
+/// Assists turned off for the project, by id, e.g.
+///
+/// ```toml
+/// [assists]
+/// disabled = ["add_edoc", "flip_sep"]
+/// ```
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Ord,
+    PartialOrd,
+    Deserialize,
+    Serialize,
+    Default
+)]
+pub struct AssistsConfig {
+    #[serde(default)]
+    pub disabled: Vec<String>,
+}
+
+impl AssistsConfig {
+    fn is_empty(&self) -> bool {
+        self.disabled.is_empty()
+    }
+}
+
+/// Number of entries kept in memory by the caches of expensive queries,
+/// before the least recently used ones are evicted, e.g.
+///
+/// ```toml
+/// [lru]
+/// parse = 512
+/// def_map = 4096
+/// eqwalizer_ast = 256
+/// ```
+///
+/// Capacities left out are chosen from the size of the project.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Ord,
+    PartialOrd,
+    Deserialize,
+    Serialize,
+    Default
+)]
+pub struct LruConfig {
+    /// Syntax trees
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parse: Option<usize>,
+    /// Def maps, local and with the definitions of included headers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub def_map: Option<usize>,
+    /// ASTs and stubs of eqWAlizer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eqwalizer_ast: Option<usize>,
+}
+
+impl LruConfig {
+    fn is_empty(&self) -> bool {
+        *self == LruConfig::default()
+    }
+
+    /// The capacities set in `self`, completed by the ones in `other`.
+    pub fn or(self, other: LruConfig) -> LruConfig {
+        LruConfig {
+            parse: self.parse.or(other.parse),
+            def_map: self.def_map.or(other.def_map),
+            eqwalizer_ast: self.eqwalizer_ast.or(other.eqwalizer_ast),
+        }
+    }
+}
+
 impl ElpConfig {
     pub fn new(
         config_path: AbsPathBuf,
@@ -468,6 +645,9 @@ impl ElpConfig {
         build_info: Option<BuildInfoConfig>,
         eqwalizer: EqwalizerConfig,
         rebar: ElpRebarConfig,
+        routers: Vec<RouterConfig>,
+        assists: AssistsConfig,
+        lru: LruConfig,
     ) -> Self {
         Self {
             config_path: Some(config_path),
@@ -475,6 +655,9 @@ impl ElpConfig {
             build_info,
             eqwalizer,
             rebar,
+            routers,
+            assists,
+            lru,
         }
     }
     pub fn try_parse(path: &AbsPath) -> Result<ElpConfig> {
@@ -622,6 +805,9 @@ pub struct Project {
     pub project_build_data: ProjectBuildData,
     pub project_apps: Vec<ProjectAppData>,
     pub eqwalizer_config: EqwalizerConfig,
+    pub routers: Vec<RouterConfig>,
+    pub assists: AssistsConfig,
+    pub lru: LruConfig,
 }
 
 #[derive(Clone, Debug)]
@@ -656,6 +842,9 @@ impl Project {
             project_build_data: ProjectBuildData::Otp,
             project_apps,
             eqwalizer_config: EqwalizerConfig::default(),
+            routers: Vec::default(),
+            assists: AssistsConfig::default(),
+            lru: LruConfig::default(),
         }
     }
 
@@ -665,6 +854,9 @@ impl Project {
             project_build_data: ProjectBuildData::Rebar(Default::default()),
             project_apps: Vec::default(),
             eqwalizer_config: EqwalizerConfig::default(),
+            routers: Vec::default(),
+            assists: AssistsConfig::default(),
+            lru: LruConfig::default(),
         }
     }
 
@@ -691,6 +883,18 @@ impl Project {
             .filter(|app| app.app_type == AppType::Dep)
     }
 
+    /// Whether the project was made up for files outside of any project,
+    /// see `StaticProject::standalone`.
+    pub fn is_standalone(&self) -> bool {
+        matches!(
+            &self.project_build_data,
+            ProjectBuildData::Static(StaticProject {
+                standalone: true,
+                ..
+            })
+        )
+    }
+
     pub fn otp_apps(&self) -> impl Iterator<Item = &ProjectAppData> + '_ {
         self.project_apps
             .iter()
@@ -807,6 +1011,9 @@ pub struct ProjectAppData {
     //list of directories required by module to compile
     //usually includes all dependencies include paths and otp
     pub include_path: Vec<AbsPathBuf>,
+    /// The applications this one depends on at runtime, as listed in
+    /// its `.app.src` or BUCK target
+    pub applications: Vec<AppName>,
 }
 
 impl ProjectAppData {
@@ -828,6 +1035,7 @@ impl ProjectAppData {
             app_type: AppType::App,
             include_path: vec![],
             abs_src_dirs: src_dirs,
+            applications: vec![],
         }
     }
 
@@ -851,6 +1059,41 @@ impl ProjectAppData {
             app_type: AppType::Otp,
             include_path: vec![include, src, parent],
             abs_src_dirs: vec![abs_src_dir],
+            applications: vec![],
+        }
+    }
+
+    /// Resolve the symlinks in the paths of the application, so that a
+    /// file reached through a symlinked directory (e.g. a buck or bazel
+    /// output directory) gets a single `FileId`.
+    pub fn canonicalize(&mut self) {
+        let canonicalize_all = |paths: &mut Vec<AbsPathBuf>| {
+            paths
+                .iter_mut()
+                .for_each(|path| *path = canonicalize_path(path))
+        };
+        self.dir = canonicalize_path(&self.dir);
+        self.ebin = self.ebin.as_deref().map(canonicalize_path);
+        canonicalize_all(&mut self.include_dirs);
+        canonicalize_all(&mut self.abs_src_dirs);
+        canonicalize_all(&mut self.include_path);
+    }
+
+    /// Read the applications this one depends on from its `.app.src`
+    /// file, or else from the `.app` file in its ebin directory.
+    pub fn load_applications(&mut self) {
+        let app_file = format!("{}.app", self.name);
+        let app_src_file = format!("{app_file}.src");
+        let candidates = self
+            .abs_src_dirs
+            .iter()
+            .map(|dir| dir.join(&app_src_file))
+            .chain(self.ebin.iter().map(|ebin| ebin.join(&app_file)));
+        for path in candidates {
+            if let Ok(text) = fs::read_to_string(&path) {
+                self.applications = app_file_applications(&text);
+                return;
+            }
         }
     }
 
@@ -885,9 +1128,54 @@ impl ProjectAppData {
         self.macros.dedup();
         self.parse_transforms.extend(other.parse_transforms);
         self.parse_transforms.dedup();
+        // Keep the order the applications are first listed in
+        for application in other.applications {
+            if !self.applications.contains(&application) {
+                self.applications.push(application);
+            }
+        }
     }
 }
 
+/// The applications and included applications listed in the text of an
+/// application resource file.
+fn app_file_applications(text: &str) -> Vec<AppName> {
+    let terms = match term::consult(text) {
+        Ok(terms) => terms,
+        Err(err) => {
+            log::warn!("Could not read application resource file: {err}");
+            return vec![];
+        }
+    };
+    let mut res = Vec::new();
+    for term in &terms {
+        let Term::Tuple(elements) = term else {
+            continue;
+        };
+        let [Term::Atom(tag), _, Term::List(properties)] = elements.as_slice() else {
+            continue;
+        };
+        if tag != "application" {
+            continue;
+        }
+        for property in properties {
+            let Term::Tuple(property) = property else {
+                continue;
+            };
+            let [Term::Atom(key), Term::List(apps)] = property.as_slice() else {
+                continue;
+            };
+            if key == "applications" || key == "included_applications" {
+                res.extend(apps.iter().filter_map(|app| match app {
+                    Term::Atom(name) => Some(AppName(name.clone())),
+                    _ => None,
+                }));
+            }
+        }
+    }
+    res
+}
+
 impl Project {
     pub fn compile_deps(&self) -> Result<()> {
         let _timer = timeit!("compile deps");
@@ -908,7 +1196,7 @@ impl Project {
 
     pub fn load(
         manifest: &ProjectManifest,
-        eqwalizer_config: EqwalizerConfig,
+        elp_config: &ElpConfig,
         query_config: &BuckQueryConfig,
     ) -> Result<Project> {
         let (project_build_info, mut project_apps, otp_root) = match manifest {
@@ -948,7 +1236,10 @@ impl Project {
                 let otp_root = Otp::find_otp()?;
                 let config_path = config.config_path().to_path_buf();
                 let (mut apps, deps) = json::gen_app_data(config, AbsPath::assert(&otp_root));
-                let project = StaticProject { config_path };
+                let project = StaticProject {
+                    config_path,
+                    standalone: false,
+                };
                 apps.extend(deps);
                 (ProjectBuildData::Static(project), apps, otp_root)
             }
@@ -959,7 +1250,10 @@ impl Project {
                 let mut apps = config.to_project_app_data(abs_otp_root);
                 let eqwalizer_support_app =
                     eqwalizer_support::eqwalizer_suppport_data(abs_otp_root);
-                let project = StaticProject { config_path };
+                let project = StaticProject {
+                    config_path,
+                    standalone: config.single_file,
+                };
                 apps.push(eqwalizer_support_app);
                 (ProjectBuildData::Static(project), apps, otp_root)
             }
@@ -967,11 +1261,30 @@ impl Project {
 
         let (otp, otp_project_apps) = Otp::discover(otp_root);
         project_apps.extend(otp_project_apps);
+        for app in &mut project_apps {
+            app.canonicalize();
+            if app.applications.is_empty() {
+                app.load_applications();
+            }
+        }
+        // Two apps reached through different symlinks are the same app,
+        // keeping both would give every module of it twice.
+        let mut seen_dirs = FxHashSet::default();
+        project_apps.retain(|app| {
+            let first = seen_dirs.insert(app.dir.clone());
+            if !first {
+                log::info!("Ignoring app {} seen twice at {}", app.name, app.dir);
+            }
+            first
+        });
         Ok(Project {
             otp,
             project_build_data: project_build_info,
             project_apps,
-            eqwalizer_config,
+            eqwalizer_config: elp_config.eqwalizer.clone(),
+            routers: elp_config.routers.clone(),
+            assists: elp_config.assists.clone(),
+            lru: elp_config.lru,
         })
     }
 
@@ -1016,6 +1329,36 @@ pub fn to_abs_path_buf(path: &PathBuf) -> Result<AbsPathBuf> {
     Ok(AbsPathBuf::assert(path))
 }
 
+/// The path under which the file system knows `path`, with the symlinks
+/// resolved and, on case-insensitive file systems, the case of the
+/// entries on disk. A path that does not exist yet is canonicalized
+/// through its parent, so that a file being created gets the same path
+/// as once it is on disk. A path that cannot be resolved, or that is
+/// not valid UTF8 once resolved, is returned unchanged.
+///
+/// Windows is left alone, `fs::canonicalize` gives verbatim paths there
+/// which do not compare equal to the ones the client sends.
+pub fn canonicalize_path(path: &AbsPath) -> AbsPathBuf {
+    if cfg!(windows) {
+        return path.to_path_buf();
+    }
+    let canonical = match fs::canonicalize(path) {
+        Ok(canonical) => Some(canonical),
+        Err(_) => path
+            .parent()
+            .zip(path.file_name())
+            .and_then(|(parent, name)| {
+                fs::canonicalize(parent)
+                    .ok()
+                    .map(|parent| parent.join(name))
+            }),
+    };
+    canonical
+        .and_then(|canonical| Utf8PathBuf::from_path_buf(canonical).ok())
+        .map(AbsPathBuf::assert)
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
@@ -1069,10 +1412,20 @@ mod tests {
                         eqwalizer: EqwalizerConfig {
                             enable_all: true,
                             max_tasks: 4,
+                            apps: {},
                         },
                         rebar: ElpRebarConfig {
                             profile: "test",
                         },
+                        routers: [],
+                        assists: AssistsConfig {
+                            disabled: [],
+                        },
+                        lru: LruConfig {
+                            parse: None,
+                            def_map: None,
+                            eqwalizer_ast: None,
+                        },
                     },
                     Rebar(
                         RebarConfig {
@@ -1132,10 +1485,20 @@ mod tests {
                         eqwalizer: EqwalizerConfig {
                             enable_all: true,
                             max_tasks: 4,
+                            apps: {},
                         },
                         rebar: ElpRebarConfig {
                             profile: "test",
                         },
+                        routers: [],
+                        assists: AssistsConfig {
+                            disabled: [],
+                        },
+                        lru: LruConfig {
+                            parse: None,
+                            def_map: None,
+                            eqwalizer_ast: None,
+                        },
                     },
                     Json(
                         JsonConfig {
@@ -1240,10 +1603,20 @@ mod tests {
                         eqwalizer: EqwalizerConfig {
                             enable_all: true,
                             max_tasks: 4,
+                            apps: {},
                         },
                         rebar: ElpRebarConfig {
                             profile: "test",
                         },
+                        routers: [],
+                        assists: AssistsConfig {
+                            disabled: [],
+                        },
+                        lru: LruConfig {
+                            parse: None,
+                            def_map: None,
+                            eqwalizer_ast: None,
+                        },
                     },
                     JsonConfig {
                         apps: [
@@ -1393,10 +1766,20 @@ mod tests {
                         eqwalizer: EqwalizerConfig {
                             enable_all: true,
                             max_tasks: 4,
+                            apps: {},
                         },
                         rebar: ElpRebarConfig {
                             profile: "test",
                         },
+                        routers: [],
+                        assists: AssistsConfig {
+                            disabled: [],
+                        },
+                        lru: LruConfig {
+                            parse: None,
+                            def_map: None,
+                            eqwalizer_ast: None,
+                        },
                     },
                     NoManifest(
                         NoManifestConfig {
@@ -1422,6 +1805,7 @@ mod tests {
                             extra_src_dirs: [
                                 "test",
                             ],
+                            single_file: false,
                         },
                     ),
                 ),
@@ -1460,10 +1844,20 @@ mod tests {
                             eqwalizer: EqwalizerConfig {
                                 enable_all: true,
                                 max_tasks: 4,
+                                apps: {},
                             },
                             rebar: ElpRebarConfig {
                                 profile: "test",
                             },
+                            routers: [],
+                            assists: AssistsConfig {
+                                disabled: [],
+                            },
+                            lru: LruConfig {
+                                parse: None,
+                                def_map: None,
+                                eqwalizer_ast: None,
+                            },
                         },
                         NoManifest(
                             NoManifestConfig {
@@ -1483,6 +1877,7 @@ mod tests {
                                 ],
                                 include_dirs: [],
                                 extra_src_dirs: [],
+                                single_file: false,
                             },
                         ),
                     ),
@@ -1652,10 +2047,20 @@ mod tests {
                     eqwalizer: EqwalizerConfig {
                         enable_all: true,
                         max_tasks: 4,
+                        apps: {},
                     },
                     rebar: ElpRebarConfig {
                         profile: "other",
                     },
+                    routers: [],
+                    assists: AssistsConfig {
+                        disabled: [],
+                    },
+                    lru: LruConfig {
+                        parse: None,
+                        def_map: None,
+                        eqwalizer_ast: None,
+                    },
                 }
             "#]]
             .assert_eq(&debug_normalise_temp_dir(dir, &elp_config));
@@ -1664,6 +2069,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_toml_routers() {
+        let config: ElpConfig = toml::from_str(
+            r#"
+            [[routers]]
+            module = "my_router"
+            function = "compile"
+            arity = 2
+            arg = 1
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.routers,
+            vec![RouterConfig {
+                module: "my_router".to_string(),
+                function: "compile".to_string(),
+                arity: 2,
+                arg: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_toml_assists() {
+        let config: ElpConfig = toml::from_str(
+            r#"
+            [assists]
+            disabled = ["add_edoc", "flip_sep"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.assists.disabled,
+            vec!["add_edoc".to_string(), "flip_sep".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_toml_lru() {
+        let config: ElpConfig = toml::from_str(
+            r#"
+            [lru]
+            parse = 512
+            eqwalizer_ast = 64
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.lru,
+            LruConfig {
+                parse: Some(512),
+                def_map: None,
+                eqwalizer_ast: Some(64),
+            }
+        );
+    }
+
     #[test]
     fn serde_serialize_elp_toml() {
         let result = toml::to_string::<ElpConfig>(&ElpConfig {
@@ -1692,10 +2155,14 @@ mod tests {
             eqwalizer: EqwalizerConfig {
                 enable_all: true,
                 max_tasks: 34,
+                apps: BTreeMap::from([("legacy".to_string(), EqwalizerLevel::Warnings)]),
             },
             rebar: ElpRebarConfig {
                 profile: "my_profile".to_string(),
             },
+            routers: vec![],
+            assists: AssistsConfig::default(),
+            lru: LruConfig::default(),
         })
         .unwrap();
         expect![[r#"
@@ -1714,6 +2181,9 @@ mod tests {
             enable_all = true
             max_tasks = 34
 
+            [eqwalizer.apps]
+            legacy = "warnings"
+
             [rebar]
             profile = "my_profile"
         "#]]
@@ -1739,6 +2209,10 @@ mod tests {
             enable_all = true
             max_tasks = 34
 
+            [eqwalizer.apps]
+            legacy = "warnings"
+            billing = "strict-no-dynamic"
+
             [rebar]
             profile = "my_profile"
              "#,
@@ -1782,10 +2256,23 @@ mod tests {
                 eqwalizer: EqwalizerConfig {
                     enable_all: true,
                     max_tasks: 34,
+                    apps: {
+                        "billing": StrictNoDynamic,
+                        "legacy": Warnings,
+                    },
                 },
                 rebar: ElpRebarConfig {
                     profile: "my_profile",
                 },
+                routers: [],
+                assists: AssistsConfig {
+                    disabled: [],
+                },
+                lru: LruConfig {
+                    parse: None,
+                    def_map: None,
+                    eqwalizer_ast: None,
+                },
             }
         "#]]
         .assert_debug_eq(&lints);
@@ -1809,4 +2296,90 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_app_file_applications() {
+        let text = r#"
+            {application, my_app, [
+                {description, "My app"},
+                {vsn, "1.0.0"},
+                %% {applications, [commented_out]},
+                {applications, [kernel, stdlib,
+                                'cowboy']},
+                {included_applications, [my_lib]},
+                {mod, {my_app, []}},
+                {env, [{applications, [not_a_dependency]}]}
+            ]}.
+        "#;
+        expect![[r#"
+            [
+                AppName(
+                    "kernel",
+                ),
+                AppName(
+                    "stdlib",
+                ),
+                AppName(
+                    "cowboy",
+                ),
+                AppName(
+                    "my_lib",
+                ),
+            ]
+        "#]]
+        .assert_debug_eq(&app_file_applications(text));
+    }
+
+    #[test]
+    fn test_combine_applications() {
+        let app_data = |applications: &[&str]| {
+            let mut app_data = ProjectAppData::fixture_app_data(
+                AppName("app_a".to_string()),
+                AbsPathBuf::assert("/app_a".into()),
+                vec![],
+                vec![],
+                vec![],
+            );
+            app_data.applications = applications
+                .iter()
+                .map(|name| AppName(name.to_string()))
+                .collect();
+            app_data
+        };
+        let mut combined = app_data(&["kernel", "app_b"]);
+        combined.combine(app_data(&["app_c", "kernel", "app_b"]));
+        assert_eq!(
+            vec![
+                AppName("kernel".to_string()),
+                AppName("app_b".to_string()),
+                AppName("app_c".to_string()),
+            ],
+            combined.applications
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_canonicalize_path_through_symlink() {
+        let dir = TempDir::new();
+        let real = dir.path().join("buck-out/app_a/src");
+        fs::create_dir_all(&real).unwrap();
+        fs::write(real.join("app.erl"), "-module(app).").unwrap();
+        std::os::unix::fs::symlink(dir.path().join("buck-out"), dir.path().join("out")).unwrap();
+        let dir_str = dir.path().to_string_lossy().to_string();
+        let canonical = |path: PathBuf| {
+            let path = canonicalize_path(&to_abs_path_buf(&path).unwrap());
+            format!("{:?}\n", path.as_str()).replace(&dir_str, "TMPDIR")
+        };
+
+        expect![[r#"
+            "TMPDIR/buck-out/app_a/src/app.erl"
+        "#]]
+        .assert_eq(&canonical(dir.path().join("out/app_a/src/app.erl")));
+        // Not created yet, resolved through its parent
+        expect![[r#"
+            "TMPDIR/buck-out/app_a/src/new.erl"
+        "#]]
+        .assert_eq(&canonical(dir.path().join("out/app_a/src/new.erl")));
+    }
 }
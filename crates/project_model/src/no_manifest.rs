@@ -24,6 +24,9 @@ pub struct NoManifestConfig {
     pub abs_src_dirs: Vec<AbsPathBuf>,
     pub include_dirs: Vec<AbsPathBuf>,
     pub extra_src_dirs: Vec<String>,
+    /// Only the directory of the file is part of the app, the includes
+    /// come from OTP, see `ProjectManifest::single_file`.
+    pub single_file: bool,
 }
 
 impl NoManifestConfig {
@@ -47,6 +50,19 @@ impl NoManifestConfig {
             abs_src_dirs,
             include_dirs,
             extra_src_dirs,
+            single_file: false,
+        }
+    }
+
+    pub fn single_file(root_path: AbsPathBuf, name: AppName) -> Self {
+        Self {
+            config_path: root_path.join(".static"),
+            abs_src_dirs: vec![root_path.clone()],
+            root_path,
+            name,
+            include_dirs: vec![],
+            extra_src_dirs: vec![],
+            single_file: true,
         }
     }
 
@@ -66,9 +82,10 @@ impl NoManifestConfig {
             macros: vec![],
             parse_transforms: vec![],
             include_path: vec![otp_root.to_path_buf()],
+            applications: vec![],
         };
         data.include_path.extend(data.include_dirs());
-        if let Some(path) = self.root_path.parent() {
+        if let Some(path) = self.root_path.parent().filter(|_| !self.single_file) {
             data.include_path.push(path.to_path_buf());
         }
         vec![data]
@@ -26,6 +26,9 @@ use crate::ProjectAppData;
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Otp {
     pub lib_dir: AbsPathBuf,
+    /// The full OTP version, e.g. `26.2.1`, if it can be found in the
+    /// releases of the installation
+    pub version: Option<String>,
 }
 
 lazy_static! {
@@ -127,14 +130,27 @@ impl Otp {
 
     pub fn discover(path: Utf8PathBuf) -> (Otp, Vec<ProjectAppData>) {
         let apps = Self::discover_otp_apps(&path);
+        let version = Self::read_version(&path);
         (
             Otp {
                 lib_dir: AbsPathBuf::assert(path),
+                version,
             },
             apps,
         )
     }
 
+    /// Reads the `releases/<release>/OTP_VERSION` file of the installation
+    /// the lib dir belongs to.
+    fn read_version(lib_dir: &Utf8Path) -> Option<String> {
+        let releases = lib_dir.parent()?.join("releases");
+        fs::read_dir(releases)
+            .ok()?
+            .filter_map(|entry| fs::read_to_string(entry.ok()?.path().join("OTP_VERSION")).ok())
+            .map(|version| version.trim().to_string())
+            .max()
+    }
+
     fn discover_otp_apps(path: &Utf8Path) -> Vec<ProjectAppData> {
         log::info!("Loading OTP apps from {:?}", path);
         if let Ok(entries) = fs::read_dir(path) {
@@ -139,6 +139,10 @@ impl RebarProject {
         Self { root, rebar_config }
     }
 
+    pub fn rebar3_command(&self) -> CommandProxy<'_> {
+        self.rebar_config.rebar3_command()
+    }
+
     pub fn from_rebar_build_info(
         path: impl AsRef<Path>,
         rebar_config: RebarConfig,
@@ -203,6 +207,7 @@ impl RebarProject {
                 app_type: is_dep,
                 include_path: vec![],
                 abs_src_dirs,
+                applications: vec![],
             })
         }
     }
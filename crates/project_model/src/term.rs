@@ -0,0 +1,375 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Reading the Erlang terms of a configuration file, such as an
+//! application resource file or a `sys.config`, the way `file:consult/1`
+//! does.
+//!
+//! Only what is needed to find the structure of the terms is kept:
+//! numbers are left as written, and the segments of binaries are kept
+//! without their size and type.
+
+use std::fmt;
+
+use anyhow::bail;
+use anyhow::Result;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Atom(String),
+    /// Integers, floats and characters, as written
+    Number(String),
+    String(String),
+    Binary(Vec<Term>),
+    Tuple(Vec<Term>),
+    /// The tail of an improper list is left out
+    List(Vec<Term>),
+    Map(Vec<(Term, Term)>),
+}
+
+/// The terms of `text`, each followed by a full stop.
+pub fn consult(text: &str) -> Result<Vec<Term>> {
+    let mut reader = Reader { text, pos: 0 };
+    let mut res = Vec::new();
+    while reader.peek().is_some() {
+        res.push(reader.term()?);
+        reader.expect(".")?;
+    }
+    Ok(res)
+}
+
+/// The term as Erlang source, with the simplifications made when reading it.
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Atom(name) => {
+                let mut chars = name.chars();
+                let plain = chars.next().map_or(false, |c| c.is_ascii_lowercase())
+                    && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '@');
+                if plain {
+                    write!(f, "{name}")
+                } else {
+                    write!(f, "'{}'", escape(name, '\''))
+                }
+            }
+            Term::Number(number) => write!(f, "{number}"),
+            Term::String(string) => write!(f, "\"{}\"", escape(string, '"')),
+            Term::Binary(elements) => write!(f, "<<{}>>", join(elements)),
+            Term::Tuple(elements) => write!(f, "{{{}}}", join(elements)),
+            Term::List(elements) => write!(f, "[{}]", join(elements)),
+            Term::Map(pairs) => {
+                let pairs: Vec<String> = pairs
+                    .iter()
+                    .map(|(key, value)| format!("{key} => {value}"))
+                    .collect();
+                write!(f, "#{{{}}}", pairs.join(", "))
+            }
+        }
+    }
+}
+
+fn escape(text: &str, quote: char) -> String {
+    text.chars()
+        .flat_map(|c| match c {
+            '\n' => vec!['\\', 'n'],
+            '\t' => vec!['\\', 't'],
+            '\r' => vec!['\\', 'r'],
+            '\\' => vec!['\\', '\\'],
+            c if c == quote => vec!['\\', c],
+            c => vec![c],
+        })
+        .collect()
+}
+
+fn join(terms: &[Term]) -> String {
+    terms
+        .iter()
+        .map(|term| term.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// The next character after any whitespace and comments.
+    fn peek(&mut self) -> Option<char> {
+        loop {
+            let rest = &self.text()[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with('%') {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else {
+                return trimmed.chars().next();
+            }
+        }
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        if self.peek().is_some() && self.text()[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if !self.eat(token) {
+            self.unexpected()?;
+        }
+        Ok(())
+    }
+
+    fn unexpected<T>(&mut self) -> Result<T> {
+        match self.peek() {
+            Some(c) => bail!("unexpected {c:?} at offset {}", self.pos),
+            None => bail!("unexpected end of file"),
+        }
+    }
+
+    fn term(&mut self) -> Result<Term> {
+        if self.eat("{") {
+            return Ok(Term::Tuple(self.elements("}")?));
+        }
+        if self.eat("[") {
+            let mut elements = Vec::new();
+            if !self.eat("]") {
+                loop {
+                    elements.push(self.term()?);
+                    if self.eat("|") {
+                        self.term()?;
+                        self.expect("]")?;
+                        break;
+                    }
+                    if self.eat("]") {
+                        break;
+                    }
+                    self.expect(",")?;
+                }
+            }
+            return Ok(Term::List(elements));
+        }
+        if self.eat("#{") {
+            let mut pairs = Vec::new();
+            if !self.eat("}") {
+                loop {
+                    let key = self.term()?;
+                    if !self.eat("=>") {
+                        self.expect(":=")?;
+                    }
+                    pairs.push((key, self.term()?));
+                    if self.eat("}") {
+                        break;
+                    }
+                    self.expect(",")?;
+                }
+            }
+            return Ok(Term::Map(pairs));
+        }
+        if self.eat("<<") {
+            return Ok(Term::Binary(self.elements(">>")?));
+        }
+        match self.peek() {
+            Some('\'') => Ok(Term::Atom(self.quoted('\'')?)),
+            Some('"') => {
+                let mut string = self.quoted('"')?;
+                // Adjacent strings are concatenated
+                while self.peek() == Some('"') {
+                    string.push_str(&self.quoted('"')?);
+                }
+                Ok(Term::String(string))
+            }
+            Some(c) if c.is_ascii_lowercase() => Ok(Term::Atom(self.word().to_string())),
+            Some(c) if c.is_ascii_digit() || c == '-' || c == '$' => self.number(),
+            _ => self.unexpected(),
+        }
+    }
+
+    /// Terms separated by commas, up to `close`, along with the size and
+    /// type of binary segments.
+    fn elements(&mut self, close: &str) -> Result<Vec<Term>> {
+        let mut elements = Vec::new();
+        if self.eat(close) {
+            return Ok(elements);
+        }
+        loop {
+            elements.push(self.term()?);
+            if close == ">>" {
+                if self.eat(":") {
+                    self.term()?;
+                }
+                if self.eat("/") {
+                    self.word();
+                    while self.eat("-") {
+                        self.word();
+                    }
+                }
+            }
+            if self.eat(close) {
+                return Ok(elements);
+            }
+            self.expect(",")?;
+        }
+    }
+
+    fn word(&mut self) -> &'a str {
+        let rest = &self.text()[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '@'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn number(&mut self) -> Result<Term> {
+        let start = self.pos;
+        self.eat("-");
+        if self.eat("$") {
+            let rest = &self.text()[self.pos..];
+            let mut chars = rest.chars();
+            let len = match chars.next() {
+                Some('\\') => 1 + chars.next().map_or(0, char::len_utf8),
+                Some(c) => c.len_utf8(),
+                None => return self.unexpected(),
+            };
+            self.pos += len;
+        } else {
+            let rest = &self.text()[self.pos..];
+            let mut prev = None;
+            let len = rest
+                .char_indices()
+                .find(|&(idx, c)| {
+                    let next_is_digit =
+                        rest[idx + c.len_utf8()..].starts_with(|next: char| next.is_ascii_digit());
+                    let part_of_number = c.is_ascii_alphanumeric()
+                        || c == '_'
+                        || c == '#'
+                        || (c == '.' && next_is_digit)
+                        || ((c == '+' || c == '-') && matches!(prev, Some('e' | 'E')));
+                    prev = Some(c);
+                    !part_of_number
+                })
+                .map_or(rest.len(), |(idx, _)| idx);
+            if len == 0 {
+                return self.unexpected();
+            }
+            self.pos += len;
+        }
+        Ok(Term::Number(self.text()[start..self.pos].to_string()))
+    }
+
+    fn quoted(&mut self, quote: char) -> Result<String> {
+        let start = self.pos;
+        self.pos += quote.len_utf8();
+        let mut res = String::new();
+        let mut chars = self.text()[self.pos..].chars();
+        while let Some(c) = chars.next() {
+            self.pos += c.len_utf8();
+            match c {
+                '\\' => {
+                    let Some(escaped) = chars.next() else {
+                        break;
+                    };
+                    self.pos += escaped.len_utf8();
+                    res.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        's' => ' ',
+                        other => other,
+                    });
+                }
+                c if c == quote => return Ok(res),
+                c => res.push(c),
+            }
+        }
+        bail!("unterminated {quote} at offset {start}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::consult;
+    use super::Term;
+
+    fn atom(name: &str) -> Term {
+        Term::Atom(name.to_string())
+    }
+
+    #[test]
+    fn consult_terms() {
+        let terms = consult(
+            r#"
+            %% A comment, with a ]
+            {app, 'quoted atom', "a" "b\"c", [1, -2.5e-3, 16#ff, $a, $\n | tail]}.
+            #{key => <<"bin", 1:8, 2/utf8>>, other := []}.
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            terms,
+            vec![
+                Term::Tuple(vec![
+                    atom("app"),
+                    atom("quoted atom"),
+                    Term::String("ab\"c".to_string()),
+                    Term::List(
+                        ["1", "-2.5e-3", "16#ff", "$a", "$\\n"]
+                            .iter()
+                            .map(|number| Term::Number(number.to_string()))
+                            .collect()
+                    ),
+                ]),
+                Term::Map(vec![
+                    (
+                        atom("key"),
+                        Term::Binary(vec![
+                            Term::String("bin".to_string()),
+                            Term::Number("1".to_string()),
+                            Term::Number("2".to_string()),
+                        ])
+                    ),
+                    (atom("other"), Term::List(vec![])),
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn display_terms() {
+        let terms =
+            consult(r#"{'quoted atom', "a\"b", [1, -2.5e-3 | tail]}. #{key := <<"bin", 1:8>>}."#)
+                .unwrap();
+        let text: Vec<String> = terms.iter().map(|term| term.to_string()).collect();
+        assert_eq!(
+            text,
+            vec![
+                r#"{'quoted atom', "a\"b", [1, -2.5e-3]}"#,
+                r#"#{key => <<"bin", 1>>}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_terms() {
+        assert!(consult("{app, name, []}").is_err());
+        assert!(consult("{app, name, [}.").is_err());
+        assert!(consult("{app, Name, []}.").is_err());
+        assert!(consult("{app, \"name}.").is_err());
+    }
+}
@@ -79,6 +79,22 @@
 //! foo() -> ?BAR.
 //! "
 //! ```
+//!
+//! The runtime dependencies of an app, as listed in its `.app.src`, are
+//! given with `deps`. An `include_path` not starting with `/` is relative
+//! to the app dir. The OTP version of the project can be set at the top.
+//! ```not_rust
+//! "
+//! //- otp_version:26.2.1
+//! //- /app_a/src/a.erl app:app_a deps:app_b,kernel include_path:priv/include
+//! -module(a).
+//! //- /app_b/src/b.erl app:app_b
+//! -module(b).
+//! "
+//! ```
+//!
+//! A fixture starting with `//- standalone` is analyzed like a file opened
+//! outside of any project, see `StaticProject::standalone`.
 
 use std::fs;
 use std::fs::File;
@@ -180,6 +196,11 @@ impl DiagnosticsEnabled {
 pub struct FixtureWithProjectMeta {
     pub fixture: Vec<Fixture>,
     pub diagnostics_enabled: DiagnosticsEnabled,
+    /// The OTP version the fixture pretends to be built with
+    pub otp_version: Option<String>,
+    /// Whether the fixture is loaded as a single file outside of any
+    /// project
+    pub standalone: bool,
 }
 
 impl FixtureWithProjectMeta {
@@ -197,6 +218,8 @@ impl FixtureWithProjectMeta {
         let mut fixture = fixture.as_str();
         let mut res: Vec<Fixture> = Vec::new();
         let mut diagnostics_enabled = DiagnosticsEnabled::default();
+        let mut otp_version = None;
+        let mut standalone = false;
 
         // ---------------------------------------
         // Each of the following is optional, but they must always
@@ -231,6 +254,18 @@ impl FixtureWithProjectMeta {
             fixture = remain;
         }
 
+        if let Some(meta) = fixture.strip_prefix("//- otp_version:") {
+            let (version, remain) = meta.split_once('\n').unwrap();
+            otp_version = Some(version.trim().to_string());
+            fixture = remain;
+        }
+
+        if let Some(meta) = fixture.strip_prefix("//- standalone") {
+            let (_meta, remain) = meta.split_once('\n').unwrap();
+            standalone = true;
+            fixture = remain;
+        }
+
         diagnostics_enabled.set_default_native();
 
         // End of optional top-level meta info
@@ -279,6 +314,8 @@ impl FixtureWithProjectMeta {
         FixtureWithProjectMeta {
             fixture: res,
             diagnostics_enabled,
+            otp_version,
+            standalone,
         }
     }
 
@@ -302,8 +339,10 @@ impl FixtureWithProjectMeta {
     }
 
     //- /module.erl app:foo
+    //- /module.erl app:foo deps:bar,kernel
     //- /opt/lib/comp-1.3/include/comp.hrl otp_app:/opt/lib/comp-1.3
     //- /my_app/test/file_SUITE.erl extra:test
+    //- /my_app/src/file.erl include_path:include
     fn parse_meta_line(meta: &str) -> Fixture {
         assert!(meta.starts_with("//-"));
         let meta = meta["//-".len()..].trim();
@@ -317,6 +356,7 @@ impl FixtureWithProjectMeta {
         );
 
         let mut app_name = None;
+        let mut applications = Vec::new();
         let mut include_dirs = Vec::new();
         let mut extra_dirs = Vec::new();
         let mut otp = None;
@@ -328,8 +368,15 @@ impl FixtureWithProjectMeta {
                 .unwrap_or_else(|| panic!("invalid meta line: {:?}", meta));
             match key {
                 "app" => app_name = Some(AppName(value.to_string())),
-                "include_path" => include_dirs
-                    .push(AbsPath::assert(&Utf8PathBuf::from(value.to_string())).normalize()),
+                "deps" => applications.extend(
+                    value
+                        .split(',')
+                        .filter(|name| !name.is_empty())
+                        .map(|name| AppName(name.to_string())),
+                ),
+                // Relative include paths are resolved against the app
+                // dir, once it is known
+                "include_path" => include_dirs.push(value.to_string()),
                 "otp_app" => {
                     // We have an app directory, the OTP lib dir is its parent
                     let path = AbsPathBuf::assert(Utf8PathBuf::from(value.to_string()));
@@ -337,7 +384,13 @@ impl FixtureWithProjectMeta {
                     let versioned_name = path.file_name().unwrap();
                     let app = ProjectAppData::otp_app_data(&versioned_name, &path);
 
-                    otp = Some((Otp { lib_dir }, app));
+                    otp = Some((
+                        Otp {
+                            lib_dir,
+                            version: None,
+                        },
+                        app,
+                    ));
                 }
                 "extra" => {
                     // We have an extra directory, such as for a test suite
@@ -374,10 +427,20 @@ impl FixtureWithProjectMeta {
                     }
                 }
             }
-            (
-                None,
-                ProjectAppData::fixture_app_data(app_name, dir, include_dirs, src_dirs, extra_dirs),
-            )
+            let include_dirs = include_dirs
+                .iter()
+                .map(|include_dir| {
+                    if include_dir.starts_with('/') {
+                        AbsPath::assert(&Utf8PathBuf::from(include_dir)).normalize()
+                    } else {
+                        dir.join(include_dir).normalize()
+                    }
+                })
+                .collect();
+            let mut app_data =
+                ProjectAppData::fixture_app_data(app_name, dir, include_dirs, src_dirs, extra_dirs);
+            app_data.applications = applications;
+            (None, app_data)
         };
 
         Fixture {
@@ -400,6 +463,7 @@ mod tests {
     use paths::Utf8PathBuf;
 
     use super::FixtureWithProjectMeta;
+    use crate::AppName;
 
     #[test]
     #[should_panic]
@@ -544,7 +608,55 @@ bar() -> ok.
                 parse_transforms: [],
                 app_type: App,
                 include_path: [],
+                applications: [],
             }"#]]
         .assert_eq(format!("{:#?}", meta0.app_data).as_str());
     }
+
+    #[test]
+    fn parse_fixture_standalone() {
+        let fixture = FixtureWithProjectMeta::parse(
+            r#"
+//- standalone
+//- /src/a.erl
+-module(a).
+"#,
+        );
+        assert!(fixture.standalone);
+        assert_eq!(1, fixture.fixture.len());
+    }
+
+    #[test]
+    fn parse_fixture_app_deps_and_otp_version() {
+        let fixture = FixtureWithProjectMeta::parse(
+            r#"
+//- otp_version:26.2.1
+//- /app_a/src/a.erl app:app_a deps:app_b,kernel include_path:priv/include
+-module(a).
+//- /app_b/src/b.erl app:app_b include_path:/app_b/include
+-module(b).
+"#,
+        );
+        assert_eq!(Some("26.2.1".to_string()), fixture.otp_version);
+        assert!(!fixture.standalone);
+        let parsed = fixture.fixture;
+        assert_eq!(2, parsed.len());
+
+        let app_a = &parsed[0].app_data;
+        assert_eq!(
+            vec![AppName("app_b".to_string()), AppName("kernel".to_string())],
+            app_a.applications
+        );
+        assert_eq!(
+            vec![AbsPath::assert(&Utf8PathBuf::from("/app_a/priv/include")).normalize()],
+            app_a.include_dirs
+        );
+
+        let app_b = &parsed[1].app_data;
+        assert!(app_b.applications.is_empty());
+        assert_eq!(
+            vec![AbsPath::assert(&Utf8PathBuf::from("/app_b/include")).normalize()],
+            app_b.include_dirs
+        );
+    }
 }
@@ -0,0 +1,263 @@
+/*
+ * Copyright (c) Meta Platforms, Inc. and affiliates.
+ *
+ * This source code is licensed under both the MIT license found in the
+ * LICENSE-MIT file in the root directory of this source tree and the Apache
+ * License, Version 2.0 found in the LICENSE-APACHE file in the root directory
+ * of this source tree.
+ */
+
+//! Support for yecc (`.yrl`) and leex (`.xrl`) grammar files.
+//!
+//! Both consist of a grammar part with a syntax of its own, followed by
+//! an optional `Erlang code.` section holding plain Erlang. Only the
+//! latter is parsed as Erlang, the grammar part is scanned just enough
+//! to find the symbols it defines and uses.
+
+use rowan::TextRange;
+use rowan::TextSize;
+use smol_str::SmolStr;
+
+const ERLANG_CODE_HEADER: &str = "Erlang code.";
+const LEEX_DEFINITIONS_HEADER: &str = "Definitions.";
+const LEEX_RULES_HEADER: &str = "Rules.";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrammarSymbol {
+    pub name: SmolStr,
+    pub range: TextRange,
+}
+
+/// Offset at which the `Erlang code.` section body starts, if there
+/// is one.
+pub fn erlang_code_start(text: &str) -> Option<TextSize> {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        offset += line.len();
+        if line.trim_end() == ERLANG_CODE_HEADER {
+            return Some(TextSize::from(offset as u32));
+        }
+    }
+    None
+}
+
+/// Symbols defined in the grammar part: the macro definitions of a
+/// leex `Definitions.` section, or the rule heads of a yecc grammar.
+pub fn symbol_definitions(text: &str) -> Vec<GrammarSymbol> {
+    let grammar = grammar_part(text);
+    if is_leex(grammar) {
+        leex_definitions(grammar)
+    } else {
+        yecc_rule_heads(grammar)
+    }
+}
+
+/// The grammar symbol under the cursor, if any.
+pub fn symbol_at(text: &str, offset: TextSize) -> Option<GrammarSymbol> {
+    let grammar = grammar_part(text);
+    let offset: usize = offset.into();
+    if offset > grammar.len() {
+        return None;
+    }
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '@';
+    let start = grammar[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_ident(*c))
+        .last()
+        .map_or(offset, |(idx, _)| idx);
+    let end = grammar[offset..]
+        .char_indices()
+        .find(|(_, c)| !is_ident(*c))
+        .map_or(grammar.len(), |(idx, _)| offset + idx);
+    if start == end || in_comment(grammar, start) {
+        return None;
+    }
+    Some(GrammarSymbol {
+        name: SmolStr::new(&grammar[start..end]),
+        range: TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32)),
+    })
+}
+
+fn grammar_part(text: &str) -> &str {
+    let end = erlang_code_start(text).map_or(text.len(), |start| start.into());
+    &text[..end]
+}
+
+fn is_leex(grammar: &str) -> bool {
+    grammar.lines().any(|line| {
+        let line = line.trim_end();
+        line == LEEX_DEFINITIONS_HEADER || line == LEEX_RULES_HEADER
+    })
+}
+
+fn in_comment(text: &str, offset: usize) -> bool {
+    let line_start = text[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+    text[line_start..offset].contains('%')
+}
+
+/// Definitions are lines of the form `NAME = REGEXP` between the
+/// `Definitions.` and `Rules.` headers.
+fn leex_definitions(grammar: &str) -> Vec<GrammarSymbol> {
+    let mut res = Vec::new();
+    let mut in_definitions = false;
+    let mut offset = 0;
+    for line in grammar.split_inclusive('\n') {
+        let line_offset = offset;
+        offset += line.len();
+        match line.trim_end() {
+            LEEX_DEFINITIONS_HEADER => in_definitions = true,
+            LEEX_RULES_HEADER | ERLANG_CODE_HEADER => in_definitions = false,
+            _ if in_definitions => {
+                if let Some((name, _regexp)) = line.split_once('=') {
+                    let trimmed = name.trim();
+                    if !trimmed.is_empty() && !trimmed.starts_with('%') {
+                        let start = line_offset + (name.len() - name.trim_start().len());
+                        res.push(GrammarSymbol {
+                            name: SmolStr::new(trimmed),
+                            range: TextRange::at(
+                                TextSize::from(start as u32),
+                                TextSize::of(trimmed),
+                            ),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    res
+}
+
+/// Rules have the form `Head -> Symbols : Code.`, possibly spanning
+/// several lines. Declarations such as `Nonterminals a b c.` have no
+/// arrow, and are skipped.
+fn yecc_rule_heads(grammar: &str) -> Vec<GrammarSymbol> {
+    let mut res = Vec::new();
+    let bytes = grammar.as_bytes();
+    let mut form_start = true;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'%' => {
+                // Comment to end of line
+                idx = grammar[idx..]
+                    .find('\n')
+                    .map_or(bytes.len(), |len| idx + len);
+                continue;
+            }
+            b'\'' | b'"' => {
+                idx = skip_quoted(bytes, idx);
+                form_start = false;
+                continue;
+            }
+            b'.' if bytes
+                .get(idx + 1)
+                .map_or(true, |next| next.is_ascii_whitespace()) =>
+            {
+                form_start = true;
+            }
+            c if c.is_ascii_whitespace() => {}
+            c if form_start && (c.is_ascii_alphabetic() || c == b'_') => {
+                let end = grammar[idx..]
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '@'))
+                    .map_or(bytes.len(), |len| idx + len);
+                if grammar[end..].trim_start().starts_with("->") {
+                    res.push(GrammarSymbol {
+                        name: SmolStr::new(&grammar[idx..end]),
+                        range: TextRange::new(
+                            TextSize::from(idx as u32),
+                            TextSize::from(end as u32),
+                        ),
+                    });
+                }
+                form_start = false;
+                idx = end;
+                continue;
+            }
+            _ => form_start = false,
+        }
+        idx += 1;
+    }
+    res
+}
+
+fn skip_quoted(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let mut idx = start + 1;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' => idx += 2,
+            c if c == quote => return idx + 1,
+            _ => idx += 1,
+        }
+    }
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::SourceFile;
+
+    const YECC: &str = r#"Nonterminals expr term.
+Terminals '+' int.
+Rootsymbol expr.
+
+expr -> expr '+' term : {plus, '$1', '$3'}.
+expr -> term : '$1'.
+%% term -> ignored
+term -> int : value('$1').
+
+Erlang code.
+
+value({int, _, V}) -> V.
+"#;
+
+    const LEEX: &str = r#"Definitions.
+
+D = [0-9]
+WS = [\s\t\n]
+
+Rules.
+
+{D}+ : {token, {int, TokenLine, list_to_integer(TokenChars)}}.
+{WS}+ : skip_token.
+
+Erlang code.
+"#;
+
+    fn names(symbols: Vec<GrammarSymbol>) -> Vec<String> {
+        symbols.into_iter().map(|s| s.name.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_erlang_code() {
+        let start = erlang_code_start(YECC).unwrap();
+        let parse = SourceFile::parse_skipping(YECC, start.into());
+        assert!(parse.errors().is_empty());
+        assert_eq!(parse.syntax_node().text().to_string(), YECC);
+        assert_eq!(parse.tree().forms().count(), 1);
+    }
+
+    #[test]
+    fn yecc_heads() {
+        assert_eq!(
+            names(symbol_definitions(YECC)),
+            vec!["expr", "expr", "term"]
+        );
+    }
+
+    #[test]
+    fn leex_defs() {
+        assert_eq!(names(symbol_definitions(LEEX)), vec!["D", "WS"]);
+    }
+
+    #[test]
+    fn symbol_under_cursor() {
+        let offset = YECC.find("term :").unwrap() + 2;
+        let symbol = symbol_at(YECC, TextSize::from(offset as u32)).unwrap();
+        assert_eq!(symbol.name, "term");
+        assert_eq!(symbol_at(YECC, TextSize::of(YECC)), None);
+    }
+}
@@ -30,6 +30,7 @@ mod token_text;
 
 pub mod algo;
 pub mod ast;
+pub mod grammar_file;
 pub mod label;
 pub mod syntax_node;
 pub mod ted;
@@ -116,19 +117,22 @@ struct Converter<'tree, 'text> {
     errors: Vec<SyntaxError>,
     builder: GreenNodeBuilder<'static>,
     last_position: usize,
+    /// Length of the leading text left out of the parse
+    skipped: usize,
     pending_syntax_error: Option<Node<'tree>>,
     // Debug counter to try and track down the panic on calling self.builder.finish()
     open_count: isize,
 }
 
 impl<'tree, 'text> Converter<'tree, 'text> {
-    pub fn new(tree: &'tree Tree, text: &'text str) -> Converter<'tree, 'text> {
+    pub fn new(tree: &'tree Tree, text: &'text str, skipped: usize) -> Converter<'tree, 'text> {
         Converter {
             cursor: tree.walk(),
             text,
             errors: Vec::new(),
             builder: GreenNodeBuilder::new(),
             last_position: 0,
+            skipped,
             pending_syntax_error: None,
             open_count: 0,
         }
@@ -295,6 +299,14 @@ impl<'tree, 'text> Converter<'tree, 'text> {
     }
 
     fn update_position(&mut self, start: usize, end: usize) {
+        if self.last_position == 0 {
+            let skipped = self.skipped.min(start);
+            if skipped > 0 {
+                let kind = ELPLanguage::kind_to_raw(SyntaxKind::COMMENT);
+                self.builder.token(kind, &self.text[..skipped]);
+                self.last_position = skipped;
+            }
+        }
         if self.last_position < start {
             let kind = ELPLanguage::kind_to_raw(SyntaxKind::WHITESPACE);
             let text = &self.text[self.last_position..start];
@@ -315,9 +327,17 @@ fn convert_range(range: Range<usize>) -> TextRange {
 
 impl SourceFile {
     pub fn parse_text(text: &str) -> Parse<SourceFile> {
+        SourceFile::parse_skipping(text, shebang_len(text))
+    }
+
+    /// Parses `text` without its first `skip` bytes, which are not Erlang
+    /// and are kept in the tree as a single comment.
+    pub fn parse_skipping(text: &str, skip: usize) -> Parse<SourceFile> {
         let mut parser = Parser::new();
-        let tree = parser.parse(text).expect("parsing should always succeed");
-        let (green, errors) = Converter::new(&tree, text).convert();
+        let tree = parser
+            .parse_skipping(text, skip)
+            .expect("parsing should always succeed");
+        let (green, errors) = Converter::new(&tree, text, skip).convert();
         let root = SyntaxNode::new_root(green.clone());
 
         assert_eq!(root.kind(), SyntaxKind::SOURCE_FILE);
@@ -349,6 +369,16 @@ impl SourceFile {
     }
 }
 
+/// An escript may start with a `#!` interpreter line, which is not
+/// Erlang syntax.
+fn shebang_len(text: &str) -> usize {
+    if text.starts_with("#!") {
+        text.find('\n').unwrap_or(text.len())
+    } else {
+        0
+    }
+}
+
 // ---------------------------------------------------------------------
 
 /// Matches a `SyntaxNode` against an `ast` type.
@@ -917,6 +947,22 @@ mod tests {
         expect!["[Error(19..20)]"].assert_eq(format!("{:?}", parse.errors()).as_str());
     }
 
+    #[test]
+    fn escript_shebang() {
+        let source_code = "#!/usr/bin/env escript\n%%! -smp enable\nmain(_) -> ok.\n";
+        let parse = ast::SourceFile::parse_text(source_code);
+        assert!(parse.errors().is_empty());
+        assert_eq!(parse.tree().forms().count(), 1);
+        assert_eq!(
+            parse.syntax_node().text_range(),
+            TextRange::new(TextSize::from(0), TextSize::of(source_code))
+        );
+        assert_eq!(parse.syntax_node().text().to_string(), source_code);
+        let shebang = parse.syntax_node().first_token().unwrap();
+        assert_eq!(shebang.kind(), SyntaxKind::COMMENT);
+        assert_eq!(shebang.text(), "#!/usr/bin/env escript");
+    }
+
     #[test]
     fn rowan() {
         let source_code = r#"
@@ -24,6 +24,28 @@ impl Parser {
     pub fn parse(&mut self, text: &str) -> Option<Tree> {
         self.0.parse(text, None)
     }
+
+    /// Parses `text` without its first `skip` bytes, which are left as a
+    /// gap before the first node of the tree.
+    pub fn parse_skipping(&mut self, text: &str, skip: usize) -> Option<Tree> {
+        if skip > 0 {
+            let range = Range {
+                start_byte: skip,
+                end_byte: text.len(),
+                start_point: point_at(text, skip),
+                end_point: point_at(text, text.len()),
+            };
+            self.0.set_included_ranges(&[range]).ok()?;
+        }
+        self.0.parse(text, None)
+    }
+}
+
+fn point_at(text: &str, offset: usize) -> Point {
+    let before = &text[..offset];
+    let row = before.matches('\n').count();
+    let column = offset - before.rfind('\n').map_or(0, |newline| newline + 1);
+    Point::new(row, column)
 }
 
 impl Default for Parser {
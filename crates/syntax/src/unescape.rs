@@ -97,6 +97,34 @@ pub fn unescape_string(s_in: &str) -> Option<Cow<str>> {
     Some(Cow::Owned(s))
 }
 
+/// The contents of a triple-quoted string without a sigil, which are
+/// verbatim, with the indentation of its closing delimiter removed from
+/// each line.
+pub fn triple_quoted_contents(text: &str) -> Option<String> {
+    let quotes = text.len() - text.trim_start_matches('"').len();
+    if quotes < 3 || text.len() < 2 * quotes || !text.ends_with(&text[..quotes]) {
+        return None;
+    }
+    let mut lines: Vec<&str> = text[quotes..text.len() - quotes].split('\n').collect();
+    if lines.len() < 2 {
+        return None;
+    }
+    let indent = lines.pop()?;
+    let first = lines.remove(0);
+    if !first.trim().is_empty() || !indent.trim().is_empty() {
+        return None;
+    }
+    let lines = lines
+        .iter()
+        .map(|line| match line.strip_prefix(indent) {
+            Some(line) => Some(line),
+            None if indent.starts_with(line) => Some(""),
+            None => None,
+        })
+        .collect::<Option<Vec<&str>>>()?;
+    Some(lines.join("\n"))
+}
+
 fn unescape_octal(c: char, queue: &mut VecDeque<char>) -> Option<char> {
     let mut s = String::new();
     s.push(c);